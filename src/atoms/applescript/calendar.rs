@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Utc};
-use std::process::Command;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+use super::watchdog::run_osascript;
+use crate::types::AgendaEvent;
 
 pub fn create_calendar_event(
     title: &str,
@@ -31,7 +33,57 @@ end tell"#,
         calendar_clause, escaped_title, escaped_notes, start_formatted, end_formatted
     );
 
-    run_applescript(&script).context("Failed to create calendar event")
+    run_applescript(&script)
+        .map(|_| ())
+        .context("Failed to create calendar event")
+}
+
+/// Today's events of `calendar_name`, or of every calendar, in the order
+/// Calendar lists them.
+pub fn todays_events(calendar_name: Option<&str>) -> Result<Vec<AgendaEvent>> {
+    let calendars_clause = if let Some(cal) = calendar_name {
+        format!("{{calendar \"{}\"}}", escape_applescript_string(cal))
+    } else {
+        "calendars".to_string()
+    };
+
+    // One event per line: start and end in seconds from midnight, whether
+    // it lasts all day, and its title
+    let script = format!(
+        r#"set today to current date
+set time of today to 0
+set tomorrow to today + (1 * days)
+set out to ""
+tell application "Calendar"
+    repeat with cal in {}
+        repeat with e in (every event of cal whose start date ≥ today and start date < tomorrow)
+            set out to out & ((start date of e) - today) & tab & ((end date of e) - today) & tab & (allday event of e) & tab & (summary of e) & linefeed
+        end repeat
+    end repeat
+end tell
+return out"#,
+        calendars_clause
+    );
+
+    let output = run_applescript(&script).context("Failed to read calendar events")?;
+    Ok(output.lines().filter_map(parse_event_line).collect())
+}
+
+/// An event line of the `todays_events` script.
+fn parse_event_line(line: &str) -> Option<AgendaEvent> {
+    let mut fields = line.splitn(4, '\t');
+    let start: i64 = fields.next()?.trim().parse().ok()?;
+    let end: i64 = fields.next()?.trim().parse().ok()?;
+    let all_day = fields.next()?.trim() == "true";
+    let title = fields.next()?.trim().to_string();
+    let time =
+        |secs: i64| NaiveTime::from_num_seconds_from_midnight_opt(secs.clamp(0, 86_399) as u32, 0);
+    Some(AgendaEvent {
+        title,
+        start: time(start)?,
+        end: time(end)?,
+        all_day,
+    })
 }
 
 fn escape_applescript_string(s: &str) -> String {
@@ -40,17 +92,30 @@ fn escape_applescript_string(s: &str) -> String {
         .replace('\n', "\\n")
 }
 
-fn run_applescript(script: &str) -> Result<()> {
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output()
-        .context("Failed to execute osascript")?;
+/// Run `script`, returning what it printed.
+fn run_applescript(script: &str) -> Result<String> {
+    let output = run_osascript(script)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         anyhow::bail!("AppleScript error: {}", stderr);
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_line() {
+        let event = parse_event_line("32400\t34200\tfalse\tStandup\twith team").unwrap();
+        assert_eq!(event.title, "Standup\twith team");
+        assert_eq!(event.start, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(event.end, NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert!(!event.all_day);
+        assert!(parse_event_line("0\t86400\ttrue\tHoliday").unwrap().all_day);
+        assert!(parse_event_line("garbage").is_none());
+    }
 }
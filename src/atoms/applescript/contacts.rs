@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+
+use super::watchdog::run_osascript;
+
+/// Full names of the people in the Contacts app, sorted and without
+/// repeats.
+pub fn contact_names() -> Result<Vec<String>> {
+    let script = r#"tell application "Contacts"
+    set names to name of every person
+end tell
+set AppleScript's text item delimiters to linefeed
+return names as text"#;
+
+    let output = run_applescript(script).context("Failed to read contacts")?;
+    let mut names: Vec<String> = output
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "missing value")
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Run `script`, returning what it printed.
+fn run_applescript(script: &str) -> Result<String> {
+    let output = run_osascript(script)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("AppleScript error: {}", stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+
+use super::watchdog::run_osascript;
+
+/// Open a new message in Mail, addressed and filled in but not sent, for
+/// the user to review. `sender` picks the account to send from.
+pub fn create_mail_draft(
+    to: &[String],
+    cc: &[String],
+    subject: &str,
+    body: &str,
+    sender: Option<&str>,
+) -> Result<()> {
+    let recipients: Vec<String> = to
+        .iter()
+        .map(|address| recipient_line("to", address))
+        .chain(cc.iter().map(|address| recipient_line("cc", address)))
+        .collect();
+    let sender_line = sender
+        .map(|s| {
+            format!(
+                "\n        set sender to \"{}\"",
+                escape_applescript_string(s)
+            )
+        })
+        .unwrap_or_default();
+
+    let script = format!(
+        r#"tell application "Mail"
+    set newMessage to make new outgoing message with properties {{subject:"{}", content:"{}", visible:true}}
+    tell newMessage{}{}
+    end tell
+    activate
+end tell"#,
+        escape_applescript_string(subject),
+        escape_applescript_string(body),
+        recipients.concat(),
+        sender_line
+    );
+
+    run_applescript(&script).context("Failed to create Mail draft")
+}
+
+fn recipient_line(kind: &str, address: &str) -> String {
+    format!(
+        "\n        make new {} recipient at end of {} recipients with properties {{address:\"{}\"}}",
+        kind,
+        kind,
+        escape_applescript_string(address)
+    )
+}
+
+fn escape_applescript_string(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn run_applescript(script: &str) -> Result<()> {
+    let output = run_osascript(script)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("AppleScript error: {}", stderr);
+    }
+
+    Ok(())
+}
@@ -1,11 +1,23 @@
 mod bear;
 mod calendar;
+mod contacts;
+mod mail;
 mod notes;
 mod obsidian;
+mod omnifocus;
 mod reminders;
+mod service;
+mod things;
+mod watchdog;
 
 pub use bear::create_bear_note;
-pub use calendar::create_calendar_event;
-pub use notes::create_apple_note;
+pub use calendar::{create_calendar_event, todays_events};
+pub use contacts::contact_names;
+pub use mail::create_mail_draft;
+pub use notes::{attach_to_apple_note, create_apple_note};
 pub use obsidian::create_obsidian_note;
-pub use reminders::create_reminder;
+pub use omnifocus::create_omnifocus_task;
+pub use reminders::{completed_reminders, create_reminder};
+pub use service::install_capture_service;
+pub use things::create_things_todo;
+pub use watchdog::{active_osascript_count, kill_osascript_children};
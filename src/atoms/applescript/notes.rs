@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::path::Path;
+
+use super::watchdog::run_osascript;
 
 pub fn create_apple_note(title: &str, body: &str, folder: Option<&str>) -> Result<()> {
     let escaped_title = escape_applescript_string(title);
@@ -23,6 +25,35 @@ end tell"#,
     run_applescript(&script).context("Failed to create Apple Note")
 }
 
+/// Create a note and attach a file (e.g. an audio recording) to it.
+pub fn attach_to_apple_note(
+    title: &str,
+    body: &str,
+    folder: Option<&str>,
+    attachment: &Path,
+) -> Result<()> {
+    let folder_clause = if let Some(f) = folder {
+        format!("folder \"{}\"", escape_applescript_string(f))
+    } else {
+        "default account's first folder".to_string()
+    };
+
+    let script = format!(
+        r#"tell application "Notes"
+    tell {}
+        set newNote to make new note with properties {{name:"{}", body:"{}"}}
+        make new attachment at end of attachments of newNote with data (POSIX file "{}")
+    end tell
+end tell"#,
+        folder_clause,
+        escape_applescript_string(title),
+        escape_applescript_string(body),
+        escape_applescript_string(&attachment.to_string_lossy())
+    );
+
+    run_applescript(&script).context("Failed to attach file to Apple Note")
+}
+
 fn escape_applescript_string(s: &str) -> String {
     s.replace('\\', "\\\\")
         .replace('"', "\\\"")
@@ -30,11 +61,7 @@ fn escape_applescript_string(s: &str) -> String {
 }
 
 fn run_applescript(script: &str) -> Result<()> {
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output()
-        .context("Failed to execute osascript")?;
+    let output = run_osascript(script)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
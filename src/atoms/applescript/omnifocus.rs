@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use std::process::Command;
+use urlencoding::encode;
+
+/// Add a task to the OmniFocus inbox, or `project`, through its URL
+/// scheme, saved without showing the quick entry window. `due` sets its
+/// due date.
+pub fn create_omnifocus_task(
+    name: &str,
+    note: Option<&str>,
+    due: Option<DateTime<Utc>>,
+    project: Option<&str>,
+) -> Result<()> {
+    let url = omnifocus_add_url(name, note, due, project);
+
+    let output = Command::new("open")
+        .arg(&url)
+        .output()
+        .context("Failed to open OmniFocus URL")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to open OmniFocus: {}", stderr);
+    }
+
+    Ok(())
+}
+
+fn omnifocus_add_url(
+    name: &str,
+    note: Option<&str>,
+    due: Option<DateTime<Utc>>,
+    project: Option<&str>,
+) -> String {
+    let mut url = format!("omnifocus:///add?name={}", encode(name));
+    if let Some(note) = note {
+        url.push_str(&format!("&note={}", encode(note)));
+    }
+    if let Some(due) = due {
+        let due = due
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        url.push_str(&format!("&due={}", encode(&due)));
+    }
+    if let Some(project) = project {
+        url.push_str(&format!("&project={}", encode(project)));
+    }
+    url.push_str("&autosave=true");
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_omnifocus_add_url() {
+        assert_eq!(
+            omnifocus_add_url("Buy milk", Some("2 bottles"), None, Some("Errands")),
+            "omnifocus:///add?name=Buy%20milk&note=2%20bottles&project=Errands&autosave=true"
+        );
+    }
+}
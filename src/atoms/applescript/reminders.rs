@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use std::process::Command;
+
+use super::watchdog::run_osascript;
 
 pub fn create_reminder(
     title: &str,
@@ -33,7 +34,35 @@ end tell"#,
         list_clause, escaped_title, escaped_notes, date_clause
     );
 
-    run_applescript(&script).context("Failed to create reminder")
+    run_applescript(&script)
+        .map(|_| ())
+        .context("Failed to create reminder")
+}
+
+/// Names of the completed reminders in `list_name`, or the default list.
+pub fn completed_reminders(list_name: Option<&str>) -> Result<Vec<String>> {
+    let list_clause = if let Some(list) = list_name {
+        format!("list \"{}\"", escape_applescript_string(list))
+    } else {
+        "default list".to_string()
+    };
+
+    let script = format!(
+        r#"tell application "Reminders"
+    set done to name of every reminder of {} whose completed is true
+end tell
+set AppleScript's text item delimiters to linefeed
+return done as text"#,
+        list_clause
+    );
+
+    let output = run_applescript(&script).context("Failed to read reminders")?;
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect())
 }
 
 fn escape_applescript_string(s: &str) -> String {
@@ -42,19 +71,16 @@ fn escape_applescript_string(s: &str) -> String {
         .replace('\n', "\\n")
 }
 
-fn run_applescript(script: &str) -> Result<()> {
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output()
-        .context("Failed to execute osascript")?;
+/// Run `script`, returning what it printed.
+fn run_applescript(script: &str) -> Result<String> {
+    let output = run_osascript(script)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         anyhow::bail!("AppleScript error: {}", stderr);
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 #[cfg(test)]
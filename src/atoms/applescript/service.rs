@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SERVICE_NAME: &str = "Send to Kenotex";
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn info_plist() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>NSServices</key>
+	<array>
+		<dict>
+			<key>NSMenuItem</key>
+			<dict>
+				<key>default</key>
+				<string>{}</string>
+			</dict>
+			<key>NSMessage</key>
+			<string>runWorkflowAsService</string>
+			<key>NSSendTypes</key>
+			<array>
+				<string>public.utf8-plain-text</string>
+			</array>
+		</dict>
+	</array>
+</dict>
+</plist>
+"#,
+        SERVICE_NAME
+    )
+}
+
+/// Automator Quick Action with a single "Run Shell Script" step that pipes
+/// the selected text to `command` on stdin.
+fn document_wflow(command: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>AMApplicationBuild</key>
+	<string>521</string>
+	<key>AMApplicationVersion</key>
+	<string>2.10</string>
+	<key>AMDocumentVersion</key>
+	<string>2</string>
+	<key>actions</key>
+	<array>
+		<dict>
+			<key>action</key>
+			<dict>
+				<key>AMAccepts</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Optional</key>
+					<true/>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>AMActionVersion</key>
+				<string>2.0.3</string>
+				<key>AMProvides</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>ActionBundlePath</key>
+				<string>/System/Library/Automator/Run Shell Script.action</string>
+				<key>ActionName</key>
+				<string>Run Shell Script</string>
+				<key>ActionParameters</key>
+				<dict>
+					<key>COMMAND_STRING</key>
+					<string>{}</string>
+					<key>CheckedForUserDefaultShell</key>
+					<true/>
+					<key>inputMethod</key>
+					<integer>0</integer>
+					<key>shell</key>
+					<string>/bin/bash</string>
+					<key>source</key>
+					<string></string>
+				</dict>
+				<key>BundleIdentifier</key>
+				<string>com.apple.RunShellScript</string>
+				<key>CFBundleVersion</key>
+				<string>2.0.3</string>
+				<key>CanShowSelectedItemsWhenRun</key>
+				<false/>
+				<key>CanShowWhenRun</key>
+				<true/>
+				<key>Class Name</key>
+				<string>RunShellScriptAction</string>
+				<key>InputUUID</key>
+				<string>6A1B2C3D-0000-4000-8000-000000000001</string>
+				<key>OutputUUID</key>
+				<string>6A1B2C3D-0000-4000-8000-000000000002</string>
+				<key>UUID</key>
+				<string>6A1B2C3D-0000-4000-8000-000000000003</string>
+			</dict>
+		</dict>
+	</array>
+	<key>connectors</key>
+	<dict/>
+	<key>workflowMetaData</key>
+	<dict>
+		<key>serviceInputTypeIdentifier</key>
+		<string>com.apple.Automator.text</string>
+		<key>serviceOutputTypeIdentifier</key>
+		<string>com.apple.Automator.nothing</string>
+		<key>serviceProcessesInput</key>
+		<integer>0</integer>
+		<key>workflowTypeIdentifier</key>
+		<string>com.apple.Automator.servicesMenu</string>
+	</dict>
+</dict>
+</plist>
+"#,
+        escape_xml(command)
+    )
+}
+
+/// Write the "Send to Kenotex" Quick Action into `services_dir`
+/// (normally `~/Library/Services`) and return the workflow path. It appears
+/// in the Services menu for selected text; Shortcuts can run the same
+/// `command` with a "Run Shell Script" action.
+pub fn install_capture_service(services_dir: &Path, command: &str) -> Result<PathBuf> {
+    let workflow = services_dir.join(format!("{}.workflow", SERVICE_NAME));
+    let contents = workflow.join("Contents");
+    fs::create_dir_all(&contents)
+        .with_context(|| format!("Failed to create service directory: {:?}", contents))?;
+    fs::write(contents.join("Info.plist"), info_plist())
+        .context("Failed to write service Info.plist")?;
+    fs::write(contents.join("document.wflow"), document_wflow(command))
+        .context("Failed to write service workflow")?;
+    Ok(workflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_capture_service() {
+        let dir = std::env::temp_dir().join(format!("kenotex-service-{}", uuid::Uuid::new_v4()));
+        let workflow =
+            install_capture_service(&dir, "'/opt/ke & co/kenotex' capture --from-service").unwrap();
+
+        assert_eq!(workflow, dir.join("Send to Kenotex.workflow"));
+        let wflow = fs::read_to_string(workflow.join("Contents/document.wflow")).unwrap();
+        assert!(
+            wflow.contains("<string>'/opt/ke &amp; co/kenotex' capture --from-service</string>")
+        );
+        let plist = fs::read_to_string(workflow.join("Contents/Info.plist")).unwrap();
+        assert!(plist.contains("<string>Send to Kenotex</string>"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
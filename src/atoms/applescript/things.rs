@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use std::process::Command;
+use urlencoding::encode;
+
+/// Add a to-do to Things 3 through its URL scheme. `when` schedules it
+/// with a reminder; `list` names a project or area.
+pub fn create_things_todo(
+    title: &str,
+    notes: Option<&str>,
+    when: Option<DateTime<Utc>>,
+    list: Option<&str>,
+) -> Result<()> {
+    let url = things_add_url(title, notes, when, list);
+
+    let output = Command::new("open")
+        .arg(&url)
+        .output()
+        .context("Failed to open Things URL")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to open Things: {}", stderr);
+    }
+
+    Ok(())
+}
+
+fn things_add_url(
+    title: &str,
+    notes: Option<&str>,
+    when: Option<DateTime<Utc>>,
+    list: Option<&str>,
+) -> String {
+    let mut url = format!("things:///add?title={}", encode(title));
+    if let Some(notes) = notes {
+        url.push_str(&format!("&notes={}", encode(notes)));
+    }
+    if let Some(when) = when {
+        let when = when
+            .with_timezone(&Local)
+            .format("%Y-%m-%d@%H:%M")
+            .to_string();
+        url.push_str(&format!("&when={}", encode(&when)));
+    }
+    if let Some(list) = list {
+        url.push_str(&format!("&list={}", encode(list)));
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_things_add_url() {
+        assert_eq!(
+            things_add_url("Buy milk", Some("2% fat"), None, Some("Home & Garden")),
+            "things:///add?title=Buy%20milk&notes=2%25%20fat&list=Home%20%26%20Garden"
+        );
+        let when = Local::now().with_timezone(&Utc);
+        let url = things_add_url("Call Ann", None, Some(when), None);
+        let expected = when.with_timezone(&Local).format("%Y-%m-%d%%40%H%%3A%M");
+        assert!(url.ends_with(&format!("&when={}", expected)), "{}", url);
+    }
+}
@@ -0,0 +1,129 @@
+use std::io::Read;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+
+/// Longest an `osascript` run may take before it is killed. Generous, since
+/// the first run against an app waits on the user's permission prompt.
+const OSASCRIPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often a waiting run checks whether its child has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// `osascript` children still running, by pid.
+static CHILDREN: LazyLock<Mutex<Vec<(u32, Child)>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+fn children() -> std::sync::MutexGuard<'static, Vec<(u32, Child)>> {
+    // A panic elsewhere while holding the lock leaves the list usable
+    CHILDREN.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Read everything from a child's pipe on another thread, so a chatty script
+/// never blocks on a full pipe while it is being waited on.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// Run `osascript -e script` and collect its output. The child is tracked
+/// until it has been reaped: one that outlives `OSASCRIPT_TIMEOUT` is killed,
+/// and `kill_osascript_children` stops any still running on exit.
+pub fn run_osascript(script: &str) -> Result<Output> {
+    run_tracked(
+        Command::new("osascript").arg("-e").arg(script),
+        OSASCRIPT_TIMEOUT,
+    )
+    .context("Failed to execute osascript")
+}
+
+fn run_tracked(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    children().push((pid, child));
+
+    let started = Instant::now();
+    let status = loop {
+        let mut tracked = children();
+        let Some(index) = tracked.iter().position(|(p, _)| *p == pid) else {
+            bail!("osascript was stopped");
+        };
+        if let Some(status) = tracked[index].1.try_wait()? {
+            tracked.remove(index);
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            let (_, mut child) = tracked.remove(index);
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("osascript timed out after {}s", timeout.as_secs());
+        }
+        drop(tracked);
+        thread::sleep(POLL_INTERVAL);
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Number of `osascript` children currently running.
+pub fn active_osascript_count() -> usize {
+    children().len()
+}
+
+/// Kill and reap every `osascript` child still running; returns how many.
+/// Called on exit and from the panic hook so none outlive the app.
+pub fn kill_osascript_children() -> usize {
+    let mut tracked = children();
+    let count = tracked.len();
+    for (_, mut child) in tracked.drain(..) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracked_runs_are_reaped_or_killed() {
+        let output = run_tracked(
+            Command::new("sh").args(["-c", "echo hi"]),
+            OSASCRIPT_TIMEOUT,
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hi\n");
+
+        let err =
+            run_tracked(Command::new("sleep").arg("5"), Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let waiting =
+            thread::spawn(|| run_tracked(Command::new("sleep").arg("5"), OSASCRIPT_TIMEOUT));
+        let started = Instant::now();
+        while active_osascript_count() == 0 && started.elapsed() < Duration::from_secs(2) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(kill_osascript_children() >= 1);
+        assert!(waiting.join().unwrap().is_err());
+        assert_eq!(active_osascript_count(), 0);
+    }
+}
@@ -0,0 +1,47 @@
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Whether the system is in dark mode: `AppleInterfaceStyle` on macOS,
+/// GNOME's `color-scheme` elsewhere. `None` when it can't be told.
+pub fn system_dark_mode() -> Option<bool> {
+    if cfg!(target_os = "macos") {
+        // The key only exists in dark mode, so a failed read means light
+        let output = Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+            .ok()?;
+        return Some(String::from_utf8_lossy(&output.stdout).trim() == "Dark");
+    }
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).contains("dark"))
+}
+
+/// Check `system_dark_mode` every `interval` on a thread of its own and
+/// send each change, the first reading included. The thread ends at the
+/// first change after the receiver is dropped.
+pub fn watch_system_appearance(interval: Duration) -> mpsc::Receiver<bool> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            if let Some(dark) = system_dark_mode()
+                && last != Some(dark)
+            {
+                if tx.send(dark).is_err() {
+                    return;
+                }
+                last = Some(dark);
+            }
+            thread::sleep(interval);
+        }
+    });
+    rx
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result, bail};
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+fn assets_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join("assets")
+}
+
+/// Copy `source` into `{data_dir}/assets/` and return the copy's path.
+/// A file already in the assets folder is returned as is; an existing file
+/// of the same name with identical contents is reused, otherwise a numbered
+/// name is picked.
+pub fn copy_to_assets(base_dir: &Path, source: &Path) -> Result<PathBuf> {
+    if !source.is_file() {
+        bail!("File not found: {:?}", source);
+    }
+    let dir = assets_dir(base_dir);
+    if source.parent() == Some(dir.as_path()) {
+        return Ok(source.to_path_buf());
+    }
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create assets directory: {:?}", dir))?;
+
+    let name = source
+        .file_name()
+        .with_context(|| format!("Not a file: {:?}", source))?;
+    let size = fs::metadata(source)?.len();
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = Path::new(name)
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut dest = dir.join(name);
+    let mut n = 1;
+    while dest.exists() {
+        if fs::metadata(&dest).is_ok_and(|m| m.len() == size) && same_contents(source, &dest)? {
+            return Ok(dest);
+        }
+        n += 1;
+        dest = dir.join(format!("{}-{}{}", stem, n, ext));
+    }
+
+    fs::copy(source, &dest).with_context(|| format!("Failed to copy asset: {:?}", source))?;
+    Ok(dest)
+}
+
+/// Whether the files `a` and `b` hold the same bytes, read in chunks so a
+/// large recording is never loaded whole.
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let open = |path: &Path| {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("Failed to read asset: {:?}", path))
+    };
+    let (mut a, mut b) = (open(a)?, open(b)?);
+    let (mut buf_a, mut buf_b) = ([0u8; 8192], [0u8; 8192]);
+    loop {
+        let read = a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(b.read(&mut buf_b[..1])? == 0);
+        }
+        b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_to_assets() {
+        let base = std::env::temp_dir().join(format!("kenotex-assets-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&base).unwrap();
+        let rec = base.join("standup.m4a");
+        fs::write(&rec, b"audio").unwrap();
+
+        let copy = copy_to_assets(&base, &rec).unwrap();
+        assert_eq!(copy, base.join("assets/standup.m4a"));
+        assert_eq!(copy_to_assets(&base, &rec).unwrap(), copy);
+        assert_eq!(copy_to_assets(&base, &copy).unwrap(), copy);
+
+        fs::write(&rec, b"longer audio").unwrap();
+        assert_eq!(
+            copy_to_assets(&base, &rec).unwrap(),
+            base.join("assets/standup-2.m4a")
+        );
+        // Same name and size but other bytes is another file
+        fs::write(&rec, b"AUDIO").unwrap();
+        assert_eq!(
+            copy_to_assets(&base, &rec).unwrap(),
+            base.join("assets/standup-3.m4a")
+        );
+        assert!(copy_to_assets(&base, &base.join("missing.m4a")).is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
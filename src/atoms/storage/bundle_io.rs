@@ -0,0 +1,125 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Write `note.md` plus an `assets/` folder into a gzipped tar at `output`.
+///
+/// When `passphrase_env` names an environment variable, the archive is
+/// encrypted with `openssl enc -aes-256-cbc -pbkdf2` using that variable as
+/// the passphrase. Recipients decrypt with
+/// `openssl enc -d -aes-256-cbc -pbkdf2 -in FILE | tar xz`.
+pub fn write_share_bundle(
+    markdown: &str,
+    assets: &[PathBuf],
+    output: &Path,
+    passphrase_env: Option<&str>,
+) -> Result<()> {
+    if let Some(var) = passphrase_env
+        && std::env::var(var).map_or(true, |v| v.is_empty())
+    {
+        bail!("Environment variable {} is not set", var);
+    }
+
+    let staging = std::env::temp_dir().join(format!("kenotex-bundle-{}", uuid::Uuid::new_v4()));
+    let result = stage_and_archive(&staging, markdown, assets, output, passphrase_env);
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn stage_and_archive(
+    staging: &Path,
+    markdown: &str,
+    assets: &[PathBuf],
+    output: &Path,
+    passphrase_env: Option<&str>,
+) -> Result<()> {
+    let assets_dir = staging.join("assets");
+    fs::create_dir_all(&assets_dir)
+        .with_context(|| format!("Failed to create staging directory: {:?}", assets_dir))?;
+    fs::write(staging.join("note.md"), markdown)?;
+    for asset in assets {
+        if let Some(name) = asset.file_name() {
+            fs::copy(asset, assets_dir.join(name))
+                .with_context(|| format!("Failed to copy asset: {:?}", asset))?;
+        }
+    }
+
+    let Some(var) = passphrase_env else {
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(output)
+            .arg("-C")
+            .arg(staging)
+            .arg(".")
+            .status()
+            .context("Failed to run tar")?;
+        if !status.success() {
+            bail!("tar exited with {}", status);
+        }
+        return Ok(());
+    };
+
+    let mut tar = Command::new("tar")
+        .arg("-czf")
+        .arg("-")
+        .arg("-C")
+        .arg(staging)
+        .arg(".")
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to run tar")?;
+    let tar_out = tar.stdout.take().context("Failed to capture tar output")?;
+    let status = Command::new("openssl")
+        .args(["enc", "-aes-256-cbc", "-pbkdf2", "-salt", "-pass"])
+        .arg(format!("env:{}", var))
+        .arg("-out")
+        .arg(output)
+        .stdin(tar_out)
+        .status()
+        .context("Failed to run openssl")?;
+    let tar_status = tar.wait()?;
+    if !tar_status.success() {
+        bail!("tar exited with {}", tar_status);
+    }
+    if !status.success() {
+        bail!("openssl exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_bundle_contains_note_and_assets() {
+        let dir =
+            std::env::temp_dir().join(format!("kenotex-bundle-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let asset = dir.join("photo.png");
+        fs::write(&asset, "png").unwrap();
+        let output = dir.join("share.tar.gz");
+
+        write_share_bundle("# Note", &[asset], &output, None).unwrap();
+
+        let listing = Command::new("tar")
+            .arg("-tzf")
+            .arg(&output)
+            .output()
+            .unwrap();
+        let listing = String::from_utf8_lossy(&listing.stdout);
+        assert!(listing.contains("note.md"));
+        assert!(listing.contains("assets/photo.png"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_passphrase_is_rejected() {
+        let output = std::env::temp_dir().join("kenotex-never-written.enc");
+        let err = write_share_bundle("x", &[], &output, Some("KENOTEX_TEST_UNSET_PASSPHRASE"));
+        assert!(err.is_err());
+        assert!(!output.exists());
+    }
+}
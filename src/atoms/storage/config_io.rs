@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use toml_edit::DocumentMut;
 
 use crate::types::Config;
 
@@ -42,8 +43,13 @@ pub fn load_config() -> Result<Config> {
         return Ok(config);
     }
 
+    read_config(&path)
+}
+
+/// Parse the config file at `path`.
+pub fn read_config(path: &Path) -> Result<Config> {
     let content =
-        fs::read_to_string(&path).with_context(|| format!("Failed to read config: {:?}", path))?;
+        fs::read_to_string(path).with_context(|| format!("Failed to read config: {:?}", path))?;
 
     let config: Config = toml::from_str(&content).with_context(|| "Failed to parse config.toml")?;
 
@@ -82,6 +88,24 @@ pub fn save_config(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Set `[section] key = value` in the config file at `path`, keeping the
+/// rest of the file, comments included, as it is.
+pub fn save_config_value(path: &Path, section: &str, key: &str, value: &str) -> Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read config: {:?}", path)),
+    };
+    let mut doc: DocumentMut = content
+        .parse()
+        .with_context(|| "Failed to parse config.toml")?;
+    if !doc.contains_table(section) {
+        doc[section] = toml_edit::table();
+    }
+    doc[section][key] = toml_edit::value(value);
+    fs::write(path, doc.to_string()).with_context(|| format!("Failed to write config: {:?}", path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,6 +118,21 @@ mod tests {
         assert_eq!(config.keyboard.layout, "qwerty");
     }
 
+    #[test]
+    fn test_notion_destination() {
+        let config: Config = toml::from_str(
+            "[destinations.notes]\napp = \"notion\"\n\
+             [destinations.notion]\ndatabase_id = \"db1\"\n\
+             [destinations.notion.properties]\ntags = \"Tags\"",
+        )
+        .unwrap();
+        let destinations = config.destinations;
+        assert_eq!(destinations.notes.app, Some(crate::types::NotesApp::Notion));
+        assert_eq!(destinations.notion.database_id.as_deref(), Some("db1"));
+        assert_eq!(destinations.notion.properties.title, "Name");
+        assert_eq!(destinations.notion.properties.tags.as_deref(), Some("Tags"));
+    }
+
     #[test]
     fn test_config_path_xdg() {
         // Verify config path uses ~/.config/kenotex on Unix
@@ -136,4 +175,38 @@ mod tests {
         let resolved = resolve_data_dir(None);
         assert_eq!(resolved, config_dir());
     }
+
+    #[test]
+    fn test_save_config_value_keeps_comments() {
+        let path =
+            std::env::temp_dir().join(format!("kenotex-config-{}.toml", uuid::Uuid::new_v4()));
+        fs::write(&path, "# mine\n[general]\ntheme = \"nord\" # dark\n").unwrap();
+        save_config_value(&path, "general", "list_sort", "title").unwrap();
+        save_config_value(&path, "general", "list_sort", "size").unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# mine\n[general]\ntheme = \"nord\" # dark\nlist_sort = \"size\"\n"
+        );
+        fs::remove_file(&path).unwrap();
+
+        save_config_value(&path, "general", "list_sort", "created").unwrap();
+        let config: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.general.list_sort, "created");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_layout_preset_under_overrides() {
+        let parse = |toml: &str| toml::from_str::<Config>(toml).map(|c| c.keyboard);
+        let keys = parse("[keyboard]\nlayout = \"dvorak\"\nmove_up = \"k\"").unwrap();
+        assert_eq!((keys.move_left.as_str(), keys.move_up.as_str()), ("d", "k"));
+        assert_eq!(keys.delete_line, "j");
+        let keys = parse("[keyboard]\nlayout = \"Workman\"").unwrap();
+        assert_eq!((keys.move_down.as_str(), keys.yank.as_str()), ("n", "j"));
+        assert_eq!(parse("[keyboard]\nlayout = \"colemak\"").unwrap().undo, "z");
+        assert_eq!(parse("[general]").unwrap().move_left, "h");
+        let err = parse("[keyboard]\nlayout = \"azerty\"").unwrap_err();
+        assert!(err.to_string().contains("unknown keyboard.layout"));
+    }
 }
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::{config_dir, expand_tilde};
+
+/// Where hunspell dictionaries are looked for, in order: `dictionaries/`
+/// in the config directory, then the system locations on macOS and Linux.
+pub fn dictionary_dirs() -> Vec<PathBuf> {
+    vec![
+        config_dir().join("dictionaries"),
+        expand_tilde("~/Library/Spelling"),
+        PathBuf::from("/Library/Spelling"),
+        PathBuf::from("/usr/share/hunspell"),
+        PathBuf::from("/usr/share/myspell"),
+        PathBuf::from("/usr/share/myspell/dicts"),
+    ]
+}
+
+/// The `.dic` text of dictionary `language` (`en_US`; `en-US` works too)
+/// from the first of `dirs` that has it, with its `.aff` text when present.
+pub fn load_dictionary(
+    dirs: &[PathBuf],
+    language: &str,
+) -> Result<Option<(String, Option<String>)>> {
+    let names = [language.replace('-', "_"), language.replace('_', "-")];
+    let Some(dic) = dirs
+        .iter()
+        .flat_map(|dir| {
+            names
+                .iter()
+                .map(move |name| dir.join(format!("{}.dic", name)))
+        })
+        .find(|path| path.is_file())
+    else {
+        return Ok(None);
+    };
+    let words = read(&dic)?;
+    let aff = dic.with_extension("aff");
+    let rules = if aff.is_file() {
+        Some(read(&aff)?)
+    } else {
+        None
+    };
+    Ok(Some((words, rules)))
+}
+
+/// Dictionaries are often Latin-1; bytes that are not UTF-8 are read as
+/// Latin-1.
+fn read(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_dictionary_by_language() {
+        let dir = std::env::temp_dir().join(format!("kenotex-dict-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("de_DE.dic"), b"1\nStra\xdfe\n").unwrap();
+        fs::write(dir.join("en-GB.dic"), "1\ncolour\n").unwrap();
+        fs::write(dir.join("en-GB.aff"), "SET UTF-8\n").unwrap();
+        let dirs = [dir.join("missing"), dir.clone()];
+
+        let (words, rules) = load_dictionary(&dirs, "de-DE").unwrap().unwrap();
+        assert_eq!(words, "1\nStraße\n");
+        assert!(rules.is_none());
+        let (_, rules) = load_dictionary(&dirs, "en_GB").unwrap().unwrap();
+        assert_eq!(rules.as_deref(), Some("SET UTF-8\n"));
+        assert!(load_dictionary(&dirs, "fr_FR").unwrap().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
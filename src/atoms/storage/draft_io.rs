@@ -1,9 +1,14 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
 
-use crate::types::Note;
+use crate::types::{
+    Frontmatter, Note, insert_frontmatter_field, parse_tags, take_frontmatter_field,
+};
 
 fn drafts_dir(base_dir: &Path) -> PathBuf {
     base_dir.join("drafts")
@@ -13,6 +18,11 @@ fn archives_dir(base_dir: &Path) -> PathBuf {
     base_dir.join("archives")
 }
 
+/// Deleted notes wait here until purged.
+fn trash_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join("trash")
+}
+
 pub fn ensure_data_dirs(base_dir: &Path) -> Result<()> {
     let dir = drafts_dir(base_dir);
     if !dir.exists() {
@@ -27,33 +37,226 @@ pub fn ensure_data_dirs(base_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn draft_path(base_dir: &Path, id: &str, is_archived: bool) -> PathBuf {
-    let dir = if is_archived {
+/// How note files are named on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileNaming {
+    /// `<id>.md`.
+    #[default]
+    Id,
+    /// `<slugified title>.md`, with the id kept in a `kenotex_id:` frontmatter
+    /// field.
+    Title,
+}
+
+impl FileNaming {
+    /// Parse a `file_naming` config value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "id" | "uuid" => Some(FileNaming::Id),
+            "title" | "slug" => Some(FileNaming::Title),
+            _ => None,
+        }
+    }
+}
+
+/// Frontmatter key holding the note id when the file name is not the id.
+/// Namespaced so a note's own `id:` field stays part of its content.
+const ID_KEY: &str = "kenotex_id";
+
+/// Modification time and length of a file, to tell when it changed.
+type FileStamp = (SystemTime, u64);
+
+/// Ids read from note files by path, valid while the file's stamp is
+/// unchanged, so finding a note not named `<id>.md` doesn't re-read every
+/// file in its directory.
+static ID_CACHE: LazyLock<Mutex<HashMap<PathBuf, (FileStamp, String)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn cached_id(path: &Path, stamp: FileStamp) -> Option<String> {
+    let cache = ID_CACHE.lock().ok()?;
+    cache
+        .get(path)
+        .filter(|(cached, _)| *cached == stamp)
+        .map(|(_, id)| id.clone())
+}
+
+fn cache_id(path: &Path, id: &str) {
+    if let (Some(stamp), Ok(mut cache)) = (file_stamp(path), ID_CACHE.lock()) {
+        cache.insert(path.to_path_buf(), (stamp, id.to_string()));
+    }
+}
+
+/// Whether `id` can name a file in a notes directory: not empty, no path
+/// separators, not `.` or `..`.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id != "." && id != ".." && !id.contains(['/', '\\', '\0'])
+}
+
+pub fn notes_dir(base_dir: &Path, is_archived: bool) -> PathBuf {
+    if is_archived {
         archives_dir(base_dir)
     } else {
         drafts_dir(base_dir)
-    };
-    dir.join(format!("{}.md", id))
+    }
 }
 
-pub fn load_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<Note> {
-    let path = draft_path(base_dir, id, is_archived);
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Split a file's content into the note content and its id: the
+/// `kenotex_id` frontmatter field when it holds a valid id, otherwise the
+/// file stem.
+fn split_note_id(raw: String, path: &Path) -> (String, String) {
+    match take_frontmatter_field(&raw, ID_KEY) {
+        Some((content, id)) if is_valid_id(&id) => (content, id),
+        _ => (raw, file_stem(path)),
+    }
+}
+
+/// Id of the note stored at `path`, if it is a readable note file.
+pub fn note_id_for_path(path: &Path) -> Option<String> {
+    if !is_markdown(path) {
+        return None;
+    }
+    let stamp = file_stamp(path);
+    if let Some(id) = stamp.and_then(|stamp| cached_id(path, stamp)) {
+        return Some(id);
+    }
+    match fs::read_to_string(path) {
+        Ok(raw) => {
+            let id = split_note_id(raw, path).1;
+            cache_id(path, &id);
+            Some(id)
+        }
+        Err(_) => Some(file_stem(path)),
+    }
+}
+
+/// Note content stored at `path`, without the `kenotex_id` frontmatter field.
+pub fn note_content_for_path(path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    Some(split_note_id(raw, path).0)
+}
+
+/// Find the file holding note `id`: `<id>.md` if present, otherwise the
+/// file whose frontmatter carries the id.
+fn find_note_path(base_dir: &Path, id: &str, is_archived: bool) -> Option<PathBuf> {
+    find_in_dir(&notes_dir(base_dir, is_archived), id)
+}
 
-    let content =
-        fs::read_to_string(&path).with_context(|| format!("Failed to read draft: {:?}", path))?;
+fn find_in_dir(dir: &Path, id: &str) -> Option<PathBuf> {
+    if !is_valid_id(id) {
+        return None;
+    }
+    let by_id = dir.join(format!("{}.md", id));
+    if by_id.exists() {
+        return Some(by_id);
+    }
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .find(|path| note_id_for_path(path).as_deref() == Some(id))
+}
+
+/// File-name friendly version of a note title: lowercase, runs of anything
+/// but letters and digits turned into one `-`; `fallback` when none is left.
+pub fn title_slug(title: &str, fallback: &str) -> String {
+    let slug = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug
+    }
+}
 
-    let metadata = fs::metadata(&path)?;
+/// Where `note` should be written under `naming`. Also returns the file it
+/// currently lives in, if any, so a rename can remove it.
+fn target_path(base_dir: &Path, note: &Note, naming: FileNaming) -> (PathBuf, Option<PathBuf>) {
+    let dir = notes_dir(base_dir, note.is_archived);
+    match naming {
+        FileNaming::Id => {
+            // Keep whatever name the note already has so switching modes
+            // never mass-renames files
+            let existing = find_note_path(base_dir, &note.id, note.is_archived);
+            let path = existing
+                .clone()
+                .unwrap_or_else(|| dir.join(format!("{}.md", note.id)));
+            (path, existing)
+        }
+        FileNaming::Title => {
+            let slug = title_slug(&note.title, "untitled");
+            for n in 1.. {
+                let name = if n == 1 {
+                    format!("{}.md", slug)
+                } else {
+                    format!("{}-{}.md", slug, n)
+                };
+                let path = dir.join(name);
+                if !path.exists() {
+                    let existing = find_note_path(base_dir, &note.id, note.is_archived);
+                    return (path, existing);
+                }
+                if note_id_for_path(&path).as_deref() == Some(note.id.as_str()) {
+                    return (path.clone(), Some(path));
+                }
+            }
+            unreachable!()
+        }
+    }
+}
+
+fn read_note(path: &Path, is_archived: bool) -> Result<Note> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read draft: {:?}", path))?;
+    let (content, id) = split_note_id(raw, path);
+    cache_id(path, &id);
+
+    let metadata = fs::metadata(path)?;
     let created_at: DateTime<Utc> = metadata
         .created()
         .map(|t| t.into())
         .unwrap_or_else(|_| Utc::now());
     let updated_at: DateTime<Utc> = metadata.modified().map(|t| t.into()).unwrap_or(created_at);
 
+    // Frontmatter dates survive copies that reset file times, but a later
+    // edit of the file still counts as the last update
+    let frontmatter = Frontmatter::parse(&content);
+    let created_at = frontmatter
+        .as_ref()
+        .and_then(|fm| fm.date("created"))
+        .unwrap_or(created_at);
+    let updated_at = frontmatter
+        .as_ref()
+        .and_then(|fm| fm.date("updated"))
+        .map_or(updated_at, |date| date.max(updated_at));
+
     let title = Note::extract_title(&content);
+    let tags = parse_tags(&content);
 
     Ok(Note {
-        id: id.to_string(),
+        id,
         title,
+        tags,
         content,
         created_at,
         updated_at,
@@ -62,86 +265,422 @@ pub fn load_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<Note>
     })
 }
 
+pub fn load_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<Note> {
+    let path = find_note_path(base_dir, id, is_archived)
+        .with_context(|| format!("Failed to read draft: {} not found", id))?;
+    read_note(&path, is_archived)
+}
+
+/// Like `load_draft`, but a note that does not exist is `None` rather than
+/// an error. A note file that exists but cannot be read is still an error.
+pub fn find_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<Option<Note>> {
+    find_note_path(base_dir, id, is_archived)
+        .map(|path| read_note(&path, is_archived))
+        .transpose()
+}
+
 pub fn load_all_drafts(base_dir: &Path, archived: bool) -> Result<Vec<Note>> {
-    let dir = if archived {
-        archives_dir(base_dir)
-    } else {
-        drafts_dir(base_dir)
-    };
+    load_dir(&notes_dir(base_dir, archived), archived)
+}
 
+fn load_dir(dir: &Path, archived: bool) -> Result<Vec<Note>> {
     if !dir.exists() {
         return Ok(Vec::new());
     }
 
     let mut notes = Vec::new();
 
-    for entry in fs::read_dir(&dir)? {
+    for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
 
-        if path.extension().is_some_and(|ext| ext == "md")
-            && let Some(stem) = path.file_stem() {
-                let id = stem.to_string_lossy().to_string();
-                match load_draft(base_dir, &id, archived) {
-                    Ok(note) => notes.push(note),
-                    Err(e) => eprintln!("Warning: Failed to load draft {}: {}", id, e),
-                }
+        if is_markdown(&path) {
+            match read_note(&path, archived) {
+                Ok(note) => notes.push(note),
+                Err(e) => eprintln!("Warning: Failed to load draft {:?}: {}", path, e),
             }
+        }
     }
 
-    notes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    notes.sort_by_key(|n| std::cmp::Reverse(n.updated_at));
     Ok(notes)
 }
 
-pub fn save_draft(base_dir: &Path, note: &Note) -> Result<()> {
+/// Look up a draft or archived note by id, case-insensitive title or
+/// alias, or id prefix. An alias claimed by more than one note is an error.
+pub fn find_note(base_dir: &Path, query: &str) -> Result<Option<Note>> {
+    let mut notes = load_all_drafts(base_dir, false)?;
+    notes.extend(load_all_drafts(base_dir, true)?);
+
+    let query_lower = query.to_lowercase();
+    let found = notes.iter().position(|n| n.id == query).or_else(|| {
+        notes
+            .iter()
+            .position(|n| n.title.to_lowercase() == query_lower)
+    });
+    if let Some(idx) = found {
+        return Ok(Some(notes.swap_remove(idx)));
+    }
+
+    let claimed: Vec<usize> = (0..notes.len())
+        .filter(|&idx| notes[idx].has_alias(query))
+        .collect();
+    match claimed[..] {
+        [] => {}
+        [idx] => return Ok(Some(notes.swap_remove(idx))),
+        _ => {
+            let titles: Vec<&str> = claimed.iter().map(|&i| notes[i].title.as_str()).collect();
+            bail!(
+                "Alias '{}' is claimed by several notes: {}",
+                query,
+                titles.join(", ")
+            );
+        }
+    }
+
+    let found = notes
+        .iter()
+        .position(|n| !query.is_empty() && n.id.starts_with(query));
+    Ok(found.map(|idx| notes.swap_remove(idx)))
+}
+
+/// Write `note`, naming new files per `naming`. Under `FileNaming::Title`
+/// the file follows title changes; the old file is removed after the write.
+pub fn save_draft(base_dir: &Path, note: &Note, naming: FileNaming) -> Result<()> {
+    if !is_valid_id(&note.id) {
+        bail!("Invalid note id: {:?}", note.id);
+    }
     ensure_data_dirs(base_dir)?;
-    let path = draft_path(base_dir, &note.id, note.is_archived);
+    let (path, existing) = target_path(base_dir, note, naming);
 
-    fs::write(&path, &note.content).with_context(|| format!("Failed to save draft: {:?}", path))?;
+    let content = if file_stem(&path) == note.id {
+        note.content.clone()
+    } else {
+        insert_frontmatter_field(&note.content, ID_KEY, &note.id)
+    };
+    fs::write(&path, content).with_context(|| format!("Failed to save draft: {:?}", path))?;
+    cache_id(&path, &note.id);
+
+    if let Some(old) = existing.filter(|old| *old != path) {
+        fs::remove_file(&old).with_context(|| format!("Failed to remove old draft: {:?}", old))?;
+    }
 
     Ok(())
 }
 
 pub fn delete_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<()> {
-    let path = draft_path(base_dir, id, is_archived);
-
-    if path.exists() {
+    if let Some(path) = find_note_path(base_dir, id, is_archived) {
         fs::remove_file(&path).with_context(|| format!("Failed to delete draft: {:?}", path))?;
     }
 
     Ok(())
 }
 
-pub fn archive_draft(base_dir: &Path, note: &mut Note) -> Result<()> {
-    let old_path = draft_path(base_dir, &note.id, false);
-    note.is_archived = true;
-    let new_path = draft_path(base_dir, &note.id, true);
+/// Move a note between `drafts/` and `archives/`, keeping its file name.
+fn move_draft(base_dir: &Path, note: &mut Note, to_archive: bool) -> Result<()> {
+    let old_path = find_note_path(base_dir, &note.id, !to_archive);
+    note.is_archived = to_archive;
 
     ensure_data_dirs(base_dir)?;
 
-    if old_path.exists() {
-        fs::rename(&old_path, &new_path)
-            .with_context(|| format!("Failed to archive draft: {:?}", old_path))?;
-    } else {
-        save_draft(base_dir, note)?;
+    match old_path {
+        Some(old_path) => {
+            let new_path =
+                notes_dir(base_dir, to_archive).join(old_path.file_name().unwrap_or_default());
+            if new_path.exists() {
+                // Name taken on the other side: write under the id instead
+                save_draft(base_dir, note, FileNaming::Id)?;
+                fs::remove_file(&old_path)
+                    .with_context(|| format!("Failed to move draft: {:?}", old_path))?;
+            } else {
+                fs::rename(&old_path, &new_path)
+                    .with_context(|| format!("Failed to move draft: {:?}", old_path))?;
+            }
+        }
+        None => save_draft(base_dir, note, FileNaming::Id)?,
     }
 
     Ok(())
 }
 
-pub fn restore_draft(base_dir: &Path, note: &mut Note) -> Result<()> {
-    let old_path = draft_path(base_dir, &note.id, true);
-    note.is_archived = false;
-    let new_path = draft_path(base_dir, &note.id, false);
+/// Move the file at `from` into `dir`, keeping its name unless another
+/// note has it there, in which case it becomes `<id>.md`.
+fn move_into(from: &Path, dir: &Path, id: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {:?}", dir))?;
+    let mut to = dir.join(from.file_name().unwrap_or_default());
+    if to.exists() && note_id_for_path(&to).as_deref() != Some(id) {
+        if !is_valid_id(id) {
+            bail!("Invalid note id: {:?}", id);
+        }
+        to = dir.join(format!("{}.md", id));
+    }
+    fs::rename(from, &to).with_context(|| format!("Failed to move note: {:?}", from))?;
+    Ok(to)
+}
 
-    ensure_data_dirs(base_dir)?;
+/// Move note `id` from the drafts or the archive to `trash/`. The file's
+/// modification time becomes the deletion time, which the trash list
+/// sorts by and `purge_trash` ages it from.
+pub fn trash_draft(base_dir: &Path, id: &str, is_archived: bool) -> Result<()> {
+    let Some(path) = find_note_path(base_dir, id, is_archived) else {
+        return Ok(());
+    };
+    let path = move_into(&path, &trash_dir(base_dir), id)?;
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+        .with_context(|| format!("Failed to stamp trashed note: {:?}", path))?;
+    Ok(())
+}
 
-    if old_path.exists() {
-        fs::rename(&old_path, &new_path)
-            .with_context(|| format!("Failed to restore draft: {:?}", old_path))?;
-    } else {
-        save_draft(base_dir, note)?;
+/// The notes in the trash, most recently deleted first.
+pub fn load_trash(base_dir: &Path) -> Result<Vec<Note>> {
+    load_dir(&trash_dir(base_dir), false)
+}
+
+/// Move a trashed note back to the drafts.
+pub fn restore_from_trash(base_dir: &Path, note: &mut Note) -> Result<()> {
+    note.is_archived = false;
+    match find_in_dir(&trash_dir(base_dir), &note.id) {
+        Some(path) => move_into(&path, &drafts_dir(base_dir), &note.id).map(|_| ()),
+        None => save_draft(base_dir, note, FileNaming::Id),
     }
+}
 
+/// Delete the trashed copy of note `id` for good.
+pub fn purge_trashed(base_dir: &Path, id: &str) -> Result<()> {
+    if let Some(path) = find_in_dir(&trash_dir(base_dir), id) {
+        fs::remove_file(&path).with_context(|| format!("Failed to delete note: {:?}", path))?;
+    }
     Ok(())
 }
+
+/// Delete the notes trashed more than `days` days ago; 0 keeps them all.
+/// Returns how many went.
+pub fn purge_trash(base_dir: &Path, days: u64) -> Result<usize> {
+    let dir = trash_dir(base_dir);
+    if days == 0 || !dir.exists() {
+        return Ok(0);
+    }
+    let max_age = std::time::Duration::from_secs(days * 24 * 60 * 60);
+    let mut purged = 0;
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let age = fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+        if is_markdown(&path) && age.is_some_and(|age| age > max_age) {
+            fs::remove_file(&path).with_context(|| format!("Failed to delete note: {:?}", path))?;
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+pub fn archive_draft(base_dir: &Path, note: &mut Note) -> Result<()> {
+    move_draft(base_dir, note, true)
+}
+
+pub fn restore_draft(base_dir: &Path, note: &mut Note) -> Result<()> {
+    move_draft(base_dir, note, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("kenotex-drafts-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_title_naming_keeps_id_in_frontmatter() {
+        let dir = temp_dir();
+        let mut note = Note::new(
+            "id-1".into(),
+            "Trip Plan".into(),
+            "# Trip Plan\nBody".into(),
+        );
+        save_draft(&dir, &note, FileNaming::Title).unwrap();
+
+        let path = dir.join("drafts/trip-plan.md");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\nkenotex_id: id-1\n---\n# Trip Plan\nBody"
+        );
+        let loaded = load_draft(&dir, "id-1", false).unwrap();
+        assert_eq!(loaded.content, "# Trip Plan\nBody");
+
+        // Retitling renames the file; identity is unchanged
+        note.update_content("# Kyoto\nBody".into());
+        save_draft(&dir, &note, FileNaming::Title).unwrap();
+        assert!(!path.exists());
+        assert!(dir.join("drafts/kyoto.md").exists());
+        assert_eq!(load_all_drafts(&dir, false).unwrap()[0].id, "id-1");
+
+        delete_draft(&dir, "id-1", false).unwrap();
+        assert!(load_all_drafts(&dir, false).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_own_id_field_is_content_and_bad_ids_are_refused() {
+        let dir = temp_dir();
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+        let path = dir.join("drafts/paper.md");
+        fs::write(&path, "---\nid: 10.1000/xyz\n---\n# Paper").unwrap();
+        let loaded = load_draft(&dir, "paper", false).unwrap();
+        assert_eq!(loaded.content, "---\nid: 10.1000/xyz\n---\n# Paper");
+
+        // A mangled key never leaves the notes directory
+        fs::write(&path, "---\nkenotex_id: ../x\n---\n# Paper").unwrap();
+        assert_eq!(note_id_for_path(&path).as_deref(), Some("paper"));
+        let escape = Note::new("../x".into(), "X".into(), "# X".into());
+        assert!(save_draft(&dir, &escape, FileNaming::Id).is_err());
+        assert!(load_draft(&dir, "../x", false).is_err());
+        assert!(!dir.join("x.md").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_draft_tells_missing_from_unreadable() {
+        let dir = temp_dir();
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+        assert!(find_draft(&dir, "inbox", false).unwrap().is_none());
+        fs::write(dir.join("drafts/inbox.md"), [0xff, 0xfe]).unwrap();
+        assert!(find_draft(&dir, "inbox", false).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_frontmatter_dates() {
+        let dir = temp_dir();
+        let content =
+            "+++\ncreated = \"2020-01-02T03:04:05Z\"\nupdated = \"2020-01-03\"\n+++\n# Old";
+        let note = Note::new("d".into(), "Old".into(), content.into());
+        save_draft(&dir, &note, FileNaming::Title).unwrap();
+
+        let loaded = load_draft(&dir, "d", false).unwrap();
+        assert_eq!(loaded.content, content);
+        assert_eq!(loaded.created_at.to_rfc3339(), "2020-01-02T03:04:05+00:00");
+        // The file was written after the `updated` date
+        assert!(loaded.updated_at > loaded.created_at + chrono::Duration::days(365));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_title_naming_avoids_collisions() {
+        let dir = temp_dir();
+        let a = Note::new("a".into(), "Notes".into(), "# Notes".into());
+        let b = Note::new("b".into(), "Notes".into(), "# Notes\nother".into());
+        save_draft(&dir, &a, FileNaming::Title).unwrap();
+        save_draft(&dir, &b, FileNaming::Title).unwrap();
+        save_draft(&dir, &a, FileNaming::Title).unwrap();
+
+        assert_eq!(
+            note_id_for_path(&dir.join("drafts/notes.md")).as_deref(),
+            Some("a")
+        );
+        assert_eq!(
+            note_id_for_path(&dir.join("drafts/notes-2.md")).as_deref(),
+            Some("b")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_id_naming_keeps_existing_file_name() {
+        let dir = temp_dir();
+        let mut note = Note::new("id-2".into(), "Plan".into(), "# Plan".into());
+        save_draft(&dir, &note, FileNaming::Title).unwrap();
+        note.update_content("# Plan\nmore".into());
+        save_draft(&dir, &note, FileNaming::Id).unwrap();
+
+        assert!(dir.join("drafts/plan.md").exists());
+        assert!(!dir.join("drafts/id-2.md").exists());
+        assert_eq!(
+            load_draft(&dir, "id-2", false).unwrap().content,
+            "# Plan\nmore"
+        );
+
+        archive_draft(&dir, &mut note).unwrap();
+        assert!(dir.join("archives/plan.md").exists());
+        assert_eq!(load_draft(&dir, "id-2", true).unwrap().id, "id-2");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let dir = temp_dir();
+        let plan = Note::new("p".into(), "Plan".into(), "# Plan".into());
+        let old = Note::new("o".into(), "Old".into(), "# Old".into());
+        save_draft(&dir, &plan, FileNaming::Title).unwrap();
+        save_draft(&dir, &old, FileNaming::Id).unwrap();
+
+        trash_draft(&dir, "p", false).unwrap();
+        trash_draft(&dir, "o", false).unwrap();
+        assert!(load_all_drafts(&dir, false).unwrap().is_empty());
+        assert!(dir.join("trash/plan.md").exists());
+        let mut trashed = load_trash(&dir).unwrap();
+        assert_eq!(trashed.len(), 2);
+
+        // Only notes trashed longer ago than the limit are purged
+        let long_ago = SystemTime::now() - std::time::Duration::from_secs(40 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(dir.join("trash/o.md"))
+            .unwrap()
+            .set_modified(long_ago)
+            .unwrap();
+        assert_eq!(purge_trash(&dir, 0).unwrap(), 0);
+        assert_eq!(purge_trash(&dir, 30).unwrap(), 1);
+
+        let plan = trashed.iter_mut().find(|n| n.id == "p").unwrap();
+        restore_from_trash(&dir, plan).unwrap();
+        assert_eq!(load_draft(&dir, "p", false).unwrap().content, "# Plan");
+        assert!(load_trash(&dir).unwrap().is_empty());
+
+        trash_draft(&dir, "p", false).unwrap();
+        purge_trashed(&dir, "p").unwrap();
+        assert!(load_trash(&dir).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_note_by_alias() {
+        let dir = temp_dir();
+        let note = |id: &str, content: &str| {
+            Note::new(id.into(), Note::extract_title(content), content.into())
+        };
+        let plan = note("id-3", "---\naliases: [Roadmap, Q3]\n---\n# Plan");
+        save_draft(&dir, &plan, FileNaming::Id).unwrap();
+        save_draft(&dir, &note("id-4", "# Roadmap"), FileNaming::Id).unwrap();
+
+        let found = |query| find_note(&dir, query).unwrap().map(|n| n.id);
+        assert_eq!(found("q3").as_deref(), Some("id-3"));
+        // A title wins over another note's alias
+        assert_eq!(found("roadmap").as_deref(), Some("id-4"));
+        assert_eq!(found("id-4").as_deref(), Some("id-4"));
+        assert_eq!(found("Q4"), None);
+
+        save_draft(
+            &dir,
+            &note("id-5", "---\naliases: q3\n---\n# Other"),
+            FileNaming::Id,
+        )
+        .unwrap();
+        let err = find_note(&dir, "Q3").unwrap_err().to_string();
+        assert!(err.contains("Plan") && err.contains("Other"), "{}", err);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -12,27 +12,52 @@ pub fn resolve_editor() -> String {
         .unwrap_or_else(|_| "vi".to_string())
 }
 
-/// Writes content to a temporary file for external editing.
+/// Writes content to a temporary file for external editing, named after
+/// `stem` and ending in `.md` so the editor highlights it as Markdown.
 /// Returns the path to the created temp file.
-pub fn write_temp_file(content: &str) -> Result<PathBuf> {
+pub fn write_temp_file(content: &str, stem: &str) -> Result<PathBuf> {
     let tmp_dir = std::env::temp_dir();
     let pid = std::process::id();
-    let path = tmp_dir.join(format!("kenotex_{}.md", pid));
+    let path = tmp_dir.join(format!("kenotex_{}_{}.md", pid, stem));
     fs::write(&path, content).context("Failed to write temp file for external editor")?;
     Ok(path)
 }
 
-/// Spawns the editor process and waits for it to exit.
+/// The program and arguments that open `path` at 1-based `line`.
 /// The editor string is split by whitespace to support commands like `"code --wait"`.
-/// Returns `true` if the editor exited successfully.
-pub fn spawn_editor(editor: &str, path: &Path) -> Result<bool> {
+/// Editors known to take a line get it in their own syntax (`+line`,
+/// `path:line` or `--goto path:line`); others just get the path.
+pub fn editor_command(editor: &str, path: &Path, line: usize) -> Result<(String, Vec<String>)> {
     let mut parts = editor.split_whitespace();
     let program = parts.next().context("Empty editor command")?;
-    let args: Vec<&str> = parts.collect();
+    let mut args: Vec<String> = parts.map(str::to_string).collect();
+    let path = path.display().to_string();
+    let name = Path::new(program)
+        .file_name()
+        .map_or(program, |n| n.to_str().unwrap_or(program));
+    match name {
+        "vi" | "vim" | "nvim" | "gvim" | "mvim" | "nano" | "emacs" | "emacsclient" | "kak"
+        | "micro" | "mg" | "joe" => {
+            args.push(format!("+{}", line));
+            args.push(path);
+        }
+        "hx" | "helix" | "subl" => args.push(format!("{}:{}", path, line)),
+        "code" | "codium" | "cursor" => {
+            args.push("--goto".to_string());
+            args.push(format!("{}:{}", path, line));
+        }
+        _ => args.push(path),
+    }
+    Ok((program.to_string(), args))
+}
 
-    let status = Command::new(program)
+/// Spawns the editor on `path` at `line` and waits for it to exit.
+/// Returns `true` if the editor exited successfully.
+pub fn spawn_editor(editor: &str, path: &Path, line: usize) -> Result<bool> {
+    let (program, args) = editor_command(editor, path, line)?;
+
+    let status = Command::new(&program)
         .args(&args)
-        .arg(path)
         .status()
         .with_context(|| format!("Failed to spawn editor: {}", editor))?;
 
@@ -111,8 +136,9 @@ mod tests {
     #[test]
     fn test_write_and_read_temp_file() {
         let content = "Hello, external editor!\nLine two.";
-        let path = write_temp_file(content).unwrap();
+        let path = write_temp_file(content, "trip-plan").unwrap();
         assert!(path.exists());
+        assert!(path.to_string_lossy().ends_with("_trip-plan.md"));
 
         let read_back = read_temp_file(&path).unwrap();
         assert_eq!(read_back, content);
@@ -121,6 +147,26 @@ mod tests {
         assert!(!path.exists());
     }
 
+    #[test]
+    fn test_editor_command_passes_line() {
+        let path = Path::new("/tmp/note.md");
+        let command = |editor: &str| editor_command(editor, path, 12).unwrap();
+        assert_eq!(
+            command("/usr/bin/nvim"),
+            (
+                "/usr/bin/nvim".to_string(),
+                vec!["+12".into(), "/tmp/note.md".into()]
+            )
+        );
+        assert_eq!(command("hx").1, ["/tmp/note.md:12"]);
+        assert_eq!(
+            command("code --wait").1,
+            ["--wait", "--goto", "/tmp/note.md:12"]
+        );
+        assert_eq!(command("ed").1, ["/tmp/note.md"]);
+        assert!(editor_command("  ", path, 1).is_err());
+    }
+
     #[test]
     fn test_cleanup_nonexistent_file() {
         let path = std::env::temp_dir().join("kenotex_nonexistent_test.md");
@@ -10,13 +10,30 @@ use notify_debouncer_mini::{DebouncedEventKind, Debouncer, new_debouncer};
 pub enum FileEvent {
     Modified(PathBuf, bool),
     Removed(PathBuf, bool),
+    /// The backend reported an error; events may be missed until restarted.
+    Error(String),
 }
 
 pub struct FileWatcherHandle {
-    _debouncer: Debouncer<notify::RecommendedWatcher>,
+    debouncer: Debouncer<notify::RecommendedWatcher>,
+    watched: Vec<PathBuf>,
     pub receiver: mpsc::Receiver<FileEvent>,
 }
 
+impl FileWatcherHandle {
+    /// Stop watching and shut the debouncer thread down. Events still queued
+    /// in the channel are discarded.
+    pub fn shutdown(mut self) {
+        for dir in &self.watched {
+            let _ = self.debouncer.watcher().unwatch(dir);
+        }
+        // Dropping the debouncer signals its thread to exit; once it has, the
+        // sender side of the channel is gone too
+        drop(self.debouncer);
+        while self.receiver.try_recv().is_ok() {}
+    }
+}
+
 pub fn start_watcher(
     drafts_dir: &Path,
     archives_dir: &Path,
@@ -29,34 +46,41 @@ pub fn start_watcher(
     let mut debouncer = new_debouncer(
         Duration::from_millis(debounce_ms),
         move |res: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
-            if let Ok(events) = res {
-                for event in events {
-                    let path = &event.path;
-                    if path.extension().is_some_and(|ext| ext == "md") {
-                        let is_archived = path.starts_with(&archives_dir_owned);
-                        let file_event = match event.kind {
-                            DebouncedEventKind::Any => {
-                                if path.exists() {
-                                    FileEvent::Modified(path.clone(), is_archived)
-                                } else {
-                                    FileEvent::Removed(path.clone(), is_archived)
-                                }
+            let events = match res {
+                Ok(events) => events,
+                Err(e) => {
+                    let _ = tx.send(FileEvent::Error(e.to_string()));
+                    return;
+                }
+            };
+            for event in events {
+                let path = &event.path;
+                if path.extension().is_some_and(|ext| ext == "md") {
+                    let is_archived = path.starts_with(&archives_dir_owned);
+                    let file_event = match event.kind {
+                        DebouncedEventKind::Any => {
+                            if path.exists() {
+                                FileEvent::Modified(path.clone(), is_archived)
+                            } else {
+                                FileEvent::Removed(path.clone(), is_archived)
                             }
-                            DebouncedEventKind::AnyContinuous | _ => continue,
-                        };
-                        let _ = tx.send(file_event);
-                    }
+                        }
+                        DebouncedEventKind::AnyContinuous | _ => continue,
+                    };
+                    let _ = tx.send(file_event);
                 }
             }
         },
     )
     .with_context(|| "Failed to create file watcher")?;
 
+    let mut watched = Vec::new();
     if drafts_dir.exists() {
         debouncer
             .watcher()
             .watch(drafts_dir, RecursiveMode::NonRecursive)
             .with_context(|| format!("Failed to watch drafts dir: {:?}", drafts_dir))?;
+        watched.push(drafts_dir.to_path_buf());
     }
 
     if archives_dir.exists() {
@@ -64,10 +88,55 @@ pub fn start_watcher(
             .watcher()
             .watch(archives_dir, RecursiveMode::NonRecursive)
             .with_context(|| format!("Failed to watch archives dir: {:?}", archives_dir))?;
+        watched.push(archives_dir.to_path_buf());
     }
 
     Ok(FileWatcherHandle {
-        _debouncer: debouncer,
+        debouncer,
+        watched,
+        receiver: rx,
+    })
+}
+
+/// Watch a single config file. Its directory is watched rather than the
+/// file itself, since editors often save by replacing the file; only
+/// events for `config_file` come through, as `Modified(path, false)`.
+pub fn start_config_watcher(config_file: &Path, debounce_ms: u64) -> Result<FileWatcherHandle> {
+    let (tx, rx) = mpsc::channel();
+
+    let config_file_owned = config_file.to_path_buf();
+
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(debounce_ms),
+        move |res: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            let events = match res {
+                Ok(events) => events,
+                Err(e) => {
+                    let _ = tx.send(FileEvent::Error(e.to_string()));
+                    return;
+                }
+            };
+            let changed = events.iter().any(|event| {
+                event.path == config_file_owned && event.kind == DebouncedEventKind::Any
+            });
+            if changed && config_file_owned.exists() {
+                let _ = tx.send(FileEvent::Modified(config_file_owned.clone(), false));
+            }
+        },
+    )
+    .with_context(|| "Failed to create config watcher")?;
+
+    let dir = config_file
+        .parent()
+        .with_context(|| format!("No directory for config: {:?}", config_file))?;
+    debouncer
+        .watcher()
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch config dir: {:?}", dir))?;
+
+    Ok(FileWatcherHandle {
+        debouncer,
+        watched: vec![dir.to_path_buf()],
         receiver: rx,
     })
 }
@@ -0,0 +1,321 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+/// The directories of the data directory that are synced: note content.
+/// Everything else (the config with its tokens when the data directory is
+/// the config directory, per-device stats and marks, caches) stays local.
+const SYNCED_DIRS: [&str; 4] = ["drafts", "archives", "trash", "assets"];
+
+/// Written on `git init`: ignores everything but `SYNCED_DIRS`, so `git
+/// status` and other tools agree with what sync stages.
+const GITIGNORE: &str = "/*\n!/.gitignore\n!/drafts/\n!/archives/\n!/trash/\n!/assets/\n";
+
+/// What one `git_sync` did.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncOutcome {
+    /// Local changes were committed.
+    pub committed: bool,
+    /// The remote was pulled from and pushed to.
+    pub pushed: bool,
+    /// Files the pull added, changed or removed.
+    pub changed: Vec<PathBuf>,
+    /// Notes the pull merged with conflict markers, committed as they are.
+    pub conflicts: Vec<PathBuf>,
+}
+
+/// Run git in `base` and return its output, or fail with what it printed.
+/// Paths in the output are raw, not quoted as git does for non-ASCII names.
+fn git(base: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(base)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git {}: {}", args[0], stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn paths(base: &Path, listing: &str) -> Vec<PathBuf> {
+    listing
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| base.join(line))
+        .collect()
+}
+
+/// Options naming kenotex as the author, for commands that commit, when
+/// git has no identity configured.
+fn identity(base: &Path) -> &'static [&'static str] {
+    if git(base, &["config", "user.email"]).is_ok() {
+        &[]
+    } else {
+        &[
+            "-c",
+            "user.name=kenotex",
+            "-c",
+            "user.email=kenotex@localhost",
+        ]
+    }
+}
+
+/// Whether `path` (relative to the data directory) is note content.
+fn is_synced(path: &str) -> bool {
+    path == ".gitignore"
+        || SYNCED_DIRS.iter().any(|dir| {
+            path.strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+/// Stage the changes in `SYNCED_DIRS` and commit them. Returns false when
+/// there was nothing to commit.
+fn commit_all(base: &Path, message: &str) -> Result<bool> {
+    let mut add = vec!["add", "-A", "--"];
+    add.extend(
+        [".gitignore"]
+            .into_iter()
+            .chain(SYNCED_DIRS)
+            .filter(|path| base.join(path).exists()),
+    );
+    git(base, &add)?;
+    let merging = base.join(".git/MERGE_HEAD").exists();
+    if !merging && git(base, &["diff", "--cached", "--name-only"])?.is_empty() {
+        return Ok(false);
+    }
+    let args = [
+        identity(base),
+        &["commit", "-q", "--no-edit", "-m", message],
+    ]
+    .concat();
+    git(base, &args)?;
+    Ok(true)
+}
+
+/// Commit the note changes in the data directory `base` (making it a
+/// repository first if needed), then, when it has a remote named `remote`,
+/// merge the remote's copy of the current branch and push. Conflicting
+/// notes are committed with git's `<<<<<<<` markers in them for the user to
+/// resolve, so the repository never stays mid-merge. A conflict in any
+/// other file (tracked by an older setup) aborts the merge instead, since
+/// markers there would break loading it.
+pub fn git_sync(base: &Path, remote: &str) -> Result<SyncOutcome> {
+    if !base.join(".git").exists() {
+        git(base, &["init", "-q"])?;
+        let ignore = base.join(".gitignore");
+        if !ignore.exists() {
+            fs::write(&ignore, GITIGNORE)?;
+        }
+    }
+    let stamp = chrono::Local::now().format("%Y-%m-%d %H:%M");
+    let mut outcome = SyncOutcome {
+        committed: commit_all(base, &format!("kenotex sync {}", stamp))?,
+        ..Default::default()
+    };
+
+    if git(base, &["remote", "get-url", remote]).is_err() {
+        return Ok(outcome);
+    }
+    let branch = git(base, &["symbolic-ref", "--short", "HEAD"])?;
+    let before = git(base, &["rev-parse", "--verify", "-q", "HEAD"]).ok();
+    let has_remote_branch = git(
+        base,
+        &["ls-remote", "--exit-code", "--heads", remote, &branch],
+    )
+    .is_ok();
+    if has_remote_branch {
+        let pull = ["pull", "-q", "--no-rebase", "--no-edit", remote, &branch];
+        let args = [identity(base), &pull].concat();
+        if let Err(e) = git(base, &args) {
+            let conflicts = git(base, &["diff", "--name-only", "--diff-filter=U"])?;
+            if conflicts.is_empty() {
+                return Err(e);
+            }
+            let others: Vec<&str> = conflicts.lines().filter(|path| !is_synced(path)).collect();
+            if !others.is_empty() {
+                git(base, &["merge", "--abort"])?;
+                bail!(
+                    "conflict in {}; merge aborted, resolve it with git in {}",
+                    others.join(", "),
+                    base.display()
+                );
+            }
+            outcome.conflicts = paths(base, &conflicts);
+            commit_all(base, &format!("kenotex sync {} (conflicts)", stamp))?;
+        }
+        let changed = match &before {
+            Some(before) => git(base, &["diff", "--name-only", before, "HEAD"])?,
+            None => git(base, &["ls-files"])?,
+        };
+        outcome.changed = paths(base, &changed);
+    }
+    if git(base, &["rev-parse", "--verify", "-q", "HEAD"]).is_ok() {
+        git(base, &["push", "-q", remote, &branch])?;
+        outcome.pushed = true;
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("kenotex-sync-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+        dir
+    }
+
+    fn clone_of(remote: &Path, name: &str) -> PathBuf {
+        let dir = temp_dir(name);
+        fs::remove_dir_all(&dir).unwrap();
+        git(
+            remote.parent().unwrap(),
+            &[
+                "clone",
+                "-q",
+                remote.to_str().unwrap(),
+                dir.to_str().unwrap(),
+            ],
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sync_merges_and_surfaces_conflicts() {
+        let remote = temp_dir("remote");
+        git(&remote, &["init", "-q", "--bare"]).unwrap();
+        let laptop = temp_dir("laptop");
+        fs::write(laptop.join("drafts/a.md"), "# A\none").unwrap();
+
+        // No remote yet: local commits only
+        let outcome = git_sync(&laptop, "origin").unwrap();
+        assert!(outcome.committed && !outcome.pushed);
+        assert!(
+            fs::read_to_string(laptop.join(".gitignore"))
+                .unwrap()
+                .contains("!/drafts/")
+        );
+        assert!(!git_sync(&laptop, "origin").unwrap().committed);
+
+        git(
+            &laptop,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        )
+        .unwrap();
+        assert!(git_sync(&laptop, "origin").unwrap().pushed);
+
+        let desktop = clone_of(&remote, "desktop");
+        fs::write(desktop.join("drafts/a.md"), "# A\ndesktop").unwrap();
+        fs::write(desktop.join("drafts/b.md"), "# B").unwrap();
+        git_sync(&desktop, "origin").unwrap();
+
+        fs::write(laptop.join("drafts/a.md"), "# A\nlaptop").unwrap();
+        let outcome = git_sync(&laptop, "origin").unwrap();
+        assert_eq!(outcome.conflicts, [laptop.join("drafts/a.md")]);
+        assert!(outcome.changed.contains(&laptop.join("drafts/b.md")));
+        let merged = fs::read_to_string(laptop.join("drafts/a.md")).unwrap();
+        assert!(
+            merged.contains("<<<<<<<") && merged.contains("laptop") && merged.contains("desktop")
+        );
+        assert!(git(&laptop, &["status", "--porcelain"]).unwrap().is_empty());
+
+        // The conflicted merge went out, so the other side gets it as is
+        let outcome = git_sync(&desktop, "origin").unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.changed, [desktop.join("drafts/a.md")]);
+        for dir in [remote, laptop, desktop] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn test_sync_non_ascii_note_names() {
+        let remote = temp_dir("remote");
+        git(&remote, &["init", "-q", "--bare"]).unwrap();
+        let laptop = temp_dir("laptop");
+        fs::write(laptop.join("drafts/会议.md"), "# 会议\none").unwrap();
+        git_sync(&laptop, "origin").unwrap();
+        git(
+            &laptop,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        )
+        .unwrap();
+        git_sync(&laptop, "origin").unwrap();
+
+        let desktop = clone_of(&remote, "desktop");
+        fs::write(desktop.join("drafts/会议.md"), "# 会议\ndesktop").unwrap();
+        fs::write(desktop.join("drafts/笔记.md"), "# 笔记").unwrap();
+        git_sync(&desktop, "origin").unwrap();
+
+        fs::write(laptop.join("drafts/会议.md"), "# 会议\nlaptop").unwrap();
+        let outcome = git_sync(&laptop, "origin").unwrap();
+        assert_eq!(outcome.conflicts, [laptop.join("drafts/会议.md")]);
+        assert!(outcome.changed.contains(&laptop.join("drafts/笔记.md")));
+
+        let outcome = git_sync(&desktop, "origin").unwrap();
+        assert_eq!(outcome.changed, [desktop.join("drafts/会议.md")]);
+        for dir in [remote, laptop, desktop] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn test_sync_keeps_local_files_and_aborts_their_conflicts() {
+        let remote = temp_dir("remote");
+        git(&remote, &["init", "-q", "--bare"]).unwrap();
+        let laptop = temp_dir("laptop");
+        fs::write(laptop.join("drafts/a.md"), "# A").unwrap();
+        fs::write(
+            laptop.join("config.toml"),
+            "[destinations.todoist]\ntoken = \"x\"",
+        )
+        .unwrap();
+        fs::write(laptop.join("stats.toml"), "laptop").unwrap();
+        git_sync(&laptop, "origin").unwrap();
+        let tracked = git(&laptop, &["ls-files"]).unwrap();
+        assert_eq!(tracked, ".gitignore\ndrafts/a.md");
+
+        // A repository from before tracked everything
+        git(&laptop, &["add", "-f", "stats.toml"]).unwrap();
+        git(
+            &laptop,
+            &[identity(&laptop), &["commit", "-q", "-m", "old"]].concat(),
+        )
+        .unwrap();
+        git(
+            &laptop,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        )
+        .unwrap();
+        git_sync(&laptop, "origin").unwrap();
+        let desktop = clone_of(&remote, "desktop");
+        fs::write(desktop.join("stats.toml"), "desktop").unwrap();
+        let args = [identity(&desktop), &["commit", "-q", "-am", "stats"]].concat();
+        git(&desktop, &args).unwrap();
+        git(&desktop, &["push", "-q", "origin", "HEAD"]).unwrap();
+
+        fs::write(laptop.join("stats.toml"), "laptop 2").unwrap();
+        let args = [identity(&laptop), &["commit", "-q", "-am", "stats"]].concat();
+        git(&laptop, &args).unwrap();
+        let err = git_sync(&laptop, "origin").unwrap_err().to_string();
+        assert!(err.contains("conflict in stats.toml"), "{}", err);
+        assert_eq!(
+            fs::read_to_string(laptop.join("stats.toml")).unwrap(),
+            "laptop 2"
+        );
+        assert!(!laptop.join(".git/MERGE_HEAD").exists());
+        for dir in [remote, laptop, desktop] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
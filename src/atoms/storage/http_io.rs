@@ -0,0 +1,138 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+/// curl exit codes for a network that is not there: host not resolved,
+/// connection refused, timeout.
+const OFFLINE_EXITS: [i32; 3] = [6, 7, 28];
+
+/// POST `body` as JSON to `url` of the web API `service` with curl.
+pub(super) fn post_json(
+    service: &str,
+    url: &str,
+    secret_headers: &[String],
+    headers: &[&str],
+    body: &str,
+) -> Result<()> {
+    let mut args = vec!["--header", "Content-Type: application/json"];
+    for header in headers {
+        args.extend(["--header", header]);
+    }
+    args.extend(["--data-binary", body]);
+    post(service, url, secret_headers, &args).map(|_| ())
+}
+
+/// POST `fields` form-encoded to `url` of the web API `service` with curl
+/// and return the response body.
+pub(super) fn post_form(
+    service: &str,
+    url: &str,
+    secret_headers: &[String],
+    fields: &[(&str, &str)],
+) -> Result<String> {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    let mut args = Vec::new();
+    for field in &fields {
+        args.extend(["--data-urlencode", field.as_str()]);
+    }
+    post(service, url, secret_headers, &args)
+}
+
+/// Run a curl POST with `args` and return the response body.
+/// `secret_headers` go in through stdin so they never show in the process
+/// list. A missing network is reported as such, so a block can be sent
+/// again later.
+fn post(service: &str, url: &str, secret_headers: &[String], args: &[&str]) -> Result<String> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail-with-body"])
+        .args(["--connect-timeout", "5", "--max-time", "15"])
+        .args(["--header", "@-"])
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = curl.stdin.take() {
+        for header in secret_headers {
+            writeln!(stdin, "{}", header)?;
+        }
+    }
+    let output = curl.wait_with_output().context("Failed to run curl")?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        Some(code) if OFFLINE_EXITS.contains(&code) => {
+            bail!("{} unreachable (offline?), block left unsent", service)
+        }
+        Some(22) => bail!(
+            "{} refused it: {}",
+            service,
+            String::from_utf8_lossy(&output.stdout).trim()
+        ),
+        _ => bail!("curl: {}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
+
+/// The API token: `configured` when set, else the password of the macOS
+/// keychain item `keychain_service`. `setting` names the config key in the
+/// error.
+pub(super) fn api_token(
+    configured: Option<&str>,
+    keychain_service: &str,
+    setting: &str,
+) -> Result<String> {
+    if let Some(token) = configured.map(str::trim).filter(|t| !t.is_empty()) {
+        return Ok(token.to_string());
+    }
+    // No `security` (not macOS) is the same as no keychain item
+    let token = Command::new("security")
+        .args(["find-generic-password", "-s", keychain_service, "-w"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    if token.is_empty() {
+        bail!(
+            "no token (set {} or keychain item {})",
+            setting,
+            keychain_service
+        );
+    }
+    Ok(token)
+}
+
+/// `s` as a JSON string literal.
+pub(super) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string_and_configured_token() {
+        assert_eq!(json_string("a \"b\"\\\n\u{1}"), r#""a \"b\"\\\n\u0001""#);
+        assert_eq!(api_token(Some(" abc "), "x", "y").unwrap(), "abc");
+    }
+}
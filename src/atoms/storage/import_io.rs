@@ -0,0 +1,133 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A file (or TextBundle) read from an export, ready for conversion.
+#[derive(Debug, Clone)]
+pub struct ImportSource {
+    pub path: PathBuf,
+    pub text: String,
+    /// TextBundle `info.json`, when the source is a bundle.
+    pub info_json: Option<String>,
+}
+
+fn is_textbundle(path: &Path) -> bool {
+    path.is_dir()
+        && path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("textbundle"))
+}
+
+fn read_textbundle(path: &Path) -> Result<ImportSource> {
+    let text_file = ["text.md", "text.markdown", "text.txt"]
+        .iter()
+        .map(|name| path.join(name))
+        .find(|p| p.is_file())
+        .with_context(|| format!("No text file in bundle: {:?}", path))?;
+    let text = fs::read_to_string(&text_file)
+        .with_context(|| format!("Failed to read import: {:?}", text_file))?;
+    Ok(ImportSource {
+        path: path.to_path_buf(),
+        text,
+        info_json: fs::read_to_string(path.join("info.json")).ok(),
+    })
+}
+
+fn collect(path: &Path, accept: &dyn Fn(&Path) -> bool, out: &mut Vec<ImportSource>) -> Result<()> {
+    if is_textbundle(path) {
+        out.push(read_textbundle(path)?);
+    } else if path.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory: {:?}", path))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            collect(&entry, accept, out)?;
+        }
+    } else if accept(path) {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read import: {:?}", path))?;
+        out.push(ImportSource {
+            path: path.to_path_buf(),
+            text,
+            info_json: None,
+        });
+    }
+    Ok(())
+}
+
+/// Read every importable file under `path`. Directories are walked
+/// recursively, `.textbundle` folders are read as a single note, and a Bear
+/// `.bearbk` backup is unzipped to a temporary directory first. `accept`
+/// decides which plain files are worth reading.
+pub fn read_import_sources(
+    path: &Path,
+    accept: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<ImportSource>> {
+    if !path.exists() {
+        bail!("Import path does not exist: {:?}", path);
+    }
+
+    let mut sources = Vec::new();
+    let is_backup = path.is_file()
+        && path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("bearbk"));
+    if !is_backup {
+        collect(path, accept, &mut sources)?;
+        return Ok(sources);
+    }
+
+    let staging = std::env::temp_dir().join(format!("kenotex-import-{}", uuid::Uuid::new_v4()));
+    let result = Command::new("unzip")
+        .arg("-q")
+        .arg(path)
+        .arg("-d")
+        .arg(&staging)
+        .status()
+        .context("Failed to run unzip")
+        .and_then(|status| {
+            if !status.success() {
+                bail!("unzip exited with {}", status);
+            }
+            collect(&staging, accept, &mut sources)
+        });
+    let _ = fs::remove_dir_all(&staging);
+    result.map(|_| sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_import_sources_walks_dirs_and_bundles() {
+        let dir =
+            std::env::temp_dir().join(format!("kenotex-import-test-{}", uuid::Uuid::new_v4()));
+        let bundle = dir.join("Note.textbundle");
+        fs::create_dir_all(bundle.join("assets")).unwrap();
+        fs::write(bundle.join("text.md"), "# Bundled").unwrap();
+        fs::write(bundle.join("info.json"), "{}").unwrap();
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested/a.html"), "<div>a</div>").unwrap();
+        fs::write(dir.join("photo.png"), [0u8]).unwrap();
+
+        let accept = |p: &Path| p.extension().is_some_and(|e| e == "html");
+        let sources = read_import_sources(&dir, &accept).unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].path, bundle);
+        assert_eq!(sources[0].text, "# Bundled");
+        assert_eq!(sources[0].info_json.as_deref(), Some("{}"));
+        assert_eq!(sources[1].text, "<div>a</div>");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_import_sources_missing_path() {
+        let accept = |_: &Path| true;
+        assert!(read_import_sources(Path::new("/nonexistent/kenotex-export"), &accept).is_err());
+    }
+}
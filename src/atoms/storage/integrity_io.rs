@@ -0,0 +1,310 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::draft_io::{is_markdown, note_id_for_path, notes_dir};
+
+/// Folder under the data dir that damaged note files are moved into.
+pub const RECOVERY_DIR: &str = "recovery";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuarantineReason {
+    /// Could not be read as UTF-8 text.
+    Unreadable(String),
+    /// An identical file holds the same note id and was modified more
+    /// recently.
+    DuplicateId(String),
+}
+
+impl QuarantineReason {
+    pub fn describe(&self) -> String {
+        match self {
+            QuarantineReason::Unreadable(error) => format!("unreadable: {}", error),
+            QuarantineReason::DuplicateId(id) => format!("duplicate of note {}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuarantinedFile {
+    pub original: PathBuf,
+    pub moved_to: PathBuf,
+    pub reason: QuarantineReason,
+}
+
+/// Files with different content that claim the same note id. They are
+/// left in place for the user to sort out.
+#[derive(Debug, Clone)]
+pub struct DuplicateNote {
+    pub id: String,
+    pub paths: Vec<PathBuf>,
+}
+
+/// What the startup scan moved aside or found but left alone.
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub quarantined: Vec<QuarantinedFile>,
+    /// Run folder under `recovery/`, when anything was moved.
+    pub recovery_dir: Option<PathBuf>,
+    pub duplicates: Vec<DuplicateNote>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.quarantined.is_empty() && self.duplicates.is_empty()
+    }
+
+    /// One-line status message, e.g. `Moved 2 damaged note files to
+    /// recovery/20250101-120000 (1 unreadable, 1 duplicate)`, followed by
+    /// any ids claimed by differing files.
+    pub fn summary(&self) -> Option<String> {
+        let moved = self.moved_summary();
+        let shared = (!self.duplicates.is_empty()).then(|| {
+            let files: Vec<String> = self
+                .duplicates
+                .iter()
+                .flat_map(|d| &d.paths)
+                .map(|p| p.display().to_string())
+                .collect();
+            format!(
+                "Notes sharing an id, left as they are: {}",
+                files.join(", ")
+            )
+        });
+        match (moved, shared) {
+            (Some(moved), Some(shared)) => Some(format!("{}; {}", moved, shared)),
+            (moved, shared) => moved.or(shared),
+        }
+    }
+
+    fn moved_summary(&self) -> Option<String> {
+        let dir = self.recovery_dir.as_ref()?;
+        let count = |f: fn(&QuarantineReason) -> bool| {
+            self.quarantined.iter().filter(|q| f(&q.reason)).count()
+        };
+        let parts: Vec<String> = [
+            (
+                count(|r| matches!(r, QuarantineReason::Unreadable(_))),
+                "unreadable",
+            ),
+            (
+                count(|r| matches!(r, QuarantineReason::DuplicateId(_))),
+                "duplicate",
+            ),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{} {}", n, label))
+        .collect();
+        Some(match self.quarantined.len() {
+            1 => format!(
+                "Moved 1 damaged note file to {} ({})",
+                dir.display(),
+                parts.join(", ")
+            ),
+            n => format!(
+                "Moved {} damaged note files to {} ({})",
+                n,
+                dir.display(),
+                parts.join(", ")
+            ),
+        })
+    }
+}
+
+/// Check every note file in `drafts/` and `archives/` and move unreadable
+/// files and identical copies of a note into `recovery/<timestamp>/`, so
+/// that loading sees only good notes. Of identical copies the most recently
+/// modified one stays; files that share an id but differ are only reported.
+/// Empty files are fine: a new blank note is saved as one. A `report.txt`
+/// in the run folder lists what was moved and why.
+pub fn scan_and_repair(base_dir: &Path) -> Result<IntegrityReport> {
+    let mut flagged: Vec<(PathBuf, QuarantineReason)> = Vec::new();
+    let mut duplicates: Vec<DuplicateNote> = Vec::new();
+    // id -> (path, modified) of the copy being kept
+    let mut by_id: HashMap<String, (PathBuf, SystemTime)> = HashMap::new();
+
+    for archived in [false, true] {
+        let dir = notes_dir(base_dir, archived);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| is_markdown(p) && p.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let metadata = fs::metadata(&path)?;
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    flagged.push((path, QuarantineReason::Unreadable(e.to_string())));
+                    continue;
+                }
+            };
+            let Some(id) = note_id_for_path(&path) else {
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            match by_id.get_mut(&id) {
+                Some((kept, _)) if fs::read_to_string(&*kept).ok() != Some(content) => {
+                    match duplicates.iter_mut().find(|d| d.id == id) {
+                        Some(duplicate) => duplicate.paths.push(path),
+                        None => duplicates.push(DuplicateNote {
+                            id,
+                            paths: vec![kept.clone(), path],
+                        }),
+                    }
+                }
+                Some((_, kept_modified)) if *kept_modified >= modified => {
+                    flagged.push((path, QuarantineReason::DuplicateId(id)));
+                }
+                Some((kept, kept_modified)) => {
+                    let older = std::mem::replace(kept, path);
+                    *kept_modified = modified;
+                    flagged.push((older, QuarantineReason::DuplicateId(id)));
+                }
+                None => {
+                    by_id.insert(id, (path, modified));
+                }
+            }
+        }
+    }
+
+    let mut report = IntegrityReport {
+        duplicates,
+        ..IntegrityReport::default()
+    };
+    if flagged.is_empty() {
+        return Ok(report);
+    }
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let run_dir = base_dir.join(RECOVERY_DIR).join(stamp);
+    for (path, reason) in flagged {
+        let folder = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let target_dir = run_dir.join(folder);
+        fs::create_dir_all(&target_dir)
+            .with_context(|| format!("Failed to create recovery directory: {:?}", target_dir))?;
+        let moved_to = unique_path(&target_dir, &path);
+        fs::rename(&path, &moved_to).with_context(|| format!("Failed to quarantine {:?}", path))?;
+        report.quarantined.push(QuarantinedFile {
+            original: path,
+            moved_to,
+            reason,
+        });
+    }
+
+    let lines: Vec<String> = report
+        .quarantined
+        .iter()
+        .map(|q| {
+            format!(
+                "{} -> {}: {}",
+                q.original.display(),
+                q.moved_to.display(),
+                q.reason.describe()
+            )
+        })
+        .collect();
+    fs::write(run_dir.join("report.txt"), lines.join("\n") + "\n")
+        .context("Failed to write recovery report")?;
+    report.recovery_dir = Some(run_dir);
+    Ok(report)
+}
+
+/// `dir/<file name>`, numbered if that name is taken.
+fn unique_path(dir: &Path, path: &Path) -> PathBuf {
+    let name = path.file_name().map(PathBuf::from).unwrap_or_default();
+    let candidate = dir.join(&name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (2..)
+        .map(|n| dir.join(format!("{}-{}.md", stem, n)))
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("kenotex-integrity-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_quarantines_unreadable_and_identical_duplicates() {
+        let dir = temp_dir();
+        let drafts = dir.join("drafts");
+        let archives = dir.join("archives");
+        fs::create_dir_all(&drafts).unwrap();
+        fs::create_dir_all(&archives).unwrap();
+
+        fs::write(drafts.join("good.md"), "# Good").unwrap();
+        fs::write(drafts.join("empty.md"), "").unwrap();
+        fs::write(drafts.join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        fs::write(archives.join("dup.md"), "# Copy").unwrap();
+        let newer = fs::File::create(drafts.join("dup.md")).unwrap();
+        std::io::Write::write_all(&mut &newer, b"# Copy").unwrap();
+        newer
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+
+        let report = scan_and_repair(&dir).unwrap();
+        assert_eq!(report.quarantined.len(), 2);
+        assert_eq!(
+            report.summary().unwrap(),
+            format!(
+                "Moved 2 damaged note files to {} (1 unreadable, 1 duplicate)",
+                report.recovery_dir.as_ref().unwrap().display()
+            )
+        );
+
+        assert!(drafts.join("good.md").exists());
+        assert!(drafts.join("dup.md").exists());
+        assert!(drafts.join("empty.md").exists());
+        assert!(!archives.join("dup.md").exists());
+        let run_dir = report.recovery_dir.unwrap();
+        assert!(run_dir.join("archives/dup.md").exists());
+        assert!(run_dir.join("drafts/binary.md").exists());
+        let log = fs::read_to_string(run_dir.join("report.txt")).unwrap();
+        assert!(log.contains("duplicate of note dup"));
+
+        assert!(scan_and_repair(&dir).unwrap().is_clean());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reports_differing_duplicates_without_moving_them() {
+        let dir = temp_dir();
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+        fs::create_dir_all(dir.join("archives")).unwrap();
+        fs::write(dir.join("drafts/inbox.md"), "# Inbox\nnew").unwrap();
+        fs::write(dir.join("archives/inbox.md"), "# Inbox\nold").unwrap();
+
+        let report = scan_and_repair(&dir).unwrap();
+        assert!(report.quarantined.is_empty() && report.recovery_dir.is_none());
+        assert!(!report.is_clean());
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].id, "inbox");
+        assert!(report.summary().unwrap().starts_with("Notes sharing an id"));
+        assert!(dir.join("drafts/inbox.md").exists() && dir.join("archives/inbox.md").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
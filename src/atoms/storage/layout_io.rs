@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::LayoutState;
+
+fn layout_path(base_dir: &Path) -> PathBuf {
+    base_dir.join("layout.toml")
+}
+
+/// Load saved pane proportions, returning the defaults if none exist yet.
+pub fn load_layout(base_dir: &Path) -> Result<LayoutState> {
+    let path = layout_path(base_dir);
+    if !path.exists() {
+        return Ok(LayoutState::default());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read layout: {:?}", path))?;
+    toml::from_str(&content).with_context(|| "Failed to parse layout.toml")
+}
+
+pub fn save_layout(base_dir: &Path, layout: &LayoutState) -> Result<()> {
+    let path = layout_path(base_dir);
+    let content = toml::to_string_pretty(layout).with_context(|| "Failed to serialize layout")?;
+    fs::write(&path, content).with_context(|| format!("Failed to write layout: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kenotex-layout-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load_layout(&dir).unwrap(), LayoutState::default());
+
+        let mut layout = LayoutState::default();
+        layout.ratios_mut("Editor").vertical = 35;
+        save_layout(&dir, &layout).unwrap();
+        let loaded = load_layout(&dir).unwrap();
+        assert_eq!(loaded, layout);
+        assert_eq!(loaded.ratios("Editor").horizontal, 50);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::MarkStore;
+
+fn marks_path(base_dir: &Path) -> PathBuf {
+    base_dir.join("marks.toml")
+}
+
+/// Load saved marks, returning an empty store if none exist yet.
+pub fn load_marks(base_dir: &Path) -> Result<MarkStore> {
+    let path = marks_path(base_dir);
+    if !path.exists() {
+        return Ok(MarkStore::default());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read marks: {:?}", path))?;
+    toml::from_str(&content).with_context(|| "Failed to parse marks.toml")
+}
+
+pub fn save_marks(base_dir: &Path, marks: &MarkStore) -> Result<()> {
+    let path = marks_path(base_dir);
+    let content = toml::to_string_pretty(marks).with_context(|| "Failed to serialize marks")?;
+    fs::write(&path, content).with_context(|| format!("Failed to write marks: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Mark;
+
+    #[test]
+    fn test_marks_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kenotex-marks-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load_marks(&dir).unwrap(), MarkStore::default());
+
+        let mut marks = MarkStore::default();
+        marks.set("note-1", 'a', Mark { row: 3, col: 7 });
+        marks.set("note-1", 'b', Mark { row: 0, col: 0 });
+        marks.set("note-2", 'a', Mark { row: 9, col: 1 });
+        save_marks(&dir, &marks).unwrap();
+
+        let loaded = load_marks(&dir).unwrap();
+        assert_eq!(loaded, marks);
+        assert_eq!(loaded.get("note-1", 'a'), Some(Mark { row: 3, col: 7 }));
+        assert_eq!(loaded.get("note-2", 'b'), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,17 +1,60 @@
+mod appearance;
+mod asset_io;
+mod bundle_io;
 mod clipboard;
 mod config_io;
+mod dictionary_io;
 mod draft_io;
 mod external_editor;
 pub mod file_watcher;
+mod git_sync;
+mod http_io;
+mod import_io;
+mod integrity_io;
+mod layout_io;
+mod marks_io;
+mod notion_io;
+mod pdf_io;
+mod save_worker;
+mod search_index_io;
+mod snapshot_io;
+mod stats_io;
+mod template_io;
+mod theme_io;
+mod tmux;
+mod todoist_io;
 
+pub use appearance::watch_system_appearance;
+pub use asset_io::copy_to_assets;
+pub use bundle_io::write_share_bundle;
 pub use clipboard::{clipboard_copy, clipboard_paste};
 pub use config_io::{
-    config_dir, ensure_config_dir, expand_tilde, load_config, resolve_data_dir, save_config,
+    config_dir, config_path, ensure_config_dir, expand_tilde, load_config, read_config,
+    resolve_data_dir, save_config, save_config_value,
 };
+pub use dictionary_io::{dictionary_dirs, load_dictionary};
 pub use draft_io::{
-    archive_draft, delete_draft, ensure_data_dirs, load_all_drafts, load_draft, restore_draft,
-    save_draft,
+    FileNaming, archive_draft, delete_draft, ensure_data_dirs, find_draft, find_note,
+    load_all_drafts, load_draft, load_trash, note_content_for_path, note_id_for_path, purge_trash,
+    purge_trashed, restore_draft, restore_from_trash, save_draft, title_slug, trash_draft,
 };
 pub use external_editor::{
     cleanup_temp_file, read_temp_file, resolve_editor, spawn_editor, write_temp_file,
 };
+pub use git_sync::{SyncOutcome, git_sync};
+pub use import_io::{ImportSource, read_import_sources};
+pub use integrity_io::{
+    DuplicateNote, IntegrityReport, QuarantineReason, RECOVERY_DIR, scan_and_repair,
+};
+pub use layout_io::{load_layout, save_layout};
+pub use marks_io::{load_marks, save_marks};
+pub use notion_io::{NotionPage, create_notion_page, notion_token};
+pub use pdf_io::run_pdf_command;
+pub use save_worker::{SaveOutcome, SaveWorker};
+pub use search_index_io::{load_search_index, save_search_index};
+pub use snapshot_io::{list_snapshots, load_snapshot, save_snapshot};
+pub use stats_io::{append_writing_log, load_writing_history, save_writing_history};
+pub use template_io::{list_templates, load_template, templates_dir};
+pub use theme_io::{load_themes, themes_dir};
+pub use tmux::{in_tmux, set_tmux_pane_title, tmux_pane_title};
+pub use todoist_io::{TodoistTask, create_todoist_tasks, todoist_token};
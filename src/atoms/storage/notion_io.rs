@@ -0,0 +1,159 @@
+use anyhow::Result;
+
+use super::http_io::{api_token, json_string, post_json};
+use crate::types::NotionProperties;
+
+const PAGES_URL: &str = "https://api.notion.com/v1/pages";
+const NOTION_VERSION: &str = "Notion-Version: 2022-06-28";
+
+/// Keychain service the Notion token is read from when the config has
+/// none.
+const NOTION_KEYCHAIN_SERVICE: &str = "kenotex-notion";
+
+/// Most blocks Notion takes with a new page, and most characters in one
+/// piece of rich text.
+const MAX_CHILDREN: usize = 100;
+const MAX_TEXT: usize = 2000;
+
+/// A row to add to a Notion database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotionPage {
+    pub title: String,
+    /// One paragraph per line.
+    pub body: String,
+    pub tags: Vec<String>,
+    /// ISO 8601 date or date and time.
+    pub date: Option<String>,
+}
+
+/// The integration token: `configured` when set, else the macOS keychain
+/// item `kenotex-notion`.
+pub fn notion_token(configured: Option<&str>) -> Result<String> {
+    api_token(
+        configured,
+        NOTION_KEYCHAIN_SERVICE,
+        "destinations.notion.token",
+    )
+}
+
+/// Add `page` to the database `database_id` through the Notion API,
+/// filling the columns `properties` names.
+pub fn create_notion_page(
+    token: &str,
+    database_id: &str,
+    properties: &NotionProperties,
+    page: &NotionPage,
+) -> Result<()> {
+    let body = page_json(database_id, properties, page);
+    let auth = format!("Authorization: Bearer {}", token);
+    post_json("Notion", PAGES_URL, &[auth], &[NOTION_VERSION], &body)
+}
+
+/// The JSON body of a new page. Tags and date are left out when their
+/// column is not configured.
+fn page_json(database_id: &str, properties: &NotionProperties, page: &NotionPage) -> String {
+    let mut columns = vec![format!(
+        "{}:{{\"title\":{}}}",
+        json_string(&properties.title),
+        rich_text(&page.title)
+    )];
+    if let Some(tags) = &properties.tags {
+        let options: Vec<String> = page
+            .tags
+            .iter()
+            .map(|tag| format!("{{\"name\":{}}}", json_string(tag)))
+            .collect();
+        columns.push(format!(
+            "{}:{{\"multi_select\":[{}]}}",
+            json_string(tags),
+            options.join(",")
+        ));
+    }
+    if let (Some(column), Some(date)) = (&properties.date, &page.date) {
+        columns.push(format!(
+            "{}:{{\"date\":{{\"start\":{}}}}}",
+            json_string(column),
+            json_string(date)
+        ));
+    }
+
+    let mut lines: Vec<String> = page.body.lines().map(str::to_string).collect();
+    if lines.len() > MAX_CHILDREN {
+        let rest = lines.split_off(MAX_CHILDREN - 1).join("\n");
+        lines.push(rest);
+    }
+    let children: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            format!(
+                "{{\"object\":\"block\",\"type\":\"paragraph\",\"paragraph\":{{\"rich_text\":{}}}}}",
+                rich_text(line)
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"parent\":{{\"database_id\":{}}},\"properties\":{{{}}},\"children\":[{}]}}",
+        json_string(database_id),
+        columns.join(","),
+        children.join(",")
+    )
+}
+
+/// `text` as Notion rich text, cut into pieces it accepts.
+fn rich_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let pieces: Vec<String> = chars
+        .chunks(MAX_TEXT)
+        .map(|piece| {
+            let piece: String = piece.iter().collect();
+            format!(
+                "{{\"type\":\"text\",\"text\":{{\"content\":{}}}}}",
+                json_string(&piece)
+            )
+        })
+        .collect();
+    format!("[{}]", pieces.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_json() {
+        let page = NotionPage {
+            title: "Q2 roadmap".to_string(),
+            body: "Ask Ann".to_string(),
+            tags: vec!["work".to_string()],
+            date: Some("2024-03-01".to_string()),
+        };
+        let mut properties = NotionProperties::default();
+        assert_eq!(
+            page_json("db1", &properties, &page),
+            concat!(
+                r#"{"parent":{"database_id":"db1"},"properties":{"Name":{"title":[{"type":"text","text":{"content":"Q2 roadmap"}}]}},"#,
+                r#""children":[{"object":"block","type":"paragraph","paragraph":{"rich_text":[{"type":"text","text":{"content":"Ask Ann"}}]}}]}"#
+            )
+        );
+        properties.tags = Some("Tags".to_string());
+        properties.date = Some("Date".to_string());
+        let json = page_json("db1", &properties, &page);
+        assert!(
+            json.contains(r#""Tags":{"multi_select":[{"name":"work"}]}"#),
+            "{}",
+            json
+        );
+        assert!(
+            json.contains(r#""Date":{"date":{"start":"2024-03-01"}}"#),
+            "{}",
+            json
+        );
+        assert_eq!(
+            rich_text(&"x".repeat(MAX_TEXT + 1))
+                .matches("content")
+                .count(),
+            2
+        );
+    }
+}
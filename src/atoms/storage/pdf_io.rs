@@ -0,0 +1,55 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+/// Run the PDF tool `command` (e.g. `wkhtmltopdf {input} {output}`) on the
+/// HTML file `input` to write `output`. The command is split on whitespace;
+/// without the placeholders the two paths are appended in that order.
+pub fn run_pdf_command(command: &str, input: &Path, output: &Path) -> Result<()> {
+    let input = input.to_string_lossy();
+    let output = output.to_string_lossy();
+    let mut args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| arg.replace("{input}", &input).replace("{output}", &output))
+        .collect();
+    if !command.contains("{input}") && !command.contains("{output}") {
+        args.extend([input.to_string(), output.to_string()]);
+    }
+    let Some((program, args)) = args.split_first() else {
+        bail!("Empty PDF command");
+    };
+    let result = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        bail!("{} failed: {}", program, stderr.trim());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_placeholders_and_failures() {
+        let dir = std::env::temp_dir().join(format!("kenotex-pdf-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("note.html"), dir.join("note.pdf"));
+        fs::write(&input, "<p>hi</p>").unwrap();
+
+        run_pdf_command("cp {input} {output}", &input, &output).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "<p>hi</p>");
+        fs::remove_file(&output).unwrap();
+        run_pdf_command("cp", &input, &output).unwrap();
+        assert!(output.exists());
+        assert!(run_pdf_command("false", &input, &output).is_err());
+        assert!(run_pdf_command("", &input, &output).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use super::draft_io::{FileNaming, save_draft};
+use crate::types::Note;
+
+/// Result of writing one note. `requests` counts the submissions that were
+/// coalesced into this write.
+#[derive(Debug)]
+pub struct SaveOutcome {
+    pub id: String,
+    pub result: Result<()>,
+    pub requests: usize,
+}
+
+/// One queued write: the data directory, the note and how to name its file.
+type SaveRequest = (PathBuf, Note, FileNaming);
+
+/// Writes notes on a dedicated thread so disk I/O never blocks the UI.
+///
+/// Requests that pile up while a write is in progress are coalesced: only the
+/// newest content of each note is written, named per its newest request.
+pub struct SaveWorker {
+    tx: Option<Sender<SaveRequest>>,
+    rx: Receiver<SaveOutcome>,
+    handle: Option<JoinHandle<()>>,
+    in_flight: usize,
+}
+
+impl SaveWorker {
+    /// Start the writer thread.
+    pub fn spawn() -> Self {
+        let (tx, requests) = mpsc::channel::<SaveRequest>();
+        let (outcomes, rx) = mpsc::channel();
+
+        let handle = std::thread::spawn(move || {
+            while let Ok(first) = requests.recv() {
+                let mut batch: Vec<(PathBuf, Note, FileNaming, usize)> = Vec::new();
+                for (dir, note, naming) in std::iter::once(first).chain(requests.try_iter()) {
+                    match batch
+                        .iter_mut()
+                        .find(|(d, n, _, _)| *d == dir && n.id == note.id)
+                    {
+                        Some(entry) => {
+                            entry.1 = note;
+                            entry.2 = naming;
+                            entry.3 += 1;
+                        }
+                        None => batch.push((dir, note, naming, 1)),
+                    }
+                }
+                for (dir, note, naming, requests) in batch {
+                    let outcome = SaveOutcome {
+                        result: save_draft(&dir, &note, naming),
+                        id: note.id,
+                        requests,
+                    };
+                    if outcomes.send(outcome).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            tx: Some(tx),
+            rx,
+            handle: Some(handle),
+            in_flight: 0,
+        }
+    }
+
+    /// Queue a write of `note` under `base_dir`, naming a new or renamed
+    /// file per `naming`.
+    pub fn submit(&mut self, base_dir: PathBuf, note: Note, naming: FileNaming) {
+        if let Some(tx) = &self.tx
+            && tx.send((base_dir, note, naming)).is_ok()
+        {
+            self.in_flight += 1;
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.in_flight > 0
+    }
+
+    /// Finished writes, without blocking.
+    pub fn poll(&mut self) -> Vec<SaveOutcome> {
+        let outcomes: Vec<SaveOutcome> = self.rx.try_iter().collect();
+        self.account(&outcomes);
+        outcomes
+    }
+
+    /// Block until every queued write has finished.
+    pub fn flush(&mut self) -> Vec<SaveOutcome> {
+        let mut outcomes = Vec::new();
+        while self.in_flight > 0 {
+            let Ok(outcome) = self.rx.recv() else {
+                self.in_flight = 0;
+                break;
+            };
+            self.in_flight = self.in_flight.saturating_sub(outcome.requests);
+            outcomes.push(outcome);
+        }
+        outcomes
+    }
+
+    fn account(&mut self, outcomes: &[SaveOutcome]) {
+        let done: usize = outcomes.iter().map(|o| o.requests).sum();
+        self.in_flight = self.in_flight.saturating_sub(done);
+    }
+}
+
+impl Drop for SaveWorker {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread once queued writes are done
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_save_worker_writes_latest_content() {
+        let dir = std::env::temp_dir().join(format!("kenotex-save-{}", uuid::Uuid::new_v4()));
+        let mut worker = SaveWorker::spawn();
+
+        for content in ["one", "two", "three"] {
+            let note = Note::new("n1".to_string(), "t".to_string(), content.to_string());
+            worker.submit(dir.clone(), note, FileNaming::Id);
+        }
+        assert!(worker.is_busy());
+
+        let outcomes = worker.flush();
+        assert!(!worker.is_busy());
+        assert!(outcomes.iter().all(|o| o.result.is_ok() && o.id == "n1"));
+        assert_eq!(outcomes.iter().map(|o| o.requests).sum::<usize>(), 3);
+        assert_eq!(
+            fs::read_to_string(dir.join("drafts/n1.md")).unwrap(),
+            "three"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_worker_names_files_per_request() {
+        let dir = std::env::temp_dir().join(format!("kenotex-save-{}", uuid::Uuid::new_v4()));
+        let mut worker = SaveWorker::spawn();
+        let note = Note::new("n2".into(), "Plan".into(), "# Plan".into());
+        worker.submit(dir.clone(), note.clone(), FileNaming::Id);
+        worker.flush();
+        worker.submit(dir.clone(), note, FileNaming::Title);
+        worker.flush();
+
+        assert!(dir.join("drafts/plan.md").exists());
+        assert!(!dir.join("drafts/n2.md").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_worker_reports_errors() {
+        let file = std::env::temp_dir().join(format!("kenotex-save-{}", uuid::Uuid::new_v4()));
+        fs::write(&file, "not a directory").unwrap();
+        let mut worker = SaveWorker::spawn();
+
+        let note = Note::new("n".into(), "t".into(), "x".into());
+        worker.submit(file.clone(), note, FileNaming::Id);
+        let outcomes = worker.flush();
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].result.is_err());
+
+        fs::remove_file(&file).unwrap();
+    }
+}
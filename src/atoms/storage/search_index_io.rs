@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::IndexSnapshot;
+
+fn index_path(base_dir: &Path) -> PathBuf {
+    base_dir.join("search_index.toml")
+}
+
+/// Load the saved search index, returning an empty one if none exists yet.
+pub fn load_search_index(base_dir: &Path) -> Result<IndexSnapshot> {
+    let path = index_path(base_dir);
+    if !path.exists() {
+        return Ok(IndexSnapshot::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read search index: {:?}", path))?;
+    toml::from_str(&content).with_context(|| "Failed to parse search_index.toml")
+}
+
+pub fn save_search_index(base_dir: &Path, index: &IndexSnapshot) -> Result<()> {
+    let path = index_path(base_dir);
+    let content = toml::to_string(index).with_context(|| "Failed to serialize search index")?;
+    fs::write(&path, content)
+        .with_context(|| format!("Failed to write search index: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IndexedNote;
+
+    #[test]
+    fn test_search_index_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kenotex-index-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load_search_index(&dir).unwrap(), IndexSnapshot::default());
+
+        let mut index = IndexSnapshot::default();
+        index.notes.push(IndexedNote {
+            id: "note-1".to_string(),
+            hash: 42,
+        });
+        index.trigrams.insert("a\nb".to_string(), vec![0]);
+        index.trigrams.insert("\"x.".to_string(), vec![0]);
+        save_search_index(&dir, &index).unwrap();
+        assert_eq!(load_search_index(&dir).unwrap(), index);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+
+use crate::types::{NoteSnapshot, SnapshotReason};
+
+/// Snapshots kept per note; taking another removes the oldest.
+pub const SNAPSHOT_LIMIT: usize = 20;
+
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S%6f";
+
+/// `snapshots/<note id>/` in the data directory.
+fn snapshots_dir(data_dir: &Path, note_id: &str) -> PathBuf {
+    data_dir.join("snapshots").join(note_id)
+}
+
+/// The snapshots of note `note_id`, newest first. Files with other names
+/// are ignored.
+pub fn list_snapshots(data_dir: &Path, note_id: &str) -> Result<Vec<NoteSnapshot>> {
+    let dir = snapshots_dir(data_dir, note_id);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", dir)),
+    };
+    let mut snapshots: Vec<NoteSnapshot> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            let (stamp, reason) = stem.split_once('_')?;
+            Some(NoteSnapshot {
+                taken: NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?,
+                reason: SnapshotReason::from_name(reason)?,
+                path,
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.taken));
+    Ok(snapshots)
+}
+
+/// Keep `content` of note `note_id` as it is before `reason`, unless the
+/// newest snapshot already holds it. Only the newest `SNAPSHOT_LIMIT` are
+/// kept.
+pub fn save_snapshot(
+    data_dir: &Path,
+    note_id: &str,
+    reason: SnapshotReason,
+    content: &str,
+    taken: NaiveDateTime,
+) -> Result<()> {
+    let existing = list_snapshots(data_dir, note_id)?;
+    if let Some(newest) = existing.first()
+        && fs::read_to_string(&newest.path).is_ok_and(|text| text == content)
+    {
+        return Ok(());
+    }
+    let dir = snapshots_dir(data_dir, note_id);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(format!(
+        "{}_{}.md",
+        taken.format(STAMP_FORMAT),
+        reason.as_str()
+    ));
+    fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))?;
+    for old in existing.iter().skip(SNAPSHOT_LIMIT - 1) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(())
+}
+
+pub fn load_snapshot(snapshot: &NoteSnapshot) -> Result<String> {
+    fs::read_to_string(&snapshot.path)
+        .with_context(|| format!("Failed to read snapshot {:?}", snapshot.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_newest_first_and_pruned() {
+        let dir = std::env::temp_dir().join(format!("kenotex-snapshots-{}", uuid::Uuid::new_v4()));
+        let at = |minute: u32| {
+            chrono::NaiveDate::from_ymd_opt(2024, 6, 3)
+                .unwrap()
+                .and_hms_opt(9, minute, 0)
+                .unwrap()
+        };
+        assert!(list_snapshots(&dir, "n1").unwrap().is_empty());
+
+        save_snapshot(&dir, "n1", SnapshotReason::Agenda, "one", at(0)).unwrap();
+        save_snapshot(&dir, "n1", SnapshotReason::Replace, "one", at(1)).unwrap();
+        save_snapshot(&dir, "n1", SnapshotReason::Replace, "two", at(2)).unwrap();
+        let snapshots = list_snapshots(&dir, "n1").unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].reason, SnapshotReason::Replace);
+        assert_eq!(snapshots[0].taken, at(2));
+        assert_eq!(load_snapshot(&snapshots[1]).unwrap(), "one");
+
+        for minute in 3..30 {
+            save_snapshot(
+                &dir,
+                "n1",
+                SnapshotReason::Processing,
+                &minute.to_string(),
+                at(minute),
+            )
+            .unwrap();
+        }
+        let snapshots = list_snapshots(&dir, "n1").unwrap();
+        assert_eq!(snapshots.len(), SNAPSHOT_LIMIT);
+        assert_eq!(snapshots[0].taken, at(29));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
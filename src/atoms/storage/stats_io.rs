@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::types::WritingHistory;
+
+fn stats_path(base_dir: &Path) -> PathBuf {
+    base_dir.join("stats.toml")
+}
+
+/// Load the writing history, returning an empty history if none exists yet.
+pub fn load_writing_history(base_dir: &Path) -> Result<WritingHistory> {
+    let path = stats_path(base_dir);
+    if !path.exists() {
+        return Ok(WritingHistory::default());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read stats: {:?}", path))?;
+    toml::from_str(&content).with_context(|| "Failed to parse stats.toml")
+}
+
+pub fn save_writing_history(base_dir: &Path, history: &WritingHistory) -> Result<()> {
+    let path = stats_path(base_dir);
+    let content =
+        toml::to_string_pretty(history).with_context(|| "Failed to serialize writing stats")?;
+    fs::write(&path, content).with_context(|| format!("Failed to write stats: {:?}", path))?;
+    Ok(())
+}
+
+/// Append a single line to a plain-text writing log, creating it if needed.
+pub fn append_writing_log(path: &Path, line: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open writing log: {:?}", path))?;
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write log: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kenotex-stats-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            load_writing_history(&dir).unwrap(),
+            WritingHistory::default()
+        );
+
+        let mut history = WritingHistory::default();
+        history.days.insert("2026-10-14".to_string(), 420);
+        save_writing_history(&dir, &history).unwrap();
+        assert_eq!(load_writing_history(&dir).unwrap(), history);
+
+        let log = dir.join("writing.log");
+        append_writing_log(&log, "first").unwrap();
+        append_writing_log(&log, "second").unwrap();
+        assert_eq!(fs::read_to_string(&log).unwrap(), "first\nsecond\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
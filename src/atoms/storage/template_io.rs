@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::config_dir;
+
+/// `templates/` in the config directory, where note templates live.
+pub fn templates_dir() -> PathBuf {
+    config_dir().join("templates")
+}
+
+/// Markdown or text files in `dir`: the template names (file stems) with
+/// their paths, sorted by name. A missing directory has no templates.
+fn template_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", dir)),
+    };
+    let mut templates: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == "md" || ext == "txt")
+        })
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some((name, path))
+        })
+        .collect();
+    templates.sort_by_key(|(name, _)| name.to_lowercase());
+    Ok(templates)
+}
+
+/// Names of the templates in `dir`, sorted.
+pub fn list_templates(dir: &Path) -> Result<Vec<String>> {
+    Ok(template_files(dir)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect())
+}
+
+/// Text of the template called `name` (in any case) in `dir`, if there is
+/// one.
+pub fn load_template(dir: &Path, name: &str) -> Result<Option<String>> {
+    let name = name.to_lowercase();
+    let Some((_, path)) = template_files(dir)?
+        .into_iter()
+        .find(|(n, _)| n.to_lowercase() == name)
+    else {
+        return Ok(None);
+    };
+    fs::read_to_string(&path)
+        .map(Some)
+        .with_context(|| format!("Failed to read template {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_and_load_templates() {
+        let dir = std::env::temp_dir().join(format!("kenotex-templates-{}", uuid::Uuid::new_v4()));
+        assert!(list_templates(&dir).unwrap().is_empty());
+
+        fs::create_dir_all(dir.join("nested.md")).unwrap();
+        fs::write(dir.join("meeting.md"), "# {{title}}").unwrap();
+        fs::write(dir.join("Journal.txt"), "{{date}}").unwrap();
+        fs::write(dir.join("notes.toml"), "").unwrap();
+        assert_eq!(list_templates(&dir).unwrap(), ["Journal", "meeting"]);
+        assert_eq!(
+            load_template(&dir, "MEETING").unwrap().as_deref(),
+            Some("# {{title}}")
+        );
+        assert_eq!(load_template(&dir, "standup").unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+use super::config_dir;
+use crate::types::Theme;
+
+/// `themes/` in the config directory, where user themes live.
+pub fn themes_dir() -> PathBuf {
+    config_dir().join("themes")
+}
+
+/// The `.toml` themes in `dir`, sorted by file name, each parsed on its
+/// own so one broken file does not hide the others. A missing directory
+/// has no themes.
+pub fn load_themes(dir: &Path) -> Result<Vec<Result<Theme>>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", dir)),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    Ok(paths.iter().map(|path| load_theme(path)).collect())
+}
+
+/// A theme file: every color role of `Theme` as `#rrggbb`, and a `name`
+/// (the file stem when left out).
+fn load_theme(path: &Path) -> Result<Theme> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut table: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+    if !table.contains_key("name") {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        table.insert("name".to_string(), toml::Value::String(stem.into_owned()));
+    }
+    let theme: Theme = toml::Value::Table(table)
+        .try_into()
+        .with_context(|| format!("Failed to parse {:?}", path))?;
+    theme
+        .validate()
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    Ok(theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: &str = "bg = \"#101010\"\nfg = \"#e0e0e0\"\ncursor = \"#ffffff\"
+selection = \"#303030\"\nborder = \"#404040\"\naccent = \"#00aaff\"\nsuccess = \"#00ff00\"
+warning = \"#ffaa00\"\nerror = \"#ff0000\"\npanel = \"#080808\"\nvisual_char = \"#203040\"
+visual_line = \"#202020\"\nvisual_block = \"#402040\"\nsearch_match = \"#aaaa00\"\n";
+
+    #[test]
+    fn test_load_themes() {
+        let dir = std::env::temp_dir().join(format!("kenotex-themes-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a_dusk.toml"),
+            format!("name = \"Dusk\"\n{}", COLORS),
+        )
+        .unwrap();
+        fs::write(dir.join("b_plain.toml"), COLORS).unwrap();
+        fs::write(dir.join("c_short.toml"), "bg = \"#000000\"").unwrap();
+        fs::write(dir.join("d_bad.toml"), COLORS.replace("#e0e0e0", "grey")).unwrap();
+        fs::write(dir.join("notes.md"), "not a theme").unwrap();
+
+        let themes = load_themes(&dir).unwrap();
+        assert_eq!(themes.len(), 4);
+        assert_eq!(themes[0].as_ref().unwrap().name, "Dusk");
+        assert_eq!(themes[1].as_ref().unwrap().name, "b_plain");
+        assert!(themes[2].is_err());
+        let err = themes[3].as_ref().unwrap_err().to_string();
+        assert!(err.contains("fg"), "{}", err);
+        assert!(load_themes(&dir.join("missing")).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+use std::process::Command;
+
+/// Whether kenotex runs inside a tmux pane.
+pub fn in_tmux() -> bool {
+    std::env::var_os("TMUX").is_some_and(|v| !v.is_empty())
+}
+
+/// The title of the current tmux pane, to put back on exit.
+pub fn tmux_pane_title() -> Option<String> {
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "#{pane_title}"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| {
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string()
+    })
+}
+
+/// Set the title of the current tmux pane. Failures are ignored: the
+/// title is a convenience and tmux may be gone.
+pub fn set_tmux_pane_title(title: &str) {
+    let _ = Command::new("tmux")
+        .args(["select-pane", "-T", title])
+        .output();
+}
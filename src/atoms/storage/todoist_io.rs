@@ -0,0 +1,168 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::http_io::{api_token, json_string, post_form};
+
+const SYNC_URL: &str = "https://api.todoist.com/api/v1/sync";
+
+/// Keychain service the Todoist token is read from when the config has
+/// none: `security add-generic-password -s kenotex-todoist -a todoist -w`.
+const TODOIST_KEYCHAIN_SERVICE: &str = "kenotex-todoist";
+
+/// The API token: `configured` when set, else the macOS keychain item.
+pub fn todoist_token(configured: Option<&str>) -> Result<String> {
+    api_token(
+        configured,
+        TODOIST_KEYCHAIN_SERVICE,
+        "destinations.todoist.token",
+    )
+}
+
+/// One task to create.
+#[derive(Debug, Clone, Copy)]
+pub struct TodoistTask<'a> {
+    pub content: &'a str,
+    pub description: Option<&'a str>,
+    pub due: Option<DateTime<Utc>>,
+}
+
+/// Create `tasks` in one request to Todoist's Sync API. Each command's uuid
+/// is derived from `block_key` (which block of which note the tasks come
+/// from) and the tasks, so sending the same block again after a partial
+/// failure or a lost response skips the tasks Todoist already created: it
+/// runs a command uuid only once. The same tasks in another block are new.
+pub fn create_todoist_tasks(
+    token: &str,
+    block_key: &str,
+    tasks: &[TodoistTask],
+    project_id: Option<&str>,
+) -> Result<()> {
+    let uuids = command_uuids(block_key, tasks, project_id);
+    let body = commands_json(tasks, project_id, &uuids);
+    let auth = format!("Authorization: Bearer {}", token);
+    let response = post_form("Todoist", SYNC_URL, &[auth], &[("commands", &body)])?;
+
+    let response: String = response.split_whitespace().collect();
+    let failed = uuids
+        .iter()
+        .filter(|uuid| !response.contains(&format!("\"{}\":\"ok\"", uuid)))
+        .count();
+    if failed > 0 {
+        bail!(
+            "{} of {} tasks not created; sending the block again creates only those",
+            failed,
+            tasks.len()
+        );
+    }
+    Ok(())
+}
+
+/// A stable uuid per task: the same tasks in the same block give the same
+/// uuids on every send.
+fn command_uuids(block_key: &str, tasks: &[TodoistTask], project_id: Option<&str>) -> Vec<String> {
+    let block: Vec<String> = tasks
+        .iter()
+        .map(|task| task_json(task, project_id))
+        .collect();
+    let block = block.join("\n");
+    (0..tasks.len())
+        .map(|index| {
+            let key = format!("{}\n{}\n{}", block_key, index, block);
+            let high = fnv1a(FNV_OFFSET, key.as_bytes());
+            let low = fnv1a(FNV_OFFSET ^ u64::MAX, key.as_bytes());
+            uuid::Uuid::from_u64_pair(high, low).to_string()
+        })
+        .collect()
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is fixed across builds.
+fn fnv1a(offset: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(offset, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The `commands` JSON of the request: one `item_add` per task.
+fn commands_json(tasks: &[TodoistTask], project_id: Option<&str>, uuids: &[String]) -> String {
+    let commands: Vec<String> = tasks
+        .iter()
+        .zip(uuids)
+        .enumerate()
+        .map(|(index, (task, uuid))| {
+            format!(
+                "{{\"type\":\"item_add\",\"uuid\":{},\"temp_id\":\"task-{}\",\"args\":{}}}",
+                json_string(uuid),
+                index,
+                task_json(task, project_id)
+            )
+        })
+        .collect();
+    format!("[{}]", commands.join(","))
+}
+
+/// The arguments of one new task.
+fn task_json(task: &TodoistTask, project_id: Option<&str>) -> String {
+    let mut fields = vec![format!("\"content\":{}", json_string(task.content))];
+    if let Some(description) = task.description {
+        fields.push(format!("\"description\":{}", json_string(description)));
+    }
+    if let Some(due) = task.due {
+        let due = due.to_rfc3339_opts(SecondsFormat::Secs, true);
+        fields.push(format!("\"due\":{{\"date\":{}}}", json_string(&due)));
+    }
+    if let Some(project_id) = project_id {
+        fields.push(format!("\"project_id\":{}", json_string(project_id)));
+    }
+    format!("{{{}}}", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_task_json() {
+        let due = Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap();
+        let task = TodoistTask {
+            content: "Call \"Ann\"",
+            description: Some("re: Q3\nplan"),
+            due: Some(due),
+        };
+        assert_eq!(
+            task_json(&task, Some("220474322")),
+            r#"{"content":"Call \"Ann\"","description":"re: Q3\nplan","due":{"date":"2024-03-01T09:30:00Z"},"project_id":"220474322"}"#
+        );
+        let milk = TodoistTask {
+            content: "Milk",
+            description: None,
+            due: None,
+        };
+        assert_eq!(task_json(&milk, None), r#"{"content":"Milk"}"#);
+        assert_eq!(
+            commands_json(&[milk], None, &["u1".to_string()]),
+            r#"[{"type":"item_add","uuid":"u1","temp_id":"task-0","args":{"content":"Milk"}}]"#
+        );
+    }
+
+    #[test]
+    fn test_command_uuids_are_stable_per_block() {
+        let task = |content| TodoistTask {
+            content,
+            description: None,
+            due: None,
+        };
+        let block = [task("Milk"), task("Eggs")];
+        let uuids = command_uuids("note#0", &block, None);
+        assert_eq!(uuids, command_uuids("note#0", &block, None));
+        assert_ne!(uuids[0], uuids[1]);
+        // The same item in another block is another task
+        assert_ne!(uuids[0], command_uuids("note#0", &[task("Milk")], None)[0]);
+        assert_ne!(uuids, command_uuids("note#0", &block, Some("42")));
+        // So is the same list in another note or further down the note
+        assert_ne!(uuids, command_uuids("other#0", &block, None));
+        assert_ne!(uuids, command_uuids("note#120", &block, None));
+    }
+}
@@ -0,0 +1,111 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::types::{Locale, Theme};
+
+/// Centered list of the open notes, `%` marking the one in the editor.
+/// Also lists the notes linking to the current one, the tags and the
+/// `:lint-blocks` problems, under another title.
+pub struct BufferPicker<'a> {
+    title: &'static str,
+    titles: &'a [String],
+    current: Option<usize>,
+    selected: usize,
+    theme: &'a Theme,
+    locale: Locale,
+}
+
+impl<'a> BufferPicker<'a> {
+    pub fn new(titles: &'a [String], selected: usize, theme: &'a Theme) -> Self {
+        Self {
+            title: "Buffers",
+            titles,
+            current: None,
+            selected,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Block title, before translation.
+    pub fn title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    pub fn current(mut self, current: Option<usize>) -> Self {
+        self.current = current;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+impl Widget for BufferPicker<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let overlay_width = 48.min(area.width.saturating_sub(4));
+        let overlay_height =
+            (self.titles.len().max(1) as u16 + 2).min(area.height.saturating_sub(4));
+
+        let overlay_x = (area.width.saturating_sub(overlay_width)) / 2;
+        let overlay_y = (area.height.saturating_sub(overlay_height)) / 2;
+
+        let overlay_area = Rect::new(overlay_x, overlay_y, overlay_width, overlay_height);
+
+        Clear.render(overlay_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.locale.tr(self.title)))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent_color()))
+            .style(Style::default().bg(self.theme.panel_color()));
+
+        let inner = block.inner(overlay_area);
+        block.render(overlay_area, buf);
+
+        // Scroll so the selected row stays visible
+        let visible = inner.height.max(1) as usize;
+        let offset = self.selected.saturating_sub(visible - 1);
+        let lines: Vec<Line> = self
+            .titles
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(idx, title)| {
+                let style = if idx == self.selected {
+                    Style::default()
+                        .bg(self.theme.selection_color())
+                        .fg(self.theme.fg_color())
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(self.theme.fg_color())
+                };
+                let prefix = if idx == self.selected { "> " } else { "  " };
+                let marker = if Some(idx) == self.current {
+                    "% "
+                } else {
+                    "  "
+                };
+                Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(marker, Style::default().fg(self.theme.accent_color())),
+                    Span::styled(title.clone(), style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .style(Style::default().bg(self.theme.panel_color()))
+            .render(inner, buf);
+    }
+}
@@ -0,0 +1,93 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::types::Theme;
+
+const MAX_WIDTH: u16 = 40;
+
+/// Candidates offered for the mention, tag, link or word before the
+/// cursor, drawn next to it.
+pub struct CompletionPopup<'a> {
+    names: &'a [String],
+    selected: usize,
+    marker: &'a str,
+    theme: &'a Theme,
+}
+
+impl<'a> CompletionPopup<'a> {
+    pub fn new(names: &'a [String], selected: usize, theme: &'a Theme) -> Self {
+        Self {
+            names,
+            selected,
+            marker: "",
+            theme,
+        }
+    }
+
+    /// Shown before each candidate, like `@` or `[[`.
+    pub fn marker(mut self, marker: &'a str) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    /// Where to draw the popup for the cursor at `cursor` within `screen`:
+    /// below it, or above it when there is no room below.
+    pub fn area(&self, cursor: (u16, u16), screen: Rect) -> Rect {
+        let widest = self.names.iter().map(|n| n.width()).max().unwrap_or(0) as u16;
+        let width = (widest + self.marker.width() as u16 + 4)
+            .min(MAX_WIDTH)
+            .min(screen.width);
+        let height = (self.names.len() as u16 + 2).min(screen.height);
+        let x = cursor.0.min(screen.right().saturating_sub(width));
+        let y = if cursor.1 + 1 + height <= screen.bottom() {
+            cursor.1 + 1
+        } else {
+            cursor.1.saturating_sub(height).max(screen.y)
+        };
+        Rect::new(x, y, width, height)
+    }
+}
+
+impl Widget for CompletionPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent_color()))
+            .style(Style::default().bg(self.theme.panel_color()));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let selected = self.selected.min(self.names.len().saturating_sub(1));
+        let lines: Vec<Line> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let style = if idx == selected {
+                    Style::default()
+                        .bg(self.theme.selection_color())
+                        .fg(self.theme.fg_color())
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(self.theme.fg_color())
+                };
+                Line::from(vec![
+                    Span::styled(self.marker, Style::default().fg(self.theme.accent_color())),
+                    Span::styled(name.to_string(), style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .style(Style::default().bg(self.theme.panel_color()))
+            .render(inner, buf);
+    }
+}
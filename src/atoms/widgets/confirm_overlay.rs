@@ -6,16 +6,26 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
-use crate::types::Theme;
+use crate::types::{Locale, Theme};
 
 pub struct ConfirmOverlay<'a> {
     title: &'a str,
     theme: &'a Theme,
+    locale: Locale,
 }
 
 impl<'a> ConfirmOverlay<'a> {
     pub fn new(title: &'a str, theme: &'a Theme) -> Self {
-        Self { title, theme }
+        Self {
+            title,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 }
 
@@ -32,7 +42,7 @@ impl Widget for ConfirmOverlay<'_> {
         Clear.render(overlay_area, buf);
 
         let block = Block::default()
-            .title(" Confirm Delete ")
+            .title(format!(" {} ", self.locale.tr("Confirm Delete")))
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.theme.warning_color()))
@@ -49,16 +59,24 @@ impl Widget for ConfirmOverlay<'_> {
             self.title.to_string()
         };
 
+        let question = self.locale.tr("Delete '{}'?");
+        let (before, after) = question.split_once("{}").unwrap_or((&question, ""));
         let lines = vec![
             Line::from(vec![
-                Span::styled("Delete '", Style::default().fg(self.theme.fg_color())),
+                Span::styled(
+                    before.to_string(),
+                    Style::default().fg(self.theme.fg_color()),
+                ),
                 Span::styled(
                     display_title,
                     Style::default()
                         .fg(self.theme.warning_color())
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled("'?", Style::default().fg(self.theme.fg_color())),
+                Span::styled(
+                    after.to_string(),
+                    Style::default().fg(self.theme.fg_color()),
+                ),
             ]),
             Line::from(vec![
                 Span::styled(
@@ -67,14 +85,20 @@ impl Widget for ConfirmOverlay<'_> {
                         .fg(self.theme.accent_color())
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(": Yes  ", Style::default().fg(self.theme.border_color())),
+                Span::styled(
+                    format!(": {}  ", self.locale.tr("Yes")),
+                    Style::default().fg(self.theme.border_color()),
+                ),
                 Span::styled(
                     "n/Esc",
                     Style::default()
                         .fg(self.theme.accent_color())
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(": No", Style::default().fg(self.theme.border_color())),
+                Span::styled(
+                    format!(": {}", self.locale.tr("No")),
+                    Style::default().fg(self.theme.border_color()),
+                ),
             ]),
         ];
 
@@ -11,7 +11,7 @@ use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 use crate::molecules::editor::RenderSelection;
-use crate::types::{AppMode, Theme};
+use crate::types::{AppMode, ProcessedStyle, Theme};
 
 use super::md_highlight::{MdTokenKind, tokenize_inline};
 
@@ -106,13 +106,16 @@ fn build_display_line(graphemes: &[(String, Style, usize)]) -> Line<'static> {
 }
 
 /// Pre-scan all lines to determine which are inside code block fences (```).
-fn compute_code_block_flags(content: &str) -> Vec<bool> {
-    let lines: Vec<&str> = content.lines().collect();
+/// Fold summaries stand for a whole block, so they neither open nor close one.
+fn compute_code_block_flags(lines: &[&str], folds: &[usize]) -> Vec<bool> {
     let mut flags = vec![false; lines.len()];
     let mut in_code_block = false;
 
     for (idx, line) in lines.iter().enumerate() {
         let trimmed = line.trim();
+        if folds.binary_search(&idx).is_ok() {
+            continue;
+        }
         if trimmed.starts_with("```") {
             flags[idx] = true; // fence line itself is marked
             in_code_block = !in_code_block;
@@ -124,34 +127,66 @@ fn compute_code_block_flags(content: &str) -> Vec<bool> {
     flags
 }
 
+/// Pre-scan all lines to find the blocks (runs of non-blank lines) marked
+/// as sent in a `ProcessedStyle`.
+fn compute_processed_flags(lines: &[&str], folds: &[usize]) -> Vec<bool> {
+    let mut flags = vec![false; lines.len()];
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start..lines.len())
+            .find(|&idx| lines[idx].trim().is_empty() || folds.binary_search(&idx).is_ok())
+            .unwrap_or(lines.len());
+        if end > start
+            && ProcessedStyle::opens(lines[start])
+            && ProcessedStyle::of(&lines[start..end].join("\n")).is_some()
+        {
+            flags[start..end].fill(true);
+        }
+        start = end + 1;
+    }
+    flags
+}
+
 pub struct EditorWidget<'a> {
-    content: &'a str,
+    lines: &'a [&'a str],
     cursor_pos: (usize, usize),
     theme: &'a Theme,
     mode: AppMode,
     title: &'a str,
     scroll_offset: u16,
+    soft_wrap: bool,
+    horizontal_scroll: u16,
     visual_selection: Option<RenderSelection>,
     search_matches: &'a [(usize, usize, usize)],
+    spell_errors: &'a [(usize, usize, usize)],
+    auto_links: &'a [(usize, usize, usize)],
+    folds: &'a [usize],
+    focused: bool,
 }
 
 impl<'a> EditorWidget<'a> {
     pub fn new(
-        content: &'a str,
+        lines: &'a [&'a str],
         cursor_pos: (usize, usize),
         theme: &'a Theme,
         mode: AppMode,
         title: &'a str,
     ) -> Self {
         Self {
-            content,
+            lines,
             cursor_pos,
             theme,
             mode,
             title,
             scroll_offset: 0,
+            soft_wrap: true,
+            horizontal_scroll: 0,
             visual_selection: None,
             search_matches: &[],
+            spell_errors: &[],
+            auto_links: &[],
+            folds: &[],
+            focused: true,
         }
     }
 
@@ -160,6 +195,41 @@ impl<'a> EditorWidget<'a> {
         self
     }
 
+    /// With soft-wrap off each line is one row, shifted left by
+    /// `horizontal_scroll` columns.
+    pub fn soft_wrap(mut self, soft_wrap: bool) -> Self {
+        self.soft_wrap = soft_wrap;
+        self
+    }
+
+    pub fn horizontal_scroll(mut self, columns: u16) -> Self {
+        self.horizontal_scroll = columns;
+        self
+    }
+
+    /// An unfocused split pane draws no cursor and a dimmed title.
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Width lines are wrapped at inside `inner`.
+    fn wrap_width(&self, inner: Rect) -> u16 {
+        if self.soft_wrap {
+            inner.width
+        } else {
+            super::wrap_calc::NO_WRAP
+        }
+    }
+
+    /// Screen x of display column `col`, or `None` when it is scrolled out
+    /// of view.
+    fn column_x(&self, inner: Rect, col: u16) -> Option<u16> {
+        col.checked_sub(self.horizontal_scroll)
+            .filter(|&c| c < inner.width)
+            .map(|c| inner.x + c)
+    }
+
     pub fn visual_selection(mut self, sel: Option<RenderSelection>) -> Self {
         self.visual_selection = sel;
         self
@@ -170,6 +240,66 @@ impl<'a> EditorWidget<'a> {
         self
     }
 
+    /// Misspelled words to underline, as (row, grapheme column, length).
+    pub fn spell_errors(mut self, errors: &'a [(usize, usize, usize)]) -> Self {
+        self.spell_errors = errors;
+        self
+    }
+
+    /// Note ids and dates `gf` follows, drawn like `[[links]]`, as (row,
+    /// grapheme column, length).
+    pub fn auto_links(mut self, links: &'a [(usize, usize, usize)]) -> Self {
+        self.auto_links = links;
+        self
+    }
+
+    /// Screen cells of `len` graphemes from `col` of line `row`, as far as
+    /// they are in view.
+    fn range_cells(
+        &self,
+        inner: Rect,
+        width: u16,
+        row: usize,
+        col: usize,
+        len: usize,
+    ) -> Vec<(u16, u16)> {
+        use super::wrap_calc;
+
+        let Some(line) = self.lines.get(row) else {
+            return Vec::new();
+        };
+        let rows_before: u16 = self
+            .lines
+            .iter()
+            .take(row)
+            .map(|l| wrap_calc::display_rows_for_line(l, width))
+            .sum();
+        let positions = wrap_calc::visual_positions_in_range(line, col, col + len, width);
+        let mut cells = Vec::new();
+        for (wrap_row, col, gw) in positions {
+            // Rows scrolled off the top have no screen position
+            let Some(screen_y) = (inner.y + rows_before + wrap_row).checked_sub(self.scroll_offset)
+            else {
+                continue;
+            };
+            if screen_y < inner.y || screen_y >= inner.y + inner.height {
+                continue;
+            }
+            cells.extend(
+                (0..gw)
+                    .filter_map(|dx| self.column_x(inner, col + dx))
+                    .map(|x| (x, screen_y)),
+            );
+        }
+        cells
+    }
+
+    /// Rows of `lines` that are closed fold summaries, in ascending order.
+    pub fn folds(mut self, rows: &'a [usize]) -> Self {
+        self.folds = rows;
+        self
+    }
+
     /// Applies selection background while preserving markdown formatting.
     ///
     /// Ratatui's `Cell::set_style()` replaces the entire style, so we must manually
@@ -227,16 +357,26 @@ impl<'a> EditorWidget<'a> {
                 .fg(self.theme.warning_color())
                 .bg(self.theme.panel_color()),
             MdTokenKind::Delimiter => base_style.add_modifier(Modifier::DIM),
+            MdTokenKind::WikiLink => base_style
+                .fg(self.theme.accent_color())
+                .add_modifier(Modifier::UNDERLINED),
+            MdTokenKind::Tag => base_style.fg(self.theme.success_color()),
             MdTokenKind::OrderedListPrefix | MdTokenKind::UnorderedListPrefix => {
                 base_style.fg(self.theme.border_color())
             }
         }
     }
 
-    fn highlight_line(&self, line: &str, line_idx: usize, in_code_block: bool) -> Line<'a> {
+    fn highlight_line(
+        &self,
+        line: &str,
+        line_idx: usize,
+        in_code_block: bool,
+        processed: bool,
+    ) -> Line<'a> {
         let mut spans = Vec::new();
 
-        let is_cursor_line = line_idx == self.cursor_pos.0;
+        let is_cursor_line = self.focused && line_idx == self.cursor_pos.0;
         let base_style = if is_cursor_line && self.mode == AppMode::Normal {
             Style::default()
                 .fg(self.theme.fg_color())
@@ -245,6 +385,19 @@ impl<'a> EditorWidget<'a> {
             Style::default().fg(self.theme.fg_color())
         };
 
+        // A closed fold is drawn as one plain line
+        if self.folds.binary_search(&line_idx).is_ok() {
+            let bg = if is_cursor_line && self.mode == AppMode::Normal {
+                self.theme.selection_color()
+            } else {
+                self.theme.panel_color()
+            };
+            return Line::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(self.theme.accent_color()).bg(bg),
+            ));
+        }
+
         // Code block handling
         if in_code_block {
             let trimmed = line.trim();
@@ -267,6 +420,16 @@ impl<'a> EditorWidget<'a> {
             return Line::from(spans);
         }
 
+        // Blocks already sent are dimmed
+        if processed {
+            return Line::from(Span::styled(
+                line.to_string(),
+                base_style
+                    .fg(self.theme.border_color())
+                    .add_modifier(Modifier::DIM),
+            ));
+        }
+
         // Line-level patterns (heading, checkbox, smart tag)
         if let Some(m) = HEADING_RE.find(line) {
             let prefix = &line[..m.end()];
@@ -388,16 +551,17 @@ impl<'a> EditorWidget<'a> {
         let (sr, sc) = start;
         let (er, ec) = end;
 
-        let content_lines: Vec<String> = self.content.lines().map(String::from).collect();
+        let content_lines = self.lines;
+        let width = self.wrap_width(inner);
 
         let mut rows_before: u16 = content_lines
             .iter()
             .take(sr)
-            .map(|l| wrap_calc::display_rows_for_line(l, inner.width))
+            .map(|l| wrap_calc::display_rows_for_line(l, width))
             .sum();
 
         for row in sr..=er {
-            let line = content_lines.get(row).map(|s| s.as_str()).unwrap_or("");
+            let line = content_lines.get(row).copied().unwrap_or("");
             let graphemes: Vec<&str> = line.graphemes(true).collect();
 
             let col_start = if row == sr { sc } else { 0 };
@@ -408,29 +572,27 @@ impl<'a> EditorWidget<'a> {
             };
             let col_end = col_end.min(graphemes.len() + 1);
 
-            let positions =
-                wrap_calc::visual_positions_in_range(line, col_start, col_end, inner.width);
+            let positions = wrap_calc::visual_positions_in_range(line, col_start, col_end, width);
 
             for (wrap_row, col, gw) in positions {
                 let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
                 if screen_y < inner.y || screen_y >= inner.y + inner.height {
                     continue;
                 }
-                let screen_x = inner.x + col;
                 for dx in 0..gw {
-                    if screen_x + dx < inner.x + inner.width {
+                    if let Some(x) = self.column_x(inner, col + dx) {
                         Self::apply_selection_to_cell(
                             buf,
-                            screen_x + dx,
+                            x,
                             screen_y,
-                            self.theme.accent_color(),
+                            self.theme.visual_char_color(),
                             self.theme.fg_color(),
                         );
                     }
                 }
             }
 
-            rows_before += wrap_calc::display_rows_for_line(line, inner.width);
+            rows_before += wrap_calc::display_rows_for_line(line, width);
         }
     }
 
@@ -443,17 +605,18 @@ impl<'a> EditorWidget<'a> {
     ) {
         use super::wrap_calc;
 
-        let content_lines: Vec<String> = self.content.lines().map(String::from).collect();
+        let content_lines = self.lines;
+        let width = self.wrap_width(inner);
 
         let mut rows_before: u16 = content_lines
             .iter()
             .take(start_row)
-            .map(|l| wrap_calc::display_rows_for_line(l, inner.width))
+            .map(|l| wrap_calc::display_rows_for_line(l, width))
             .sum();
 
         for row in start_row..=end_row {
-            let line = content_lines.get(row).map(|s| s.as_str()).unwrap_or("");
-            let num_display_rows = wrap_calc::display_rows_for_line(line, inner.width);
+            let line = content_lines.get(row).copied().unwrap_or("");
+            let num_display_rows = wrap_calc::display_rows_for_line(line, width);
 
             for wrap_row in 0..num_display_rows {
                 let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
@@ -464,7 +627,7 @@ impl<'a> EditorWidget<'a> {
                             buf,
                             inner.x + x,
                             screen_y,
-                            self.theme.accent_color(),
+                            self.theme.visual_line_color(),
                             self.theme.fg_color(),
                         );
                     }
@@ -477,10 +640,10 @@ impl<'a> EditorWidget<'a> {
 
     /// Render block selection highlighting.
     ///
-    /// `left_col` and `right_col` are display columns (inclusive), not grapheme indices.
-    /// For each line, we find graphemes that overlap the display column range
-    /// and highlight their cells. Wide characters (CJK) that partially overlap
-    /// the boundary are fully included.
+    /// `left_col` and `right_col` are display columns (inclusive) along the
+    /// unwrapped line, not grapheme indices, so a resize only moves the cells
+    /// on screen and never changes which text is selected. Wide characters
+    /// (CJK) that partially overlap the boundary are fully included.
     fn render_block_selection(
         &self,
         top_row: usize,
@@ -491,123 +654,100 @@ impl<'a> EditorWidget<'a> {
         buf: &mut Buffer,
     ) {
         use super::wrap_calc;
-        use unicode_segmentation::UnicodeSegmentation;
-        use unicode_width::UnicodeWidthStr;
 
-        let content_lines: Vec<String> = self.content.lines().map(String::from).collect();
-        let w = if inner.width == 0 { 1 } else { inner.width as usize };
+        let content_lines = self.lines;
+        let width = self.wrap_width(inner);
 
         let mut rows_before: u16 = content_lines
             .iter()
             .take(top_row)
-            .map(|l| wrap_calc::display_rows_for_line(l, inner.width))
+            .map(|l| wrap_calc::display_rows_for_line(l, width))
             .sum();
 
         for row in top_row..=bottom_row {
-            let line = content_lines.get(row).map(|s| s.as_str()).unwrap_or("");
-            let graphemes: Vec<&str> = line.graphemes(true).collect();
-
-            // Walk graphemes, tracking display position and wrap state,
-            // highlighting cells that overlap [left_col, right_col].
-            let mut wrap_row: u16 = 0;
-            let mut display_col: usize = 0;
-
-            for g in &graphemes {
-                let gw = g.width().max(1);
-
-                // Check for wrap
-                if display_col + gw > w {
-                    wrap_row += 1;
-                    display_col = 0;
-                }
-
-                let g_end = display_col + gw - 1; // inclusive end
-
-                // Check if grapheme overlaps [left_col, right_col]
-                if display_col <= right_col && g_end >= left_col {
-                    let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
-                    if screen_y >= inner.y && screen_y < inner.y + inner.height {
-                        for dx in 0..gw {
-                            let screen_x = inner.x + display_col as u16 + dx as u16;
-                            if screen_x < inner.x + inner.width {
-                                Self::apply_selection_to_cell(
-                                    buf,
-                                    screen_x,
-                                    screen_y,
-                                    self.theme.accent_color(),
-                                    self.theme.fg_color(),
-                                );
-                            }
-                        }
-                    }
+            let line = content_lines.get(row).copied().unwrap_or("");
+            for (wrap_row, col, gw, virtual_space) in
+                wrap_calc::block_positions(line, left_col, right_col, width)
+            {
+                let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
+                if screen_y < inner.y || screen_y >= inner.y + inner.height {
+                    continue;
                 }
-
-                display_col += gw;
-            }
-
-            // Handle virtual spaces beyond line end
-            let line_display_width = display_col;
-            if right_col >= line_display_width {
-                let virtual_start = left_col.max(line_display_width);
-                for dcol in virtual_start..=right_col {
-                    // Track wrapping for virtual positions
-                    if dcol > 0 && dcol >= w && (dcol % w == 0) {
-                        wrap_row += 1;
-                        // Virtual position wrapping resets to column 0
-                    }
-                    let col_in_row = dcol % w;
-                    let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
-                    if screen_y >= inner.y && screen_y < inner.y + inner.height {
-                        let screen_x = inner.x + col_in_row as u16;
-                        if screen_x < inner.x + inner.width {
+                for dx in 0..gw {
+                    if let Some(screen_x) = self.column_x(inner, col + dx) {
+                        if virtual_space {
                             buf[(screen_x, screen_y)].set_char(' ');
-                            Self::apply_selection_to_cell(
-                                buf,
-                                screen_x,
-                                screen_y,
-                                self.theme.accent_color(),
-                                self.theme.fg_color(),
-                            );
                         }
+                        Self::apply_selection_to_cell(
+                            buf,
+                            screen_x,
+                            screen_y,
+                            self.theme.visual_block_color(),
+                            self.theme.fg_color(),
+                        );
                     }
                 }
             }
 
-            rows_before += wrap_calc::display_rows_for_line(line, inner.width);
+            rows_before += wrap_calc::display_rows_for_line(line, width);
         }
     }
 }
 
 impl Widget for EditorWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let title_color = if self.focused {
+            self.theme.accent_color()
+        } else {
+            self.theme.border_color()
+        };
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.theme.border_color()))
             .title(format!(" {} ", self.title))
-            .title_style(Style::default().fg(self.theme.accent_color()));
+            .title_style(Style::default().fg(title_color));
 
         let inner = block.inner(area);
         block.render(area, buf);
 
+        let width = self.wrap_width(inner);
+
         // Pre-compute code block flags
-        let code_block_flags = compute_code_block_flags(self.content);
+        let code_block_flags = compute_code_block_flags(self.lines, self.folds);
+        let processed_flags = compute_processed_flags(self.lines, self.folds);
+
+        // Only lines in view are highlighted: whole lines above the scroll
+        // offset are skipped and highlighting stops once the area is full.
+        let mut first = 0;
+        let mut skipped_rows: u16 = 0;
+        while let Some(line) = self.lines.get(first) {
+            let rows = super::wrap_calc::display_rows_for_line(line, width);
+            if skipped_rows + rows > self.scroll_offset {
+                break;
+            }
+            skipped_rows += rows;
+            first += 1;
+        }
+        let scroll = self.scroll_offset - skipped_rows;
 
         // Pre-split styled lines using character-level wrapping so that
         // the rendered text matches wrap_calc's cursor position calculations.
-        let display_lines: Vec<Line> = self
-            .content
-            .lines()
+        let display_lines: Vec<Line> = self.lines[first..]
+            .iter()
             .enumerate()
-            .map(|(idx, line)| {
+            .map(|(i, line)| {
+                let idx = first + i;
                 let in_code_block = code_block_flags.get(idx).copied().unwrap_or(false);
-                self.highlight_line(line, idx, in_code_block)
+                let processed = processed_flags.get(idx).copied().unwrap_or(false);
+                self.highlight_line(line, idx, in_code_block, processed)
             })
-            .flat_map(|line| split_line_by_width(line, inner.width))
+            .flat_map(|line| split_line_by_width(line, width))
+            .take(scroll as usize + inner.height as usize)
             .collect();
 
         let paragraph = Paragraph::new(display_lines)
             .style(Style::default().bg(self.theme.bg_color()))
-            .scroll((self.scroll_offset, 0));
+            .scroll((scroll, self.horizontal_scroll));
 
         paragraph.render(inner, buf);
 
@@ -616,47 +756,46 @@ impl Widget for EditorWidget<'_> {
             self.render_selection(selection, inner, buf);
         }
 
+        // Draw note ids and dates as links
+        for &(row, col, len) in self.auto_links {
+            for (x, y) in self.range_cells(inner, width, row, col, len) {
+                let cell = &mut buf[(x, y)];
+                cell.fg = self.theme.accent_color();
+                cell.modifier |= Modifier::UNDERLINED;
+            }
+        }
+
+        // Underline misspelled words
+        for &(row, col, len) in self.spell_errors {
+            for (x, y) in self.range_cells(inner, width, row, col, len) {
+                let cell = &mut buf[(x, y)];
+                cell.modifier |= Modifier::UNDERLINED;
+                cell.underline_color = self.theme.error_color();
+            }
+        }
+
         // Render search match highlights
         if !self.search_matches.is_empty() {
-            use super::wrap_calc;
-
-            let content_lines: Vec<String> = self.content.lines().map(String::from).collect();
+            let selection_bg = self.visual_selection.as_ref().map(|sel| match sel {
+                RenderSelection::CharacterRange { .. } => self.theme.visual_char_color(),
+                RenderSelection::LineRange { .. } => self.theme.visual_line_color(),
+                RenderSelection::BlockRegion { .. } => self.theme.visual_block_color(),
+            });
 
             for &(match_row, match_col, match_len) in self.search_matches {
-                if match_row >= content_lines.len() {
-                    continue;
-                }
-
-                let rows_before: u16 = content_lines
-                    .iter()
-                    .take(match_row)
-                    .map(|l| wrap_calc::display_rows_for_line(l, inner.width))
-                    .sum();
-
-                let line = &content_lines[match_row];
-                let positions = wrap_calc::visual_positions_in_range(
-                    line,
-                    match_col,
-                    match_col + match_len,
-                    inner.width,
-                );
-
-                for (wrap_row, col, gw) in positions {
-                    let screen_y = inner.y + rows_before + wrap_row - self.scroll_offset;
-                    if screen_y < inner.y || screen_y >= inner.y + inner.height {
-                        continue;
-                    }
-                    let screen_x = inner.x + col;
-                    for dx in 0..gw {
-                        if screen_x + dx < inner.x + inner.width {
-                            Self::apply_selection_to_cell(
-                                buf,
-                                screen_x + dx,
-                                screen_y,
-                                self.theme.warning_color(),
-                                self.theme.fg_color(),
-                            );
-                        }
+                for (x, y) in self.range_cells(inner, width, match_row, match_col, match_len) {
+                    let in_selection = selection_bg.is_some_and(|bg| buf[(x, y)].bg == bg);
+                    Self::apply_selection_to_cell(
+                        buf,
+                        x,
+                        y,
+                        self.theme.search_match_color(),
+                        self.theme.fg_color(),
+                    );
+                    // Underline matches inside a selection so they stay
+                    // visible even when the two backgrounds are close.
+                    if in_selection {
+                        buf[(x, y)].modifier |= Modifier::UNDERLINED | Modifier::BOLD;
                     }
                 }
             }
@@ -664,27 +803,19 @@ impl Widget for EditorWidget<'_> {
 
         // Render block cursor only in Normal mode
         // Insert mode uses native terminal cursor (I-beam) set in main.rs
-        if self.mode == AppMode::Normal {
+        if self.focused && self.mode == AppMode::Normal {
             use super::wrap_calc;
 
             let cursor_row = self.cursor_pos.0;
             let cursor_col = self.cursor_pos.1;
 
-            let content_lines: Vec<String> = self.content.lines().map(String::from).collect();
-            let vpos = wrap_calc::visual_cursor_position(
-                &content_lines,
-                cursor_row,
-                cursor_col,
-                inner.width,
-            );
+            let content_lines = self.lines;
+            let vpos =
+                wrap_calc::visual_cursor_position(content_lines, cursor_row, cursor_col, width);
 
-            let cursor_x = inner.x + vpos.col;
             let cursor_y = inner.y + vpos.rows_before + vpos.wrap_row - self.scroll_offset;
 
-            if cursor_y >= inner.y
-                && cursor_y < inner.y + inner.height
-                && cursor_x < inner.x + inner.width
-            {
+            if cursor_y >= inner.y && cursor_y < inner.y + inner.height {
                 let cursor_style = Style::default()
                     .fg(self.theme.bg_color())
                     .bg(self.theme.cursor_color());
@@ -697,8 +828,8 @@ impl Widget for EditorWidget<'_> {
                     .unwrap_or(1) as u16;
 
                 for dx in 0..char_width {
-                    if cursor_x + dx < inner.x + inner.width {
-                        buf[(cursor_x + dx, cursor_y)].set_style(cursor_style);
+                    if let Some(x) = self.column_x(inner, vpos.col + dx) {
+                        buf[(x, cursor_y)].set_style(cursor_style);
                     }
                 }
             }
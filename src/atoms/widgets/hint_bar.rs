@@ -6,17 +6,28 @@ use ratatui::{
     widgets::{Paragraph, Widget},
 };
 
-use crate::types::{AppMode, Theme, View};
+use crate::types::{AppMode, Locale, Theme, View};
 
 pub struct HintBar<'a> {
     mode: AppMode,
     view: View,
     theme: &'a Theme,
+    locale: Locale,
 }
 
 impl<'a> HintBar<'a> {
     pub fn new(mode: AppMode, view: View, theme: &'a Theme) -> Self {
-        Self { mode, view, theme }
+        Self {
+            mode,
+            view,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 
     fn hints(&self) -> Vec<(&str, &str)> {
@@ -34,6 +45,7 @@ impl<'a> HintBar<'a> {
                 ("^Q", "Quit"),
             ],
             (View::Editor, AppMode::Insert) => vec![("Esc", "Normal"), ("^G", "ExtEdit")],
+            (View::Editor, AppMode::Replace) => vec![("Esc", "Normal"), ("BS", "Restore")],
             (View::Editor, AppMode::Visual(_)) => vec![
                 ("Esc", "Normal"),
                 ("d", "Delete"),
@@ -44,7 +56,16 @@ impl<'a> HintBar<'a> {
                 ("v/V/^V", "Switch"),
             ],
             (_, AppMode::Search) => vec![("Enter", "Confirm"), ("Esc", "Cancel")],
+            (_, AppMode::Filter) => vec![("Enter", "Apply"), ("Esc", "Clear")],
+            (_, AppMode::Command) => vec![("Enter", "Run"), ("Esc", "Cancel")],
             (_, AppMode::ConfirmDelete) => vec![("y", "Confirm"), ("n/Esc", "Cancel")],
+            (_, AppMode::ConfirmReplace) => vec![
+                ("y", "Replace"),
+                ("n", "Skip"),
+                ("a", "All"),
+                ("l", "Last"),
+                ("q/Esc", "Stop"),
+            ],
             (View::DraftList, AppMode::Normal) => vec![
                 ("j/k", "Nav"),
                 ("Enter", "Open"),
@@ -52,13 +73,30 @@ impl<'a> HintBar<'a> {
                 ("d", "Delete"),
                 ("n", "New"),
                 ("A", "Archives"),
+                ("T", "Trash"),
+                ("Spc", "Select"),
+                ("s", "Sort"),
                 ("/", "Search"),
+                ("f", "Filter"),
             ],
             (View::ArchiveList, AppMode::Normal) => vec![
                 ("j/k", "Nav"),
                 ("Enter", "View"),
                 ("r", "Restore"),
                 ("d", "Delete"),
+                ("Spc", "Select"),
+                ("s", "Sort"),
+                ("/", "Search"),
+                ("f", "Filter"),
+                ("Esc", "Back"),
+            ],
+            (View::Trash, AppMode::Normal) => vec![
+                ("j/k", "Nav"),
+                ("r", "Restore"),
+                ("d", "Purge"),
+                ("Spc", "Select"),
+                ("s", "Sort"),
+                ("/", "Search"),
                 ("Esc", "Back"),
             ],
             _ => vec![],
@@ -87,7 +125,10 @@ impl Widget for HintBar<'_> {
                 spans.push(Span::styled(" \u{2502} ", sep_style));
             }
             spans.push(Span::styled(*key, key_style));
-            spans.push(Span::styled(format!(" {}", desc), desc_style));
+            spans.push(Span::styled(
+                format!(" {}", self.locale.tr(desc)),
+                desc_style,
+            ));
         }
 
         Paragraph::new(Line::from(spans))
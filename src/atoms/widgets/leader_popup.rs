@@ -5,53 +5,77 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::types::Theme;
+use crate::types::{LeaderCommand, LeaderStep, Locale, Theme, leader_steps};
 
-const LEADER_COMMANDS: &[(&str, &str)] = &[
-    ("s", "Process blocks"),
-    ("l", "Draft list"),
-    ("nn", "New note"),
-    ("q", "Quit"),
-    ("h", "Toggle hints"),
-    ("d", "Toggle checkbox"),
-    ("mc", "Insert checkbox"),
-    ("b", "Bold"),
-    ("i", "Italic"),
-    ("x", "Strikethrough"),
-    ("c", "Inline code"),
-    ("C", "Code block"),
-];
-
-const POPUP_WIDTH: u16 = 24;
+/// Narrowest the popup gets, borders included.
+const MIN_WIDTH: u16 = 24;
 
 pub struct LeaderPopup<'a> {
     theme: &'a Theme,
+    bindings: &'a [(String, LeaderCommand)],
+    prefix: &'a str,
+    locale: Locale,
 }
 
 impl<'a> LeaderPopup<'a> {
-    pub fn new(theme: &'a Theme) -> Self {
-        Self { theme }
+    /// Lists the keys that may follow `prefix`, the keys typed after Space
+    /// so far, in `bindings`: a command, or `+` and where a longer sequence
+    /// leads.
+    pub fn new(theme: &'a Theme, bindings: &'a [(String, LeaderCommand)], prefix: &'a str) -> Self {
+        Self {
+            theme,
+            bindings,
+            prefix,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 }
 
 impl Widget for LeaderPopup<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let popup_height = LEADER_COMMANDS.len() as u16 + 2; // +2 for borders
+        let label = |step| match step {
+            LeaderStep::Run(command) => self.locale.tr(command.label()).into_owned(),
+            LeaderStep::Group { first, count: 1 } => format!("+{}", self.locale.tr(first.label())),
+            LeaderStep::Group { count, .. } => {
+                self.locale.tr(&format!("+{} commands", count)).into_owned()
+            }
+        };
+        let entries: Vec<(char, bool, String)> = leader_steps(self.bindings, self.prefix)
+            .into_iter()
+            .map(|(key, step)| (key, matches!(step, LeaderStep::Group { .. }), label(step)))
+            .collect();
+        let title = if self.prefix.is_empty() {
+            " Leader ".to_string()
+        } else {
+            format!(" Leader {} ", self.prefix)
+        };
+        let label_width = entries.iter().map(|(_, _, l)| l.width()).max().unwrap_or(0);
+        // Borders, the key with a space either side of it
+        let popup_width = ((label_width + 5) as u16)
+            .max(title.width() as u16 + 2)
+            .max(MIN_WIDTH);
+        let popup_height = entries.len() as u16 + 2; // +2 for borders
 
-        if area.width < POPUP_WIDTH + 1 || area.height < popup_height + 3 {
+        if area.width < popup_width + 1 || area.height < popup_height + 3 {
             return;
         }
 
-        let x = area.width.saturating_sub(POPUP_WIDTH + 1);
+        let x = area.width.saturating_sub(popup_width + 1);
         let y = area.height.saturating_sub(popup_height + 3); // 3 = status bar (2) + hint bar (1)
 
-        let popup_area = Rect::new(x, y, POPUP_WIDTH, popup_height);
+        let popup_area = Rect::new(x, y, popup_width, popup_height);
 
         Clear.render(popup_area, buf);
 
         let block = Block::default()
-            .title(" Leader ")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.theme.accent_color()))
             .style(Style::default().bg(self.theme.panel_color()));
@@ -63,13 +87,14 @@ impl Widget for LeaderPopup<'_> {
             .fg(self.theme.accent_color())
             .add_modifier(Modifier::BOLD);
         let desc_style = Style::default().fg(self.theme.fg_color());
+        let group_style = Style::default().fg(self.theme.accent_color());
 
-        let lines: Vec<Line> = LEADER_COMMANDS
-            .iter()
-            .map(|(key, desc)| {
+        let lines: Vec<Line> = entries
+            .into_iter()
+            .map(|(key, group, desc)| {
                 Line::from(vec![
-                    Span::styled(format!(" {:<3}", key), key_style),
-                    Span::styled(*desc, desc_style),
+                    Span::styled(format!(" {} ", key), key_style),
+                    Span::styled(desc, if group { group_style } else { desc_style }),
                 ])
             })
             .collect();
@@ -1,3 +1,5 @@
+use crate::types::tag_spans;
+
 /// Markdown inline syntax tokenizer for editor highlighting.
 /// Pure tokenizer function with no dependencies on ratatui or theme types.
 
@@ -12,6 +14,8 @@ pub enum MdTokenKind {
     Delimiter,
     OrderedListPrefix,
     UnorderedListPrefix,
+    WikiLink,
+    Tag,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,7 +25,7 @@ pub struct MdToken {
 }
 
 /// Tokenizes a line of text into markdown inline elements.
-/// Single left-to-right pass with delimiter priority: ` > ~~ > *** > ** > *
+/// Single left-to-right pass with delimiter priority: #tag > [[ > ` > ~~ > *** > ** > *
 pub fn tokenize_inline(line: &str) -> Vec<MdToken> {
     let mut tokens = Vec::new();
 
@@ -46,11 +50,57 @@ pub fn tokenize_inline(line: &str) -> Vec<MdToken> {
     let mut i = 0;
     let chars: Vec<char> = remaining.chars().collect();
     let mut plain_buffer = String::new();
+    let tags = tag_spans(remaining);
 
     while i < chars.len() {
         let mut handled = false;
 
-        // Try bold italic first (longest * sequence)
+        if let Some(tag) = tags.iter().find(|tag| tag.start == i) {
+            if !plain_buffer.is_empty() {
+                tokens.push(MdToken {
+                    text: plain_buffer.clone(),
+                    kind: MdTokenKind::Plain,
+                });
+                plain_buffer.clear();
+            }
+            tokens.push(MdToken {
+                text: chars[tag.clone()].iter().collect(),
+                kind: MdTokenKind::Tag,
+            });
+            i = tag.end;
+            continue;
+        }
+
+        // Try a [[wiki link]] first
+        if i + 1 < chars.len()
+            && chars[i] == '['
+            && chars[i + 1] == '['
+            && let Some((content, end)) = scan_wiki_link(&chars, i)
+        {
+            if !plain_buffer.is_empty() {
+                tokens.push(MdToken {
+                    text: plain_buffer.clone(),
+                    kind: MdTokenKind::Plain,
+                });
+                plain_buffer.clear();
+            }
+            tokens.push(MdToken {
+                text: "[[".to_string(),
+                kind: MdTokenKind::Delimiter,
+            });
+            tokens.push(MdToken {
+                text: content,
+                kind: MdTokenKind::WikiLink,
+            });
+            tokens.push(MdToken {
+                text: "]]".to_string(),
+                kind: MdTokenKind::Delimiter,
+            });
+            i = end;
+            handled = true;
+        }
+
+        // Try bold italic (longest * sequence)
         if i + 2 < chars.len() && chars[i] == '*' && chars[i + 1] == '*' && chars[i + 2] == '*' {
             match scan_delimited(&chars, i, "***") {
                 Some((content, end)) => {
@@ -272,6 +322,24 @@ fn scan_delimited(chars: &[char], start: usize, delim: &str) -> Option<(String,
     None
 }
 
+/// Scans a `[[link]]` starting at `start` and returns (content, next_index).
+/// Returns None if it is not closed on the line or is empty.
+fn scan_wiki_link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut i = start + 2;
+    let mut content = String::new();
+    while i + 1 < chars.len() {
+        if chars[i] == ']' && chars[i + 1] == ']' {
+            return (!content.trim().is_empty()).then_some((content, i + 2));
+        }
+        if chars[i] == '[' {
+            return None;
+        }
+        content.push(chars[i]);
+        i += 1;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,4 +451,24 @@ mod tests {
         assert!(tokens.iter().any(|t| t.kind == MdTokenKind::Italic));
         assert!(tokens.iter().any(|t| t.kind == MdTokenKind::InlineCode));
     }
+
+    #[test]
+    fn test_wiki_link() {
+        let tokens = tokenize_inline("See [[Home|start]] and [[]]");
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[1].text, "[[");
+        assert_eq!(tokens[2].kind, MdTokenKind::WikiLink);
+        assert_eq!(tokens[2].text, "Home|start");
+        assert_eq!(tokens[4].text, " and [[]]");
+    }
+
+    #[test]
+    fn test_tag() {
+        let tokens = tokenize_inline("- call #mom about `#code` #1");
+        assert_eq!(tokens.len(), 8);
+        assert_eq!(tokens[2].kind, MdTokenKind::Tag);
+        assert_eq!(tokens[2].text, "#mom");
+        assert_eq!(tokens[5].kind, MdTokenKind::InlineCode);
+        assert_eq!(tokens[7].text, " #1");
+    }
 }
@@ -1,18 +1,30 @@
+mod buffer_picker;
+mod completion_popup;
 mod confirm_overlay;
 mod editor_widget;
 mod hint_bar;
 mod leader_popup;
 mod list_item;
 pub mod md_highlight;
+mod outline_sidebar;
 mod processing_overlay;
+mod quickfix_panel;
+mod rename_overlay;
+mod stats_overlay;
 mod status_bar;
 pub mod wrap_calc;
 
+pub use buffer_picker::BufferPicker;
+pub use completion_popup::CompletionPopup;
 pub use confirm_overlay::ConfirmOverlay;
 pub use editor_widget::EditorWidget;
 pub use hint_bar::HintBar;
 pub use leader_popup::LeaderPopup;
 pub use list_item::ListItemWidget;
+pub use outline_sidebar::OutlineSidebar;
 pub use processing_overlay::ProcessingOverlay;
+pub use quickfix_panel::QuickfixPanel;
+pub use rename_overlay::RenameOverlay;
+pub use stats_overlay::StatsOverlay;
 pub use status_bar::StatusBar;
 pub use wrap_calc::{VisualPosition, display_rows_for_line, visual_cursor_position};
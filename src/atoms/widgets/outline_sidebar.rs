@@ -0,0 +1,119 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::molecules::editor::OutlineEntry;
+use crate::types::{Locale, Theme};
+
+/// Headings of the note, indented by level, left of the editor. While it
+/// has the keyboard the selected row is highlighted; otherwise the section
+/// holding the cursor is.
+pub struct OutlineSidebar<'a> {
+    entries: &'a [OutlineEntry],
+    selected: Option<usize>,
+    current: Option<usize>,
+    theme: &'a Theme,
+    locale: Locale,
+}
+
+impl<'a> OutlineSidebar<'a> {
+    pub fn new(entries: &'a [OutlineEntry], theme: &'a Theme) -> Self {
+        Self {
+            entries,
+            selected: None,
+            current: None,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Row under the sidebar's own cursor, when it has the keyboard.
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Section the editor cursor is in.
+    pub fn current(mut self, current: Option<usize>) -> Self {
+        self.current = current;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Screen row of the selected heading inside `area`, for the terminal
+    /// cursor in accessibility mode.
+    pub fn selected_row(&self, area: Rect) -> Option<u16> {
+        let selected = self.selected?;
+        let visible = area.height.saturating_sub(2).max(1) as usize;
+        let offset = selected.saturating_sub(visible - 1);
+        Some(area.y + 1 + (selected - offset) as u16)
+    }
+}
+
+impl Widget for OutlineSidebar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let border_color = if self.selected.is_some() {
+            self.theme.accent_color()
+        } else {
+            self.theme.border_color()
+        };
+        let block = Block::default()
+            .title(format!(" {} ", self.locale.tr("Outline")))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color))
+            .title_style(Style::default().fg(self.theme.accent_color()));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.entries.is_empty() {
+            Paragraph::new(self.locale.tr("No headings").into_owned())
+                .style(
+                    Style::default()
+                        .fg(self.theme.border_color())
+                        .add_modifier(Modifier::DIM),
+                )
+                .render(inner, buf);
+            return;
+        }
+
+        // Scroll so the highlighted row stays visible
+        let highlighted = self.selected.or(self.current);
+        let visible = inner.height.max(1) as usize;
+        let offset = highlighted.map_or(0, |row| row.saturating_sub(visible - 1));
+        let lines: Vec<Line> = self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(idx, entry)| {
+                let style = if Some(idx) == highlighted {
+                    Style::default()
+                        .bg(self.theme.selection_color())
+                        .fg(self.theme.fg_color())
+                        .add_modifier(Modifier::BOLD)
+                } else if entry.level == 1 {
+                    Style::default().fg(self.theme.accent_color())
+                } else {
+                    Style::default().fg(self.theme.fg_color())
+                };
+                let indent = "  ".repeat(entry.level.saturating_sub(1) as usize);
+                Line::from(vec![
+                    Span::raw(indent),
+                    Span::styled(entry.title.clone(), style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
@@ -6,12 +6,13 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
-use crate::types::{BlockType, ProcessingStatus, SmartBlock, Theme};
+use crate::types::{BlockType, Locale, ProcessingStatus, SmartBlock, Theme};
 
 pub struct ProcessingOverlay<'a> {
     blocks: &'a [SmartBlock],
     theme: &'a Theme,
     current_index: usize,
+    locale: Locale,
 }
 
 impl<'a> ProcessingOverlay<'a> {
@@ -20,14 +21,22 @@ impl<'a> ProcessingOverlay<'a> {
             blocks,
             theme,
             current_index,
+            locale: Locale::default(),
         }
     }
 
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
     fn block_type_color(&self, block_type: BlockType) -> ratatui::style::Color {
         match block_type {
             BlockType::Reminder => self.theme.accent_color(),
             BlockType::Calendar => self.theme.error_color(),
             BlockType::Note => self.theme.warning_color(),
+            BlockType::Audio => self.theme.success_color(),
+            BlockType::Mail => self.theme.fg_color(),
         }
     }
 
@@ -36,6 +45,8 @@ impl<'a> ProcessingOverlay<'a> {
             BlockType::Reminder => "[v]",
             BlockType::Calendar => "[c]",
             BlockType::Note => "[n]",
+            BlockType::Audio => "[a]",
+            BlockType::Mail => "[m]",
         }
     }
 }
@@ -53,7 +64,7 @@ impl Widget for ProcessingOverlay<'_> {
         Clear.render(overlay_area, buf);
 
         let block = Block::default()
-            .title(" Processing Blocks ")
+            .title(format!(" {} ", self.locale.tr("Processing Blocks")))
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.theme.accent_color()))
@@ -96,8 +107,10 @@ impl Widget for ProcessingOverlay<'_> {
             let type_icon = self.block_type_icon(smart_block.block_type);
             let type_color = self.block_type_color(smart_block.block_type);
 
+            // Every state has its own glyph; the colors only reinforce it
             let status_icon = match smart_block.status {
-                ProcessingStatus::Pending => " ",
+                ProcessingStatus::Pending if is_current => ">",
+                ProcessingStatus::Pending => ".",
                 ProcessingStatus::Sent => "+",
                 ProcessingStatus::Failed => "x",
                 ProcessingStatus::Skipped => "-",
@@ -0,0 +1,111 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::molecules::editor::QuickfixList;
+use crate::types::{Locale, Theme};
+
+/// The locations of the last `:lint-blocks` (or other listing command)
+/// under the editor, one `line: text` row each. The current location is
+/// highlighted; the border takes the accent color while the panel has the
+/// keyboard.
+pub struct QuickfixPanel<'a> {
+    list: &'a QuickfixList,
+    focused: bool,
+    theme: &'a Theme,
+    locale: Locale,
+}
+
+impl<'a> QuickfixPanel<'a> {
+    pub fn new(list: &'a QuickfixList, theme: &'a Theme) -> Self {
+        Self {
+            list,
+            focused: false,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Rows the panel wants: its locations, at most `max_rows`, plus the
+    /// borders.
+    pub fn height(list: &QuickfixList, max_rows: u16) -> u16 {
+        (list.locations().len() as u16).clamp(1, max_rows) + 2
+    }
+
+    /// Screen row of the current location inside `area`, for the terminal
+    /// cursor in accessibility mode.
+    pub fn selected_row(&self, area: Rect) -> u16 {
+        let visible = area.height.saturating_sub(2).max(1) as usize;
+        let offset = self.list.current().saturating_sub(visible - 1);
+        area.y + 1 + (self.list.current() - offset) as u16
+    }
+}
+
+impl Widget for QuickfixPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let border_color = if self.focused {
+            self.theme.accent_color()
+        } else {
+            self.theme.border_color()
+        };
+        let title = format!(
+            " {} ({}/{}) ",
+            self.locale.tr(self.list.title()),
+            self.list.current() + 1,
+            self.list.locations().len()
+        );
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color))
+            .title_style(Style::default().fg(self.theme.accent_color()));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Scroll so the current location stays visible
+        let visible = inner.height.max(1) as usize;
+        let offset = self.list.current().saturating_sub(visible - 1);
+        let lines: Vec<Line> = self
+            .list
+            .locations()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(idx, location)| {
+                let style = if idx == self.list.current() {
+                    Style::default()
+                        .bg(self.theme.selection_color())
+                        .fg(self.theme.fg_color())
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(self.theme.fg_color())
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>4}: ", location.row + 1),
+                        Style::default().fg(self.theme.border_color()),
+                    ),
+                    Span::styled(location.text.clone(), style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
@@ -0,0 +1,119 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::types::{Locale, Theme};
+
+/// Centered prompt for the new title of a note.
+pub struct RenameOverlay<'a> {
+    title: &'a str,
+    cursor: usize,
+    theme: &'a Theme,
+    locale: Locale,
+}
+
+impl<'a> RenameOverlay<'a> {
+    /// `cursor` is a grapheme index into `title`.
+    pub fn new(title: &'a str, cursor: usize, theme: &'a Theme) -> Self {
+        Self {
+            title,
+            cursor,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    fn overlay_area(area: Rect) -> Rect {
+        let width = 48.min(area.width.saturating_sub(4));
+        let height = 5.min(area.height.saturating_sub(4));
+        Rect::new(
+            (area.width.saturating_sub(width)) / 2,
+            (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        )
+    }
+
+    /// Columns of the title scrolled out on the left so the cursor fits in
+    /// `width`.
+    fn scroll(&self, width: u16) -> usize {
+        let before: String = self.title.graphemes(true).take(self.cursor).collect();
+        before
+            .width()
+            .saturating_sub(width.saturating_sub(1) as usize)
+    }
+
+    /// Screen position of the cursor when rendered into `area`.
+    pub fn cursor_position(&self, area: Rect) -> (u16, u16) {
+        let inner = Self::overlay_area(area).inner(Margin::new(2, 1));
+        let before: String = self.title.graphemes(true).take(self.cursor).collect();
+        let col = before.width() - self.scroll(inner.width);
+        (inner.x + col as u16, inner.y)
+    }
+}
+
+impl Widget for RenameOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let overlay_area = Self::overlay_area(area);
+        Clear.render(overlay_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.locale.tr("Rename")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent_color()))
+            .style(Style::default().bg(self.theme.panel_color()));
+        block.render(overlay_area, buf);
+
+        let inner = overlay_area.inner(Margin::new(2, 1));
+        let scroll = self.scroll(inner.width);
+        let mut col = 0;
+        let visible: String = self
+            .title
+            .graphemes(true)
+            .filter(|g| {
+                let shown = col >= scroll;
+                col += g.width();
+                shown
+            })
+            .collect();
+
+        let key = |text: &'static str| {
+            Span::styled(
+                text,
+                Style::default()
+                    .fg(self.theme.accent_color())
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let label =
+            |text: String| Span::styled(text, Style::default().fg(self.theme.border_color()));
+        let lines = vec![
+            Line::from(Span::styled(
+                visible,
+                Style::default().fg(self.theme.fg_color()),
+            )),
+            Line::default(),
+            Line::from(vec![
+                key("Enter"),
+                label(format!(": {}  ", self.locale.tr("Rename"))),
+                key("Esc"),
+                label(format!(": {}", self.locale.tr("Cancel"))),
+            ]),
+        ];
+        Paragraph::new(lines)
+            .style(Style::default().bg(self.theme.panel_color()))
+            .render(inner, buf);
+    }
+}
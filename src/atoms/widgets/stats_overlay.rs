@@ -0,0 +1,138 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::types::{Locale, StatsSummary, Theme};
+
+const BAR_WIDTH: usize = 20;
+
+pub struct StatsOverlay<'a> {
+    summary: &'a StatsSummary,
+    theme: &'a Theme,
+    locale: Locale,
+}
+
+impl<'a> StatsOverlay<'a> {
+    pub fn new(summary: &'a StatsSummary, theme: &'a Theme) -> Self {
+        Self {
+            summary,
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    fn row(&self, label: &str, value: String) -> Line<'static> {
+        let label = self.locale.tr(label);
+        let padding = 10usize.saturating_sub(label.width());
+        Line::from(vec![
+            Span::styled(
+                format!("{}{}", label, " ".repeat(padding)),
+                Style::default().fg(self.theme.border_color()),
+            ),
+            Span::styled(
+                self.locale.tr(&value).into_owned(),
+                Style::default()
+                    .fg(self.theme.fg_color())
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ])
+    }
+}
+
+impl Widget for StatsOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let overlay_width = 44.min(area.width.saturating_sub(4));
+        let overlay_height = (self.summary.last_week.len() as u16 + 10).min(area.height);
+
+        let overlay_x = (area.width.saturating_sub(overlay_width)) / 2;
+        let overlay_y = (area.height.saturating_sub(overlay_height)) / 2;
+
+        let overlay_area = Rect::new(overlay_x, overlay_y, overlay_width, overlay_height);
+
+        Clear.render(overlay_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.locale.tr("Writing Stats")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent_color()))
+            .style(Style::default().bg(self.theme.panel_color()));
+
+        let inner = block.inner(overlay_area);
+        block.render(overlay_area, buf);
+
+        let summary = self.summary;
+        let mut lines = vec![
+            self.row(
+                "Session",
+                format!(
+                    "{} words in {} notes ({} min)",
+                    summary.session_words, summary.session_notes, summary.session_minutes
+                ),
+            ),
+            self.row("Today", format!("{} words", summary.today)),
+            self.row(
+                "Streak",
+                format!(
+                    "{} day{}",
+                    summary.streak,
+                    if summary.streak == 1 { "" } else { "s" }
+                ),
+            ),
+            self.row(
+                "Best day",
+                summary
+                    .best_day
+                    .as_ref()
+                    .map(|(day, words)| format!("{} ({} words)", day, words))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Line::raw(""),
+        ];
+
+        let max = summary
+            .last_week
+            .iter()
+            .map(|(_, w)| *w)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (day, words) in &summary.last_week {
+            let filled = words * BAR_WIDTH / max;
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} ", day.get(5..).unwrap_or(day)),
+                    Style::default().fg(self.theme.border_color()),
+                ),
+                Span::styled(
+                    "█".repeat(filled),
+                    Style::default().fg(self.theme.success_color()),
+                ),
+                Span::styled(
+                    format!(" {}", words),
+                    Style::default().fg(self.theme.fg_color()),
+                ),
+            ]));
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            self.locale.tr("Press any key to close"),
+            Style::default().fg(self.theme.border_color()),
+        ));
+
+        Paragraph::new(lines)
+            .style(Style::default().bg(self.theme.panel_color()))
+            .render(inner, buf);
+    }
+}
@@ -5,6 +5,8 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Paragraph, Widget},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::types::{AppMode, Theme, View};
 
@@ -14,7 +16,16 @@ pub struct StatusBar<'a> {
     theme: &'a Theme,
     message: &'a str,
     search_query: &'a str,
+    filter: &'a str,
+    prompt_cursor: Option<usize>,
+    command_line: &'a str,
     file_name: &'a str,
+    read_only: bool,
+    title_drift: bool,
+    search_status: &'a str,
+    word_goal: Option<(usize, usize)>,
+    selection: &'a str,
+    accessible: bool,
 }
 
 impl<'a> StatusBar<'a> {
@@ -25,7 +36,16 @@ impl<'a> StatusBar<'a> {
             theme,
             message: "",
             search_query: "",
+            filter: "",
+            prompt_cursor: None,
+            command_line: "",
             file_name: "",
+            read_only: false,
+            title_drift: false,
+            search_status: "",
+            word_goal: None,
+            selection: "",
+            accessible: false,
         }
     }
 
@@ -39,19 +59,108 @@ impl<'a> StatusBar<'a> {
         self
     }
 
+    /// List view filter, edited in Filter mode and otherwise shown as a chip.
+    pub fn filter(mut self, filter: &'a str) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Grapheme index of the cursor in the search or filter prompt; defaults
+    /// to the end.
+    pub fn prompt_cursor(mut self, cursor: usize) -> Self {
+        self.prompt_cursor = Some(cursor);
+        self
+    }
+
+    pub fn command_line(mut self, line: &'a str) -> Self {
+        self.command_line = line;
+        self
+    }
+
     pub fn file_name(mut self, name: &'a str) -> Self {
         self.file_name = name;
         self
     }
 
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Mark the title as out of step with the note's first heading.
+    pub fn title_drift(mut self, drift: bool) -> Self {
+        self.title_drift = drift;
+        self
+    }
+
+    pub fn search_status(mut self, status: &'a str) -> Self {
+        self.search_status = status;
+        self
+    }
+
+    /// Words written and target, rendered as a progress gauge.
+    pub fn word_goal(mut self, progress: Option<(usize, usize)>) -> Self {
+        self.word_goal = progress;
+        self
+    }
+
+    /// Size of the Visual selection, e.g. `sel: 3 lines`.
+    pub fn selection(mut self, selection: &'a str) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Spell out what colors otherwise convey and drop decorative chips.
+    pub fn accessible(mut self, accessible: bool) -> Self {
+        self.accessible = accessible;
+        self
+    }
+
+    /// The prompt being typed: prefix, text and grapheme cursor.
+    fn prompt(&self) -> Option<(&'static str, &'a str, Option<usize>)> {
+        match self.mode {
+            AppMode::Search => Some(("/", self.search_query, self.prompt_cursor)),
+            AppMode::Filter => Some(("filter: ", self.filter, self.prompt_cursor)),
+            AppMode::Command => Some((":", self.command_line, None)),
+            _ => None,
+        }
+    }
+
+    /// Screen position of the prompt cursor when rendered into `area`.
+    pub fn cursor_position(&self, area: Rect) -> Option<(u16, u16)> {
+        let (prefix, input, cursor) = self.prompt()?;
+        let before: String = match cursor {
+            Some(cursor) => input.graphemes(true).take(cursor).collect(),
+            None => input.to_string(),
+        };
+        let x = area.x + (prefix.width() + before.width()) as u16;
+        Some((x.min(area.right().saturating_sub(1)), area.y))
+    }
+
+    fn word_goal_label(&self) -> Option<String> {
+        const GAUGE_CELLS: usize = 10;
+        let (words, goal) = self.word_goal?;
+        let percent = (words * 100 / goal.max(1)).min(100);
+        let filled = percent * GAUGE_CELLS / 100;
+        Some(format!(
+            " {}/{} {}{} {}% ",
+            words,
+            goal,
+            "█".repeat(filled),
+            "░".repeat(GAUGE_CELLS - filled),
+            percent
+        ))
+    }
+
     fn mode_color(&self) -> ratatui::style::Color {
         match self.mode {
             AppMode::Normal => self.theme.accent_color(),
             AppMode::Insert => self.theme.success_color(),
+            AppMode::Replace => self.theme.error_color(),
             AppMode::Visual(_) => self.theme.warning_color(),
             AppMode::Processing => self.theme.error_color(),
-            AppMode::Search => self.theme.warning_color(),
-            AppMode::ConfirmDelete => self.theme.warning_color(),
+            AppMode::Search | AppMode::Command | AppMode::Filter => self.theme.warning_color(),
+            AppMode::ConfirmDelete | AppMode::ConfirmReplace => self.theme.warning_color(),
         }
     }
 
@@ -60,6 +169,7 @@ impl<'a> StatusBar<'a> {
             View::Editor => "[]",
             View::DraftList => "=",
             View::ArchiveList => "@",
+            View::Trash => "x",
         }
     }
 }
@@ -68,19 +178,27 @@ impl Widget for StatusBar<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let chunks = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).split(area);
 
-        let message_line = if self.mode == AppMode::Search {
-            Line::from(vec![
-                Span::styled("/", Style::default().fg(self.theme.warning_color())),
-                Span::styled(
-                    self.search_query.to_string(),
-                    Style::default().fg(self.theme.fg_color()),
-                ),
-                Span::styled(
+        let message_line = if let Some((prefix, input, cursor)) = self.prompt() {
+            let graphemes: Vec<&str> = input.graphemes(true).collect();
+            let cursor = cursor.unwrap_or(graphemes.len()).min(graphemes.len());
+            let text_style = Style::default().fg(self.theme.fg_color());
+            let cursor_span = match graphemes.get(cursor) {
+                Some(g) => Span::styled(g.to_string(), text_style.add_modifier(Modifier::REVERSED)),
+                None => Span::styled(
                     "_",
                     Style::default()
                         .fg(self.theme.cursor_color())
                         .add_modifier(Modifier::SLOW_BLINK),
                 ),
+            };
+            Line::from(vec![
+                Span::styled(prefix, Style::default().fg(self.theme.warning_color())),
+                Span::styled(graphemes[..cursor].concat(), text_style),
+                cursor_span,
+                Span::styled(
+                    graphemes.get(cursor + 1..).unwrap_or_default().concat(),
+                    text_style,
+                ),
             ])
         } else {
             Line::from(Span::styled(
@@ -119,6 +237,97 @@ impl Widget for StatusBar<'_> {
             Span::raw("")
         };
 
+        let title_drift_label = if self.accessible {
+            " title differs from heading "
+        } else {
+            " ≠ heading "
+        };
+        let title_drift_span = if self.title_drift {
+            Span::styled(
+                title_drift_label,
+                Style::default()
+                    .bg(self.theme.panel_color())
+                    .fg(self.theme.warning_color()),
+            )
+        } else {
+            Span::raw("")
+        };
+        let title_drift_width = title_drift_span.width() as u16;
+
+        let read_only_label = if self.accessible {
+            " read-only "
+        } else {
+            " RO "
+        };
+        let read_only_span = if self.read_only {
+            Span::styled(
+                read_only_label,
+                Style::default()
+                    .bg(self.theme.warning_color())
+                    .fg(self.theme.bg_color())
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw("")
+        };
+        let read_only_width = read_only_span.width() as u16;
+
+        let search_status_span = if !self.search_status.is_empty() {
+            Span::styled(
+                format!(" {} ", self.search_status),
+                Style::default()
+                    .bg(self.theme.panel_color())
+                    .fg(self.theme.search_match_color()),
+            )
+        } else {
+            Span::raw("")
+        };
+        let search_status_width = search_status_span.width() as u16;
+
+        let selection_span = if !self.selection.is_empty() {
+            Span::styled(
+                format!(" {} ", self.selection),
+                Style::default()
+                    .bg(self.theme.panel_color())
+                    .fg(self.mode_color()),
+            )
+        } else {
+            Span::raw("")
+        };
+        let selection_width = selection_span.width() as u16;
+
+        let filter_span = if !self.filter.is_empty() && self.view != View::Editor {
+            Span::styled(
+                format!(" filter: {} ", self.filter),
+                Style::default()
+                    .bg(self.theme.accent_color())
+                    .fg(self.theme.bg_color()),
+            )
+        } else {
+            Span::raw("")
+        };
+        let filter_width = filter_span.width() as u16;
+
+        let word_goal_span = match self.word_goal_label() {
+            Some(mut label) => {
+                let reached = self.word_goal.is_some_and(|(words, goal)| words >= goal);
+                if reached && self.accessible {
+                    label.push_str("reached ");
+                }
+                let color = if reached {
+                    self.theme.success_color()
+                } else {
+                    self.theme.accent_color()
+                };
+                Span::styled(
+                    label,
+                    Style::default().bg(self.theme.panel_color()).fg(color),
+                )
+            }
+            None => Span::raw(""),
+        };
+        let word_goal_width = word_goal_span.width() as u16;
+
         let meta_span = Span::styled(
             " utf-8 | markdown | 100% ",
             Style::default()
@@ -133,26 +342,42 @@ impl Widget for StatusBar<'_> {
                 .fg(self.theme.border_color()),
         );
 
-        let status_line = Line::from(vec![
+        let mut status_line = Line::from(vec![
             mode_span,
             view_span,
             file_span,
-            Span::styled(
+            title_drift_span,
+            read_only_span,
+            selection_span,
+            filter_span,
+            search_status_span,
+            word_goal_span,
+        ]);
+        // The meta and icon chips are decoration a screen reader would
+        // read out on every update.
+        if !self.accessible {
+            status_line.push_span(Span::styled(
                 " ".repeat(
                     area.width
                         .saturating_sub(
                             self.mode.as_str().len() as u16
                                 + self.view.as_str().len() as u16
                                 + self.file_name.len() as u16
+                                + title_drift_width
+                                + read_only_width
+                                + selection_width
+                                + filter_width
+                                + search_status_width
+                                + word_goal_width
                                 + 30,
                         )
                         .into(),
                 ),
                 Style::default().bg(self.theme.panel_color()),
-            ),
-            meta_span,
-            icons_span,
-        ]);
+            ));
+            status_line.push_span(meta_span);
+            status_line.push_span(icons_span);
+        }
 
         Paragraph::new(status_line)
             .style(Style::default().bg(self.theme.panel_color()))
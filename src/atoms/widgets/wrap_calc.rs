@@ -1,6 +1,10 @@
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+/// Width to pass when soft-wrap is off: every line is one display row and
+/// `col` is the plain display column.
+pub const NO_WRAP: u16 = u16::MAX;
+
 /// Visual position of a cursor after accounting for soft-wrap.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VisualPosition {
@@ -44,8 +48,8 @@ pub fn display_rows_for_line(line: &str, width: u16) -> u16 {
 /// Compute the visual cursor position accounting for soft-wrap of all lines.
 ///
 /// `cursor_col` is a grapheme index (not display-width).
-pub fn visual_cursor_position(
-    lines: &[String],
+pub fn visual_cursor_position<S: AsRef<str>>(
+    lines: &[S],
     cursor_row: usize,
     cursor_col: usize,
     width: u16,
@@ -56,11 +60,11 @@ pub fn visual_cursor_position(
     let rows_before: u16 = lines
         .iter()
         .take(cursor_row)
-        .map(|l| display_rows_for_line(l, width))
+        .map(|l| display_rows_for_line(l.as_ref(), width))
         .sum();
 
     // Compute wrap_row and col within the cursor's line
-    let line = lines.get(cursor_row).map(|s| s.as_str()).unwrap_or("");
+    let line = lines.get(cursor_row).map(|s| s.as_ref()).unwrap_or("");
     let line_display_rows = display_rows_for_line(line, width);
 
     let mut wrap_row: u16 = 0;
@@ -88,6 +92,19 @@ pub fn visual_cursor_position(
     }
 }
 
+/// Logical line shown on display row `row` when wrapped at `width`; the last
+/// line when `row` is past the end.
+pub fn line_at_display_row<S: AsRef<str>>(lines: &[S], row: u16, width: u16) -> usize {
+    let mut rows_before: u16 = 0;
+    for (idx, line) in lines.iter().enumerate() {
+        rows_before = rows_before.saturating_add(display_rows_for_line(line.as_ref(), width));
+        if row < rows_before {
+            return idx;
+        }
+    }
+    lines.len().saturating_sub(1)
+}
+
 /// Compute `(wrap_row, col)` for each grapheme index in `[col_start, col_end)`.
 ///
 /// Returns one entry per grapheme in the range. Each entry gives the display-row
@@ -200,6 +217,56 @@ pub fn virtual_block_positions(
     result
 }
 
+/// Cells of a Visual Block selection on one line: `(wrap_row, col, width,
+/// virtual)` for each grapheme overlapping the display columns
+/// `[left_col, right_col]`, then one-cell virtual spaces for the columns past
+/// the end of the line.
+///
+/// The columns are logical (measured along the unwrapped line), so the same
+/// selection covers the same text whatever `width` it is wrapped at; only
+/// where the cells land on screen changes.
+pub fn block_positions(
+    line: &str,
+    left_col: usize,
+    right_col: usize,
+    width: u16,
+) -> Vec<(u16, u16, u16, bool)> {
+    let w = if width == 0 { 1 } else { width as usize };
+    let mut result = Vec::new();
+    let mut wrap_row: u16 = 0;
+    // Column on the display row, and along the logical line
+    let mut col: usize = 0;
+    let mut logical: usize = 0;
+
+    for g in line.graphemes(true) {
+        let gw = g.width().max(1);
+        if col + gw > w {
+            wrap_row += 1;
+            col = 0;
+        }
+        if logical <= right_col && logical + gw > left_col {
+            result.push((wrap_row, col as u16, gw as u16, false));
+        }
+        col += gw;
+        logical += gw;
+    }
+
+    // Virtual spaces continue where the text stops
+    while logical <= right_col {
+        if col + 1 > w {
+            wrap_row += 1;
+            col = 0;
+        }
+        if logical >= left_col {
+            result.push((wrap_row, col as u16, 1, true));
+        }
+        col += 1;
+        logical += 1;
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,6 +388,16 @@ mod tests {
         assert_eq!(vpos.line_display_rows, 1);
     }
 
+    #[test]
+    fn test_line_at_display_row() {
+        let lines = ["short", "a long line that wraps", "end"];
+        assert_eq!(line_at_display_row(&lines, 0, 10), 0);
+        assert_eq!(line_at_display_row(&lines, 1, 10), 1);
+        assert_eq!(line_at_display_row(&lines, 3, 10), 1);
+        assert_eq!(line_at_display_row(&lines, 4, 10), 2);
+        assert_eq!(line_at_display_row(&lines, 40, 10), 2);
+    }
+
     #[test]
     fn test_visual_positions_in_range_simple() {
         let positions = visual_positions_in_range("hello", 1, 4, 10);
@@ -397,4 +474,29 @@ mod tests {
         assert_eq!(positions[3], (1, 1, 1));
         assert_eq!(positions[4], (1, 2, 1));
     }
+
+    #[test]
+    fn test_block_positions_follow_logical_columns() {
+        // Columns 2..=3 of "abcdef" are "cd" at any width
+        assert_eq!(
+            block_positions("abcdef", 2, 3, 10),
+            [(0, 2, 1, false), (0, 3, 1, false)]
+        );
+        assert_eq!(
+            block_positions("abcdef", 2, 3, 3),
+            [(0, 2, 1, false), (1, 0, 1, false)]
+        );
+        // A wide character overlapping the edge is included whole
+        assert_eq!(block_positions("a你b", 2, 2, 10), [(0, 1, 2, false)]);
+        // Past the end of the line, virtual spaces wrap like text
+        assert_eq!(
+            block_positions("ab", 1, 4, 3),
+            [
+                (0, 1, 1, false),
+                (0, 2, 1, true),
+                (1, 0, 1, true),
+                (1, 1, 1, true)
+            ]
+        );
+    }
 }
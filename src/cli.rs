@@ -0,0 +1,365 @@
+use anyhow::{Context, Result, bail};
+
+use kenotex::types::View;
+
+/// Parsed command-line arguments for the TUI binary.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub version: bool,
+    pub view: Option<View>,
+    pub read_only: bool,
+    /// Record smart blocks instead of sending them.
+    pub dry_run: bool,
+    /// Pretend to send smart blocks, with delays and synthetic failures.
+    pub simulate_dispatch: bool,
+    /// Force the accessibility mode on, whatever the config says.
+    pub accessible: bool,
+    /// Output path for `--cheat-sheet`; `-` writes to stdout.
+    pub cheat_sheet: Option<String>,
+    /// Note id or title for `--share`.
+    pub share: Option<String>,
+    pub output: Option<String>,
+    /// Environment variable holding the passphrase for an encrypted bundle.
+    pub passphrase_env: Option<String>,
+    /// Apple Notes / Bear export file or directory for `--import`.
+    pub import: Option<String>,
+    /// Note id, title or alias to open in the editor at startup.
+    pub open: Option<String>,
+    /// Set by the `capture` subcommand.
+    pub capture: Option<CaptureArgs>,
+    /// Set by the `list`, `search`, `doctor` and `dispatch` subcommands.
+    pub script: Option<ScriptArgs>,
+}
+
+/// Arguments for `kenotex capture`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CaptureArgs {
+    /// Read the text to capture from stdin (macOS Services / Shortcuts).
+    pub from_service: bool,
+    /// Write the "Send to Kenotex" Quick Action instead of capturing.
+    pub install_service: bool,
+    /// Text given on the command line.
+    pub text: Vec<String>,
+}
+
+impl CaptureArgs {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                "--from-service" => parsed.from_service = true,
+                "--install-service" => parsed.install_service = true,
+                flag if flag.starts_with("--") => bail!("Unknown capture option: {}", flag),
+                _ => parsed.text.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// The subcommands meant for scripts.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptCommand {
+    /// Drafts, or the archive with `--archive`.
+    List { archived: bool },
+    /// Drafts and archived notes matching the query.
+    Search { query: String },
+    /// Move damaged note files to `recovery/` and report them.
+    Doctor,
+    /// Send the smart blocks of a note.
+    Dispatch { note: String, dry_run: bool },
+}
+
+/// How a script subcommand prints its result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScriptFormat {
+    #[default]
+    Text,
+    Json,
+    /// Alfred script filter items, for `list` and `search`.
+    Alfred,
+}
+
+/// Arguments for `kenotex list|search|doctor|dispatch`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ScriptArgs {
+    pub command: ScriptCommand,
+    pub format: ScriptFormat,
+}
+
+impl ScriptArgs {
+    fn parse(name: &str, mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let (mut archived, mut dry_run) = (false, false);
+        let mut format = ScriptFormat::Text;
+        let mut words = Vec::new();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            match flag {
+                "--json" => format = ScriptFormat::Json,
+                "--format" => {
+                    let value = match inline_value {
+                        Some(v) => v,
+                        None => args.next().context("--format requires a value")?,
+                    };
+                    format = match value.as_str() {
+                        "text" => ScriptFormat::Text,
+                        "json" => ScriptFormat::Json,
+                        "alfred" if ["list", "search"].contains(&name) => ScriptFormat::Alfred,
+                        _ => bail!("Unknown {} format: {}", name, value),
+                    };
+                }
+                "--archive" if name == "list" => archived = true,
+                "--dry-run" if name == "dispatch" => dry_run = true,
+                flag if flag.starts_with("--") => bail!("Unknown {} option: {}", name, flag),
+                _ => words.push(arg.clone()),
+            }
+        }
+        let command = match name {
+            "list" | "doctor" if !words.is_empty() => {
+                bail!("{} takes no arguments", name)
+            }
+            "list" => ScriptCommand::List { archived },
+            "doctor" => ScriptCommand::Doctor,
+            _ if words.is_empty() => bail!("{} requires a note or query", name),
+            "search" => ScriptCommand::Search {
+                query: words.join(" "),
+            },
+            _ => ScriptCommand::Dispatch {
+                note: words.join(" "),
+                dry_run,
+            },
+        };
+        Ok(Self { command, format })
+    }
+}
+
+impl CliArgs {
+    /// Parse arguments, excluding the program name.
+    pub fn parse<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut iter = args.into_iter().peekable();
+
+        if iter.peek().is_some_and(|first| first == "capture") {
+            iter.next();
+            parsed.capture = Some(CaptureArgs::parse(iter)?);
+            return Ok(parsed);
+        }
+        if let Some(name) =
+            iter.next_if(|first| ["list", "search", "doctor", "dispatch"].contains(&first.as_str()))
+        {
+            parsed.script = Some(ScriptArgs::parse(&name, iter)?);
+            return Ok(parsed);
+        }
+
+        while let Some(arg) = iter.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+
+            match flag.as_str() {
+                "--version" | "-V" => parsed.version = true,
+                "--read-only" => parsed.read_only = true,
+                "--dry-run" => parsed.dry_run = true,
+                "--simulate-dispatch" => parsed.simulate_dispatch = true,
+                "--accessible" => parsed.accessible = true,
+                "--cheat-sheet" => {
+                    let target = match inline_value {
+                        Some(v) => v,
+                        None => match iter.peek() {
+                            Some(next) if !next.starts_with("--") => {
+                                iter.next().unwrap_or_default()
+                            }
+                            _ => "-".to_string(),
+                        },
+                    };
+                    parsed.cheat_sheet = Some(target);
+                }
+                "--share" | "--output" | "-o" | "--passphrase-env" | "--import" | "--open" => {
+                    let value = match inline_value {
+                        Some(v) => v,
+                        None => iter
+                            .next()
+                            .with_context(|| format!("{} requires a value", flag))?,
+                    };
+                    match flag.as_str() {
+                        "--share" => parsed.share = Some(value),
+                        "--passphrase-env" => parsed.passphrase_env = Some(value),
+                        "--import" => parsed.import = Some(value),
+                        "--open" => parsed.open = Some(value),
+                        _ => parsed.output = Some(value),
+                    }
+                }
+                "--view" => {
+                    let value = match inline_value {
+                        Some(v) => v,
+                        None => iter.next().context("--view requires a value")?,
+                    };
+                    let view = View::from_name(&value).with_context(|| {
+                        format!(
+                            "Unknown view '{}' (expected drafts, archive, or editor)",
+                            value
+                        )
+                    })?;
+                    parsed.view = Some(view);
+                }
+                other => bail!("Unknown argument: {}", other),
+            }
+        }
+
+        if parsed.passphrase_env.is_some() && parsed.output.is_none() {
+            bail!("--passphrase-env requires --output");
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_view_separate_value() {
+        let cli = CliArgs::parse(args(&["--view", "drafts"])).unwrap();
+        assert_eq!(cli.view, Some(View::DraftList));
+    }
+
+    #[test]
+    fn test_parse_view_inline_value() {
+        let cli = CliArgs::parse(args(&["--view=archive"])).unwrap();
+        assert_eq!(cli.view, Some(View::ArchiveList));
+    }
+
+    #[test]
+    fn test_parse_view_invalid() {
+        assert!(CliArgs::parse(args(&["--view", "sideways"])).is_err());
+        assert!(CliArgs::parse(args(&["--view"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_read_only() {
+        let cli = CliArgs::parse(args(&["--read-only", "--view", "drafts"])).unwrap();
+        assert!(cli.read_only);
+        assert!(!cli.accessible);
+        assert_eq!(cli.view, Some(View::DraftList));
+
+        let cli = CliArgs::parse(args(&["--accessible", "--dry-run"])).unwrap();
+        assert!(cli.accessible);
+        assert!(cli.dry_run);
+        assert!(!cli.simulate_dispatch);
+
+        let cli = CliArgs::parse(args(&["--simulate-dispatch"])).unwrap();
+        assert!(cli.simulate_dispatch);
+    }
+
+    #[test]
+    fn test_parse_cheat_sheet() {
+        let cli = CliArgs::parse(args(&["--cheat-sheet", "keys.html"])).unwrap();
+        assert_eq!(cli.cheat_sheet.as_deref(), Some("keys.html"));
+
+        let cli = CliArgs::parse(args(&["--cheat-sheet"])).unwrap();
+        assert_eq!(cli.cheat_sheet.as_deref(), Some("-"));
+
+        let cli = CliArgs::parse(args(&["--cheat-sheet", "--read-only"])).unwrap();
+        assert_eq!(cli.cheat_sheet.as_deref(), Some("-"));
+        assert!(cli.read_only);
+    }
+
+    #[test]
+    fn test_parse_share() {
+        let cli = CliArgs::parse(args(&[
+            "--share",
+            "Trip plan",
+            "-o",
+            "trip.tar.gz",
+            "--passphrase-env=SHARE_PASS",
+        ]))
+        .unwrap();
+        assert_eq!(cli.share.as_deref(), Some("Trip plan"));
+        assert_eq!(cli.output.as_deref(), Some("trip.tar.gz"));
+        assert_eq!(cli.passphrase_env.as_deref(), Some("SHARE_PASS"));
+
+        assert!(CliArgs::parse(args(&["--share"])).is_err());
+        assert!(CliArgs::parse(args(&["--share", "x", "--passphrase-env", "P"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_import() {
+        let cli = CliArgs::parse(args(&["--import", "~/Notes Export"])).unwrap();
+        assert_eq!(cli.import.as_deref(), Some("~/Notes Export"));
+        assert!(CliArgs::parse(args(&["--import"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_capture() {
+        let cli = CliArgs::parse(args(&["capture", "--from-service"])).unwrap();
+        let capture = cli.capture.unwrap();
+        assert!(capture.from_service);
+        assert!(capture.text.is_empty());
+
+        let cli = CliArgs::parse(args(&["capture", "call", "--view"]));
+        assert!(cli.is_err());
+
+        let cli = CliArgs::parse(args(&["capture", "call", "mom"])).unwrap();
+        assert_eq!(cli.capture.unwrap().text, vec!["call", "mom"]);
+    }
+
+    #[test]
+    fn test_parse_script_commands() {
+        let cli = CliArgs::parse(args(&["list", "--archive", "--json"])).unwrap();
+        let script = cli.script.unwrap();
+        assert_eq!(script.command, ScriptCommand::List { archived: true });
+        assert_eq!(script.format, ScriptFormat::Json);
+
+        let cli = CliArgs::parse(args(&["search", "trip", "plan"])).unwrap();
+        let script = cli.script.unwrap();
+        let query = "trip plan".to_string();
+        assert_eq!(script.command, ScriptCommand::Search { query });
+        assert_eq!(script.format, ScriptFormat::Text);
+
+        let cli = CliArgs::parse(args(&["list", "--format", "alfred"])).unwrap();
+        assert_eq!(cli.script.unwrap().format, ScriptFormat::Alfred);
+        let cli = CliArgs::parse(args(&["doctor", "--format=json"])).unwrap();
+        assert_eq!(cli.script.unwrap().format, ScriptFormat::Json);
+        assert!(CliArgs::parse(args(&["doctor", "--format", "alfred"])).is_err());
+
+        let cli = CliArgs::parse(args(&["dispatch", "Inbox", "--dry-run"])).unwrap();
+        let note = "Inbox".to_string();
+        let dispatch = ScriptCommand::Dispatch {
+            note,
+            dry_run: true,
+        };
+        assert_eq!(cli.script.unwrap().command, dispatch);
+
+        assert!(CliArgs::parse(args(&["search"])).is_err());
+        assert!(CliArgs::parse(args(&["doctor", "now"])).is_err());
+        assert!(CliArgs::parse(args(&["list", "--dry-run"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_open() {
+        let cli = CliArgs::parse(args(&["--open", "3f2a"])).unwrap();
+        assert_eq!(cli.open.as_deref(), Some("3f2a"));
+        assert!(CliArgs::parse(args(&["--open"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let cli = CliArgs::parse(args(&["-V"])).unwrap();
+        assert!(cli.version);
+        assert_eq!(cli.view, None);
+    }
+}
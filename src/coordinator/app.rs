@@ -1,21 +1,79 @@
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use uuid::Uuid;
 
-use crate::atoms::storage::file_watcher::FileEvent;
+use crate::atoms::storage::file_watcher::{self, FileEvent, FileWatcherHandle};
 use crate::atoms::storage::{
-    delete_draft, ensure_config_dir, ensure_data_dirs, load_all_drafts, load_config, load_draft,
-    resolve_data_dir, save_draft,
+    FileNaming, SaveOutcome, SaveWorker, SyncOutcome, append_writing_log, config_path,
+    copy_to_assets, delete_draft, dictionary_dirs, ensure_config_dir, ensure_data_dirs,
+    expand_tilde, git_sync, list_snapshots, list_templates, load_all_drafts, load_config,
+    load_dictionary, load_draft, load_layout, load_marks, load_search_index, load_snapshot,
+    load_template, load_themes, load_trash, load_writing_history, note_id_for_path, purge_trash,
+    purge_trashed, read_config, resolve_data_dir, restore_from_trash, run_pdf_command,
+    save_config_value, save_draft, save_layout, save_marks, save_search_index, save_snapshot,
+    save_writing_history, scan_and_repair, templates_dir, themes_dir, trash_draft,
+    watch_system_appearance,
 };
-use crate::molecules::config::ThemeManager;
-use crate::molecules::distribution::{DispatchResult, dispatch_block, parse_smart_blocks};
-use crate::molecules::editor::{RenderSelection, TextBuffer, VimMode, VisualMode};
+use crate::molecules::capture::{
+    adjacent_daily, daily_content, daily_date, daily_title, fill_template,
+};
+use crate::molecules::config::{
+    CheatSheetFormat, ThemeManager, generate_cheat_sheet, scheduled_dark,
+};
+use crate::molecules::distribution::{
+    AppleScriptDispatcher, DispatchResult, Dispatcher, RecordingDispatcher, SimulatedDispatcher,
+    audio_block_path, check_off_reminders, fill_agenda_placeholder, format_agenda, lint_blocks,
+    parse_smart_blocks, replace_audio_path,
+};
+use crate::molecules::editor::{
+    AutoLink, AutoLinkKind, BufferList, COMPLETION_LIMIT, ChangeRecorder, CompletionKind,
+    Dictionary, EVEN_SPLIT, ExCommand, FoldRegion, FoldedLines, Folds, JumpList, LineRange,
+    Location, OutlineEntry, Pane, PaneDir, PaneLayout, PaneView, PromptInput, QuickfixList,
+    RenderCache, RenderSelection, ReplaceAllSession, SCROLL_OFF, SUGGESTION_LIMIT, SearchMatch,
+    SearchPattern, Snippet, SnippetSession, SplitDir, Substitute, SubstituteSession, Substitution,
+    TextBuffer, ViewAlign, Viewport, VimMode, VisualMode, auto_links, buffer_words, completion_at,
+    completion_matches, follow_line, frontmatter_end, link_at, mention_matches, merge_three_way,
+    parse_ex_command, replace_hunks, section_at, snippet_trigger, spelling_errors,
+    split_at_headings, word_at, word_spans,
+};
+use crate::molecules::import::import_into_drafts;
 use crate::molecules::list::{
-    ArchiveList, DraftList, FileChangeAction, FileChangeTracker, classify_event,
+    ArchiveList, Backlinks, DraftList, FileChangeAction, FileChangeTracker, NoteList,
+    RecentRemoval, RemovalKind, ReviewAction, ReviewSession, SearchIndex, SortOrder,
+    alias_conflict, classify_event, find_note_match, note_match_index, resolve_link, tag_counts,
 };
-use crate::types::{AppMode, Config, Note, ProcessingStatus, SmartBlock, Theme, View};
+use crate::molecules::share::{
+    ExportFormat, expand_transclusions, render_html, sanitize_for_sharing, share_file_stem,
+};
+use crate::molecules::stats::{WritingSession, count_words, summarize};
+use crate::types::{
+    AppMode, AppearanceMode, BlockType, Config, Frontmatter, LayoutState, Locale, Mark, MarkStore,
+    Note, NoteSnapshot, ProcessedStyle, ProcessingStatus, SmartBlock, SnapshotReason, StatsSummary,
+    Theme, View, WritingHistory, add_tag, as_tag, set_frontmatter_field, strip_frontmatter,
+    take_frontmatter_field,
+};
+
+/// How often the system dark mode setting is read.
+const APPEARANCE_POLL: Duration = Duration::from_secs(5);
+
+/// Percent of the space one `Ctrl-W +/-/</>` moves a pane border by.
+const PANE_RESIZE_STEP: i32 = 5;
+
+/// Minimum time between writes of `stats.toml` while editing.
+const STATS_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the UI loop wakes while in use.
+const TICK_RATE: Duration = Duration::from_millis(100);
+/// Time without input after which the UI loop may slow down.
+const IDLE_AFTER: Duration = Duration::from_secs(3);
 
 pub struct App {
     pub mode: AppMode,
@@ -23,34 +81,182 @@ pub struct App {
     pub config: Config,
     pub theme_manager: ThemeManager,
     pub vim_mode: VimMode,
+    /// Keys of the last change, for `.`.
+    pub change_recorder: ChangeRecorder,
 
     pub buffer: TextBuffer,
     pub current_note: Option<Note>,
+    /// Notes opened this session; all but the current one keep their text
+    /// parked here.
+    pub buffers: BufferList,
+    /// Selected row while the buffer picker is open.
+    pub buffer_picker: Option<usize>,
+    /// Notes linking to the current one and the selected row, while the
+    /// backlinks panel is open.
+    pub backlink_picker: Option<(Vec<Note>, usize)>,
+    /// Tags of the drafts with their note counts, and the selected row,
+    /// while the tag browser is open.
+    pub tag_picker: Option<(Vec<(String, usize)>, usize)>,
+    /// Formats offered by `Space e`, and the selected row.
+    pub export_picker: Option<(Vec<ExportFormat>, usize)>,
+    /// Templates offered by `n` / `Space n` (`None` for a blank note), and
+    /// the selected row.
+    pub template_picker: Option<(Vec<Option<String>>, usize)>,
+    /// Snapshots of the current note offered by `:history`, newest first,
+    /// and the selected row.
+    pub snapshot_picker: Option<(Vec<NoteSnapshot>, usize)>,
+    /// Spellings offered by `z=` for the word under the cursor, and the
+    /// selected row.
+    pub spell_picker: Option<(Vec<String>, usize)>,
+    /// Locations listed by the last command that produces them
+    /// (`:lint-blocks`), kept for `]q` / `[q` after the panel is hidden.
+    pub quickfix: Option<QuickfixList>,
+    /// Whether the locations panel is shown under the editor.
+    pub show_quickfix: bool,
+    /// Whether the locations panel has the keyboard.
+    pub quickfix_focused: bool,
+    /// The `:review` under way.
+    pub review: Option<ReviewSession>,
+    /// New title being typed while the rename prompt is open.
+    pub rename_title: Option<String>,
+    pub rename_input: PromptInput,
+    /// Id of the note the rename prompt is for.
+    rename_id: String,
+    /// Address book names for `@mention` completion, read on the first `@`.
+    contacts: Option<Vec<String>>,
+    /// Selected row of the Insert mode completion popup.
+    pub completion_selected: usize,
+    /// Whether Ctrl-N asked to complete the word before the cursor.
+    pub word_completion: bool,
+    /// Tabstops left from the last snippet expanded in Insert mode.
+    pub snippet: Option<SnippetSession>,
+    /// Whether the outline sidebar is shown next to the editor.
+    pub show_outline: bool,
+    /// Selected heading while the outline sidebar has the keyboard.
+    pub outline_selection: Option<usize>,
     pub draft_list: DraftList,
+    /// Empty until the archive list is first opened.
     pub archive_list: ArchiveList,
+    /// Read from `trash/` each time the trash view is opened.
+    pub trash_list: NoteList,
+    /// Trigram index the list filter uses; kept up to date on saves and
+    /// file events and saved as `search_index.toml` on exit.
+    search_index: SearchIndex,
+    /// `[[link]]` targets of every note, brought up to date on saves and
+    /// whenever the backlinks panel opens.
+    backlinks: Backlinks,
+    archives_loaded: bool,
 
     pub command_message: String,
     pub search_query: String,
+    /// Cursor and history of the `/` prompt.
+    pub search_input: PromptInput,
+    /// List selection when `/` was opened, restored if the search is cancelled.
+    pub list_search_origin: usize,
+    /// Text of the `f` prompt; applied to the active list as it is typed.
+    pub filter_query: String,
+    pub filter_input: PromptInput,
+    /// Text typed after `:` in Command mode.
+    pub command_line: String,
 
     pub processing_blocks: Vec<SmartBlock>,
     pub processing_index: usize,
+    /// Sends smart blocks to their destinations (AppleScript by default).
+    pub dispatcher: Box<dyn Dispatcher>,
+    /// `--dry-run`: blocks are recorded instead of sent and the note is
+    /// left as it was.
+    pub dry_run: bool,
 
     pub show_hints: bool,
+    /// `general.accessibility` or `--accessible`.
+    pub accessible: bool,
+    /// `general.language`; messages are stored in English and translated
+    /// when drawn.
+    pub locale: Locale,
 
     pub should_quit: bool,
     pub dirty: bool,
+    pub read_only: bool,
     pub external_editor_requested: bool,
     pub last_save: std::time::Instant,
+    save_worker: SaveWorker,
 
     pub visual_mode: Option<VisualMode>,
     pub block_insert_positions: Vec<(usize, usize)>,
     pub last_yank_linewise: bool,
     pub visual_target_display_col: Option<usize>,
+    /// Rows of the last Visual selection, for `:'<,'>`.
+    pub last_visual_rows: Option<(usize, usize)>,
+    /// The `:s///c` run waiting for an answer.
+    pub substitution: Option<SubstituteSession>,
+    /// The `:replace-all` run waiting for an answer.
+    pub replace_all: Option<ReplaceAllSession>,
+    /// `m{a-z}` marks of all notes, saved in `marks.toml`.
+    pub marks: MarkStore,
+    pub jump_list: JumpList,
+    /// What each character typed in Replace mode overtyped, for Backspace.
+    pub replace_originals: Vec<Option<String>>,
 
     pub data_dir: PathBuf,
+    /// `config.toml`, where list sort changes are written back and which is
+    /// watched for edits; `None` when built with `with_config`, until set.
+    pub config_file: Option<PathBuf>,
+    /// Where user themes are read from; `None` when built with
+    /// `with_config`, until set.
+    pub themes_dir: Option<PathBuf>,
+    /// Where note templates are read from; `None` when built with
+    /// `with_config`, until set.
+    pub templates_dir: Option<PathBuf>,
+    /// Where hunspell dictionaries are looked for; empty when built with
+    /// `with_config`, until set.
+    pub dictionary_dirs: Vec<PathBuf>,
+    /// Dictionaries read so far by language, `None` where none was found.
+    dictionaries: RefCell<HashMap<String, Option<Rc<Dictionary>>>>,
     pub file_change_tracker: FileChangeTracker,
+    watcher: Option<FileWatcherHandle>,
+    /// Watches `config_file` for edits to reload.
+    config_watcher: Option<FileWatcherHandle>,
     pub pending_external_reload: Option<String>,
     pub pending_delete_title: Option<String>,
+    /// The last delete or archive in a list, undoable with `u` for a while.
+    pub recent_removal: Option<RecentRemoval>,
+    /// The git sync running in the background, if any.
+    sync_job: Option<mpsc::Receiver<Result<SyncOutcome>>>,
+    /// When the last sync finished, or the app started, for timed syncs.
+    last_sync: std::time::Instant,
+    /// System dark mode readings, with `[appearance] auto = "system"`.
+    appearance_watch: Option<mpsc::Receiver<bool>>,
+    /// Whether `[appearance]` last applied its dark theme (`None` before
+    /// it applied either).
+    dark_appearance: Option<bool>,
+
+    /// Scroll position of the editor, kept between frames.
+    pub viewport: Viewport,
+    /// Editor panes; the focused one shows `buffer` and `viewport`.
+    pub panes: PaneLayout,
+    /// Pane proportions per view, saved in `layout.toml`.
+    pub layout: LayoutState,
+    /// Closed folds of each note opened this session, by note id.
+    folds: HashMap<String, Folds>,
+    /// Search matches and word goal of the text last drawn.
+    render_cache: RenderCache,
+    /// Whether something shown changed outside key handling since the last
+    /// frame; see `take_redraw`.
+    redraw: bool,
+    /// `general.soft_wrap`, toggled with `:set wrap` / `:set nowrap`.
+    pub soft_wrap: bool,
+    /// `general.search_ignore_diacritics`, toggled with
+    /// `:set ignorediacritics` / `:set noignorediacritics`.
+    pub ignore_diacritics: bool,
+    /// `general.spell`, toggled with `:set spell` / `:set nospell`.
+    pub spell: bool,
+
+    pub writing_history: WritingHistory,
+    pub writing_session: WritingSession,
+    /// `writing_history` has changes not yet written to `stats.toml`.
+    stats_dirty: bool,
+    last_stats_save: std::time::Instant,
+    pub show_stats: bool,
 }
 
 impl App {
@@ -59,74 +265,473 @@ impl App {
 
         let config = load_config()?;
         let data_dir = resolve_data_dir(config.general.data_dir.as_deref());
+        let mut app = Self::with_config(config, data_dir)?;
+        app.config_file = Some(config_path());
+        app.templates_dir = Some(templates_dir());
+        app.dictionary_dirs = dictionary_dirs();
+        app.themes_dir = Some(themes_dir());
+        app.load_user_themes();
+        app.poll_appearance();
+        Ok(app)
+    }
+
+    /// Build the app from `config` on `data_dir`, without reading or
+    /// creating `config.toml`.
+    pub fn with_config(config: Config, data_dir: PathBuf) -> Result<Self> {
         ensure_data_dirs(&data_dir)?;
 
-        let theme_manager = ThemeManager::with_theme(&config.general.theme);
+        let mut theme_manager = ThemeManager::with_theme(&config.general.theme);
+        theme_manager.apply_overrides(&config.colors);
 
         let vim_mode = VimMode::with_config(config.keyboard.clone());
 
-        let drafts = load_all_drafts(&data_dir, false)?;
-        let archives = load_all_drafts(&data_dir, true)?;
+        let writing_history = load_writing_history(&data_dir).unwrap_or_default();
+        let writing_session = WritingSession::new(chrono::Local::now(), &writing_history);
+        let marks = load_marks(&data_dir).unwrap_or_default();
+        let layout = load_layout(&data_dir).unwrap_or_default();
 
-        let draft_list = DraftList::new(drafts);
-        let archive_list = ArchiveList::new(archives);
+        let integrity_message = Self::repair_data_dir(&data_dir);
+        purge_trash(&data_dir, config.general.trash_days).unwrap_or_default();
+        let drafts = load_all_drafts(&data_dir, false)?;
+        let mut draft_list = DraftList::new(drafts);
+        draft_list
+            .set_sort_order(SortOrder::from_name(&config.general.list_sort).unwrap_or_default());
+        let mut search_index =
+            SearchIndex::from_snapshot(load_search_index(&data_dir).unwrap_or_default());
+        search_index.sync(draft_list.notes());
 
         let (buffer, current_note) = if let Some(note) = draft_list.selected_note() {
             (TextBuffer::from_string(&note.content), Some(note.clone()))
         } else {
             (TextBuffer::new(), None)
         };
+        let mut buffers = BufferList::new();
+        if let Some(note) = &current_note {
+            buffers.activate(note);
+        }
 
         let show_hints = config.general.show_hints;
+        let accessible = config.general.accessibility;
+        let locale = Locale::from_name(&config.general.language).unwrap_or_default();
+        let view = View::from_name(&config.general.default_view).unwrap_or_default();
+        let soft_wrap = config.general.soft_wrap;
+        let ignore_diacritics = config.general.search_ignore_diacritics;
+        let spell = config.general.spell;
 
-        Ok(Self {
+        let mut app = Self {
             mode: AppMode::Normal,
-            view: View::Editor,
+            view,
             config,
             theme_manager,
             vim_mode,
+            change_recorder: ChangeRecorder::new(),
             buffer,
             current_note,
+            buffers,
+            buffer_picker: None,
+            backlink_picker: None,
+            tag_picker: None,
+            export_picker: None,
+            template_picker: None,
+            snapshot_picker: None,
+            spell_picker: None,
+            quickfix: None,
+            show_quickfix: false,
+            quickfix_focused: false,
+            review: None,
+            rename_title: None,
+            rename_input: PromptInput::new(),
+            rename_id: String::new(),
+            contacts: None,
+            completion_selected: 0,
+            word_completion: false,
+            snippet: None,
+            show_outline: false,
+            outline_selection: None,
             draft_list,
-            archive_list,
-            command_message: String::new(),
+            archive_list: ArchiveList::default(),
+            trash_list: NoteList::default(),
+            search_index,
+            backlinks: Backlinks::new(),
+            archives_loaded: false,
+            command_message: integrity_message.unwrap_or_default(),
             search_query: String::new(),
+            search_input: PromptInput::new(),
+            list_search_origin: 0,
+            filter_query: String::new(),
+            filter_input: PromptInput::new(),
+            command_line: String::new(),
             processing_blocks: Vec::new(),
             processing_index: 0,
+            dispatcher: Box::new(AppleScriptDispatcher),
+            dry_run: false,
             show_hints,
+            accessible,
+            locale,
             should_quit: false,
             dirty: false,
+            read_only: false,
             external_editor_requested: false,
             last_save: std::time::Instant::now(),
+            save_worker: SaveWorker::spawn(),
             visual_mode: None,
             block_insert_positions: Vec::new(),
             last_yank_linewise: false,
             visual_target_display_col: None,
+            last_visual_rows: None,
+            substitution: None,
+            replace_all: None,
+            marks,
+            jump_list: JumpList::new(),
+            replace_originals: Vec::new(),
             data_dir,
+            config_file: None,
+            themes_dir: None,
+            templates_dir: None,
+            dictionary_dirs: Vec::new(),
+            dictionaries: RefCell::new(HashMap::new()),
             file_change_tracker: FileChangeTracker::new(),
+            watcher: None,
+            config_watcher: None,
             pending_external_reload: None,
             pending_delete_title: None,
-        })
+            recent_removal: None,
+            sync_job: None,
+            appearance_watch: None,
+            dark_appearance: None,
+            last_sync: std::time::Instant::now(),
+            viewport: Viewport::new(),
+            panes: PaneLayout::new(),
+            layout,
+            folds: HashMap::new(),
+            render_cache: RenderCache::new(),
+            redraw: true,
+            soft_wrap,
+            ignore_diacritics,
+            spell,
+            writing_history,
+            writing_session,
+            stats_dirty: false,
+            last_stats_save: std::time::Instant::now(),
+            show_stats: false,
+        };
+        if app.view == View::ArchiveList {
+            app.load_archives()?;
+        }
+        Ok(app)
     }
 
     pub fn theme(&self) -> &Theme {
         self.theme_manager.current()
     }
 
+    pub fn file_naming(&self) -> FileNaming {
+        FileNaming::from_name(&self.config.general.file_naming).unwrap_or_default()
+    }
+
     pub fn set_mode(&mut self, mode: AppMode) {
         self.mode = mode;
     }
 
+    /// Switch views, reading the archive from disk the first time it is
+    /// opened.
     pub fn set_view(&mut self, view: View) {
+        if view == View::ArchiveList
+            && !self.archives_loaded
+            && let Err(e) = self.load_archives()
+        {
+            self.set_message(&format!("Loading archives failed: {}", e));
+        }
+        if view == View::Trash
+            && let Err(e) = self.load_trash()
+        {
+            self.set_message(&format!("Loading trash failed: {}", e));
+            return;
+        }
         self.view = view;
     }
 
+    /// Read the trash afresh, first purging notes past `trash_days`.
+    fn load_trash(&mut self) -> Result<()> {
+        self.flush_saves()?;
+        purge_trash(&self.data_dir, self.config.general.trash_days)?;
+        self.trash_list = NoteList::new(load_trash(&self.data_dir)?);
+        self.trash_list.set_sort_order(self.list_sort());
+        Ok(())
+    }
+
+    pub fn archives_loaded(&self) -> bool {
+        self.archives_loaded
+    }
+
+    fn load_archives(&mut self) -> Result<()> {
+        let archives = load_all_drafts(&self.data_dir, true)?;
+        self.archive_list = ArchiveList::new(archives);
+        self.archive_list.set_sort_order(self.list_sort());
+        self.archives_loaded = true;
+        self.file_change_tracker
+            .record_notes(self.archive_list.notes().iter());
+        self.sync_search_index();
+        Ok(())
+    }
+
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.search_index
+    }
+
+    /// Index notes that changed since they were last indexed. Once the
+    /// archive is loaded every note is known, so entries of notes deleted
+    /// while the app was closed are dropped too.
+    fn sync_search_index(&mut self) {
+        let notes = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes());
+        let mut changed = self.search_index.sync(notes) > 0;
+        if self.archives_loaded {
+            let known: HashSet<&str> = self
+                .draft_list
+                .notes()
+                .iter()
+                .chain(self.archive_list.notes())
+                .map(|n| n.id.as_str())
+                .collect();
+            changed |= self.search_index.retain(|id| known.contains(id)) > 0;
+        }
+        if changed {
+            self.refilter_lists();
+        }
+    }
+
+    /// Re-apply the list filters after the search index changed.
+    fn refilter_lists(&mut self) {
+        for list in [&mut self.draft_list, &mut self.archive_list] {
+            if !list.search_query().is_empty() {
+                let query = list.search_query().to_string();
+                filter_with_index(&self.search_index, list, query);
+            }
+        }
+    }
+
+    /// Write the search index if it changed since it was loaded or saved.
+    pub fn save_search_index(&mut self) -> Result<()> {
+        if self.search_index.is_changed() {
+            save_search_index(&self.data_dir, &self.search_index.snapshot())?;
+            self.search_index.mark_saved();
+        }
+        Ok(())
+    }
+
+    /// `:reindex`: build the search index again from the note files.
+    pub fn rebuild_search_index(&mut self) -> Result<()> {
+        self.flush_saves()?;
+        let mut notes = load_all_drafts(&self.data_dir, false)?;
+        notes.extend(load_all_drafts(&self.data_dir, true)?);
+        self.search_index = SearchIndex::build(&notes);
+        self.save_search_index()?;
+        self.refilter_lists();
+        self.set_message(&format!(
+            "Search index rebuilt: {} notes",
+            self.search_index.len()
+        ));
+        Ok(())
+    }
+
+    /// Re-read the archive if it has been loaded; otherwise it is read
+    /// fresh when first opened anyway.
+    fn reload_archives(&mut self) -> Result<()> {
+        if self.archives_loaded {
+            self.archive_list
+                .update_notes(load_all_drafts(&self.data_dir, true)?);
+        }
+        Ok(())
+    }
+
     pub fn set_message(&mut self, msg: &str) {
         self.command_message = msg.to_string();
+        self.redraw = true;
     }
 
     pub fn clear_message(&mut self) {
-        self.command_message.clear();
+        if !self.command_message.is_empty() {
+            self.command_message.clear();
+            self.redraw = true;
+        }
+    }
+
+    /// How long the UI loop may block waiting for input, `since_input` after
+    /// the last key. `TICK_RATE` while in use; once idle for `IDLE_AFTER`
+    /// with nothing waiting on a timer (block processing, auto-save, queued
+    /// writes, the undo window of a delete), `general.idle_poll_ms`, so an
+    /// open but unused kenotex hardly wakes the CPU. File changes then show
+    /// up within that time instead of at once.
+    pub fn poll_timeout(&self, since_input: Duration) -> Duration {
+        let idle_poll = Duration::from_millis(self.config.general.idle_poll_ms);
+        let busy = self.mode == AppMode::Processing
+            || self.dirty
+            || self.save_worker.is_busy()
+            || self.recent_removal.is_some()
+            || self.sync_job.is_some();
+        if busy || since_input < IDLE_AFTER || idle_poll <= TICK_RATE {
+            TICK_RATE
+        } else {
+            idle_poll
+        }
+    }
+
+    /// Whether a frame has to be drawn for changes made by background work
+    /// (messages, finished saves, file events) since the last call. The UI
+    /// loop redraws after every input anyway.
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.redraw)
+    }
+
+    /// Add the themes in `themes_dir` to the built-in ones and select
+    /// `general.theme` again, since it may name one of them. Files that
+    /// fail to load are reported in the status bar.
+    pub fn load_user_themes(&mut self) {
+        let Some(dir) = self.themes_dir.clone() else {
+            return;
+        };
+        let themes = match load_themes(&dir) {
+            Ok(themes) => themes,
+            Err(e) => {
+                self.set_message(&format!("Themes failed: {}", e));
+                return;
+            }
+        };
+        let mut loaded = Vec::new();
+        for theme in themes {
+            match theme {
+                Ok(theme) => loaded.push(theme),
+                Err(e) => self.set_message(&format!("Theme skipped: {:#}", e)),
+            }
+        }
+        self.theme_manager.add_themes(loaded);
+        if !self.theme_manager.set_theme(&self.config.general.theme) {
+            let name = self.config.general.theme.clone();
+            self.set_message(&format!("Unknown theme: {}", name));
+        }
+        self.theme_manager.apply_overrides(&self.config.colors);
+    }
+
+    /// Read `config_file` again and apply it: keybindings, theme and colors,
+    /// language, the `general` display settings that changed, and
+    /// everything read as it is used (auto-save interval, destinations,
+    /// sync). `data_dir` keeps its startup value. A file that fails to
+    /// parse leaves the settings as they were.
+    pub fn reload_config(&mut self) {
+        let Some(path) = &self.config_file else {
+            return;
+        };
+        let config = match read_config(path) {
+            Ok(config) => config,
+            Err(e) => {
+                self.set_message(&format!("Config not reloaded: {:#}", e));
+                return;
+            }
+        };
+        if same(&config, &self.config) {
+            return;
+        }
+        let old = std::mem::replace(&mut self.config, config);
+        self.redraw = true;
+        self.set_message("Config reloaded");
+
+        if !same(&old.keyboard, &self.config.keyboard) {
+            self.vim_mode = VimMode::with_config(self.config.keyboard.clone());
+        }
+        let general = &self.config.general;
+        if old.general.language != general.language {
+            self.locale = Locale::from_name(&general.language).unwrap_or_default();
+        }
+        if old.general.show_hints != general.show_hints {
+            self.show_hints = general.show_hints;
+        }
+        if old.general.soft_wrap != general.soft_wrap {
+            self.soft_wrap = general.soft_wrap;
+        }
+        if old.general.search_ignore_diacritics != general.search_ignore_diacritics {
+            self.ignore_diacritics = general.search_ignore_diacritics;
+        }
+        if old.general.spell != general.spell {
+            self.spell = general.spell;
+        }
+        if old.general.list_sort != general.list_sort {
+            let order = self.list_sort();
+            self.draft_list.set_sort_order(order);
+            self.archive_list.set_sort_order(order);
+        }
+        if old.general.file_watch != general.file_watch
+            || old.general.file_watch_debounce_ms != general.file_watch_debounce_ms
+        {
+            self.stop_file_watcher();
+            self.start_file_watcher();
+        }
+
+        let theme_changed = old.general.theme != self.config.general.theme
+            || !same(&old.colors, &self.config.colors);
+        if theme_changed || !same(&old.appearance, &self.config.appearance) {
+            if theme_changed {
+                self.theme_manager = ThemeManager::with_theme(&self.config.general.theme);
+                self.theme_manager.apply_overrides(&self.config.colors);
+                self.load_user_themes();
+            }
+            self.appearance_watch = None;
+            self.dark_appearance = None;
+            self.start_appearance_watcher();
+            self.poll_appearance();
+        }
+    }
+
+    /// With `[appearance] auto = "system"`, start watching the system's
+    /// dark mode setting.
+    pub fn start_appearance_watcher(&mut self) {
+        if self.config.appearance.auto == AppearanceMode::System {
+            self.appearance_watch = Some(watch_system_appearance(APPEARANCE_POLL));
+        }
+    }
+
+    /// Switch to the `[appearance]` light or dark theme when the system
+    /// setting or the schedule has changed sides.
+    pub fn poll_appearance(&mut self) {
+        let dark = match self.config.appearance.auto {
+            AppearanceMode::Off => None,
+            AppearanceMode::System => self
+                .appearance_watch
+                .as_ref()
+                .and_then(|watch| watch.try_iter().last()),
+            AppearanceMode::Schedule => {
+                let appearance = &self.config.appearance;
+                let now = chrono::Local::now().time();
+                scheduled_dark(now, &appearance.light_at, &appearance.dark_at)
+            }
+        };
+        if let Some(dark) = dark {
+            self.set_appearance(dark);
+        }
+    }
+
+    /// Apply the `[appearance]` dark or light theme, unless that side is
+    /// applied already (so a theme picked with `T` stays until the next
+    /// change).
+    pub fn set_appearance(&mut self, dark: bool) {
+        if self.dark_appearance == Some(dark) {
+            return;
+        }
+        self.dark_appearance = Some(dark);
+        let appearance = &self.config.appearance;
+        let name = if dark {
+            appearance.dark.clone()
+        } else {
+            appearance.light.clone()
+        };
+        if self.theme_manager.set_theme(&name) {
+            self.redraw = true;
+        } else {
+            self.set_message(&format!("Unknown theme: {}", name));
+        }
     }
 
     pub fn cycle_theme(&mut self) {
@@ -144,13 +749,79 @@ impl App {
         self.set_message(msg);
     }
 
+    /// Start a new note: pick a template first when there are any, else
+    /// open a blank one.
     pub fn new_note(&mut self) {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let templates = match &self.templates_dir {
+            Some(dir) => list_templates(dir).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        if templates.is_empty() {
+            self.new_blank_note();
+            return;
+        }
+        let mut choices = vec![None];
+        choices.extend(templates.into_iter().map(Some));
+        self.template_picker = Some((choices, 0));
+    }
+
+    pub fn move_template_picker(&mut self, down: bool) {
+        if let Some((choices, row)) = &mut self.template_picker {
+            *row = if down {
+                (*row + 1).min(choices.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the template picker; with `accept`, create the note from the
+    /// selected template (or a blank one).
+    pub fn close_template_picker(&mut self, accept: bool) {
+        if let Some((choices, row)) = self.template_picker.take()
+            && accept
+            && let Some(choice) = choices.get(row)
+        {
+            match choice {
+                Some(name) => self.new_note_from_template(name, None),
+                None => self.new_blank_note(),
+            }
+        }
+    }
+
+    /// Create a note from the template `name`, with `{{date}}`, `{{time}}`
+    /// and `{{title}}` (`title`, else "Untitled") filled in.
+    pub fn new_note_from_template(&mut self, name: &str, title: Option<&str>) {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let template = match &self.templates_dir {
+            Some(dir) => load_template(dir, name),
+            None => Ok(None),
+        };
+        match template {
+            Ok(Some(template)) => {
+                let now = chrono::Local::now().naive_local();
+                let content = fill_template(&template, title.unwrap_or("Untitled"), now);
+                self.new_note_with_content(&content);
+                self.set_message(&format!("New note from '{}'", name));
+            }
+            Ok(None) => self.set_message(&format!("No template named '{}'", name)),
+            Err(e) => self.set_message(&format!("Template failed: {}", e)),
+        }
+    }
+
+    pub fn new_blank_note(&mut self) {
         let id = Uuid::new_v4().to_string();
         let note = Note::new(id, "Untitled".to_string(), String::new());
 
-        self.buffer = TextBuffer::new();
-        self.current_note = Some(note.clone());
-        self.draft_list.add_note(note);
+        self.draft_list.add_note(note.clone());
+        self.switch_buffer(note);
         self.dirty = true;
 
         self.set_view(View::Editor);
@@ -158,150 +829,1738 @@ impl App {
         self.set_message("New note created");
     }
 
+    /// Create a new draft pre-filled with `content` and open it in Normal mode.
+    pub fn new_note_with_content(&mut self, content: &str) {
+        let id = Uuid::new_v4().to_string();
+        let note = Note::new(id, Note::extract_title(content), content.to_string());
+
+        self.draft_list.add_note(note.clone());
+        self.switch_buffer(note);
+        self.dirty = true;
+
+        self.set_view(View::Editor);
+        self.set_mode(AppMode::Normal);
+    }
+
+    pub fn open_cheat_sheet(&mut self) {
+        let sheet = generate_cheat_sheet(&self.config.keyboard, CheatSheetFormat::Markdown);
+        self.new_note_with_content(&sheet);
+        self.set_message("Keymap cheat sheet created");
+    }
+
+    /// Queue the current note on the save worker. Write failures are
+    /// reported when the worker finishes (see `poll_saves`).
     pub fn save_current_note(&mut self) -> Result<()> {
-        if let Some(ref mut note) = self.current_note {
-            note.update_content(self.buffer.to_string());
-            self.file_change_tracker.record_save(&note.id);
-            save_draft(&self.data_dir, note)?;
-            self.draft_list.update_note(note);
-            self.dirty = false;
-            self.last_save = std::time::Instant::now();
-            self.set_message("Saved");
+        if let Some(note) = self.commit_buffer_to_note()? {
+            let naming = self.file_naming();
+            self.save_worker.submit(self.data_dir.clone(), note, naming);
+            self.set_message("Saving...");
         }
         Ok(())
     }
 
-    pub fn auto_save_if_needed(&mut self) -> Result<()> {
-        if self.dirty
-            && self.last_save.elapsed().as_millis()
-                >= self.config.general.auto_save_interval_ms as u128
-        {
-            self.save_current_note()?;
+    /// Copy the buffer into the current note and record writing stats.
+    /// Returns the note to write, or `None` when there is nothing to save.
+    fn commit_buffer_to_note(&mut self) -> Result<Option<Note>> {
+        if self.read_only {
+            return Ok(None);
         }
-        Ok(())
+        let Some(ref mut note) = self.current_note else {
+            return Ok(None);
+        };
+        let before = count_words(strip_frontmatter(&note.content));
+        note.update_content(self.buffer.to_string());
+        let after = count_words(strip_frontmatter(&note.content));
+        if before != after {
+            self.writing_session.record(&note.id, before, after);
+            self.writing_session
+                .apply_to(&mut self.writing_history, chrono::Local::now());
+            self.stats_dirty = true;
+        }
+        self.file_change_tracker.record_save(&note.id);
+        self.file_change_tracker
+            .record_content(&note.id, &note.content);
+        self.draft_list.update_note(note);
+        let indexed = self.search_index.update(note);
+        self.backlinks.update(note);
+        let note = note.clone();
+        if indexed {
+            self.refilter_lists();
+        }
+        self.dirty = false;
+        self.last_save = std::time::Instant::now();
+        Ok(Some(note))
     }
 
-    pub fn open_selected_note(&mut self) {
-        match self.view {
-            View::DraftList => {
-                if let Some(note) = self.draft_list.selected_note() {
-                    self.buffer = TextBuffer::from_string(&note.content);
-                    self.current_note = Some(note.clone());
-                    self.set_view(View::Editor);
-                    self.set_mode(AppMode::Normal);
-                }
-            }
-            View::ArchiveList => {
-                if let Some(note) = self.archive_list.selected_note() {
-                    self.buffer = TextBuffer::from_string(&note.content);
-                    self.current_note = Some(note.clone());
-                    self.set_view(View::Editor);
-                    self.set_mode(AppMode::Normal);
-                }
-            }
-            _ => {}
+    pub fn auto_save_if_needed(&mut self) -> Result<()> {
+        self.poll_saves();
+
+        let general = &self.config.general;
+        if !self.dirty || general.auto_save_interval_ms == 0 {
+            return Ok(());
+        }
+        // Large notes save less often so rewrites don't pile up on slow disks
+        let interval = if self.buffer.byte_len() as u64 >= general.large_note_threshold_kb * 1024 {
+            general.large_note_auto_save_interval_ms
+        } else {
+            general.auto_save_interval_ms
+        };
+        // Wait for the previous write rather than queueing another behind it
+        if self.last_save.elapsed().as_millis() < interval as u128 || self.save_worker.is_busy() {
+            return Ok(());
         }
+        self.save_current_note()
     }
 
-    pub fn archive_selected_note(&mut self) -> Result<()> {
-        if self.view == View::DraftList
-            && let Some(mut note) = self.draft_list.remove_selected() {
-                note.is_archived = true;
-                let old_id = note.id.clone();
-                delete_draft(&self.data_dir, &old_id, false)?;
-                save_draft(&self.data_dir, &note)?;
+    /// Handle writes the save worker has finished, without blocking.
+    pub fn poll_saves(&mut self) {
+        let outcomes = self.save_worker.poll();
+        if !outcomes.is_empty() {
+            self.redraw = true;
+        }
+        let _ = self.apply_save_outcomes(outcomes);
 
-                let archives = load_all_drafts(&self.data_dir, true)?;
-                self.archive_list.update_notes(archives);
+        if self.stats_dirty
+            && self.last_stats_save.elapsed() >= STATS_SAVE_INTERVAL
+            && let Err(e) = self.flush_writing_stats()
+        {
+            self.set_message(&format!("Writing stats not saved: {}", e));
+        }
+    }
 
-                self.set_message("Note archived");
-            }
+    /// Write pending writing stats to `stats.toml`. Stats are secondary to
+    /// notes, so callers report a failure rather than stop on it.
+    pub fn flush_writing_stats(&mut self) -> Result<()> {
+        if !self.stats_dirty {
+            return Ok(());
+        }
+        self.last_stats_save = std::time::Instant::now();
+        save_writing_history(&self.data_dir, &self.writing_history)?;
+        self.stats_dirty = false;
         Ok(())
     }
 
-    pub fn restore_selected_note(&mut self) -> Result<()> {
-        if self.view == View::ArchiveList
-            && let Some(mut note) = self.archive_list.remove_selected() {
-                note.is_archived = false;
-                let old_id = note.id.clone();
-                delete_draft(&self.data_dir, &old_id, true)?;
-                save_draft(&self.data_dir, &note)?;
-
-                let drafts = load_all_drafts(&self.data_dir, false)?;
-                self.draft_list.update_notes(drafts);
+    /// An alias of the current note that another loaded note also claims,
+    /// with that note's title.
+    fn alias_conflict(&self) -> Option<(String, String)> {
+        let note = self.current_note.as_ref()?;
+        let others = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes());
+        alias_conflict(note, others).map(|(alias, other)| (alias, other.title.clone()))
+    }
 
-                self.set_message("Note restored");
-            }
-        Ok(())
+    /// Wait for every queued write, e.g. before quitting or touching note
+    /// files directly. Returns the first write error.
+    pub fn flush_saves(&mut self) -> Result<()> {
+        let outcomes = self.save_worker.flush();
+        self.apply_save_outcomes(outcomes)
     }
 
-    pub fn delete_selected_note(&mut self) -> Result<()> {
-        match self.view {
-            View::DraftList => {
-                if let Some(note) = self.draft_list.remove_selected() {
-                    delete_draft(&self.data_dir, &note.id, false)?;
-                    self.set_message("Note deleted");
-                }
-            }
-            View::ArchiveList => {
-                if let Some(note) = self.archive_list.remove_selected() {
-                    delete_draft(&self.data_dir, &note.id, true)?;
-                    self.set_message("Note deleted");
+    fn apply_save_outcomes(&mut self, outcomes: Vec<SaveOutcome>) -> Result<()> {
+        let mut first_error = None;
+        for outcome in outcomes {
+            // The write may land well after it was queued; keep the watcher quiet
+            self.file_change_tracker.record_save(&outcome.id);
+            let is_current = self
+                .current_note
+                .as_ref()
+                .is_some_and(|n| n.id == outcome.id);
+            match outcome.result {
+                Ok(()) if is_current => match self.alias_conflict() {
+                    Some((alias, title)) => self.set_message(&format!(
+                        "Saved; alias '{}' is also claimed by '{}'",
+                        alias, title
+                    )),
+                    None => self.set_message("Saved"),
+                },
+                Ok(()) => {}
+                Err(e) => {
+                    self.set_message(&format!("Save failed: {}", e));
+                    if is_current {
+                        // Keep the note dirty so auto-save retries
+                        self.dirty = true;
+                    }
+                    first_error.get_or_insert(e);
                 }
             }
-            _ => {}
         }
-        Ok(())
+        first_error.map_or(Ok(()), Err)
     }
 
-    pub fn request_delete(&mut self) {
-        let title = match self.view {
-            View::DraftList => self.draft_list.selected_note().map(|n| n.title.clone()),
-            View::ArchiveList => self.archive_list.selected_note().map(|n| n.title.clone()),
-            _ => None,
-        };
-        if let Some(title) = title {
-            self.pending_delete_title = Some(title);
-            self.set_mode(AppMode::ConfirmDelete);
+    /// The list shown in the current list view (drafts in the editor).
+    pub fn active_list(&self) -> &NoteList {
+        match self.view {
+            View::ArchiveList => &self.archive_list,
+            View::Trash => &self.trash_list,
+            _ => &self.draft_list,
         }
     }
 
-    pub fn confirm_delete(&mut self) -> Result<()> {
-        self.delete_selected_note()?;
-        self.pending_delete_title = None;
-        self.set_mode(AppMode::Normal);
-        Ok(())
+    pub fn active_list_mut(&mut self) -> &mut NoteList {
+        match self.view {
+            View::ArchiveList => &mut self.archive_list,
+            View::Trash => &mut self.trash_list,
+            _ => &mut self.draft_list,
+        }
     }
 
-    pub fn cancel_delete(&mut self) {
-        self.pending_delete_title = None;
-        self.set_mode(AppMode::Normal);
-        self.clear_message();
+    pub fn open_selected_note(&mut self) {
+        if self.view == View::Editor {
+            return;
+        }
+        if self.view == View::Trash {
+            self.set_message("Restore the note with r to open it");
+            return;
+        }
+        if let Some(note) = self.active_list().selected_note().cloned() {
+            // Opening from the list reads the note afresh, cursor at the top;
+            // only buffer switches restore a parked buffer
+            if self.current_note.as_ref().is_some_and(|n| n.id == note.id) {
+                self.buffer = TextBuffer::from_string(&note.content);
+                self.current_note = Some(note);
+                self.hide_frontmatter();
+            } else {
+                self.buffers.discard_text(&note.id);
+                self.switch_buffer(note);
+            }
+            self.set_view(View::Editor);
+            self.set_mode(AppMode::Normal);
+        }
     }
 
-    pub fn start_processing(&mut self) {
-        if self.current_note.is_none() {
+    /// `--open NOTE`: show the note with this id, title or alias in the
+    /// editor.
+    pub fn open_note(&mut self, query: &str) {
+        if !self.ensure_archives_loaded() {
             return;
         }
+        let notes: Vec<&Note> = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .collect();
+        let found = notes
+            .iter()
+            .find(|n| n.id == query)
+            .copied()
+            .or_else(|| resolve_link(query, &notes))
+            .cloned();
+        match found {
+            Some(note) => {
+                self.switch_buffer(note);
+                self.set_view(View::Editor);
+                self.set_mode(AppMode::Normal);
+            }
+            None => self.set_message(&format!("No note named '{}'", query)),
+        }
+    }
 
-        // Parse from current buffer so byte offsets match the live content
-        let buffer_content = self.buffer.to_string();
-        let blocks = parse_smart_blocks(&buffer_content);
-        if blocks.is_empty() {
-            self.set_message("No blocks to process");
+    /// `Space D` / `:today`: open today's daily note.
+    pub fn open_today(&mut self) {
+        self.open_daily_note(chrono::Local::now().date_naive());
+    }
+
+    /// Open the daily note of `date` (the note titled with the date in
+    /// `[daily] title_format`), creating it from `[daily] template` if there
+    /// is none.
+    pub fn open_daily_note(&mut self, date: chrono::NaiveDate) {
+        if !self.ensure_archives_loaded() {
             return;
         }
+        let daily = &self.config.daily;
+        let found = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .find(|n| daily_date(&n.title, &daily.title_format) == Some(date))
+            .cloned();
+        let title = daily_title(date, &daily.title_format);
+        match found {
+            Some(note) => {
+                self.switch_buffer(note);
+                self.set_view(View::Editor);
+                self.set_mode(AppMode::Normal);
+            }
+            None if self.read_only => {
+                self.set_message(&format!("No note named '{}'", title));
+            }
+            None => {
+                let content = daily_content(date, &daily.title_format, &daily.template);
+                self.new_note_with_content(&content);
+                self.set_message(&format!("Created '{}'", title));
+            }
+        }
+    }
 
-        self.processing_blocks = blocks;
-        self.processing_index = 0;
-        self.set_mode(AppMode::Processing);
+    /// `]d` / `[d`: open the daily note `count` notes after or before the
+    /// one being edited, or today when that is not a daily note. Only days
+    /// that have a note count.
+    pub fn cycle_daily_note(&mut self, forward: bool, count: usize) {
+        if !self.ensure_archives_loaded() {
+            return;
+        }
+        let format = &self.config.daily.title_format;
+        let from = self
+            .current_note
+            .as_ref()
+            .and_then(|n| daily_date(&n.title, format))
+            .unwrap_or_else(|| chrono::Local::now().date_naive());
+        let dates: Vec<chrono::NaiveDate> = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .filter_map(|n| daily_date(&n.title, format))
+            .collect();
+        match adjacent_daily(&dates, from, forward, count) {
+            Some(date) => self.open_daily_note(date),
+            None if forward => self.set_message("No later daily note"),
+            None => self.set_message("No earlier daily note"),
+        }
     }
 
-    pub fn process_next_block(&mut self) -> bool {
-        if self.processing_index < self.processing_blocks.len() {
-            let result = dispatch_block(
-                &self.processing_blocks[self.processing_index],
-                &self.config.destinations,
+    /// Make `note` the note in the editor. The note being edited is saved
+    /// and parked in `buffers` with its cursor and undo history; a note
+    /// opened before gets its own back. Does nothing if the save fails.
+    pub fn switch_buffer(&mut self, note: Note) {
+        if self.current_note.as_ref().is_some_and(|n| n.id == note.id) {
+            return;
+        }
+        if self.dirty
+            && let Err(e) = self.save_current_note()
+        {
+            self.set_message(&format!("Save failed: {}", e));
+            return;
+        }
+        let previous = std::mem::replace(&mut self.buffer, TextBuffer::new());
+        if let Some(current) = self.current_note.take() {
+            self.buffers.park(&current, previous);
+        }
+        self.buffer = self
+            .buffers
+            .activate(&note)
+            .unwrap_or_else(|| TextBuffer::from_string(&note.content));
+        self.current_note = Some(note);
+        self.dirty = false;
+        self.visual_mode = None;
+        self.pending_external_reload = None;
+        self.viewport.reset();
+        self.hide_frontmatter();
+        self.center_cursor();
+    }
+
+    /// `:bnext` / `:bprev` and `]b` / `[b`: switch to the open note `count`
+    /// places after or before the current one.
+    pub fn cycle_buffer(&mut self, forward: bool, count: usize) {
+        let id = self.current_note.as_ref().map_or("", |n| n.id.as_str());
+        match self.buffers.neighbour(id, forward, count).cloned() {
+            Some(note) => {
+                self.switch_buffer(note);
+                self.set_view(View::Editor);
+            }
+            None => self.set_message("No other buffers"),
+        }
+    }
+
+    /// `:split` / `:vsplit`, `Ctrl-W s` / `Ctrl-W v`: show the current note,
+    /// or the one titled `title`, in a second pane and focus it. Both panes
+    /// keep their own cursor and scroll position; a `read_only` pane
+    /// refuses edits.
+    pub fn split_pane(&mut self, dir: SplitDir, title: Option<&str>, read_only: bool) {
+        self.store_focused_pane();
+        if let Some(title) = title {
+            if !self.ensure_archives_loaded() {
+                return;
+            }
+            let notes: Vec<&Note> = self
+                .draft_list
+                .notes()
+                .iter()
+                .chain(self.archive_list.notes())
+                .collect();
+            let Some(note) = resolve_link(title, &notes).cloned() else {
+                self.set_message(&format!("No note named '{}'", title));
+                return;
+            };
+            let id = note.id.clone();
+            self.switch_buffer(note);
+            if self.current_note.as_ref().is_none_or(|n| n.id != id) {
+                return;
+            }
+        }
+        let pane = Pane {
+            note_id: self.current_note.as_ref().map(|n| n.id.clone()),
+            cursor: self.buffer.cursor_position(),
+            viewport: self.viewport.clone(),
+            read_only,
+        };
+        let ratios = self.layout.ratios(View::Editor.as_str());
+        let ratio = match dir {
+            SplitDir::Horizontal => ratios.horizontal,
+            SplitDir::Vertical => ratios.vertical,
+        };
+        self.panes.split(dir, pane, ratio);
+        self.set_view(View::Editor);
+        self.set_mode(AppMode::Normal);
+    }
+
+    /// Whether edits are refused: the whole session is read-only, or the
+    /// focused pane was opened with `:sview` / `:vsview`.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+            || (self.view == View::Editor && self.panes.pane(self.panes.focus()).read_only)
+    }
+
+    /// Focus pane `target`, switching the editor to its note and restoring
+    /// its cursor and scroll position. Stays put if saving the note being
+    /// left fails.
+    pub fn focus_pane(&mut self, target: usize) {
+        if target == self.panes.focus() || target >= self.panes.len() {
+            return;
+        }
+        self.store_focused_pane();
+        let current_id = self.current_note.as_ref().map(|n| n.id.clone());
+        let target_id = self.panes.pane(target).note_id.clone();
+        if let Some(id) = target_id.filter(|id| Some(id) != current_id.as_ref())
+            && let Some(i) = self.buffers.position(&id)
+        {
+            let note = self.buffers.entries()[i].note.clone();
+            self.switch_buffer(note);
+            if self.current_note.as_ref().is_none_or(|n| n.id != id) {
+                return;
+            }
+        }
+        self.panes.set_focus(target);
+        let pane = self.panes.pane(target);
+        self.viewport = pane.viewport.clone();
+        self.buffer.set_cursor(pane.cursor.0, pane.cursor.1);
+    }
+
+    /// `Ctrl-W h/j/k/l`: focus the pane in `dir`, if any.
+    pub fn focus_pane_towards(&mut self, dir: PaneDir) {
+        if let Some(target) = self.panes.neighbour(dir) {
+            self.focus_pane(target);
+        }
+    }
+
+    /// `Ctrl-W w` / `Ctrl-W W`: focus the pane `count` places on.
+    pub fn cycle_pane(&mut self, forward: bool, count: usize) {
+        let target = self.panes.next(forward, count);
+        self.focus_pane(target);
+    }
+
+    /// `:close`, `Ctrl-W c`: close the focused pane and focus the next.
+    pub fn close_pane(&mut self) {
+        if !self.panes.is_split() {
+            self.set_message("Cannot close last pane");
+            return;
+        }
+        let closing = self.panes.focus();
+        self.focus_pane(self.panes.next(true, 1));
+        if self.panes.focus() != closing {
+            self.panes.close(closing);
+        }
+    }
+
+    /// `:only`, `Ctrl-W o`: close every pane but the focused one.
+    pub fn only_pane(&mut self) {
+        self.panes.only();
+    }
+
+    /// `Ctrl-W +/-` (`Horizontal`) and `Ctrl-W >/<` (`Vertical`): make the
+    /// focused pane `count` steps bigger or smaller, and keep the new
+    /// proportion for later splits.
+    pub fn resize_pane(&mut self, dir: SplitDir, grow: bool, count: usize) {
+        let step = (PANE_RESIZE_STEP * count.min(100) as i32).min(100);
+        let delta = if grow { step } else { -step };
+        let Some(ratio) = self.panes.resize(dir, delta) else {
+            return;
+        };
+        let ratios = self.layout.ratios_mut(View::Editor.as_str());
+        match dir {
+            SplitDir::Horizontal => ratios.horizontal = ratio,
+            SplitDir::Vertical => ratios.vertical = ratio,
+        }
+        self.save_layout();
+    }
+
+    /// `Ctrl-W =`: split every pane evenly again, now and for later splits.
+    pub fn equalize_panes(&mut self) {
+        self.panes.equalize();
+        let ratios = self.layout.ratios_mut(View::Editor.as_str());
+        ratios.horizontal = EVEN_SPLIT;
+        ratios.vertical = EVEN_SPLIT;
+        self.save_layout();
+    }
+
+    fn save_layout(&mut self) {
+        if let Err(e) = save_layout(&self.data_dir, &self.layout) {
+            self.set_message(&format!("Saving layout failed: {}", e));
+        }
+    }
+
+    /// Record the editor state in the focused pane before focus leaves it.
+    fn store_focused_pane(&mut self) {
+        let note_id = self.current_note.as_ref().map(|n| n.id.clone());
+        let cursor = self.buffer.cursor_position();
+        let pane = self.panes.pane_mut(self.panes.focus());
+        pane.note_id = note_id;
+        pane.cursor = cursor;
+        pane.viewport = self.viewport.clone();
+    }
+
+    /// Text, title and cursor of the unfocused pane `index`. A pane on the
+    /// focused note shows the live buffer; others show their parked buffer
+    /// or, if that was dropped, the note as last saved.
+    pub fn pane_view(&self, index: usize) -> PaneView<'_> {
+        let pane = self.panes.pane(index);
+        let id = pane.note_id.as_deref();
+        let (lines, title): (Vec<&str>, &str) = match &self.current_note {
+            Some(note) if Some(note.id.as_str()) == id => (
+                self.buffer.content().iter().map(String::as_str).collect(),
+                &note.title,
+            ),
+            _ => match id.and_then(|id| self.buffers.position(id)) {
+                Some(i) => {
+                    let entry = &self.buffers.entries()[i];
+                    let lines = match self.buffers.text(&entry.note.id) {
+                        Some(text) => text.content().iter().map(String::as_str).collect(),
+                        None => entry.note.content.split('\n').collect(),
+                    };
+                    (lines, &entry.note.title)
+                }
+                None => (vec![""], "Untitled"),
+            },
+        };
+        let row = pane.cursor.0.min(lines.len().saturating_sub(1));
+        let len = lines
+            .get(row)
+            .map_or(0, |line| line.graphemes(true).count());
+        PaneView {
+            cursor: (row, pane.cursor.1.min(len)),
+            lines,
+            title,
+        }
+    }
+
+    /// Scroll offset of the unfocused pane `index`, drawn in an area of
+    /// `area_width` by `area_height` including its border.
+    pub fn pane_scroll_offset(
+        &self,
+        index: usize,
+        view: &PaneView,
+        area_width: u16,
+        area_height: u16,
+    ) -> u16 {
+        use crate::atoms::widgets::wrap_calc;
+
+        let width = self.wrap_width(area_width.saturating_sub(2));
+        let (row, col) = view.cursor;
+        let vpos = wrap_calc::visual_cursor_position(&view.lines, row, col, width);
+        self.panes.pane(index).viewport.follow(
+            vpos.rows_before + vpos.wrap_row,
+            width,
+            area_height.saturating_sub(2),
+        )
+    }
+
+    /// Headings of the current note, as listed in the outline sidebar.
+    pub fn outline(&self) -> Rc<[OutlineEntry]> {
+        self.render_cache.outline(&self.buffer)
+    }
+
+    /// `Space o`: show the outline sidebar and give it the keyboard, starting
+    /// on the section the cursor is in; hide it if it is already shown.
+    pub fn toggle_outline(&mut self) {
+        if self.show_outline && self.outline_selection.is_none() {
+            self.show_outline = false;
+            return;
+        }
+        let row = self.buffer.cursor_position().0;
+        self.show_outline = true;
+        self.outline_selection = Some(section_at(&self.outline(), row).unwrap_or(0));
+    }
+
+    pub fn move_outline(&mut self, down: bool) {
+        let last = self.outline().len().saturating_sub(1);
+        if let Some(row) = &mut self.outline_selection {
+            *row = if down {
+                (*row + 1).min(last)
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Give the keyboard back to the editor, first jumping to the selected
+    /// heading when `jump`. The sidebar stays shown.
+    pub fn leave_outline(&mut self, jump: bool) {
+        let Some(selected) = self.outline_selection.take() else {
+            return;
+        };
+        if jump && let Some(entry) = self.outline().get(selected) {
+            self.record_jump();
+            self.buffer.set_cursor(entry.row, 0);
+            self.center_cursor();
+        }
+    }
+
+    fn folds_key(&self) -> String {
+        self.current_note
+            .as_ref()
+            .map_or_else(String::new, |n| n.id.clone())
+    }
+
+    /// Foldable regions of the current note.
+    pub fn fold_regions(&self) -> Rc<[FoldRegion]> {
+        self.render_cache.fold_regions(&self.buffer)
+    }
+
+    /// The closed folds of the current note that decide what is hidden.
+    pub fn closed_folds(&self) -> Vec<FoldRegion> {
+        match self.folds.get(&self.folds_key()) {
+            Some(folds) if !folds.is_empty() => folds.closed_regions(&self.fold_regions()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The editor's lines as drawn, with closed folds as summary lines.
+    pub fn folded_lines(&self) -> FoldedLines<'_> {
+        let lines: Vec<&str> = self.buffer.content().iter().map(String::as_str).collect();
+        FoldedLines::new(&lines, &self.closed_folds())
+    }
+
+    /// `za`: open the fold under the cursor, or close the innermost section
+    /// or code block around it and put the cursor on its first line.
+    pub fn toggle_fold(&mut self) {
+        let regions = self.fold_regions();
+        let (row, col) = self.buffer.cursor_position();
+        let key = self.folds_key();
+        if let Some(closed) = self.folds.entry(key).or_default().toggle(&regions, row) {
+            self.buffer.set_cursor(closed.start, col);
+        }
+    }
+
+    /// `zM`: fold every section and code block.
+    pub fn close_all_folds(&mut self) {
+        let regions = self.fold_regions();
+        let key = self.folds_key();
+        self.folds.entry(key).or_default().close_all(&regions);
+        let (row, col) = self.buffer.cursor_position();
+        if let Some(fold) = self.closed_folds().iter().find(|r| r.contains(row)) {
+            self.buffer.set_cursor(fold.start, col);
+        }
+    }
+
+    /// `zR`: open every fold.
+    pub fn open_all_folds(&mut self) {
+        let key = self.folds_key();
+        self.folds.entry(key).or_default().open_all();
+    }
+
+    /// With `hide_frontmatter`, fold the frontmatter of a note the first time
+    /// it is shown this session.
+    fn hide_frontmatter(&mut self) {
+        let key = self.folds_key();
+        if !self.config.general.hide_frontmatter || self.folds.contains_key(&key) {
+            return;
+        }
+        let lines: Vec<&str> = self.buffer.content().iter().map(String::as_str).collect();
+        if frontmatter_end(&lines).is_some() {
+            let regions = self.fold_regions();
+            self.folds.entry(key).or_default().toggle(&regions, 0);
+        }
+    }
+
+    /// Keep the cursor off hidden rows after it moved from row `from`. A
+    /// line motion (`j`/`k`) steps over the fold; anything else, such as a
+    /// search or a jump, opens the folds around the new position. Folds whose
+    /// section an edit removed are dropped.
+    pub fn settle_folds(&mut self, from: usize, line_motion: bool) {
+        let key = self.folds_key();
+        if self.folds.get(&key).is_none_or(Folds::is_empty) {
+            return;
+        }
+        let regions = self.fold_regions();
+        let (row, col) = self.buffer.cursor_position();
+        let folds = self.folds.entry(key).or_default();
+        folds.retain_valid(&regions);
+        let Some(fold) = folds
+            .closed_regions(&regions)
+            .into_iter()
+            .find(|r| r.contains(row) && r.start != row)
+        else {
+            return;
+        };
+        if !line_motion {
+            folds.reveal(&regions, row);
+        } else if row > from && fold.end + 1 < self.buffer.line_count() {
+            self.buffer.set_cursor(fold.end + 1, col);
+        } else {
+            self.buffer.set_cursor(fold.start, col);
+        }
+    }
+
+    /// Show the buffer picker with the current note selected.
+    pub fn open_buffer_picker(&mut self) {
+        let id = self.current_note.as_ref().map_or("", |n| n.id.as_str());
+        self.buffer_picker = Some(self.buffers.position(id).unwrap_or(0));
+    }
+
+    pub fn move_buffer_picker(&mut self, down: bool) {
+        let last = self.buffers.len().saturating_sub(1);
+        if let Some(row) = &mut self.buffer_picker {
+            *row = if down {
+                (*row + 1).min(last)
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the picker, switching to the selected note when `accept`.
+    pub fn close_buffer_picker(&mut self, accept: bool) {
+        let Some(row) = self.buffer_picker.take() else {
+            return;
+        };
+        if accept && let Some(entry) = self.buffers.entries().get(row) {
+            let note = entry.note.clone();
+            self.switch_buffer(note);
+            self.set_view(View::Editor);
+            self.set_mode(AppMode::Normal);
+        }
+    }
+
+    /// Read the archive if it is not loaded yet, so links can resolve to
+    /// archived notes. Returns false, with a message, if that fails.
+    fn ensure_archives_loaded(&mut self) -> bool {
+        if self.archives_loaded {
+            return true;
+        }
+        if let Err(e) = self.load_archives() {
+            self.set_message(&format!("Loading archives failed: {}", e));
+            return false;
+        }
+        true
+    }
+
+    /// `gf` / Enter: open the note named by the `[[link]]` under the cursor,
+    /// by title or alias, or create a draft with that title. A note id or
+    /// date under the cursor opens that note or that day's daily note.
+    pub fn follow_link(&mut self) {
+        let line = self.buffer.current_line_content();
+        // `$` leaves the cursor just past the last character
+        let (row, col) = self.buffer.cursor_position();
+        let col = col.min(line.graphemes(true).count().saturating_sub(1));
+        let Some(link) = link_at(line, col) else {
+            match self
+                .auto_links()
+                .iter()
+                .find(|link| link.row == row && link.contains(col))
+            {
+                Some(link) => self.follow_auto_link(link.kind.clone()),
+                None => self.set_message("No link under cursor"),
+            }
+            return;
+        };
+        if !self.ensure_archives_loaded() {
+            return;
+        }
+        let notes: Vec<&Note> = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .collect();
+        match resolve_link(&link.target, &notes).cloned() {
+            Some(note) => self.switch_buffer(note),
+            None if self.read_only => {
+                self.set_message(&format!("No note named '{}'", link.target));
+            }
+            None => {
+                self.new_note_with_content(&format!("# {}\n", link.target));
+                self.set_message(&format!("Created '{}'", link.target));
+            }
+        }
+    }
+
+    fn follow_auto_link(&mut self, kind: AutoLinkKind) {
+        match kind {
+            AutoLinkKind::Date(date) => self.open_daily_note(date),
+            AutoLinkKind::NoteId(id) => {
+                if !self.ensure_archives_loaded() {
+                    return;
+                }
+                match self.note_by_id(&id).cloned() {
+                    Some(note) => self.switch_buffer(note),
+                    None => self.set_message(&format!("No note with id {}", id)),
+                }
+            }
+        }
+    }
+
+    /// The loaded draft or archived note with id `id`.
+    fn note_by_id(&self, id: &str) -> Option<&Note> {
+        self.draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .find(|note| note.id == id)
+    }
+
+    /// Note ids and dates in the editor that `gf` follows, as
+    /// `[auto_links]` enables them, shared between frames until the text
+    /// changes.
+    pub fn auto_links(&self) -> Rc<[AutoLink]> {
+        let patterns = &self.config.auto_links;
+        if self.view != View::Editor || !(patterns.note_ids || patterns.dates) {
+            return Rc::from([]);
+        }
+        self.render_cache.auto_links(&self.buffer, || {
+            let lines: Vec<&str> = self.buffer.content().iter().map(String::as_str).collect();
+            auto_links(&lines, patterns)
+        })
+    }
+
+    /// Where the editor underlines `auto_links`: every date, and the ids
+    /// of loaded notes other than the one being edited.
+    pub fn auto_link_matches(&self) -> Vec<SearchMatch> {
+        let current = self.current_note.as_ref().map(|n| n.id.as_str());
+        self.auto_links()
+            .iter()
+            .filter(|link| match &link.kind {
+                AutoLinkKind::Date(_) => true,
+                AutoLinkKind::NoteId(id) => {
+                    Some(id.as_str()) != current && self.note_by_id(id).is_some()
+                }
+            })
+            .map(|link| (link.row, link.start, link.end - link.start))
+            .collect()
+    }
+
+    /// Keep the current note as it is now, before `reason` rewrites it. A
+    /// failed snapshot is reported but does not stop the operation.
+    fn freeze_current_note(&mut self, reason: SnapshotReason) {
+        let Some(id) = self.current_note.as_ref().map(|n| n.id.clone()) else {
+            return;
+        };
+        let now = chrono::Local::now().naive_local();
+        if let Err(e) = save_snapshot(&self.data_dir, &id, reason, &self.buffer.to_string(), now) {
+            self.set_message(&format!("Snapshot failed: {}", e));
+        }
+    }
+
+    /// `:history`: list the snapshots taken of the current note.
+    pub fn open_snapshot_picker(&mut self) {
+        let Some(id) = self.current_note.as_ref().map(|n| n.id.clone()) else {
+            self.set_message("No note open");
+            return;
+        };
+        match list_snapshots(&self.data_dir, &id) {
+            Ok(snapshots) if snapshots.is_empty() => self.set_message("No snapshots of this note"),
+            Ok(snapshots) => self.snapshot_picker = Some((snapshots, 0)),
+            Err(e) => self.set_message(&format!("History failed: {}", e)),
+        }
+    }
+
+    pub fn move_snapshot_picker(&mut self, down: bool) {
+        if let Some((snapshots, row)) = &mut self.snapshot_picker {
+            *row = if down {
+                (*row + 1).min(snapshots.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the history picker; with `accept`, restore the selected
+    /// snapshot.
+    pub fn close_snapshot_picker(&mut self, accept: bool) {
+        if let Some((snapshots, row)) = self.snapshot_picker.take()
+            && accept
+            && let Some(snapshot) = snapshots.get(row)
+        {
+            self.restore_snapshot(snapshot);
+        }
+    }
+
+    /// Put `snapshot` back as the text of the current note, as one undo
+    /// step. The text it replaces is kept as a snapshot first.
+    fn restore_snapshot(&mut self, snapshot: &NoteSnapshot) {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let content = match load_snapshot(snapshot) {
+            Ok(content) => content,
+            Err(e) => {
+                self.set_message(&format!("Restore failed: {}", e));
+                return;
+            }
+        };
+        self.freeze_current_note(SnapshotReason::Restore);
+        self.buffer.replace_content(&content);
+        self.dirty = true;
+        self.set_message(&format!(
+            "Restored the snapshot from {}",
+            snapshot.taken.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    /// Dictionary the current note is checked against: its `spelllang`
+    /// frontmatter field, else `general.spell_language`.
+    pub fn spell_language(&self) -> String {
+        let lines: Vec<&str> = self.buffer.content().iter().map(String::as_str).collect();
+        frontmatter_end(&lines)
+            .and_then(|end| Frontmatter::parse(&lines[..=end].join("\n")))
+            .and_then(|fm| fm.get("spelllang").map(str::to_string))
+            .filter(|lang| !lang.is_empty())
+            .unwrap_or_else(|| self.config.general.spell_language.clone())
+    }
+
+    /// The dictionary for `spell_language`, read the first time it is
+    /// needed; `None` when no dictionary directory has it.
+    fn spell_dictionary(&self) -> Option<Rc<Dictionary>> {
+        let language = self.spell_language();
+        self.dictionaries
+            .borrow_mut()
+            .entry(language)
+            .or_insert_with_key(|language| {
+                let (words, rules) = load_dictionary(&self.dictionary_dirs, language).ok()??;
+                Some(Rc::new(Dictionary::parse(&words, rules.as_deref())))
+            })
+            .clone()
+    }
+
+    fn no_dictionary_message(&mut self) {
+        let language = self.spell_language();
+        self.set_message(&format!("No dictionary for {}", language));
+    }
+
+    /// Misspelled words of the editor, shared between frames until the
+    /// text or its language changes. Empty while spell checking is off.
+    pub fn spell_errors(&self) -> Rc<[SearchMatch]> {
+        if !self.spell || self.view != View::Editor {
+            return Rc::from([]);
+        }
+        let Some(dictionary) = self.spell_dictionary() else {
+            return Rc::from([]);
+        };
+        self.render_cache
+            .spell_errors(&self.buffer, &self.spell_language(), || {
+                let lines: Vec<&str> = self.buffer.content().iter().map(String::as_str).collect();
+                spelling_errors(&dictionary, &lines)
+            })
+    }
+
+    /// `:set spelllang=LANG`: check the current note against `LANG` from
+    /// now on, kept in its `spelllang` field.
+    fn set_spell_language(&mut self, language: &str) -> Result<()> {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        let Some(id) = self.current_note.as_ref().map(|n| n.id.clone()) else {
+            self.set_message("No note open");
+            return Ok(());
+        };
+        self.rewrite_note(&id, |content| {
+            set_frontmatter_field(content, "spelllang", language)
+        })?;
+        if self.spell_dictionary().is_some() {
+            self.set_message(&format!("Spell language: {}", language));
+        } else {
+            self.no_dictionary_message();
+        }
+        Ok(())
+    }
+
+    /// `]s` / `[s`: move to the `count`th misspelled word after or before
+    /// the cursor, wrapping around the note.
+    pub fn cycle_spell_error(&mut self, forward: bool, count: usize) {
+        if !self.spell {
+            self.set_message("Spell checking is off");
+            return;
+        }
+        if self.spell_dictionary().is_none() {
+            self.no_dictionary_message();
+            return;
+        }
+        let errors = self.spell_errors();
+        if errors.is_empty() {
+            self.set_message("No misspelled words");
+            return;
+        }
+        let mut at = self.buffer.cursor_position();
+        for _ in 0..count {
+            let next = if forward {
+                errors
+                    .iter()
+                    .find(|(row, col, _)| (*row, *col) > at)
+                    .unwrap_or(&errors[0])
+            } else {
+                errors
+                    .iter()
+                    .rev()
+                    .find(|(row, col, _)| (*row, *col) < at)
+                    .unwrap_or(&errors[errors.len() - 1])
+            };
+            at = (next.0, next.1);
+        }
+        self.buffer.set_cursor(at.0, at.1);
+    }
+
+    /// The word under the cursor: row, grapheme column and text.
+    fn word_under_cursor(&self) -> Option<(usize, usize, String)> {
+        let (row, col) = self.buffer.cursor_position();
+        let line = self.buffer.content().get(row)?;
+        let (start, len) = word_spans(line)
+            .into_iter()
+            .find(|(start, len)| (*start..start + len).contains(&col))?;
+        let word = line.graphemes(true).skip(start).take(len).collect();
+        Some((row, start, word))
+    }
+
+    /// `z=`: offer spellings for the word under the cursor.
+    pub fn open_spell_picker(&mut self) {
+        let Some((_, _, word)) = self.word_under_cursor() else {
+            self.set_message("No word under the cursor");
+            return;
+        };
+        let Some(dictionary) = self.spell_dictionary() else {
+            self.no_dictionary_message();
+            return;
+        };
+        let suggestions = dictionary.suggest(&word, SUGGESTION_LIMIT);
+        if suggestions.is_empty() {
+            self.set_message(&format!("No suggestions for {}", word));
+        } else {
+            self.spell_picker = Some((suggestions, 0));
+        }
+    }
+
+    pub fn move_spell_picker(&mut self, down: bool) {
+        if let Some((suggestions, row)) = &mut self.spell_picker {
+            *row = if down {
+                (*row + 1).min(suggestions.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the spelling picker; with `accept`, replace the word under
+    /// the cursor with the selected spelling.
+    pub fn close_spell_picker(&mut self, accept: bool) {
+        let Some((suggestions, selected)) = self.spell_picker.take() else {
+            return;
+        };
+        if !accept {
+            return;
+        }
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let (Some(spelling), Some((row, start, word))) =
+            (suggestions.get(selected), self.word_under_cursor())
+        else {
+            return;
+        };
+        let graphemes: Vec<&str> = self.buffer.content()[row].graphemes(true).collect();
+        let end = start + word.graphemes(true).count();
+        let line = format!(
+            "{}{}{}",
+            graphemes[..start].concat(),
+            spelling,
+            graphemes[end..].concat()
+        );
+        self.buffer.save_undo_snapshot();
+        self.buffer.replace_line(row, line);
+        self.buffer.set_cursor(row, start);
+        self.dirty = true;
+    }
+
+    /// `Space L` / `:backlinks`: list the notes linking to the current one
+    /// by its title or an alias.
+    pub fn open_backlinks(&mut self) {
+        let Some(note) = self.current_note.clone() else {
+            return;
+        };
+        if !self.ensure_archives_loaded() {
+            return;
+        }
+        let notes = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes());
+        self.backlinks.sync(notes.clone());
+        let known: HashSet<&str> = notes.clone().map(|n| n.id.as_str()).collect();
+        self.backlinks.retain(|id| known.contains(id));
+
+        let ids = self.backlinks.linking_to(&note);
+        let linking: Vec<Note> = notes
+            .filter(|n| ids.contains(&n.id.as_str()))
+            .cloned()
+            .collect();
+        if linking.is_empty() {
+            self.set_message("No notes link here");
+        } else {
+            self.backlink_picker = Some((linking, 0));
+        }
+    }
+
+    pub fn move_backlink_picker(&mut self, down: bool) {
+        if let Some((notes, row)) = &mut self.backlink_picker {
+            *row = if down {
+                (*row + 1).min(notes.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the backlinks panel, opening the selected note when `accept`.
+    pub fn close_backlink_picker(&mut self, accept: bool) {
+        let Some((mut notes, row)) = self.backlink_picker.take() else {
+            return;
+        };
+        if accept && row < notes.len() {
+            self.switch_buffer(notes.swap_remove(row));
+            self.set_view(View::Editor);
+            self.set_mode(AppMode::Normal);
+        }
+    }
+
+    /// `Space t` / `:tags`: list the tags of the drafts with their counts.
+    pub fn open_tag_picker(&mut self) {
+        let tags = tag_counts(self.draft_list.notes());
+        if tags.is_empty() {
+            self.set_message("No tags");
+        } else {
+            self.tag_picker = Some((tags, 0));
+        }
+    }
+
+    pub fn move_tag_picker(&mut self, down: bool) {
+        if let Some((tags, row)) = &mut self.tag_picker {
+            *row = if down {
+                (*row + 1).min(tags.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the tag browser; with `accept`, show the drafts with the
+    /// selected tag.
+    pub fn close_tag_picker(&mut self, accept: bool) {
+        let Some((tags, row)) = self.tag_picker.take() else {
+            return;
+        };
+        if accept && let Some((tag, _)) = tags.get(row) {
+            self.set_view(View::DraftList);
+            self.set_mode(AppMode::Normal);
+            self.set_list_filter(format!("#{}", tag));
+            self.select_list_index(0);
+        }
+    }
+
+    /// `Space r` / `:rename`: ask for a new title for the note in the editor,
+    /// or the one under the cursor in a list.
+    pub fn open_rename(&mut self) {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let note = if self.view == View::Editor {
+            self.current_note.as_ref()
+        } else {
+            self.active_list().selected_note()
+        };
+        let Some(note) = note else {
+            self.set_message("No note open");
+            return;
+        };
+        let title = note.title.clone();
+        self.rename_id = note.id.clone();
+        self.rename_input.reset(&title);
+        self.rename_title = Some(title);
+    }
+
+    /// Close the rename prompt, renaming the note when `accept` and the
+    /// title is not blank.
+    pub fn close_rename(&mut self, accept: bool) -> Result<()> {
+        let Some(title) = self.rename_title.take() else {
+            return Ok(());
+        };
+        let id = std::mem::take(&mut self.rename_id);
+        if accept && !title.trim().is_empty() {
+            self.rename_note(&id, title.trim())?;
+        }
+        Ok(())
+    }
+
+    /// Give note `id` the title `title` by rewriting its title line. Under
+    /// `FileNaming::Title` the save renames its file too.
+    pub fn rename_note(&mut self, id: &str, title: &str) -> Result<()> {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        self.rewrite_note(id, |content| Note::retitle(content, title))?;
+        self.set_message(&format!("Renamed to '{}'", title));
+        Ok(())
+    }
+
+    /// The first heading of the note in the editor when its `title`
+    /// frontmatter field, shown everywhere else, no longer matches it.
+    pub fn title_drift(&self) -> Option<String> {
+        if self.view != View::Editor || self.current_note.is_none() {
+            return None;
+        }
+        self.render_cache
+            .title_drift(&self.buffer, || Note::title_drift(&self.buffer.to_string()))
+    }
+
+    /// What `terminal_title` / `tmux_title` set the title to: the note in
+    /// the editor, `[+]` while it has unsaved changes, or the list shown.
+    pub fn window_title(&self) -> String {
+        let name = match (&self.current_note, self.view) {
+            (Some(note), View::Editor) if self.dirty => format!("{} [+]", note.title),
+            (Some(note), View::Editor) => note.title.clone(),
+            (None, View::Editor) => "Untitled".to_string(),
+            (_, view) => view.as_str().to_string(),
+        };
+        format!("{} - kenotex", name)
+    }
+
+    /// `Space T` / `:sync-title`: set the `title` field of the note in the
+    /// editor, or the one under the cursor in a list, to its first heading.
+    pub fn sync_title(&mut self) -> Result<()> {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        let (id, content) = if self.view == View::Editor {
+            match &self.current_note {
+                Some(note) => (note.id.clone(), self.buffer.to_string()),
+                None => {
+                    self.set_message("No note open");
+                    return Ok(());
+                }
+            }
+        } else {
+            match self.active_list().selected_note() {
+                Some(note) => (note.id.clone(), note.content.clone()),
+                None => {
+                    self.set_message("No note open");
+                    return Ok(());
+                }
+            }
+        };
+        let Some(heading) = Note::title_drift(&content) else {
+            self.set_message("Title matches the first heading");
+            return Ok(());
+        };
+        self.rewrite_note(&id, |content| {
+            set_frontmatter_field(content, "title", &heading)
+        })?;
+        self.set_message(&format!("Title set to '{}'", heading));
+        Ok(())
+    }
+
+    /// `p` in a list: pin the note under the cursor to the top of its list
+    /// in every sort order, or unpin it, with its `pinned` frontmatter field.
+    pub fn toggle_pin_selected(&mut self) -> Result<()> {
+        let Some(note) = self.active_list().selected_note().cloned() else {
+            return Ok(());
+        };
+        let pin = !note.is_pinned();
+        self.rewrite_note(&note.id, |content| {
+            if pin {
+                set_frontmatter_field(content, "pinned", "true")
+            } else {
+                take_frontmatter_field(content, "pinned")
+                    .map_or_else(|| content.to_string(), |(rest, _)| rest)
+            }
+        })?;
+        let list = self.active_list_mut();
+        list.set_sort_order(list.sort_order());
+        if pin {
+            self.set_message(&format!("Pinned '{}'", note.title));
+        } else {
+            self.set_message(&format!("Unpinned '{}'", note.title));
+        }
+        Ok(())
+    }
+
+    /// Replace the text of note `id` with `edit` of it and save it, in the
+    /// editor if it is open there.
+    fn rewrite_note(&mut self, id: &str, edit: impl FnOnce(&str) -> String) -> Result<()> {
+        if self.current_note.as_ref().is_some_and(|n| n.id == id) {
+            let content = edit(&self.buffer.to_string());
+            self.buffer.replace_content(&content);
+            self.dirty = true;
+            self.save_current_note()?;
+            self.flush_saves()?;
+            if let Some(note) = &self.current_note {
+                self.archive_list.update_note(note);
+            }
+        } else {
+            let note = self
+                .draft_list
+                .notes()
+                .iter()
+                .chain(self.archive_list.notes())
+                .find(|n| n.id == id)
+                .cloned();
+            let Some(mut note) = note else {
+                return Ok(());
+            };
+            self.flush_saves()?;
+            note.update_content(edit(&note.content));
+            self.file_change_tracker.record_save(&note.id);
+            self.file_change_tracker
+                .record_content(&note.id, &note.content);
+            save_draft(&self.data_dir, &note, self.file_naming())?;
+            if note.is_archived {
+                self.archive_list.update_note(&note);
+            } else {
+                self.draft_list.update_note(&note);
+            }
+            self.buffers.discard_text(&note.id);
+            self.buffers.update_note(&note);
+            self.search_index.update(&note);
+            self.backlinks.update(&note);
+            self.refilter_lists();
+        }
+        Ok(())
+    }
+
+    /// `:tag <name>`: add `#name` to the current note, or in a list to the
+    /// marked notes (the one under the cursor if none is marked).
+    pub fn add_tag_to_note(&mut self, name: &str) -> Result<()> {
+        if self.view == View::Editor && self.current_note.is_none() {
+            self.set_message("No note open");
+            return Ok(());
+        }
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        let Some(tag) = as_tag(&format!("#{}", name)).map(str::to_lowercase) else {
+            self.set_message(&format!("Invalid tag: {}", name));
+            return Ok(());
+        };
+        if self.view != View::Editor {
+            return self.tag_marked_notes(&tag);
+        }
+        self.buffer
+            .replace_content(&add_tag(&self.buffer.to_string(), &tag));
+        self.dirty = true;
+        let id = self.current_note.as_ref().map(|n| n.id.as_str());
+        if let Some(review) = &mut self.review
+            && review.current() == id
+        {
+            review.record(ReviewAction::Tag(tag.clone()));
+            self.set_message(&self.review_prompt().unwrap_or_default());
+        } else {
+            self.set_message(&format!("Tagged #{}", tag));
+        }
+        Ok(())
+    }
+
+    fn tag_marked_notes(&mut self, tag: &str) -> Result<()> {
+        let list = self.active_list();
+        let ids: Vec<String> = if list.marked_count() == 0 {
+            list.selected_note()
+                .map(|n| n.id.clone())
+                .into_iter()
+                .collect()
+        } else {
+            list.get_selected_notes()
+                .iter()
+                .map(|n| n.id.clone())
+                .collect()
+        };
+        if ids.is_empty() {
+            return Ok(());
+        }
+        for id in &ids {
+            self.rewrite_note(id, |content| add_tag(content, tag))?;
+        }
+        self.active_list_mut().clear_marks();
+        self.set_message(&format!("Tagged {} note(s) #{}", ids.len(), tag));
+        Ok(())
+    }
+
+    /// `:review`: go through the drafts modified in the past week one at a
+    /// time, oldest first.
+    pub fn start_review(&mut self) {
+        let since = chrono::Utc::now() - chrono::Duration::days(7);
+        match ReviewSession::new(self.draft_list.notes(), since) {
+            Some(review) => {
+                self.review = Some(review);
+                self.show_review_note();
+            }
+            None => self.set_message("No notes modified in the past week"),
+        }
+    }
+
+    /// What the review keys do, with the position in the review.
+    pub fn review_prompt(&self) -> Option<String> {
+        let (at, of) = self.review.as_ref()?.position();
+        Some(format!(
+            "Review {}/{}: a archive, n keep, p process, t tag, q stop",
+            at, of
+        ))
+    }
+
+    /// Open the note under review, skipping notes deleted meanwhile, or
+    /// finish the review after the last one.
+    fn show_review_note(&mut self) {
+        loop {
+            let Some(review) = &mut self.review else {
+                return;
+            };
+            let Some(id) = review.current() else {
+                self.finish_review();
+                return;
+            };
+            match self.draft_list.notes().iter().find(|n| n.id == id).cloned() {
+                Some(note) => {
+                    self.switch_buffer(note);
+                    self.set_view(View::Editor);
+                    self.set_mode(AppMode::Normal);
+                    self.set_message(&self.review_prompt().unwrap_or_default());
+                    return;
+                }
+                None => {
+                    review.advance();
+                }
+            }
+        }
+    }
+
+    /// `n` in a review: leave the note as it is and go to the next one.
+    pub fn review_keep(&mut self) {
+        if let Some(review) = &mut self.review {
+            review.advance();
+            self.show_review_note();
+        }
+    }
+
+    /// `a` in a review: archive the note and go to the next one.
+    pub fn review_archive(&mut self) -> Result<()> {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        let Some(id) = self
+            .review
+            .as_ref()
+            .and_then(|r| r.current().map(str::to_string))
+        else {
+            return Ok(());
+        };
+        if self.dirty {
+            self.save_current_note()?;
+        }
+        self.flush_saves()?;
+        let Some(note) = self.draft_list.notes().iter().find(|n| n.id == id).cloned() else {
+            return Ok(());
+        };
+        self.move_notes(std::slice::from_ref(&note), true)?;
+        self.draft_list
+            .update_notes(load_all_drafts(&self.data_dir, false)?);
+        if let Some(current) = &mut self.current_note
+            && current.id == note.id
+        {
+            current.is_archived = true;
+        }
+        if let Some(review) = &mut self.review {
+            review.record(ReviewAction::Archive);
+            review.advance();
+        }
+        self.show_review_note();
+        Ok(())
+    }
+
+    /// `p` in a review: process the smart blocks of the note, staying on it.
+    pub fn review_process(&mut self) {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        self.start_processing();
+        if self.mode == AppMode::Processing
+            && let Some(review) = &mut self.review
+        {
+            review.record(ReviewAction::Process);
+        }
+    }
+
+    /// End the review, early with `q`, and open its summary note.
+    pub fn finish_review(&mut self) {
+        let Some(review) = self.review.take() else {
+            return;
+        };
+        let summary = review.summary(chrono::Local::now().date_naive());
+        if self.read_only {
+            self.set_message("Review finished");
+            return;
+        }
+        self.new_note_with_content(&summary);
+        self.set_message("Review summary created");
+    }
+
+    /// Move the marked notes, or the one under the cursor, between the
+    /// draft and archive lists. Returns the notes as they were before.
+    fn move_selected_notes(&mut self, archive: bool) -> Result<Vec<Note>> {
+        self.flush_saves()?;
+        let notes = self.active_list_mut().remove_marked_or_selected();
+        self.move_notes(&notes, archive)?;
+        Ok(notes)
+    }
+
+    /// Write `notes` to the archive, or back to the drafts, and reload
+    /// the list they went to.
+    fn move_notes(&mut self, notes: &[Note], archive: bool) -> Result<()> {
+        for mut note in notes.iter().cloned() {
+            note.is_archived = archive;
+            note.selected = false;
+            delete_draft(&self.data_dir, &note.id, !archive)?;
+            save_draft(&self.data_dir, &note, self.file_naming())?;
+            self.buffers.update_note(&note);
+        }
+        if archive {
+            self.reload_archives()?;
+        } else {
+            self.draft_list
+                .update_notes(load_all_drafts(&self.data_dir, false)?);
+        }
+        Ok(())
+    }
+
+    pub fn archive_selected_note(&mut self) -> Result<()> {
+        if self.view == View::DraftList {
+            let notes = self.move_selected_notes(true)?;
+            self.record_removal(RemovalKind::Archived, notes);
+        }
+        Ok(())
+    }
+
+    pub fn restore_selected_note(&mut self) -> Result<()> {
+        if self.view == View::ArchiveList {
+            match self.move_selected_notes(false)?.len() {
+                0 => {}
+                1 => self.set_message("Note restored"),
+                n => self.set_message(&format!("{} notes restored", n)),
+            }
+        }
+        Ok(())
+    }
+
+    /// `r` in the trash: move the marked notes, or the one under the
+    /// cursor, back to the drafts.
+    pub fn restore_from_trash(&mut self) -> Result<()> {
+        if self.view != View::Trash {
+            return Ok(());
+        }
+        let notes = self.trash_list.remove_marked_or_selected();
+        for mut note in notes.iter().cloned() {
+            note.selected = false;
+            restore_from_trash(&self.data_dir, &mut note)?;
+        }
+        self.draft_list
+            .update_notes(load_all_drafts(&self.data_dir, false)?);
+        self.sync_search_index();
+        match notes.len() {
+            0 => {}
+            1 => self.set_message("Note restored"),
+            n => self.set_message(&format!("{} notes restored", n)),
+        }
+        Ok(())
+    }
+
+    /// `d` in the trash: delete the marked notes, or the one under the
+    /// cursor, for good.
+    fn purge_selected_notes(&mut self) -> Result<()> {
+        let notes = self.trash_list.remove_marked_or_selected();
+        for note in &notes {
+            purge_trashed(&self.data_dir, &note.id)?;
+        }
+        match notes.len() {
+            0 => {}
+            1 => self.set_message("Note deleted permanently"),
+            n => self.set_message(&format!("{} notes deleted permanently", n)),
+        }
+        Ok(())
+    }
+
+    /// `:empty-trash`: delete every note in the trash for good.
+    pub fn empty_trash(&mut self) -> Result<()> {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        self.flush_saves()?;
+        let notes = load_trash(&self.data_dir)?;
+        for note in &notes {
+            purge_trashed(&self.data_dir, &note.id)?;
+        }
+        self.trash_list = NoteList::new(Vec::new());
+        match notes.len() {
+            0 => self.set_message("Trash is empty"),
+            1 => self.set_message("Note deleted permanently"),
+            n => self.set_message(&format!("{} notes deleted permanently", n)),
+        }
+        Ok(())
+    }
+
+    /// Move the marked notes, or the one under the cursor, to the trash;
+    /// in the trash, delete them for good.
+    pub fn delete_selected_note(&mut self) -> Result<()> {
+        self.flush_saves()?;
+        match self.view {
+            View::Editor => return Ok(()),
+            View::Trash => return self.purge_selected_notes(),
+            _ => {}
+        }
+        let archived = self.view == View::ArchiveList;
+        let notes = self.active_list_mut().remove_marked_or_selected();
+        for note in &notes {
+            trash_draft(&self.data_dir, &note.id, archived)?;
+            self.buffers.remove(&note.id);
+            self.search_index.remove(&note.id);
+            self.backlinks.remove(&note.id);
+        }
+        self.record_removal(RemovalKind::Deleted, notes);
+        Ok(())
+    }
+
+    /// Offer `u` for the notes just deleted or archived.
+    fn record_removal(&mut self, kind: RemovalKind, notes: Vec<Note>) {
+        if notes.is_empty() {
+            return;
+        }
+        let removal = RecentRemoval::new(kind, notes);
+        self.set_message(&removal.message());
+        self.recent_removal = Some(removal);
+    }
+
+    /// Drop the undo offer once its window has passed, along with its
+    /// status message.
+    pub fn expire_recent_removal(&mut self) {
+        if let Some(removal) = &self.recent_removal
+            && removal.is_expired(std::time::Instant::now())
+        {
+            if self.command_message == removal.message() {
+                self.clear_message();
+            }
+            self.recent_removal = None;
+        }
+    }
+
+    /// Write back the notes of the last delete or archive (`u` in a list).
+    pub fn undo_removal(&mut self) -> Result<()> {
+        self.expire_recent_removal();
+        let Some(removal) = self.recent_removal.take() else {
+            self.set_message("Nothing to undo");
+            return Ok(());
+        };
+        self.flush_saves()?;
+        for note in &removal.notes {
+            match removal.kind {
+                RemovalKind::Archived => delete_draft(&self.data_dir, &note.id, true)?,
+                RemovalKind::Deleted => purge_trashed(&self.data_dir, &note.id)?,
+            }
+            let mut note = note.clone();
+            note.selected = false;
+            save_draft(&self.data_dir, &note, self.file_naming())?;
+        }
+        self.draft_list
+            .update_notes(load_all_drafts(&self.data_dir, false)?);
+        self.reload_archives()?;
+        self.sync_search_index();
+        if let Some(note) = removal.notes.first() {
+            self.active_list_mut().select_id(&note.id);
+        }
+        match removal.notes.len() {
+            1 => self.set_message("Note restored"),
+            n => self.set_message(&format!("{} notes restored", n)),
+        }
+        Ok(())
+    }
+
+    pub fn request_delete(&mut self) {
+        if self.view == View::Editor {
+            return;
+        }
+        let list = self.active_list();
+        let title = match list.marked_count() {
+            0 => list.selected_note().map(|n| n.title.clone()),
+            1 => list.get_selected_notes().first().map(|n| n.title.clone()),
+            n => Some(format!("{} notes", n)),
+        };
+        if let Some(title) = title {
+            self.pending_delete_title = Some(title);
+            self.set_mode(AppMode::ConfirmDelete);
+        }
+    }
+
+    pub fn confirm_delete(&mut self) -> Result<()> {
+        self.delete_selected_note()?;
+        self.pending_delete_title = None;
+        self.set_mode(AppMode::Normal);
+        Ok(())
+    }
+
+    pub fn cancel_delete(&mut self) {
+        self.pending_delete_title = None;
+        self.set_mode(AppMode::Normal);
+        self.clear_message();
+    }
+
+    pub fn start_processing(&mut self) {
+        let Some(note_id) = self.current_note.as_ref().map(|note| note.id.clone()) else {
+            return;
+        };
+
+        // Parse from current buffer so byte offsets match the live content
+        let buffer_content = self.buffer.to_string();
+        let blocks: Vec<SmartBlock> = parse_smart_blocks(&buffer_content)
+            .into_iter()
+            .map(|block| block.with_note(&note_id))
+            .collect();
+        if blocks.is_empty() {
+            self.set_message("No blocks to process");
+            return;
+        }
+
+        self.processing_blocks = blocks;
+        self.processing_index = 0;
+        self.set_mode(AppMode::Processing);
+    }
+
+    pub fn process_next_block(&mut self) -> bool {
+        if self.processing_index < self.processing_blocks.len() {
+            if let Err(e) = self.stage_audio_block(self.processing_index) {
+                self.set_message(&format!("Block failed: {}", e));
+                self.processing_blocks[self.processing_index].status = ProcessingStatus::Failed;
+                self.processing_index += 1;
+                return true;
+            }
+            let result = self.dispatcher.dispatch(
+                &self.processing_blocks[self.processing_index],
+                &self.config.destinations,
             );
             self.processing_blocks[self.processing_index].status = match result {
                 DispatchResult::Sent => ProcessingStatus::Sent,
@@ -311,71 +2570,1025 @@ impl App {
                     ProcessingStatus::Failed
                 }
             };
-            self.processing_index += 1;
-            true
+            self.processing_index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Copy the recording referenced by an `:::audio` block into the assets
+    /// folder so the destination note points at a stable file.
+    fn stage_audio_block(&mut self, index: usize) -> Result<()> {
+        let block = &self.processing_blocks[index];
+        if block.block_type != BlockType::Audio || self.dry_run {
+            return Ok(());
+        }
+        let Some(path) = audio_block_path(&block.content) else {
+            return Ok(());
+        };
+        let path = expand_tilde(path);
+        let source = if path.is_absolute() {
+            path
+        } else {
+            self.current_note_dir().join(path)
+        };
+        let copy = copy_to_assets(&self.data_dir, &source)?;
+        let content = replace_audio_path(&block.content, &copy.to_string_lossy());
+        self.processing_blocks[index].content = content;
+        Ok(())
+    }
+
+    pub fn finish_processing(&mut self) {
+        // Collect ranges of Sent blocks to mark as processed
+        let mut sent_ranges: Vec<(usize, usize)> = self
+            .processing_blocks
+            .iter()
+            .filter(|b| b.status == ProcessingStatus::Sent)
+            .filter_map(|b| b.original_range)
+            .collect();
+
+        // Sort by start offset descending so we can replace from end to start
+        // without invalidating earlier offsets
+        sent_ranges.sort_by_key(|r| std::cmp::Reverse(r.0));
+
+        if !sent_ranges.is_empty() && !self.dry_run {
+            self.freeze_current_note(SnapshotReason::Processing);
+            let style =
+                ProcessedStyle::from_name(&self.config.general.processed_style).unwrap_or_default();
+            let mut content = self.buffer.to_string();
+            for (start, end) in &sent_ranges {
+                let marked = style.mark(&content[*start..*end]);
+                content.replace_range(*start..*end, &marked);
+            }
+            self.buffer = TextBuffer::from_string(&content);
+            self.dirty = true;
+        }
+
+        // Build summary message
+        let sent_count = self
+            .processing_blocks
+            .iter()
+            .filter(|b| b.status == ProcessingStatus::Sent)
+            .count();
+        let skipped_count = self
+            .processing_blocks
+            .iter()
+            .filter(|b| b.status == ProcessingStatus::Skipped)
+            .count();
+        let failed_count = self
+            .processing_blocks
+            .iter()
+            .filter(|b| b.status == ProcessingStatus::Failed)
+            .count();
+
+        let summary = if self.dry_run {
+            format!(
+                "Dry run: {} would be sent, {} skipped, {} failed",
+                sent_count, skipped_count, failed_count
+            )
+        } else {
+            format!(
+                "Processing complete: {} sent, {} skipped, {} failed",
+                sent_count, skipped_count, failed_count
+            )
+        };
+
+        self.processing_blocks.clear();
+        self.processing_index = 0;
+        self.set_mode(AppMode::Normal);
+        self.set_message(&summary);
+    }
+
+    /// `:lint-blocks`: list the smart blocks of the current note that would
+    /// not be sent as written in the locations panel.
+    pub fn lint_blocks(&mut self) {
+        let Some(note) = self
+            .current_note
+            .as_ref()
+            .filter(|_| self.view == View::Editor)
+        else {
+            self.set_message("No note open");
+            return;
+        };
+        let locations: Vec<Location> = lint_blocks(&self.buffer.to_string())
+            .into_iter()
+            .map(|issue| Location {
+                note_id: note.id.clone(),
+                row: issue.row,
+                col: 0,
+                text: issue.message,
+            })
+            .collect();
+        if locations.is_empty() {
+            self.set_message("No block problems");
+        } else {
+            self.set_quickfix(QuickfixList::new("Block problems", locations));
+        }
+    }
+
+    /// Replace the locations list, showing it in the panel with the
+    /// keyboard.
+    pub fn set_quickfix(&mut self, list: QuickfixList) {
+        self.quickfix = Some(list);
+        self.show_quickfix = true;
+        self.quickfix_focused = true;
+    }
+
+    /// `:copen`: show the locations panel and give it the keyboard.
+    pub fn open_quickfix(&mut self) {
+        if self.quickfix.is_none() {
+            self.set_message("No locations");
+            return;
+        }
+        self.show_quickfix = true;
+        self.quickfix_focused = true;
+    }
+
+    /// `:cclose` / `q` in the panel: hide the locations panel. `]q` / `[q`
+    /// still walk the list.
+    pub fn close_quickfix(&mut self) {
+        self.show_quickfix = false;
+        self.quickfix_focused = false;
+    }
+
+    pub fn move_quickfix(&mut self, down: bool) {
+        if let Some(list) = &mut self.quickfix {
+            let current = list.current();
+            list.select(if down {
+                current + 1
+            } else {
+                current.saturating_sub(1)
+            });
+        }
+    }
+
+    /// Give the keyboard back to the editor, first jumping to the selected
+    /// location when `jump`. The panel stays shown.
+    pub fn leave_quickfix(&mut self, jump: bool) {
+        self.quickfix_focused = false;
+        if jump {
+            self.jump_to_location();
+        }
+    }
+
+    /// `]q` / `[q`: jump `count` locations forward or back.
+    pub fn cycle_location(&mut self, forward: bool, count: usize) {
+        let Some(list) = &mut self.quickfix else {
+            self.set_message("No locations");
+            return;
+        };
+        if !list.step(forward, count) {
+            self.set_message("No more locations");
+            return;
+        }
+        self.jump_to_location();
+    }
+
+    /// Move the cursor to the current location, opening its note first if
+    /// another one is open.
+    fn jump_to_location(&mut self) {
+        let Some(location) = self.quickfix.as_ref().and_then(|l| l.current_location()) else {
+            return;
+        };
+        let Location {
+            note_id, row, col, ..
+        } = location.clone();
+        if self.current_note.as_ref().is_none_or(|n| n.id != note_id) {
+            let note = self
+                .draft_list
+                .notes()
+                .iter()
+                .chain(self.archive_list.notes())
+                .find(|n| n.id == note_id)
+                .cloned();
+            let Some(note) = note else {
+                self.set_message("Note not found");
+                return;
+            };
+            self.switch_buffer(note);
+            self.set_view(View::Editor);
+            self.set_mode(AppMode::Normal);
+        }
+        self.record_jump();
+        self.buffer.set_cursor(row, col);
+        self.center_cursor();
+    }
+
+    /// `:syncreminders`: check off the reminders sent from the current note
+    /// that are completed in the Reminders app.
+    pub fn sync_reminders(&mut self) {
+        if self.view != View::Editor || self.current_note.is_none() {
+            self.set_message("No note open");
+            return;
+        }
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let completed = match self
+            .dispatcher
+            .completed_reminders(&self.config.destinations)
+        {
+            Ok(completed) => completed,
+            Err(e) => {
+                self.set_message(&format!("Reminder sync failed: {}", e));
+                return;
+            }
+        };
+        let (content, count) = check_off_reminders(&self.buffer.to_string(), &completed);
+        if count == 0 {
+            self.set_message("No completed reminders");
+            return;
+        }
+        self.buffer.replace_content(&content);
+        self.dirty = true;
+        self.set_message(&format!("{} reminder(s) checked off", count));
+    }
+
+    /// `:agenda`: put today's calendar events in the current note, in place
+    /// of its `{{agenda}}` line if it has one, else below the cursor.
+    pub fn insert_agenda(&mut self) {
+        if self.view != View::Editor || self.current_note.is_none() {
+            self.set_message("No note open");
+            return;
+        }
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let events = match self.dispatcher.todays_events(&self.config.destinations) {
+            Ok(events) => events,
+            Err(e) => {
+                self.set_message(&format!("Agenda failed: {}", e));
+                return;
+            }
+        };
+        let agenda = format_agenda(&events, chrono::Local::now().date_naive());
+        self.freeze_current_note(SnapshotReason::Agenda);
+        match fill_agenda_placeholder(&self.buffer.to_string(), &agenda) {
+            Some(content) => self.buffer.replace_content(&content),
+            None => {
+                self.buffer.save_undo_snapshot();
+                self.buffer.paste_line_below(&agenda);
+            }
+        }
+        self.dirty = true;
+        self.set_message(&format!("{} event(s) today", events.len()));
+    }
+
+    /// Read the address book for `@mention` completion, once.
+    pub fn load_contacts(&mut self) {
+        if self.contacts.is_some() {
+            return;
+        }
+        let contacts = self.dispatcher.contact_names().unwrap_or_else(|e| {
+            self.set_message(&format!("Contacts unavailable: {}", e));
+            Vec::new()
+        });
+        self.contacts = Some(contacts);
+    }
+
+    /// The completion popup for the text before the cursor in Insert mode:
+    /// what it completes, the column the replaced text starts at and the
+    /// candidates. Mentions wait for the contacts, read on the first `@`.
+    pub fn completions(&self) -> Option<(CompletionKind, usize, Vec<String>)> {
+        if self.view != View::Editor || self.mode != AppMode::Insert {
+            return None;
+        }
+        let (_, col) = self.buffer.cursor_position();
+        let (kind, start, typed) = completion_at(
+            self.buffer.current_line_content(),
+            col,
+            self.word_completion,
+        )?;
+        let owned: Vec<String> = match kind {
+            CompletionKind::Mention => Vec::new(),
+            CompletionKind::Tag => tag_counts(self.draft_list.notes())
+                .into_iter()
+                .map(|(tag, _)| tag)
+                .collect(),
+            CompletionKind::Link => self
+                .draft_list
+                .notes()
+                .iter()
+                .chain(self.archive_list.notes())
+                .map(|note| note.title.clone())
+                .collect(),
+            CompletionKind::Word => buffer_words(&self.buffer.to_string()),
+        };
+        let items = match kind {
+            CompletionKind::Mention => {
+                mention_matches(self.contacts.as_deref()?, &typed, COMPLETION_LIMIT)
+            }
+            _ => completion_matches(&owned, &typed, COMPLETION_LIMIT),
+        };
+        let items: Vec<String> = items.into_iter().map(str::to_string).collect();
+        (!items.is_empty()).then_some((kind, start, items))
+    }
+
+    pub fn move_completion(&mut self, forward: bool) {
+        let count = self.completions().map_or(0, |(_, _, items)| items.len());
+        if count > 0 {
+            self.completion_selected = if forward {
+                (self.completion_selected + 1) % count
+            } else {
+                (self.completion_selected + count - 1) % count
+            };
+        }
+    }
+
+    /// Ctrl-N in Insert mode with no popup shown: offer the words of the
+    /// note for the word before the cursor. Returns false when there is no
+    /// word there.
+    pub fn start_word_completion(&mut self) -> bool {
+        let (_, col) = self.buffer.cursor_position();
+        if word_at(self.buffer.current_line_content(), col).is_none() {
+            return false;
+        }
+        self.word_completion = true;
+        self.completion_selected = 0;
+        if self.completions().is_none() {
+            self.word_completion = false;
+            self.set_message("No completions");
+        }
+        true
+    }
+
+    /// Replace the text being completed with the selected candidate:
+    /// `@Full Name`, `#tag`, `[[Title]]` or the word. Returns false when the
+    /// popup is not shown.
+    pub fn accept_completion(&mut self) -> bool {
+        let Some((kind, start, items)) = self.completions() else {
+            return false;
+        };
+        let item = &items[self.completion_selected.min(items.len() - 1)];
+        let (row, col) = self.buffer.cursor_position();
+        let line = self.buffer.current_line_content().to_string();
+        let graphemes: Vec<&str> = line.graphemes(true).collect();
+        let rest = graphemes[col..].concat();
+        // Inside an existing `[[…]]` the closing brackets are not doubled
+        let rest = match kind {
+            CompletionKind::Link => rest.strip_prefix("]]").unwrap_or(&rest),
+            _ => &rest,
+        };
+        let text = kind.text(item);
+        let completed = format!("{}{}{}", graphemes[..start].concat(), text, rest);
+        self.buffer.replace_line(row, completed);
+        self.buffer
+            .set_cursor(row, start + text.graphemes(true).count());
+        self.completion_selected = 0;
+        self.word_completion = false;
+        self.dirty = true;
+        true
+    }
+
+    /// Tab in Insert mode: expand the `[snippets]` trigger just typed, or
+    /// jump to the next tabstop of the last expansion. Returns false when
+    /// there is neither, so Tab indents as usual.
+    pub fn snippet_tab(&mut self) -> bool {
+        let (row, col) = self.buffer.cursor_position();
+        let line = self.buffer.current_line_content().to_string();
+        if let Some((start, body)) = snippet_trigger(&line, col, &self.config.snippets) {
+            let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+            let snippet = Snippet::parse(body, &indent);
+            self.buffer.delete_range(row, start, row, col);
+            self.buffer.set_cursor(row, start);
+            self.buffer.insert_text(&snippet.text);
+            let mut stops = snippet
+                .stops
+                .into_iter()
+                .map(|(r, c)| (row + r, if r == 0 { start + c } else { c }));
+            let (first_row, first_col) = stops.next().unwrap_or((row, start));
+            self.buffer.set_cursor(first_row, first_col);
+            let session =
+                SnippetSession::new(stops.collect(), (first_row, first_col), &self.buffer);
+            self.snippet = (!session.is_done()).then_some(session);
+            self.dirty = true;
+            return true;
+        }
+        let Some(session) = &mut self.snippet else {
+            return false;
+        };
+        let next = session.next(&self.buffer);
+        if session.is_done() {
+            self.snippet = None;
+        }
+        let Some((row, col)) = next else {
+            return false;
+        };
+        self.buffer.set_cursor(row, col);
+        true
+    }
+
+    /// Record blocks instead of sending them, for `--dry-run`.
+    pub fn enable_dry_run(&mut self) {
+        self.dispatcher = Box::new(RecordingDispatcher::new());
+        self.dry_run = true;
+    }
+
+    /// Fake sends with delays and occasional failures, for
+    /// `--simulate-dispatch`. Like a dry run, the note is left unchanged.
+    pub fn enable_simulated_dispatch(&mut self) {
+        self.dispatcher = Box::new(SimulatedDispatcher::new());
+        self.dry_run = true;
+    }
+
+    pub fn refresh_lists(&mut self) -> Result<()> {
+        let drafts = load_all_drafts(&self.data_dir, false)?;
+        self.draft_list.update_notes(drafts);
+        self.reload_archives()?;
+        self.track_note_contents();
+        self.sync_search_index();
+        Ok(())
+    }
+
+    /// `:split-headings`: move each top-level section of the current note
+    /// into a new draft, leaving `[[links]]` to them when `links` is set.
+    pub fn split_note_at_headings(&mut self, links: bool) -> Result<()> {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        if self.current_note.is_none() {
+            self.set_message("No note open");
+            return Ok(());
+        }
+        let Some(split) = split_at_headings(&self.buffer.to_string(), links, Note::extract_title)
+        else {
+            self.set_message("No headings to split at");
+            return Ok(());
+        };
+        self.freeze_current_note(SnapshotReason::Split);
+        self.flush_saves()?;
+        for section in &split.sections {
+            let id = Uuid::new_v4().to_string();
+            let note = Note::new(id, Note::extract_title(section), section.clone());
+            self.file_change_tracker.record_save(&note.id);
+            self.file_change_tracker
+                .record_content(&note.id, &note.content);
+            save_draft(&self.data_dir, &note, self.file_naming())?;
+            self.draft_list.add_note(note.clone());
+            self.search_index.update(&note);
+            self.backlinks.update(&note);
+        }
+        self.buffer.replace_content(&split.remaining);
+        self.dirty = true;
+        self.save_current_note()?;
+        self.refilter_lists();
+        self.set_message(&format!("Split into {} note(s)", split.sections.len()));
+        Ok(())
+    }
+
+    /// `:import PATH`: convert the markdown files (or the Apple Notes / Bear
+    /// export) under `path` into drafts and show the draft list.
+    pub fn import_notes(&mut self, path: &str) -> Result<()> {
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        let summary =
+            match import_into_drafts(&self.data_dir, &expand_tilde(path), self.file_naming()) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    self.set_message(&format!("Import failed: {}", e));
+                    return Ok(());
+                }
+            };
+        self.refresh_lists()?;
+        if summary.notes > 0 {
+            self.set_view(View::DraftList);
+        }
+        self.set_message(&format!(
+            "Imported {} note(s) from {} file(s)",
+            summary.notes, summary.files
+        ));
+        Ok(())
+    }
+
+    /// Run a command entered on the `:` command line. Parse and command
+    /// errors are shown in the status bar.
+    pub fn run_ex_command(&mut self, input: &str) -> Result<()> {
+        let command = match parse_ex_command(input) {
+            Ok(command) => command,
+            Err(e) => {
+                self.set_message(&e.to_string());
+                return Ok(());
+            }
+        };
+        match command {
+            ExCommand::ChangeDataDir(path) => {
+                let dir = match path {
+                    Some(path) => expand_tilde(&path),
+                    None => resolve_data_dir(self.config.general.data_dir.as_deref()),
+                };
+                if let Err(e) = self.switch_data_dir(dir) {
+                    self.set_message(&format!("cd failed: {}", e));
+                }
+            }
+            ExCommand::PrintDataDir => {
+                let dir = self.data_dir.display().to_string();
+                self.set_message(&dir);
+            }
+            ExCommand::SetWrap(on) => {
+                self.soft_wrap = on;
+                self.viewport.set_left(0);
+                self.set_message(if on { "Soft-wrap on" } else { "Soft-wrap off" });
+            }
+            ExCommand::SetIgnoreDiacritics(on) => {
+                self.ignore_diacritics = on;
+                self.set_message(if on {
+                    "Search ignores diacritics"
+                } else {
+                    "Search matches diacritics"
+                });
+            }
+            ExCommand::SetSpell(on) => {
+                self.spell = on;
+                if !on {
+                    self.set_message("Spell checking off");
+                } else if self.spell_dictionary().is_some() {
+                    self.set_message("Spell checking on");
+                } else {
+                    self.no_dictionary_message();
+                }
+            }
+            ExCommand::SetSpellLang(language) => self.set_spell_language(&language)?,
+            ExCommand::CycleBuffer(forward) => self.cycle_buffer(forward, 1),
+            ExCommand::ListBuffers => self.open_buffer_picker(),
+            ExCommand::Backlinks => self.open_backlinks(),
+            ExCommand::History => self.open_snapshot_picker(),
+            ExCommand::Tags => self.open_tag_picker(),
+            ExCommand::AddTag(name) => self.add_tag_to_note(&name)?,
+            ExCommand::Review => self.start_review(),
+            ExCommand::SyncReminders => self.sync_reminders(),
+            ExCommand::Agenda => self.insert_agenda(),
+            ExCommand::Rename(title) => {
+                self.open_rename();
+                if let Some(title) = title
+                    && self.rename_title.is_some()
+                {
+                    self.rename_title = Some(title);
+                    self.close_rename(true)?;
+                }
+            }
+            ExCommand::SyncTitle => self.sync_title()?,
+            ExCommand::GitSync => self.start_sync()?,
+            ExCommand::Export { format: None, .. } => self.open_export_picker(),
+            ExCommand::Export {
+                format: Some(format),
+                path,
+            } => self.export_current_note(format, path.as_deref()),
+            ExCommand::LintBlocks => self.lint_blocks(),
+            ExCommand::SplitHeadings { links } => self.split_note_at_headings(links)?,
+            ExCommand::Import(path) => self.import_notes(&path)?,
+            ExCommand::Today => self.open_today(),
+            ExCommand::New {
+                template: Some(template),
+                title,
+            } => self.new_note_from_template(&template, title.as_deref()),
+            ExCommand::New { template: None, .. } => self.new_note(),
+            ExCommand::Trash => self.set_view(View::Trash),
+            ExCommand::EmptyTrash => self.empty_trash()?,
+            ExCommand::OpenQuickfix => self.open_quickfix(),
+            ExCommand::CloseQuickfix => self.close_quickfix(),
+            ExCommand::Split {
+                dir,
+                title,
+                read_only,
+            } => self.split_pane(dir, title.as_deref(), read_only),
+            ExCommand::ClosePane => self.close_pane(),
+            ExCommand::OnlyPane => self.only_pane(),
+            ExCommand::Reindex => {
+                if let Err(e) = self.rebuild_search_index() {
+                    self.set_message(&format!("Reindex failed: {}", e));
+                }
+            }
+            ExCommand::Substitute(sub) => self.substitute(sub),
+            ExCommand::ReplaceAll(sub) => self.replace_all(sub),
+        }
+        Ok(())
+    }
+
+    /// Run `:s` on the editor buffer. With the `c` flag each match waits for
+    /// an answer in `AppMode::ConfirmReplace`.
+    /// The pattern of `cmd` (the last search when empty) and its compiled
+    /// substitution, or `None` after reporting why there is none.
+    fn compile_substitution(&mut self, cmd: &Substitute) -> Option<(String, Substitution)> {
+        let pattern = if !cmd.pattern.is_empty() {
+            cmd.pattern.clone()
+        } else if !self.search_query.is_empty() {
+            regex::escape(&self.search_query)
         } else {
-            false
+            self.set_message("No previous search pattern");
+            return None;
+        };
+        match Substitution::new(&pattern, &cmd.replacement, cmd.global, cmd.ignore_case) {
+            Ok(sub) => Some((pattern, sub)),
+            Err(e) => {
+                self.set_message(&e.to_string());
+                None
+            }
         }
     }
 
-    pub fn finish_processing(&mut self) {
-        // Collect ranges of Sent blocks for comment wrapping
-        let mut sent_ranges: Vec<(usize, usize)> = self
-            .processing_blocks
+    fn substitute(&mut self, cmd: Substitute) {
+        if self.view != View::Editor {
+            self.set_message("Substitute only works in the editor");
+            return;
+        }
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let Some((pattern, sub)) = self.compile_substitution(&cmd) else {
+            return;
+        };
+
+        let last = self.buffer.line_count().saturating_sub(1);
+        let (start, end) = match cmd.range {
+            LineRange::Current => {
+                let row = self.buffer.cursor_position().0;
+                (row, row)
+            }
+            LineRange::All => (0, last),
+            LineRange::Selection => match self.last_visual_rows {
+                Some(rows) => rows,
+                None => {
+                    self.set_message("No Visual selection");
+                    return;
+                }
+            },
+            LineRange::Lines(first, end) => (first - 1, end - 1),
+        };
+        let (start, end) = (start.min(last), end.min(last));
+
+        if cmd.confirm {
+            let mut session = SubstituteSession::new(sub, start, end);
+            if session.advance(&mut self.buffer) {
+                self.substitution = Some(session);
+                self.set_mode(AppMode::ConfirmReplace);
+                self.set_message("Replace this match? (y/n/a/q/l)");
+            } else {
+                self.set_message(&format!("Pattern not found: {}", pattern));
+            }
+            return;
+        }
+
+        let found = (start..=end).any(|row| sub.matches(&self.buffer.content()[row]));
+        if !found {
+            self.set_message(&format!("Pattern not found: {}", pattern));
+            return;
+        }
+        self.buffer.save_undo_snapshot();
+        let (replacements, lines) = self.buffer.substitute(&sub, start, end);
+        self.dirty = true;
+        self.set_message(&format!(
+            "{} substitution(s) on {} line(s)",
+            replacements, lines
+        ));
+    }
+
+    /// Answer the `:s///c` prompt: `y` replace, `n` skip, `a` replace all
+    /// remaining, `l` replace and stop, `q` stop.
+    pub fn answer_substitution(&mut self, answer: char) {
+        let Some(mut session) = self.substitution.take() else {
+            self.set_mode(AppMode::Normal);
+            return;
+        };
+        let more = match answer {
+            'y' => {
+                self.replace_match(&mut session);
+                session.advance(&mut self.buffer)
+            }
+            'l' => {
+                self.replace_match(&mut session);
+                false
+            }
+            'n' => {
+                session.skip(&self.buffer);
+                session.advance(&mut self.buffer)
+            }
+            'a' => loop {
+                self.replace_match(&mut session);
+                if !session.advance(&mut self.buffer) {
+                    break false;
+                }
+            },
+            _ => false,
+        };
+
+        if more {
+            self.substitution = Some(session);
+        } else {
+            self.set_mode(AppMode::Normal);
+            self.set_message(&format!("{} substitution(s)", session.replaced));
+        }
+    }
+
+    fn replace_match(&mut self, session: &mut SubstituteSession) {
+        // One undo step for the whole run
+        if session.replaced == 0 {
+            self.buffer.save_undo_snapshot();
+        }
+        session.replace(&mut self.buffer);
+        self.dirty = true;
+    }
+
+    /// `:replace-all`: list every draft line `cmd` would change in the
+    /// locations panel, then ask about each one in turn.
+    fn replace_all(&mut self, cmd: Substitute) {
+        if self.is_read_only() {
+            self.set_message("Read-only mode: changes are disabled");
+            return;
+        }
+        let Some((pattern, sub)) = self.compile_substitution(&cmd) else {
+            return;
+        };
+        // Open notes are searched as edited, not as last saved
+        let current_id = self.current_note.as_ref().map(|n| n.id.clone());
+        let texts: Vec<(&str, &str, String)> = self
+            .draft_list
+            .notes()
             .iter()
-            .filter(|b| b.status == ProcessingStatus::Sent)
-            .filter_map(|b| b.original_range)
+            .map(|note| {
+                let text = if current_id.as_deref() == Some(note.id.as_str()) {
+                    self.buffer.to_string()
+                } else if let Some(text) = self.buffers.text(&note.id) {
+                    text.to_string()
+                } else {
+                    note.content.clone()
+                };
+                (note.id.as_str(), note.title.as_str(), text)
+            })
             .collect();
+        let hunks = replace_hunks(
+            texts
+                .iter()
+                .map(|(id, title, text)| (*id, *title, text.as_str())),
+            &sub,
+        );
+        if hunks.is_empty() {
+            self.set_message(&format!("Pattern not found: {}", pattern));
+            return;
+        }
 
-        // Sort by start offset descending so we can replace from end to start
-        // without invalidating earlier offsets
-        sent_ranges.sort_by(|a, b| b.0.cmp(&a.0));
+        let locations = hunks
+            .iter()
+            .map(|hunk| Location {
+                note_id: hunk.note_id.clone(),
+                row: hunk.row,
+                col: 0,
+                text: format!(
+                    "{}: {} → {}",
+                    hunk.title,
+                    hunk.before.trim(),
+                    hunk.after.trim()
+                ),
+            })
+            .collect();
+        self.set_quickfix(QuickfixList::new("Replace preview", locations));
+        self.quickfix_focused = false;
+        self.replace_all = Some(ReplaceAllSession::new(hunks));
+        self.jump_to_location();
+        self.set_mode(AppMode::ConfirmReplace);
+        self.set_message("Replace this line? (y/n/a/q/l)");
+    }
+
+    /// Answer the `:replace-all` prompt: `y` take the line, `n` leave it,
+    /// `a` take it and all the rest, `l` take it and stop, `q` stop. Taken
+    /// lines are written once the run ends.
+    pub fn answer_replace_all(&mut self, answer: char) -> Result<()> {
+        let Some(mut session) = self.replace_all.take() else {
+            self.set_mode(AppMode::Normal);
+            return Ok(());
+        };
+        let more = match answer {
+            'y' => session.answer(true),
+            'n' => session.answer(false),
+            'l' => {
+                session.answer(true);
+                false
+            }
+            'a' => {
+                session.accept_rest();
+                false
+            }
+            _ => false,
+        };
+        if more {
+            if let Some(list) = &mut self.quickfix {
+                list.select(session.current());
+            }
+            self.replace_all = Some(session);
+            self.jump_to_location();
+            return Ok(());
+        }
+        self.set_mode(AppMode::Normal);
+        self.finish_replace_all(&session)
+    }
+
+    /// Write the taken lines of a `:replace-all`, one undo step per note.
+    /// A line that changed since the preview is left alone.
+    fn finish_replace_all(&mut self, session: &ReplaceAllSession) -> Result<()> {
+        let (mut lines, mut notes) = (0, 0);
+        for (id, hunks) in session.accepted_by_note() {
+            let Some(note) = self.draft_list.notes().iter().find(|n| n.id == id).cloned() else {
+                continue;
+            };
+            self.switch_buffer(note);
+            if self.current_note.as_ref().is_none_or(|n| n.id != id) {
+                continue;
+            }
+            let mut changed = 0;
+            for hunk in hunks {
+                if self.buffer.content().get(hunk.row) != Some(&hunk.before) {
+                    continue;
+                }
+                if changed == 0 {
+                    self.freeze_current_note(SnapshotReason::Replace);
+                    self.buffer.save_undo_snapshot();
+                }
+                self.buffer.replace_line(hunk.row, hunk.after.clone());
+                changed += 1;
+            }
+            if changed > 0 {
+                self.dirty = true;
+                self.save_current_note()?;
+                lines += changed;
+                notes += 1;
+            }
+        }
+        self.quickfix = None;
+        self.close_quickfix();
+        self.set_view(View::Editor);
+        self.set_message(&format!("{} line(s) changed in {} note(s)", lines, notes));
+        Ok(())
+    }
+
+    /// Switch to another data directory without restarting: pending writes
+    /// are finished, the watcher and lists are torn down, and the new
+    /// location is created if needed and loaded.
+    pub fn switch_data_dir(&mut self, dir: PathBuf) -> Result<()> {
+        if self.dirty {
+            self.save_current_note()?;
+        }
+        self.flush_saves()?;
+        ensure_data_dirs(&dir)?;
+        let integrity_message = Self::repair_data_dir(&dir);
+        let drafts = load_all_drafts(&dir, false)?;
+
+        self.finish_writing_session()?;
+        if let Err(e) = self.flush_writing_stats() {
+            self.set_message(&format!("Writing stats not saved: {}", e));
+        }
+        self.save_search_index()?;
+        self.stop_file_watcher();
+
+        self.draft_list = DraftList::new(drafts);
+        self.draft_list.set_sort_order(self.list_sort());
+        self.search_index = SearchIndex::from_snapshot(load_search_index(&dir).unwrap_or_default());
+        self.search_index.sync(self.draft_list.notes());
+        self.backlinks = Backlinks::new();
+        self.review = None;
+        self.archive_list = ArchiveList::default();
+        self.archives_loaded = false;
+        let (buffer, current_note) = match self.draft_list.selected_note() {
+            Some(note) => (TextBuffer::from_string(&note.content), Some(note.clone())),
+            None => (TextBuffer::new(), None),
+        };
+        self.buffers.clear();
+        if let Some(note) = &current_note {
+            self.buffers.activate(note);
+        }
+        self.buffer = buffer;
+        self.current_note = current_note;
+        self.dirty = false;
+        self.visual_mode = None;
+        self.search_query.clear();
+        self.pending_external_reload = None;
+        self.file_change_tracker = FileChangeTracker::new();
+        self.writing_history = load_writing_history(&dir).unwrap_or_default();
+        self.writing_session = WritingSession::new(chrono::Local::now(), &self.writing_history);
+        self.stats_dirty = false;
+        self.marks = load_marks(&dir).unwrap_or_default();
+        self.layout = load_layout(&dir).unwrap_or_default();
+        self.viewport.reset();
+        self.panes = PaneLayout::new();
+        self.folds.clear();
+        self.data_dir = dir;
+        match self.view {
+            View::ArchiveList => self.load_archives()?,
+            View::Trash => self.load_trash()?,
+            _ => {}
+        }
+
+        match integrity_message {
+            Some(message) => self.set_message(&message),
+            None => self.set_message(&format!("Data directory: {}", self.data_dir.display())),
+        }
+        self.start_file_watcher();
+        Ok(())
+    }
+
+    /// Quarantine damaged note files before loading `dir`. Returns the
+    /// message to show when anything was moved or the scan failed.
+    fn repair_data_dir(dir: &Path) -> Option<String> {
+        match scan_and_repair(dir) {
+            Ok(report) => report.summary(),
+            Err(e) => Some(format!("Integrity scan failed: {}", e)),
+        }
+    }
+
+    /// Start watching the data directory if `file_watch` is enabled. Failures
+    /// are reported in the status bar; the app keeps working without it.
+    pub fn start_file_watcher(&mut self) {
+        if !self.config.general.file_watch || self.watcher.is_some() {
+            return;
+        }
+        self.track_note_contents();
+        match file_watcher::start_watcher(
+            &self.data_dir.join("drafts"),
+            &self.data_dir.join("archives"),
+            self.config.general.file_watch_debounce_ms,
+        ) {
+            Ok(handle) => self.watcher = Some(handle),
+            Err(e) => self.set_message(&format!("File watcher failed: {}", e)),
+        }
+    }
 
-        if !sent_ranges.is_empty() {
-            let mut content = self.buffer.to_string();
-            for (start, end) in &sent_ranges {
-                let block_text = &content[*start..*end];
-                let commented = format!("<!-- {} -->", block_text);
-                content.replace_range(*start..*end, &commented);
-            }
-            self.buffer = TextBuffer::from_string(&content);
-            self.dirty = true;
+    /// Record the loaded content of every note so identical rewrites on
+    /// disk are ignored.
+    fn track_note_contents(&mut self) {
+        let notes = self.draft_list.notes().iter();
+        self.file_change_tracker
+            .record_notes(notes.chain(self.archive_list.notes()));
+    }
+
+    pub fn stop_file_watcher(&mut self) {
+        if let Some(watcher) = self.watcher.take() {
+            watcher.shutdown();
         }
+    }
 
-        // Build summary message
-        let sent_count = self
-            .processing_blocks
-            .iter()
-            .filter(|b| b.status == ProcessingStatus::Sent)
-            .count();
-        let skipped_count = self
-            .processing_blocks
-            .iter()
-            .filter(|b| b.status == ProcessingStatus::Skipped)
-            .count();
-        let failed_count = self
-            .processing_blocks
-            .iter()
-            .filter(|b| b.status == ProcessingStatus::Failed)
-            .count();
+    /// Tear the watcher down and start a fresh one for the current data dir.
+    pub fn restart_file_watcher(&mut self) {
+        self.stop_file_watcher();
+        if !self.config.general.file_watch {
+            self.set_message("File watching is disabled (file_watch = false)");
+            return;
+        }
+        self.start_file_watcher();
+        if self.watcher.is_some() {
+            self.set_message("File watcher restarted");
+        }
+    }
 
-        let summary = format!(
-            "Processing complete: {} sent, {} skipped, {} failed",
-            sent_count, skipped_count, failed_count
-        );
+    /// Apply pending file watcher events without blocking.
+    pub fn poll_file_events(&mut self) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let events: Vec<FileEvent> = watcher.receiver.try_iter().collect();
+        if !events.is_empty() {
+            self.redraw = true;
+        }
+        for event in events {
+            if let FileEvent::Error(e) = &event {
+                self.set_message(&format!("File watcher error: {} (Space+R to restart)", e));
+            } else if let Err(e) = self.handle_file_event(event) {
+                self.set_message(&format!("File event error: {}", e));
+            }
+        }
+    }
 
-        self.processing_blocks.clear();
-        self.processing_index = 0;
-        self.set_mode(AppMode::Normal);
-        self.set_message(&summary);
+    /// Start watching `config_file` for edits. Failures are reported in the
+    /// status bar; the config then only loads at startup.
+    pub fn start_config_watcher(&mut self) {
+        let Some(path) = &self.config_file else {
+            return;
+        };
+        match file_watcher::start_config_watcher(path, self.config.general.file_watch_debounce_ms) {
+            Ok(handle) => self.config_watcher = Some(handle),
+            Err(e) => self.set_message(&format!("Config watcher failed: {}", e)),
+        }
     }
 
-    pub fn refresh_lists(&mut self) -> Result<()> {
-        let drafts = load_all_drafts(&self.data_dir, false)?;
-        let archives = load_all_drafts(&self.data_dir, true)?;
-        self.draft_list.update_notes(drafts);
-        self.archive_list.update_notes(archives);
-        Ok(())
+    /// Reload the config if the watcher saw it change, without blocking.
+    pub fn poll_config_events(&mut self) {
+        let Some(watcher) = &self.config_watcher else {
+            return;
+        };
+        let mut changed = false;
+        for event in watcher.receiver.try_iter().collect::<Vec<_>>() {
+            match event {
+                FileEvent::Error(e) => self.set_message(&format!("Config watcher error: {}", e)),
+                _ => changed = true,
+            }
+        }
+        if changed {
+            self.reload_config();
+        }
     }
 
     pub fn handle_file_event(&mut self, event: FileEvent) -> Result<()> {
@@ -396,16 +3609,21 @@ impl App {
                 if is_current {
                     if self.dirty {
                         self.pending_external_reload = Some(id);
-                        self.set_message(
-                            "File changed externally. Ctrl+L to reload, or save to keep yours.",
-                        );
+                        self.set_message(if self.config.general.reload_merge {
+                            "File changed externally. Ctrl+L to merge, or save to keep yours."
+                        } else {
+                            "File changed externally. Ctrl+L to reload, or save to keep yours."
+                        });
                     } else {
                         self.reload_current_note_from_disk()?;
-                        self.set_message("File reloaded");
                     }
                 } else if let Ok(updated_note) = load_draft(&self.data_dir, &id, is_archived) {
+                    self.buffers.discard_text(&id);
+                    self.buffers.update_note(&updated_note);
+                    self.file_change_tracker
+                        .record_content(&updated_note.id, &updated_note.content);
                     if is_archived {
-                        self.archive_list.update_single_note(updated_note);
+                        self.archive_list.update_note(&updated_note);
                     } else {
                         self.draft_list.update_note(&updated_note);
                     }
@@ -415,6 +3633,9 @@ impl App {
                 self.refresh_lists()?;
 
                 if let FileChangeAction::DeletedNote { ref id, .. } = action {
+                    self.buffers.remove(id);
+                    self.search_index.remove(id);
+                    self.backlinks.remove(id);
                     let is_current = self.current_note.as_ref().is_some_and(|n| n.id == *id);
                     if is_current {
                         self.buffer = TextBuffer::new();
@@ -431,29 +3652,162 @@ impl App {
         Ok(())
     }
 
+    /// `Space g` / `:sync`: save, then commit the data directory and pull
+    /// and push its remote on a background thread (`git_sync`).
+    pub fn start_sync(&mut self) -> Result<()> {
+        if !self.config.sync.enabled {
+            self.set_message("Git sync is off (set enabled = true under [sync])");
+            return Ok(());
+        }
+        if self.read_only {
+            self.set_message("Read-only mode: changes are disabled");
+            return Ok(());
+        }
+        if self.sync_job.is_some() {
+            self.set_message("Sync already running");
+            return Ok(());
+        }
+        if self.dirty {
+            self.save_current_note()?;
+        }
+        self.flush_saves()?;
+        let (tx, rx) = mpsc::channel();
+        let dir = self.data_dir.clone();
+        let remote = self.config.sync.remote.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(git_sync(&dir, &remote));
+        });
+        self.sync_job = Some(rx);
+        self.set_message("Syncing...");
+        Ok(())
+    }
+
+    /// Start a sync once `sync.interval_minutes` have passed, and apply a
+    /// finished one without blocking. Files the pull changed go through
+    /// `handle_file_event` like watcher events, so a clean buffer reloads
+    /// (conflict markers and all) and a dirty one is offered Ctrl+L.
+    pub fn poll_sync(&mut self) -> Result<()> {
+        let interval = Duration::from_secs(self.config.sync.interval_minutes * 60);
+        if self.config.sync.enabled
+            && !interval.is_zero()
+            && self.sync_job.is_none()
+            && self.last_sync.elapsed() >= interval
+        {
+            self.start_sync()?;
+        }
+        let Some(job) = &self.sync_job else {
+            return Ok(());
+        };
+        let result = match job.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return Ok(()),
+            Err(mpsc::TryRecvError::Disconnected) => Err(anyhow!("sync stopped")),
+        };
+        self.apply_sync(result)
+    }
+
+    /// Block until a running sync is done, e.g. before quitting, so git is
+    /// never cut off halfway.
+    pub fn wait_for_sync(&mut self) -> Result<()> {
+        let Some(job) = &self.sync_job else {
+            return Ok(());
+        };
+        let result = job.recv().unwrap_or_else(|_| Err(anyhow!("sync stopped")));
+        self.apply_sync(result)
+    }
+
+    fn apply_sync(&mut self, result: Result<SyncOutcome>) -> Result<()> {
+        self.sync_job = None;
+        self.last_sync = std::time::Instant::now();
+        self.redraw = true;
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.set_message(&format!("Sync failed: {}", e));
+                return Ok(());
+            }
+        };
+        let pending = self.pending_external_reload.clone();
+        let archives = self.data_dir.join("archives");
+        for path in outcome.changed {
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            if let Some(id) = note_id_for_path(&path) {
+                self.file_change_tracker.forget_save(&id);
+            }
+            let is_archived = path.starts_with(&archives);
+            let event = if path.exists() {
+                FileEvent::Modified(path, is_archived)
+            } else {
+                FileEvent::Removed(path, is_archived)
+            };
+            self.handle_file_event(event)?;
+        }
+        // A dirty buffer's "changed externally" prompt matters more
+        if self.pending_external_reload != pending {
+            return Ok(());
+        }
+        if !outcome.conflicts.is_empty() {
+            self.set_message(&format!(
+                "Synced with conflicts in {} file(s)",
+                outcome.conflicts.len()
+            ));
+        } else if outcome.pushed {
+            self.set_message("Synced");
+        } else if outcome.committed {
+            self.set_message("Changes committed (no remote to push to)");
+        } else {
+            self.set_message("Nothing to sync");
+        }
+        Ok(())
+    }
+
     pub fn reload_current_note_from_disk(&mut self) -> Result<()> {
+        self.flush_saves()?;
         if let Some(ref note) = self.current_note {
             let id = note.id.clone();
             let is_archived = note.is_archived;
+            let saved_content = note.content.clone();
             match load_draft(&self.data_dir, &id, is_archived) {
                 Ok(reloaded) => {
+                    self.file_change_tracker
+                        .record_content(&reloaded.id, &reloaded.content);
                     let current_content = self.buffer.to_string();
                     if reloaded.content == current_content {
                         // Content unchanged (e.g. self-save detected by file watcher).
                         // Update metadata only; keep buffer and cursor intact.
                         self.current_note = Some(reloaded.clone());
                         self.pending_external_reload = None;
+                        self.set_message("File reloaded");
+                    } else if self.dirty && self.config.general.reload_merge {
+                        // Keep local edits: merge them onto the disk version,
+                        // using the last saved content as the common base
+                        let merged =
+                            merge_three_way(&saved_content, &current_content, &reloaded.content);
+                        self.buffer.replace_content(&merged.text);
+                        self.dirty = merged.text != reloaded.content;
+                        self.current_note = Some(reloaded.clone());
+                        self.pending_external_reload = None;
+                        if merged.conflicts > 0 {
+                            self.set_message(&format!(
+                                "Merged with {} conflict(s): resolve the <<<<<<< markers",
+                                merged.conflicts
+                            ));
+                        } else {
+                            self.set_message("Merged external changes");
+                        }
                     } else {
-                        // Genuine external edit: replace buffer but preserve cursor
-                        let (old_row, old_col) = self.buffer.cursor_position();
-                        self.buffer = TextBuffer::from_string(&reloaded.content);
-                        self.buffer.set_cursor(old_row, old_col);
+                        // Genuine external edit: replace the buffer as an undoable
+                        // change so an unwanted reload can be taken back
+                        self.buffer.replace_content(&reloaded.content);
                         self.current_note = Some(reloaded.clone());
                         self.dirty = false;
                         self.pending_external_reload = None;
+                        self.set_message("File reloaded");
                     }
                     if is_archived {
-                        self.archive_list.update_single_note(reloaded);
+                        self.archive_list.update_note(&reloaded);
                     } else {
                         self.draft_list.update_note(&reloaded);
                     }
@@ -473,19 +3827,484 @@ impl App {
         let inner_width = area_width.saturating_sub(2);
         let inner_height = area_height.saturating_sub(2);
 
-        let content = self.buffer.to_string();
-        let lines: Vec<String> = content.lines().map(String::from).collect();
-        let vpos = wrap_calc::visual_cursor_position(&lines, cursor_row, cursor_col, inner_width);
+        let folded = self.folded_lines();
+        let vpos = wrap_calc::visual_cursor_position(
+            &folded.as_strs(),
+            folded.display_row(cursor_row),
+            cursor_col,
+            self.wrap_width(inner_width),
+        );
         let cursor_display_row = vpos.rows_before + vpos.wrap_row;
+        self.viewport.follow(
+            cursor_display_row,
+            self.wrap_width(inner_width),
+            inner_height,
+        )
+    }
+
+    /// Ctrl-D / Ctrl-U: scroll the view and move the cursor by half a screen,
+    /// `count` times.
+    pub fn scroll_half_page(&mut self, down: bool, count: usize) {
+        let (_, height) = self.viewport.size();
+        let rows = (height / 2).max(1) as usize * count;
+        for _ in 0..rows {
+            if down {
+                self.buffer.move_down();
+            } else {
+                self.buffer.move_up();
+            }
+        }
+        self.scroll_view(down, rows);
+        self.keep_cursor_in_view();
+    }
+
+    /// Ctrl-F / Ctrl-B: scroll the view a screen, less two rows of overlap,
+    /// `count` times. When the view cannot move, the cursor goes to the
+    /// first or last line instead.
+    pub fn scroll_page(&mut self, down: bool, count: usize) {
+        let (_, height) = self.viewport.size();
+        let rows = height.saturating_sub(2).max(1) as usize * count;
+        if self.scroll_view(down, rows) {
+            self.keep_cursor_in_view();
+        } else {
+            let row = if down {
+                self.buffer.line_count() - 1
+            } else {
+                0
+            };
+            self.buffer.set_cursor(row, 0);
+            self.buffer.move_to_first_non_blank();
+        }
+    }
+
+    /// Move the view `rows` display rows, returning whether it moved.
+    fn scroll_view(&mut self, down: bool, rows: usize) -> bool {
+        let before = self.viewport.top();
+        let rows = rows.min(i32::MAX as usize) as i32;
+        self.viewport
+            .scroll_by(if down { rows } else { -rows }, self.max_scroll_top());
+        self.viewport.top() != before
+    }
+
+    /// After the view scrolled, move the cursor onto a line inside it, clear
+    /// of the scroll-off margins, so the view stays where it is.
+    fn keep_cursor_in_view(&mut self) {
+        use crate::atoms::widgets::wrap_calc;
+
+        let (width, height) = self.viewport.size();
+        let top = self.viewport.top();
+        let off = SCROLL_OFF.min(height.saturating_sub(1) / 2);
+        let folded = self.folded_lines();
+        let lines = folded.as_strs();
+        let (cursor_row, cursor_col) = self.buffer.cursor_position();
+        let vpos = wrap_calc::visual_cursor_position(
+            &lines,
+            folded.display_row(cursor_row),
+            cursor_col,
+            width,
+        );
+        let display_row = vpos.rows_before + vpos.wrap_row;
+
+        let first = if top == 0 { 0 } else { top + off };
+        let last = (top + height).saturating_sub(off + 1).max(first);
+        let target = if display_row < first {
+            first
+        } else if display_row > last {
+            last
+        } else {
+            return;
+        };
+        let row = folded.buffer_row(wrap_calc::line_at_display_row(&lines, target, width));
+        self.buffer.set_cursor(row, 0);
+        self.buffer.move_to_first_non_blank();
+    }
+
+    /// `zz`, `zt`, `zb`: move the cursor row to the center, top or bottom
+    /// of the view.
+    pub fn align_view(&self, align: ViewAlign) {
+        self.viewport.align(align);
+    }
+
+    /// Highest top row that still fills the editor with text.
+    fn max_scroll_top(&self) -> u16 {
+        let (width, height) = self.viewport.size();
+        let total: u16 = self
+            .folded_lines()
+            .lines
+            .iter()
+            .map(|line| crate::atoms::widgets::wrap_calc::display_rows_for_line(line, width))
+            .fold(0, u16::saturating_add);
+        total.saturating_sub(height)
+    }
+
+    /// Width the editor wraps lines at, given its inner width.
+    pub fn wrap_width(&self, inner_width: u16) -> u16 {
+        if self.soft_wrap {
+            inner_width
+        } else {
+            crate::atoms::widgets::wrap_calc::NO_WRAP
+        }
+    }
+
+    /// First visible display column of the editor. Always 0 with soft-wrap;
+    /// otherwise the view scrolls sideways just far enough to show the
+    /// cursor.
+    pub fn horizontal_scroll(&self, area_width: u16) -> u16 {
+        use crate::atoms::widgets::wrap_calc;
 
-        if inner_height == 0 {
+        let inner_width = area_width.saturating_sub(2);
+        if self.soft_wrap || inner_width == 0 {
             return 0;
         }
+        let (cursor_row, cursor_col) = self.buffer.cursor_position();
+        let line = self
+            .buffer
+            .content()
+            .get(cursor_row)
+            .map_or("", String::as_str);
+        let col = wrap_calc::visual_cursor_position(&[line], 0, cursor_col, wrap_calc::NO_WRAP).col;
+        let cell_width = line
+            .graphemes(true)
+            .nth(cursor_col)
+            .map_or(1, |g| g.width().max(1)) as u16;
+
+        let mut left = self.viewport.left();
+        if col < left {
+            left = col;
+        } else if col + cell_width > left + inner_width {
+            left = (col + cell_width).saturating_sub(inner_width);
+        }
+        self.viewport.set_left(left);
+        left
+    }
+
+    /// Words written and the `word_goal` frontmatter target for the open note.
+    pub fn word_goal_progress(&self) -> Option<(usize, usize)> {
+        if self.view != View::Editor {
+            return None;
+        }
+        self.render_cache.word_goal(&self.buffer, || {
+            let content = self.buffer.to_string();
+            let frontmatter = Frontmatter::parse(&content)?;
+            let goal = frontmatter.get("word_goal")?.parse::<usize>().ok()?;
+            if goal == 0 {
+                return None;
+            }
+            Some((count_words(&content[frontmatter.body_offset..]), goal))
+        })
+    }
+
+    /// The search query as matched in the editor.
+    pub fn search_pattern(&self) -> SearchPattern {
+        SearchPattern::new(&self.search_query, self.ignore_diacritics)
+    }
+
+    /// Matches of the search query in the editor, shared between frames
+    /// until the text or query changes.
+    pub fn search_matches(&self) -> Rc<[SearchMatch]> {
+        self.render_cache
+            .search_matches(&self.buffer, &self.search_pattern())
+    }
+
+    /// Directory holding the current note's file, used to resolve relative asset links.
+    pub fn current_note_dir(&self) -> PathBuf {
+        let archived = self.current_note.as_ref().is_some_and(|n| n.is_archived);
+        let subdir = if archived { "archives" } else { "drafts" };
+        self.data_dir.join(subdir)
+    }
+
+    /// `Space e` / `:export`: ask for the export format. PDF is only
+    /// offered once `general.pdf_command` is set.
+    pub fn open_export_picker(&mut self) {
+        if self.current_note.is_none() {
+            self.set_message("No note open");
+            return;
+        }
+        let formats = ExportFormat::ALL
+            .into_iter()
+            .filter(|&f| f != ExportFormat::Pdf || !self.config.general.pdf_command.is_empty())
+            .collect();
+        self.export_picker = Some((formats, 0));
+    }
+
+    pub fn move_export_picker(&mut self, down: bool) {
+        if let Some((formats, row)) = &mut self.export_picker {
+            *row = if down {
+                (*row + 1).min(formats.len().saturating_sub(1))
+            } else {
+                row.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the format picker; with `accept`, export in the selected
+    /// format to `exports/`.
+    pub fn close_export_picker(&mut self, accept: bool) {
+        if let Some((formats, row)) = self.export_picker.take()
+            && accept
+            && let Some(&format) = formats.get(row)
+        {
+            self.export_current_note(format, None);
+        }
+    }
+
+    /// Export the current note with `![[embeds]]` expanded and private parts
+    /// removed (`sanitize_for_sharing`): as markdown, as an HTML page in the
+    /// theme's colors, or as that page run through `general.pdf_command`.
+    /// It goes to `path` (a file, or a directory to put it in), else to
+    /// `exports/` in the data directory.
+    pub fn export_current_note(&mut self, format: ExportFormat, path: Option<&str>) {
+        if !self.ensure_archives_loaded() {
+            return;
+        }
+        if self.current_note.is_none() {
+            self.set_message("No note open");
+            return;
+        }
+        if format == ExportFormat::Pdf && self.config.general.pdf_command.is_empty() {
+            self.set_message("Set general.pdf_command to export PDF");
+            return;
+        }
+        match self.write_export(format, path) {
+            Ok(target) => self.set_message(&format!("Exported to {}", target.display())),
+            Err(e) => self.set_message(&format!("Export failed: {}", e)),
+        }
+    }
+
+    fn write_export(&self, format: ExportFormat, path: Option<&str>) -> Result<PathBuf> {
+        let Some(note) = &self.current_note else {
+            return Err(anyhow!("No note open"));
+        };
+        let notes: Vec<&Note> = self
+            .draft_list
+            .notes()
+            .iter()
+            .chain(self.archive_list.notes())
+            .collect();
+        let lookup = |title: &str| resolve_link(title, &notes).map(|n| n.content.clone());
+        let content = expand_transclusions(&self.buffer.to_string(), &note.title, &lookup);
+        let shared = sanitize_for_sharing(&content, &self.current_note_dir());
+        let file_name = format!("{}.{}", share_file_stem(&note.title), format.extension());
+        let target = match path.map(expand_tilde) {
+            Some(path) if path.is_dir() => path.join(file_name),
+            Some(path) => path,
+            None => self.data_dir.join("exports").join(file_name),
+        };
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match format {
+            ExportFormat::Markdown => std::fs::write(&target, shared)?,
+            ExportFormat::Html => {
+                std::fs::write(&target, render_html(&shared, &note.title, self.theme()))?
+            }
+            ExportFormat::Pdf => {
+                let html =
+                    std::env::temp_dir().join(format!("kenotex_{}.html", std::process::id()));
+                std::fs::write(&html, render_html(&shared, &note.title, self.theme()))?;
+                let result = run_pdf_command(&self.config.general.pdf_command, &html, &target);
+                let _ = std::fs::remove_file(&html);
+                result?;
+            }
+        }
+        Ok(target)
+    }
+
+    pub fn stats_summary(&self) -> StatsSummary {
+        summarize(
+            &self.writing_session,
+            &self.writing_history,
+            chrono::Local::now(),
+        )
+    }
+
+    pub fn toggle_stats(&mut self) {
+        self.show_stats = !self.show_stats;
+    }
+
+    /// Append a session summary to the configured `writing_log`, if any words
+    /// were written.
+    pub fn finish_writing_session(&self) -> Result<()> {
+        if let Some(log) = &self.config.general.writing_log
+            && self.writing_session.words_written() > 0
+        {
+            let line = self.writing_session.log_line(chrono::Local::now());
+            append_writing_log(&expand_tilde(log), &line)?;
+        }
+        Ok(())
+    }
+
+    /// Center the editor view on the cursor at the next render.
+    pub fn center_cursor(&self) {
+        self.viewport.align(ViewAlign::Center);
+    }
+
+    /// Point the jump list at the current note, dropping another note's jumps.
+    fn sync_jump_list(&mut self) {
+        let id = self.current_note.as_ref().map_or("", |n| n.id.as_str());
+        self.jump_list.switch_note(id);
+    }
+
+    /// Remember the cursor as the start of a jump, for Ctrl-O.
+    pub fn record_jump(&mut self) {
+        self.sync_jump_list();
+        self.jump_list.push(self.buffer.cursor_position());
+    }
 
-        if cursor_display_row >= inner_height {
-            cursor_display_row - inner_height + 5
+    /// Move to an older (Ctrl-O) or newer (Ctrl-I) jump list position.
+    pub fn walk_jump_list(&mut self, back: bool) {
+        self.sync_jump_list();
+        let target = if back {
+            self.jump_list.back(self.buffer.cursor_position())
         } else {
-            0
+            self.jump_list.forward()
+        };
+        match target {
+            Some((row, col)) => {
+                self.buffer.set_cursor(row, col);
+                self.center_cursor();
+            }
+            None if back => self.set_message("Already at oldest position in jump list"),
+            None => self.set_message("Already at newest position in jump list"),
+        }
+    }
+
+    /// Save the cursor as mark `mark` of the current note (`ma`).
+    pub fn set_mark(&mut self, mark: char) {
+        let Some(note) = &self.current_note else {
+            return;
+        };
+        let (row, col) = self.buffer.cursor_position();
+        self.marks.set(&note.id, mark, Mark { row, col });
+        if let Err(e) = save_marks(&self.data_dir, &self.marks) {
+            self.set_message(&format!("Saving marks failed: {}", e));
+        }
+    }
+
+    /// Jump to mark `mark`: its exact position, or else the first non-blank
+    /// character of its line. Positions past the end of the note are clamped.
+    pub fn jump_to_mark(&mut self, mark: char, exact: bool) {
+        let target = self
+            .current_note
+            .as_ref()
+            .and_then(|note| self.marks.get(&note.id, mark));
+        let Some(target) = target else {
+            self.set_message(&format!("Mark not set: {}", mark));
+            return;
+        };
+        self.record_jump();
+        self.buffer.set_cursor(target.row, target.col);
+        if !exact {
+            self.buffer.move_to_first_non_blank();
+        }
+        self.center_cursor();
+    }
+
+    /// Status bar label for the active editor search, e.g. `match 2 of 5`.
+    pub fn search_status(&self) -> Option<String> {
+        if self.search_query.is_empty() {
+            return None;
+        }
+        let (index, count) = if self.view == View::Editor {
+            let cursor = self.buffer.cursor_position();
+            let matches = self.search_matches();
+            let index = matches
+                .iter()
+                .position(|&(r, c, _)| (r, c) == cursor)
+                .map(|i| i + 1);
+            (index, matches.len())
+        } else {
+            let (notes, selected) = self.visible_notes();
+            note_match_index(&notes, selected, &self.search_query)
+        };
+        match (index, count) {
+            (_, 0) => None,
+            (Some(k), n) => Some(format!("match {} of {}", k, n)),
+            (None, 1) => Some("1 match".to_string()),
+            (None, n) => Some(format!("{} matches", n)),
+        }
+    }
+
+    /// Notes shown in the active list view and the selected position.
+    fn visible_notes(&self) -> (Vec<&Note>, usize) {
+        let list = self.active_list();
+        (list.filtered_notes(), list.selected_index())
+    }
+
+    pub fn list_selected_index(&self) -> usize {
+        self.visible_notes().1
+    }
+
+    pub fn select_list_index(&mut self, index: usize) {
+        self.active_list_mut().select(index);
+    }
+
+    /// Cycle the sort order of the active list.
+    /// Switch the active list to the next sort order and remember it in
+    /// `general.list_sort`.
+    pub fn cycle_list_sort(&mut self) {
+        let order = self.active_list_mut().cycle_sort();
+        self.config.general.list_sort = order.label().to_string();
+        let saved = match &self.config_file {
+            Some(path) => save_config_value(path, "general", "list_sort", order.label()),
+            None => Ok(()),
+        };
+        match saved {
+            Ok(()) => self.set_message(&format!("Sort: {}", order.label())),
+            Err(e) => self.set_message(&format!("Saving sort order failed: {}", e)),
+        }
+    }
+
+    fn list_sort(&self) -> SortOrder {
+        SortOrder::from_name(&self.config.general.list_sort).unwrap_or_default()
+    }
+
+    /// Filter of the active list view (empty in the editor).
+    pub fn list_filter(&self) -> &str {
+        match self.view {
+            View::DraftList => self.draft_list.search_query(),
+            View::ArchiveList => self.archive_list.search_query(),
+            View::Trash => self.trash_list.search_query(),
+            View::Editor => "",
+        }
+    }
+
+    pub fn set_list_filter(&mut self, filter: String) {
+        match self.view {
+            View::DraftList => filter_with_index(&self.search_index, &mut self.draft_list, filter),
+            View::ArchiveList => {
+                filter_with_index(&self.search_index, &mut self.archive_list, filter)
+            }
+            View::Trash => filter_with_index(&self.search_index, &mut self.trash_list, filter),
+            View::Editor => {}
+        }
+    }
+
+    /// Cursor of whichever prompt is open.
+    pub fn prompt_cursor(&self) -> usize {
+        if self.mode == AppMode::Filter {
+            self.filter_input.cursor()
+        } else {
+            self.search_input.cursor()
+        }
+    }
+
+    /// Select the next (or previous) visible note matching the search,
+    /// starting after the selection with `skip_current` (`n`/`N`).
+    pub fn jump_to_list_match(&mut self, forward: bool, skip_current: bool) {
+        let (notes, selected) = self.visible_notes();
+        let found = find_note_match(&notes, selected, &self.search_query, forward, skip_current);
+        let Some((index, wrapped)) = found else {
+            self.set_message(&format!("Pattern not found: {}", self.search_query));
+            return;
+        };
+        self.select_list_index(index);
+        if wrapped && forward {
+            self.set_message("search hit BOTTOM, continuing at TOP");
+        } else if wrapped {
+            self.set_message("search hit TOP, continuing at BOTTOM");
+        } else {
+            self.clear_message();
         }
     }
 
@@ -495,6 +4314,16 @@ impl App {
         Some(visual_mode.render_data(&self.buffer, cursor))
     }
 
+    /// What the Visual selection covers, e.g. `sel: 3 lines`.
+    pub fn selection_status(&self) -> Option<String> {
+        let visual_mode = self.visual_mode.as_ref()?;
+        let cursor = self.buffer.cursor_position();
+        Some(format!(
+            "sel: {}",
+            visual_mode.summary(&self.buffer, cursor)
+        ))
+    }
+
     pub fn enter_visual_mode(&mut self, visual_type: crate::molecules::editor::VisualType) {
         use crate::molecules::editor::VisualType;
 
@@ -529,7 +4358,10 @@ impl App {
     }
 
     pub fn exit_visual_mode(&mut self) {
-        self.visual_mode = None;
+        if let Some(visual_mode) = self.visual_mode.take() {
+            let (anchor, cursor) = (visual_mode.anchor().0, self.buffer.cursor_position().0);
+            self.last_visual_rows = Some((anchor.min(cursor), anchor.max(cursor)));
+        }
         self.mode = AppMode::Normal;
         self.visual_target_display_col = None;
     }
@@ -635,6 +4467,8 @@ impl App {
     }
 
     pub fn exit_insert_mode(&mut self) {
+        self.snippet = None;
+        self.word_completion = false;
         // Handle block insert replay
         if !self.block_insert_positions.is_empty() {
             if let Some(&first_pos) = self.block_insert_positions.first() {
@@ -674,10 +4508,21 @@ impl App {
         self.external_editor_requested = true;
     }
 
+    /// Take in the text written by the external editor as one undo step.
+    /// The cursor stays on its line, following it when lines were added or
+    /// removed above, and the view keeps its scroll position.
     pub fn apply_external_editor_result(&mut self, new_content: String) {
-        self.buffer = TextBuffer::from_string(&new_content);
-        self.buffer.set_cursor(0, 0);
         self.set_mode(AppMode::Normal);
+        let old_content = self.buffer.to_string();
+        if new_content == old_content {
+            self.set_message("No changes from external editor");
+            return;
+        }
+        self.freeze_current_note(SnapshotReason::ExternalEditor);
+        let (row, col) = self.buffer.cursor_position();
+        self.buffer.replace_content(&new_content);
+        self.buffer
+            .set_cursor(follow_line(&old_content, &new_content, row), col);
         self.dirty = true;
         self.set_message("Buffer updated from external editor");
     }
@@ -688,3 +4533,21 @@ impl Default for App {
         Self::new().expect("Failed to initialize app")
     }
 }
+
+/// Filter `list` by `query`, checking only the notes the search index
+/// allows. Notes it does not know yet, such as a new unsaved one, are
+/// always checked.
+fn filter_with_index(index: &SearchIndex, list: &mut NoteList, query: String) {
+    let candidates = index.candidates(&query).map(|mut ids| {
+        let unindexed = list.notes().iter().filter(|n| !index.contains(&n.id));
+        ids.extend(unindexed.map(|n| n.id.clone()));
+        ids
+    });
+    list.set_search_query_in(query, candidates);
+}
+
+/// Whether two config sections hold the same settings (they are compared
+/// as TOML, having no `PartialEq`).
+fn same<T: serde::Serialize>(a: &T, b: &T) -> bool {
+    toml::Value::try_from(a).ok() == toml::Value::try_from(b).ok()
+}
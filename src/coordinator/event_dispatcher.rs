@@ -1,29 +1,64 @@
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use super::App;
 use crate::atoms::storage::{clipboard_copy, clipboard_paste};
-use crate::molecules::editor::VimAction;
 use crate::molecules::editor::list_prefix;
+use crate::molecules::editor::{PromptInput, TextBuffer, VimAction, mention_at};
 use crate::types::{AppMode, View};
 
+const READ_ONLY_MESSAGE: &str = "Read-only mode: changes are disabled";
+
 pub struct EventDispatcher;
 
 impl EventDispatcher {
     /// Handle a bracketed paste event (Cmd+V / terminal paste).
     pub fn handle_paste(app: &mut App, text: String) -> Result<()> {
+        // Prompts are a single line; keep the first one
+        let line = text.lines().next().unwrap_or_default();
+        if let Some(title) = app.rename_title.as_mut() {
+            app.rename_input.insert(title, line);
+            return Ok(());
+        }
+        match app.mode {
+            AppMode::Search => {
+                app.search_input.insert(&mut app.search_query, line);
+                Self::incremental_list_search(app);
+                return Ok(());
+            }
+            AppMode::Filter => {
+                app.filter_input.insert(&mut app.filter_query, line);
+                app.set_list_filter(app.filter_query.clone());
+                return Ok(());
+            }
+            _ => {}
+        }
+        if app.is_read_only() {
+            app.set_message(READ_ONLY_MESSAGE);
+            return Ok(());
+        }
         match app.mode {
             AppMode::Insert => {
                 app.buffer.save_undo_snapshot();
                 app.buffer.insert_text(&text);
                 app.dirty = true;
             }
-            AppMode::Normal => {
-                if !text.is_empty() {
-                    app.buffer.save_undo_snapshot();
-                    app.buffer.paste_after_cursor(&text);
-                    app.dirty = true;
+            AppMode::Replace => {
+                for c in text.chars() {
+                    let original = if c == '\n' {
+                        app.buffer.insert_newline();
+                        None
+                    } else {
+                        app.buffer.overtype_char(c)
+                    };
+                    app.replace_originals.push(original);
                 }
+                app.dirty = true;
+            }
+            AppMode::Normal if !text.is_empty() => {
+                app.buffer.save_undo_snapshot();
+                app.buffer.paste_after_cursor(&text);
+                app.dirty = true;
             }
             _ => {}
         }
@@ -40,45 +75,504 @@ impl EventDispatcher {
             return Ok(());
         }
 
+        if app.mode == AppMode::ConfirmReplace && app.replace_all.is_some() {
+            match key.code {
+                KeyCode::Char(c @ ('y' | 'n' | 'a' | 'l' | 'q')) => app.answer_replace_all(c)?,
+                KeyCode::Esc => app.answer_replace_all('q')?,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if app.mode == AppMode::ConfirmReplace {
+            match key.code {
+                KeyCode::Char(c @ ('y' | 'n' | 'a' | 'l' | 'q')) => app.answer_substitution(c),
+                KeyCode::Esc => app.answer_substitution('q'),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // The stats dashboard swallows the next key and closes
+        if app.show_stats {
+            app.toggle_stats();
+            return Ok(());
+        }
+
+        if app.buffer_picker.is_some() {
+            Self::handle_buffer_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.backlink_picker.is_some() {
+            Self::handle_backlink_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.tag_picker.is_some() {
+            Self::handle_tag_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.export_picker.is_some() {
+            Self::handle_export_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.template_picker.is_some() {
+            Self::handle_template_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.snapshot_picker.is_some() {
+            Self::handle_snapshot_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.spell_picker.is_some() {
+            Self::handle_spell_picker_key(app, key);
+            return Ok(());
+        }
+
+        if app.quickfix_focused {
+            Self::handle_quickfix_key(app, key);
+            return Ok(());
+        }
+
+        if app.outline_selection.is_some() {
+            Self::handle_outline_key(app, key);
+            return Ok(());
+        }
+
+        if app.rename_title.is_some() {
+            Self::handle_rename_key(app, key)?;
+            return Ok(());
+        }
+
+        if app.mode == AppMode::Insert && Self::handle_completion_key(app, key) {
+            return Ok(());
+        }
+
+        if app.review.is_some()
+            && app.view == View::Editor
+            && app.mode == AppMode::Normal
+            && !app.vim_mode.is_command_pending()
+            && Self::handle_review_key(app, key)?
+        {
+            return Ok(());
+        }
+
+        let (mode, view) = (app.mode, app.view);
+        let (from_row, _) = app.buffer.cursor_position();
         let action = app.vim_mode.handle_key(key, app.mode);
 
+        if app.is_read_only() && Self::blocked_in_read_only(app, &action) {
+            app.set_message(READ_ONLY_MESSAGE);
+            return Ok(());
+        }
+        let repeatable = action.is_repeatable();
+
         match app.mode {
-            AppMode::Normal => Self::handle_normal_action(app, action)?,
+            AppMode::Normal => {
+                let count = app.vim_mode.count();
+                Self::handle_normal_action(app, action, count)?
+            }
             AppMode::Insert => Self::handle_insert_action(app, action)?,
+            AppMode::Replace => Self::handle_replace_action(app, action)?,
             AppMode::Visual(_) => Self::handle_visual_action(app, action)?,
             AppMode::Search => Self::handle_search_action(app, action, key)?,
-            AppMode::Processing | AppMode::ConfirmDelete => {}
+            AppMode::Filter => Self::handle_filter_action(app, action, key)?,
+            AppMode::Command => Self::handle_command_action(app, action, key)?,
+            AppMode::Processing | AppMode::ConfirmDelete | AppMode::ConfirmReplace => {}
+        }
+
+        if view == View::Editor {
+            // Searches, jumps and edits that land in a closed fold open it
+            if app.view == View::Editor {
+                app.settle_folds(from_row, false);
+            }
+            Self::record_change_key(app, key, mode, repeatable);
+        }
+        Ok(())
+    }
+
+    /// Move through the buffer picker; Enter switches, Esc or q closes it.
+    fn handle_buffer_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_buffer_picker(true),
+            KeyCode::Up => app.move_buffer_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_buffer_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_buffer_picker(false),
+            KeyCode::Enter => app.close_buffer_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_buffer_picker(false),
+            _ => {}
+        }
+    }
+
+    /// Move through the backlinks panel; Enter opens the note, Esc or q
+    /// closes it.
+    fn handle_backlink_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_backlink_picker(true),
+            KeyCode::Up => app.move_backlink_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_backlink_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_backlink_picker(false),
+            KeyCode::Enter => app.close_backlink_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_backlink_picker(false),
+            _ => {}
+        }
+    }
+
+    /// Move through the focused locations panel; Enter jumps to the
+    /// location, Esc goes back to the editor and q also hides the panel.
+    fn handle_quickfix_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_quickfix(true),
+            KeyCode::Up => app.move_quickfix(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_quickfix(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_quickfix(false),
+            KeyCode::Enter => app.leave_quickfix(true),
+            KeyCode::Esc => app.leave_quickfix(false),
+            KeyCode::Char('q') => app.close_quickfix(),
+            _ => {}
+        }
+    }
+
+    /// Move through the tag browser; Enter filters the drafts by the tag,
+    /// Esc or q closes it.
+    fn handle_tag_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_tag_picker(true),
+            KeyCode::Up => app.move_tag_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_tag_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_tag_picker(false),
+            KeyCode::Enter => app.close_tag_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_tag_picker(false),
+            _ => {}
+        }
+    }
+
+    fn handle_export_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_export_picker(true),
+            KeyCode::Up => app.move_export_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_export_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_export_picker(false),
+            KeyCode::Enter => app.close_export_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_export_picker(false),
+            _ => {}
+        }
+    }
+
+    fn handle_template_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_template_picker(true),
+            KeyCode::Up => app.move_template_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_template_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_template_picker(false),
+            KeyCode::Enter => app.close_template_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_template_picker(false),
+            _ => {}
+        }
+    }
+
+    fn handle_snapshot_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_snapshot_picker(true),
+            KeyCode::Up => app.move_snapshot_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_snapshot_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_snapshot_picker(false),
+            KeyCode::Enter => app.close_snapshot_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_snapshot_picker(false),
+            _ => {}
+        }
+    }
+
+    fn handle_spell_picker_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_spell_picker(true),
+            KeyCode::Up => app.move_spell_picker(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_spell_picker(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_spell_picker(false),
+            KeyCode::Enter => app.close_spell_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_spell_picker(false),
+            _ => {}
         }
+    }
 
+    /// Edit the title in the rename prompt; Enter renames, Esc cancels.
+    fn handle_rename_key(app: &mut App, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => return app.close_rename(true),
+            KeyCode::Esc => return app.close_rename(false),
+            _ => {}
+        }
+        let (input, Some(title)) = (&mut app.rename_input, app.rename_title.as_mut()) else {
+            return Ok(());
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('u') if ctrl => input.delete_to_start(title),
+            KeyCode::Char('w') if ctrl => input.delete_word(title),
+            KeyCode::Char(c) if !ctrl => input.insert(title, c.encode_utf8(&mut [0; 4])),
+            KeyCode::Backspace => {
+                input.backspace(title);
+            }
+            KeyCode::Delete => input.delete(title),
+            KeyCode::Left => input.move_left(),
+            KeyCode::Right => input.move_right(title),
+            KeyCode::Home => input.move_home(),
+            KeyCode::End => input.move_end(title),
+            _ => {}
+        }
         Ok(())
     }
 
-    fn handle_normal_action(app: &mut App, action: VimAction) -> Result<()> {
+    /// Keys of the completion popup: Tab inserts the selected candidate,
+    /// Down/Up or Ctrl-N/Ctrl-P move. Without a popup, Ctrl-N offers the
+    /// words of the note. Returns false for other keys.
+    fn handle_completion_key(app: &mut App, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if app.completions().is_none() {
+            return ctrl && key.code == KeyCode::Char('n') && app.start_word_completion();
+        }
+        match key.code {
+            KeyCode::Tab => return app.accept_completion(),
+            KeyCode::Down => app.move_completion(true),
+            KeyCode::Up => app.move_completion(false),
+            KeyCode::Char('n') if ctrl => app.move_completion(true),
+            KeyCode::Char('p') if ctrl => app.move_completion(false),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The `:review` keys: `a` archive, `n` keep, `p` process, `t` tag and
+    /// `q` or Esc stop. Returns false for other keys, which read and edit
+    /// the note as usual.
+    fn handle_review_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(false);
+        }
+        match key.code {
+            KeyCode::Char('a') => app.review_archive()?,
+            KeyCode::Char('n') => app.review_keep(),
+            KeyCode::Char('p') => app.review_process(),
+            KeyCode::Char('t') => {
+                app.command_line = "tag ".to_string();
+                app.set_mode(AppMode::Command);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => app.finish_review(),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Move through the focused outline sidebar; Enter jumps to the heading,
+    /// Esc goes back to the editor and q also hides the sidebar.
+    fn handle_outline_key(app: &mut App, key: KeyEvent) {
+        let keys = &app.config.keyboard;
+        match key.code {
+            KeyCode::Down => app.move_outline(true),
+            KeyCode::Up => app.move_outline(false),
+            KeyCode::Char(c) if c.to_string() == keys.move_down => app.move_outline(true),
+            KeyCode::Char(c) if c.to_string() == keys.move_up => app.move_outline(false),
+            KeyCode::Enter => app.leave_outline(true),
+            KeyCode::Esc => app.leave_outline(false),
+            KeyCode::Char('q') => {
+                app.leave_outline(false);
+                app.show_outline = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Feed a key handled in the editor to the `.` recorder. `mode` is the
+    /// mode the key was typed in.
+    fn record_change_key(app: &mut App, key: KeyEvent, mode: AppMode, repeatable: bool) {
+        match mode {
+            AppMode::Normal => {
+                app.change_recorder.push_normal_key(key);
+                if app.vim_mode.is_command_pending() {
+                    return;
+                }
+                if repeatable {
+                    app.change_recorder
+                        .finish_command(matches!(app.mode, AppMode::Insert | AppMode::Replace));
+                } else {
+                    app.change_recorder.discard_command();
+                }
+            }
+            AppMode::Insert | AppMode::Replace => {
+                app.change_recorder.push_insert_key(key, app.mode != mode)
+            }
+            _ => {}
+        }
+    }
+
+    /// Replay the last recorded change `count` times (`.`, `3.`).
+    fn repeat_last_change(app: &mut App, count: usize) -> Result<()> {
+        let keys = app.change_recorder.last_change().to_vec();
+        if keys.is_empty() {
+            app.set_message("No previous change to repeat");
+            return Ok(());
+        }
+        app.change_recorder.set_replaying(true);
+        let mut result = Ok(());
+        'replay: for _ in 0..count {
+            for &key in &keys {
+                result = Self::handle_key(app, key);
+                if result.is_err() {
+                    break 'replay;
+                }
+            }
+        }
+        app.change_recorder.set_replaying(false);
+        result
+    }
+
+    /// Search input is never blocked; list views only block note creation and
+    /// processing since their other actions are navigation.
+    fn blocked_in_read_only(app: &App, action: &VimAction) -> bool {
+        match app.mode {
+            AppMode::Search | AppMode::Command | AppMode::Filter => false,
+            _ if app.view == View::Editor => action.is_mutating(),
+            _ => matches!(
+                action,
+                VimAction::LeaderNew
+                    | VimAction::LeaderProcess
+                    | VimAction::LeaderCheatSheet
+                    | VimAction::LeaderRename
+            ),
+        }
+    }
+
+    fn handle_normal_action(app: &mut App, action: VimAction, count: usize) -> Result<()> {
         match app.view {
-            View::Editor => Self::handle_editor_normal(app, action)?,
-            View::DraftList | View::ArchiveList => Self::handle_list_normal(app, action)?,
+            View::Editor => Self::handle_editor_normal(app, action, count)?,
+            View::DraftList | View::ArchiveList | View::Trash => {
+                Self::handle_list_normal(app, action)?
+            }
         }
         Ok(())
     }
 
-    fn handle_editor_normal(app: &mut App, action: VimAction) -> Result<()> {
+    /// Cursor motions that a count prefix repeats (`5j`, `3w`).
+    fn counted_motion(action: &VimAction) -> Option<fn(&mut TextBuffer)> {
+        match action {
+            VimAction::MoveLeft => Some(TextBuffer::move_left),
+            VimAction::MoveRight => Some(TextBuffer::move_right),
+            VimAction::MoveUp => Some(TextBuffer::move_up),
+            VimAction::MoveDown => Some(TextBuffer::move_down),
+            VimAction::MoveWordForward => Some(TextBuffer::move_word_forward),
+            VimAction::MoveWordBackward => Some(TextBuffer::move_word_backward),
+            _ => None,
+        }
+    }
+
+    /// `{` `}` `(` `)`: like `G` they are jumps, so they are not in
+    /// `counted_motion`.
+    fn paragraph_or_sentence_motion(action: &VimAction) -> fn(&mut TextBuffer) {
+        match action {
+            VimAction::MoveParagraphForward => TextBuffer::move_paragraph_forward,
+            VimAction::MoveParagraphBackward => TextBuffer::move_paragraph_backward,
+            VimAction::MoveSentenceForward => TextBuffer::move_sentence_forward,
+            _ => TextBuffer::move_sentence_backward,
+        }
+    }
+
+    fn handle_editor_normal(app: &mut App, action: VimAction, count: usize) -> Result<()> {
+        if let Some(motion) = Self::counted_motion(&action) {
+            // Step by step, so each step over a closed fold counts once
+            for _ in 0..count {
+                let (from, _) = app.buffer.cursor_position();
+                motion(&mut app.buffer);
+                app.settle_folds(from, true);
+            }
+            return Ok(());
+        }
+
         match action {
-            VimAction::MoveLeft => app.buffer.move_left(),
-            VimAction::MoveRight => app.buffer.move_right(),
-            VimAction::MoveUp => app.buffer.move_up(),
-            VimAction::MoveDown => app.buffer.move_down(),
-            VimAction::MoveWordForward => app.buffer.move_word_forward(),
-            VimAction::MoveWordBackward => app.buffer.move_word_backward(),
             VimAction::MoveLineStart => app.buffer.move_to_line_start(),
             VimAction::MoveLineEnd => app.buffer.move_to_line_end(),
-            VimAction::MoveFileStart => app.buffer.move_to_first_line(),
-            VimAction::MoveFileEnd => app.buffer.move_to_last_line(),
+            VimAction::MoveFileStart => {
+                app.record_jump();
+                app.buffer.move_to_first_line();
+            }
+            VimAction::MoveFileEnd => {
+                app.record_jump();
+                app.buffer.move_to_last_line();
+            }
+            VimAction::MoveParagraphForward
+            | VimAction::MoveParagraphBackward
+            | VimAction::MoveSentenceForward
+            | VimAction::MoveSentenceBackward => {
+                app.record_jump();
+                let motion = Self::paragraph_or_sentence_motion(&action);
+                for _ in 0..count {
+                    motion(&mut app.buffer);
+                }
+            }
+            VimAction::SetMark(mark) => app.set_mark(mark),
+            VimAction::JumpToMark { mark, exact } => app.jump_to_mark(mark, exact),
+            VimAction::JumpBack | VimAction::JumpForward => {
+                for _ in 0..count {
+                    app.walk_jump_list(action == VimAction::JumpBack);
+                }
+            }
+            VimAction::ScrollHalfPage { down } => app.scroll_half_page(down, count),
+            VimAction::ScrollPage { down } => app.scroll_page(down, count),
+            VimAction::AlignView(align) => app.align_view(align),
+            VimAction::ToggleFold => app.toggle_fold(),
+            VimAction::OpenAllFolds => app.open_all_folds(),
+            VimAction::CloseAllFolds => app.close_all_folds(),
+            VimAction::NextOpenTask | VimAction::PrevOpenTask => {
+                let forward = action == VimAction::NextOpenTask;
+                if !(0..count).all(|_| app.buffer.move_to_open_task(forward)) {
+                    app.set_message("No more open tasks");
+                }
+            }
+            VimAction::FirstOpenTask => {
+                if app.buffer.move_to_first_open_task() {
+                    app.center_cursor();
+                } else {
+                    app.set_message("No open tasks");
+                }
+            }
 
             VimAction::InsertMode => {
                 app.buffer.save_undo_snapshot();
                 app.set_mode(AppMode::Insert);
                 app.set_message("-- INSERT --");
             }
+            VimAction::ReplaceChar(c) => app.dirty |= app.buffer.replace_chars(c, count),
+            VimAction::IncrementNumber | VimAction::DecrementNumber => {
+                let count = count as i64;
+                let delta = if action == VimAction::IncrementNumber {
+                    count
+                } else {
+                    -count
+                };
+                if app.buffer.increment_number(delta) {
+                    app.dirty = true;
+                } else {
+                    app.set_message("No number under cursor");
+                }
+            }
+            VimAction::EnterReplaceMode => {
+                app.buffer.save_undo_snapshot();
+                app.replace_originals.clear();
+                app.set_mode(AppMode::Replace);
+                app.set_message("-- REPLACE --");
+            }
             VimAction::InsertModeAppend => {
                 app.buffer.save_undo_snapshot();
                 app.buffer.move_right();
@@ -123,7 +617,7 @@ impl EventDispatcher {
 
             VimAction::DeleteChar => {
                 app.buffer.save_undo_snapshot();
-                app.buffer.delete_char();
+                app.buffer.delete_chars(count);
                 app.dirty = true;
             }
             VimAction::DeleteLine => {
@@ -134,13 +628,23 @@ impl EventDispatcher {
 
             VimAction::Delete(motion) => {
                 app.buffer.save_undo_snapshot();
-                let (text, linewise) = app.buffer.apply_motion_delete(motion);
+                let (text, linewise) = app.buffer.apply_motion_delete(motion, count);
                 let _ = clipboard_copy(&text);
                 app.last_yank_linewise = linewise;
                 app.dirty = true;
             }
+            VimAction::RepeatLastChange => Self::repeat_last_change(app, count)?,
+            VimAction::Change(motion) => {
+                app.buffer.save_undo_snapshot();
+                let (text, linewise) = app.buffer.apply_motion_change(motion, count);
+                let _ = clipboard_copy(&text);
+                app.last_yank_linewise = linewise;
+                app.dirty = true;
+                app.set_mode(AppMode::Insert);
+                app.set_message("-- INSERT --");
+            }
             VimAction::Yank(motion) => {
-                let (text, linewise) = app.buffer.apply_motion_yank(motion);
+                let (text, linewise) = app.buffer.apply_motion_yank(motion, count);
                 let _ = clipboard_copy(&text);
                 app.last_yank_linewise = linewise;
                 app.set_message("Yanked");
@@ -171,7 +675,7 @@ impl EventDispatcher {
             }
 
             VimAction::Undo => {
-                if app.buffer.undo() {
+                if (0..count).fold(false, |undone, _| app.buffer.undo() || undone) {
                     app.dirty = true;
                     app.set_message("Undo");
                 } else {
@@ -179,7 +683,7 @@ impl EventDispatcher {
                 }
             }
             VimAction::Redo => {
-                if app.buffer.redo() {
+                if (0..count).fold(false, |redone, _| app.buffer.redo() || redone) {
                     app.dirty = true;
                     app.set_message("Redo");
                 } else {
@@ -237,6 +741,39 @@ impl EventDispatcher {
             VimAction::LeaderProcess => {
                 app.start_processing();
             }
+            VimAction::LeaderCheatSheet => {
+                app.open_cheat_sheet();
+            }
+            VimAction::LeaderStats => {
+                app.toggle_stats();
+            }
+            VimAction::LeaderShare => {
+                app.open_export_picker();
+            }
+            VimAction::LeaderRestartWatcher => {
+                app.restart_file_watcher();
+            }
+            VimAction::LeaderBuffers => app.open_buffer_picker(),
+            VimAction::LeaderOutline => app.toggle_outline(),
+            VimAction::LeaderBacklinks => app.open_backlinks(),
+            VimAction::LeaderTags => app.open_tag_picker(),
+            VimAction::LeaderRename => app.open_rename(),
+            VimAction::LeaderSyncTitle => app.sync_title()?,
+            VimAction::LeaderToday => app.open_today(),
+            VimAction::LeaderGitSync => app.start_sync()?,
+            VimAction::FollowLink => app.follow_link(),
+            VimAction::CycleBuffer { forward } => app.cycle_buffer(forward, count),
+            VimAction::CycleDailyNote { forward } => app.cycle_daily_note(forward, count),
+            VimAction::CycleLocation { forward } => app.cycle_location(forward, count),
+            VimAction::CycleSpellError { forward } => app.cycle_spell_error(forward, count),
+            VimAction::SpellSuggest => app.open_spell_picker(),
+            VimAction::SplitPane(dir) => app.split_pane(dir, None, false),
+            VimAction::FocusPane(dir) => app.focus_pane_towards(dir),
+            VimAction::CyclePane { forward } => app.cycle_pane(forward, count),
+            VimAction::ClosePane => app.close_pane(),
+            VimAction::OnlyPane => app.only_pane(),
+            VimAction::ResizePane { dir, grow } => app.resize_pane(dir, grow, count),
+            VimAction::EqualizePanes => app.equalize_panes(),
 
             VimAction::InsertCheckbox => {
                 app.buffer.save_undo_snapshot();
@@ -265,40 +802,26 @@ impl EventDispatcher {
             VimAction::CycleTheme => {
                 app.cycle_theme();
             }
-            VimAction::Search => {
+            VimAction::Search | VimAction::Filter => {
+                app.search_input.reset(&app.search_query);
                 app.set_mode(AppMode::Search);
             }
-            VimAction::SearchNext => {
-                if !app.search_query.is_empty() {
-                    let (row, col) = app.buffer.cursor_position();
-                    if let Some((r, c)) = app.buffer.find_next(&app.search_query, row, col) {
-                        app.buffer.set_cursor(r, c);
-                        app.set_message(&format!("/{}", app.search_query));
-                    } else {
-                        app.set_message(&format!("Pattern not found: {}", app.search_query));
-                    }
-                }
+            VimAction::EnterCommandMode => {
+                app.command_line.clear();
+                app.set_mode(AppMode::Command);
             }
-            VimAction::SearchPrev => {
-                if !app.search_query.is_empty() {
-                    let (row, col) = app.buffer.cursor_position();
-                    if let Some((r, c)) = app.buffer.find_prev(&app.search_query, row, col) {
-                        app.buffer.set_cursor(r, c);
-                        app.set_message(&format!("?{}", app.search_query));
-                    } else {
-                        app.set_message(&format!("Pattern not found: {}", app.search_query));
-                    }
-                }
+            VimAction::SearchNext if !app.search_query.is_empty() => {
+                Self::jump_to_match(app, true);
             }
-            VimAction::ClearSearch | VimAction::ExitToNormal => {
-                if !app.search_query.is_empty() {
-                    app.search_query.clear();
-                    app.clear_message();
-                }
+            VimAction::SearchPrev if !app.search_query.is_empty() => {
+                Self::jump_to_match(app, false);
+            }
+            VimAction::ClearSearch | VimAction::ExitToNormal if !app.search_query.is_empty() => {
+                app.search_query.clear();
+                app.clear_message();
             }
             VimAction::ReloadBuffer => {
                 app.reload_current_note_from_disk()?;
-                app.set_message("File reloaded");
             }
             VimAction::ExternalEditor => {
                 app.request_external_editor();
@@ -315,18 +838,16 @@ impl EventDispatcher {
     fn handle_list_normal(app: &mut App, action: VimAction) -> Result<()> {
         match action {
             VimAction::MoveUp => {
-                if app.view == View::DraftList {
-                    app.draft_list.move_up();
-                } else {
-                    app.archive_list.move_up();
-                }
+                app.active_list_mut().move_up();
             }
             VimAction::MoveDown => {
-                if app.view == View::DraftList {
-                    app.draft_list.move_down();
-                } else {
-                    app.archive_list.move_down();
-                }
+                app.active_list_mut().move_down();
+            }
+            VimAction::MoveFileStart => {
+                app.select_list_index(0);
+            }
+            VimAction::MoveFileEnd => {
+                app.select_list_index(usize::MAX);
             }
 
             VimAction::InsertMode | VimAction::MoveRight => {
@@ -339,25 +860,59 @@ impl EventDispatcher {
             VimAction::LeaderNew => {
                 app.new_note();
             }
+            VimAction::LeaderCheatSheet => {
+                app.open_cheat_sheet();
+            }
+            VimAction::LeaderStats => {
+                app.toggle_stats();
+            }
+            VimAction::LeaderRestartWatcher => {
+                app.restart_file_watcher();
+            }
+            VimAction::LeaderBuffers => app.open_buffer_picker(),
+            VimAction::LeaderTags => app.open_tag_picker(),
+            VimAction::LeaderRename => app.open_rename(),
+            VimAction::LeaderSyncTitle => app.sync_title()?,
+            VimAction::LeaderToday => app.open_today(),
+            VimAction::LeaderGitSync => app.start_sync()?,
+            VimAction::CycleBuffer { forward } => app.cycle_buffer(forward, 1),
+            VimAction::CycleDailyNote { forward } => app.cycle_daily_note(forward, 1),
+            VimAction::CycleLocation { forward } => app.cycle_location(forward, 1),
 
             VimAction::Search => {
+                app.list_search_origin = app.list_selected_index();
+                app.search_input.reset(&app.search_query);
                 app.set_mode(AppMode::Search);
             }
-            VimAction::SearchNext | VimAction::SearchPrev => {
-                // List views use filter-based search, n/N are no-ops here
+            VimAction::Filter => {
+                app.filter_query = app.list_filter().to_string();
+                app.filter_input.reset(&app.filter_query);
+                app.set_mode(AppMode::Filter);
+            }
+            VimAction::EnterCommandMode => {
+                app.command_line.clear();
+                app.set_mode(AppMode::Command);
+            }
+            VimAction::SearchNext | VimAction::SearchPrev if !app.search_query.is_empty() => {
+                app.jump_to_list_match(action == VimAction::SearchNext, true);
             }
             VimAction::ClearSearch => {
                 app.search_query.clear();
-                if app.view == View::DraftList {
-                    app.draft_list.clear_search();
-                } else {
-                    app.archive_list.clear_search();
-                }
+                app.active_list_mut().clear_search();
                 app.clear_message();
             }
 
+            // Esc clears the search, then the filter, then leaves the list
+            VimAction::ExitToNormal if !app.search_query.is_empty() => {
+                app.search_query.clear();
+                app.clear_message();
+            }
+            VimAction::ExitToNormal if !app.list_filter().is_empty() => {
+                app.set_list_filter(String::new());
+                app.set_message("Filter cleared");
+            }
             VimAction::ExitToNormal => {
-                if app.view == View::ArchiveList {
+                if matches!(app.view, View::ArchiveList | View::Trash) {
                     app.set_view(View::DraftList);
                 } else {
                     app.set_view(View::Editor);
@@ -383,6 +938,33 @@ impl EventDispatcher {
     }
 
     pub fn handle_list_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+        if app.buffer_picker.is_some()
+            || app.backlink_picker.is_some()
+            || app.tag_picker.is_some()
+            || app.template_picker.is_some()
+            || app.snapshot_picker.is_some()
+            || app.spell_picker.is_some()
+            || app.rename_title.is_some()
+        {
+            return Ok(false);
+        }
+        let mutating = match key.code {
+            KeyCode::Char('d')
+            | KeyCode::Char('a')
+            | KeyCode::Char('r')
+            | KeyCode::Char('u')
+            | KeyCode::Char('p') => true,
+            KeyCode::Char('n') => app.search_query.is_empty(),
+            _ => false,
+        };
+        if app.is_read_only() && mutating {
+            app.set_message(READ_ONLY_MESSAGE);
+            return Ok(true);
+        }
+        if app.view == View::Trash {
+            return Self::handle_trash_key(app, key);
+        }
+
         match key.code {
             KeyCode::Char('d') => {
                 app.request_delete();
@@ -404,14 +986,28 @@ impl EventDispatcher {
                 }
                 Ok(true)
             }
+            KeyCode::Char('T') if app.view == View::DraftList => {
+                app.set_view(View::Trash);
+                Ok(true)
+            }
             KeyCode::Char('n') if app.search_query.is_empty() => {
                 app.new_note();
                 Ok(true)
             }
             KeyCode::Char(' ') => {
-                if app.view == View::DraftList {
-                    app.draft_list.toggle_selected();
-                }
+                app.active_list_mut().toggle_selected();
+                Ok(true)
+            }
+            KeyCode::Char('s') => {
+                app.cycle_list_sort();
+                Ok(true)
+            }
+            KeyCode::Char('p') => {
+                app.toggle_pin_selected()?;
+                Ok(true)
+            }
+            KeyCode::Char('u') => {
+                app.undo_removal()?;
                 Ok(true)
             }
             KeyCode::Enter | KeyCode::Char('l') | KeyCode::Char('i') => {
@@ -422,13 +1018,45 @@ impl EventDispatcher {
         }
     }
 
+    /// The trash only restores, purges, marks and sorts; its notes are not
+    /// opened, archived or pinned.
+    fn handle_trash_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('r') => app.restore_from_trash()?,
+            KeyCode::Char('d') => app.request_delete(),
+            KeyCode::Char(' ') => app.active_list_mut().toggle_selected(),
+            KeyCode::Char('s') => app.cycle_list_sort(),
+            KeyCode::Enter | KeyCode::Char('l') | KeyCode::Char('i') => app.open_selected_note(),
+            KeyCode::Char('a') | KeyCode::Char('p') | KeyCode::Char('u') | KeyCode::Char('A') => {}
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
     fn handle_insert_action(app: &mut App, action: VimAction) -> Result<()> {
+        // Ctrl-N word completion lasts while the word is being typed
+        let typing_word = match action {
+            VimAction::InsertChar(c) => c.is_alphanumeric() || c == '_',
+            VimAction::Backspace => true,
+            _ => false,
+        };
+        if !typing_word {
+            app.word_completion = false;
+        }
         match action {
             VimAction::InsertChar(c) => {
                 app.buffer.insert_char(c);
                 app.dirty = true;
+                app.completion_selected = 0;
+                let (_, col) = app.buffer.cursor_position();
+                if c == '@' && mention_at(app.buffer.current_line_content(), col).is_some() {
+                    app.load_contacts();
+                }
             }
             VimAction::InsertTab => {
+                if app.snippet_tab() {
+                    return Ok(());
+                }
                 let tab_width = app.config.general.tab_width;
                 app.buffer.insert_tab(tab_width);
                 app.dirty = true;
@@ -477,6 +1105,41 @@ impl EventDispatcher {
         Ok(())
     }
 
+    /// Overtype in Replace mode. Backspace restores what was overtyped,
+    /// back to where the cursor last moved.
+    fn handle_replace_action(app: &mut App, action: VimAction) -> Result<()> {
+        match action {
+            VimAction::InsertChar(c) => {
+                let original = app.buffer.overtype_char(c);
+                app.replace_originals.push(original);
+                app.dirty = true;
+            }
+            VimAction::InsertNewline => {
+                app.buffer.insert_newline();
+                app.replace_originals.push(None);
+                app.dirty = true;
+            }
+            VimAction::Backspace => match app.replace_originals.pop() {
+                Some(original) => app.buffer.restore_overtyped(original.as_deref()),
+                None => app.buffer.move_left(),
+            },
+            VimAction::MoveLeft
+            | VimAction::MoveRight
+            | VimAction::MoveUp
+            | VimAction::MoveDown => {
+                app.replace_originals.clear();
+                return Self::handle_insert_action(app, action);
+            }
+            VimAction::ExitToNormal => {
+                app.replace_originals.clear();
+                app.set_mode(AppMode::Normal);
+                app.clear_message();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn handle_visual_action(app: &mut App, action: VimAction) -> Result<()> {
         use crate::molecules::editor::VisualType;
 
@@ -548,6 +1211,12 @@ impl EventDispatcher {
             VimAction::MoveLineEnd => app.buffer.move_to_line_end(),
             VimAction::MoveFileStart => app.buffer.move_to_first_line(),
             VimAction::MoveFileEnd => app.buffer.move_to_last_line(),
+            VimAction::MoveParagraphForward
+            | VimAction::MoveParagraphBackward
+            | VimAction::MoveSentenceForward
+            | VimAction::MoveSentenceBackward => {
+                Self::paragraph_or_sentence_motion(&action)(&mut app.buffer)
+            }
 
             // Mode switching
             VimAction::SwitchToVisualCharacter => {
@@ -594,6 +1263,12 @@ impl EventDispatcher {
                 app.clear_message();
             }
 
+            VimAction::EnterCommandMode => {
+                // `'<,'>` refers to the selection being left
+                app.exit_visual_mode();
+                app.command_line = "'<,'>".to_string();
+                app.set_mode(AppMode::Command);
+            }
             VimAction::VisualToggleComment => {
                 app.buffer.save_undo_snapshot();
                 app.visual_toggle_comment();
@@ -653,42 +1328,132 @@ impl EventDispatcher {
         Ok(())
     }
 
-    fn handle_search_action(app: &mut App, action: VimAction, key: KeyEvent) -> Result<()> {
+    /// Move the cursor to the next (or previous) match, announcing wraps
+    /// explicitly and centering the view on the match.
+    fn jump_to_match(app: &mut App, forward: bool) {
+        let (row, col) = app.buffer.cursor_position();
+        let found = if forward {
+            app.buffer.find_next(app.search_pattern(), row, col)
+        } else {
+            app.buffer.find_prev(app.search_pattern(), row, col)
+        };
+        let Some((r, c)) = found else {
+            app.set_message(&format!("Pattern not found: {}", app.search_query));
+            return;
+        };
+
+        app.record_jump();
+        app.buffer.set_cursor(r, c);
+        app.center_cursor();
+        let wrapped = if forward {
+            (r, c) <= (row, col)
+        } else {
+            (r, c) >= (row, col)
+        };
+        if wrapped && forward {
+            app.set_message("search hit BOTTOM, continuing at TOP");
+        } else if wrapped {
+            app.set_message("search hit TOP, continuing at BOTTOM");
+        } else if forward {
+            app.set_message(&format!("/{}", app.search_query));
+        } else {
+            app.set_message(&format!("?{}", app.search_query));
+        }
+    }
+
+    /// Apply a prompt editing key to `text`. Returns whether the text changed.
+    fn edit_prompt(input: &mut PromptInput, text: &mut String, action: VimAction) -> bool {
         match action {
-            VimAction::InsertChar(c) => {
-                app.search_query.push(c);
-                match app.view {
-                    View::DraftList => app.draft_list.add_search_char(c),
-                    View::ArchiveList => app.archive_list.add_search_char(c),
-                    View::Editor => {}
-                }
+            VimAction::InsertChar(c) => input.insert(text, c.encode_utf8(&mut [0; 4])),
+            VimAction::Backspace => return input.backspace(text),
+            VimAction::DeleteChar => input.delete(text),
+            VimAction::PromptDeleteToStart => input.delete_to_start(text),
+            VimAction::PromptDeleteWord => input.delete_word(text),
+            VimAction::MoveUp => input.history_prev(text),
+            VimAction::MoveDown => input.history_next(text),
+            VimAction::MoveLeft => input.move_left(),
+            VimAction::MoveRight => input.move_right(text),
+            VimAction::MoveLineStart => input.move_home(),
+            VimAction::MoveLineEnd => input.move_end(text),
+            _ => return false,
+        }
+        !matches!(
+            action,
+            VimAction::MoveLeft
+                | VimAction::MoveRight
+                | VimAction::MoveLineStart
+                | VimAction::MoveLineEnd
+        )
+    }
+
+    fn handle_search_action(app: &mut App, action: VimAction, key: KeyEvent) -> Result<()> {
+        if action != VimAction::ExitToNormal {
+            if Self::edit_prompt(&mut app.search_input, &mut app.search_query, action) {
+                Self::incremental_list_search(app);
             }
-            VimAction::Backspace => {
-                app.search_query.pop();
-                match app.view {
-                    View::DraftList => app.draft_list.remove_search_char(),
-                    View::ArchiveList => app.archive_list.remove_search_char(),
-                    View::Editor => {}
-                }
+            return Ok(());
+        }
+
+        app.set_mode(AppMode::Normal);
+        if key.code == KeyCode::Esc {
+            app.search_query.clear();
+            if app.view != View::Editor {
+                app.select_list_index(app.list_search_origin);
+            }
+        } else {
+            app.search_input.push_history(&app.search_query);
+            if app.view == View::Editor && !app.search_query.is_empty() {
+                // Enter pressed — jump to first match
+                Self::jump_to_match(app, true);
+            }
+        }
+        Ok(())
+    }
+
+    /// In list views the selection follows the search as it is typed,
+    /// starting from where it was when `/` was pressed.
+    fn incremental_list_search(app: &mut App) {
+        if app.view == View::Editor {
+            return;
+        }
+        app.select_list_index(app.list_search_origin);
+        if !app.search_query.is_empty() {
+            app.jump_to_list_match(true, false);
+        }
+    }
+
+    /// The list filter applies as it is typed; Enter keeps it and Esc
+    /// clears it.
+    fn handle_filter_action(app: &mut App, action: VimAction, key: KeyEvent) -> Result<()> {
+        if action != VimAction::ExitToNormal {
+            if Self::edit_prompt(&mut app.filter_input, &mut app.filter_query, action) {
+                app.set_list_filter(app.filter_query.clone());
+            }
+            return Ok(());
+        }
+
+        app.set_mode(AppMode::Normal);
+        if key.code == KeyCode::Esc {
+            app.filter_query.clear();
+            app.set_list_filter(String::new());
+        } else {
+            app.filter_input.push_history(&app.filter_query);
+        }
+        Ok(())
+    }
+
+    fn handle_command_action(app: &mut App, action: VimAction, key: KeyEvent) -> Result<()> {
+        match action {
+            VimAction::InsertChar(c) => app.command_line.push(c),
+            // Backspace on an empty line leaves the command line, as in Vim
+            VimAction::Backspace if app.command_line.pop().is_none() => {
+                app.set_mode(AppMode::Normal);
             }
             VimAction::ExitToNormal => {
                 app.set_mode(AppMode::Normal);
-                if key.code == KeyCode::Esc {
-                    app.search_query.clear();
-                    match app.view {
-                        View::DraftList => app.draft_list.clear_search(),
-                        View::ArchiveList => app.archive_list.clear_search(),
-                        View::Editor => {}
-                    }
-                } else if app.view == View::Editor && !app.search_query.is_empty() {
-                    // Enter pressed — jump to first match
-                    let (row, col) = app.buffer.cursor_position();
-                    if let Some((r, c)) = app.buffer.find_next(&app.search_query, row, col) {
-                        app.buffer.set_cursor(r, c);
-                        app.set_message(&format!("/{}", app.search_query));
-                    } else {
-                        app.set_message(&format!("Pattern not found: {}", app.search_query));
-                    }
+                let line = std::mem::take(&mut app.command_line);
+                if key.code == KeyCode::Enter {
+                    app.run_ex_command(&line)?;
                 }
             }
             _ => {}
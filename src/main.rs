@@ -1,7 +1,11 @@
-use std::io;
-use std::time::Duration;
+mod cli;
+mod report;
 
-use anyhow::Result;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
 use crossterm::{
     cursor::SetCursorStyle,
     event::{
@@ -9,34 +13,76 @@ use crossterm::{
         Event, KeyCode,
     },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    style::Print,
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
+    },
 };
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
     layout::{Constraint, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
+use unicode_width::UnicodeWidthStr;
 
+use kenotex::atoms::applescript::{install_capture_service, kill_osascript_children};
 use kenotex::coordinator::{App, EventDispatcher};
-use kenotex::types::{AppMode, View};
+use kenotex::molecules::capture::{INBOX_ID, append_inbox_entry, inbox_entry};
+use kenotex::molecules::config::{CheatSheetFormat, generate_cheat_sheet};
+use kenotex::molecules::editor::section_at;
+use kenotex::molecules::import::import_into_drafts;
+use kenotex::molecules::list::resolve_link;
+use kenotex::molecules::share::{
+    expand_transclusions, find_local_assets, rewrite_asset_links, sanitize_for_sharing,
+    share_file_stem,
+};
+use kenotex::types::{AppMode, Note, View};
 
-use kenotex::atoms::storage::file_watcher::{self, FileWatcherHandle};
 use kenotex::atoms::storage::{
-    cleanup_temp_file, read_temp_file, resolve_editor, spawn_editor, write_temp_file,
+    FileNaming, cleanup_temp_file, expand_tilde, find_draft, find_note, in_tmux, load_all_drafts,
+    load_config, read_temp_file, resolve_data_dir, resolve_editor, save_draft, set_tmux_pane_title,
+    spawn_editor, tmux_pane_title, write_share_bundle, write_temp_file,
 };
 use kenotex::atoms::widgets::{
-    ConfirmOverlay, EditorWidget, HintBar, LeaderPopup, ProcessingOverlay, StatusBar,
+    BufferPicker, CompletionPopup, ConfirmOverlay, EditorWidget, HintBar, LeaderPopup,
+    OutlineSidebar, ProcessingOverlay, QuickfixPanel, RenameOverlay, StatsOverlay, StatusBar,
 };
 
 fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.iter().any(|a| a == "--version" || a == "-V") {
+    let args = cli::CliArgs::parse(std::env::args().skip(1))?;
+    if args.version {
         println!("kenotex {}", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
+    if let Some(target) = &args.cheat_sheet {
+        return write_cheat_sheet(target);
+    }
+    if let Some(capture_args) = &args.capture {
+        return capture(capture_args);
+    }
+    if let Some(script_args) = &args.script {
+        return report::run_script(script_args);
+    }
+    if let Some(path) = &args.import {
+        return import_notes(path);
+    }
+    if let Some(query) = &args.share {
+        return share_note(
+            query,
+            args.output.as_deref(),
+            args.passphrase_env.as_deref(),
+        );
+    }
+
+    // Stop any osascript still running before a panic takes the app down
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        kill_osascript_children();
+        default_hook(info);
+    }));
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -51,26 +97,49 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::new()?;
+    if let Some(view) = args.view {
+        app.set_view(view);
+    }
+    if let Some(query) = &args.open {
+        app.open_note(query);
+    }
+    if args.read_only {
+        app.read_only = true;
+        app.set_message("Read-only mode");
+    }
+    if args.accessible {
+        app.accessible = true;
+    }
+    if args.simulate_dispatch {
+        app.enable_simulated_dispatch();
+        app.set_message("Simulated dispatch: blocks will not be sent");
+    } else if args.dry_run {
+        app.enable_dry_run();
+        app.set_message("Dry run: blocks will not be sent");
+    }
 
-    let watcher_handle = if app.config.general.file_watch {
-        let drafts = app.data_dir.join("drafts");
-        let archives = app.data_dir.join("archives");
-        match file_watcher::start_watcher(
-            &drafts,
-            &archives,
-            app.config.general.file_watch_debounce_ms,
-        ) {
-            Ok(handle) => Some(handle),
-            Err(e) => {
-                app.set_message(&format!("File watcher failed: {}", e));
-                None
-            }
-        }
-    } else {
-        None
-    };
+    app.start_file_watcher();
+    app.start_appearance_watcher();
+    app.start_config_watcher();
+
+    // Keep the titles found at start so they can be put back on exit
+    let terminal_title = app.config.general.terminal_title;
+    if terminal_title {
+        execute!(terminal.backend_mut(), Print(PUSH_TITLE))?;
+    }
+    let tmux_title =
+        (app.config.general.tmux_title && in_tmux()).then(|| tmux_pane_title().unwrap_or_default());
 
-    let result = run_app(&mut terminal, &mut app, watcher_handle.as_ref());
+    let result = run_app(&mut terminal, &mut app);
+    app.stop_file_watcher();
+    kill_osascript_children();
+
+    if terminal_title {
+        execute!(terminal.backend_mut(), Print(POP_TITLE))?;
+    }
+    if let Some(title) = tmux_title {
+        set_tmux_pane_title(&title);
+    }
 
     disable_raw_mode()?;
     execute!(
@@ -84,32 +153,179 @@ fn main() -> Result<()> {
     if let Err(e) = result {
         eprintln!("Error: {}", e);
     }
+    if let Err(e) = app.finish_writing_session() {
+        eprintln!("Error: {}", e);
+    }
+    if let Err(e) = app.flush_writing_stats() {
+        eprintln!("Error: {}", e);
+    }
+    if let Err(e) = app.save_search_index() {
+        eprintln!("Error: {}", e);
+    }
 
     Ok(())
 }
 
-fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut App,
-    watcher: Option<&FileWatcherHandle>,
-) -> Result<()> {
-    let tick_rate = Duration::from_millis(100);
+fn write_cheat_sheet(target: &str) -> Result<()> {
+    let config = load_config()?;
+    if target == "-" {
+        print!(
+            "{}",
+            generate_cheat_sheet(&config.keyboard, CheatSheetFormat::Markdown)
+        );
+    } else {
+        let sheet = generate_cheat_sheet(&config.keyboard, CheatSheetFormat::from_path(target));
+        std::fs::write(target, sheet)?;
+        println!("Wrote keymap cheat sheet to {}", target);
+    }
+    Ok(())
+}
+
+/// Export a note for sending to someone else. Without `--output` the sanitized
+/// markdown goes to stdout; a `.md` output gets the same markdown; any other
+/// output becomes a tar.gz bundle with assets (encrypted with a passphrase).
+fn share_note(query: &str, output: Option<&str>, passphrase_env: Option<&str>) -> Result<()> {
+    let config = load_config()?;
+    let data_dir = resolve_data_dir(config.general.data_dir.as_deref());
+    let Some(note) = find_note(&data_dir, query)? else {
+        anyhow::bail!("No note matches '{}'", query);
+    };
+    let subdir = if note.is_archived {
+        "archives"
+    } else {
+        "drafts"
+    };
+    let note_dir = data_dir.join(subdir);
+    let mut notes = load_all_drafts(&data_dir, false)?;
+    notes.extend(load_all_drafts(&data_dir, true)?);
+    let notes: Vec<&Note> = notes.iter().collect();
+    let lookup = |title: &str| resolve_link(title, &notes).map(|n| n.content.clone());
+    let content = expand_transclusions(&note.content, &note.title, &lookup);
+
+    match output {
+        None => print!("{}", sanitize_for_sharing(&content, &note_dir)),
+        Some(path) if passphrase_env.is_none() && path.ends_with(".md") => {
+            std::fs::write(path, sanitize_for_sharing(&content, &note_dir))?;
+            println!("Wrote shareable markdown to {}", path);
+        }
+        Some(path) => {
+            let assets = find_local_assets(&content, &note_dir);
+            let markdown = rewrite_asset_links(&content, &note_dir);
+            write_share_bundle(&markdown, &assets, Path::new(path), passphrase_env)?;
+            let kind = if passphrase_env.is_some() {
+                "encrypted bundle"
+            } else {
+                "bundle"
+            };
+            println!("Wrote {} with {} asset(s) to {}", kind, assets.len(), path);
+        }
+    }
+    Ok(())
+}
+
+/// Append text to the inbox note, or install the macOS Quick Action that
+/// pipes selected text into `kenotex capture --from-service`.
+fn capture(args: &cli::CaptureArgs) -> Result<()> {
+    if args.install_service {
+        let exe = std::env::current_exe()?.display().to_string();
+        let command = format!("'{}' capture --from-service", exe.replace('\'', r"'\''"));
+        let services = dirs::home_dir()
+            .context("Could not determine home directory")?
+            .join("Library/Services");
+        let workflow = install_capture_service(&services, &command)?;
+        println!("Installed {}", workflow.display());
+        println!("Select text in any app and choose Services > Send to Kenotex.");
+        println!(
+            "For Shortcuts, add a \"Run Shell Script\" action running: {}",
+            command
+        );
+        return Ok(());
+    }
+
+    let text = if args.from_service || args.text.is_empty() {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        args.text.join(" ")
+    };
+    if text.trim().is_empty() {
+        anyhow::bail!("Nothing to capture");
+    }
+
+    let config = load_config()?;
+    let data_dir = resolve_data_dir(config.general.data_dir.as_deref());
+    let mut note = match find_draft(&data_dir, INBOX_ID, false)? {
+        Some(note) => note,
+        None if find_draft(&data_dir, INBOX_ID, true)?.is_some() => {
+            anyhow::bail!("The inbox is archived; restore it to capture into it")
+        }
+        None => Note::new(INBOX_ID.to_string(), "Inbox".to_string(), String::new()),
+    };
+    let entry = inbox_entry(&text, chrono::Local::now());
+    note.update_content(append_inbox_entry(&note.content, &entry));
+    let naming = FileNaming::from_name(&config.general.file_naming).unwrap_or_default();
+    save_draft(&data_dir, &note, naming)?;
+
+    if !args.from_service {
+        println!("Captured to inbox");
+    }
+    Ok(())
+}
+
+/// Convert an Apple Notes or Bear export, or a folder of markdown files,
+/// into drafts.
+fn import_notes(path: &str) -> Result<()> {
+    let config = load_config()?;
+    let data_dir = resolve_data_dir(config.general.data_dir.as_deref());
+    let naming = FileNaming::from_name(&config.general.file_naming).unwrap_or_default();
+    let summary = import_into_drafts(&data_dir, &expand_tilde(path), naming)?;
+    println!(
+        "Imported {} note(s) from {} file(s) into {}",
+        summary.notes,
+        summary.files,
+        data_dir.join("drafts").display()
+    );
+    if summary.empty_files > 0 {
+        println!("{} file(s) held no note", summary.empty_files);
+    }
+    Ok(())
+}
+
+/// xterm escapes saving and restoring the window title on the terminal's
+/// title stack.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+
+fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
     let mut last_mode = app.mode;
+    let mut redraw = true;
+    let mut last_input = Instant::now();
+    let mut last_title = String::new();
 
     loop {
         // Update cursor style when mode changes
         if app.mode != last_mode {
             let cursor_style = match app.mode {
                 AppMode::Insert => SetCursorStyle::BlinkingBar,
+                AppMode::Replace => SetCursorStyle::SteadyUnderScore,
                 _ => SetCursorStyle::SteadyBlock,
             };
             execute!(terminal.backend_mut(), cursor_style)?;
             last_mode = app.mode;
         }
 
-        terminal.draw(|f| ui(f, app))?;
+        // Only draw when input or background work changed something, so an
+        // idle editor costs no more than the poll
+        if app.take_redraw() || redraw {
+            terminal.draw(|f| ui(f, app))?;
+            update_title(terminal, app, &mut last_title)?;
+        }
+        redraw = false;
 
-        if event::poll(tick_rate)? {
+        if event::poll(app.poll_timeout(last_input.elapsed()))? {
+            redraw = true;
+            last_input = Instant::now();
             match event::read()? {
                 Event::Key(key) => {
                     if app.mode == AppMode::Processing {
@@ -119,7 +335,7 @@ fn run_app(
                         continue;
                     }
 
-                    if matches!(app.view, View::DraftList | View::ArchiveList)
+                    if matches!(app.view, View::DraftList | View::ArchiveList | View::Trash)
                         && app.mode == AppMode::Normal
                         && !app.vim_mode.is_leader_pending()
                         && EventDispatcher::handle_list_key(app, key)? {
@@ -131,6 +347,8 @@ fn run_app(
                     if app.external_editor_requested {
                         app.external_editor_requested = false;
                         handle_external_editor(terminal, app)?;
+                        // The editor may have set a title of its own
+                        last_title.clear();
                         continue;
                     }
                 }
@@ -142,15 +360,13 @@ fn run_app(
         }
 
         // Process file watcher events (non-blocking)
-        if let Some(watcher) = watcher {
-            while let Ok(event) = watcher.receiver.try_recv() {
-                if let Err(e) = app.handle_file_event(event) {
-                    app.set_message(&format!("File event error: {}", e));
-                }
-            }
-        }
+        app.poll_file_events();
+        app.poll_config_events();
+        app.poll_sync()?;
+        app.poll_appearance();
 
         if app.mode == AppMode::Processing {
+            redraw = true;
             std::thread::sleep(Duration::from_millis(400));
             if !app.process_next_block() {
                 app.finish_processing();
@@ -158,11 +374,14 @@ fn run_app(
         }
 
         app.auto_save_if_needed()?;
+        app.expire_recent_removal();
 
         if app.should_quit {
             if app.dirty {
                 app.save_current_note()?;
             }
+            app.flush_saves()?;
+            app.wait_for_sync()?;
             break;
         }
     }
@@ -170,12 +389,39 @@ fn run_app(
     Ok(())
 }
 
+/// Set the terminal and tmux pane titles to `app.window_title()` when it
+/// changed since `last`, as `terminal_title` and `tmux_title` ask.
+fn update_title(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &App,
+    last: &mut String,
+) -> Result<()> {
+    let general = &app.config.general;
+    if !general.terminal_title && !general.tmux_title {
+        return Ok(());
+    }
+    let title = app.window_title();
+    if title == *last {
+        return Ok(());
+    }
+    if general.terminal_title {
+        execute!(terminal.backend_mut(), SetTitle(&title))?;
+    }
+    if general.tmux_title && in_tmux() {
+        set_tmux_pane_title(&title);
+    }
+    *last = title;
+    Ok(())
+}
+
 fn handle_external_editor(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
 ) -> Result<()> {
     let editor = resolve_editor();
-    let temp_path = write_temp_file(&app.buffer.to_string())?;
+    let stem = share_file_stem(app.current_note.as_ref().map_or("", |n| n.title.as_str()));
+    let temp_path = write_temp_file(&app.buffer.to_string(), &stem)?;
+    let line = app.buffer.cursor_position().0 + 1;
 
     // Suspend TUI
     disable_raw_mode()?;
@@ -187,7 +433,7 @@ fn handle_external_editor(
     )?;
 
     // Spawn editor (blocks until exit)
-    let editor_ok = spawn_editor(&editor, &temp_path);
+    let editor_ok = spawn_editor(&editor, &temp_path, line);
 
     // Restore TUI unconditionally
     enable_raw_mode()?;
@@ -223,7 +469,8 @@ fn ui(f: &mut Frame, app: &App) {
     f.render_widget(Clear, f.area());
     f.render_widget(Block::default().style(bg_style), f.area());
 
-    let hint_height = if app.show_hints { 1 } else { 0 };
+    let show_hints = app.show_hints && !app.accessible;
+    let hint_height = if show_hints { 1 } else { 0 };
     let main_chunks = Layout::vertical([
         Constraint::Min(1),              // [0] content
         Constraint::Length(hint_height), // [1] hint bar
@@ -231,9 +478,31 @@ fn ui(f: &mut Frame, app: &App) {
     ])
     .split(f.area());
 
+    let quickfix = app
+        .quickfix
+        .as_ref()
+        .filter(|_| app.view == View::Editor && app.show_quickfix);
+    let mut content_area = main_chunks[0];
+    let mut panel_area = Rect::default();
+    if let Some(list) = quickfix {
+        let height = QuickfixPanel::height(list, 6);
+        [content_area, panel_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(height)])
+                .areas(main_chunks[0]);
+    }
+
     match app.view {
+        View::Editor if app.show_outline => {
+            let width = (content_area.width / 4).clamp(20, 36);
+            let [outline_area, editor_area] =
+                Layout::horizontal([Constraint::Length(width), Constraint::Min(1)])
+                    .areas(content_area);
+            // After the editor, so a focused sidebar gets the terminal cursor
+            render_editor(f, app, editor_area);
+            render_outline(f, app, outline_area);
+        }
         View::Editor => {
-            render_editor(f, app, main_chunks[0]);
+            render_editor(f, app, content_area);
         }
         View::DraftList => {
             render_draft_list(f, app, main_chunks[0]);
@@ -241,40 +510,258 @@ fn ui(f: &mut Frame, app: &App) {
         View::ArchiveList => {
             render_archive_list(f, app, main_chunks[0]);
         }
+        View::Trash => {
+            render_trash_list(f, app, main_chunks[0]);
+        }
+    }
+
+    // After the editor, so a focused panel gets the terminal cursor
+    if let Some(list) = quickfix {
+        let panel = QuickfixPanel::new(list, theme)
+            .focused(app.quickfix_focused)
+            .locale(app.locale);
+        if app.accessible && app.quickfix_focused {
+            f.set_cursor_position((panel_area.x + 1, panel.selected_row(panel_area)));
+        }
+        f.render_widget(panel, panel_area);
     }
 
-    if app.show_hints {
-        f.render_widget(HintBar::new(app.mode, app.view, theme), main_chunks[1]);
+    if show_hints {
+        f.render_widget(
+            HintBar::new(app.mode, app.view, theme).locale(app.locale),
+            main_chunks[1],
+        );
     }
 
+    // Keep the review keys in sight once other messages are cleared
+    let review_prompt = match app.review_prompt() {
+        Some(prompt) if app.command_message.is_empty() && app.view == View::Editor => prompt,
+        _ => app.command_message.clone(),
+    };
+    let message = app.locale.tr(&review_prompt);
+    let search_status = app.search_status().unwrap_or_default();
+    let search_status = app.locale.tr(&search_status);
+    let selection = if app.accessible {
+        app.selection_status().unwrap_or_default()
+    } else {
+        String::new()
+    };
     let status_bar = StatusBar::new(app.mode, app.view, theme)
-        .message(&app.command_message)
+        .message(&message)
+        .read_only(app.is_read_only())
+        .title_drift(app.title_drift().is_some())
+        .accessible(app.accessible)
+        .selection(&selection)
+        .search_status(&search_status)
+        .word_goal(app.word_goal_progress())
         .search_query(&app.search_query)
+        .filter(app.list_filter())
+        .prompt_cursor(app.prompt_cursor())
+        .command_line(&app.command_line)
         .file_name(
             app.current_note
                 .as_ref()
                 .map(|n| n.title.as_str())
                 .unwrap_or(""),
         );
+    let prompt_cursor = status_bar.cursor_position(main_chunks[2]);
     f.render_widget(status_bar, main_chunks[2]);
 
-    if app.vim_mode.is_leader_pending() {
-        f.render_widget(LeaderPopup::new(theme), f.area());
+    // Screen readers follow the terminal cursor, so keep it on whatever has
+    // focus: the prompt being typed, else the status line for modes that
+    // wait for an answer. The editor and lists place it while rendering.
+    if app.accessible {
+        match prompt_cursor {
+            Some(position) => f.set_cursor_position(position),
+            None if matches!(
+                app.mode,
+                AppMode::Processing | AppMode::ConfirmDelete | AppMode::ConfirmReplace
+            ) =>
+            {
+                f.set_cursor_position((main_chunks[2].x, main_chunks[2].y))
+            }
+            None => {}
+        }
+    }
+
+    if let Some(prefix) = app.vim_mode.leader_prefix() {
+        let popup = LeaderPopup::new(theme, app.vim_mode.leader_bindings(), prefix);
+        f.render_widget(popup.locale(app.locale), f.area());
     }
 
     if app.mode == AppMode::Processing && !app.processing_blocks.is_empty() {
-        let overlay = ProcessingOverlay::new(&app.processing_blocks, theme, app.processing_index);
+        let overlay = ProcessingOverlay::new(&app.processing_blocks, theme, app.processing_index)
+            .locale(app.locale);
+        f.render_widget(overlay, f.area());
+    }
+
+    if let Some(selected) = app.buffer_picker {
+        let current_id = app.current_note.as_ref().map(|n| n.id.as_str());
+        let current = current_id.and_then(|id| app.buffers.position(id));
+        // The current note's title may have changed since it was opened
+        let titles: Vec<String> = app
+            .buffers
+            .entries()
+            .iter()
+            .map(|entry| match &app.current_note {
+                Some(note) if note.id == entry.note.id => note.title.clone(),
+                _ => entry.note.title.clone(),
+            })
+            .collect();
+        f.render_widget(
+            BufferPicker::new(&titles, selected, theme)
+                .current(current)
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((notes, selected)) = &app.backlink_picker {
+        let titles: Vec<String> = notes.iter().map(|n| n.title.clone()).collect();
+        f.render_widget(
+            BufferPicker::new(&titles, *selected, theme)
+                .title("Backlinks")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((tags, selected)) = &app.tag_picker {
+        let titles: Vec<String> = tags
+            .iter()
+            .map(|(tag, count)| format!("#{} ({})", tag, count))
+            .collect();
+        f.render_widget(
+            BufferPicker::new(&titles, *selected, theme)
+                .title("Tags")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((formats, selected)) = &app.export_picker {
+        let titles: Vec<String> = formats.iter().map(|f| f.label().to_string()).collect();
+        f.render_widget(
+            BufferPicker::new(&titles, *selected, theme)
+                .title("Export")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((choices, selected)) = &app.template_picker {
+        let titles: Vec<String> = choices
+            .iter()
+            .map(|c| {
+                c.clone()
+                    .unwrap_or_else(|| app.locale.tr("Blank note").into_owned())
+            })
+            .collect();
+        f.render_widget(
+            BufferPicker::new(&titles, *selected, theme)
+                .title("New note")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((snapshots, selected)) = &app.snapshot_picker {
+        let titles: Vec<String> = snapshots
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}  {}",
+                    s.taken.format("%Y-%m-%d %H:%M"),
+                    app.locale.tr(s.reason.label())
+                )
+            })
+            .collect();
+        f.render_widget(
+            BufferPicker::new(&titles, *selected, theme)
+                .title("History")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some((suggestions, selected)) = &app.spell_picker {
+        f.render_widget(
+            BufferPicker::new(suggestions, *selected, theme)
+                .title("Spelling")
+                .locale(app.locale),
+            f.area(),
+        );
+    }
+
+    if let Some(title) = &app.rename_title {
+        let overlay =
+            RenameOverlay::new(title, app.rename_input.cursor(), theme).locale(app.locale);
+        f.set_cursor_position(overlay.cursor_position(f.area()));
         f.render_widget(overlay, f.area());
     }
 
+    if app.show_stats {
+        let summary = app.stats_summary();
+        f.render_widget(
+            StatsOverlay::new(&summary, theme).locale(app.locale),
+            f.area(),
+        );
+    }
+
     if app.mode == AppMode::ConfirmDelete
         && let Some(title) = &app.pending_delete_title
     {
-        f.render_widget(ConfirmOverlay::new(title, theme), f.area());
+        f.render_widget(
+            ConfirmOverlay::new(title, theme).locale(app.locale),
+            f.area(),
+        );
     }
 }
 
+fn render_outline(f: &mut Frame, app: &App, area: Rect) {
+    let entries = app.outline();
+    let current = section_at(&entries, app.buffer.cursor_position().0);
+    let sidebar = OutlineSidebar::new(&entries, app.theme())
+        .selected(app.outline_selection)
+        .current(current)
+        .locale(app.locale);
+    if app.accessible
+        && let Some(row) = sidebar.selected_row(area)
+    {
+        f.set_cursor_position((area.x + 1, row));
+    }
+    f.render_widget(sidebar, area);
+}
+
+/// The focused pane draws the live buffer; other split panes draw the
+/// note and cursor they were left on.
 fn render_editor(f: &mut Frame, app: &App, area: Rect) {
+    for (index, rect) in app.panes.rects(area) {
+        if index == app.panes.focus() {
+            render_focused_pane(f, app, rect);
+        } else {
+            render_pane(f, app, index, rect);
+        }
+    }
+}
+
+fn render_pane(f: &mut Frame, app: &App, index: usize, area: Rect) {
+    let view = app.pane_view(index);
+    let scroll = app.pane_scroll_offset(index, &view, area.width, area.height);
+    let left = if app.soft_wrap {
+        0
+    } else {
+        app.panes.pane(index).viewport.left()
+    };
+    let editor = EditorWidget::new(&view.lines, view.cursor, app.theme(), app.mode, view.title)
+        .scroll_offset(scroll)
+        .soft_wrap(app.soft_wrap)
+        .horizontal_scroll(left)
+        .focused(false);
+    f.render_widget(editor, area);
+}
+
+fn render_focused_pane(f: &mut Frame, app: &App, area: Rect) {
     let theme = app.theme();
     let title = app
         .current_note
@@ -282,63 +769,141 @@ fn render_editor(f: &mut Frame, app: &App, area: Rect) {
         .map(|n| n.title.as_str())
         .unwrap_or("Untitled");
 
-    let content = app.buffer.to_string();
-    let search_matches = app.buffer.find_all(&app.search_query);
-    let editor = EditorWidget::new(
-        &content,
-        app.buffer.cursor_position(),
-        theme,
-        app.mode,
-        title,
-    )
-    .scroll_offset(app.scroll_offset(area.width, area.height))
-    .visual_selection(app.get_visual_selection())
-    .search_matches(&search_matches);
+    // Only the focused pane draws closed folds; the others show every line
+    let folded = app.folded_lines();
+    let lines = folded.as_strs();
+    let search_matches = folded.matches(&app.search_matches());
+    let spell_errors = folded.matches(&app.spell_errors());
+    let auto_links = folded.matches(&app.auto_link_matches());
+    let scroll_left = app.horizontal_scroll(area.width);
+    let (row, col) = app.buffer.cursor_position();
+    let cursor = (folded.display_row(row), col);
+    let editor = EditorWidget::new(&lines, cursor, theme, app.mode, title)
+        .scroll_offset(app.scroll_offset(area.width, area.height))
+        .soft_wrap(app.soft_wrap)
+        .horizontal_scroll(scroll_left)
+        .visual_selection(app.get_visual_selection().map(|s| folded.selection(s)))
+        .search_matches(&search_matches)
+        .spell_errors(&spell_errors)
+        .auto_links(&auto_links)
+        .folds(folded.summaries());
 
     f.render_widget(editor, area);
 
-    // In Insert mode, show native terminal cursor (I-beam)
-    if app.mode == AppMode::Insert {
+    // In Insert and Replace mode, show the native terminal cursor; in
+    // accessibility mode always, for screen readers
+    if app.accessible || matches!(app.mode, AppMode::Insert | AppMode::Replace) {
         use kenotex::atoms::widgets::wrap_calc;
 
-        let (cursor_row, cursor_col) = app.buffer.cursor_position();
         let inner_x = area.x + 1; // Account for border
         let inner_y = area.y + 1;
         let inner_width = area.width.saturating_sub(2);
 
-        let content_lines: Vec<String> = content.lines().map(String::from).collect();
-        let vpos =
-            wrap_calc::visual_cursor_position(&content_lines, cursor_row, cursor_col, inner_width);
+        let vpos = wrap_calc::visual_cursor_position(
+            &lines,
+            cursor.0,
+            cursor.1,
+            app.wrap_width(inner_width),
+        );
 
-        let cursor_x = inner_x + vpos.col;
+        let cursor_x = inner_x + vpos.col.saturating_sub(scroll_left);
         let scroll = app.scroll_offset(area.width, area.height);
         let cursor_y = inner_y + vpos.rows_before + vpos.wrap_row - scroll;
 
         // Set cursor position for native terminal cursor
         if cursor_y >= inner_y && cursor_y < area.y + area.height - 1 {
             f.set_cursor_position((cursor_x, cursor_y));
+
+            if let Some((kind, _, items)) = app.completions() {
+                let popup = CompletionPopup::new(&items, app.completion_selected, theme)
+                    .marker(kind.marker());
+                let popup_area = popup.area((cursor_x, cursor_y), f.area());
+                f.render_widget(popup, popup_area);
+            }
         }
     }
 }
 
 fn render_draft_list(f: &mut Frame, app: &App, area: Rect) {
     let theme = app.theme();
+    render_note_list(
+        f,
+        app,
+        area,
+        (" Drafts ", theme.accent_color()),
+        ("# ", "No drafts. Press 'n' to create one."),
+    );
+}
+
+fn render_archive_list(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    render_note_list(
+        f,
+        app,
+        area,
+        (" Archive ", theme.warning_color()),
+        ("@ ", "No archived notes."),
+    );
+}
+
+fn render_trash_list(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    render_note_list(
+        f,
+        app,
+        area,
+        (" Trash ", theme.error_color()),
+        ("x ", "Trash is empty"),
+    );
+}
+
+/// Header with counts, sort order and page, then one row per note on the
+/// cursor's page: cursor, mark, icon (a pin for pinned notes), title and
+/// last-updated date.
+fn render_note_list(
+    f: &mut Frame,
+    app: &App,
+    area: Rect,
+    (title, title_color): (&str, Color),
+    (icon, empty_text): (&str, &str),
+) {
+    let theme = app.theme();
+    let list = app.active_list();
 
     let header_chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).split(area);
 
-    let header = Paragraph::new(Line::from(vec![
+    let count = if list.len() == list.total_count() {
+        format!("({} items)", list.total_count())
+    } else {
+        format!("({} of {} items)", list.len(), list.total_count())
+    };
+    let mut header_spans = vec![
         Span::styled(
-            " Drafts ",
+            title,
             Style::default()
-                .fg(theme.accent_color())
+                .fg(title_color)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(
-            format!("({} items)", app.draft_list.total_count()),
+        Span::styled(count, Style::default().fg(theme.border_color())),
+    ];
+    if list.marked_count() > 0 {
+        header_spans.push(Span::styled(
+            format!(" {} selected", list.marked_count()),
+            Style::default().fg(theme.warning_color()),
+        ));
+    }
+    header_spans.push(Span::styled(
+        format!(" sorted by {}", list.sort_order().label()),
+        Style::default().fg(theme.border_color()),
+    ));
+    let (page, page_number, page_count) = list.page(app.config.general.list_page_size);
+    if page_count > 1 {
+        header_spans.push(Span::styled(
+            format!(" page {}/{}", page_number, page_count),
             Style::default().fg(theme.border_color()),
-        ),
-    ]))
-    .block(
+        ));
+    }
+    let header = Paragraph::new(Line::from(header_spans)).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.border_color()))
@@ -346,11 +911,11 @@ fn render_draft_list(f: &mut Frame, app: &App, area: Rect) {
     );
     f.render_widget(header, header_chunks[0]);
 
-    let notes = app.draft_list.filtered_notes();
-    let selected_idx = app.draft_list.selected_index();
+    let notes = list.filtered_notes();
+    let selected_idx = list.selected_index() - page.start;
 
     if notes.is_empty() {
-        let empty = Paragraph::new("No drafts. Press 'n' to create one.")
+        let empty = Paragraph::new(empty_text)
             .style(Style::default().fg(theme.border_color()))
             .block(
                 Block::default()
@@ -358,108 +923,58 @@ fn render_draft_list(f: &mut Frame, app: &App, area: Rect) {
                     .border_style(Style::default().fg(theme.border_color())),
             );
         f.render_widget(empty, header_chunks[1]);
-    } else {
-        let items: Vec<ListItem> = notes
-            .iter()
-            .enumerate()
-            .map(|(idx, note)| {
-                let is_selected = idx == selected_idx;
-                let style = if is_selected {
-                    Style::default()
-                        .bg(theme.selection_color())
-                        .fg(theme.fg_color())
-                } else {
-                    Style::default().fg(theme.fg_color())
-                };
-
-                let prefix = if is_selected { "> " } else { "  " };
-                let selected_marker = if note.selected { "* " } else { "" };
-
-                ListItem::new(Line::from(vec![
-                    Span::styled(prefix, style),
-                    Span::styled(selected_marker, Style::default().fg(theme.warning_color())),
-                    Span::styled(&note.title, style.add_modifier(Modifier::BOLD)),
-                ]))
-            })
-            .collect();
-
-        let list = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.border_color()))
-                .style(Style::default().bg(theme.bg_color())),
-        );
-        f.render_widget(list, header_chunks[1]);
+        return;
     }
-}
-
-fn render_archive_list(f: &mut Frame, app: &App, area: Rect) {
-    let theme = app.theme();
 
-    let header_chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).split(area);
+    let inner_width = header_chunks[1].width.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = notes[page]
+        .iter()
+        .enumerate()
+        .map(|(idx, note)| {
+            let is_selected = idx == selected_idx;
+            let style = if is_selected {
+                Style::default()
+                    .bg(theme.selection_color())
+                    .fg(theme.fg_color())
+            } else {
+                Style::default().fg(theme.fg_color())
+            };
 
-    let header = Paragraph::new(Line::from(vec![
-        Span::styled(
-            " Archive ",
-            Style::default()
-                .fg(theme.warning_color())
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(
-            format!("({} items)", app.archive_list.len()),
-            Style::default().fg(theme.border_color()),
-        ),
-    ]))
-    .block(
+            let prefix = if is_selected { "> " } else { "  " };
+            let marker = if note.selected { "* " } else { "  " };
+            let icon = if note.is_pinned() { "📌" } else { icon };
+            let date = note.updated_at.format("%Y-%m-%d").to_string();
+            let used = prefix.len() + marker.len() + icon.width() + note.title.width();
+            let gap = inner_width.saturating_sub(used + date.len()).max(1);
+
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(marker, Style::default().fg(theme.warning_color())),
+                Span::styled(icon, Style::default().fg(title_color)),
+                Span::styled(&note.title, style.add_modifier(Modifier::BOLD)),
+                Span::styled(" ".repeat(gap), style),
+                Span::styled(date, style.fg(theme.border_color())),
+            ]))
+        })
+        .collect();
+
+    let list_widget = List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.border_color()))
             .style(Style::default().bg(theme.bg_color())),
     );
-    f.render_widget(header, header_chunks[0]);
-
-    let notes = app.archive_list.filtered_notes();
-    let selected_idx = app.archive_list.selected_index();
-
-    if notes.is_empty() {
-        let empty = Paragraph::new("No archived notes.")
-            .style(Style::default().fg(theme.border_color()))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border_color())),
-            );
-        f.render_widget(empty, header_chunks[1]);
-    } else {
-        let items: Vec<ListItem> = notes
-            .iter()
-            .enumerate()
-            .map(|(idx, note)| {
-                let is_selected = idx == selected_idx;
-                let style = if is_selected {
-                    Style::default()
-                        .bg(theme.selection_color())
-                        .fg(theme.fg_color())
-                } else {
-                    Style::default().fg(theme.fg_color())
-                };
-
-                let prefix = if is_selected { "> " } else { "  " };
-
-                ListItem::new(Line::from(vec![
-                    Span::styled(prefix, style),
-                    Span::styled("@ ", Style::default().fg(theme.warning_color())),
-                    Span::styled(&note.title, style.add_modifier(Modifier::BOLD)),
-                ]))
-            })
-            .collect();
+    // The state scrolls the list so the selected note stays in view
+    let mut state = ListState::default().with_selected(Some(selected_idx));
+    f.render_stateful_widget(list_widget, header_chunks[1], &mut state);
+    if app.accessible {
+        set_list_cursor(f, header_chunks[1], selected_idx - state.offset());
+    }
+}
 
-        let list = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.border_color()))
-                .style(Style::default().bg(theme.bg_color())),
-        );
-        f.render_widget(list, header_chunks[1]);
+/// Put the terminal cursor on the selected row of a bordered list.
+fn set_list_cursor(f: &mut Frame, area: Rect, selected: usize) {
+    if selected < area.height.saturating_sub(2) as usize {
+        f.set_cursor_position((area.x + 1, area.y + 1 + selected as u16));
     }
 }
@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+
+/// Replaced by the daily note's title in `[daily] template`.
+pub const DATE_PLACEHOLDER: &str = "{{date}}";
+
+/// Title of the daily note for `date`, with `format` (a chrono format).
+pub fn daily_title(date: NaiveDate, format: &str) -> String {
+    date.format(format).to_string()
+}
+
+/// The date a note titled `title` is the daily note of, if any.
+pub fn daily_date(title: &str, format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(title.trim(), format).ok()
+}
+
+/// Content of a new daily note for `date`: `template` with `{{date}}`
+/// filled in, or just the title heading when the template is empty.
+pub fn daily_content(date: NaiveDate, format: &str, template: &str) -> String {
+    let title = daily_title(date, format);
+    if template.trim().is_empty() {
+        return format!("# {}\n", title);
+    }
+    template.replace(DATE_PLACEHOLDER, &title)
+}
+
+/// Among `dates`, the `count`th daily note after `from` (or before it).
+pub fn adjacent_daily(
+    dates: &[NaiveDate],
+    from: NaiveDate,
+    forward: bool,
+    count: usize,
+) -> Option<NaiveDate> {
+    let mut dates: Vec<NaiveDate> = dates
+        .iter()
+        .copied()
+        .filter(|&d| if forward { d > from } else { d < from })
+        .collect();
+    dates.sort();
+    dates.dedup();
+    if !forward {
+        dates.reverse();
+    }
+    dates.get(count.max(1) - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_titles_and_content() {
+        assert_eq!(daily_title(day(3), "%Y-%m-%d"), "2024-06-03");
+        assert_eq!(daily_date("2024-06-03", "%Y-%m-%d"), Some(day(3)));
+        assert_eq!(daily_date("Monday 2024-06-03", "%Y-%m-%d"), None);
+        assert_eq!(daily_date("Mon 03 Jun 2024", "%a %d %b %Y"), Some(day(3)));
+        assert_eq!(
+            daily_content(day(3), "%Y-%m-%d", "# {{date}}\n\n{{agenda}}\n"),
+            "# 2024-06-03\n\n{{agenda}}\n"
+        );
+        assert_eq!(daily_content(day(3), "%d.%m.%Y", ""), "# 03.06.2024\n");
+    }
+
+    #[test]
+    fn test_adjacent_daily() {
+        let dates = [day(1), day(9), day(4), day(6)];
+        assert_eq!(adjacent_daily(&dates, day(4), true, 1), Some(day(6)));
+        assert_eq!(adjacent_daily(&dates, day(4), true, 2), Some(day(9)));
+        assert_eq!(adjacent_daily(&dates, day(4), false, 1), Some(day(1)));
+        assert_eq!(adjacent_daily(&dates, day(5), false, 1), Some(day(4)));
+        assert_eq!(adjacent_daily(&dates, day(9), true, 1), None);
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Local};
+
+/// Draft id of the note that collects captured text.
+pub const INBOX_ID: &str = "inbox";
+
+const INBOX_HEADER: &str = "# Inbox\n";
+
+/// Format captured text as a timestamped inbox entry.
+pub fn inbox_entry(text: &str, now: DateTime<Local>) -> String {
+    format!("### {}\n\n{}\n", now.format("%Y-%m-%d %H:%M"), text.trim())
+}
+
+/// Append an entry to the inbox note, starting a new inbox if it is empty.
+pub fn append_inbox_entry(content: &str, entry: &str) -> String {
+    let mut out = if content.trim().is_empty() {
+        INBOX_HEADER.to_string()
+    } else {
+        content.to_string()
+    };
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push('\n');
+    out.push_str(entry);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_inbox_entry() {
+        let now = Local.with_ymd_and_hms(2024, 6, 1, 14, 5, 0).unwrap();
+        assert_eq!(
+            inbox_entry("  selected text\n", now),
+            "### 2024-06-01 14:05\n\nselected text\n"
+        );
+    }
+
+    #[test]
+    fn test_append_inbox_entry() {
+        assert_eq!(append_inbox_entry("", "entry\n"), "# Inbox\n\nentry\n");
+        assert_eq!(
+            append_inbox_entry("# Inbox\n\nold", "new\n"),
+            "# Inbox\n\nold\n\nnew\n"
+        );
+    }
+}
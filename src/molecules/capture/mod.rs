@@ -0,0 +1,7 @@
+mod daily;
+mod inbox;
+mod template;
+
+pub use daily::{DATE_PLACEHOLDER, adjacent_daily, daily_content, daily_date, daily_title};
+pub use inbox::{INBOX_ID, append_inbox_entry, inbox_entry};
+pub use template::fill_template;
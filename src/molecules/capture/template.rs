@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+
+/// `template` with `{{date}}` (`YYYY-MM-DD`), `{{time}}` (`HH:MM`) and
+/// `{{title}}` filled in for a note created at `now`.
+pub fn fill_template(template: &str, title: &str, now: NaiveDateTime) -> String {
+    template
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+        .replace("{{title}}", title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_fill_template() {
+        let now = NaiveDate::from_ymd_opt(2024, 6, 3)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap();
+        assert_eq!(
+            fill_template("# {{title}}\n{{date}} {{time}}\n{{other}}", "Standup", now),
+            "# Standup\n2024-06-03 09:05\n{{other}}"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveTime;
+
+/// Whether the `[appearance]` schedule is dark at `time`: from `dark_at`
+/// until `light_at`, across midnight when `dark_at` is the later of the
+/// two. `None` when either is not `HH:MM`.
+pub fn scheduled_dark(time: NaiveTime, light_at: &str, dark_at: &str) -> Option<bool> {
+    let light_at = NaiveTime::parse_from_str(light_at.trim(), "%H:%M").ok()?;
+    let dark_at = NaiveTime::parse_from_str(dark_at.trim(), "%H:%M").ok()?;
+    Some(if light_at <= dark_at {
+        time < light_at || time >= dark_at
+    } else {
+        time >= dark_at && time < light_at
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_scheduled_dark() {
+        assert_eq!(scheduled_dark(at(6, 59), "07:00", "19:00"), Some(true));
+        assert_eq!(scheduled_dark(at(7, 0), "07:00", "19:00"), Some(false));
+        assert_eq!(scheduled_dark(at(19, 0), "07:00", "19:00"), Some(true));
+        // Dark during the day, for night owls
+        assert_eq!(scheduled_dark(at(12, 0), "20:00", "08:00"), Some(true));
+        assert_eq!(scheduled_dark(at(23, 0), "20:00", "08:00"), Some(false));
+        assert_eq!(scheduled_dark(at(12, 0), "7am", "19:00"), None);
+    }
+}
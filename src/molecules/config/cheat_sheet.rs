@@ -0,0 +1,360 @@
+//! Keymap cheat sheet generation from the effective `KeyboardConfig`.
+
+use crate::types::{KeyboardConfig, LeaderCommand};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheatSheetFormat {
+    Markdown,
+    Html,
+}
+
+impl CheatSheetFormat {
+    /// Pick a format from an output path: `.html`/`.htm` → HTML, otherwise Markdown.
+    pub fn from_path(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".html") || lower.ends_with(".htm") {
+            CheatSheetFormat::Html
+        } else {
+            CheatSheetFormat::Markdown
+        }
+    }
+}
+
+/// A titled group of `(key, description)` rows.
+pub struct KeymapSection {
+    pub title: &'static str,
+    pub entries: Vec<(String, String)>,
+}
+
+/// Render a binding string like `ctrl+r` as `Ctrl+R` and `" "` as `Space`.
+pub fn display_key(binding: &str) -> String {
+    if binding == " " {
+        return "Space".to_string();
+    }
+    if let Some(rest) = binding.strip_prefix("ctrl+") {
+        return format!("Ctrl+{}", rest.to_uppercase());
+    }
+    binding.to_string()
+}
+
+fn row(key: impl Into<String>, desc: &str) -> (String, String) {
+    (key.into(), desc.to_string())
+}
+
+/// The row of a leader binding, naming the ex command that does the same.
+fn leader_row(keys: &str, command: LeaderCommand) -> (String, String) {
+    let (ex, desc) = match command {
+        LeaderCommand::Export => (":export", "Export note (Markdown, HTML, PDF)"),
+        LeaderCommand::Buffers => (":ls", "Pick an open note"),
+        LeaderCommand::Backlinks => (":backlinks", "Notes linking here"),
+        LeaderCommand::Tags => (":tags", "Tag browser"),
+        LeaderCommand::Rename => (":rename", "Rename note"),
+        LeaderCommand::SyncTitle => (":sync-title", "Set the title field to the first heading"),
+        LeaderCommand::Today => (":today", "Today's daily note"),
+        LeaderCommand::GitSync => (":sync", "Git sync the data directory"),
+        LeaderCommand::RestartWatcher => return row(keys, "Restart file watcher"),
+        LeaderCommand::Outline => return row(keys, "Outline sidebar"),
+        LeaderCommand::CheatSheet => return row(keys, "Keymap cheat sheet"),
+        command => return row(keys, command.label()),
+    };
+    row(format!("{} or {}", keys, ex), desc)
+}
+
+/// Build the effective keymap grouped by mode.
+pub fn keymap_sections(keys: &KeyboardConfig) -> Vec<KeymapSection> {
+    let k = |b: &str| display_key(b);
+    let leader = |b: &str| format!("Space {}", b);
+
+    vec![
+        KeymapSection {
+            title: "Normal Mode",
+            entries: vec![
+                row(
+                    format!(
+                        "{}/{}/{}/{}",
+                        k(&keys.move_left),
+                        k(&keys.move_down),
+                        k(&keys.move_up),
+                        k(&keys.move_right)
+                    ),
+                    "Move left/down/up/right",
+                ),
+                row(
+                    format!("{}/{}", k(&keys.word_forward), k(&keys.word_backward)),
+                    "Word forward/backward",
+                ),
+                row(
+                    format!("{}/{}", k(&keys.line_start), k(&keys.line_end)),
+                    "Line start/end",
+                ),
+                row(
+                    format!(
+                        "{}{}/{}",
+                        k(&keys.file_start),
+                        k(&keys.file_start),
+                        k(&keys.file_end)
+                    ),
+                    "File start/end",
+                ),
+                row("{ / }", "Previous/next paragraph"),
+                row("( / )", "Previous/next sentence"),
+                row("]t/[t", "Next/previous open task"),
+                row("[T", "First open task"),
+                row(k(&keys.insert), "Insert mode"),
+                row(k(&keys.insert_append), "Insert mode (append)"),
+                row(k(&keys.insert_line_start), "Insert at line start"),
+                row(k(&keys.insert_line_end), "Insert at line end"),
+                row(k(&keys.insert_line_below), "Insert line below"),
+                row(k(&keys.insert_line_above), "Insert line above"),
+                row(k(&keys.delete_char), "Delete character"),
+                row("Ctrl+A/Ctrl+X", "Increment/decrement number"),
+                row("r{char}", "Replace character under cursor"),
+                row("R", "Replace mode (overtype)"),
+                row(
+                    format!("{}{}", k(&keys.delete_line), k(&keys.delete_line)),
+                    "Delete line",
+                ),
+                row(
+                    format!("{}{{motion}}", k(&keys.delete_line)),
+                    "Delete with motion",
+                ),
+                row(format!("{}{}", k(&keys.yank), k(&keys.yank)), "Yank line"),
+                row(format!("{}{{motion}}", k(&keys.yank)), "Yank with motion"),
+                row(
+                    format!("{}{{motion}}", k(&keys.change)),
+                    "Change (delete, then insert)",
+                ),
+                row(
+                    "i{obj} / a{obj}",
+                    "Text object after an operator (w \" ' ` ( [ { <)",
+                ),
+                row("{count}{cmd}", "Repeat motion/operator count times"),
+                row(".", "Repeat last change"),
+                row(k(&keys.paste_after), "Paste after"),
+                row(k(&keys.paste_before), "Paste before"),
+                row(k(&keys.undo), "Undo"),
+                row(k(&keys.redo), "Redo"),
+                row(k(&keys.visual_mode), "Visual mode"),
+                row(k(&keys.visual_line_mode), "Visual Line mode"),
+                row(k(&keys.visual_block_mode), "Visual Block mode"),
+                row(format!("{} or f", k(&keys.search)), "Search"),
+                row(k(&keys.search_next), "Next match"),
+                row(k(&keys.search_prev), "Previous match"),
+                row("m{a-z}", "Set mark"),
+                row("'{a-z} / `{a-z}", "Jump to mark line / position"),
+                row("Ctrl+O/Ctrl+I", "Older/newer jump position"),
+                row("Ctrl+D/Ctrl+U", "Scroll half a screen"),
+                row("Ctrl+F/Ctrl+B", "Scroll a screen"),
+                row("zz/zt/zb", "Cursor line to center/top/bottom"),
+                row("za/zR/zM", "Toggle fold / open all / close all"),
+                row("]s/[s, z=", "Next/previous misspelling, suggestions"),
+                row(
+                    format!("{}f or Enter", k(&keys.file_start)),
+                    "Follow [[link]] (creates the note if missing)",
+                ),
+                row(":", "Command line (:cd, :pwd, :s, :set wrap)"),
+                row(":bn/:bp or ]b/[b", "Next/previous open note"),
+                row("]d/[d", "Next/previous daily note"),
+                row(":sp/:vs or Ctrl+W s/v", "Split the editor"),
+                row(
+                    ":vs <title> / :vsview <title>",
+                    "Open a note beside this one (read-only)",
+                ),
+                row("Ctrl+W w/h/j/k/l", "Focus another pane"),
+                row("Ctrl+W +/-/</>/=", "Resize panes / even them out"),
+                row(":close/:only or Ctrl+W c/o", "Close this/other panes"),
+                row(":reindex", "Rebuild the search index"),
+                row(":tag NAME", "Add #NAME to the note (or selected notes)"),
+                row(":review", "Weekly review (a/n/p/t/q)"),
+                row(":syncreminders", "Check off completed reminders"),
+                row(":agenda", "Insert today's calendar events"),
+                row(":rename [title]", "Rename note"),
+                row(":sync-title", "Set the title field to the first heading"),
+                row(":sync", "Git sync the data directory"),
+                row(":today", "Open or create today's daily note"),
+                row(":new [template [title]]", "New note from a template"),
+                row(":export FORMAT [PATH]", "Export as md, html or pdf"),
+                row(":import PATH", "Import markdown files as drafts"),
+                row(":lint-blocks", "List malformed smart blocks"),
+                row(":split-headings[!]", "Split the note into drafts"),
+                row(
+                    ":copen/:cclose or ]q/[q",
+                    "Locations panel, next/previous location",
+                ),
+                row(
+                    ":%s/foo/bar/gc",
+                    "Substitute (% all, '<,'> selection; g all, c confirm)",
+                ),
+                row(":replace-all /foo/bar/", "Replace across all drafts"),
+                row(format!("{}cc", k(&keys.file_start)), "Toggle comment"),
+                row(">/<", "Indent/dedent line"),
+                row(k(&keys.cycle_theme), "Cycle theme"),
+                row("Ctrl+L", "Reload from disk (merges unsaved edits)"),
+                row("Ctrl+G", "External editor"),
+                row("Ctrl+Q/Ctrl+C", "Quit"),
+            ],
+        },
+        KeymapSection {
+            title: "Visual Mode",
+            entries: vec![
+                row(k(&keys.delete_line), "Delete selection"),
+                row(k(&keys.yank), "Yank selection"),
+                row(k(&keys.visual_comment), "Toggle comment"),
+                row(">/<", "Indent/dedent selection"),
+                row("I/A", "Insert at start/end (Line and Block)"),
+                row(
+                    format!("{}{}", k(&keys.file_start), k(&keys.file_start)),
+                    "File start",
+                ),
+                row("Esc", "Exit Visual mode"),
+            ],
+        },
+        KeymapSection {
+            title: "Insert Mode",
+            entries: vec![
+                row("Esc/Ctrl+C", "Normal mode"),
+                row("Tab", "Expand snippet / next tabstop / spaces"),
+                row("Shift+Tab", "Dedent line"),
+                row("@name Tab", "Complete a contact"),
+                row("#tag/[[title Tab", "Complete a tag or note title"),
+                row("Ctrl+N", "Complete a word of the note"),
+                row("Ctrl+G", "External editor"),
+            ],
+        },
+        KeymapSection {
+            title: "Leader",
+            entries: keys
+                .leader_bindings()
+                .into_iter()
+                .map(|(sequence, command)| leader_row(&leader(&sequence), command))
+                .chain([row(":history", "Restore a snapshot of the note")])
+                .collect(),
+        },
+        KeymapSection {
+            title: "List View",
+            entries: vec![
+                row(
+                    format!("{}/{}", k(&keys.move_down), k(&keys.move_up)),
+                    "Navigate",
+                ),
+                row("Enter/l/i", "Open note"),
+                row("a", "Archive note(s)"),
+                row("r", "Restore note(s)"),
+                row("d", "Move note(s) to the trash"),
+                row("u", "Undo the last delete or archive"),
+                row("n", "New note"),
+                row("A", "Toggle archive view"),
+                row("T / :trash", "Trash: r restores, d purges"),
+                row("/", "Search (jump to match)"),
+                row("n/N", "Next/previous match"),
+                row("f", "Filter (persistent)"),
+                row("gg/G", "First/last note"),
+                row("Space", "Toggle selection"),
+                row("s", "Cycle sort (updated, created, title, size)"),
+                row("p", "Pin / unpin note"),
+                row("Esc", "Clear search, filter, then back"),
+            ],
+        },
+    ]
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Generate a cheat sheet for the given keymap.
+pub fn generate_cheat_sheet(keys: &KeyboardConfig, format: CheatSheetFormat) -> String {
+    let sections = keymap_sections(keys);
+    match format {
+        CheatSheetFormat::Markdown => render_markdown(&sections, &keys.layout),
+        CheatSheetFormat::Html => render_html(&sections, &keys.layout),
+    }
+}
+
+fn render_markdown(sections: &[KeymapSection], layout: &str) -> String {
+    let mut out = format!("# Kenotex Keymap\n\nLayout: {}\n", layout);
+    for section in sections {
+        out.push_str(&format!(
+            "\n## {}\n\n| Key | Action |\n|-----|--------|\n",
+            section.title
+        ));
+        for (key, desc) in &section.entries {
+            out.push_str(&format!("| `{}` | {} |\n", escape_markdown_cell(key), desc));
+        }
+    }
+    out
+}
+
+fn render_html(sections: &[KeymapSection], layout: &str) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Kenotex Keymap</title>\n</head>\n<body>\n",
+    );
+    out.push_str(&format!(
+        "<h1>Kenotex Keymap</h1>\n<p>Layout: {}</p>\n",
+        escape_html(layout)
+    ));
+    for section in sections {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>Key</th><th>Action</th></tr>\n",
+            escape_html(section.title)
+        ));
+        for (key, desc) in &section.entries {
+            out.push_str(&format!(
+                "<tr><td><kbd>{}</kbd></td><td>{}</td></tr>\n",
+                escape_html(key),
+                escape_html(desc)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_key() {
+        assert_eq!(display_key("ctrl+r"), "Ctrl+R");
+        assert_eq!(display_key(" "), "Space");
+        assert_eq!(display_key("j"), "j");
+    }
+
+    #[test]
+    fn test_markdown_uses_overrides() {
+        let keys = KeyboardConfig::colemak();
+        let sheet = generate_cheat_sheet(&keys, CheatSheetFormat::Markdown);
+        assert!(sheet.starts_with("# Kenotex Keymap"));
+        assert!(sheet.contains("Layout: colemak"));
+        assert!(sheet.contains("| `z` | Undo |"));
+        assert!(sheet.contains("## Leader"));
+    }
+
+    #[test]
+    fn test_html_escapes() {
+        let sheet = generate_cheat_sheet(&KeyboardConfig::default(), CheatSheetFormat::Html);
+        assert!(sheet.contains("<kbd>&gt;/&lt;</kbd>"));
+        assert!(sheet.contains("<h2>Visual Mode</h2>"));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            CheatSheetFormat::from_path("keys.HTML"),
+            CheatSheetFormat::Html
+        );
+        assert_eq!(
+            CheatSheetFormat::from_path("keys.md"),
+            CheatSheetFormat::Markdown
+        );
+    }
+}
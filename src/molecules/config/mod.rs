@@ -1,5 +1,9 @@
+mod appearance;
+mod cheat_sheet;
 mod keybindings;
 mod themes;
 
+pub use appearance::scheduled_dark;
+pub use cheat_sheet::{CheatSheetFormat, KeymapSection, generate_cheat_sheet, keymap_sections};
 pub use keybindings::Keybindings;
 pub use themes::ThemeManager;
@@ -1,4 +1,4 @@
-use crate::types::Theme;
+use crate::types::{ColorOverrides, Theme};
 
 pub struct ThemeManager {
     themes: Vec<Theme>,
@@ -17,7 +17,7 @@ impl ThemeManager {
         let themes = Theme::all_themes();
         let current_index = themes
             .iter()
-            .position(|t| t.name.to_lowercase().replace(' ', "_") == theme_name.to_lowercase())
+            .position(|t| t.key() == theme_name.to_lowercase())
             .unwrap_or(0);
 
         Self {
@@ -48,7 +48,7 @@ impl ThemeManager {
         if let Some(idx) = self
             .themes
             .iter()
-            .position(|t| t.name.to_lowercase().replace(' ', "_") == name.to_lowercase())
+            .position(|t| t.key() == name.to_lowercase())
         {
             self.current_index = idx;
             true
@@ -57,6 +57,25 @@ impl ThemeManager {
         }
     }
 
+    /// Add user `themes` after the built-in ones, in order. One with the
+    /// name of a theme already here takes its place. The current theme
+    /// stays selected.
+    pub fn add_themes(&mut self, themes: Vec<Theme>) {
+        for theme in themes {
+            match self.themes.iter().position(|t| t.key() == theme.key()) {
+                Some(idx) => self.themes[idx] = theme,
+                None => self.themes.push(theme),
+            }
+        }
+    }
+
+    /// Apply user color overrides to every theme so they survive cycling.
+    pub fn apply_overrides(&mut self, overrides: &ColorOverrides) {
+        for theme in &mut self.themes {
+            theme.apply_overrides(overrides);
+        }
+    }
+
     pub fn theme_names(&self) -> Vec<&str> {
         self.themes.iter().map(|t| t.name.as_str()).collect()
     }
@@ -95,6 +114,15 @@ mod tests {
         manager.cycle_next();
         assert_eq!(manager.current().name, "Catppuccin Latte");
 
+        manager.cycle_next();
+        assert_eq!(manager.current().name, "High Contrast");
+
+        manager.cycle_next();
+        assert_eq!(manager.current().name, "Deuteranopia");
+
+        manager.cycle_next();
+        assert_eq!(manager.current().name, "Protanopia");
+
         manager.cycle_next();
         assert_eq!(manager.current().name, "Tokyo Night");
     }
@@ -104,5 +132,64 @@ mod tests {
         let mut manager = ThemeManager::new();
         assert!(manager.set_theme("gruvbox"));
         assert_eq!(manager.current().name, "Gruvbox");
+        assert!(manager.set_theme("high_contrast"));
+        assert_eq!(manager.current().name, "High Contrast");
+    }
+
+    #[test]
+    fn test_user_themes_join_the_cycle() {
+        let mut manager = ThemeManager::with_theme("protanopia");
+        let dusk = Theme {
+            name: "Dusk".to_string(),
+            ..Theme::nord()
+        };
+        let nord = Theme {
+            bg: "#000000".to_string(),
+            ..Theme::nord()
+        };
+        manager.add_themes(vec![dusk, nord]);
+        assert_eq!(manager.theme_names().len(), 11);
+        assert_eq!(manager.cycle_next().name, "Dusk");
+        assert!(manager.set_theme("nord"));
+        assert_eq!(manager.current().bg, "#000000");
+        assert!(manager.set_theme("DUSK"));
+    }
+
+    #[test]
+    fn test_color_overrides_survive_cycling() {
+        let mut manager = ThemeManager::new();
+        manager.apply_overrides(&ColorOverrides {
+            visual_line: Some("#112233".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(manager.current().visual_line, "#112233");
+        let default_char = Theme::gruvbox().visual_char;
+        manager.cycle_next();
+        assert_eq!(manager.current().visual_line, "#112233");
+        assert_eq!(manager.current().visual_char, default_char);
+    }
+
+    #[test]
+    fn test_visual_colors_distinct_from_search() {
+        for theme in Theme::all_themes() {
+            let search = theme.search_match_color();
+            assert_ne!(theme.visual_char_color(), search, "{}", theme.name);
+            assert_ne!(theme.visual_line_color(), search, "{}", theme.name);
+            assert_ne!(theme.visual_block_color(), search, "{}", theme.name);
+        }
+    }
+
+    #[test]
+    fn test_status_colors_distinct() {
+        for theme in Theme::all_themes() {
+            let colors = [
+                theme.success_color(),
+                theme.warning_color(),
+                theme.error_color(),
+            ];
+            assert_ne!(colors[0], colors[1], "{}", theme.name);
+            assert_ne!(colors[0], colors[2], "{}", theme.name);
+            assert_ne!(colors[1], colors[2], "{}", theme.name);
+        }
     }
 }
@@ -0,0 +1,95 @@
+use chrono::NaiveDate;
+
+use crate::types::AgendaEvent;
+
+/// A line of a note that `:agenda` replaces with the agenda, for daily
+/// note templates.
+pub const AGENDA_PLACEHOLDER: &str = "{{agenda}}";
+
+/// The agenda section for `date`: all-day events first, then the others by
+/// start time.
+pub fn format_agenda(events: &[AgendaEvent], date: NaiveDate) -> String {
+    let mut events: Vec<&AgendaEvent> = events.iter().collect();
+    events.sort_by_key(|e| (!e.all_day, e.start));
+    let mut out = format!("## Agenda {}\n", date.format("%Y-%m-%d"));
+    if events.is_empty() {
+        out.push_str("\n- No events\n");
+        return out;
+    }
+    out.push('\n');
+    for event in events {
+        if event.all_day {
+            out.push_str(&format!("- All day: {}\n", event.title));
+        } else {
+            out.push_str(&format!(
+                "- {}–{} {}\n",
+                event.start.format("%H:%M"),
+                event.end.format("%H:%M"),
+                event.title
+            ));
+        }
+    }
+    out
+}
+
+/// `content` with its `{{agenda}}` lines replaced by `agenda`, or `None`
+/// if it has none.
+pub fn fill_agenda_placeholder(content: &str, agenda: &str) -> Option<String> {
+    if !content.lines().any(|l| l.trim() == AGENDA_PLACEHOLDER) {
+        return None;
+    }
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|l| {
+            if l.trim() == AGENDA_PLACEHOLDER {
+                agenda.trim_end()
+            } else {
+                l
+            }
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn event(title: &str, start: u32, end: u32, all_day: bool) -> AgendaEvent {
+        AgendaEvent {
+            title: title.to_string(),
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            all_day,
+        }
+    }
+
+    #[test]
+    fn test_format_agenda() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap();
+        let events = [
+            event("Lunch", 12, 13, false),
+            event("Standup", 9, 10, false),
+            event("Holiday", 0, 0, true),
+        ];
+        assert_eq!(
+            format_agenda(&events, date),
+            "## Agenda 2024-05-08\n\n- All day: Holiday\n- 09:00–10:00 Standup\n- 12:00–13:00 Lunch\n"
+        );
+        assert_eq!(
+            format_agenda(&[], date),
+            "## Agenda 2024-05-08\n\n- No events\n"
+        );
+    }
+
+    #[test]
+    fn test_fill_placeholder() {
+        let agenda = "## Agenda\n\n- No events\n";
+        assert_eq!(
+            fill_agenda_placeholder("# Today\n{{agenda}}\n## Notes", agenda).unwrap(),
+            "# Today\n## Agenda\n\n- No events\n## Notes"
+        );
+        assert!(fill_agenda_placeholder("# Today", agenda).is_none());
+    }
+}
@@ -1,21 +1,248 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use chrono::SecondsFormat;
+
 use crate::atoms::applescript::{
-    create_apple_note, create_bear_note, create_calendar_event, create_obsidian_note,
-    create_reminder,
+    attach_to_apple_note, completed_reminders, contact_names, create_apple_note, create_bear_note,
+    create_calendar_event, create_mail_draft, create_obsidian_note, create_omnifocus_task,
+    create_reminder, create_things_todo, todays_events,
+};
+use crate::atoms::storage::{
+    NotionPage, TodoistTask, create_notion_page, create_todoist_tasks, notion_token, todoist_token,
 };
 use crate::molecules::distribution::parse_time_expression;
-use crate::types::{BlockType, Destinations, NotesApp, SmartBlock};
+use crate::types::{
+    AgendaEvent, BlockType, Destinations, NotesApp, ProcessedStyle, SmartBlock, TodoApp, parse_tags,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DispatchResult {
     Sent,
     Skipped,
     Failed(String),
 }
 
+/// Sends smart blocks to their destinations. `App` holds one, so tests and
+/// `--dry-run` can use a `RecordingDispatcher` instead of AppleScript, and
+/// `--simulate-dispatch` a `SimulatedDispatcher`.
+pub trait Dispatcher {
+    fn dispatch(&mut self, block: &SmartBlock, destinations: &Destinations) -> DispatchResult;
+
+    /// Names of the reminders completed in the Reminders app, for
+    /// `:syncreminders`. None unless the dispatcher can ask.
+    fn completed_reminders(&mut self, _destinations: &Destinations) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Today's events of the configured calendar, for `:agenda`. None
+    /// unless the dispatcher can ask.
+    fn todays_events(&mut self, _destinations: &Destinations) -> Result<Vec<AgendaEvent>> {
+        Ok(Vec::new())
+    }
+
+    /// Full names of the address book, for `@mention` completion. None
+    /// unless the dispatcher can ask.
+    fn contact_names(&mut self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+/// The real dispatcher: hands blocks to the AppleScript atoms.
+#[derive(Debug, Default)]
+pub struct AppleScriptDispatcher;
+
+impl Dispatcher for AppleScriptDispatcher {
+    fn dispatch(&mut self, block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
+        dispatch_block(block, destinations)
+    }
+
+    fn completed_reminders(&mut self, destinations: &Destinations) -> Result<Vec<String>> {
+        if destinations.reminders.app.is_empty() {
+            return Ok(Vec::new());
+        }
+        if TodoApp::from_name(&destinations.reminders.app) != Some(TodoApp::Reminders) {
+            anyhow::bail!("only Apple Reminders can be synced");
+        }
+        completed_reminders(destinations.reminders.list.as_deref())
+    }
+
+    fn todays_events(&mut self, destinations: &Destinations) -> Result<Vec<AgendaEvent>> {
+        if destinations.calendar.app.is_empty() {
+            anyhow::bail!("no calendar app configured");
+        }
+        todays_events(destinations.calendar.calendar_name.as_deref())
+    }
+
+    fn contact_names(&mut self) -> Result<Vec<String>> {
+        contact_names()
+    }
+}
+
+/// Records the blocks it is given and answers every one with the same
+/// result, without sending anything. Clones share the record.
+#[derive(Debug, Clone)]
+pub struct RecordingDispatcher {
+    result: DispatchResult,
+    recorded: Rc<RefCell<Vec<SmartBlock>>>,
+    completed: Vec<String>,
+    events: Vec<AgendaEvent>,
+    contacts: Vec<String>,
+}
+
+impl RecordingDispatcher {
+    /// A dispatcher that reports every block as sent.
+    pub fn new() -> Self {
+        Self::answering(DispatchResult::Sent)
+    }
+
+    pub fn answering(result: DispatchResult) -> Self {
+        Self {
+            result,
+            recorded: Rc::default(),
+            completed: Vec::new(),
+            events: Vec::new(),
+            contacts: Vec::new(),
+        }
+    }
+
+    /// Report the reminders named `names` as completed.
+    pub fn completing(mut self, names: &[&str]) -> Self {
+        self.completed = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Report `events` as today's calendar.
+    pub fn scheduling(mut self, events: Vec<AgendaEvent>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Report `names` as the address book.
+    pub fn knowing(mut self, names: &[&str]) -> Self {
+        self.contacts = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Blocks dispatched so far, in order. Already processed blocks are
+    /// skipped as by the real dispatcher and not recorded.
+    pub fn recorded(&self) -> Vec<SmartBlock> {
+        self.recorded.borrow().clone()
+    }
+}
+
+impl Default for RecordingDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dispatcher for RecordingDispatcher {
+    fn dispatch(&mut self, block: &SmartBlock, _: &Destinations) -> DispatchResult {
+        if is_processed(block) {
+            return DispatchResult::Skipped;
+        }
+        self.recorded.borrow_mut().push(block.clone());
+        self.result.clone()
+    }
+
+    fn completed_reminders(&mut self, _: &Destinations) -> Result<Vec<String>> {
+        Ok(self.completed.clone())
+    }
+
+    fn todays_events(&mut self, _: &Destinations) -> Result<Vec<AgendaEvent>> {
+        Ok(self.events.clone())
+    }
+
+    fn contact_names(&mut self) -> Result<Vec<String>> {
+        Ok(self.contacts.clone())
+    }
+}
+
+/// Pretends to send blocks, for `--simulate-dispatch`: each one takes a
+/// while and about one in six fails, so the processing overlay can be
+/// demoed and screenshot-tested without the macOS apps.
+#[derive(Debug, Clone)]
+pub struct SimulatedDispatcher {
+    /// xorshift64 state; never zero.
+    state: u64,
+    max_delay: Duration,
+}
+
+impl SimulatedDispatcher {
+    /// Chance that a block fails, as one in this many.
+    const FAILURE_ODDS: u64 = 6;
+
+    /// Seeded from the clock, with delays of up to 900 ms per block.
+    pub fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::seeded(nanos)
+    }
+
+    /// The same seed gives the same sequence of results.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            state: seed | 1,
+            max_delay: Duration::from_millis(900),
+        }
+    }
+
+    /// Upper bound of the per-block delay; at least a fifth of it is always
+    /// spent. `Duration::ZERO` answers immediately.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl Default for SimulatedDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dispatcher for SimulatedDispatcher {
+    fn dispatch(&mut self, block: &SmartBlock, _: &Destinations) -> DispatchResult {
+        if is_processed(block) {
+            return DispatchResult::Skipped;
+        }
+        let max = self.max_delay.as_millis() as u64;
+        if max > 0 {
+            let delay = max / 5 + self.next() % (max - max / 5 + 1);
+            std::thread::sleep(Duration::from_millis(delay));
+        }
+        if !self.next().is_multiple_of(Self::FAILURE_ODDS) {
+            return DispatchResult::Sent;
+        }
+        let app = match block.block_type {
+            BlockType::Reminder => "Reminders",
+            BlockType::Calendar => "Calendar",
+            BlockType::Note | BlockType::Audio => "Notes",
+            BlockType::Mail => "Mail",
+        };
+        DispatchResult::Failed(format!("{} did not respond (simulated)", app))
+    }
+}
+
+/// Blocks marked in a `ProcessedStyle` were sent by an earlier run.
+pub(super) fn is_processed(block: &SmartBlock) -> bool {
+    ProcessedStyle::of(&block.content).is_some()
+}
+
 pub fn dispatch_block(block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
-    // Skip blocks already wrapped in HTML comments (previously processed)
-    let trimmed = block.content.trim();
-    if trimmed.starts_with("<!--") && trimmed.ends_with("-->") {
+    if is_processed(block) {
         return DispatchResult::Skipped;
     }
 
@@ -23,55 +250,80 @@ pub fn dispatch_block(block: &SmartBlock, destinations: &Destinations) -> Dispat
         BlockType::Reminder => dispatch_reminder(block, destinations),
         BlockType::Calendar => dispatch_calendar(block, destinations),
         BlockType::Note => dispatch_note(block, destinations),
+        BlockType::Audio => dispatch_audio(block, destinations),
+        BlockType::Mail => dispatch_mail(block, destinations),
     }
 }
 
+/// Send a todo block to `reminders.app`: Apple Reminders, Things,
+/// OmniFocus or Todoist. `reminders.list` is the list, Things list or
+/// OmniFocus project; Todoist uses `todoist.project_id` and gets the whole
+/// block in one request.
 fn dispatch_reminder(block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
-    if destinations.reminders.app.is_empty() {
+    let app_name = &destinations.reminders.app;
+    if app_name.is_empty() {
         return DispatchResult::Skipped;
     }
+    let Some(app) = TodoApp::from_name(app_name) else {
+        return DispatchResult::Failed(format!("Unknown reminders app: {}", app_name));
+    };
 
+    let failed = |e: anyhow::Error| match app {
+        TodoApp::Reminders => DispatchResult::Failed(format!("Reminder failed: {}", e)),
+        _ => DispatchResult::Failed(format!("{} failed: {}", app.as_str(), e)),
+    };
     let content = strip_tag(&block.content, ":::td");
-    let list_name = destinations.reminders.list.as_deref();
+    let (title, body) = extract_title_body(&content);
 
-    // Check for checkbox items: create one reminder per item
-    let checkbox_items: Vec<&str> = content
+    // Checkbox items become one reminder each; otherwise the block is a
+    // single reminder with title/body and a due date parsed from it
+    let mut items: Vec<_> = content
         .lines()
-        .filter(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("- [ ]") || trimmed.starts_with("- []")
-        })
+        .filter_map(checkbox_title)
+        .map(|title| (title, None, None))
         .collect();
-
-    if !checkbox_items.is_empty() {
-        for item in &checkbox_items {
-            let title = item
-                .trim()
-                .trim_start_matches("- [ ]")
-                .trim_start_matches("- []")
-                .trim();
-            if let Err(e) = create_reminder(title, None, None, list_name) {
-                return DispatchResult::Failed(format!("Reminder failed: {}", e));
-            }
-        }
-        return DispatchResult::Sent;
+    if items.is_empty() {
+        let body = Some(body.as_str()).filter(|body| !body.is_empty());
+        items.push((&title, body, parse_time_expression(&content)));
     }
 
-    // Single reminder with title/body
-    let (title, body) = extract_title_body(&content);
-    let body_ref = if body.is_empty() {
-        None
-    } else {
-        Some(body.as_str())
+    let create = match app {
+        TodoApp::Reminders => create_reminder,
+        TodoApp::Things => create_things_todo,
+        TodoApp::OmniFocus => create_omnifocus_task,
+        TodoApp::Todoist => {
+            let tasks: Vec<TodoistTask> = items
+                .iter()
+                .map(|&(content, description, due)| TodoistTask {
+                    content,
+                    description,
+                    due,
+                })
+                .collect();
+            // Which block of which note, so a resend reuses the command
+            // uuids and the same list elsewhere does not
+            let block_key = format!(
+                "{}#{}",
+                block.note_id.as_deref().unwrap_or(""),
+                block.original_range.map_or(0, |(start, _)| start)
+            );
+            let todoist = &destinations.todoist;
+            let result = todoist_token(todoist.token.as_deref()).and_then(|token| {
+                create_todoist_tasks(&token, &block_key, &tasks, todoist.project_id.as_deref())
+            });
+            return match result {
+                Ok(()) => DispatchResult::Sent,
+                Err(e) => failed(e),
+            };
+        }
     };
-
-    // Try parsing time from content for due date
-    let due_date = parse_time_expression(&content);
-
-    match create_reminder(&title, body_ref, due_date, list_name) {
-        Ok(()) => DispatchResult::Sent,
-        Err(e) => DispatchResult::Failed(format!("Reminder failed: {}", e)),
+    let list_name = destinations.reminders.list.as_deref();
+    for (title, notes, due) in items {
+        if let Err(e) = create(title, notes, due, list_name) {
+            return failed(e);
+        }
     }
+    DispatchResult::Sent
 }
 
 fn dispatch_calendar(block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
@@ -119,6 +371,7 @@ fn dispatch_note(block: &SmartBlock, destinations: &Destinations) -> DispatchRes
             let vault = destinations.notes.vault.as_deref();
             create_obsidian_note(&title, &body, vault)
         }
+        NotesApp::Notion => create_notion_note(&title, &body, &content, destinations),
     };
 
     match result {
@@ -127,8 +380,177 @@ fn dispatch_note(block: &SmartBlock, destinations: &Destinations) -> DispatchRes
     }
 }
 
+/// Add a page to the `[destinations.notion]` database. The `#tags` in
+/// `text` and its time, or else today, fill the mapped columns.
+fn create_notion_note(
+    title: &str,
+    body: &str,
+    text: &str,
+    destinations: &Destinations,
+) -> Result<()> {
+    let notion = &destinations.notion;
+    let Some(database_id) = notion.database_id.as_deref() else {
+        anyhow::bail!("no database (set destinations.notion.database_id)");
+    };
+    let token = notion_token(notion.token.as_deref())?;
+    let date = match parse_time_expression(text) {
+        Some(time) => time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    };
+    let page = NotionPage {
+        title: title.to_string(),
+        body: body.to_string(),
+        tags: parse_tags(text),
+        date: Some(date),
+    };
+    create_notion_page(&token, database_id, &notion.properties, &page)
+}
+
+/// The file path on the first line of an `:::audio` block.
+pub fn audio_block_path(content: &str) -> Option<&str> {
+    let path = content
+        .lines()
+        .next()?
+        .trim()
+        .strip_prefix(":::audio")?
+        .trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Replace the file path of an `:::audio` block, keeping the other lines.
+pub fn replace_audio_path(content: &str, path: &str) -> String {
+    match content.split_once('\n') {
+        Some((_, rest)) => format!(":::audio {}\n{}", path, rest),
+        None => format!(":::audio {}", path),
+    }
+}
+
+/// Create a note for an audio recording. Apple Notes gets the file as an
+/// attachment; Bear, Obsidian and Notion get a `file://` link to it.
+fn dispatch_audio(block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
+    let notes_app = match destinations.notes.app {
+        Some(app) => app,
+        None => return DispatchResult::Skipped,
+    };
+    let Some(path) = audio_block_path(&block.content) else {
+        return DispatchResult::Failed("Audio block has no file path".to_string());
+    };
+
+    let path = Path::new(path);
+    let rest = block.content.split_once('\n').map_or("", |(_, rest)| rest);
+    let (title, body) = extract_title_body(rest);
+    let title = if title.is_empty() {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        title
+    };
+
+    let result = match notes_app {
+        NotesApp::AppleNotes => {
+            let folder = destinations.notes.folder.as_deref();
+            attach_to_apple_note(&title, &body, folder, path)
+        }
+        NotesApp::Bear => create_bear_note(&title, &with_file_link(&body, path), None),
+        NotesApp::Obsidian => {
+            let vault = destinations.notes.vault.as_deref();
+            create_obsidian_note(&title, &with_file_link(&body, path), vault)
+        }
+        NotesApp::Notion => {
+            create_notion_note(&title, &with_file_link(&body, path), rest, destinations)
+        }
+    };
+
+    match result {
+        Ok(()) => DispatchResult::Sent,
+        Err(e) => DispatchResult::Failed(format!("Audio note failed: {}", e)),
+    }
+}
+
+/// What a `:::mail` block asks for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MailDraft {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Read a `:::mail` block: `to:`, `cc:` and `subject:` lines in any order
+/// (addresses separated by commas), then the body. Text after `:::mail`
+/// on the first line is the subject, unless it is one of those lines.
+pub fn mail_block(content: &str) -> MailDraft {
+    let content = strip_tag(content, ":::mail");
+    let mut lines = content.lines().peekable();
+    let mut draft = MailDraft::default();
+    if let Some(first) = lines.peek()
+        && mail_header(first).is_none()
+    {
+        draft.subject = first.trim().to_string();
+        lines.next();
+    }
+    while let Some((key, value)) = lines.peek().and_then(|line| mail_header(line)) {
+        let addresses = value
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::to_string);
+        match key.as_str() {
+            "to" => draft.to.extend(addresses),
+            "cc" => draft.cc.extend(addresses),
+            _ => draft.subject = value.to_string(),
+        }
+        lines.next();
+    }
+    draft.body = lines.collect::<Vec<&str>>().join("\n").trim().to_string();
+    draft
+}
+
+/// A `to:`, `cc:` or `subject:` line of a `:::mail` block, as the
+/// lowercase key and the value.
+fn mail_header(line: &str) -> Option<(String, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim().to_lowercase();
+    ["to", "cc", "subject"]
+        .contains(&key.as_str())
+        .then(|| (key, value.trim()))
+}
+
+/// Open a Mail draft for a `:::mail` block, left unsent for review.
+fn dispatch_mail(block: &SmartBlock, destinations: &Destinations) -> DispatchResult {
+    if destinations.mail.app.is_empty() {
+        return DispatchResult::Skipped;
+    }
+    let draft = mail_block(&block.content);
+    if draft.to.is_empty() {
+        return DispatchResult::Failed("Mail block has no recipient".to_string());
+    }
+
+    let sender = destinations.mail.sender.as_deref();
+    match create_mail_draft(&draft.to, &draft.cc, &draft.subject, &draft.body, sender) {
+        Ok(()) => DispatchResult::Sent,
+        Err(e) => DispatchResult::Failed(format!("Mail failed: {}", e)),
+    }
+}
+
+/// Append a `file://` link to `path` below `body`.
+fn with_file_link(body: &str, path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let url = urlencoding::encode(&path.to_string_lossy()).replace("%2F", "/");
+    let link = format!("[{}](file://{})", name, url);
+    if body.is_empty() {
+        link
+    } else {
+        format!("{}\n\n{}", body, link)
+    }
+}
+
 /// Strip a tag prefix (e.g. ":::td") from the first line if present.
-fn strip_tag(content: &str, tag: &str) -> String {
+pub(super) fn strip_tag(content: &str, tag: &str) -> String {
     let mut lines = content.lines();
     if let Some(first_line) = lines.next() {
         let stripped_first = first_line.trim_start_matches(tag).trim();
@@ -143,8 +565,17 @@ fn strip_tag(content: &str, tag: &str) -> String {
     }
 }
 
+/// The reminder title of an unchecked `- [ ] item` line.
+pub(super) fn checkbox_title(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    let title = trimmed
+        .strip_prefix("- [ ]")
+        .or_else(|| trimmed.strip_prefix("- []"))?;
+    Some(title.trim())
+}
+
 /// Extract title (first line) and body (remaining lines) from content.
-fn extract_title_body(content: &str) -> (String, String) {
+pub(super) fn extract_title_body(content: &str) -> (String, String) {
     let mut lines = content.lines();
     let title = lines.next().unwrap_or("").trim().to_string();
     let body: String = lines.collect::<Vec<&str>>().join("\n").trim().to_string();
@@ -172,6 +603,72 @@ mod tests {
         assert_eq!(result, "Meeting tomorrow\nWith team");
     }
 
+    #[test]
+    fn test_audio_block_path() {
+        assert_eq!(
+            audio_block_path(":::audio ~/rec/standup.m4a\nStandup"),
+            Some("~/rec/standup.m4a")
+        );
+        assert_eq!(audio_block_path(":::audio"), None);
+        assert_eq!(audio_block_path(":::note x"), None);
+        assert_eq!(
+            replace_audio_path(":::audio a.m4a\nStandup", "/data/assets/a.m4a"),
+            ":::audio /data/assets/a.m4a\nStandup"
+        );
+    }
+
+    #[test]
+    fn test_mail_block() {
+        let draft = mail_block(
+            ":::mail Q3 plan\nTo: ann@example.com, bob@example.com\ncc: cy@example.com\n\nHi all,\nsee below.",
+        );
+        assert_eq!(draft.to, ["ann@example.com", "bob@example.com"]);
+        assert_eq!(draft.cc, ["cy@example.com"]);
+        assert_eq!(draft.subject, "Q3 plan");
+        assert_eq!(draft.body, "Hi all,\nsee below.");
+
+        let draft = mail_block(":::mail to: ann@example.com\nsubject: Lunch\nNoon?");
+        assert_eq!(draft.to, ["ann@example.com"]);
+        assert_eq!(draft.subject, "Lunch");
+        assert_eq!(draft.body, "Noon?");
+    }
+
+    #[test]
+    fn test_dispatch_mail_skipped_when_empty_app() {
+        let block = SmartBlock::new(
+            "t1".to_string(),
+            ":::mail to: ann@example.com\nHi".to_string(),
+            BlockType::Mail,
+        );
+        let mut destinations = Destinations::default();
+        destinations.mail.app = String::new();
+
+        let result = dispatch_block(&block, &destinations);
+        assert!(matches!(result, DispatchResult::Skipped));
+    }
+
+    #[test]
+    fn test_with_file_link() {
+        assert_eq!(
+            with_file_link("Weekly sync", Path::new("/data/assets/team call.m4a")),
+            "Weekly sync\n\n[team call.m4a](file:///data/assets/team%20call.m4a)"
+        );
+    }
+
+    #[test]
+    fn test_dispatch_audio_skipped_when_none_app() {
+        let block = SmartBlock::new(
+            "t1".to_string(),
+            ":::audio /tmp/a.m4a".to_string(),
+            BlockType::Audio,
+        );
+        let mut destinations = Destinations::default();
+        destinations.notes.app = None;
+
+        let result = dispatch_block(&block, &destinations);
+        assert!(matches!(result, DispatchResult::Skipped));
+    }
+
     #[test]
     fn test_extract_title_body() {
         let (title, body) = extract_title_body("My Title\nLine 1\nLine 2");
@@ -200,6 +697,23 @@ mod tests {
         assert!(matches!(result, DispatchResult::Skipped));
     }
 
+    #[test]
+    fn test_dispatch_reminder_fails_for_unknown_app() {
+        let block = SmartBlock::new(
+            "t1".to_string(),
+            ":::td Buy milk".to_string(),
+            BlockType::Reminder,
+        );
+        let mut destinations = Destinations::default();
+        destinations.reminders.app = "ticktick".to_string();
+
+        let result = dispatch_block(&block, &destinations);
+        assert_eq!(
+            result,
+            DispatchResult::Failed("Unknown reminders app: ticktick".to_string())
+        );
+    }
+
     #[test]
     fn test_dispatch_calendar_skipped_when_empty_app() {
         let block = SmartBlock::new(
@@ -214,6 +728,25 @@ mod tests {
         assert!(matches!(result, DispatchResult::Skipped));
     }
 
+    #[test]
+    fn test_dispatch_notion_fails_without_database() {
+        let block = SmartBlock::new(
+            "t1".to_string(),
+            ":::note Hello #work".to_string(),
+            BlockType::Note,
+        );
+        let mut destinations = Destinations::default();
+        destinations.notes.app = Some(NotesApp::Notion);
+
+        let result = dispatch_block(&block, &destinations);
+        assert_eq!(
+            result,
+            DispatchResult::Failed(
+                "Note failed: no database (set destinations.notion.database_id)".to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_dispatch_note_skipped_when_none_app() {
         let block = SmartBlock::new(
@@ -253,4 +786,72 @@ mod tests {
         let result = dispatch_block(&block, &destinations);
         assert!(matches!(result, DispatchResult::Skipped));
     }
+
+    #[test]
+    fn test_recording_dispatcher_records_unprocessed_blocks() {
+        let recorder = RecordingDispatcher::answering(DispatchResult::Failed("down".to_string()));
+        let mut dispatcher: Box<dyn Dispatcher> = Box::new(recorder.clone());
+        let destinations = Destinations::default();
+        let fresh = SmartBlock::new(
+            "t1".to_string(),
+            ":::td Buy milk".to_string(),
+            BlockType::Reminder,
+        );
+        let done = SmartBlock::new(
+            "t2".to_string(),
+            "<!-- :::td Buy milk -->".to_string(),
+            BlockType::Reminder,
+        );
+
+        assert_eq!(
+            dispatcher.dispatch(&fresh, &destinations),
+            DispatchResult::Failed("down".to_string())
+        );
+        assert_eq!(
+            dispatcher.dispatch(&done, &destinations),
+            DispatchResult::Skipped
+        );
+        let recorded = recorder.recorded();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].id, "t1");
+    }
+
+    #[test]
+    fn test_simulated_dispatcher_sends_and_sometimes_fails() {
+        let destinations = Destinations::default();
+        let block = SmartBlock::new(
+            "t1".to_string(),
+            ":::cal Standup at 9am".to_string(),
+            BlockType::Calendar,
+        );
+        let run = |seed| {
+            let mut dispatcher = SimulatedDispatcher::seeded(seed).max_delay(Duration::ZERO);
+            (0..60)
+                .map(|_| dispatcher.dispatch(&block, &destinations))
+                .collect::<Vec<_>>()
+        };
+
+        let results = run(42);
+        assert_eq!(results, run(42));
+        let failed = results
+            .iter()
+            .filter(|r| matches!(r, DispatchResult::Failed(_)))
+            .count();
+        assert!(failed > 0 && failed < 30, "{} failed", failed);
+        assert!(results.contains(&DispatchResult::Failed(
+            "Calendar did not respond (simulated)".to_string()
+        )));
+        assert!(!results.contains(&DispatchResult::Skipped));
+
+        let done = SmartBlock::new(
+            "t2".to_string(),
+            "<!-- :::td Buy milk -->".to_string(),
+            BlockType::Reminder,
+        );
+        let mut dispatcher = SimulatedDispatcher::seeded(1).max_delay(Duration::ZERO);
+        assert_eq!(
+            dispatcher.dispatch(&done, &destinations),
+            DispatchResult::Skipped
+        );
+    }
 }
@@ -0,0 +1,102 @@
+use super::dispatcher::{audio_block_path, is_processed, mail_block, strip_tag};
+use super::{parse_smart_blocks, parse_time_expression};
+use crate::types::BlockType;
+
+/// Tags a block can start with.
+const KNOWN_TAGS: [&str; 5] = ["td", "cal", "note", "audio", "mail"];
+
+/// A smart block that would not be sent as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockIssue {
+    /// Row of the line at fault.
+    pub row: usize,
+    pub message: String,
+}
+
+/// Problems in the blocks of `content` not sent yet: tags that are not
+/// `:::td`, `:::cal`, `:::note`, `:::audio` or `:::mail`, tags with nothing
+/// after them, mail without a recipient and calendar blocks whose time
+/// can't be read. In row order.
+pub fn lint_blocks(content: &str) -> Vec<BlockIssue> {
+    let mut issues = Vec::new();
+    for block in parse_smart_blocks(content) {
+        let Some((start, end)) = block.original_range else {
+            continue;
+        };
+        if is_processed(&block) {
+            continue;
+        }
+        let first_row = content[..start].matches('\n').count();
+        let issue = |row: usize, message: String| BlockIssue {
+            row: first_row + row,
+            message,
+        };
+
+        for (row, line) in content[start..end].lines().enumerate() {
+            if let Some(tag) = line.trim_start().strip_prefix(":::") {
+                let name = tag.split_whitespace().next().unwrap_or("");
+                if !name.is_empty() && !KNOWN_TAGS.contains(&name) {
+                    issues.push(issue(row, format!("Unknown tag :::{}", name)));
+                }
+            }
+        }
+
+        let first_line = block.content.lines().next().unwrap_or("").trim();
+        if block.block_type == BlockType::Audio {
+            if audio_block_path(&block.content).is_none() {
+                issues.push(issue(0, "No file after :::audio".to_string()));
+            }
+            continue;
+        }
+        let tag = KNOWN_TAGS
+            .iter()
+            .map(|name| format!(":::{}", name))
+            .find(|tag| first_line.starts_with(tag.as_str()));
+        if let Some(tag) = &tag
+            && strip_tag(&block.content, tag).trim().is_empty()
+        {
+            issues.push(issue(0, format!("Nothing after {}", tag)));
+            continue;
+        }
+        if block.block_type == BlockType::Mail && mail_block(&block.content).to.is_empty() {
+            issues.push(issue(0, "No recipient (to:) in :::mail".to_string()));
+        }
+        if block.block_type == BlockType::Calendar
+            && parse_time_expression(&strip_tag(&block.content, ":::cal")).is_none()
+        {
+            issues.push(issue(0, "Could not parse the time".to_string()));
+        }
+    }
+    issues.sort_by_key(|issue| issue.row);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(content: &str) -> Vec<(usize, String)> {
+        lint_blocks(content)
+            .into_iter()
+            .map(|issue| (issue.row, issue.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_blocks() {
+        let content = "# Plan\n\n:::todo Buy milk\n\n:::td\n\n:::cal Lunch with Ann\n\n\
+                       :::cal Standup tomorrow at 9am\n\n:::audio\n\n<!-- :::cal Sent -->\n\n\
+                       :::mail Hello\nsubject: Hi";
+        assert_eq!(
+            rows(content),
+            [
+                (2, "Unknown tag :::todo".to_string()),
+                (4, "Nothing after :::td".to_string()),
+                (6, "Could not parse the time".to_string()),
+                (10, "No file after :::audio".to_string()),
+                (14, "No recipient (to:) in :::mail".to_string()),
+            ]
+        );
+        assert!(lint_blocks(":::td Buy milk\n- [ ] Eggs").is_empty());
+    }
+}
@@ -1,7 +1,16 @@
+mod agenda;
 mod dispatcher;
+mod lint;
 mod parser;
+mod reminder_sync;
 mod time_parser;
 
-pub use dispatcher::{DispatchResult, dispatch_block};
+pub use agenda::{AGENDA_PLACEHOLDER, fill_agenda_placeholder, format_agenda};
+pub use dispatcher::{
+    AppleScriptDispatcher, DispatchResult, Dispatcher, MailDraft, RecordingDispatcher,
+    SimulatedDispatcher, audio_block_path, dispatch_block, mail_block, replace_audio_path,
+};
+pub use lint::{BlockIssue, lint_blocks};
 pub use parser::parse_smart_blocks;
+pub use reminder_sync::check_off_reminders;
 pub use time_parser::parse_time_expression;
@@ -1,11 +1,12 @@
 use regex::Regex;
 
-use crate::types::{BlockType, SmartBlock};
+use crate::types::{BlockType, Frontmatter, SmartBlock};
 
 pub fn parse_smart_blocks(text: &str) -> Vec<SmartBlock> {
     let mut blocks = Vec::new();
     let mut block_index = 0;
-    let mut pos = 0;
+    // Frontmatter is note metadata, never a block to dispatch
+    let mut pos = Frontmatter::parse(text).map_or(0, |fm| fm.body_offset);
     let bytes = text.as_bytes();
     let len = bytes.len();
 
@@ -63,6 +64,12 @@ pub fn parse_smart_blocks(text: &str) -> Vec<SmartBlock> {
 }
 
 fn detect_block_type(content: &str) -> BlockType {
+    if content.starts_with(":::audio") {
+        return BlockType::Audio;
+    }
+    if content.starts_with(":::mail") {
+        return BlockType::Mail;
+    }
     if content.contains(":::td") {
         return BlockType::Reminder;
     }
@@ -100,6 +107,14 @@ fn detect_block_type(content: &str) -> BlockType {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_frontmatter_is_not_a_block() {
+        let blocks = parse_smart_blocks("---\nword_goal: 300\n---\n- [ ] Buy milk");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_type, BlockType::Reminder);
+        assert_eq!(blocks[0].content, "- [ ] Buy milk");
+    }
+
     #[test]
     fn test_explicit_tags() {
         let blocks =
@@ -111,6 +126,23 @@ mod tests {
         assert_eq!(blocks[2].block_type, BlockType::Note);
     }
 
+    #[test]
+    fn test_audio_tag() {
+        let blocks = parse_smart_blocks(":::audio ~/Recordings/standup.m4a\nStandup tomorrow");
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_type, BlockType::Audio);
+        assert_eq!(blocks[0].preview(40), "~/Recordings/standup.m4a");
+    }
+
+    #[test]
+    fn test_mail_tag() {
+        let blocks = parse_smart_blocks(":::mail\nto: ann@example.com\nsubject: Notes\n- [ ] Read");
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_type, BlockType::Mail);
+    }
+
     #[test]
     fn test_checkbox_detection() {
         let blocks = parse_smart_blocks("- [ ] Task 1\n- [ ] Task 2");
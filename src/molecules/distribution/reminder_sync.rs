@@ -0,0 +1,107 @@
+use super::dispatcher::{checkbox_title, extract_title_body, strip_tag};
+use super::parse_smart_blocks;
+use crate::types::{BlockType, ProcessedStyle};
+
+/// Marks a sent single reminder as completed inside its marks.
+const DONE_MARK: &str = "[x]";
+
+/// Check off the reminders sent from `content` whose names are in
+/// `completed`: `- [ ] item` lines of a sent `:::td` block become
+/// `- [x] item`, and a sent single reminder gets `[x]` after its opening
+/// mark (`<!-- [x] :::td ... -->`). Returns the new content and how many
+/// were checked off.
+pub fn check_off_reminders(content: &str, completed: &[String]) -> (String, usize) {
+    let is_done = |title: &str| completed.iter().any(|name| name == title);
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    for block in parse_smart_blocks(content) {
+        let Some((start, end)) = block.original_range else {
+            continue;
+        };
+        if block.block_type != BlockType::Reminder {
+            continue;
+        }
+        let Some(style) = ProcessedStyle::of(&block.content) else {
+            continue;
+        };
+        let sent = style.unmark(&block.content);
+        if sent.starts_with(DONE_MARK) {
+            continue;
+        }
+
+        let text = &content[start..end];
+        if text
+            .lines()
+            .any(|line| checkbox_title(style.line_body(line)).is_some())
+        {
+            let mut offset = start;
+            for line in text.split_inclusive('\n') {
+                if let Some(title) = checkbox_title(style.line_body(line))
+                    && is_done(title)
+                {
+                    let at = line.find("- [").unwrap_or(0);
+                    let marker = if line[at..].starts_with("- [ ]") {
+                        5
+                    } else {
+                        4
+                    };
+                    edits.push((offset + at, offset + at + marker, "- [x]".to_string()));
+                }
+                offset += line.len();
+            }
+        } else if is_done(&extract_title_body(&strip_tag(&sent, ":::td")).0) {
+            let opening = style.opening();
+            let at = start + text.find(opening).unwrap_or(0) + opening.len();
+            edits.push((at, at, format!(" {}", DONE_MARK)));
+        }
+    }
+
+    let mut content = content.to_string();
+    for (start, end, text) in edits.iter().rev() {
+        content.replace_range(*start..*end, text);
+    }
+    (content, edits.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_off_items() {
+        let content =
+            "# Errands\n\n<!-- :::td\n- [ ] Milk\n- [] Eggs\n- [ ] Bread -->\n\n- [ ] Milk";
+        let (content, count) = check_off_reminders(content, &done(&["Milk", "Eggs"]));
+        assert_eq!(count, 2);
+        assert_eq!(
+            content,
+            "# Errands\n\n<!-- :::td\n- [x] Milk\n- [x] Eggs\n- [ ] Bread -->\n\n- [ ] Milk"
+        );
+
+        let content = "> ✅ sent: - [ ] Milk\n> - [ ] Bread";
+        let (content, count) = check_off_reminders(content, &done(&["Milk", "Bread"]));
+        assert_eq!(count, 2);
+        assert_eq!(content, "> ✅ sent: - [x] Milk\n> - [x] Bread");
+    }
+
+    #[test]
+    fn test_check_off_single_reminder() {
+        let content = "<!-- :::td Call Bob\nabout the trip -->\n\n:::td Call Bob";
+        let (content, count) = check_off_reminders(content, &done(&["Call Bob"]));
+        assert_eq!(count, 1);
+        assert_eq!(
+            content,
+            "<!-- [x] :::td Call Bob\nabout the trip -->\n\n:::td Call Bob"
+        );
+        // Already checked off
+        assert_eq!(check_off_reminders(&content, &done(&["Call Bob"])).1, 0);
+        assert_eq!(check_off_reminders("<!-- :::td Other -->", &done(&[])).1, 0);
+        assert_eq!(
+            check_off_reminders("%% :::td Call Bob %%", &done(&["Call Bob"])).0,
+            "%% [x] :::td Call Bob %%"
+        );
+    }
+}
@@ -147,6 +147,13 @@ fn parse_chinese_time(text: &str) -> Option<DateTime<Utc>> {
     let time_re = Regex::new(r"(\d{1,2})[点時时](?:(\d{2})?分?)?").ok()?;
     if let Some(caps) = time_re.captures(text) {
         hour = caps.get(1)?.as_str().parse().ok()?;
+    } else if date == today
+        && !["今天", "早上", "上午", "中午", "下午", "晚上"]
+            .iter()
+            .any(|word| text.contains(word))
+    {
+        // Nothing in the text says when
+        return None;
     }
 
     let time = NaiveTime::from_hms_opt(hour, 0, 0)?;
@@ -170,5 +177,6 @@ mod tests {
         assert!(parse_time_expression("明天").is_some());
         assert!(parse_time_expression("今天下午").is_some());
         assert!(parse_time_expression("下周一").is_some());
+        assert!(parse_time_expression("Lunch with Ann").is_none());
     }
 }
@@ -0,0 +1,123 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::types::AutoLinkConfig;
+
+static NOTE_ID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b")
+        .unwrap()
+});
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d{4}-\d{2}-\d{2}\b").unwrap());
+
+/// What a bare piece of text links to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoLinkKind {
+    /// A note id (a UUID): opens that note.
+    NoteId(String),
+    /// An ISO `YYYY-MM-DD` date: opens that day's daily note.
+    Date(NaiveDate),
+}
+
+/// Text recognized as a link without `[[ ]]` around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoLink {
+    pub kind: AutoLinkKind,
+    pub row: usize,
+    /// Grapheme columns of its first character and just past its last.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl AutoLink {
+    pub fn contains(&self, col: usize) -> bool {
+        (self.start..self.end).contains(&col)
+    }
+}
+
+/// Note ids and dates on `lines`, top to bottom and left to right, as
+/// `patterns` asks. Fenced code is skipped, and so are dates that are not
+/// real days (`2024-02-30`).
+pub fn auto_links(lines: &[&str], patterns: &AutoLinkConfig) -> Vec<AutoLink> {
+    let mut links = Vec::new();
+    let mut fenced = false;
+    for (row, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            continue;
+        }
+        let mut found: Vec<(usize, usize, AutoLinkKind)> = Vec::new();
+        if patterns.note_ids {
+            for m in NOTE_ID_RE.find_iter(line) {
+                let id = m.as_str().to_lowercase();
+                found.push((m.start(), m.end(), AutoLinkKind::NoteId(id)));
+            }
+        }
+        if patterns.dates {
+            for m in DATE_RE.find_iter(line) {
+                let inside_id = found.iter().any(|(s, e, _)| m.start() < *e && *s < m.end());
+                if !inside_id && let Ok(date) = NaiveDate::parse_from_str(m.as_str(), "%Y-%m-%d") {
+                    found.push((m.start(), m.end(), AutoLinkKind::Date(date)));
+                }
+            }
+        }
+        found.sort_by_key(|(start, _, _)| *start);
+        for (start, end, kind) in found {
+            links.push(AutoLink {
+                kind,
+                row,
+                start: grapheme_col(line, start),
+                end: grapheme_col(line, end),
+            });
+        }
+    }
+    links
+}
+
+/// Grapheme column of byte offset `byte` in `line`.
+fn grapheme_col(line: &str, byte: usize) -> usize {
+    line.grapheme_indices(true)
+        .take_while(|(i, _)| *i < byte)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_and_dates() {
+        let id = "0b6f3a2e-1c4d-4e5f-8a9b-0c1d2e3f4a5b";
+        let line = format!("Café on 2024-03-01, see {} or 2024-02-30", id);
+        let links = auto_links(&[&line], &AutoLinkConfig::default());
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            links[0].kind,
+            AutoLinkKind::Date(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+        );
+        assert_eq!((links[0].start, links[0].end), (8, 18));
+        assert_eq!(links[1].kind, AutoLinkKind::NoteId(id.to_string()));
+        assert!(links[1].contains(24) && !links[1].contains(60));
+    }
+
+    #[test]
+    fn test_patterns_and_fences() {
+        let lines = ["2024-03-01", "```", "2024-03-02", "```", "x2024-03-03"];
+        let rows: Vec<usize> = auto_links(&lines, &AutoLinkConfig::default())
+            .iter()
+            .map(|l| l.row)
+            .collect();
+        assert_eq!(rows, [0]);
+        let ids_only = AutoLinkConfig {
+            note_ids: true,
+            dates: false,
+        };
+        assert!(auto_links(&lines, &ids_only).is_empty());
+    }
+}
@@ -4,15 +4,20 @@ use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 use super::comment;
+use super::line_rope::LineRope;
 use super::list_prefix;
 use super::markdown_fmt::{self, MarkdownFormat};
+use super::search_pattern::{SearchPattern, nfc};
+use super::sentence::sentence_starts;
+use super::substitute::Substitution;
+use super::text_object::text_object_range;
 use super::vim_mode::Motion;
 
 const MAX_UNDO_LEVELS: usize = 50;
 
 #[derive(Debug, Clone)]
 struct BufferSnapshot {
-    lines: Vec<String>,
+    lines: LineRope,
     cursor_row: usize,
     cursor_col: usize,
 }
@@ -25,7 +30,7 @@ struct UndoHistory {
 
 #[derive(Debug, Clone, Default)]
 pub struct TextBuffer {
-    lines: Vec<String>,
+    lines: LineRope,
     cursor_row: usize,
     cursor_col: usize,
     history: UndoHistory,
@@ -34,7 +39,7 @@ pub struct TextBuffer {
 impl TextBuffer {
     pub fn new() -> Self {
         Self {
-            lines: vec![String::new()],
+            lines: LineRope::from(vec![String::new()]),
             cursor_row: 0,
             cursor_col: 0,
             history: UndoHistory::default(),
@@ -49,17 +54,22 @@ impl TextBuffer {
         };
 
         Self {
-            lines,
+            lines: LineRope::from(lines),
             cursor_row: 0,
             cursor_col: 0,
             history: UndoHistory::default(),
         }
     }
 
-    pub fn content(&self) -> &[String] {
+    pub fn content(&self) -> &LineRope {
         &self.lines
     }
 
+    /// Changes whenever the text does (see `LineRope::stamp`).
+    pub fn revision(&self) -> u64 {
+        self.lines.stamp()
+    }
+
     pub fn cursor_position(&self) -> (usize, usize) {
         (self.cursor_row, self.cursor_col)
     }
@@ -70,10 +80,42 @@ impl TextBuffer {
         self.cursor_col = col.min(line_len);
     }
 
+    /// Replace the text of line `row`, keeping the cursor in bounds.
+    pub fn replace_line(&mut self, row: usize, line: String) {
+        if let Some(slot) = self.lines.get_mut(row) {
+            *slot = line;
+            let (row, col) = self.cursor_position();
+            self.set_cursor(row, col);
+        }
+    }
+
+    /// Run `sub` over rows `start..=end`. Returns (replacements, lines
+    /// changed); the cursor moves to the last changed line.
+    pub fn substitute(&mut self, sub: &Substitution, start: usize, end: usize) -> (usize, usize) {
+        let mut replacements = 0;
+        let mut changed = 0;
+        let end = end.min(self.lines.len().saturating_sub(1));
+        for row in start..=end {
+            if let Some((line, count)) = sub.apply(&self.lines[row]) {
+                self.lines[row] = line;
+                replacements += count;
+                changed += 1;
+                self.cursor_row = row;
+                self.cursor_col = 0;
+            }
+        }
+        (replacements, changed)
+    }
+
     pub fn line_count(&self) -> usize {
         self.lines.len()
     }
 
+    /// Size of the buffer as saved, in bytes, without building the string.
+    pub fn byte_len(&self) -> usize {
+        self.lines.iter().map(|l| l.len()).sum::<usize>() + self.lines.len().saturating_sub(1)
+    }
+
     fn current_line_len(&self) -> usize {
         self.lines
             .get(self.cursor_row)
@@ -120,9 +162,9 @@ impl TextBuffer {
     }
 
     /// Insert a string of text at the cursor, handling newlines by splitting lines.
-    /// Used for bracketed paste in Insert mode.
+    /// Used for bracketed paste in Insert mode; the text is put in NFC first.
     pub fn insert_text(&mut self, text: &str) {
-        for c in text.chars() {
+        for c in nfc(text).chars() {
             if c == '\n' {
                 self.insert_newline();
             } else if c != '\r' {
@@ -222,6 +264,138 @@ impl TextBuffer {
         }
     }
 
+    /// `{count}x`: delete like `delete_char`, then up to `count - 1` more
+    /// characters without reaching past the end of the line.
+    pub fn delete_chars(&mut self, count: usize) {
+        self.delete_char();
+        for _ in 1..count {
+            if self.cursor_col >= self.current_line_len() {
+                break;
+            }
+            self.delete_char();
+        }
+    }
+
+    /// `{count}r{char}`: replace `count` characters from the cursor with `c`
+    /// as one undoable change, leaving the cursor on the last one. A newline
+    /// replaces them all with a line break. Returns false, changing nothing,
+    /// if the line has fewer than `count` characters left.
+    pub fn replace_chars(&mut self, c: char, count: usize) -> bool {
+        if count == 0 || self.cursor_col + count > self.current_line_len() {
+            return false;
+        }
+        self.save_undo_snapshot();
+        if c == '\n' {
+            for _ in 0..count {
+                self.delete_char();
+            }
+            self.insert_newline();
+            return true;
+        }
+        let line = &self.lines[self.cursor_row];
+        let start = self.cursor_col;
+        let new_line: String = line
+            .graphemes(true)
+            .enumerate()
+            .map(|(i, g)| {
+                if (start..start + count).contains(&i) {
+                    c.to_string()
+                } else {
+                    g.to_string()
+                }
+            })
+            .collect();
+        self.lines[self.cursor_row] = new_line;
+        self.cursor_col = start + count - 1;
+        true
+    }
+
+    /// Add `delta` to the number under or after the cursor on the current
+    /// line (Ctrl-A / Ctrl-X), or, with none there, the last one before it
+    /// (after `$`). A `-` right before the digits makes the
+    /// number negative unless it follows a letter or digit, so the parts of
+    /// `2024-05-09` count up. Leading zeros keep the width. Returns false if
+    /// there is no number or the result overflows.
+    pub fn increment_number(&mut self, delta: i64) -> bool {
+        let g: Vec<&str> = self.current_line().graphemes(true).collect();
+        let is_digit = |s: &str| s.len() == 1 && s.as_bytes()[0].is_ascii_digit();
+        let found = (self.cursor_col..g.len())
+            .find(|&i| is_digit(g[i]))
+            .or_else(|| (0..self.cursor_col.min(g.len())).rfind(|&i| is_digit(g[i])));
+        let Some(mut start) = found else {
+            return false;
+        };
+        if start <= self.cursor_col {
+            while start > 0 && is_digit(g[start - 1]) {
+                start -= 1;
+            }
+        }
+        let end = (start..g.len())
+            .find(|&i| !is_digit(g[i]))
+            .unwrap_or(g.len());
+        let negative = start > 0
+            && g[start - 1] == "-"
+            && !(start > 1 && g[start - 2].chars().all(char::is_alphanumeric));
+
+        let digits = g[start..end].concat();
+        let Ok(value) = digits.parse::<i64>() else {
+            return false;
+        };
+        let value = if negative { -value } else { value };
+        let Some(value) = value.checked_add(delta) else {
+            return false;
+        };
+        // Leading zeros keep the width: 09 -> 10, 007 -> 008
+        let width = if digits.starts_with('0') {
+            digits.len()
+        } else {
+            0
+        };
+        let text = if value < 0 {
+            format!("-{:0width$}", value.unsigned_abs())
+        } else {
+            format!("{:0width$}", value)
+        };
+
+        let from = if negative { start - 1 } else { start };
+        let line = format!("{}{}{}", g[..from].concat(), text, g[end..].concat());
+        self.save_undo_snapshot();
+        self.lines[self.cursor_row] = line;
+        self.cursor_col = from + text.len() - 1;
+        true
+    }
+
+    /// Replace mode: overwrite the character under the cursor with `c` (or
+    /// append at the end of the line) and move past it. Returns the
+    /// character that was overwritten.
+    pub fn overtype_char(&mut self, c: char) -> Option<String> {
+        let original = self.current_line().graphemes(true).nth(self.cursor_col);
+        let original = original.map(str::to_string);
+        if original.is_some() {
+            self.delete_char();
+        }
+        self.insert_char(c);
+        original
+    }
+
+    /// Replace mode Backspace: step back over the last overtyped character
+    /// and put `original` back, or remove it if it was appended.
+    pub fn restore_overtyped(&mut self, original: Option<&str>) {
+        let Some(original) = original else {
+            self.backspace();
+            return;
+        };
+        self.move_left();
+        let col = self.cursor_col;
+        let new_line: String = self
+            .current_line()
+            .graphemes(true)
+            .enumerate()
+            .map(|(i, g)| if i == col { original } else { g })
+            .collect();
+        self.lines[self.cursor_row] = new_line;
+    }
+
     pub fn move_left(&mut self) {
         if self.cursor_col > 0 {
             self.cursor_col -= 1;
@@ -255,6 +429,14 @@ impl TextBuffer {
         self.cursor_col = 0;
     }
 
+    /// Move to the first non-whitespace character of the line.
+    pub fn move_to_first_non_blank(&mut self) {
+        self.cursor_col = self.lines[self.cursor_row]
+            .graphemes(true)
+            .take_while(|g| g.chars().all(char::is_whitespace))
+            .count();
+    }
+
     pub fn move_to_line_end(&mut self) {
         self.cursor_col = self.current_line_len();
     }
@@ -269,6 +451,102 @@ impl TextBuffer {
         self.cursor_col = 0;
     }
 
+    /// `}`: the blank line after the current paragraph, or the end of the
+    /// buffer.
+    pub fn move_paragraph_forward(&mut self) {
+        let last = self.lines.len() - 1;
+        let blank = |row: usize| self.lines[row].trim().is_empty();
+        let mut row = self.cursor_row;
+        while row < last && blank(row) {
+            row += 1;
+        }
+        while row < last && !blank(row) {
+            row += 1;
+        }
+        let col = if blank(row) {
+            0
+        } else {
+            self.lines[row].graphemes(true).count()
+        };
+        self.cursor_row = row;
+        self.cursor_col = col;
+    }
+
+    /// `{`: the blank line before the current paragraph, or the first line.
+    pub fn move_paragraph_backward(&mut self) {
+        let blank = |row: usize| self.lines[row].trim().is_empty();
+        let mut row = self.cursor_row;
+        while row > 0 && blank(row) {
+            row -= 1;
+        }
+        while row > 0 && !blank(row) {
+            row -= 1;
+        }
+        self.cursor_row = row;
+        self.cursor_col = 0;
+    }
+
+    /// `)`: the start of the next sentence, or the end of the buffer.
+    pub fn move_sentence_forward(&mut self) {
+        let cursor = (self.cursor_row, self.cursor_col);
+        let last = self.lines.len() - 1;
+        let end = (last, self.lines[last].graphemes(true).count());
+        let next = sentence_starts(self.lines.iter())
+            .into_iter()
+            .find(|&start| start > cursor);
+        (self.cursor_row, self.cursor_col) = next.unwrap_or(end);
+    }
+
+    /// `(`: the start of the current sentence, or of the previous one when
+    /// already there.
+    pub fn move_sentence_backward(&mut self) {
+        let cursor = (self.cursor_row, self.cursor_col);
+        let prev = sentence_starts(self.lines.iter())
+            .into_iter()
+            .rev()
+            .find(|&start| start < cursor);
+        (self.cursor_row, self.cursor_col) = prev.unwrap_or((0, 0));
+    }
+
+    /// Move to the next (or previous) unchecked checkbox, placing the cursor
+    /// at the start of its text. Does not wrap. Returns false if none found.
+    pub fn move_to_open_task(&mut self, forward: bool) -> bool {
+        let found = if forward {
+            (self.cursor_row + 1..self.lines.len())
+                .find(|&row| list_prefix::is_open_checkbox(&self.lines[row]))
+        } else {
+            (0..self.cursor_row)
+                .rev()
+                .find(|&row| list_prefix::is_open_checkbox(&self.lines[row]))
+        };
+        match found {
+            Some(row) => {
+                self.jump_to_task(row);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move to the first unchecked checkbox in the buffer.
+    pub fn move_to_first_open_task(&mut self) -> bool {
+        match (0..self.lines.len()).find(|&row| list_prefix::is_open_checkbox(&self.lines[row])) {
+            Some(row) => {
+                self.jump_to_task(row);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn jump_to_task(&mut self, row: usize) {
+        let line = &self.lines[row];
+        let indent = line.len() - line.trim_start().len();
+        let len = line.graphemes(true).count();
+        self.cursor_row = row;
+        self.cursor_col = (line[..indent].graphemes(true).count() + 6).min(len.saturating_sub(1));
+    }
+
     pub fn move_word_forward(&mut self) {
         let line = self.current_line();
         let graphemes: Vec<&str> = line.graphemes(true).collect();
@@ -438,8 +716,8 @@ impl TextBuffer {
         match format {
             MarkdownFormat::CodeBlock => {
                 let (new_lines, new_row, new_col) =
-                    markdown_fmt::toggle_code_block(&self.lines, self.cursor_row);
-                self.lines = new_lines;
+                    markdown_fmt::toggle_code_block(&self.lines.to_vec(), self.cursor_row);
+                self.lines = new_lines.into();
                 self.cursor_row = new_row;
                 self.cursor_col = new_col;
             }
@@ -465,8 +743,8 @@ impl TextBuffer {
         match format {
             MarkdownFormat::CodeBlock => {
                 let (new_lines, new_row, new_col) =
-                    markdown_fmt::toggle_code_block_visual(&self.lines, sr, er);
-                self.lines = new_lines;
+                    markdown_fmt::toggle_code_block_visual(&self.lines.to_vec(), sr, er);
+                self.lines = new_lines.into();
                 self.cursor_row = new_row;
                 self.cursor_col = new_col;
             }
@@ -517,68 +795,117 @@ impl TextBuffer {
         format!("{}\n", line)
     }
 
-    /// Compute the cursor position after applying a motion, without mutating self.
-    fn position_after_motion(&self, motion: Motion) -> (usize, usize) {
+    /// Compute the cursor position after applying a motion `count` times,
+    /// without mutating self.
+    fn position_after_motion(&self, motion: Motion, count: usize) -> (usize, usize) {
         let mut clone = self.clone();
         clone.history = UndoHistory::default(); // avoid cloning history
-        match motion {
-            Motion::WordForward => clone.move_word_forward(),
-            Motion::WordBackward => clone.move_word_backward(),
-            Motion::LineEnd => clone.move_to_line_end(),
-            Motion::LineStart => clone.move_to_line_start(),
-            Motion::FileEnd => clone.move_to_last_line(),
-            Motion::FileStart => clone.move_to_first_line(),
-            Motion::Line => {} // handled separately
+        for _ in 0..count.max(1) {
+            match motion {
+                Motion::WordForward => clone.move_word_forward(),
+                Motion::WordBackward => clone.move_word_backward(),
+                Motion::LineEnd => clone.move_to_line_end(),
+                Motion::LineStart => clone.move_to_line_start(),
+                Motion::FileEnd => clone.move_to_last_line(),
+                Motion::FileStart => clone.move_to_first_line(),
+                Motion::ParagraphForward => clone.move_paragraph_forward(),
+                Motion::ParagraphBackward => clone.move_paragraph_backward(),
+                Motion::SentenceForward => clone.move_sentence_forward(),
+                Motion::SentenceBackward => clone.move_sentence_backward(),
+                Motion::Line | Motion::TextObject(_) => {} // handled separately
+            }
         }
         (clone.cursor_row, clone.cursor_col)
     }
 
-    /// Delete text covered by a motion. Returns (deleted_text, is_linewise).
-    pub fn apply_motion_delete(&mut self, motion: Motion) -> (String, bool) {
-        match motion {
-            Motion::Line => {
-                let text = self.delete_line_and_return();
-                (text, true)
-            }
-            _ => {
-                let (end_row, end_col) = self.position_after_motion(motion);
-                let (start_row, start_col) = (self.cursor_row, self.cursor_col);
-
-                let ((sr, sc), (er, ec)) = if (start_row, start_col) <= (end_row, end_col) {
-                    ((start_row, start_col), (end_row, end_col))
-                } else {
-                    ((end_row, end_col), (start_row, start_col))
-                };
+    /// The `count` lines starting at the cursor (with trailing newline).
+    fn extract_lines(&self, count: usize) -> String {
+        let end = (self.cursor_row + count.max(1)).min(self.lines.len());
+        let lines: Vec<&str> = (self.cursor_row..end)
+            .map(|r| self.lines[r].as_str())
+            .collect();
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
 
-                let text = self.delete_range(sr, sc, er, ec);
-                self.cursor_row = sr;
-                self.cursor_col = sc;
-                (text, false)
-            }
+    /// Characterwise range covered by a non-line motion, ordered start..end.
+    fn motion_range(
+        &self,
+        motion: Motion,
+        count: usize,
+    ) -> Option<((usize, usize), (usize, usize))> {
+        if let Motion::TextObject(obj) = motion {
+            let (start, end) = text_object_range(self.current_line(), self.cursor_col, obj)?;
+            return Some(((self.cursor_row, start), (self.cursor_row, end)));
+        }
+        let mut end = self.position_after_motion(motion, count);
+        let start = (self.cursor_row, self.cursor_col);
+        // An exclusive motion ending at the start of a later line stops at
+        // the end of the line before, so `d}` keeps the blank line
+        if matches!(motion, Motion::ParagraphForward | Motion::SentenceForward)
+            && end.0 > start.0
+            && end.1 == 0
+        {
+            end = (end.0 - 1, self.lines[end.0 - 1].graphemes(true).count());
         }
+        Some((start.min(end), start.max(end)))
     }
 
-    /// Yank (copy) text covered by a motion. Returns (yanked_text, is_linewise).
-    pub fn apply_motion_yank(&self, motion: Motion) -> (String, bool) {
-        match motion {
-            Motion::Line => {
-                let text = self.extract_line();
-                (text, true)
+    /// Delete text covered by a motion repeated `count` times (`3dd`, `2dw`).
+    /// Returns (deleted_text, is_linewise).
+    pub fn apply_motion_delete(&mut self, motion: Motion, count: usize) -> (String, bool) {
+        if motion == Motion::Line {
+            let text = self.extract_lines(count);
+            for _ in 0..text.matches('\n').count() {
+                self.delete_line();
             }
-            _ => {
-                let (end_row, end_col) = self.position_after_motion(motion);
-                let (start_row, start_col) = (self.cursor_row, self.cursor_col);
+            return (text, true);
+        }
+        let Some(((sr, sc), (er, ec))) = self.motion_range(motion, count) else {
+            return (String::new(), false);
+        };
+        let text = self.delete_range(sr, sc, er, ec);
+        self.cursor_row = sr;
+        self.cursor_col = sc;
+        (text, false)
+    }
 
-                let ((sr, sc), (er, ec)) = if (start_row, start_col) <= (end_row, end_col) {
-                    ((start_row, start_col), (end_row, end_col))
-                } else {
-                    ((end_row, end_col), (start_row, start_col))
-                };
+    /// Yank (copy) text covered by a motion repeated `count` times.
+    /// Returns (yanked_text, is_linewise).
+    pub fn apply_motion_yank(&self, motion: Motion, count: usize) -> (String, bool) {
+        if motion == Motion::Line {
+            return (self.extract_lines(count), true);
+        }
+        match self.motion_range(motion, count) {
+            Some(((sr, sc), (er, ec))) => (self.extract_range(sr, sc, er, ec), false),
+            None => (String::new(), false),
+        }
+    }
 
-                let text = self.extract_range(sr, sc, er, ec);
-                (text, false)
+    /// Delete for a change operator and leave the cursor where typing should
+    /// start. `cc` empties the lines but keeps one to type on, and `cw` keeps
+    /// the whitespace after the word, as in Vim.
+    pub fn apply_motion_change(&mut self, motion: Motion, count: usize) -> (String, bool) {
+        if motion == Motion::Line {
+            let text = self.extract_lines(count);
+            let extra = text.matches('\n').count() - 1;
+            let next = self.cursor_row + 1;
+            self.lines.remove_range(next..next + extra);
+            self.clear_current_line();
+            return (text, true);
+        }
+        let (mut text, linewise) = self.apply_motion_delete(motion, count);
+        if motion == Motion::WordForward {
+            let kept = text.len() - text.trim_end().len();
+            if kept < text.len() {
+                let whitespace = text.split_off(text.len() - kept);
+                let (row, col) = (self.cursor_row, self.cursor_col);
+                self.insert_text(&whitespace);
+                self.set_cursor(row, col);
             }
         }
+        (text, linewise)
     }
 
     /// Delete a character-wise range and return the deleted text.
@@ -767,6 +1094,15 @@ impl TextBuffer {
         self.history.redo_stack.clear();
     }
 
+    /// Replace the whole content as one undoable change (e.g. a reload from
+    /// disk), keeping the cursor where it was when it still fits.
+    pub fn replace_content(&mut self, content: &str) {
+        self.save_undo_snapshot();
+        let (row, col) = self.cursor_position();
+        self.lines = Self::from_string(content).lines;
+        self.set_cursor(row, col);
+    }
+
     /// Undo the last change, returning true if successful.
     pub fn undo(&mut self) -> bool {
         if let Some(snapshot) = self.history.undo_stack.pop() {
@@ -803,24 +1139,24 @@ impl TextBuffer {
         }
     }
 
-    /// Search forward from (start_row, start_col+1) for `query` (case-insensitive).
-    /// Wraps around to the beginning of the buffer.
+    /// Search forward from (start_row, start_col+1) for `query` (see
+    /// `SearchPattern`). Wraps around to the beginning of the buffer.
     /// Returns Some((row, col)) of the match start, or None if not found.
     pub fn find_next(
         &self,
-        query: &str,
+        query: impl Into<SearchPattern>,
         start_row: usize,
         start_col: usize,
     ) -> Option<(usize, usize)> {
-        if query.is_empty() || self.lines.is_empty() {
+        let pattern = query.into();
+        if pattern.is_empty() || self.lines.is_empty() {
             return None;
         }
-        let query_lower = query.to_lowercase();
         let total_lines = self.lines.len();
 
         // Search current line from start_col+1 onwards
         if let Some(col) =
-            self.find_in_line_forward(&self.lines[start_row], start_col + 1, &query_lower)
+            Self::find_in_line_forward(&self.lines[start_row], start_col + 1, &pattern)
         {
             return Some((start_row, col));
         }
@@ -828,13 +1164,13 @@ impl TextBuffer {
         // Search subsequent lines, wrapping around
         for offset in 1..total_lines {
             let row = (start_row + offset) % total_lines;
-            if let Some(col) = self.find_in_line_forward(&self.lines[row], 0, &query_lower) {
+            if let Some(col) = Self::find_in_line_forward(&self.lines[row], 0, &pattern) {
                 return Some((row, col));
             }
         }
 
         // Search the start line from column 0 up to start_col
-        if let Some(col) = self.find_in_line_forward(&self.lines[start_row], 0, &query_lower)
+        if let Some(col) = Self::find_in_line_forward(&self.lines[start_row], 0, &pattern)
             && col <= start_col
         {
             return Some((start_row, col));
@@ -843,25 +1179,25 @@ impl TextBuffer {
         None
     }
 
-    /// Search backward from (start_row, start_col-1) for `query` (case-insensitive).
-    /// Wraps around to the end of the buffer.
+    /// Search backward from (start_row, start_col-1) for `query` (see
+    /// `SearchPattern`). Wraps around to the end of the buffer.
     /// Returns Some((row, col)) of the match start, or None if not found.
     pub fn find_prev(
         &self,
-        query: &str,
+        query: impl Into<SearchPattern>,
         start_row: usize,
         start_col: usize,
     ) -> Option<(usize, usize)> {
-        if query.is_empty() || self.lines.is_empty() {
+        let pattern = query.into();
+        if pattern.is_empty() || self.lines.is_empty() {
             return None;
         }
-        let query_lower = query.to_lowercase();
         let total_lines = self.lines.len();
 
         // Search current line backward from start_col-1
         if start_col > 0
             && let Some(col) =
-                self.find_in_line_backward(&self.lines[start_row], start_col - 1, &query_lower)
+                Self::find_in_line_backward(&self.lines[start_row], start_col - 1, &pattern)
         {
             return Some((start_row, col));
         }
@@ -870,8 +1206,7 @@ impl TextBuffer {
         for offset in 1..total_lines {
             let row = (start_row + total_lines - offset) % total_lines;
             let line_len = self.lines[row].graphemes(true).count();
-            if let Some(col) = self.find_in_line_backward(&self.lines[row], line_len, &query_lower)
-            {
+            if let Some(col) = Self::find_in_line_backward(&self.lines[row], line_len, &pattern) {
                 return Some((row, col));
             }
         }
@@ -880,7 +1215,7 @@ impl TextBuffer {
         let line_len = self.lines[start_row].graphemes(true).count();
         if line_len > start_col
             && let Some(col) =
-                self.find_in_line_backward(&self.lines[start_row], line_len, &query_lower)
+                Self::find_in_line_backward(&self.lines[start_row], line_len, &pattern)
             && col > start_col
         {
             return Some((start_row, col));
@@ -889,92 +1224,64 @@ impl TextBuffer {
         None
     }
 
-    /// Find all occurrences of `query` (case-insensitive) in the buffer.
+    /// Find all occurrences of `query` (see `SearchPattern`) in the buffer.
     /// Returns a Vec of (row, col, length_in_graphemes) for each match.
-    pub fn find_all(&self, query: &str) -> Vec<(usize, usize, usize)> {
-        if query.is_empty() {
+    pub fn find_all(&self, query: impl Into<SearchPattern>) -> Vec<(usize, usize, usize)> {
+        let pattern = query.into();
+        if pattern.is_empty() {
             return Vec::new();
         }
-        let query_lower = query.to_lowercase();
-        let query_grapheme_len = query_lower.graphemes(true).count();
-        let mut results = Vec::new();
-
-        for (row, line) in self.lines.iter().enumerate() {
-            let line_lower = line.to_lowercase();
-            let graphemes: Vec<&str> = line.graphemes(true).collect();
-            let mut search_from_byte = 0;
-
-            while let Some(byte_pos) = line_lower[search_from_byte..].find(&query_lower) {
-                let abs_byte = search_from_byte + byte_pos;
-                // Convert byte offset to grapheme index
-                let col = line[..abs_byte].graphemes(true).count();
-                results.push((row, col, query_grapheme_len));
-                // Advance past this match by at least one grapheme's bytes
-                let advance = if col < graphemes.len() {
-                    graphemes[col].len()
-                } else {
-                    1
-                };
-                search_from_byte = abs_byte + advance;
-            }
-        }
-
-        results
-    }
-
-    /// Find the first occurrence of `query_lower` in `line` starting at grapheme index `from_col`.
-    /// Returns the grapheme index of the match start, or None.
-    fn find_in_line_forward(
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                pattern
+                    .find_in(line)
+                    .into_iter()
+                    .map(move |(col, len)| (row, col, len))
+            })
+            .collect()
+    }
+
+    /// Return the 1-based index of the match starting at `(row, col)` and the
+    /// total number of matches for `query`.
+    pub fn match_index(
         &self,
-        line: &str,
-        from_col: usize,
-        query_lower: &str,
-    ) -> Option<usize> {
-        let graphemes: Vec<&str> = line.graphemes(true).collect();
-        if from_col >= graphemes.len() {
-            return None;
-        }
-
-        // Build the substring from from_col and its byte offset
-        let byte_offset: usize = graphemes[..from_col].iter().map(|g| g.len()).sum();
-        let search_str = &line[byte_offset..];
-        let search_lower = search_str.to_lowercase();
+        query: impl Into<SearchPattern>,
+        row: usize,
+        col: usize,
+    ) -> (Option<usize>, usize) {
+        let matches = self.find_all(query);
+        let index = matches
+            .iter()
+            .position(|&(r, c, _)| r == row && c == col)
+            .map(|i| i + 1);
+        (index, matches.len())
+    }
 
-        if let Some(byte_match) = search_lower.find(query_lower) {
-            // Convert byte match position back to grapheme index
-            let matched_bytes = &search_str[..byte_match];
-            let grapheme_offset = matched_bytes.graphemes(true).count();
-            Some(from_col + grapheme_offset)
-        } else {
-            None
-        }
+    /// Grapheme index of the first match of `pattern` in `line` starting at
+    /// or after grapheme index `from_col`.
+    fn find_in_line_forward(line: &str, from_col: usize, pattern: &SearchPattern) -> Option<usize> {
+        pattern
+            .find_in(line)
+            .into_iter()
+            .map(|(col, _)| col)
+            .find(|&col| col >= from_col)
     }
 
-    /// Find the last occurrence of `query_lower` in `line` at or before grapheme index `before_col`.
-    /// Returns the grapheme index of the match start, or None.
+    /// Grapheme index of the last match of `pattern` in `line` that ends at
+    /// or before grapheme index `before_col`.
     fn find_in_line_backward(
-        &self,
         line: &str,
         before_col: usize,
-        query_lower: &str,
+        pattern: &SearchPattern,
     ) -> Option<usize> {
-        let graphemes: Vec<&str> = line.graphemes(true).collect();
-        if graphemes.is_empty() {
-            return None;
-        }
-
-        let end_col = before_col.min(graphemes.len());
-        let byte_end: usize = graphemes[..end_col].iter().map(|g| g.len()).sum();
-        let search_str = &line[..byte_end];
-        let search_lower = search_str.to_lowercase();
-
-        if let Some(byte_match) = search_lower.rfind(query_lower) {
-            let matched_bytes = &search_str[..byte_match];
-            let grapheme_offset = matched_bytes.graphemes(true).count();
-            Some(grapheme_offset)
-        } else {
-            None
-        }
+        pattern
+            .find_in(line)
+            .into_iter()
+            .rev()
+            .find(|&(col, len)| col + len <= before_col)
+            .map(|(col, _)| col)
     }
 
     /// Calculate display column (visual column) for a given (row, col) position.
@@ -1072,6 +1379,7 @@ impl fmt::Display for TextBuffer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::molecules::editor::TextObject;
 
     #[test]
     fn test_buffer_from_string() {
@@ -1080,6 +1388,13 @@ mod tests {
         assert_eq!(buffer.to_string(), "Hello\nWorld");
     }
 
+    #[test]
+    fn test_byte_len() {
+        let buf = TextBuffer::from_string("ab\n日本\n");
+        assert_eq!(buf.byte_len(), buf.to_string().len());
+        assert_eq!(TextBuffer::new().byte_len(), 0);
+    }
+
     #[test]
     fn test_insert_char() {
         let mut buffer = TextBuffer::new();
@@ -1136,6 +1451,31 @@ mod tests {
         assert_eq!(buffer.cursor_position(), (0, 0));
     }
 
+    #[test]
+    fn test_move_to_open_task() {
+        let mut buffer =
+            TextBuffer::from_string("- [ ] one\n- [x] two\ntext\n  - [ ] three\n- [ ] four");
+        buffer.set_cursor(0, 0);
+        assert!(buffer.move_to_open_task(true));
+        assert_eq!(buffer.cursor_position(), (3, 8));
+        assert!(buffer.move_to_open_task(true));
+        assert_eq!(buffer.cursor_position(), (4, 6));
+        assert!(!buffer.move_to_open_task(true));
+        assert_eq!(buffer.cursor_position(), (4, 6));
+        assert!(buffer.move_to_open_task(false));
+        assert_eq!(buffer.cursor_position(), (3, 8));
+        assert!(buffer.move_to_first_open_task());
+        assert_eq!(buffer.cursor_position(), (0, 6));
+        assert!(!buffer.move_to_open_task(false));
+    }
+
+    #[test]
+    fn test_move_to_first_open_task_none() {
+        let mut buffer = TextBuffer::from_string("- [x] done\nplain");
+        assert!(!buffer.move_to_first_open_task());
+        assert_eq!(buffer.cursor_position(), (0, 0));
+    }
+
     #[test]
     fn test_insert_checkbox_on_plain_line() {
         let mut buffer = TextBuffer::from_string("buy milk");
@@ -1253,7 +1593,7 @@ mod tests {
     fn test_apply_motion_delete_word() {
         let mut buffer = TextBuffer::from_string("hello world");
         buffer.set_cursor(0, 0);
-        let (text, linewise) = buffer.apply_motion_delete(Motion::WordForward);
+        let (text, linewise) = buffer.apply_motion_delete(Motion::WordForward, 1);
         assert_eq!(text, "hello ");
         assert!(!linewise);
         assert_eq!(buffer.to_string(), "world");
@@ -1263,16 +1603,208 @@ mod tests {
     fn test_apply_motion_delete_line() {
         let mut buffer = TextBuffer::from_string("first\nsecond\nthird");
         buffer.set_cursor(1, 0);
-        let (text, linewise) = buffer.apply_motion_delete(Motion::Line);
+        let (text, linewise) = buffer.apply_motion_delete(Motion::Line, 1);
         assert_eq!(text, "second\n");
         assert!(linewise);
         assert_eq!(buffer.to_string(), "first\nthird");
     }
 
+    #[test]
+    fn test_apply_motion_delete_count() {
+        let mut buffer = TextBuffer::from_string("one two three four");
+        let (text, _) = buffer.apply_motion_delete(Motion::WordForward, 2);
+        assert_eq!(text, "one two ");
+        assert_eq!(buffer.to_string(), "three four");
+
+        let mut buffer = TextBuffer::from_string("a\nb\nc\nd");
+        buffer.set_cursor(1, 0);
+        let (text, linewise) = buffer.apply_motion_delete(Motion::Line, 5);
+        assert_eq!(text, "b\nc\nd\n");
+        assert!(linewise);
+        assert_eq!(buffer.to_string(), "a");
+    }
+
+    #[test]
+    fn test_text_object_delete_and_yank() {
+        let mut buffer = TextBuffer::from_string("call(\"a b\", c)");
+        buffer.set_cursor(0, 7);
+        let quoted = TextObject::from_key('"', false).unwrap();
+        let (text, linewise) = buffer.apply_motion_yank(Motion::TextObject(quoted), 1);
+        assert_eq!(text, "a b");
+        assert!(!linewise);
+
+        let parens = TextObject::from_key('(', false).unwrap();
+        let (text, _) = buffer.apply_motion_delete(Motion::TextObject(parens), 1);
+        assert_eq!(text, "\"a b\", c");
+        assert_eq!(buffer.to_string(), "call()");
+        assert_eq!(buffer.cursor_position(), (0, 5));
+
+        let (text, _) = buffer.apply_motion_delete(Motion::TextObject(quoted), 1);
+        assert!(text.is_empty());
+        assert_eq!(buffer.to_string(), "call()");
+    }
+
+    #[test]
+    fn test_apply_motion_change() {
+        let mut buffer = TextBuffer::from_string("one two three");
+        let (text, _) = buffer.apply_motion_change(Motion::WordForward, 1);
+        assert_eq!(text, "one");
+        assert_eq!(buffer.to_string(), " two three");
+        assert_eq!(buffer.cursor_position(), (0, 0));
+
+        let mut buffer = TextBuffer::from_string("a\nb\nc\nd");
+        buffer.set_cursor(1, 0);
+        let (text, linewise) = buffer.apply_motion_change(Motion::Line, 2);
+        assert_eq!(text, "b\nc\n");
+        assert!(linewise);
+        assert_eq!(buffer.to_string(), "a\n\nd");
+        assert_eq!(buffer.cursor_position(), (1, 0));
+    }
+
+    #[test]
+    fn test_replace_chars() {
+        let mut buffer = TextBuffer::from_string("你好abc");
+        assert!(buffer.replace_chars('x', 1));
+        assert_eq!(buffer.to_string(), "x好abc");
+        assert!(buffer.replace_chars('世', 3));
+        assert_eq!(buffer.to_string(), "世世世bc");
+        assert_eq!(buffer.cursor_position(), (0, 2));
+
+        assert!(!buffer.replace_chars('z', 4));
+        assert_eq!(buffer.to_string(), "世世世bc");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "x好abc");
+
+        buffer.set_cursor(0, 2);
+        assert!(buffer.replace_chars('\n', 2));
+        assert_eq!(buffer.to_string(), "x好\nc");
+    }
+
+    #[test]
+    fn test_overtype_and_restore() {
+        let mut buffer = TextBuffer::from_string("ab你");
+        buffer.set_cursor(0, 1);
+        let typed: Vec<Option<String>> = "xyz".chars().map(|c| buffer.overtype_char(c)).collect();
+        assert_eq!(buffer.to_string(), "axyz");
+        assert_eq!(typed, vec![Some("b".into()), Some("你".into()), None]);
+
+        for original in typed.iter().rev() {
+            buffer.restore_overtyped(original.as_deref());
+        }
+        assert_eq!(buffer.to_string(), "ab你");
+        assert_eq!(buffer.cursor_position(), (0, 1));
+    }
+
+    #[test]
+    fn test_paragraph_motions() {
+        let mut buffer = TextBuffer::from_string("a\nb\n\n\nc\nd");
+        buffer.move_paragraph_forward();
+        assert_eq!(buffer.cursor_position(), (2, 0));
+        buffer.move_paragraph_forward();
+        assert_eq!(buffer.cursor_position(), (5, 1));
+        buffer.move_paragraph_backward();
+        assert_eq!(buffer.cursor_position(), (3, 0));
+        buffer.move_paragraph_backward();
+        assert_eq!(buffer.cursor_position(), (0, 0));
+
+        let mut buffer = TextBuffer::from_string("one\ntwo\n\nthree");
+        let (text, linewise) = buffer.apply_motion_delete(Motion::ParagraphForward, 1);
+        assert_eq!(text, "one\ntwo");
+        assert!(!linewise);
+        assert_eq!(buffer.to_string(), "\n\nthree");
+    }
+
+    #[test]
+    fn test_sentence_motions() {
+        let mut buffer = TextBuffer::from_string("First one. Second one!\nThird.");
+        buffer.set_cursor(0, 3);
+        buffer.move_sentence_forward();
+        assert_eq!(buffer.cursor_position(), (0, 11));
+        buffer.move_sentence_forward();
+        assert_eq!(buffer.cursor_position(), (1, 0));
+        buffer.move_sentence_forward();
+        assert_eq!(buffer.cursor_position(), (1, 6));
+        buffer.set_cursor(0, 14);
+        buffer.move_sentence_backward();
+        assert_eq!(buffer.cursor_position(), (0, 11));
+        buffer.move_sentence_backward();
+        assert_eq!(buffer.cursor_position(), (0, 0));
+
+        let (text, _) = buffer.apply_motion_delete(Motion::SentenceForward, 1);
+        assert_eq!(text, "First one. ");
+        assert_eq!(buffer.to_string(), "Second one!\nThird.");
+    }
+
+    #[test]
+    fn test_increment_number() {
+        let mut buffer = TextBuffer::from_string("item 9 of 10");
+        assert!(buffer.increment_number(1));
+        assert_eq!(buffer.to_string(), "item 10 of 10");
+        assert_eq!(buffer.cursor_position(), (0, 6));
+        assert!(buffer.increment_number(-15));
+        assert_eq!(buffer.to_string(), "item -5 of 10");
+        assert!(buffer.increment_number(5));
+        assert_eq!(buffer.to_string(), "item 0 of 10");
+
+        let mut buffer = TextBuffer::from_string("due 2024-05-09");
+        buffer.set_cursor(0, 12);
+        assert!(buffer.increment_number(1));
+        assert_eq!(buffer.to_string(), "due 2024-05-10");
+        buffer.set_cursor(0, 9);
+        assert!(buffer.increment_number(-5));
+        assert_eq!(buffer.to_string(), "due 2024-00-10");
+
+        // After `$` the number before the cursor counts
+        let mut buffer = TextBuffer::from_string("page 41");
+        buffer.move_to_line_end();
+        assert!(buffer.increment_number(1));
+        assert_eq!(buffer.to_string(), "page 42");
+        assert_eq!(buffer.cursor_position(), (0, 6));
+
+        let mut buffer = TextBuffer::from_string("数量 x");
+        buffer.move_to_line_end();
+        assert!(!buffer.increment_number(1));
+        assert!(!buffer.undo());
+    }
+
+    #[test]
+    fn test_move_to_first_non_blank() {
+        let mut buffer = TextBuffer::from_string("    - item\nplain");
+        buffer.set_cursor(0, 8);
+        buffer.move_to_first_non_blank();
+        assert_eq!(buffer.cursor_position(), (0, 4));
+        buffer.set_cursor(1, 3);
+        buffer.move_to_first_non_blank();
+        assert_eq!(buffer.cursor_position(), (1, 0));
+    }
+
+    #[test]
+    fn test_replace_content_is_undoable() {
+        let mut buffer = TextBuffer::from_string("mine\nline two");
+        buffer.set_cursor(1, 6);
+        buffer.replace_content("theirs");
+        assert_eq!(buffer.to_string(), "theirs");
+        assert_eq!(buffer.cursor_position(), (0, 6));
+
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "mine\nline two");
+        assert_eq!(buffer.cursor_position(), (1, 6));
+        assert!(buffer.redo());
+        assert_eq!(buffer.to_string(), "theirs");
+    }
+
+    #[test]
+    fn test_delete_chars_stops_at_line_end() {
+        let mut buffer = TextBuffer::from_string("abc\ndef");
+        buffer.set_cursor(0, 1);
+        buffer.delete_chars(10);
+        assert_eq!(buffer.to_string(), "a\ndef");
+    }
+
     #[test]
     fn test_apply_motion_yank_line() {
         let buffer = TextBuffer::from_string("first\nsecond");
-        let (text, linewise) = buffer.apply_motion_yank(Motion::Line);
+        let (text, linewise) = buffer.apply_motion_yank(Motion::Line, 1);
         assert_eq!(text, "first\n");
         assert!(linewise);
         assert_eq!(buffer.to_string(), "first\nsecond"); // unchanged
@@ -1453,6 +1985,14 @@ mod tests {
         assert!(matches.is_empty());
     }
 
+    #[test]
+    fn test_match_index() {
+        let buffer = TextBuffer::from_string("foo bar\nbaz foo\nfoo");
+        assert_eq!(buffer.match_index("foo", 1, 4), (Some(2), 3));
+        assert_eq!(buffer.match_index("foo", 0, 1), (None, 3));
+        assert_eq!(buffer.match_index("qux", 0, 0), (None, 0));
+    }
+
     #[test]
     fn test_find_all_multiline() {
         let buffer = TextBuffer::from_string("foo bar\nbaz foo\nfoo");
@@ -1463,6 +2003,21 @@ mod tests {
         assert_eq!(matches[2], (2, 0, 3));
     }
 
+    #[test]
+    fn test_find_normalizes_unicode() {
+        // "café" with a combining accent, then a composed one
+        let mut buffer = TextBuffer::from_string("cafe\u{301}\ncafé");
+        assert_eq!(buffer.find_all("café"), [(0, 0, 4), (1, 0, 4)]);
+        assert_eq!(buffer.find_next("CAFÉ", 0, 0), Some((1, 0)));
+        assert!(buffer.find_all("cafe").is_empty());
+        let pattern = SearchPattern::new("cafe", true);
+        assert_eq!(buffer.find_prev(pattern, 1, 0), Some((0, 0)));
+
+        buffer.set_cursor(1, 4);
+        buffer.insert_text(" e\u{301}");
+        assert_eq!(buffer.current_line_content(), "café é");
+    }
+
     #[test]
     fn test_paste_after_cursor_multiline() {
         let mut buffer = TextBuffer::from_string("hello world");
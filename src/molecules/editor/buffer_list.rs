@@ -0,0 +1,164 @@
+use super::buffer::TextBuffer;
+use crate::types::Note;
+
+/// A note opened in the editor this session.
+#[derive(Debug, Clone)]
+pub struct OpenBuffer {
+    pub note: Note,
+    /// Parked text with its cursor and undo history; `None` while the note
+    /// is the one being edited, whose text is `App::buffer`.
+    text: Option<TextBuffer>,
+}
+
+/// Notes opened in the editor, in the order they were first opened, for
+/// `:bnext` / `:bprev` and the buffer picker.
+#[derive(Debug, Clone, Default)]
+pub struct BufferList {
+    entries: Vec<OpenBuffer>,
+}
+
+impl BufferList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[OpenBuffer] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.note.id == id)
+    }
+
+    /// Parked text of the note with `id`, if any.
+    pub fn text(&self, id: &str) -> Option<&TextBuffer> {
+        self.entries[self.position(id)?].text.as_ref()
+    }
+
+    /// Keep `text` for `note`, which is no longer being edited.
+    pub fn park(&mut self, note: &Note, text: TextBuffer) {
+        match self.position(&note.id) {
+            Some(i) => {
+                self.entries[i].note = note.clone();
+                self.entries[i].text = Some(text);
+            }
+            None => self.entries.push(OpenBuffer {
+                note: note.clone(),
+                text: Some(text),
+            }),
+        }
+    }
+
+    /// Mark `note` as the one being edited, adding it at the end if it is
+    /// new. Returns its parked text, if any.
+    pub fn activate(&mut self, note: &Note) -> Option<TextBuffer> {
+        match self.position(&note.id) {
+            Some(i) => self.entries[i].text.take(),
+            None => {
+                self.entries.push(OpenBuffer {
+                    note: note.clone(),
+                    text: None,
+                });
+                None
+            }
+        }
+    }
+
+    /// The note `count` buffers after (or before) the one with `id`,
+    /// wrapping around. `None` when there is no other buffer.
+    pub fn neighbour(&self, id: &str, forward: bool, count: usize) -> Option<&Note> {
+        let len = self.entries.len();
+        let from = self.position(id)?;
+        let step = count % len;
+        if len < 2 || step == 0 {
+            return None;
+        }
+        let to = if forward {
+            (from + step) % len
+        } else {
+            (from + len - step) % len
+        };
+        Some(&self.entries[to].note)
+    }
+
+    /// Replace the stored note, e.g. after it moved to the archive.
+    pub fn update_note(&mut self, note: &Note) {
+        if let Some(i) = self.position(&note.id) {
+            self.entries[i].note = note.clone();
+        }
+    }
+
+    /// Drop the parked text of a note changed on disk, so it is read again
+    /// when switched to.
+    pub fn discard_text(&mut self, id: &str) {
+        if let Some(i) = self.position(id) {
+            self.entries[i].text = None;
+        }
+    }
+
+    /// Close the buffer of a deleted note.
+    pub fn remove(&mut self, id: &str) {
+        self.entries.retain(|e| e.note.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str) -> Note {
+        Note::new(id.to_string(), id.to_string(), String::new())
+    }
+
+    #[test]
+    fn test_park_and_activate_keep_text() {
+        let mut list = BufferList::new();
+        assert!(list.activate(&note("a")).is_none());
+
+        let mut text = TextBuffer::from_string("one\ntwo");
+        text.set_cursor(1, 2);
+        list.park(&note("a"), text);
+        assert!(list.activate(&note("b")).is_none());
+        assert_eq!(list.len(), 2);
+
+        assert_eq!(list.text("a").unwrap().cursor_position(), (1, 2));
+        let text = list.activate(&note("a")).unwrap();
+        assert_eq!(text.cursor_position(), (1, 2));
+        // Taken out again while it is being edited
+        assert!(list.activate(&note("a")).is_none());
+
+        list.park(&note("b"), TextBuffer::new());
+        list.discard_text("b");
+        assert!(list.activate(&note("b")).is_none());
+    }
+
+    #[test]
+    fn test_neighbour_wraps() {
+        let mut list = BufferList::new();
+        for id in ["a", "b", "c"] {
+            list.activate(&note(id));
+        }
+        let id = |n: Option<&Note>| n.map(|n| n.id.clone());
+        assert_eq!(id(list.neighbour("a", true, 1)), Some("b".to_string()));
+        assert_eq!(id(list.neighbour("a", false, 1)), Some("c".to_string()));
+        assert_eq!(id(list.neighbour("c", true, 2)), Some("b".to_string()));
+        assert_eq!(id(list.neighbour("a", true, 3)), None);
+
+        list.remove("b");
+        list.remove("c");
+        assert_eq!(id(list.neighbour("a", true, 1)), None);
+        assert_eq!(id(list.neighbour("x", true, 1)), None);
+    }
+}
@@ -0,0 +1,193 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::mention::mention_at;
+use crate::molecules::list::FuzzyQuery;
+
+/// How many candidates the completion popup offers.
+pub const COMPLETION_LIMIT: usize = 8;
+
+/// What the Insert mode completion popup is completing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    /// `@name`: contacts from the address book.
+    Mention,
+    /// `#tag`: tags of the drafts.
+    Tag,
+    /// `[[title`: note titles.
+    Link,
+    /// A word asked for with Ctrl-N: words of the note.
+    Word,
+}
+
+impl CompletionKind {
+    /// Shown before each candidate in the popup.
+    pub fn marker(self) -> &'static str {
+        match self {
+            CompletionKind::Mention => "@",
+            CompletionKind::Tag => "#",
+            CompletionKind::Link => "[[",
+            CompletionKind::Word => "",
+        }
+    }
+
+    /// What replaces the typed text when `item` is accepted.
+    pub fn text(self, item: &str) -> String {
+        match self {
+            CompletionKind::Link => format!("[[{}]]", item),
+            kind => format!("{}{}", kind.marker(), item),
+        }
+    }
+}
+
+/// The `#tag` being typed before grapheme column `col` of `line`: the
+/// column of its `#` and the text after it, at least one character. The
+/// `#` starts the line or follows whitespace or `(`, as in `tag_spans`.
+pub fn tag_at(line: &str, col: usize) -> Option<(usize, String)> {
+    let graphemes: Vec<&str> = line.graphemes(true).take(col).collect();
+    let hash = graphemes.iter().rposition(|g| *g == "#")?;
+    let typed = graphemes[hash + 1..].concat();
+    let starts_word =
+        hash == 0 || graphemes[hash - 1] == "(" || graphemes[hash - 1].trim().is_empty();
+    let is_tag = !typed.is_empty()
+        && typed
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'));
+    (starts_word && is_tag).then_some((hash, typed))
+}
+
+/// The `[[link` being typed before grapheme column `col` of `line`: the
+/// column of its `[[` and the title typed so far. Typing `]`, `|` or `#`
+/// ends it.
+pub fn partial_link_at(line: &str, col: usize) -> Option<(usize, String)> {
+    let graphemes: Vec<&str> = line.graphemes(true).take(col).collect();
+    let open = graphemes.windows(2).rposition(|pair| pair == ["[", "["])?;
+    let typed = graphemes[open + 2..].concat();
+    (!typed.contains([']', '|', '#'])).then_some((open, typed))
+}
+
+/// The word before grapheme column `col` of `line`: where it starts and
+/// its text, letters, digits and `_`.
+pub fn word_at(line: &str, col: usize) -> Option<(usize, String)> {
+    let graphemes: Vec<&str> = line.graphemes(true).take(col).collect();
+    let start = graphemes
+        .iter()
+        .rposition(|g| !g.chars().all(is_word_char))
+        .map_or(0, |i| i + 1);
+    (start < graphemes.len()).then(|| (start, graphemes[start..].concat()))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// What the popup completes at grapheme column `col` of `line`: a link, a
+/// mention or a tag, else a word when `words` was asked for. Returns the
+/// kind, the column the replaced text starts at and the typed text.
+pub fn completion_at(
+    line: &str,
+    col: usize,
+    words: bool,
+) -> Option<(CompletionKind, usize, String)> {
+    if let Some((start, typed)) = partial_link_at(line, col) {
+        return Some((CompletionKind::Link, start, typed));
+    }
+    if let Some((start, typed)) = mention_at(line, col) {
+        return Some((CompletionKind::Mention, start, typed));
+    }
+    if let Some((start, typed)) = tag_at(line, col) {
+        return Some((CompletionKind::Tag, start, typed));
+    }
+    if words && let Some((start, typed)) = word_at(line, col) {
+        return Some((CompletionKind::Word, start, typed));
+    }
+    None
+}
+
+/// The distinct words of `text` of three or more characters, in the order
+/// they first appear.
+pub fn buffer_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !is_word_char(c)) {
+        if word.chars().count() >= 3 && !words.iter().any(|w| w == word) {
+            words.push(word.to_string());
+        }
+    }
+    words
+}
+
+/// Up to `limit` of `candidates` for `typed`, fuzzily: those with a word
+/// starting with it, then those containing it, then those with its
+/// characters in order. A candidate typed in full is left out.
+pub fn completion_matches<'a>(candidates: &'a [String], typed: &str, limit: usize) -> Vec<&'a str> {
+    let query = FuzzyQuery::new(typed);
+    let mut tiers: [Vec<&str>; 3] = Default::default();
+    for candidate in candidates.iter().map(String::as_str) {
+        if candidate == typed {
+            continue;
+        }
+        let tier = if candidate
+            .split(|c: char| !is_word_char(c))
+            .any(|word| query.is_prefix_of(word))
+        {
+            0
+        } else if query.is_substring_of(candidate) {
+            1
+        } else if query.is_subsequence_of(candidate) {
+            2
+        } else {
+            continue;
+        };
+        tiers[tier].push(candidate);
+    }
+    let mut matches = tiers.concat();
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_at() {
+        assert_eq!(tag_at("see #wo", 7), Some((4, "wo".to_string())));
+        assert!(tag_at("# Heading", 1).is_none());
+        assert!(tag_at("page#an", 7).is_none());
+        assert_eq!(
+            partial_link_at("see [[Mee", 9),
+            Some((4, "Mee".to_string()))
+        );
+        assert!(partial_link_at("[[Plan]] x", 10).is_none());
+        assert_eq!(word_at("foo ba", 6), Some((4, "ba".to_string())));
+        assert!(word_at("foo ", 4).is_none());
+        assert_eq!(
+            completion_at("ask @Jo", 7, false),
+            Some((CompletionKind::Mention, 4, "Jo".to_string()))
+        );
+        assert!(completion_at("plain", 5, false).is_none());
+        assert_eq!(
+            completion_at("plain", 5, true),
+            Some((CompletionKind::Word, 0, "plain".to_string()))
+        );
+        assert_eq!(CompletionKind::Link.text("Plan"), "[[Plan]]");
+        assert_eq!(CompletionKind::Tag.text("work"), "#work");
+    }
+
+    #[test]
+    fn test_matches_rank_prefix_then_fuzzy() {
+        let candidates = buffer_words("Meeting notes: a tiny meet-up, no mtg. Meeting again");
+        assert_eq!(
+            candidates,
+            ["Meeting", "notes", "tiny", "meet", "mtg", "again"]
+        );
+        assert_eq!(
+            completion_matches(&candidates, "mt", 8),
+            ["mtg", "Meeting", "meet"]
+        );
+        assert_eq!(
+            completion_matches(&candidates, "in", 8),
+            ["Meeting", "tiny", "again"]
+        );
+        assert_eq!(completion_matches(&candidates, "meet", 1), ["Meeting"]);
+    }
+}
@@ -0,0 +1,125 @@
+use crossterm::event::KeyEvent;
+
+/// Keys of the last buffer change, replayed by `.`.
+///
+/// A change is the Normal mode command that made it, count and operator keys
+/// included. When that command enters Insert mode the change runs until
+/// Insert mode is left, so `ciwfoo<Esc>` repeats as a whole.
+#[derive(Debug, Default)]
+pub struct ChangeRecorder {
+    /// Keys of the Normal mode command being typed.
+    command: Vec<KeyEvent>,
+    /// Keys of a change that is still in Insert mode.
+    inserting: Option<Vec<KeyEvent>>,
+    last: Vec<KeyEvent>,
+    replaying: bool,
+}
+
+impl ChangeRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key typed in Normal mode to the command being recorded.
+    pub fn push_normal_key(&mut self, key: KeyEvent) {
+        if !self.replaying {
+            self.command.push(key);
+        }
+    }
+
+    /// The command was complete but changed nothing; forget it.
+    pub fn discard_command(&mut self) {
+        self.command.clear();
+    }
+
+    /// The command changed the buffer. If it left the editor in Insert mode
+    /// the change continues with the keys typed there.
+    pub fn finish_command(&mut self, inserting: bool) {
+        if self.replaying {
+            return;
+        }
+        let keys = std::mem::take(&mut self.command);
+        if inserting {
+            self.inserting = Some(keys);
+        } else {
+            self.last = keys;
+        }
+    }
+
+    /// Add a key typed in Insert mode; `leaving` ends the change.
+    pub fn push_insert_key(&mut self, key: KeyEvent, leaving: bool) {
+        if self.replaying {
+            return;
+        }
+        if let Some(keys) = &mut self.inserting {
+            keys.push(key);
+        }
+        if leaving && let Some(keys) = self.inserting.take() {
+            self.last = keys;
+        }
+    }
+
+    pub fn last_change(&self) -> &[KeyEvent] {
+        &self.last
+    }
+
+    /// While replaying, keys are executed but not recorded.
+    pub fn set_replaying(&mut self, replaying: bool) {
+        self.replaying = replaying;
+        self.command.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_records_normal_command() {
+        let mut recorder = ChangeRecorder::new();
+        recorder.push_normal_key(key('2'));
+        recorder.push_normal_key(key('d'));
+        recorder.push_normal_key(key('w'));
+        recorder.finish_command(false);
+        assert_eq!(recorder.last_change(), &[key('2'), key('d'), key('w')]);
+
+        recorder.push_normal_key(key('j'));
+        recorder.discard_command();
+        assert_eq!(recorder.last_change().len(), 3);
+    }
+
+    #[test]
+    fn test_insert_session_ends_on_leave() {
+        let mut recorder = ChangeRecorder::new();
+        recorder.push_normal_key(key('x'));
+        recorder.finish_command(false);
+
+        recorder.push_normal_key(key('i'));
+        recorder.finish_command(true);
+        recorder.push_insert_key(key('h'), false);
+        assert_eq!(recorder.last_change(), &[key('x')]);
+
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        recorder.push_insert_key(esc, true);
+        assert_eq!(recorder.last_change(), &[key('i'), key('h'), esc]);
+    }
+
+    #[test]
+    fn test_replay_is_not_recorded() {
+        let mut recorder = ChangeRecorder::new();
+        recorder.push_normal_key(key('x'));
+        recorder.finish_command(false);
+
+        recorder.set_replaying(true);
+        recorder.push_normal_key(key('d'));
+        recorder.push_normal_key(key('d'));
+        recorder.finish_command(false);
+        recorder.set_replaying(false);
+        assert_eq!(recorder.last_change(), &[key('x')]);
+    }
+}
@@ -0,0 +1,562 @@
+use anyhow::{Result, bail};
+
+use super::panes::SplitDir;
+use crate::molecules::share::ExportFormat;
+
+/// A command typed on the `:` command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// `:cd <path>` switches the data directory; `:cd` alone returns to the
+    /// configured one.
+    ChangeDataDir(Option<String>),
+    /// `:pwd` shows the active data directory.
+    PrintDataDir,
+    /// `:set wrap` / `:set nowrap` toggles soft-wrap in the editor.
+    SetWrap(bool),
+    /// `:set ignorediacritics` / `:set noignorediacritics` toggles accent
+    /// insensitive `/` search.
+    SetIgnoreDiacritics(bool),
+    /// `:set spell` / `:set nospell` toggles underlining misspelled words.
+    SetSpell(bool),
+    /// `:set spelllang=LANG` checks the current note against dictionary
+    /// `LANG`, kept in its `spelllang` field.
+    SetSpellLang(String),
+    /// `:bnext` (true) / `:bprev` (false) switches to another open note.
+    CycleBuffer(bool),
+    /// `:ls` / `:buffers` opens the buffer picker.
+    ListBuffers,
+    /// `:backlinks` lists the notes linking to the current one.
+    Backlinks,
+    /// `:history` lists the snapshots taken of the current note.
+    History,
+    /// `:tags` opens the tag browser.
+    Tags,
+    /// `:tag <name>` adds `#name` to the current note.
+    AddTag(String),
+    /// `:review` goes through the notes modified in the past week.
+    Review,
+    /// `:syncreminders` checks off the sent reminders completed since.
+    SyncReminders,
+    /// `:agenda` inserts today's calendar events.
+    Agenda,
+    /// `:rename [title]` renames the note; without a title it asks for one.
+    Rename(Option<String>),
+    /// `:sync-title` sets the `title` field to the first heading.
+    SyncTitle,
+    /// `:sync` commits the data directory and pulls/pushes its git remote.
+    GitSync,
+    /// `:export md|html|pdf [path]` exports the note, to `exports/` unless
+    /// a path is given; `:export` alone asks for the format.
+    Export {
+        format: Option<ExportFormat>,
+        path: Option<String>,
+    },
+    /// `:today` opens or creates today's daily note.
+    Today,
+    /// `:new [template [title]]` creates a note from a template, with
+    /// `{{title}}` set to `title`; `:new` alone offers the templates.
+    New {
+        template: Option<String>,
+        title: Option<String>,
+    },
+    /// `:import PATH` imports markdown files, or an Apple Notes or Bear
+    /// export, as drafts.
+    Import(String),
+    /// `:lint-blocks` lists the smart blocks that would not be sent as written.
+    LintBlocks,
+    /// `:split-headings` moves each top-level section of the note into a
+    /// draft of its own and leaves a link to it; `:split-headings!` leaves
+    /// no links.
+    SplitHeadings { links: bool },
+    /// `:trash` opens the trash view.
+    Trash,
+    /// `:empty-trash` deletes every trashed note for good.
+    EmptyTrash,
+    /// `:copen` shows the locations panel and gives it the keyboard.
+    OpenQuickfix,
+    /// `:cclose` hides the locations panel.
+    CloseQuickfix,
+    /// `:split [title]` / `:vsplit [title]` shows the current note, or the
+    /// one named `title`, in a second pane; `:sview` / `:vsview` do the same
+    /// with a pane that refuses edits.
+    Split {
+        dir: SplitDir,
+        title: Option<String>,
+        read_only: bool,
+    },
+    /// `:close` closes the focused pane.
+    ClosePane,
+    /// `:only` closes every other pane.
+    OnlyPane,
+    /// `:reindex` rebuilds the search index from the note files.
+    Reindex,
+    /// `:[range]s/pattern/replacement/[flags]`
+    Substitute(Substitute),
+    /// `:replace-all /pattern/replacement/[flags]` substitutes in every
+    /// draft, asking about each changed line.
+    ReplaceAll(Substitute),
+}
+
+/// Lines an ex command applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineRange {
+    /// No range, or `.`
+    Current,
+    /// `%`
+    All,
+    /// `'<,'>`, the last Visual selection
+    Selection,
+    /// `N` or `N,M`, 1-based and inclusive
+    Lines(usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitute {
+    pub range: LineRange,
+    /// Regex; empty means the last search query.
+    pub pattern: String,
+    /// Vim-style replacement: `&` is the match, `\1`..`\9` are groups.
+    pub replacement: String,
+    /// `g`: every match on a line, not only the first.
+    pub global: bool,
+    /// `c`: ask before each replacement.
+    pub confirm: bool,
+    /// `i`: ignore case.
+    pub ignore_case: bool,
+}
+
+/// Parse the text typed after `:`.
+pub fn parse_ex_command(input: &str) -> Result<ExCommand> {
+    let input = input.trim();
+    let (range, rest) = parse_range(input)?;
+
+    if let Some(args) = rest.strip_prefix('s')
+        && args.starts_with(|c: char| !c.is_alphanumeric() && !c.is_whitespace())
+    {
+        return parse_substitute(range, args).map(ExCommand::Substitute);
+    }
+    if range.is_some() {
+        bail!("No range allowed: {}", input);
+    }
+
+    let (name, arg) = match rest.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (rest, ""),
+    };
+
+    match name {
+        "cd" => Ok(ExCommand::ChangeDataDir(
+            (!arg.is_empty()).then(|| arg.to_string()),
+        )),
+        "pwd" if arg.is_empty() => Ok(ExCommand::PrintDataDir),
+        "set" | "se" => match arg {
+            "wrap" => Ok(ExCommand::SetWrap(true)),
+            "nowrap" => Ok(ExCommand::SetWrap(false)),
+            "ignorediacritics" => Ok(ExCommand::SetIgnoreDiacritics(true)),
+            "noignorediacritics" => Ok(ExCommand::SetIgnoreDiacritics(false)),
+            "spell" => Ok(ExCommand::SetSpell(true)),
+            "nospell" => Ok(ExCommand::SetSpell(false)),
+            _ => match arg.strip_prefix("spelllang=") {
+                Some(lang) if !lang.is_empty() => Ok(ExCommand::SetSpellLang(lang.to_string())),
+                _ => bail!("Unknown option: {}", arg),
+            },
+        },
+        "bn" | "bnext" if arg.is_empty() => Ok(ExCommand::CycleBuffer(true)),
+        "bp" | "bprev" | "bprevious" | "bN" | "bNext" if arg.is_empty() => {
+            Ok(ExCommand::CycleBuffer(false))
+        }
+        "ls" | "buffers" if arg.is_empty() => Ok(ExCommand::ListBuffers),
+        "backlinks" if arg.is_empty() => Ok(ExCommand::Backlinks),
+        "history" if arg.is_empty() => Ok(ExCommand::History),
+        "tags" if arg.is_empty() => Ok(ExCommand::Tags),
+        "tag" if !arg.is_empty() => Ok(ExCommand::AddTag(arg.trim_start_matches('#').to_string())),
+        "tag" => bail!("Tag name required"),
+        "review" if arg.is_empty() => Ok(ExCommand::Review),
+        "syncreminders" if arg.is_empty() => Ok(ExCommand::SyncReminders),
+        "agenda" if arg.is_empty() => Ok(ExCommand::Agenda),
+        "rename" => Ok(ExCommand::Rename(
+            (!arg.is_empty()).then(|| arg.to_string()),
+        )),
+        "sync-title" if arg.is_empty() => Ok(ExCommand::SyncTitle),
+        "sync" if arg.is_empty() => Ok(ExCommand::GitSync),
+        "export" if arg.is_empty() => Ok(ExCommand::Export {
+            format: None,
+            path: None,
+        }),
+        "export" => {
+            let (format, path) = match arg.split_once(char::is_whitespace) {
+                Some((format, path)) => (format, Some(path.trim().to_string())),
+                None => (arg, None),
+            };
+            match ExportFormat::from_name(format) {
+                Some(format) => Ok(ExCommand::Export {
+                    format: Some(format),
+                    path,
+                }),
+                None => bail!("Unknown export format: {}", format),
+            }
+        }
+        "today" if arg.is_empty() => Ok(ExCommand::Today),
+        "replace-all" if arg.is_empty() => bail!("Pattern required"),
+        "replace-all" => parse_substitute(Some(LineRange::All), arg).map(ExCommand::ReplaceAll),
+        "new" => {
+            let (template, title) = match arg.split_once(char::is_whitespace) {
+                Some((template, title)) => (template, title.trim()),
+                None => (arg, ""),
+            };
+            Ok(ExCommand::New {
+                template: (!template.is_empty()).then(|| template.to_string()),
+                title: (!title.is_empty()).then(|| title.to_string()),
+            })
+        }
+        "import" if arg.is_empty() => bail!("Import path required"),
+        "import" => Ok(ExCommand::Import(arg.to_string())),
+        "lint-blocks" if arg.is_empty() => Ok(ExCommand::LintBlocks),
+        "split-headings" if arg.is_empty() => Ok(ExCommand::SplitHeadings { links: true }),
+        "split-headings!" if arg.is_empty() => Ok(ExCommand::SplitHeadings { links: false }),
+        "trash" if arg.is_empty() => Ok(ExCommand::Trash),
+        "empty-trash" if arg.is_empty() => Ok(ExCommand::EmptyTrash),
+        "cope" | "copen" if arg.is_empty() => Ok(ExCommand::OpenQuickfix),
+        "ccl" | "cclose" if arg.is_empty() => Ok(ExCommand::CloseQuickfix),
+        "reindex" if arg.is_empty() => Ok(ExCommand::Reindex),
+        "sp" | "split" | "vs" | "vsplit" | "sv" | "sview" | "vsv" | "vsview" => {
+            Ok(ExCommand::Split {
+                dir: if name.starts_with('v') {
+                    SplitDir::Vertical
+                } else {
+                    SplitDir::Horizontal
+                },
+                title: (!arg.is_empty()).then(|| arg.to_string()),
+                read_only: matches!(name, "sv" | "sview" | "vsv" | "vsview"),
+            })
+        }
+        "clo" | "close" if arg.is_empty() => Ok(ExCommand::ClosePane),
+        "on" | "only" if arg.is_empty() => Ok(ExCommand::OnlyPane),
+        "" => bail!("Empty command"),
+        _ => bail!("Not a command: {}", input),
+    }
+}
+
+/// Split a leading line range off `input`.
+fn parse_range(input: &str) -> Result<(Option<LineRange>, &str)> {
+    if let Some(rest) = input.strip_prefix('%') {
+        return Ok((Some(LineRange::All), rest));
+    }
+    if let Some(rest) = input.strip_prefix("'<,'>") {
+        return Ok((Some(LineRange::Selection), rest));
+    }
+    if let Some(rest) = input.strip_prefix('.') {
+        return Ok((Some(LineRange::Current), rest));
+    }
+
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let end = digits(input);
+    if end == 0 {
+        return Ok((None, input));
+    }
+    let first: usize = input[..end].parse()?;
+    let (last, rest) = match input[end..].strip_prefix(',') {
+        Some(after) => {
+            let len = digits(after);
+            if len == 0 {
+                bail!("Invalid range: {}", input);
+            }
+            (after[..len].parse()?, &after[len..])
+        }
+        None => (first, &input[end..]),
+    };
+    if first == 0 || last == 0 {
+        bail!("Invalid range: {}", input);
+    }
+    Ok((
+        Some(LineRange::Lines(first.min(last), first.max(last))),
+        rest,
+    ))
+}
+
+/// Parse `/pattern/replacement/flags`; any punctuation works as the
+/// delimiter and `\<delimiter>` escapes it.
+fn parse_substitute(range: Option<LineRange>, args: &str) -> Result<Substitute> {
+    let mut chars = args.chars();
+    let delimiter = chars.next().unwrap_or('/');
+
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in chars {
+        let fields = parts.len();
+        let part = parts.last_mut().unwrap();
+        if escaped {
+            if c != delimiter {
+                part.push('\\');
+            }
+            part.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter && fields < 3 {
+            parts.push(String::new());
+        } else {
+            part.push(c);
+        }
+    }
+    if escaped {
+        parts.last_mut().unwrap().push('\\');
+    }
+
+    let mut parts = parts.into_iter();
+    let pattern = parts.next().unwrap_or_default();
+    let replacement = parts.next().unwrap_or_default();
+    let flags = parts.next().unwrap_or_default();
+
+    let mut sub = Substitute {
+        range: range.unwrap_or(LineRange::Current),
+        pattern,
+        replacement,
+        global: false,
+        confirm: false,
+        ignore_case: false,
+    };
+    for flag in flags.trim().chars() {
+        match flag {
+            'g' => sub.global = true,
+            'c' => sub.confirm = true,
+            'i' => sub.ignore_case = true,
+            'I' => sub.ignore_case = false,
+            _ => bail!("Unknown flag: {}", flag),
+        }
+    }
+    Ok(sub)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cd() {
+        assert_eq!(
+            parse_ex_command("cd ~/Notes/work ").unwrap(),
+            ExCommand::ChangeDataDir(Some("~/Notes/work".to_string()))
+        );
+        assert_eq!(
+            parse_ex_command("cd").unwrap(),
+            ExCommand::ChangeDataDir(None)
+        );
+        assert_eq!(parse_ex_command(" pwd").unwrap(), ExCommand::PrintDataDir);
+        assert_eq!(parse_ex_command("reindex").unwrap(), ExCommand::Reindex);
+        assert!(parse_ex_command("reindex all").is_err());
+    }
+
+    fn substitute(input: &str) -> Substitute {
+        match parse_ex_command(input).unwrap() {
+            ExCommand::Substitute(sub) => sub,
+            other => panic!("not a substitute: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_substitute() {
+        let sub = substitute("s/foo/bar/");
+        assert_eq!(sub.range, LineRange::Current);
+        assert_eq!(
+            (sub.pattern.as_str(), sub.replacement.as_str()),
+            ("foo", "bar")
+        );
+        assert!(!sub.global && !sub.confirm);
+
+        let sub = substitute("%s/a\\/b/c/gc");
+        assert_eq!(sub.range, LineRange::All);
+        assert_eq!(sub.pattern, "a/b");
+        assert!(sub.global && sub.confirm);
+
+        let sub = substitute("'<,'>s#(\\d+)#<\\1>#");
+        assert_eq!(sub.range, LineRange::Selection);
+        assert_eq!(sub.pattern, "(\\d+)");
+        assert_eq!(sub.replacement, "<\\1>");
+
+        assert_eq!(substitute("3,1s/x/y").range, LineRange::Lines(1, 3));
+        assert_eq!(substitute("s/x").replacement, "");
+
+        let ExCommand::ReplaceAll(sub) = parse_ex_command("replace-all /todo/done/gi").unwrap()
+        else {
+            panic!("not a replace-all");
+        };
+        assert_eq!(
+            (sub.pattern.as_str(), sub.replacement.as_str()),
+            ("todo", "done")
+        );
+        assert!(sub.global && sub.ignore_case);
+        assert!(parse_ex_command("replace-all").is_err());
+    }
+
+    #[test]
+    fn test_parse_substitute_errors() {
+        assert!(parse_ex_command("s/x/y/q").is_err());
+        assert!(parse_ex_command("0s/x/y/").is_err());
+        assert!(parse_ex_command("%cd /tmp").is_err());
+        assert!(parse_ex_command("set").is_err());
+    }
+
+    #[test]
+    fn test_parse_set_wrap() {
+        assert_eq!(
+            parse_ex_command("set nowrap").unwrap(),
+            ExCommand::SetWrap(false)
+        );
+        assert_eq!(
+            parse_ex_command("se wrap").unwrap(),
+            ExCommand::SetWrap(true)
+        );
+        assert_eq!(
+            parse_ex_command("set noignorediacritics").unwrap(),
+            ExCommand::SetIgnoreDiacritics(false)
+        );
+        assert_eq!(
+            parse_ex_command("set nospell").unwrap(),
+            ExCommand::SetSpell(false)
+        );
+        assert_eq!(
+            parse_ex_command("set spelllang=de_DE").unwrap(),
+            ExCommand::SetSpellLang("de_DE".to_string())
+        );
+        assert!(parse_ex_command("set spelllang=").is_err());
+        assert!(parse_ex_command("set number").is_err());
+    }
+
+    #[test]
+    fn test_parse_buffer_commands() {
+        assert_eq!(
+            parse_ex_command("bn").unwrap(),
+            ExCommand::CycleBuffer(true)
+        );
+        assert_eq!(
+            parse_ex_command("bprevious").unwrap(),
+            ExCommand::CycleBuffer(false)
+        );
+        assert_eq!(parse_ex_command("ls").unwrap(), ExCommand::ListBuffers);
+        assert_eq!(parse_ex_command("backlinks").unwrap(), ExCommand::Backlinks);
+        assert_eq!(parse_ex_command("history").unwrap(), ExCommand::History);
+        assert_eq!(parse_ex_command("tags").unwrap(), ExCommand::Tags);
+        assert_eq!(
+            parse_ex_command("tag #work").unwrap(),
+            ExCommand::AddTag("work".to_string())
+        );
+        assert_eq!(parse_ex_command("review").unwrap(), ExCommand::Review);
+        assert_eq!(
+            parse_ex_command("syncreminders").unwrap(),
+            ExCommand::SyncReminders
+        );
+        assert_eq!(parse_ex_command("agenda").unwrap(), ExCommand::Agenda);
+        assert_eq!(parse_ex_command("rename").unwrap(), ExCommand::Rename(None));
+        assert_eq!(
+            parse_ex_command("rename  Q3 plan").unwrap(),
+            ExCommand::Rename(Some("Q3 plan".to_string()))
+        );
+        assert_eq!(
+            parse_ex_command("sync-title").unwrap(),
+            ExCommand::SyncTitle
+        );
+        assert_eq!(parse_ex_command("sync").unwrap(), ExCommand::GitSync);
+        assert_eq!(
+            parse_ex_command("import ~/Obsidian Vault").unwrap(),
+            ExCommand::Import("~/Obsidian Vault".to_string())
+        );
+        assert!(parse_ex_command("import").is_err());
+        assert_eq!(parse_ex_command("today").unwrap(), ExCommand::Today);
+        assert_eq!(
+            parse_ex_command("new").unwrap(),
+            ExCommand::New {
+                template: None,
+                title: None
+            }
+        );
+        assert_eq!(
+            parse_ex_command("new meeting  Weekly sync").unwrap(),
+            ExCommand::New {
+                template: Some("meeting".to_string()),
+                title: Some("Weekly sync".to_string())
+            }
+        );
+        assert_eq!(
+            parse_ex_command("export html  ~/Desktop/plan.html").unwrap(),
+            ExCommand::Export {
+                format: Some(ExportFormat::Html),
+                path: Some("~/Desktop/plan.html".to_string())
+            }
+        );
+        assert_eq!(
+            parse_ex_command("export").unwrap(),
+            ExCommand::Export {
+                format: None,
+                path: None
+            }
+        );
+        assert!(parse_ex_command("export docx").is_err());
+        assert_eq!(
+            parse_ex_command("lint-blocks").unwrap(),
+            ExCommand::LintBlocks
+        );
+        assert_eq!(
+            parse_ex_command("split-headings").unwrap(),
+            ExCommand::SplitHeadings { links: true }
+        );
+        assert_eq!(
+            parse_ex_command("split-headings!").unwrap(),
+            ExCommand::SplitHeadings { links: false }
+        );
+        assert_eq!(parse_ex_command("copen").unwrap(), ExCommand::OpenQuickfix);
+        assert_eq!(parse_ex_command("trash").unwrap(), ExCommand::Trash);
+        assert_eq!(
+            parse_ex_command("empty-trash").unwrap(),
+            ExCommand::EmptyTrash
+        );
+        assert_eq!(parse_ex_command("ccl").unwrap(), ExCommand::CloseQuickfix);
+        assert!(parse_ex_command("tag").is_err());
+        assert!(parse_ex_command("bnext 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_window_commands() {
+        assert_eq!(
+            parse_ex_command("vsplit").unwrap(),
+            ExCommand::Split {
+                dir: SplitDir::Vertical,
+                title: None,
+                read_only: false,
+            }
+        );
+        assert_eq!(
+            parse_ex_command("sp").unwrap(),
+            ExCommand::Split {
+                dir: SplitDir::Horizontal,
+                title: None,
+                read_only: false,
+            }
+        );
+        assert_eq!(
+            parse_ex_command("vsview Meeting notes").unwrap(),
+            ExCommand::Split {
+                dir: SplitDir::Vertical,
+                title: Some("Meeting notes".to_string()),
+                read_only: true,
+            }
+        );
+        assert_eq!(
+            parse_ex_command("sv").unwrap(),
+            ExCommand::Split {
+                dir: SplitDir::Horizontal,
+                title: None,
+                read_only: true,
+            }
+        );
+        assert_eq!(parse_ex_command("clo").unwrap(), ExCommand::ClosePane);
+        assert_eq!(parse_ex_command("only").unwrap(), ExCommand::OnlyPane);
+    }
+
+    #[test]
+    fn test_parse_unknown() {
+        assert!(parse_ex_command("").is_err());
+        assert!(parse_ex_command("frobnicate").is_err());
+        assert!(parse_ex_command("pwd extra").is_err());
+    }
+}
@@ -0,0 +1,326 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use super::outline::outline;
+use super::render_cache::SearchMatch;
+use super::visual_mode::RenderSelection;
+
+/// Rows `start..=end` of a foldable region: a heading's section, up to the
+/// next heading of the same or a higher level, a fenced code block or the
+/// frontmatter.
+/// The start row stays visible as the fold's summary line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldRegion {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FoldRegion {
+    pub fn contains(&self, row: usize) -> bool {
+        (self.start..=self.end).contains(&row)
+    }
+
+    /// Rows hidden when the region is folded.
+    pub fn hidden(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Foldable regions of `lines`, ordered by start row (outer before inner
+/// at the same row). Single-line regions are left out.
+pub fn fold_regions(lines: &[&str]) -> Vec<FoldRegion> {
+    let headings = outline(lines.iter().copied());
+    let last = lines.len().saturating_sub(1);
+    let mut regions: Vec<FoldRegion> = headings
+        .iter()
+        .enumerate()
+        .map(|(i, heading)| {
+            let end = headings[i + 1..]
+                .iter()
+                .find(|next| next.level <= heading.level)
+                .map_or(last, |next| next.row - 1);
+            FoldRegion {
+                start: heading.row,
+                end,
+            }
+        })
+        .collect();
+
+    let mut fence: Option<(usize, &str)> = None;
+    for (row, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        match fence {
+            Some((start, marker)) if trimmed.starts_with(marker) => {
+                regions.push(FoldRegion { start, end: row });
+                fence = None;
+            }
+            Some(_) => {}
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                fence = Some((row, &trimmed[..3]));
+            }
+            None => {}
+        }
+    }
+
+    if let Some(end) = frontmatter_end(lines) {
+        regions.push(FoldRegion { start: 0, end });
+    }
+
+    regions.retain(|r| r.end > r.start);
+    regions.sort_by_key(|r| (r.start, std::cmp::Reverse(r.end)));
+    regions
+}
+
+/// Row of the fence closing the frontmatter that opens `lines`.
+pub fn frontmatter_end(lines: &[&str]) -> Option<usize> {
+    let close: &[&str] = match lines.first()?.trim_end() {
+        "---" => &["---", "..."],
+        "+++" => &["+++"],
+        _ => return None,
+    };
+    lines
+        .iter()
+        .skip(1)
+        .position(|line| close.contains(&line.trim()))
+        .map(|i| i + 1)
+}
+
+/// Closed folds of one note, by the start row of their region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Folds {
+    closed: BTreeSet<usize>,
+}
+
+impl Folds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closed.is_empty()
+    }
+
+    /// The closed regions that are not inside another closed region, i.e.
+    /// the ones that decide what is hidden.
+    pub fn closed_regions(&self, regions: &[FoldRegion]) -> Vec<FoldRegion> {
+        let mut outer: Vec<FoldRegion> = Vec::new();
+        for region in regions {
+            if !self.closed.contains(&region.start)
+                || outer.last().is_some_and(|o| o.contains(region.start))
+            {
+                continue;
+            }
+            // Of two regions starting on a row the outer one comes first
+            outer.push(*region);
+        }
+        outer
+    }
+
+    /// `za`: open the closed fold holding `row`, else close the innermost
+    /// region holding it. Returns the region closed, if any.
+    pub fn toggle(&mut self, regions: &[FoldRegion], row: usize) -> Option<FoldRegion> {
+        if let Some(open) = self
+            .closed_regions(regions)
+            .into_iter()
+            .find(|r| r.contains(row))
+        {
+            self.closed.remove(&open.start);
+            return None;
+        }
+        let inner = regions.iter().rev().find(|r| r.contains(row))?;
+        self.closed.insert(inner.start);
+        Some(*inner)
+    }
+
+    /// `zM`: close every region.
+    pub fn close_all(&mut self, regions: &[FoldRegion]) {
+        self.closed = regions.iter().map(|r| r.start).collect();
+    }
+
+    /// `zR`: open every fold.
+    pub fn open_all(&mut self) {
+        self.closed.clear();
+    }
+
+    /// Open every fold hiding `row`, e.g. after a search lands inside one.
+    pub fn reveal(&mut self, regions: &[FoldRegion], row: usize) {
+        for region in regions.iter().filter(|r| r.contains(row) && r.start != row) {
+            self.closed.remove(&region.start);
+        }
+    }
+
+    /// Forget folds whose region went away after an edit.
+    pub fn retain_valid(&mut self, regions: &[FoldRegion]) {
+        self.closed
+            .retain(|start| regions.iter().any(|r| r.start == *start));
+    }
+}
+
+/// The lines the editor shows with folds closed: each closed region is its
+/// start line followed by a count of the hidden rows.
+#[derive(Debug)]
+pub struct FoldedLines<'a> {
+    pub lines: Vec<Cow<'a, str>>,
+    /// Buffer row of each shown line.
+    rows: Vec<usize>,
+    /// Indexes into `lines` of fold summaries.
+    summaries: Vec<usize>,
+}
+
+impl<'a> FoldedLines<'a> {
+    /// `closed` as returned by `Folds::closed_regions`.
+    pub fn new(lines: &[&'a str], closed: &[FoldRegion]) -> Self {
+        let mut shown = Vec::with_capacity(lines.len());
+        let mut rows = Vec::with_capacity(lines.len());
+        let mut summaries = Vec::new();
+        let mut closed = closed.iter().peekable();
+        let mut row = 0;
+        while row < lines.len() {
+            match closed.next_if(|r| r.start == row) {
+                Some(region) => {
+                    let hidden = region.hidden();
+                    let noun = if hidden == 1 { "line" } else { "lines" };
+                    summaries.push(shown.len());
+                    shown.push(Cow::Owned(format!(
+                        "{} ··· {} {}",
+                        lines[row], hidden, noun
+                    )));
+                    rows.push(row);
+                    row = region.end + 1;
+                }
+                None => {
+                    shown.push(Cow::Borrowed(lines[row]));
+                    rows.push(row);
+                    row += 1;
+                }
+            }
+        }
+        Self {
+            lines: shown,
+            rows,
+            summaries,
+        }
+    }
+
+    pub fn as_strs(&self) -> Vec<&str> {
+        self.lines.iter().map(|line| line.as_ref()).collect()
+    }
+
+    /// Shown line holding buffer row `row`; a hidden row maps to its fold.
+    pub fn display_row(&self, row: usize) -> usize {
+        self.rows.partition_point(|&r| r <= row).saturating_sub(1)
+    }
+
+    /// Buffer row of shown line `line`.
+    pub fn buffer_row(&self, line: usize) -> usize {
+        self.rows
+            .get(line)
+            .or(self.rows.last())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether buffer row `row` is shown, as itself or as a fold summary.
+    pub fn is_shown(&self, row: usize) -> bool {
+        self.rows.binary_search(&row).is_ok()
+    }
+
+    pub fn summaries(&self) -> &[usize] {
+        &self.summaries
+    }
+
+    /// `matches` on shown lines, moved to their shown line.
+    pub fn matches(&self, matches: &[SearchMatch]) -> Vec<SearchMatch> {
+        matches
+            .iter()
+            .filter(|(row, _, _)| self.is_shown(*row))
+            .map(|&(row, col, len)| (self.display_row(row), col, len))
+            .collect()
+    }
+
+    /// `selection` with its rows moved to the shown lines holding them.
+    pub fn selection(&self, selection: RenderSelection) -> RenderSelection {
+        let row = |r| self.display_row(r);
+        match selection {
+            RenderSelection::CharacterRange { start, end } => RenderSelection::CharacterRange {
+                start: (row(start.0), start.1),
+                end: (row(end.0), end.1),
+            },
+            RenderSelection::LineRange { start_row, end_row } => RenderSelection::LineRange {
+                start_row: row(start_row),
+                end_row: row(end_row),
+            },
+            RenderSelection::BlockRegion {
+                top_row,
+                bottom_row,
+                left_col,
+                right_col,
+            } => RenderSelection::BlockRegion {
+                top_row: row(top_row),
+                bottom_row: row(bottom_row),
+                left_col,
+                right_col,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: [&str; 9] = ["# A", "a", "```", "# code", "```", "## B", "b", "# C", "c"];
+
+    fn region(start: usize, end: usize) -> FoldRegion {
+        FoldRegion { start, end }
+    }
+
+    #[test]
+    fn test_regions_of_headings_and_fences() {
+        assert_eq!(
+            fold_regions(&NOTE),
+            [region(0, 6), region(2, 4), region(5, 6), region(7, 8)]
+        );
+        let note = ["+++", "a = 1", "+++", "# A", "a"];
+        assert_eq!(frontmatter_end(&note), Some(2));
+        assert_eq!(fold_regions(&note), [region(0, 2), region(3, 4)]);
+        assert!(frontmatter_end(&["---", "a: 1"]).is_none());
+    }
+
+    #[test]
+    fn test_toggle_and_fold_view() {
+        let regions = fold_regions(&NOTE);
+        let mut folds = Folds::new();
+        // The innermost region closes first
+        assert_eq!(folds.toggle(&regions, 6), Some(region(5, 6)));
+        assert_eq!(folds.toggle(&regions, 1), Some(region(0, 6)));
+        assert_eq!(folds.closed_regions(&regions), [region(0, 6)]);
+
+        let view = FoldedLines::new(&NOTE, &folds.closed_regions(&regions));
+        assert_eq!(view.as_strs(), ["# A ··· 6 lines", "# C", "c"]);
+        assert_eq!(view.display_row(4), 0);
+        assert_eq!(view.display_row(8), 2);
+        assert_eq!(view.buffer_row(1), 7);
+        assert!(!view.is_shown(3));
+        assert_eq!(view.summaries(), [0]);
+
+        // Opening the outer fold shows the inner one still closed
+        assert_eq!(folds.toggle(&regions, 0), None);
+        let view = FoldedLines::new(&NOTE, &folds.closed_regions(&regions));
+        assert_eq!(view.lines.len(), 8);
+        assert_eq!(view.lines[5], "## B ··· 1 line");
+        assert_eq!(
+            view.matches(&[(0, 0, 1), (6, 0, 1), (7, 0, 1)]),
+            [(0, 0, 1), (6, 0, 1)]
+        );
+
+        folds.close_all(&regions);
+        assert_eq!(folds.closed_regions(&regions), [region(0, 6), region(7, 8)]);
+        folds.reveal(&regions, 3);
+        assert_eq!(folds.closed_regions(&regions), [region(5, 6), region(7, 8)]);
+        folds.retain_valid(&[region(7, 8)]);
+        assert_eq!(folds.closed_regions(&regions), [region(7, 8)]);
+        folds.open_all();
+        assert!(folds.is_empty());
+    }
+}
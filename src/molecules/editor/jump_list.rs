@@ -0,0 +1,110 @@
+/// Jumps kept per note; older ones are dropped.
+const MAX_JUMPS: usize = 100;
+
+/// Cursor positions before jumps (search, `G`, `gg`, mark jumps), walked
+/// with Ctrl-O / Ctrl-I. Positions belong to one note; the list starts over
+/// when another note is edited.
+#[derive(Debug, Default)]
+pub struct JumpList {
+    note_id: String,
+    entries: Vec<(usize, usize)>,
+    /// Position in `entries`; equal to its length when not walking the list.
+    index: usize,
+}
+
+impl JumpList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the jumps of another note.
+    pub fn switch_note(&mut self, note_id: &str) {
+        if self.note_id != note_id {
+            self.note_id = note_id.to_string();
+            self.entries.clear();
+            self.index = 0;
+        }
+    }
+
+    /// Record `pos` as the place a jump starts from. Newer entries left
+    /// after walking back are dropped, and so is an older entry on the same
+    /// row.
+    pub fn push(&mut self, pos: (usize, usize)) {
+        self.entries.truncate(self.index);
+        self.entries.retain(|&(row, _)| row != pos.0);
+        self.entries.push(pos);
+        if self.entries.len() > MAX_JUMPS {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len();
+    }
+
+    /// Step back to an older position (Ctrl-O). `current` is remembered so
+    /// Ctrl-I can return to it.
+    pub fn back(&mut self, current: (usize, usize)) -> Option<(usize, usize)> {
+        if self.index == 0 {
+            return None;
+        }
+        if self.index == self.entries.len() {
+            self.entries.push(current);
+        }
+        self.index -= 1;
+        Some(self.entries[self.index])
+    }
+
+    /// Step forward to a newer position (Ctrl-I).
+    pub fn forward(&mut self) -> Option<(usize, usize)> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.entries[self.index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut jumps = JumpList::new();
+        assert_eq!(jumps.back((0, 0)), None);
+        jumps.push((0, 0));
+        jumps.push((10, 2));
+
+        assert_eq!(jumps.back((20, 0)), Some((10, 2)));
+        assert_eq!(jumps.back((10, 2)), Some((0, 0)));
+        assert_eq!(jumps.back((0, 0)), None);
+        assert_eq!(jumps.forward(), Some((10, 2)));
+        assert_eq!(jumps.forward(), Some((20, 0)));
+        assert_eq!(jumps.forward(), None);
+    }
+
+    #[test]
+    fn test_push_drops_newer_and_same_row() {
+        let mut jumps = JumpList::new();
+        jumps.push((1, 0));
+        jumps.push((5, 0));
+        jumps.push((9, 0));
+        assert_eq!(jumps.back((12, 0)), Some((9, 0)));
+        assert_eq!(jumps.back((9, 0)), Some((5, 0)));
+
+        // Jumping from (5, 3) forgets (9, 0), (12, 0) and the old (5, 0)
+        jumps.push((5, 3));
+        assert_eq!(jumps.forward(), None);
+        assert_eq!(jumps.back((30, 0)), Some((5, 3)));
+        assert_eq!(jumps.back((5, 3)), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_switch_note_clears() {
+        let mut jumps = JumpList::new();
+        jumps.switch_note("a");
+        jumps.push((4, 0));
+        jumps.switch_note("a");
+        assert_eq!(jumps.back((0, 0)), Some((4, 0)));
+        jumps.switch_note("b");
+        assert_eq!(jumps.back((0, 0)), None);
+    }
+}
@@ -0,0 +1,257 @@
+use std::ops::{Index, IndexMut, Range};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Lines per chunk when a rope is built; a chunk is split once it grows to
+/// twice this.
+const CHUNK_LEN: usize = 256;
+
+/// Source of content stamps, unique for the whole process.
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(1);
+
+/// The lines of a `TextBuffer`, stored as shared chunks.
+///
+/// Cloning only copies the chunk pointers, so undo snapshots are cheap. A
+/// chunk is copied the first time it is edited while a snapshot still
+/// shares it, which keeps the cost of an edit proportional to the chunk
+/// rather than to the whole note.
+#[derive(Debug, Clone, Default)]
+pub struct LineRope {
+    chunks: Vec<Rc<Vec<String>>>,
+    /// Index of the first line of each chunk.
+    starts: Vec<usize>,
+    len: usize,
+    /// Renewed on every edit; ropes with the same stamp hold the same lines.
+    stamp: u64,
+}
+
+impl LineRope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Identifies the current lines: it changes with every edit, and a
+    /// clone keeps it only until either copy is edited. Lets renderers
+    /// reuse work done for the same text.
+    pub fn stamp(&self) -> u64 {
+        self.stamp
+    }
+
+    fn touch(&mut self) {
+        self.stamp = NEXT_STAMP.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Chunk holding line `index` and the offset within it.
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let chunk = self.starts.partition_point(|&start| start <= index) - 1;
+        (chunk, index - self.starts[chunk])
+    }
+
+    pub fn get(&self, index: usize) -> Option<&String> {
+        if index >= self.len {
+            return None;
+        }
+        let (chunk, offset) = self.locate(index);
+        self.chunks[chunk].get(offset)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut String> {
+        if index >= self.len {
+            return None;
+        }
+        self.touch();
+        let (chunk, offset) = self.locate(index);
+        Rc::make_mut(&mut self.chunks[chunk]).get_mut(offset)
+    }
+
+    pub fn push(&mut self, line: String) {
+        self.touch();
+        match self.chunks.last_mut() {
+            Some(last) if last.len() < CHUNK_LEN * 2 => Rc::make_mut(last).push(line),
+            _ => {
+                self.starts.push(self.len);
+                self.chunks.push(Rc::new(vec![line]));
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Insert `line` before line `index` (or at the end when `index` is the
+    /// length).
+    pub fn insert(&mut self, index: usize, line: String) {
+        assert!(index <= self.len, "insert index {} out of range", index);
+        if index == self.len {
+            self.push(line);
+            return;
+        }
+        self.touch();
+        let (chunk, offset) = self.locate(index);
+        let lines = Rc::make_mut(&mut self.chunks[chunk]);
+        lines.insert(offset, line);
+        if lines.len() >= CHUNK_LEN * 2 {
+            let tail = lines.split_off(CHUNK_LEN);
+            self.chunks.insert(chunk + 1, Rc::new(tail));
+            self.starts.insert(chunk + 1, 0);
+        }
+        self.len += 1;
+        self.reindex(chunk);
+    }
+
+    pub fn remove(&mut self, index: usize) -> String {
+        assert!(index < self.len, "remove index {} out of range", index);
+        self.touch();
+        let (chunk, offset) = self.locate(index);
+        let lines = Rc::make_mut(&mut self.chunks[chunk]);
+        let line = lines.remove(offset);
+        if lines.is_empty() {
+            self.chunks.remove(chunk);
+            self.starts.remove(chunk);
+        }
+        self.len -= 1;
+        self.reindex(chunk);
+        line
+    }
+
+    pub fn remove_range(&mut self, range: Range<usize>) {
+        for _ in range.clone() {
+            self.remove(range.start);
+        }
+    }
+
+    /// Recompute chunk starts from chunk `from` on.
+    fn reindex(&mut self, from: usize) {
+        let mut start = match from.checked_sub(1) {
+            Some(prev) => self.starts[prev] + self.chunks[prev].len(),
+            None => 0,
+        };
+        for (chunk, lines) in self.chunks.iter().enumerate().skip(from) {
+            self.starts[chunk] = start;
+            start += lines.len();
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.chunks.iter().flat_map(|chunk| chunk.iter())
+    }
+
+    pub fn join(&self, separator: &str) -> String {
+        let mut out = String::new();
+        for (i, line) in self.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            out.push_str(line);
+        }
+        out
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl From<Vec<String>> for LineRope {
+    fn from(lines: Vec<String>) -> Self {
+        let mut rope = LineRope::new();
+        rope.touch();
+        let mut lines = lines.into_iter().peekable();
+        while lines.peek().is_some() {
+            let chunk: Vec<String> = lines.by_ref().take(CHUNK_LEN).collect();
+            rope.starts.push(rope.len);
+            rope.len += chunk.len();
+            rope.chunks.push(Rc::new(chunk));
+        }
+        rope
+    }
+}
+
+impl Index<usize> for LineRope {
+    type Output = String;
+
+    fn index(&self, index: usize) -> &String {
+        self.get(index)
+            .unwrap_or_else(|| panic!("line {} out of range ({} lines)", index, self.len))
+    }
+}
+
+impl IndexMut<usize> for LineRope {
+    fn index_mut(&mut self, index: usize) -> &mut String {
+        let len = self.len;
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("line {} out of range ({} lines)", index, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(count: usize) -> Vec<String> {
+        (0..count).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_edits_match_vec() {
+        let mut expected = numbered(CHUNK_LEN * 3 + 7);
+        let mut rope = LineRope::from(expected.clone());
+
+        for i in 0..CHUNK_LEN * 2 {
+            let at = (i * 37) % (expected.len() + 1);
+            expected.insert(at, format!("new {}", i));
+            rope.insert(at, format!("new {}", i));
+        }
+        for i in 0..CHUNK_LEN * 4 {
+            let at = (i * 53) % expected.len();
+            assert_eq!(rope.remove(at), expected.remove(at));
+        }
+        rope.remove_range(3..10);
+        expected.drain(3..10);
+        rope[1].push('!');
+        expected[1].push('!');
+
+        assert_eq!(rope.len(), expected.len());
+        assert_eq!(rope.to_vec(), expected);
+        assert_eq!(rope.iter().next_back(), expected.last());
+        assert_eq!(rope.get(expected.len()), None);
+    }
+
+    #[test]
+    fn test_clone_is_copy_on_write() {
+        let mut rope = LineRope::from(numbered(CHUNK_LEN * 2));
+        let snapshot = rope.clone();
+        rope[0] = "edited".to_string();
+
+        assert_eq!(snapshot[0], "0");
+        assert_eq!(rope[0], "edited");
+        assert!(!Rc::ptr_eq(&rope.chunks[0], &snapshot.chunks[0]));
+        // The untouched chunk is still shared
+        assert!(Rc::ptr_eq(&rope.chunks[1], &snapshot.chunks[1]));
+    }
+
+    #[test]
+    fn test_stamp_changes_with_edits() {
+        let mut rope = LineRope::from(numbered(3));
+        let snapshot = rope.clone();
+        assert_eq!(rope.stamp(), snapshot.stamp());
+        assert_ne!(rope.stamp(), LineRope::from(numbered(3)).stamp());
+
+        rope.push("3".to_string());
+        let pushed = rope.stamp();
+        assert_ne!(pushed, snapshot.stamp());
+        rope[0].push('!');
+        assert_ne!(rope.stamp(), pushed);
+        // Going back to the snapshot brings its stamp back
+        let mut restored = snapshot.clone();
+        assert_eq!(restored.stamp(), snapshot.stamp());
+        restored.remove(0);
+        assert_ne!(restored.stamp(), pushed);
+    }
+}
@@ -150,6 +150,12 @@ pub fn has_checkbox_prefix(line: &str) -> bool {
         || trimmed == "- [X]"
 }
 
+/// Check if a line is an unchecked checkbox (`- [ ] `), i.e. an open task.
+pub fn is_open_checkbox(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("- [ ] ") || trimmed == "- [ ]"
+}
+
 /// Toggle a checkbox between checked and unchecked.
 ///
 /// - `- [ ] ` → `- [x] ` (check)
@@ -288,6 +294,16 @@ mod tests {
         assert!(!has_checkbox_prefix("plain text"));
     }
 
+    // ── is_open_checkbox ────────────────────────────────────────────
+
+    #[test]
+    fn test_is_open_checkbox() {
+        assert!(is_open_checkbox("- [ ] todo"));
+        assert!(is_open_checkbox("    - [ ]"));
+        assert!(!is_open_checkbox("- [x] done"));
+        assert!(!is_open_checkbox("- item"));
+    }
+
     // ── insert_checkbox_prefix ──────────────────────────────────────
 
     #[test]
@@ -581,7 +581,7 @@ mod tests {
             "```".to_string(),
             "world".to_string(),
         ];
-        let (new_lines, row, col) = toggle_code_block(&lines, 2);
+        let (new_lines, row, _col) = toggle_code_block(&lines, 2);
         assert_eq!(new_lines.len(), 3);
         assert_eq!(new_lines[0], "hello");
         assert_eq!(new_lines[1], "code");
@@ -618,7 +618,7 @@ mod tests {
             "```".to_string(),
             "after".to_string(),
         ];
-        let (new_lines, row, col) = toggle_code_block_visual(&lines, 0, 3);
+        let (new_lines, _row, _col) = toggle_code_block_visual(&lines, 0, 3);
         assert_eq!(new_lines.len(), 3);
         assert_eq!(new_lines[0], "code1");
         assert_eq!(new_lines[1], "code2");
@@ -0,0 +1,59 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::molecules::list::FuzzyQuery;
+
+/// The `@mention` being typed before grapheme column `col` of `line`: the
+/// column of its `@` and the text typed after it. The `@` starts the line
+/// or follows whitespace or `(`, so `me@example.com` is not a mention.
+pub fn mention_at(line: &str, col: usize) -> Option<(usize, String)> {
+    let graphemes: Vec<&str> = line.graphemes(true).take(col).collect();
+    let at = graphemes.iter().rposition(|g| *g == "@")?;
+    let typed = graphemes[at + 1..].concat();
+    let starts_word = at == 0 || graphemes[at - 1] == "(" || graphemes[at - 1].trim().is_empty();
+    (starts_word && !typed.contains(char::is_whitespace)).then_some((at, typed))
+}
+
+/// Up to `limit` of `names` for the typed text of a mention: first those
+/// with a word starting with it, then those containing it. A name typed in
+/// full is left out.
+pub fn mention_matches<'a>(names: &'a [String], typed: &str, limit: usize) -> Vec<&'a str> {
+    let query = FuzzyQuery::new(typed);
+    let (mut matches, containing): (Vec<&str>, Vec<&str>) = names
+        .iter()
+        .map(String::as_str)
+        .filter(|name| *name != typed && query.is_substring_of(name))
+        .partition(|name| name.split_whitespace().any(|word| query.is_prefix_of(word)));
+    matches.extend(containing);
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mention_at() {
+        assert_eq!(mention_at("ask @Jo", 7), Some((4, "Jo".to_string())));
+        assert_eq!(mention_at("@", 1), Some((0, String::new())));
+        assert_eq!(mention_at("(@ren", 5), Some((1, "ren".to_string())));
+        assert!(mention_at("me@example.com", 14).is_none());
+        assert!(mention_at("@Jo Smith", 9).is_none());
+        assert!(mention_at("ask @Jo", 3).is_none());
+    }
+
+    #[test]
+    fn test_mention_matches() {
+        let names: Vec<String> = ["Anna Smith", "Jo Ann", "Joanne Réa", "Rea"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(
+            mention_matches(&names, "ann", 8),
+            ["Anna Smith", "Jo Ann", "Joanne Réa"]
+        );
+        assert_eq!(mention_matches(&names, "rea", 8), ["Joanne Réa", "Rea"]);
+        assert_eq!(mention_matches(&names, "Rea", 8), ["Joanne Réa"]);
+        assert_eq!(mention_matches(&names, "", 2), ["Anna Smith", "Jo Ann"]);
+    }
+}
@@ -0,0 +1,219 @@
+/// Outcome of a three-way merge: the merged text and how many overlapping
+/// hunks were written out with conflict markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    pub text: String,
+    pub conflicts: usize,
+}
+
+pub const CONFLICT_START: &str = "<<<<<<< yours";
+pub const CONFLICT_SEPARATOR: &str = "=======";
+pub const CONFLICT_END: &str = ">>>>>>> disk";
+
+/// Line-based three-way merge of `ours` (the buffer) and `theirs` (the file
+/// on disk), both derived from `base` (the last saved version). Hunks changed
+/// on only one side are taken from that side; hunks changed differently on
+/// both sides are kept with `<<<<<<<`/`=======`/`>>>>>>>` markers.
+pub fn merge_three_way(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base: Vec<&str> = base.split('\n').collect();
+    let ours: Vec<&str> = ours.split('\n').collect();
+    let theirs: Vec<&str> = theirs.split('\n').collect();
+
+    let to_ours = match_base(&base, &ours);
+    let to_theirs = match_base(&base, &theirs);
+
+    let mut out: Vec<&str> = Vec::new();
+    let mut conflicts = 0;
+    let (mut i, mut j, mut k) = (0, 0, 0);
+
+    loop {
+        // Lines unchanged on both sides
+        while i < base.len() && to_ours[i] == Some(j) && to_theirs[i] == Some(k) {
+            out.push(base[i]);
+            i += 1;
+            j += 1;
+            k += 1;
+        }
+        if i == base.len() && j == ours.len() && k == theirs.len() {
+            break;
+        }
+
+        // The changed hunk runs up to the next base line both sides kept
+        let next = (i..base.len()).find(|&n| to_ours[n].is_some() && to_theirs[n].is_some());
+        let (bi, oj, tk) = match next {
+            Some(n) => (n, to_ours[n].unwrap_or(j), to_theirs[n].unwrap_or(k)),
+            None => (base.len(), ours.len(), theirs.len()),
+        };
+        let (b, o, t) = (&base[i..bi], &ours[j..oj], &theirs[k..tk]);
+
+        if o == b || o == t {
+            out.extend_from_slice(t);
+        } else if t == b {
+            out.extend_from_slice(o);
+        } else {
+            conflicts += 1;
+            out.push(CONFLICT_START);
+            out.extend_from_slice(o);
+            out.push(CONFLICT_SEPARATOR);
+            out.extend_from_slice(t);
+            out.push(CONFLICT_END);
+        }
+        (i, j, k) = (bi, oj, tk);
+    }
+
+    MergeResult {
+        text: out.join("\n"),
+        conflicts,
+    }
+}
+
+/// Where line `row` of `before` ended up in `after`: its own row when the
+/// line was kept, else the row after the nearest kept line above it.
+pub fn follow_line(before: &str, after: &str, row: usize) -> usize {
+    let before: Vec<&str> = before.split('\n').collect();
+    let after: Vec<&str> = after.split('\n').collect();
+    let matches = match_base(&before, &after);
+    let row = row.min(before.len() - 1);
+    let followed = match matches[row] {
+        Some(to) => to,
+        None => matches[..row]
+            .iter()
+            .rev()
+            .find_map(|m| *m)
+            .map_or(0, |to| to + 1),
+    };
+    followed.min(after.len() - 1)
+}
+
+/// For each line of `base`, the index of the line it matches in `other`.
+fn match_base(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+
+    // Most edits touch a small region; diff only the part between the
+    // common prefix and suffix.
+    let prefix = base.iter().zip(other).take_while(|(a, b)| a == b).count();
+    let suffix = base[prefix..]
+        .iter()
+        .rev()
+        .zip(other[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    for (n, m) in matches.iter_mut().enumerate().take(prefix) {
+        *m = Some(n);
+    }
+    for n in 0..suffix {
+        matches[base.len() - 1 - n] = Some(other.len() - 1 - n);
+    }
+    let base_mid = &base[prefix..base.len() - suffix];
+    let other_mid = &other[prefix..other.len() - suffix];
+    for (a, b) in myers_pairs(base_mid, other_mid) {
+        matches[prefix + a] = Some(prefix + b);
+    }
+    matches
+}
+
+/// Equal line pairs on a shortest edit script between `a` and `b` (Myers).
+fn myers_pairs(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let offset = n + m + 1;
+    let at = |k: isize| (k + offset) as usize;
+    let mut v = vec![0isize; (2 * offset + 1) as usize];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=(n + m) {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[at(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    pairs.reverse();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_overlapping_edits_merge() {
+        let base = "# Title\none\ntwo\nthree\nfour";
+        let ours = "# Title\none\ntwo (mine)\nthree\nfour";
+        let theirs = "# Title\none\ntwo\nthree\nfour\nfive";
+        let merged = merge_three_way(base, ours, theirs);
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "# Title\none\ntwo (mine)\nthree\nfour\nfive");
+    }
+
+    #[test]
+    fn test_identical_edits_merge() {
+        let merged = merge_three_way("a\nb\nc", "a\nB\nc", "a\nB\nc");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "a\nB\nc");
+
+        let merged = merge_three_way("a\nb\nc", "a\nc", "x\na\nb\nc");
+        assert_eq!(merged.text, "x\na\nc");
+    }
+
+    #[test]
+    fn test_overlapping_edits_conflict() {
+        let merged = merge_three_way("a\nb\nc", "a\nmine\nc", "a\ntheirs\nc");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< yours\nmine\n=======\ntheirs\n>>>>>>> disk\nc"
+        );
+    }
+
+    #[test]
+    fn test_follow_line() {
+        let before = "# Title\none\ntwo\nthree";
+        assert_eq!(follow_line(before, "# Title\nnew\n\none\ntwo\nthree", 2), 4);
+        assert_eq!(follow_line(before, "# Title\none\n2\nthree", 2), 2);
+        assert_eq!(follow_line(before, "# Title", 3), 0);
+        assert_eq!(follow_line(before, "other", 0), 0);
+    }
+
+    #[test]
+    fn test_myers_pairs() {
+        let a = ["a", "b", "c", "a", "b", "b", "a"];
+        let b = ["c", "b", "a", "b", "a", "c"];
+        let pairs = myers_pairs(&a, &b);
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs.iter().all(|&(x, y)| a[x] == b[y]));
+        assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+    }
+}
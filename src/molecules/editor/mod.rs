@@ -1,11 +1,62 @@
+mod auto_link;
 mod buffer;
+mod buffer_list;
 pub mod comment;
+mod completion;
+mod dot_repeat;
+mod ex_command;
+mod folds;
+mod jump_list;
+mod line_rope;
 pub mod list_prefix;
 pub mod markdown_fmt;
+mod mention;
+mod merge;
+mod note_split;
+mod outline;
+mod panes;
+mod prompt_input;
+mod quickfix;
+mod render_cache;
+mod replace_all;
+mod search_pattern;
+mod sentence;
+mod snippet;
+mod spell;
+mod substitute;
+mod text_object;
+mod viewport;
 mod vim_mode;
 pub mod visual_mode;
+mod wiki_link;
 
+pub use auto_link::{AutoLink, AutoLinkKind, auto_links};
 pub use buffer::TextBuffer;
+pub use buffer_list::{BufferList, OpenBuffer};
+pub use completion::{
+    COMPLETION_LIMIT, CompletionKind, buffer_words, completion_at, completion_matches, word_at,
+};
+pub use dot_repeat::ChangeRecorder;
+pub use ex_command::{ExCommand, LineRange, Substitute, parse_ex_command};
+pub use folds::{FoldRegion, FoldedLines, Folds, fold_regions, frontmatter_end};
+pub use jump_list::JumpList;
+pub use line_rope::LineRope;
 pub use markdown_fmt::MarkdownFormat;
+pub use mention::{mention_at, mention_matches};
+pub use merge::{MergeResult, follow_line, merge_three_way};
+pub use note_split::{NoteSplit, split_at_headings};
+pub use outline::{OutlineEntry, outline, section_at};
+pub use panes::{EVEN_SPLIT, Pane, PaneDir, PaneLayout, PaneView, SplitDir};
+pub use prompt_input::PromptInput;
+pub use quickfix::{Location, QuickfixList};
+pub use render_cache::{RenderCache, SearchMatch};
+pub use replace_all::{ReplaceAllSession, ReplaceHunk, replace_hunks};
+pub use search_pattern::SearchPattern;
+pub use snippet::{Snippet, SnippetSession, snippet_trigger};
+pub use spell::{Dictionary, SUGGESTION_LIMIT, spelling_errors, word_spans};
+pub use substitute::{SubstituteSession, Substitution};
+pub use text_object::{TextObject, text_object_range};
+pub use viewport::{SCROLL_OFF, ViewAlign, Viewport};
 pub use vim_mode::{Motion, VimAction, VimMode};
 pub use visual_mode::{RenderSelection, VisualMode, VisualType};
+pub use wiki_link::{WikiLink, link_at, link_targets, wiki_links};
@@ -0,0 +1,120 @@
+use super::outline::{OutlineEntry, outline};
+
+/// A note cut at its headings by `:split-headings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSplit {
+    /// What stays in the note: everything above the first section, then a
+    /// `[[link]]` to each section when links were asked for.
+    pub remaining: String,
+    /// The text of each section, its heading line first.
+    pub sections: Vec<String>,
+}
+
+/// The level a note splits at: its shallowest heading level. A heading
+/// alone at that level that comes first is the note's title and stays,
+/// and the split goes one level down.
+fn split_level(entries: &[OutlineEntry]) -> Option<u8> {
+    let top = entries.iter().map(|e| e.level).min()?;
+    let tops = entries.iter().filter(|e| e.level == top).count();
+    if tops == 1 && entries[0].level == top && entries.len() > 1 {
+        return entries[1..].iter().map(|e| e.level).min();
+    }
+    Some(top)
+}
+
+/// Cut `text` at its top-level headings (see `split_level`); `title_of`
+/// names each section for its link. `None` when there is no heading to
+/// cut at.
+pub fn split_at_headings(
+    text: &str,
+    links: bool,
+    title_of: impl Fn(&str) -> String,
+) -> Option<NoteSplit> {
+    let lines: Vec<&str> = text.lines().collect();
+    let entries = outline(lines.iter().copied());
+    let level = split_level(&entries)?;
+    let starts: Vec<usize> = entries
+        .iter()
+        .filter(|e| e.level == level)
+        .map(|e| e.row)
+        .collect();
+    let first = *starts.first()?;
+    let ends = starts.iter().skip(1).copied().chain([lines.len()]);
+    let sections: Vec<String> = starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| {
+            let mut end = end;
+            while end > start + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            lines[start..end].join("\n") + "\n"
+        })
+        .collect();
+
+    let mut remaining: Vec<String> = lines[..first].iter().map(|l| l.to_string()).collect();
+    if links {
+        while remaining.last().is_some_and(|l| l.trim().is_empty()) {
+            remaining.pop();
+        }
+        if !remaining.is_empty() {
+            remaining.push(String::new());
+        }
+        remaining.extend(
+            sections
+                .iter()
+                .map(|section| format!("- [[{}]]", title_of(section))),
+        );
+    }
+    let mut remaining = remaining.join("\n");
+    if !remaining.is_empty() {
+        remaining.push('\n');
+    }
+    Some(NoteSplit {
+        remaining,
+        sections,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn title(section: &str) -> String {
+        section
+            .lines()
+            .next()
+            .unwrap()
+            .trim_matches(['#', ' '])
+            .to_string()
+    }
+
+    #[test]
+    fn test_split_at_top_level_headings() {
+        let text = "intro\n\n# One\na\n## Sub\nb\n\n# Two\n```\n# not a heading\n```\n";
+        let split = split_at_headings(text, true, title).unwrap();
+        assert_eq!(
+            split.sections,
+            [
+                "# One\na\n## Sub\nb\n",
+                "# Two\n```\n# not a heading\n```\n"
+            ]
+        );
+        assert_eq!(split.remaining, "intro\n\n- [[One]]\n- [[Two]]\n");
+        let split = split_at_headings(text, false, title).unwrap();
+        assert_eq!(split.remaining, "intro\n\n");
+    }
+
+    #[test]
+    fn test_title_heading_stays() {
+        let text = "---\ntags: [x]\n---\n# Dump\n## A\na\n## B\nb\n";
+        let split = split_at_headings(text, true, title).unwrap();
+        assert_eq!(split.sections, ["## A\na\n", "## B\nb\n"]);
+        assert_eq!(
+            split.remaining,
+            "---\ntags: [x]\n---\n# Dump\n\n- [[A]]\n- [[B]]\n"
+        );
+        assert!(split_at_headings("no headings\n", true, title).is_none());
+        assert!(split_at_headings("# Only\ntext\n", true, title).is_some());
+    }
+}
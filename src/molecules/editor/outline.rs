@@ -0,0 +1,107 @@
+/// A markdown heading of the note, for the outline sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    /// 1 for `#`, up to 6 for `######`.
+    pub level: u8,
+    pub title: String,
+    pub row: usize,
+}
+
+/// ATX headings (`# Title`) of `lines`, in order. Lines in fenced code
+/// blocks and in leading frontmatter are skipped; closing `#`s are dropped.
+pub fn outline<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<OutlineEntry> {
+    let mut entries = Vec::new();
+    let mut fence: Option<&str> = None;
+    // Fence closing the frontmatter while inside it
+    let mut frontmatter: Option<&str> = None;
+    for (row, line) in lines.into_iter().enumerate() {
+        let trimmed = line.trim_start();
+        if row == 0 && matches!(line.trim_end(), "---" | "+++") {
+            frontmatter = Some(line.trim_end());
+            continue;
+        }
+        if let Some(close) = frontmatter {
+            if line.trim_end() == close {
+                frontmatter = None;
+            }
+            continue;
+        }
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        if let Some((level, title)) = heading(line) {
+            entries.push(OutlineEntry { level, title, row });
+        }
+    }
+    entries
+}
+
+/// Index of the entry whose section contains `row`: the last heading at or
+/// above it.
+pub fn section_at(entries: &[OutlineEntry], row: usize) -> Option<usize> {
+    entries.iter().rposition(|e| e.row <= row)
+}
+
+fn heading(line: &str) -> Option<(u8, String)> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let rest = &line[hashes..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim();
+    // A closing run of `#` only counts after a space
+    let title = match title.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped.trim_end(),
+        _ => title,
+    };
+    Some((hashes as u8, title.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(text: &str) -> Vec<(u8, String, usize)> {
+        outline(text.lines())
+            .into_iter()
+            .map(|e| (e.level, e.title, e.row))
+            .collect()
+    }
+
+    #[test]
+    fn test_outline_skips_code_and_frontmatter() {
+        let text = "---\ntitle: x\n# not a heading\n---\n# One\ntext\n```\n# code\n```\n## Two ##\n#hashtag\n####### seven\n### C# notes\n~~~\n## fenced\n~~~\n#";
+        assert_eq!(
+            titles(text),
+            [
+                (1, "One".to_string(), 4),
+                (2, "Two".to_string(), 9),
+                (3, "C# notes".to_string(), 12),
+                (1, String::new(), 16),
+            ]
+        );
+        assert_eq!(
+            titles("+++\n# not = 1\n+++\n# One"),
+            [(1, "One".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn test_section_at() {
+        let entries = outline("intro\n# A\na\n## B\nb".lines());
+        assert_eq!(section_at(&entries, 0), None);
+        assert_eq!(section_at(&entries, 2), Some(0));
+        assert_eq!(section_at(&entries, 3), Some(1));
+        assert_eq!(section_at(&entries, 9), Some(1));
+    }
+}
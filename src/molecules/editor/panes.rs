@@ -0,0 +1,451 @@
+use ratatui::layout::Rect;
+
+use super::viewport::Viewport;
+
+/// How `:split` (`Horizontal`, one pane above the other) or `:vsplit`
+/// (`Vertical`, side by side) divides a pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDir {
+    Horizontal,
+    Vertical,
+}
+
+/// Share of a split given to its first (top or left) pane, in percent,
+/// when nothing else is set.
+pub const EVEN_SPLIT: u16 = 50;
+/// Limits of that share, so `Ctrl-W -` never hides a pane.
+const MIN_SPLIT: u16 = 10;
+const MAX_SPLIT: u16 = 90;
+
+/// Direction of `Ctrl-W h/j/k/l`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneDir {
+    Left,
+    Down,
+    Up,
+    Right,
+}
+
+/// A window onto a note. The focused pane's live state is `App::buffer`,
+/// `App::current_note` and `App::viewport`; the fields here hold it while
+/// the pane is not focused.
+#[derive(Debug, Default)]
+pub struct Pane {
+    pub note_id: Option<String>,
+    pub cursor: (usize, usize),
+    pub viewport: Viewport,
+    /// Opened with `:sview` / `:vsview`: edits are refused while focused.
+    pub read_only: bool,
+}
+
+/// What an unfocused pane draws.
+#[derive(Debug)]
+pub struct PaneView<'a> {
+    pub lines: Vec<&'a str>,
+    pub title: &'a str,
+    pub cursor: (usize, usize),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(usize),
+    Split {
+        dir: SplitDir,
+        /// Percent of the space that goes to `first`.
+        ratio: u16,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+/// The editor's panes as a tree of splits. Leaves index into `panes`.
+#[derive(Debug)]
+pub struct PaneLayout {
+    root: Node,
+    panes: Vec<Pane>,
+    focus: usize,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaneLayout {
+    pub fn new() -> Self {
+        Self {
+            root: Node::Leaf(0),
+            panes: vec![Pane::default()],
+            focus: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.panes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    pub fn is_split(&self) -> bool {
+        self.panes.len() > 1
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, pane: usize) {
+        if pane < self.panes.len() {
+            self.focus = pane;
+        }
+    }
+
+    pub fn pane(&self, pane: usize) -> &Pane {
+        &self.panes[pane]
+    }
+
+    pub fn pane_mut(&mut self, pane: usize) -> &mut Pane {
+        &mut self.panes[pane]
+    }
+
+    /// Split the focused pane in two and focus the new half, which comes
+    /// first (above or left) like in Vim and gets `ratio` percent of the
+    /// space. Returns the new pane.
+    pub fn split(&mut self, dir: SplitDir, pane: Pane, ratio: u16) -> usize {
+        let new = self.panes.len();
+        self.panes.push(pane);
+        let focus = self.focus;
+        let ratio = ratio.clamp(MIN_SPLIT, MAX_SPLIT);
+        replace_leaf(&mut self.root, focus, &mut |leaf| Node::Split {
+            dir,
+            ratio,
+            first: Box::new(Node::Leaf(new)),
+            second: Box::new(leaf),
+        });
+        self.focus = new;
+        new
+    }
+
+    /// Grow (positive `delta`) or shrink the focused pane by `delta` percent
+    /// along `dir`: taller or shorter for `Horizontal`, wider or narrower
+    /// for `Vertical`. Moves the innermost split of that direction holding
+    /// the focused pane, and returns its new first-pane share; `None` when
+    /// no such split exists.
+    pub fn resize(&mut self, dir: SplitDir, delta: i32) -> Option<u16> {
+        resize_split(&mut self.root, self.focus, dir, delta)
+    }
+
+    /// `Ctrl-W =`: give every split an even share.
+    pub fn equalize(&mut self) {
+        equalize(&mut self.root);
+    }
+
+    /// Remove `pane`, giving its space to its sibling. The last pane cannot
+    /// be closed. Pane numbers above `pane` shift down by one.
+    pub fn close(&mut self, pane: usize) -> bool {
+        if self.panes.len() < 2 || pane >= self.panes.len() {
+            return false;
+        }
+        self.root = remove_leaf(self.root.clone(), pane).expect("another pane remains");
+        renumber(&mut self.root, pane);
+        self.panes.remove(pane);
+        if self.focus > pane {
+            self.focus -= 1;
+        } else if self.focus == pane {
+            self.focus = self.leaves()[0];
+        }
+        true
+    }
+
+    /// Close every pane but the focused one.
+    pub fn only(&mut self) {
+        let kept = std::mem::take(&mut self.panes[self.focus]);
+        *self = Self::new();
+        self.panes[0] = kept;
+    }
+
+    /// Panes in screen order, left to right and top to bottom.
+    pub fn leaves(&self) -> Vec<usize> {
+        let mut leaves = Vec::new();
+        collect_leaves(&self.root, &mut leaves);
+        leaves
+    }
+
+    /// The pane after (or before) the focused one in screen order, wrapping.
+    pub fn next(&self, forward: bool, count: usize) -> usize {
+        let leaves = self.leaves();
+        let len = leaves.len();
+        let at = leaves.iter().position(|&p| p == self.focus).unwrap_or(0);
+        let step = count % len;
+        if forward {
+            leaves[(at + step) % len]
+        } else {
+            leaves[(at + len - step) % len]
+        }
+    }
+
+    /// The pane next to the focused one in `dir`, preferring the one level
+    /// with its top-left corner.
+    pub fn neighbour(&self, dir: PaneDir) -> Option<usize> {
+        let rects = self.rects(Rect::new(0, 0, 1000, 1000));
+        let (_, from) = rects.iter().find(|(p, _)| *p == self.focus)?;
+        let overlaps = |a0: u16, a1: u16, b0: u16, b1: u16| a0 < b1 && b0 < a1;
+        rects
+            .iter()
+            .filter(|(_, r)| match dir {
+                PaneDir::Left => r.right() == from.x,
+                PaneDir::Right => r.x == from.right(),
+                PaneDir::Up => r.bottom() == from.y,
+                PaneDir::Down => r.y == from.bottom(),
+            })
+            .filter(|(_, r)| match dir {
+                PaneDir::Left | PaneDir::Right => overlaps(r.y, r.bottom(), from.y, from.bottom()),
+                PaneDir::Up | PaneDir::Down => overlaps(r.x, r.right(), from.x, from.right()),
+            })
+            .min_by_key(|(_, r)| match dir {
+                PaneDir::Left | PaneDir::Right => r.y.abs_diff(from.y),
+                PaneDir::Up | PaneDir::Down => r.x.abs_diff(from.x),
+            })
+            .map(|(p, _)| *p)
+    }
+
+    /// Where each pane goes in `area`, in screen order.
+    pub fn rects(&self, area: Rect) -> Vec<(usize, Rect)> {
+        let mut rects = Vec::new();
+        layout(&self.root, area, &mut rects);
+        rects
+    }
+}
+
+fn replace_leaf(node: &mut Node, pane: usize, make: &mut impl FnMut(Node) -> Node) {
+    match node {
+        Node::Leaf(p) if *p == pane => {
+            let leaf = std::mem::replace(node, Node::Leaf(pane));
+            *node = make(leaf);
+        }
+        Node::Leaf(_) => {}
+        Node::Split { first, second, .. } => {
+            replace_leaf(first, pane, make);
+            replace_leaf(second, pane, make);
+        }
+    }
+}
+
+/// `node` without the leaf `pane`, or `None` if that was all of it.
+fn remove_leaf(node: Node, pane: usize) -> Option<Node> {
+    match node {
+        Node::Leaf(p) if p == pane => None,
+        Node::Leaf(_) => Some(node),
+        Node::Split {
+            dir,
+            ratio,
+            first,
+            second,
+        } => match (remove_leaf(*first, pane), remove_leaf(*second, pane)) {
+            (Some(first), Some(second)) => Some(Node::Split {
+                dir,
+                ratio,
+                first: Box::new(first),
+                second: Box::new(second),
+            }),
+            (Some(only), None) | (None, Some(only)) => Some(only),
+            (None, None) => None,
+        },
+    }
+}
+
+fn renumber(node: &mut Node, removed: usize) {
+    match node {
+        Node::Leaf(p) if *p > removed => *p -= 1,
+        Node::Leaf(_) => {}
+        Node::Split { first, second, .. } => {
+            renumber(first, removed);
+            renumber(second, removed);
+        }
+    }
+}
+
+fn contains(node: &Node, pane: usize) -> bool {
+    match node {
+        Node::Leaf(p) => *p == pane,
+        Node::Split { first, second, .. } => contains(first, pane) || contains(second, pane),
+    }
+}
+
+fn resize_split(node: &mut Node, pane: usize, along: SplitDir, delta: i32) -> Option<u16> {
+    let Node::Split {
+        dir,
+        ratio,
+        first,
+        second,
+    } = node
+    else {
+        return None;
+    };
+    let in_first = contains(first, pane);
+    if !in_first && !contains(second, pane) {
+        return None;
+    }
+    let inner = if in_first { first } else { second };
+    if let Some(ratio) = resize_split(inner, pane, along, delta) {
+        return Some(ratio);
+    }
+    if *dir != along {
+        return None;
+    }
+    let delta = if in_first { delta } else { -delta };
+    let resized = (i32::from(*ratio) + delta).clamp(i32::from(MIN_SPLIT), i32::from(MAX_SPLIT));
+    *ratio = resized as u16;
+    Some(*ratio)
+}
+
+fn equalize(node: &mut Node) {
+    if let Node::Split {
+        ratio,
+        first,
+        second,
+        ..
+    } = node
+    {
+        *ratio = EVEN_SPLIT;
+        equalize(first);
+        equalize(second);
+    }
+}
+
+fn collect_leaves(node: &Node, leaves: &mut Vec<usize>) {
+    match node {
+        Node::Leaf(p) => leaves.push(*p),
+        Node::Split { first, second, .. } => {
+            collect_leaves(first, leaves);
+            collect_leaves(second, leaves);
+        }
+    }
+}
+
+fn layout(node: &Node, area: Rect, rects: &mut Vec<(usize, Rect)>) {
+    match node {
+        Node::Leaf(p) => rects.push((*p, area)),
+        Node::Split {
+            dir,
+            ratio,
+            first,
+            second,
+        } => {
+            let share = |size: u16| (u32::from(size) * u32::from(*ratio) / 100) as u16;
+            let (a, b) = match dir {
+                SplitDir::Horizontal => {
+                    let top = share(area.height);
+                    (
+                        Rect {
+                            height: top,
+                            ..area
+                        },
+                        Rect {
+                            y: area.y + top,
+                            height: area.height - top,
+                            ..area
+                        },
+                    )
+                }
+                SplitDir::Vertical => {
+                    let left = share(area.width);
+                    (
+                        Rect {
+                            width: left,
+                            ..area
+                        },
+                        Rect {
+                            x: area.x + left,
+                            width: area.width - left,
+                            ..area
+                        },
+                    )
+                }
+            };
+            layout(first, a, rects);
+            layout(second, b, rects);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_close() {
+        let mut panes = PaneLayout::new();
+        assert!(!panes.is_split());
+        assert_eq!(
+            panes.split(SplitDir::Vertical, Pane::default(), EVEN_SPLIT),
+            1
+        );
+        assert_eq!(panes.focus(), 1);
+        // The new pane is on the left
+        assert_eq!(panes.leaves(), [1, 0]);
+        panes.set_focus(0);
+        panes.split(SplitDir::Horizontal, Pane::default(), EVEN_SPLIT);
+        assert_eq!(panes.leaves(), [1, 2, 0]);
+
+        let rects = panes.rects(Rect::new(0, 0, 80, 20));
+        assert_eq!(rects[0], (1, Rect::new(0, 0, 40, 20)));
+        assert_eq!(rects[1], (2, Rect::new(40, 0, 40, 10)));
+        assert_eq!(rects[2], (0, Rect::new(40, 10, 40, 10)));
+
+        assert!(panes.close(1));
+        assert_eq!(panes.leaves(), [1, 0]);
+        assert_eq!(panes.focus(), 1);
+        assert_eq!(
+            panes.rects(Rect::new(0, 0, 80, 20))[0],
+            (1, Rect::new(0, 0, 80, 10))
+        );
+        panes.only();
+        assert_eq!(panes.len(), 1);
+        assert!(!panes.close(0));
+    }
+
+    #[test]
+    fn test_neighbour_and_next() {
+        let mut panes = PaneLayout::new();
+        panes.split(SplitDir::Vertical, Pane::default(), EVEN_SPLIT);
+        panes.split(SplitDir::Horizontal, Pane::default(), EVEN_SPLIT);
+        // [2 over 1] | 0
+        assert_eq!(panes.focus(), 2);
+        assert_eq!(panes.neighbour(PaneDir::Down), Some(1));
+        assert_eq!(panes.neighbour(PaneDir::Right), Some(0));
+        assert_eq!(panes.neighbour(PaneDir::Left), None);
+        assert_eq!(panes.neighbour(PaneDir::Up), None);
+        panes.set_focus(0);
+        assert_eq!(panes.neighbour(PaneDir::Left), Some(2));
+
+        assert_eq!(panes.next(true, 1), 2);
+        assert_eq!(panes.next(false, 1), 1);
+        assert_eq!(panes.next(true, 3), 0);
+    }
+
+    #[test]
+    fn test_resize_and_equalize() {
+        let mut panes = PaneLayout::new();
+        panes.split(SplitDir::Vertical, Pane::default(), 30);
+        panes.split(SplitDir::Horizontal, Pane::default(), EVEN_SPLIT);
+        // [2 over 1] | 0, focus on 2
+        let area = Rect::new(0, 0, 100, 20);
+        assert_eq!(panes.rects(area)[0], (2, Rect::new(0, 0, 30, 10)));
+
+        assert_eq!(panes.resize(SplitDir::Horizontal, 20), Some(70));
+        assert_eq!(panes.resize(SplitDir::Vertical, 10), Some(40));
+        assert_eq!(panes.rects(area)[0], (2, Rect::new(0, 0, 40, 14)));
+        // From the second pane of a split, growing moves the border the
+        // other way
+        panes.set_focus(0);
+        assert_eq!(panes.resize(SplitDir::Vertical, 100), Some(10));
+        assert_eq!(panes.resize(SplitDir::Horizontal, 5), None);
+
+        panes.equalize();
+        assert_eq!(panes.rects(area)[0], (2, Rect::new(0, 0, 50, 10)));
+    }
+}
@@ -0,0 +1,223 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Most recent searches kept for Up/Down recall.
+const MAX_HISTORY: usize = 50;
+
+/// Cursor and history for a one-line prompt such as `/`.
+///
+/// The text itself lives with its owner (`App::search_query`), so every
+/// edit takes it by reference. The cursor is a grapheme index.
+#[derive(Debug, Default)]
+pub struct PromptInput {
+    cursor: usize,
+    history: Vec<String>,
+    /// Index into `history` while browsing it with Up/Down.
+    browsing: Option<usize>,
+    /// What was typed before browsing started, restored past the newest entry.
+    draft: String,
+}
+
+impl PromptInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Start editing `text` with the cursor at its end.
+    pub fn reset(&mut self, text: &str) {
+        self.cursor = text.graphemes(true).count();
+        self.browsing = None;
+    }
+
+    /// Byte offset of grapheme `index` in `text`.
+    fn byte_at(text: &str, index: usize) -> usize {
+        text.grapheme_indices(true)
+            .nth(index)
+            .map_or(text.len(), |(i, _)| i)
+    }
+
+    pub fn insert(&mut self, text: &mut String, s: &str) {
+        let at = Self::byte_at(text, self.cursor);
+        text.insert_str(at, s);
+        self.cursor += s.graphemes(true).count();
+    }
+
+    /// Delete the grapheme before the cursor. Returns false at the start.
+    pub fn backspace(&mut self, text: &mut String) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        self.delete(text);
+        true
+    }
+
+    /// Delete the grapheme under the cursor.
+    pub fn delete(&mut self, text: &mut String) {
+        let start = Self::byte_at(text, self.cursor);
+        let end = Self::byte_at(text, self.cursor + 1);
+        text.replace_range(start..end, "");
+    }
+
+    /// Delete everything before the cursor (Ctrl-U).
+    pub fn delete_to_start(&mut self, text: &mut String) {
+        let at = Self::byte_at(text, self.cursor);
+        text.replace_range(..at, "");
+        self.cursor = 0;
+    }
+
+    /// Delete the word before the cursor along with any spaces after it
+    /// (Ctrl-W).
+    pub fn delete_word(&mut self, text: &mut String) {
+        let graphemes: Vec<&str> = text.graphemes(true).take(self.cursor).collect();
+        let is_space = |g: &&&str| g.chars().all(char::is_whitespace);
+        let spaces = graphemes.iter().rev().take_while(is_space).count();
+        let word = graphemes
+            .iter()
+            .rev()
+            .skip(spaces)
+            .take_while(|g| !is_space(g))
+            .count();
+        let start = self.cursor - spaces - word;
+        let (from, to) = (Self::byte_at(text, start), Self::byte_at(text, self.cursor));
+        text.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self, text: &str) {
+        self.cursor = (self.cursor + 1).min(text.graphemes(true).count());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self, text: &str) {
+        self.cursor = text.graphemes(true).count();
+    }
+
+    /// Replace `text` with the previous history entry (Up).
+    pub fn history_prev(&mut self, text: &mut String) {
+        let index = match self.browsing {
+            Some(0) => return,
+            Some(i) => i - 1,
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = text.clone();
+                self.history.len() - 1
+            }
+        };
+        self.browsing = Some(index);
+        *text = self.history[index].clone();
+        self.move_end(text);
+    }
+
+    /// Replace `text` with the next history entry, or with what was typed
+    /// before browsing once past the newest (Down).
+    pub fn history_next(&mut self, text: &mut String) {
+        let Some(index) = self.browsing else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.browsing = Some(index + 1);
+            *text = self.history[index + 1].clone();
+        } else {
+            self.browsing = None;
+            *text = std::mem::take(&mut self.draft);
+        }
+        self.move_end(text);
+    }
+
+    /// Remember a submitted entry, moving a repeated one to the end.
+    pub fn push_history(&mut self, entry: &str) {
+        self.browsing = None;
+        if entry.is_empty() {
+            return;
+        }
+        self.history.retain(|e| e != entry);
+        self.history.push(entry.to_string());
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_at_cursor() {
+        let mut text = String::from("helo");
+        let mut input = PromptInput::new();
+        input.reset(&text);
+        input.move_left();
+        input.insert(&mut text, "l");
+        assert_eq!(text, "hello");
+        assert_eq!(input.cursor(), 4);
+
+        input.move_home();
+        input.delete(&mut text);
+        assert!(!input.backspace(&mut text));
+        input.move_end(&text);
+        assert!(input.backspace(&mut text));
+        assert_eq!(text, "ell");
+
+        input.move_right(&text);
+        assert_eq!(input.cursor(), 3);
+    }
+
+    #[test]
+    fn test_delete_word_and_to_start() {
+        let mut text = String::from("foo bar  baz");
+        let mut input = PromptInput::new();
+        input.reset(&text);
+        for _ in 0..3 {
+            input.move_left();
+        }
+        input.delete_word(&mut text);
+        assert_eq!(text, "foo baz");
+        assert_eq!(input.cursor(), 4);
+
+        input.delete_to_start(&mut text);
+        assert_eq!(text, "baz");
+        assert_eq!(input.cursor(), 0);
+
+        let mut text = String::from("日本 語");
+        input.reset(&text);
+        input.delete_word(&mut text);
+        assert_eq!(text, "日本 ");
+    }
+
+    #[test]
+    fn test_history_navigation() {
+        let mut input = PromptInput::new();
+        input.push_history("one");
+        input.push_history("two");
+        input.push_history("one");
+
+        let mut text = String::from("draft");
+        input.reset(&text);
+        input.history_prev(&mut text);
+        assert_eq!(text, "one");
+        input.history_prev(&mut text);
+        assert_eq!(text, "two");
+        input.history_prev(&mut text);
+        assert_eq!(text, "two");
+        assert_eq!(input.cursor(), 3);
+
+        input.history_next(&mut text);
+        assert_eq!(text, "one");
+        input.history_next(&mut text);
+        assert_eq!(text, "draft");
+        input.history_next(&mut text);
+        assert_eq!(text, "draft");
+    }
+}
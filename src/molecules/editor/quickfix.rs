@@ -0,0 +1,102 @@
+/// A place in a note a command pointed at, with the text to show for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub note_id: String,
+    pub row: usize,
+    /// Grapheme column.
+    pub col: usize,
+    pub text: String,
+}
+
+/// Locations found by one command (`:lint-blocks`, ...), shown in the
+/// bottom panel and walked with `]q` / `[q`. `current` is the one last
+/// jumped to or selected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickfixList {
+    title: String,
+    locations: Vec<Location>,
+    current: usize,
+}
+
+impl QuickfixList {
+    pub fn new(title: &str, locations: Vec<Location>) -> Self {
+        Self {
+            title: title.to_string(),
+            locations,
+            current: 0,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn locations(&self) -> &[Location] {
+        &self.locations
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn current_location(&self) -> Option<&Location> {
+        self.locations.get(self.current)
+    }
+
+    /// Make location `index` the current one, kept in range.
+    pub fn select(&mut self, index: usize) {
+        self.current = index.min(self.locations.len().saturating_sub(1));
+    }
+
+    /// Move `count` locations forward or back. Returns false, staying put,
+    /// when there are not that many left.
+    pub fn step(&mut self, forward: bool, count: usize) -> bool {
+        let target = if forward {
+            self.current + count
+        } else {
+            match self.current.checked_sub(count) {
+                Some(target) => target,
+                None => return false,
+            }
+        };
+        if target >= self.locations.len() {
+            return false;
+        }
+        self.current = target;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(row: usize) -> Location {
+        Location {
+            note_id: "a".to_string(),
+            row,
+            col: 0,
+            text: format!("line {}", row),
+        }
+    }
+
+    #[test]
+    fn test_step_through_locations() {
+        let mut list = QuickfixList::new("Block problems", vec![at(2), at(5), at(9)]);
+        assert_eq!(list.current_location().unwrap().row, 2);
+        assert!(!list.step(false, 1));
+        assert!(list.step(true, 2));
+        assert_eq!(list.current_location().unwrap().row, 9);
+        assert!(!list.step(true, 1));
+        assert_eq!(list.current(), 2);
+        assert!(list.step(false, 1));
+        assert_eq!(list.current(), 1);
+        list.select(7);
+        assert_eq!(list.current(), 2);
+        assert!(
+            QuickfixList::new("Empty", Vec::new())
+                .current_location()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::auto_link::AutoLink;
+use super::buffer::TextBuffer;
+use super::folds::{FoldRegion, fold_regions};
+use super::outline::{OutlineEntry, outline};
+use super::search_pattern::SearchPattern;
+
+/// A search match: row, grapheme column and length in graphemes.
+pub type SearchMatch = (usize, usize, usize);
+
+/// Words written and the goal, as shown in the status bar.
+type WordGoal = Option<(usize, usize)>;
+
+#[derive(Debug)]
+struct CachedMatches {
+    revision: u64,
+    pattern: SearchPattern,
+    matches: Rc<[SearchMatch]>,
+}
+
+#[derive(Debug)]
+struct CachedSpellErrors {
+    revision: u64,
+    language: String,
+    errors: Rc<[SearchMatch]>,
+}
+
+/// Values the renderer derives from the editor text, kept until the text
+/// (`TextBuffer::revision`) or their other inputs change, so frames drawn
+/// for cursor moves or status messages don't scan the whole note again.
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    matches: RefCell<Option<CachedMatches>>,
+    word_goal: Cell<Option<(u64, WordGoal)>>,
+    outline: RefCell<Option<(u64, Rc<[OutlineEntry]>)>>,
+    folds: RefCell<Option<(u64, Rc<[FoldRegion]>)>>,
+    title_drift: RefCell<Option<(u64, Option<String>)>>,
+    spell_errors: RefCell<Option<CachedSpellErrors>>,
+    auto_links: RefCell<Option<(u64, Rc<[AutoLink]>)>>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `buffer.find_all(pattern)`, computed once per revision and pattern.
+    pub fn search_matches(
+        &self,
+        buffer: &TextBuffer,
+        pattern: &SearchPattern,
+    ) -> Rc<[SearchMatch]> {
+        let revision = buffer.revision();
+        let mut cached = self.matches.borrow_mut();
+        if let Some(hit) = cached.as_ref()
+            && hit.revision == revision
+            && hit.pattern == *pattern
+        {
+            return Rc::clone(&hit.matches);
+        }
+        let matches: Rc<[SearchMatch]> = buffer.find_all(pattern.clone()).into();
+        *cached = Some(CachedMatches {
+            revision,
+            pattern: pattern.clone(),
+            matches: Rc::clone(&matches),
+        });
+        matches
+    }
+
+    /// Headings of `buffer` for the outline sidebar.
+    pub fn outline(&self, buffer: &TextBuffer) -> Rc<[OutlineEntry]> {
+        let revision = buffer.revision();
+        let mut cached = self.outline.borrow_mut();
+        match cached.as_ref() {
+            Some((rev, entries)) if *rev == revision => Rc::clone(entries),
+            _ => {
+                let entries: Rc<[OutlineEntry]> =
+                    outline(buffer.content().iter().map(String::as_str)).into();
+                *cached = Some((revision, Rc::clone(&entries)));
+                entries
+            }
+        }
+    }
+
+    /// Foldable regions of `buffer`.
+    pub fn fold_regions(&self, buffer: &TextBuffer) -> Rc<[FoldRegion]> {
+        let revision = buffer.revision();
+        let mut cached = self.folds.borrow_mut();
+        match cached.as_ref() {
+            Some((rev, regions)) if *rev == revision => Rc::clone(regions),
+            _ => {
+                let lines: Vec<&str> = buffer.content().iter().map(String::as_str).collect();
+                let regions: Rc<[FoldRegion]> = fold_regions(&lines).into();
+                *cached = Some((revision, Rc::clone(&regions)));
+                regions
+            }
+        }
+    }
+
+    /// Word goal progress of `buffer`, running `compute` only when the text
+    /// changed since the last call.
+    pub fn word_goal(&self, buffer: &TextBuffer, compute: impl FnOnce() -> WordGoal) -> WordGoal {
+        let revision = buffer.revision();
+        match self.word_goal.get() {
+            Some((rev, progress)) if rev == revision => progress,
+            _ => {
+                let progress = compute();
+                self.word_goal.set(Some((revision, progress)));
+                progress
+            }
+        }
+    }
+
+    /// The heading of `buffer` its `title` field disagrees with, running
+    /// `compute` only when the text changed since the last call.
+    pub fn title_drift(
+        &self,
+        buffer: &TextBuffer,
+        compute: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        let revision = buffer.revision();
+        let mut cached = self.title_drift.borrow_mut();
+        match cached.as_ref() {
+            Some((rev, heading)) if *rev == revision => heading.clone(),
+            _ => {
+                let heading = compute();
+                *cached = Some((revision, heading.clone()));
+                heading
+            }
+        }
+    }
+
+    /// Misspelled words of `buffer` checked against dictionary `language`,
+    /// running `compute` only when the text or language changed.
+    pub fn spell_errors(
+        &self,
+        buffer: &TextBuffer,
+        language: &str,
+        compute: impl FnOnce() -> Vec<SearchMatch>,
+    ) -> Rc<[SearchMatch]> {
+        let revision = buffer.revision();
+        let mut cached = self.spell_errors.borrow_mut();
+        if let Some(hit) = cached.as_ref()
+            && hit.revision == revision
+            && hit.language == language
+        {
+            return Rc::clone(&hit.errors);
+        }
+        let errors: Rc<[SearchMatch]> = compute().into();
+        *cached = Some(CachedSpellErrors {
+            revision,
+            language: language.to_string(),
+            errors: Rc::clone(&errors),
+        });
+        errors
+    }
+
+    /// Note ids and dates in `buffer`, running `compute` only when the
+    /// text changed since the last call.
+    pub fn auto_links(
+        &self,
+        buffer: &TextBuffer,
+        compute: impl FnOnce() -> Vec<AutoLink>,
+    ) -> Rc<[AutoLink]> {
+        let revision = buffer.revision();
+        let mut cached = self.auto_links.borrow_mut();
+        match cached.as_ref() {
+            Some((rev, links)) if *rev == revision => Rc::clone(links),
+            _ => {
+                let links: Rc<[AutoLink]> = compute().into();
+                *cached = Some((revision, Rc::clone(&links)));
+                links
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recomputes_only_after_edits() {
+        let cache = RenderCache::new();
+        let mut buffer = TextBuffer::from_string("foo bar\nfoo");
+        let first = cache.search_matches(&buffer, &"foo".into());
+        assert_eq!(&first[..], [(0, 0, 3), (1, 0, 3)]);
+        assert!(Rc::ptr_eq(
+            &first,
+            &cache.search_matches(&buffer, &"foo".into())
+        ));
+        assert_eq!(cache.search_matches(&buffer, &"bar".into()).len(), 1);
+
+        let calls = Cell::new(0);
+        let count = || {
+            calls.set(calls.get() + 1);
+            Some((2, 10))
+        };
+        assert_eq!(cache.word_goal(&buffer, count), Some((2, 10)));
+        assert_eq!(cache.word_goal(&buffer, count), Some((2, 10)));
+        assert_eq!(calls.get(), 1);
+
+        buffer.insert_char('x');
+        assert_eq!(cache.search_matches(&buffer, &"foo".into()).len(), 2);
+        assert_eq!(
+            &cache.search_matches(&buffer, &"xfoo".into())[..],
+            [(0, 0, 4)]
+        );
+        cache.word_goal(&buffer, count);
+        assert_eq!(calls.get(), 2);
+
+        let outline = cache.outline(&buffer);
+        assert!(outline.is_empty());
+        assert!(Rc::ptr_eq(&outline, &cache.outline(&buffer)));
+        buffer.set_cursor(1, 0);
+        buffer.insert_char('#');
+        buffer.insert_char(' ');
+        assert_eq!(cache.outline(&buffer)[0].title, "foo");
+    }
+}
@@ -0,0 +1,150 @@
+use super::substitute::Substitution;
+
+/// One line `:replace-all` would change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceHunk {
+    pub note_id: String,
+    pub title: String,
+    pub row: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// The lines `sub` changes in each `(id, title, text)`, note by note and
+/// top to bottom.
+pub fn replace_hunks<'a>(
+    notes: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+    sub: &Substitution,
+) -> Vec<ReplaceHunk> {
+    let mut hunks = Vec::new();
+    for (id, title, text) in notes {
+        for (row, line) in text.split('\n').enumerate() {
+            if let Some((after, _)) = sub.apply(line) {
+                hunks.push(ReplaceHunk {
+                    note_id: id.to_string(),
+                    title: title.to_string(),
+                    row,
+                    before: line.to_string(),
+                    after,
+                });
+            }
+        }
+    }
+    hunks
+}
+
+/// A `:replace-all` run, deciding the hunks one at a time. Nothing is
+/// written until the run ends, so each note changes in one step.
+#[derive(Debug, Clone)]
+pub struct ReplaceAllSession {
+    hunks: Vec<ReplaceHunk>,
+    accepted: Vec<bool>,
+    current: usize,
+}
+
+impl ReplaceAllSession {
+    pub fn new(hunks: Vec<ReplaceHunk>) -> Self {
+        let accepted = vec![false; hunks.len()];
+        Self {
+            hunks,
+            accepted,
+            current: 0,
+        }
+    }
+
+    pub fn hunks(&self) -> &[ReplaceHunk] {
+        &self.hunks
+    }
+
+    /// Index of the hunk waiting for an answer.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Take or leave the current hunk and move to the next. Returns false
+    /// when none are left.
+    pub fn answer(&mut self, accept: bool) -> bool {
+        if let Some(slot) = self.accepted.get_mut(self.current) {
+            *slot = accept;
+            self.current += 1;
+        }
+        self.current < self.hunks.len()
+    }
+
+    /// Take the current hunk and every one after it.
+    pub fn accept_rest(&mut self) {
+        for slot in &mut self.accepted[self.current.min(self.hunks.len())..] {
+            *slot = true;
+        }
+        self.current = self.hunks.len();
+    }
+
+    /// The accepted hunks, grouped by note in the order the notes came.
+    pub fn accepted_by_note(&self) -> Vec<(&str, Vec<&ReplaceHunk>)> {
+        let mut groups: Vec<(&str, Vec<&ReplaceHunk>)> = Vec::new();
+        for (hunk, _) in self
+            .hunks
+            .iter()
+            .zip(&self.accepted)
+            .filter(|(_, accepted)| **accepted)
+        {
+            match groups.last_mut() {
+                Some((id, group)) if *id == hunk.note_id => group.push(hunk),
+                _ => groups.push((&hunk.note_id, vec![hunk])),
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> ReplaceAllSession {
+        let sub = Substitution::new("cat", "dog", true, true).unwrap();
+        let hunks = replace_hunks(
+            [
+                ("a", "Pets", "# Pets\nCat and cat\nfish"),
+                ("b", "Zoo", "no match"),
+                ("c", "Farm", "cat\nbarn\ncats"),
+            ],
+            &sub,
+        );
+        ReplaceAllSession::new(hunks)
+    }
+
+    #[test]
+    fn test_hunks_per_line() {
+        let session = session();
+        let rows: Vec<(&str, usize, &str)> = session
+            .hunks()
+            .iter()
+            .map(|h| (h.note_id.as_str(), h.row, h.after.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            [("a", 1, "dog and dog"), ("c", 0, "dog"), ("c", 2, "dogs")]
+        );
+    }
+
+    #[test]
+    fn test_answers_group_by_note() {
+        let mut session = session();
+        assert!(session.answer(true));
+        assert!(session.answer(false));
+        assert!(!session.answer(true));
+        let groups: Vec<(&str, Vec<usize>)> = session
+            .accepted_by_note()
+            .into_iter()
+            .map(|(id, hunks)| (id, hunks.iter().map(|h| h.row).collect()))
+            .collect();
+        assert_eq!(groups, [("a", vec![1]), ("c", vec![2])]);
+
+        let mut session = self::session();
+        session.answer(false);
+        session.accept_rest();
+        assert_eq!(session.current(), 3);
+        assert_eq!(session.accepted_by_note().len(), 1);
+    }
+}
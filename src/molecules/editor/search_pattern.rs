@@ -0,0 +1,110 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A `/` search query as it is compared with the text: case-insensitive
+/// and in NFC, so a composed `é` matches a decomposed `e` + `◌́`. With
+/// `ignore_diacritics` accents are stripped from both sides, so `cafe`
+/// matches `café`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPattern {
+    key: String,
+    ignore_diacritics: bool,
+}
+
+impl SearchPattern {
+    pub fn new(query: &str, ignore_diacritics: bool) -> Self {
+        Self {
+            key: query
+                .graphemes(true)
+                .map(|g| fold(g, ignore_diacritics))
+                .collect(),
+            ignore_diacritics,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key.is_empty()
+    }
+
+    /// Every match in `line`, overlapping ones included, as (grapheme
+    /// column, length in graphemes). Matches start and end on grapheme
+    /// boundaries.
+    pub fn find_in(&self, line: &str) -> Vec<(usize, usize)> {
+        if self.key.is_empty() {
+            return Vec::new();
+        }
+        // Folded text of the line and the byte offset in it where each
+        // grapheme starts, plus the end
+        let mut key = String::with_capacity(line.len());
+        let mut bounds = vec![0];
+        for g in line.graphemes(true) {
+            key.push_str(&fold(g, self.ignore_diacritics));
+            bounds.push(key.len());
+        }
+
+        let mut matches = Vec::new();
+        for (col, &start) in bounds[..bounds.len() - 1].iter().enumerate() {
+            if !key[start..].starts_with(&self.key) {
+                continue;
+            }
+            if let Ok(end) = bounds.binary_search(&(start + self.key.len())) {
+                matches.push((col, end - col));
+            }
+        }
+        matches
+    }
+}
+
+impl From<&str> for SearchPattern {
+    fn from(query: &str) -> Self {
+        Self::new(query, false)
+    }
+}
+
+impl From<&String> for SearchPattern {
+    fn from(query: &String) -> Self {
+        Self::new(query, false)
+    }
+}
+
+/// Text in NFC, e.g. pasted text whose accents arrive as combining marks.
+pub fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+fn fold(grapheme: &str, ignore_diacritics: bool) -> String {
+    if ignore_diacritics {
+        grapheme
+            .nfd()
+            .filter(|&c| !is_combining_mark(c))
+            .flat_map(char::to_lowercase)
+            .collect()
+    } else {
+        grapheme.to_lowercase().nfc().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composed_and_decomposed_match() {
+        let decomposed = "Cafe\u{301} au lait";
+        let pattern = SearchPattern::from("café");
+        assert_eq!(pattern.find_in(decomposed), [(0, 4)]);
+        assert_eq!(SearchPattern::from("É").find_in(decomposed), [(3, 1)]);
+        // Without the flag the accent matters
+        assert!(SearchPattern::from("cafe").find_in("café").is_empty());
+        assert_eq!(SearchPattern::from("aa").find_in("aaa"), [(0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_ignore_diacritics() {
+        let pattern = SearchPattern::new("cafe", true);
+        assert_eq!(pattern.find_in("Un café, deux CAFÉS"), [(3, 4), (14, 4)]);
+        assert_eq!(SearchPattern::new("naïve", true).find_in("naive"), [(0, 5)]);
+        assert_eq!(nfc("e\u{301}"), "é");
+    }
+}
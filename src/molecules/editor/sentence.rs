@@ -0,0 +1,86 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Whether `grapheme` ends a sentence, and whether it is a full-width
+/// terminator, which needs no space after it.
+fn is_terminator(grapheme: &str) -> (bool, bool) {
+    match grapheme {
+        "." | "!" | "?" => (true, false),
+        "。" | "！" | "？" => (true, true),
+        _ => (false, false),
+    }
+}
+
+fn is_closing(grapheme: &str) -> bool {
+    matches!(grapheme, ")" | "]" | "\"" | "'" | "”" | "’" | "」" | "』")
+}
+
+fn is_space(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Positions `(row, grapheme col)` where a sentence starts, in order.
+///
+/// A sentence ends at `.`, `!` or `?` (plus closing quotes or brackets)
+/// followed by whitespace or the end of the line, or right after `。！？`.
+/// Blank lines are boundaries of their own, as in Vim.
+pub fn sentence_starts<'a>(lines: impl IntoIterator<Item = &'a String>) -> Vec<(usize, usize)> {
+    let mut starts = Vec::new();
+    // The next non-blank grapheme begins a sentence
+    let mut pending = true;
+    for (row, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            starts.push((row, 0));
+            pending = true;
+            continue;
+        }
+        let g: Vec<&str> = line.graphemes(true).collect();
+        let mut col = 0;
+        while col < g.len() {
+            if pending && !is_space(g[col]) {
+                starts.push((row, col));
+                pending = false;
+            }
+            let (ends, wide) = is_terminator(g[col]);
+            if ends {
+                let mut next = col + 1;
+                while next < g.len() && is_closing(g[next]) {
+                    next += 1;
+                }
+                if wide || next == g.len() || is_space(g[next]) {
+                    pending = true;
+                    col = next;
+                    continue;
+                }
+            }
+            col += 1;
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split('\n').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_sentence_starts() {
+        let text = lines("One. Two? (Three!) Four\nstill four.\n\nv1.2 ok");
+        assert_eq!(
+            sentence_starts(&text),
+            vec![(0, 0), (0, 5), (0, 10), (0, 19), (2, 0), (3, 0)]
+        );
+    }
+
+    #[test]
+    fn test_cjk_sentences() {
+        let text = lines("你好。世界！  “引用。”后面");
+        assert_eq!(
+            sentence_starts(&text),
+            vec![(0, 0), (0, 3), (0, 8), (0, 13)]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::buffer::TextBuffer;
+
+/// A snippet body ready to insert: the text with its `$N` markers taken
+/// out, and where each tabstop ended up as (line, column) within it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub text: String,
+    pub stops: Vec<(usize, usize)>,
+}
+
+impl Snippet {
+    /// Parse `body`: `$1`, `$2`… are tabstops visited in number order and
+    /// `$0` the last one (the end of the text when absent); `$$` is a
+    /// literal `$`. Lines after the first are prefixed with `indent`.
+    pub fn parse(body: &str, indent: &str) -> Self {
+        let mut text = String::new();
+        let (mut line, mut col) = (0, 0);
+        let mut numbered: Vec<(usize, (usize, usize))> = Vec::new();
+        let mut last = None;
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '$' if chars.peek() == Some(&'$') => {
+                    chars.next();
+                    text.push('$');
+                    col += 1;
+                }
+                '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                    let mut number = 0;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        number = number * 10 + digit as usize;
+                        chars.next();
+                    }
+                    if number == 0 {
+                        last = Some((line, col));
+                    } else if !numbered.iter().any(|(n, _)| *n == number) {
+                        numbered.push((number, (line, col)));
+                    }
+                }
+                '\n' => {
+                    text.push('\n');
+                    text.push_str(indent);
+                    line += 1;
+                    col = indent.graphemes(true).count();
+                }
+                '\r' => {}
+                c => {
+                    text.push(c);
+                    col += 1;
+                }
+            }
+        }
+        numbered.sort_by_key(|(n, _)| *n);
+        let mut stops: Vec<(usize, usize)> = numbered.into_iter().map(|(_, at)| at).collect();
+        stops.push(last.unwrap_or((line, col)));
+        Self { text, stops }
+    }
+}
+
+/// The snippet whose trigger ends at grapheme `col` of `line`: the run of
+/// non-blank text before the cursor must match a key of `snippets`
+/// exactly. Returns where the trigger starts and the snippet body.
+pub fn snippet_trigger<'a>(
+    line: &str,
+    col: usize,
+    snippets: &'a BTreeMap<String, String>,
+) -> Option<(usize, &'a str)> {
+    let graphemes: Vec<&str> = line.graphemes(true).collect();
+    let col = col.min(graphemes.len());
+    let start = graphemes[..col]
+        .iter()
+        .rposition(|g| g.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    if start == col {
+        return None;
+    }
+    snippets
+        .get(&graphemes[start..col].concat())
+        .map(|body| (start, body.as_str()))
+}
+
+/// Tabstops still ahead after an expansion. What is typed at the current
+/// stop moves the later ones along with it.
+#[derive(Debug, Clone)]
+pub struct SnippetSession {
+    stops: Vec<(usize, usize)>,
+    at: (usize, usize),
+    line_len: usize,
+    line_count: usize,
+}
+
+impl SnippetSession {
+    /// A session for `stops`, with the cursor now at `at` in `buffer`.
+    pub fn new(stops: Vec<(usize, usize)>, at: (usize, usize), buffer: &TextBuffer) -> Self {
+        let (line_len, line_count) = Self::measure(at.0, buffer);
+        Self {
+            stops,
+            at,
+            line_len,
+            line_count,
+        }
+    }
+
+    fn measure(row: usize, buffer: &TextBuffer) -> (usize, usize) {
+        let len = buffer
+            .content()
+            .get(row)
+            .map_or(0, |line| line.graphemes(true).count());
+        (len, buffer.line_count())
+    }
+
+    /// The next stop, shifted by the edits made since the last one: new
+    /// lines push later rows down, otherwise text typed on the stop's line
+    /// pushes later stops on it right. None when no stops are left.
+    pub fn next(&mut self, buffer: &TextBuffer) -> Option<(usize, usize)> {
+        if self.stops.is_empty() {
+            return None;
+        }
+        let (row, col) = self.at;
+        let (line_len, line_count) = Self::measure(row, buffer);
+        let rows = line_count as isize - self.line_count as isize;
+        let cols = line_len as isize - self.line_len as isize;
+        let shift = |n: usize, by: isize| n.saturating_add_signed(by);
+        for stop in &mut self.stops {
+            if rows != 0 {
+                if stop.0 > row {
+                    stop.0 = shift(stop.0, rows);
+                }
+            } else if stop.0 == row && stop.1 >= col {
+                stop.1 = shift(stop.1, cols);
+            }
+        }
+        let next = self.stops.remove(0);
+        self.at = next;
+        (self.line_len, self.line_count) = Self::measure(next.0, buffer);
+        Some(next)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.stops.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_orders_stops() {
+        let snippet = Snippet::parse("## $2 meeting\nWith: $1\n$0\nCost: $$5", "  ");
+        assert_eq!(snippet.text, "##  meeting\n  With: \n  \n  Cost: $5");
+        assert_eq!(snippet.stops, [(1, 8), (0, 3), (2, 2)]);
+        assert_eq!(Snippet::parse("done", "").stops, [(0, 4)]);
+    }
+
+    #[test]
+    fn test_trigger_before_cursor() {
+        let snippets = BTreeMap::from([(";mt".to_string(), "body".to_string())]);
+        assert_eq!(snippet_trigger("see ;mt", 7, &snippets), Some((4, "body")));
+        assert_eq!(snippet_trigger(";mt x", 3, &snippets), Some((0, "body")));
+        assert_eq!(snippet_trigger("a;mt", 4, &snippets), None);
+        assert_eq!(snippet_trigger("see ", 4, &snippets), None);
+    }
+
+    #[test]
+    fn test_session_follows_typing() {
+        let mut buffer = TextBuffer::from_string("A  B \nC ");
+        let mut session = SnippetSession::new(vec![(0, 5), (1, 2)], (0, 2), &buffer);
+        buffer.set_cursor(0, 2);
+        buffer.insert_text("xyz");
+        assert_eq!(session.next(&buffer), Some((0, 8)));
+        buffer.insert_text("1\n2");
+        assert_eq!(session.next(&buffer), Some((2, 2)));
+        assert!(session.is_done());
+        assert_eq!(session.next(&buffer), None);
+    }
+}
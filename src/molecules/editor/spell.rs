@@ -0,0 +1,393 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::folds::frontmatter_end;
+use super::render_cache::SearchMatch;
+
+/// How many suggestions `z=` offers.
+pub const SUGGESTION_LIMIT: usize = 10;
+
+/// How flags are written after the `/` of a `.dic` entry (`FLAG` in the
+/// `.aff` file).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    Char,
+    Long,
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|pair| pair.iter().collect())
+                .collect(),
+            FlagType::Num => flags.split(',').map(|f| f.trim().to_string()).collect(),
+        }
+    }
+}
+
+/// A `PFX` or `SFX` rule of an `.aff` file.
+#[derive(Debug, Clone)]
+struct AffixRule {
+    flag: String,
+    prefix: bool,
+    cross: bool,
+    strip: String,
+    add: String,
+    condition: Regex,
+}
+
+impl AffixRule {
+    fn apply(&self, word: &str) -> Option<String> {
+        if !self.condition.is_match(word) {
+            return None;
+        }
+        if self.prefix {
+            let rest = word.strip_prefix(self.strip.as_str())?;
+            Some(format!("{}{}", self.add, rest))
+        } else {
+            let rest = word.strip_suffix(self.strip.as_str())?;
+            Some(format!("{}{}", rest, self.add))
+        }
+    }
+}
+
+/// The words of a hunspell dictionary, with the forms its prefix and
+/// suffix rules make of them.
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    /// Read a hunspell `.dic` word list (`word/FLAGS`, after a first line
+    /// with the count) and the `PFX`/`SFX` rules of its `.aff` file.
+    pub fn parse(dic: &str, aff: Option<&str>) -> Self {
+        let (flag_type, rules) = aff.map(parse_aff).unwrap_or((FlagType::Char, Vec::new()));
+        let mut words = HashSet::new();
+        for (n, line) in dic.lines().enumerate() {
+            let entry = line.split_whitespace().next().unwrap_or("");
+            if entry.is_empty() || (n == 0 && entry.parse::<usize>().is_ok()) {
+                continue;
+            }
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            let flags = flag_type.split(flags);
+            let applies = |rule: &&AffixRule| flags.contains(&rule.flag);
+            let suffixed: Vec<(String, bool)> = rules
+                .iter()
+                .filter(|r| !r.prefix)
+                .filter(applies)
+                .filter_map(|rule| rule.apply(word).map(|w| (w, rule.cross)))
+                .collect();
+            for rule in rules.iter().filter(|r| r.prefix).filter(applies) {
+                words.extend(rule.apply(word));
+                if rule.cross {
+                    words.extend(
+                        suffixed
+                            .iter()
+                            .filter(|(_, cross)| *cross)
+                            .filter_map(|(w, _)| rule.apply(w)),
+                    );
+                }
+            }
+            words.extend(suffixed.into_iter().map(|(w, _)| w));
+            words.insert(word.to_string());
+        }
+        Self { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Whether `word` is spelled right: as listed, or capitalized or in
+    /// capitals where the list has it in lower case.
+    pub fn contains(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.words.contains(&word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        let title = capitalize(&lower);
+        (word == title || word == word.to_uppercase())
+            && (self.words.contains(&lower) || self.words.contains(&title))
+    }
+
+    /// Up to `limit` words close to `word`, nearest first: those one edit
+    /// away (a letter added, removed, changed or two swapped), then two.
+    /// A capitalized `word` gets capitalized suggestions.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let lower: Vec<char> = word.to_lowercase().chars().collect();
+        let mut scored: Vec<(usize, bool, &str)> = self
+            .words
+            .iter()
+            .filter_map(|candidate| {
+                let chars: Vec<char> = candidate.to_lowercase().chars().collect();
+                if chars.len().abs_diff(lower.len()) > 2 {
+                    return None;
+                }
+                let distance = edit_distance(&lower, &chars).filter(|d| *d <= 2)?;
+                Some((distance, chars.first() != lower.first(), candidate.as_str()))
+            })
+            .collect();
+        scored.sort();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let mut suggestions: Vec<String> = Vec::new();
+        for (_, _, candidate) in scored {
+            let suggestion = if capitalized {
+                capitalize(candidate)
+            } else {
+                candidate.to_string()
+            };
+            if suggestion != word && !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+            if suggestions.len() == limit {
+                break;
+            }
+        }
+        suggestions
+    }
+}
+
+fn parse_aff(aff: &str) -> (FlagType, Vec<AffixRule>) {
+    let mut flag_type = FlagType::Char;
+    let mut cross = Vec::new();
+    let mut rules = Vec::new();
+    for line in aff.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["FLAG", "long", ..] => flag_type = FlagType::Long,
+            ["FLAG", "num", ..] => flag_type = FlagType::Num,
+            [kind @ ("PFX" | "SFX"), flag, yes_no @ ("Y" | "N"), count]
+                if count.parse::<usize>().is_ok() =>
+            {
+                cross.push((*kind, flag.to_string(), *yes_no == "Y"));
+            }
+            [kind @ ("PFX" | "SFX"), flag, strip, add, condition, ..] => {
+                let prefix = *kind == "PFX";
+                let pattern = match (prefix, *condition) {
+                    (_, ".") => String::new(),
+                    (true, c) => format!("^{}", c),
+                    (false, c) => format!("{}$", c),
+                };
+                let Ok(condition) = Regex::new(&pattern) else {
+                    continue;
+                };
+                let zero = |s: &str| {
+                    if s == "0" {
+                        String::new()
+                    } else {
+                        s.to_string()
+                    }
+                };
+                let add = add.split('/').next().unwrap_or("");
+                rules.push(AffixRule {
+                    flag: flag.to_string(),
+                    prefix,
+                    cross: cross.iter().any(|(k, f, y)| k == kind && f == flag && *y),
+                    strip: zero(strip),
+                    add: zero(add),
+                    condition,
+                });
+            }
+            _ => {}
+        }
+    }
+    (flag_type, rules)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Edits (insert, delete, substitute, swap two neighbours) from `a` to `b`.
+fn edit_distance(a: &[char], b: &[char]) -> Option<usize> {
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows.last()?.last().copied()
+}
+
+/// The words of `line`: letters, with `'` between them, as (grapheme
+/// column, length).
+pub fn word_spans(line: &str) -> Vec<(usize, usize)> {
+    let graphemes: Vec<&str> = line.graphemes(true).collect();
+    let is_letter = |i: usize| {
+        graphemes
+            .get(i)
+            .is_some_and(|g| g.chars().next().is_some_and(char::is_alphabetic))
+    };
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < graphemes.len() {
+        if !is_letter(i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while is_letter(i) || (matches!(graphemes[i], "'" | "’") && is_letter(i + 1)) {
+            i += 1;
+            if i == graphemes.len() {
+                break;
+            }
+        }
+        spans.push((start, i - start));
+    }
+    spans
+}
+
+/// Columns of `line` not checked: inline code, `[[links]]`, link targets,
+/// and whitespace separated runs that are URLs, `#tags`, `@mentions` or
+/// smart block markers.
+fn skipped_columns(line: &str) -> Vec<bool> {
+    let graphemes: Vec<&str> = line.graphemes(true).collect();
+    let mut skipped = vec![false; graphemes.len()];
+    let mut code = false;
+    let mut link_depth = 0;
+    let mut target = false;
+    for i in 0..graphemes.len() {
+        let g = graphemes[i];
+        if g == "`" {
+            code = !code;
+        } else if g == "[" && graphemes.get(i + 1) == Some(&"[") {
+            link_depth += 1;
+        } else if g == "]" && graphemes.get(i + 1) == Some(&"(") {
+            target = true;
+        } else if g == ")" {
+            target = false;
+        }
+        skipped[i] = code || link_depth > 0 || target;
+        if g == "]" && i > 0 && graphemes[i - 1] == "]" {
+            link_depth = 0;
+        }
+    }
+    let mut start = 0;
+    for end in
+        (0..=graphemes.len()).filter(|&i| i == graphemes.len() || graphemes[i].trim().is_empty())
+    {
+        let run = graphemes[start..end].concat();
+        let run = run.trim_start_matches(['(', '"', '*', '_']);
+        if run.contains("://")
+            || run.starts_with("www.")
+            || run.starts_with(['#', '@'])
+            || run.starts_with(":::")
+        {
+            skipped[start..end].fill(true);
+        }
+        start = end + 1;
+    }
+    skipped
+}
+
+/// Words of `lines` not in `dictionary`, as (row, grapheme column,
+/// length). Frontmatter, fenced code, words in a non-alphabetic script
+/// (CJK) and words joined to digits are not checked.
+pub fn spelling_errors(dictionary: &Dictionary, lines: &[&str]) -> Vec<SearchMatch> {
+    let mut errors = Vec::new();
+    let first = frontmatter_end(lines).map_or(0, |end| end + 1);
+    let mut fenced = false;
+    for (row, line) in lines.iter().enumerate().skip(first) {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            continue;
+        }
+        let graphemes: Vec<&str> = line.graphemes(true).collect();
+        let skipped = skipped_columns(line);
+        let joined = |i: Option<usize>| {
+            i.and_then(|i| graphemes.get(i))
+                .is_some_and(|g| g.chars().any(|c| c.is_numeric() || c == '_'))
+        };
+        for (col, len) in word_spans(line) {
+            let word = graphemes[col..col + len].concat();
+            if skipped[col..col + len].iter().any(|s| *s)
+                || word.chars().any(|c| c >= '\u{2E80}')
+                || joined(col.checked_sub(1))
+                || joined(Some(col + len))
+                || dictionary.contains(&word)
+            {
+                continue;
+            }
+            errors.push((row, col, len));
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nPFX U Y 1\nPFX U 0 un .\nSFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\nSFX D N 1\nSFX D 0 ed [^e]\n";
+    const DIC: &str = "5\ncity/S\nhappy/U\ndo/U\nwalk/DS\nParis\nisn't";
+
+    #[test]
+    fn test_affixes_and_case() {
+        let dictionary = Dictionary::parse(DIC, Some(AFF));
+        for word in [
+            "cities", "unhappy", "walked", "walks", "Walks", "CITY", "isn’t",
+        ] {
+            assert!(dictionary.contains(word), "{}", word);
+        }
+        for word in ["citys", "paris", "unwalk", "WaLk"] {
+            assert!(!dictionary.contains(word), "{}", word);
+        }
+        assert!(Dictionary::parse("walk/DS", None).contains("walk"));
+    }
+
+    #[test]
+    fn test_suggestions_nearest_first() {
+        let dictionary = Dictionary::parse(DIC, Some(AFF));
+        assert_eq!(dictionary.suggest("wlak", 3), ["walk", "walks"]);
+        assert_eq!(dictionary.suggest("Citiez", 1), ["Cities"]);
+    }
+
+    #[test]
+    fn test_spelling_errors_skip_markup() {
+        let dictionary = Dictionary::parse(DIC, Some(AFF));
+        let lines = [
+            "---",
+            "title: qqq",
+            "---",
+            "walk the citty `qqq` #qqq [[qqq]] [do](http://qqq) h2o 中文",
+            "```",
+            "qqq",
+            "```",
+            "isn't Paris zzz's",
+        ];
+        assert_eq!(
+            spelling_errors(&dictionary, &lines),
+            [(3, 5, 3), (3, 9, 5), (7, 12, 5)]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::buffer::TextBuffer;
+
+/// A compiled `:s` pattern and replacement.
+#[derive(Debug, Clone)]
+pub struct Substitution {
+    regex: Regex,
+    /// Replacement in `regex` syntax (`${1}`, `$$`).
+    replacement: String,
+    global: bool,
+}
+
+impl Substitution {
+    /// Compile `pattern`. `replacement` uses Vim syntax: `&` is the whole
+    /// match, `\1`..`\9` are groups, and `\&` is a literal `&`.
+    pub fn new(pattern: &str, replacement: &str, global: bool, ignore_case: bool) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .with_context(|| format!("Invalid pattern: {}", pattern))?;
+        Ok(Self {
+            regex,
+            replacement: translate_replacement(replacement),
+            global,
+        })
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        self.regex.is_match(line)
+    }
+
+    /// Byte range of the first match at or after byte `from` on `line`.
+    fn find_at(&self, line: &str, from: usize) -> Option<Range<usize>> {
+        if from > line.len() {
+            return None;
+        }
+        self.regex.find_at(line, from).map(|m| m.range())
+    }
+
+    /// The replacement text for the match at `range`.
+    fn expand(&self, line: &str, range: &Range<usize>) -> String {
+        let mut out = String::new();
+        if let Some(caps) = self.regex.captures_at(line, range.start) {
+            caps.expand(&self.replacement, &mut out);
+        }
+        out
+    }
+
+    /// Replace the first match on `line`, or every match with `g`.
+    /// Returns the new line and the number of replacements.
+    pub fn apply(&self, line: &str) -> Option<(String, usize)> {
+        let (limit, count) = if self.global {
+            (0, self.regex.find_iter(line).count())
+        } else {
+            (1, usize::from(self.regex.is_match(line)))
+        };
+        if count == 0 {
+            return None;
+        }
+        let replaced = self.regex.replacen(line, limit, self.replacement.as_str());
+        Some((replaced.into_owned(), count))
+    }
+}
+
+fn translate_replacement(vim: &str) -> String {
+    let mut out = String::new();
+    let mut chars = vim.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(d @ '0'..='9') => out.push_str(&format!("${{{}}}", d)),
+                Some('t') => out.push('\t'),
+                Some('$') | None => out.push_str("$$"),
+                Some(other) => out.push(other),
+            },
+            '&' => out.push_str("${0}"),
+            '$' => out.push_str("$$"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// An interactive `:s///c` run, stepping through matches one at a time.
+#[derive(Debug)]
+pub struct SubstituteSession {
+    sub: Substitution,
+    row: usize,
+    /// Byte offset on `row` to search from.
+    col: usize,
+    end_row: usize,
+    current: Option<Range<usize>>,
+    pub replaced: usize,
+}
+
+impl SubstituteSession {
+    pub fn new(sub: Substitution, start_row: usize, end_row: usize) -> Self {
+        Self {
+            sub,
+            row: start_row,
+            col: 0,
+            end_row,
+            current: None,
+            replaced: 0,
+        }
+    }
+
+    /// Move the cursor to the next match. Returns false when there are none
+    /// left in the range.
+    pub fn advance(&mut self, buffer: &mut TextBuffer) -> bool {
+        while self.row <= self.end_row && self.row < buffer.line_count() {
+            let line = &buffer.content()[self.row];
+            if let Some(range) = self.sub.find_at(line, self.col) {
+                let col = line[..range.start].graphemes(true).count();
+                self.current = Some(range);
+                buffer.set_cursor(self.row, col);
+                return true;
+            }
+            self.next_row();
+        }
+        self.current = None;
+        false
+    }
+
+    /// Replace the current match.
+    pub fn replace(&mut self, buffer: &mut TextBuffer) {
+        let Some(range) = self.current.take() else {
+            return;
+        };
+        let line = &buffer.content()[self.row];
+        let text = self.sub.expand(line, &range);
+        let mut new_line = line.clone();
+        new_line.replace_range(range.clone(), &text);
+        buffer.replace_line(self.row, new_line);
+        self.replaced += 1;
+        self.step_past(buffer, range.start + text.len(), range.is_empty());
+    }
+
+    /// Leave the current match as it is.
+    pub fn skip(&mut self, buffer: &TextBuffer) {
+        if let Some(range) = self.current.take() {
+            self.step_past(buffer, range.end, range.is_empty());
+        }
+    }
+
+    fn step_past(&mut self, buffer: &TextBuffer, col: usize, empty_match: bool) {
+        if !self.sub.global {
+            self.next_row();
+            return;
+        }
+        // An empty match would be found again at the same offset
+        let line = &buffer.content()[self.row];
+        self.col = if empty_match {
+            line[col..]
+                .chars()
+                .next()
+                .map_or(line.len() + 1, |c| col + c.len_utf8())
+        } else {
+            col
+        };
+    }
+
+    fn next_row(&mut self) {
+        self.row += 1;
+        self.col = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_first_or_global() {
+        let sub = Substitution::new("o", "0", false, false).unwrap();
+        assert_eq!(sub.apply("foo boo"), Some(("f0o boo".to_string(), 1)));
+        let sub = Substitution::new("o", "0", true, false).unwrap();
+        assert_eq!(sub.apply("foo boo"), Some(("f00 b00".to_string(), 4)));
+        assert_eq!(sub.apply("bar"), None);
+    }
+
+    #[test]
+    fn test_vim_replacement_syntax() {
+        let sub = Substitution::new(r"(\w+)@(\w+)", r"\2 at \1 (&) \& $5", false, false).unwrap();
+        assert_eq!(sub.apply("me@home").unwrap().0, "home at me (me@home) & $5");
+
+        let sub = Substitution::new("todo", "DONE", false, true).unwrap();
+        assert_eq!(sub.apply("TODO: x").unwrap().0, "DONE: x");
+    }
+
+    #[test]
+    fn test_buffer_substitute_range() {
+        let mut buffer = TextBuffer::from_string("a1\nb2\nc3\nd4");
+        let sub = Substitution::new(r"\d", "#", false, false).unwrap();
+        assert_eq!(buffer.substitute(&sub, 1, 2), (2, 2));
+        assert_eq!(buffer.to_string(), "a1\nb#\nc#\nd4");
+        assert_eq!(buffer.cursor_position(), (2, 0));
+    }
+
+    #[test]
+    fn test_session_confirms_each_match() {
+        let mut buffer = TextBuffer::from_string("cat cat\ncat");
+        let sub = Substitution::new("cat", "dog", true, false).unwrap();
+        let mut session = SubstituteSession::new(sub, 0, 1);
+
+        assert!(session.advance(&mut buffer));
+        session.skip(&buffer);
+        assert!(session.advance(&mut buffer));
+        assert_eq!(buffer.cursor_position(), (0, 4));
+        session.replace(&mut buffer);
+        assert!(session.advance(&mut buffer));
+        assert_eq!(buffer.cursor_position(), (1, 0));
+        session.replace(&mut buffer);
+        assert!(!session.advance(&mut buffer));
+
+        assert_eq!(session.replaced, 2);
+        assert_eq!(buffer.to_string(), "cat dog\ndog");
+    }
+
+    #[test]
+    fn test_session_empty_matches_terminate() {
+        let mut buffer = TextBuffer::from_string("ab");
+        let sub = Substitution::new("x*", "-", true, false).unwrap();
+        let mut session = SubstituteSession::new(sub, 0, 0);
+        while session.advance(&mut buffer) {
+            session.replace(&mut buffer);
+        }
+        assert_eq!(buffer.to_string(), "-a-b-");
+    }
+}
@@ -0,0 +1,196 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Text objects selected after an operator: `iw`, `aw`, `i"`, `a(`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextObject {
+    /// The word (or run of punctuation / whitespace) under the cursor;
+    /// `around` also takes the following (or else preceding) whitespace.
+    Word { around: bool },
+    /// Text between a delimiter pair such as `""` or `()`; `around` includes
+    /// the delimiters.
+    Pair {
+        open: char,
+        close: char,
+        around: bool,
+    },
+}
+
+impl TextObject {
+    /// Resolve the key typed after `i`/`a` into a text object.
+    pub fn from_key(key: char, around: bool) -> Option<Self> {
+        let (open, close) = match key {
+            'w' => return Some(TextObject::Word { around }),
+            '"' | '\'' | '`' => (key, key),
+            '(' | ')' | 'b' => ('(', ')'),
+            '[' | ']' => ('[', ']'),
+            '{' | '}' | 'B' => ('{', '}'),
+            '<' | '>' => ('<', '>'),
+            _ => return None,
+        };
+        Some(TextObject::Pair {
+            open,
+            close,
+            around,
+        })
+    }
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punct,
+}
+
+fn class_of(grapheme: &str) -> CharClass {
+    if grapheme.chars().all(char::is_whitespace) {
+        CharClass::Space
+    } else if grapheme.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+fn is(grapheme: &str, c: char) -> bool {
+    grapheme.chars().eq(std::iter::once(c))
+}
+
+/// Grapheme column range `[start, end)` of `obj` on `line` with the cursor at
+/// `col`, or `None` if there is nothing to select. Objects never span lines.
+pub fn text_object_range(line: &str, col: usize, obj: TextObject) -> Option<(usize, usize)> {
+    let g: Vec<&str> = line.graphemes(true).collect();
+    if g.is_empty() {
+        return None;
+    }
+    let col = col.min(g.len() - 1);
+
+    match obj {
+        TextObject::Word { around } => {
+            let class = class_of(g[col]);
+            let mut start = col;
+            while start > 0 && class_of(g[start - 1]) == class {
+                start -= 1;
+            }
+            let mut end = col + 1;
+            while end < g.len() && class_of(g[end]) == class {
+                end += 1;
+            }
+            if around && class != CharClass::Space {
+                let trailing = end;
+                while end < g.len() && class_of(g[end]) == CharClass::Space {
+                    end += 1;
+                }
+                if end == trailing {
+                    while start > 0 && class_of(g[start - 1]) == CharClass::Space {
+                        start -= 1;
+                    }
+                }
+            }
+            Some((start, end))
+        }
+        TextObject::Pair {
+            open,
+            close,
+            around,
+        } => {
+            let (open_at, close_at) = if open == close {
+                quote_pair(&g, col, open)?
+            } else {
+                bracket_pair(&g, col, open, close)?
+            };
+            if around {
+                Some((open_at, close_at + 1))
+            } else {
+                Some((open_at + 1, close_at))
+            }
+        }
+    }
+}
+
+/// Quotes pair up left to right; take the first pair that ends at or after
+/// the cursor, like Vim does when the cursor is before the first quote.
+fn quote_pair(g: &[&str], col: usize, quote: char) -> Option<(usize, usize)> {
+    let quotes: Vec<usize> = (0..g.len()).filter(|&i| is(g[i], quote)).collect();
+    quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, close)| col <= close)
+}
+
+/// The innermost `open`/`close` pair enclosing the cursor, respecting nesting.
+fn bracket_pair(g: &[&str], col: usize, open: char, close: char) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut open_at = None;
+    for i in (0..=col).rev() {
+        if is(g[i], close) && i != col {
+            depth += 1;
+        } else if is(g[i], open) {
+            if depth == 0 {
+                open_at = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let open_at = open_at?;
+
+    let mut depth = 0;
+    for (i, grapheme) in g.iter().enumerate().skip(open_at + 1) {
+        if is(grapheme, open) {
+            depth += 1;
+        } else if is(grapheme, close) {
+            if depth == 0 {
+                return Some((open_at, i));
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(line: &str, col: usize, key: char, around: bool) -> Option<String> {
+        let obj = TextObject::from_key(key, around)?;
+        let (start, end) = text_object_range(line, col, obj)?;
+        Some(line.graphemes(true).skip(start).take(end - start).collect())
+    }
+
+    #[test]
+    fn test_word_objects() {
+        assert_eq!(select("say hello, world", 5, 'w', false).unwrap(), "hello");
+        assert_eq!(select("say hello, world", 5, 'w', true).unwrap(), " hello");
+        assert_eq!(select("say hello world", 5, 'w', true).unwrap(), "hello ");
+        assert_eq!(select("say hello", 6, 'w', true).unwrap(), " hello");
+        assert_eq!(select("你好 世界", 0, 'w', false).unwrap(), "你好");
+    }
+
+    #[test]
+    fn test_quote_objects() {
+        let line = r#"say "hi there" and "bye""#;
+        assert_eq!(select(line, 6, '"', false).unwrap(), "hi there");
+        assert_eq!(select(line, 6, '"', true).unwrap(), "\"hi there\"");
+        assert_eq!(select(line, 0, '"', false).unwrap(), "hi there");
+        assert_eq!(select(line, 21, '"', false).unwrap(), "bye");
+        assert_eq!(
+            select("run `cargo test` now", 8, '`', false).unwrap(),
+            "cargo test"
+        );
+        assert!(select("no quotes", 2, '"', false).is_none());
+    }
+
+    #[test]
+    fn test_bracket_objects() {
+        let line = "f(a, (b), c)";
+        assert_eq!(select(line, 2, '(', false).unwrap(), "a, (b), c");
+        assert_eq!(select(line, 6, ')', false).unwrap(), "b");
+        assert_eq!(select(line, 5, 'b', true).unwrap(), "(b)");
+        assert_eq!(select(line, 1, '(', false).unwrap(), "a, (b), c");
+        assert_eq!(select(line, 11, '(', true).unwrap(), "(a, (b), c)");
+        assert!(select(line, 0, '(', false).is_none());
+        assert!(select("x", 0, 'q', false).is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use std::cell::Cell;
+
+/// Display rows kept between the cursor and the top or bottom edge when the
+/// view follows the cursor.
+pub const SCROLL_OFF: u16 = 2;
+
+/// Where `zz`, `zt` and `zb` put the cursor row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewAlign {
+    Center,
+    Top,
+    Bottom,
+}
+
+/// Scroll position of the editor, in display rows and columns.
+///
+/// The view only moves when the cursor would leave it, when it is aligned
+/// with `zz`/`zt`/`zb`, or when it is scrolled by a page. Fields are `Cell`s
+/// because the position is settled while drawing, which only has `&App`.
+#[derive(Debug, Clone, Default)]
+pub struct Viewport {
+    top: Cell<u16>,
+    left: Cell<u16>,
+    /// Wrap width and height of the editor at the last draw.
+    size: Cell<(u16, u16)>,
+    align: Cell<Option<ViewAlign>>,
+}
+
+impl Viewport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn top(&self) -> u16 {
+        self.top.get()
+    }
+
+    pub fn left(&self) -> u16 {
+        self.left.get()
+    }
+
+    pub fn set_left(&self, left: u16) {
+        self.left.set(left);
+    }
+
+    /// Wrap width and height of the editor at the last draw; `(0, 0)`
+    /// before the first.
+    pub fn size(&self) -> (u16, u16) {
+        self.size.get()
+    }
+
+    /// Scroll back to the start, e.g. for another note.
+    pub fn reset(&self) {
+        self.top.set(0);
+        self.left.set(0);
+        self.align.set(None);
+    }
+
+    /// Put the cursor row at the center, top or bottom on the next draw.
+    pub fn align(&self, align: ViewAlign) {
+        self.align.set(Some(align));
+    }
+
+    /// Move the top row by `rows` (negative scrolls up), at most to `max_top`.
+    pub fn scroll_by(&self, rows: i32, max_top: u16) {
+        let top = (self.top.get() as i32 + rows).clamp(0, max_top as i32);
+        self.top.set(top as u16);
+    }
+
+    /// Settle the top row for a cursor on display row `cursor` in an editor
+    /// `height` rows tall that wraps at `width`, and return it.
+    pub fn follow(&self, cursor: u16, width: u16, height: u16) -> u16 {
+        self.size.set((width, height));
+        if height == 0 {
+            return 0;
+        }
+        let off = SCROLL_OFF.min(height.saturating_sub(1) / 2);
+        let mut top = self.top.get();
+        match self.align.take() {
+            Some(ViewAlign::Center) => top = cursor.saturating_sub(height / 2),
+            Some(ViewAlign::Top) => top = cursor.saturating_sub(off),
+            Some(ViewAlign::Bottom) => top = (cursor + off + 1).saturating_sub(height),
+            None if cursor < top + off => top = cursor.saturating_sub(off),
+            None if cursor + off >= top + height => top = cursor + off + 1 - height,
+            None => {}
+        }
+        self.top.set(top);
+        top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_keeps_scroll_off() {
+        let view = Viewport::new();
+        assert_eq!(view.follow(5, 80, 10), 0);
+        assert_eq!(view.follow(7, 80, 10), 0);
+        assert_eq!(view.follow(8, 80, 10), 1);
+        assert_eq!(view.follow(20, 80, 10), 13);
+        // Moving back up inside the view does not scroll
+        assert_eq!(view.follow(16, 80, 10), 13);
+        assert_eq!(view.follow(14, 80, 10), 12);
+        assert_eq!(view.size(), (80, 10));
+    }
+
+    #[test]
+    fn test_align_and_scroll() {
+        let view = Viewport::new();
+        view.align(ViewAlign::Center);
+        assert_eq!(view.follow(50, 80, 10), 45);
+        view.align(ViewAlign::Top);
+        assert_eq!(view.follow(50, 80, 10), 48);
+        view.align(ViewAlign::Bottom);
+        assert_eq!(view.follow(50, 80, 10), 43);
+        // The alignment holds on the next draw
+        assert_eq!(view.follow(50, 80, 10), 43);
+
+        view.scroll_by(-100, 90);
+        assert_eq!(view.top(), 0);
+        view.scroll_by(100, 90);
+        assert_eq!(view.top(), 90);
+    }
+}
@@ -1,7 +1,10 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use super::markdown_fmt::MarkdownFormat;
-use crate::types::{AppMode, KeyboardConfig};
+use super::panes::{PaneDir, SplitDir};
+use super::text_object::TextObject;
+use super::viewport::ViewAlign;
+use crate::types::{AppMode, KeyboardConfig, LeaderCommand};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Motion {
@@ -12,6 +15,11 @@ pub enum Motion {
     LineStart,
     FileEnd,
     FileStart,
+    ParagraphForward,
+    ParagraphBackward,
+    SentenceForward,
+    SentenceBackward,
+    TextObject(TextObject),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +35,15 @@ pub enum VimAction {
     MoveLineEnd,
     MoveFileStart,
     MoveFileEnd,
+    /// `}` / `{`: next / previous blank line.
+    MoveParagraphForward,
+    MoveParagraphBackward,
+    /// `)` / `(`: next / previous sentence start.
+    MoveSentenceForward,
+    MoveSentenceBackward,
+    NextOpenTask,
+    PrevOpenTask,
+    FirstOpenTask,
     InsertMode,
     InsertModeAppend,
     InsertModeLineEnd,
@@ -61,11 +78,18 @@ pub enum VimAction {
     LeaderList,
     LeaderNew,
     LeaderProcess,
+    LeaderCheatSheet,
+    LeaderStats,
+    LeaderShare,
+    LeaderRestartWatcher,
     ToggleHints,
     InsertCheckbox,
     ToggleCheckbox,
     CycleTheme,
     Search,
+    /// Edit the list view filter (`f`); an alternative search key in the editor.
+    Filter,
+    EnterCommandMode,
     SearchNext,
     SearchPrev,
     ClearSearch,
@@ -73,6 +97,8 @@ pub enum VimAction {
     Quit,
     Delete(Motion),
     Yank(Motion),
+    /// Delete the motion's text and enter Insert mode (`cw`, `ci"`, `cc`).
+    Change(Motion),
     VisualDelete,
     VisualYank,
     PasteAfter,
@@ -82,13 +108,195 @@ pub enum VimAction {
     VisualToggleComment,
     ToggleFormat(MarkdownFormat),
     VisualToggleFormat(MarkdownFormat),
+    /// Replay the last change (`.`).
+    RepeatLastChange,
+    /// Delete the prompt text before the cursor (Ctrl-U).
+    PromptDeleteToStart,
+    /// Delete the word before the prompt cursor (Ctrl-W).
+    PromptDeleteWord,
+    /// Save the cursor position as mark `{a-z}` (`ma`).
+    SetMark(char),
+    /// Jump to a mark's line (`'a`) or exact position (`` `a ``).
+    JumpToMark {
+        mark: char,
+        exact: bool,
+    },
+    /// Replace the character under the cursor (`r{char}`).
+    ReplaceChar(char),
+    /// Overtype characters until Esc (`R`).
+    EnterReplaceMode,
+    /// Add the count to the number at or after the cursor (Ctrl-A).
+    IncrementNumber,
+    /// Subtract the count from it (Ctrl-X).
+    DecrementNumber,
+    /// Older position in the jump list (Ctrl-O).
+    JumpBack,
+    /// Newer position in the jump list (Ctrl-I).
+    JumpForward,
+    /// Scroll half a screen and move the cursor along (Ctrl-D / Ctrl-U).
+    ScrollHalfPage {
+        down: bool,
+    },
+    /// Scroll a screen (Ctrl-F / Ctrl-B).
+    ScrollPage {
+        down: bool,
+    },
+    /// Put the cursor row at the center, top or bottom (`zz`, `zt`, `zb`).
+    AlignView(ViewAlign),
+    /// Open or close the fold under the cursor (`za`).
+    ToggleFold,
+    /// Open every fold (`zR`).
+    OpenAllFolds,
+    /// Fold every section and code block (`zM`).
+    CloseAllFolds,
+    /// Switch to the next (`]b`) or previous (`[b`) open note.
+    CycleBuffer {
+        forward: bool,
+    },
+    /// Jump to the next (`]q`) or previous (`[q`) location of the
+    /// locations panel.
+    CycleLocation {
+        forward: bool,
+    },
+    /// Jump to the next (`]s`) or previous (`[s`) misspelled word.
+    CycleSpellError {
+        forward: bool,
+    },
+    /// Offer corrections for the word under the cursor (`z=`).
+    SpellSuggest,
+    /// Open the buffer picker (Space B).
+    LeaderBuffers,
+    /// Show or hide the outline sidebar (Space o).
+    LeaderOutline,
+    /// Open the `[[link]]` under the cursor (`gf` / Enter).
+    FollowLink,
+    /// List the notes linking to this one (Space L).
+    LeaderBacklinks,
+    /// Open the tag browser (Space t).
+    LeaderTags,
+    /// Rename the note (Space r).
+    LeaderRename,
+    /// Set the `title` field to the first heading (Space T).
+    LeaderSyncTitle,
+    /// Commit the data directory and pull/push its git remote (Space g).
+    LeaderGitSync,
+    /// Open or create today's daily note (Space D).
+    LeaderToday,
+    /// Go to the next (`]d`) or previous (`[d`) daily note.
+    CycleDailyNote {
+        forward: bool,
+    },
+    /// Split the focused pane (`Ctrl-W s` / `Ctrl-W v`).
+    SplitPane(SplitDir),
+    /// Focus the pane in a direction (`Ctrl-W h/j/k/l`).
+    FocusPane(PaneDir),
+    /// Focus the next (`Ctrl-W w`) or previous (`Ctrl-W W`) pane.
+    CyclePane {
+        forward: bool,
+    },
+    /// Close the focused pane (`Ctrl-W c` / `Ctrl-W q`).
+    ClosePane,
+    /// Close every other pane (`Ctrl-W o`).
+    OnlyPane,
+    /// Make the focused pane taller or shorter (`Ctrl-W +` / `Ctrl-W -`,
+    /// `Horizontal`) or wider or narrower (`Ctrl-W >` / `Ctrl-W <`,
+    /// `Vertical`).
+    ResizePane {
+        dir: SplitDir,
+        grow: bool,
+    },
+    /// Split every pane evenly (`Ctrl-W =`).
+    EqualizePanes,
+}
+
+impl VimAction {
+    /// Whether this action modifies buffer content or note storage
+    /// (including entering Insert mode, which leads to modification).
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            VimAction::InsertMode
+                | VimAction::InsertModeAppend
+                | VimAction::InsertModeLineEnd
+                | VimAction::InsertModeLineStart
+                | VimAction::InsertLineBelow
+                | VimAction::InsertLineAbove
+                | VimAction::DeleteChar
+                | VimAction::DeleteLine
+                | VimAction::Backspace
+                | VimAction::InsertChar(_)
+                | VimAction::InsertTab
+                | VimAction::Indent
+                | VimAction::Dedent
+                | VimAction::InsertNewline
+                | VimAction::VisualBlockInsertStart
+                | VimAction::VisualBlockInsertEnd
+                | VimAction::VisualLineInsertStart
+                | VimAction::VisualLineInsertEnd
+                | VimAction::VisualIndent
+                | VimAction::VisualDedent
+                | VimAction::Undo
+                | VimAction::Redo
+                | VimAction::LeaderNew
+                | VimAction::LeaderProcess
+                | VimAction::LeaderCheatSheet
+                | VimAction::LeaderRename
+                | VimAction::LeaderSyncTitle
+                | VimAction::InsertCheckbox
+                | VimAction::ToggleCheckbox
+                | VimAction::ExternalEditor
+                | VimAction::Delete(_)
+                | VimAction::Change(_)
+                | VimAction::VisualDelete
+                | VimAction::PasteAfter
+                | VimAction::PasteBefore
+                | VimAction::ToggleComment
+                | VimAction::VisualToggleComment
+                | VimAction::ToggleFormat(_)
+                | VimAction::VisualToggleFormat(_)
+                | VimAction::RepeatLastChange
+                | VimAction::ReplaceChar(_)
+                | VimAction::EnterReplaceMode
+                | VimAction::IncrementNumber
+                | VimAction::DecrementNumber
+        )
+    }
+
+    /// Normal mode changes that `.` can repeat.
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            VimAction::InsertMode
+                | VimAction::InsertModeAppend
+                | VimAction::InsertModeLineEnd
+                | VimAction::InsertModeLineStart
+                | VimAction::InsertLineBelow
+                | VimAction::InsertLineAbove
+                | VimAction::DeleteChar
+                | VimAction::DeleteLine
+                | VimAction::Indent
+                | VimAction::Dedent
+                | VimAction::InsertCheckbox
+                | VimAction::ToggleCheckbox
+                | VimAction::Delete(_)
+                | VimAction::Change(_)
+                | VimAction::PasteAfter
+                | VimAction::PasteBefore
+                | VimAction::ToggleComment
+                | VimAction::ToggleFormat(_)
+                | VimAction::ReplaceChar(_)
+                | VimAction::EnterReplaceMode
+                | VimAction::IncrementNumber
+                | VimAction::DecrementNumber
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum LeaderState {
     Inactive,
-    AwaitingFirst,
-    AwaitingSecond(char),
+    /// Space was pressed; holds the keys typed after it so far.
+    Pending(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,6 +304,7 @@ enum OperatorPending {
     None,
     Delete,
     Yank,
+    Change,
 }
 
 #[derive(Debug, Clone)]
@@ -105,7 +314,62 @@ pub struct VimMode {
     visual_g_pending: bool,
     normal_g_pending: bool,
     gc_pending: bool,
+    /// `]` or `[` was pressed, waiting for the bracket motion target.
+    bracket_pending: Option<char>,
+    /// `i` (false) or `a` (true) was pressed after an operator, waiting for
+    /// the text object key.
+    text_object_pending: Option<bool>,
+    /// `m`, `'` or `` ` `` was pressed, waiting for the mark letter.
+    mark_pending: Option<char>,
+    /// `r` was pressed, waiting for the replacement character.
+    replace_pending: bool,
+    /// `z` was pressed, waiting for `z`, `t` or `b`.
+    scroll_pending: bool,
+    /// Ctrl-W was pressed, waiting for the window command.
+    window_pending: bool,
+    /// Count typed before a command (`5` in `5j`), or after an operator (`3` in `d3w`).
+    pending_count: Option<usize>,
+    /// Count typed before an operator (`2` in `2dw`).
+    operator_count: Option<usize>,
+    /// Repeat count of the most recently resolved action.
+    count: usize,
     keys: KeyboardConfig,
+    /// `keys.leader_bindings()`, resolved once.
+    leader: Vec<(String, LeaderCommand)>,
+}
+
+/// Upper bound for count prefixes so a mistyped `99999999j` stays cheap.
+const MAX_COUNT: usize = 9999;
+
+/// What a completed leader sequence does in Normal mode.
+fn leader_action(command: LeaderCommand) -> VimAction {
+    match command {
+        LeaderCommand::Process => VimAction::LeaderProcess,
+        LeaderCommand::List => VimAction::LeaderList,
+        LeaderCommand::NewNote => VimAction::LeaderNew,
+        LeaderCommand::Quit => VimAction::Quit,
+        LeaderCommand::ToggleHints => VimAction::ToggleHints,
+        LeaderCommand::ToggleCheckbox => VimAction::ToggleCheckbox,
+        LeaderCommand::InsertCheckbox => VimAction::InsertCheckbox,
+        LeaderCommand::Bold => VimAction::ToggleFormat(MarkdownFormat::Bold),
+        LeaderCommand::Italic => VimAction::ToggleFormat(MarkdownFormat::Italic),
+        LeaderCommand::Strikethrough => VimAction::ToggleFormat(MarkdownFormat::Strikethrough),
+        LeaderCommand::InlineCode => VimAction::ToggleFormat(MarkdownFormat::InlineCode),
+        LeaderCommand::CodeBlock => VimAction::ToggleFormat(MarkdownFormat::CodeBlock),
+        LeaderCommand::WritingStats => VimAction::LeaderStats,
+        LeaderCommand::Export => VimAction::LeaderShare,
+        LeaderCommand::Today => VimAction::LeaderToday,
+        LeaderCommand::RestartWatcher => VimAction::LeaderRestartWatcher,
+        LeaderCommand::Buffers => VimAction::LeaderBuffers,
+        LeaderCommand::Outline => VimAction::LeaderOutline,
+        LeaderCommand::Backlinks => VimAction::LeaderBacklinks,
+        LeaderCommand::Tags => VimAction::LeaderTags,
+        LeaderCommand::Rename => VimAction::LeaderRename,
+        LeaderCommand::SyncTitle => VimAction::LeaderSyncTitle,
+        LeaderCommand::GitSync => VimAction::LeaderGitSync,
+        LeaderCommand::CheatSheet => VimAction::LeaderCheatSheet,
+        LeaderCommand::None => VimAction::None,
+    }
 }
 
 impl Default for VimMode {
@@ -122,6 +386,16 @@ impl VimMode {
             visual_g_pending: false,
             normal_g_pending: false,
             gc_pending: false,
+            bracket_pending: None,
+            text_object_pending: None,
+            mark_pending: None,
+            replace_pending: false,
+            scroll_pending: false,
+            window_pending: false,
+            pending_count: None,
+            operator_count: None,
+            count: 1,
+            leader: KeyboardConfig::default().leader_bindings(),
             keys: KeyboardConfig::default(),
         }
     }
@@ -133,6 +407,16 @@ impl VimMode {
             visual_g_pending: false,
             normal_g_pending: false,
             gc_pending: false,
+            bracket_pending: None,
+            text_object_pending: None,
+            mark_pending: None,
+            replace_pending: false,
+            scroll_pending: false,
+            window_pending: false,
+            pending_count: None,
+            operator_count: None,
+            count: 1,
+            leader: config.leader_bindings(),
             keys: config,
         }
     }
@@ -145,12 +429,99 @@ impl VimMode {
         self.leader_state = LeaderState::Inactive;
     }
 
+    /// The leader sequences in effect, for the leader popup.
+    pub fn leader_bindings(&self) -> &[(String, LeaderCommand)] {
+        &self.leader
+    }
+
+    /// Keys typed after Space so far, while a leader sequence is pending.
+    pub fn leader_prefix(&self) -> Option<&str> {
+        match &self.leader_state {
+            LeaderState::Pending(prefix) => Some(prefix),
+            LeaderState::Inactive => None,
+        }
+    }
+
+    /// Take `key` as the next key of the pending leader sequence: the
+    /// command once a sequence is complete, None while it may still grow
+    /// or when nothing starts with it (which ends the sequence).
+    fn advance_leader(&mut self, key: &KeyEvent) -> Option<LeaderCommand> {
+        let LeaderState::Pending(prefix) = &self.leader_state else {
+            return None;
+        };
+        let KeyCode::Char(c) = key.code else {
+            self.leader_state = LeaderState::Inactive;
+            return None;
+        };
+        let sequence = format!("{}{}", prefix, c);
+        if let Some((_, command)) = self.leader.iter().find(|(k, _)| *k == sequence) {
+            let command = *command;
+            self.leader_state = LeaderState::Inactive;
+            return Some(command);
+        }
+        self.leader_state = if self.leader.iter().any(|(k, _)| k.starts_with(&sequence)) {
+            LeaderState::Pending(sequence)
+        } else {
+            LeaderState::Inactive
+        };
+        None
+    }
+
     pub fn is_operator_pending(&self) -> bool {
         self.operator_state != OperatorPending::None
     }
 
     pub fn clear_operator(&mut self) {
         self.operator_state = OperatorPending::None;
+        self.text_object_pending = None;
+        self.pending_count = None;
+        self.operator_count = None;
+    }
+
+    /// How many times to repeat the action last returned by `handle_key`
+    /// in Normal mode (`5j` → 5, `2d3w` → 6). Always at least 1.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Whether a multi-key normal mode command is still being typed.
+    fn is_sequence_pending(&self) -> bool {
+        self.leader_state != LeaderState::Inactive
+            || self.operator_state != OperatorPending::None
+            || self.normal_g_pending
+            || self.gc_pending
+            || self.bracket_pending.is_some()
+            || self.mark_pending.is_some()
+            || self.replace_pending
+            || self.scroll_pending
+            || self.window_pending
+    }
+
+    /// Whether a Normal mode command, count included, is still being typed.
+    pub fn is_command_pending(&self) -> bool {
+        self.is_sequence_pending() || self.pending_count.is_some() || self.operator_count.is_some()
+    }
+
+    /// A digit extending the count. `0` only counts after another digit,
+    /// otherwise it keeps its line-start meaning.
+    fn count_digit(&self, key: &KeyEvent) -> Option<usize> {
+        let KeyCode::Char(c) = key.code else {
+            return None;
+        };
+        if key.modifiers.contains(KeyModifiers::CONTROL)
+            || self.leader_state != LeaderState::Inactive
+            || self.normal_g_pending
+            || self.gc_pending
+            || self.bracket_pending.is_some()
+            || self.mark_pending.is_some()
+            || self.replace_pending
+            || self.scroll_pending
+            || self.window_pending
+            || (c == '0' && self.pending_count.is_none())
+        {
+            return None;
+        }
+        c.to_digit(10).map(|d| d as usize)
     }
 
     fn key_matches(&self, c: char, binding: &str) -> bool {
@@ -177,13 +548,36 @@ impl VimMode {
         match mode {
             AppMode::Normal => self.handle_normal_mode(key),
             AppMode::Insert => self.handle_insert_mode(key),
+            AppMode::Replace => self.handle_replace_mode(key),
             AppMode::Visual(visual_type) => self.handle_visual_mode(key, visual_type),
-            AppMode::Search => self.handle_search_mode(key),
-            AppMode::Processing | AppMode::ConfirmDelete => VimAction::None,
+            // The command line and list filter are edited like the search prompt
+            AppMode::Search | AppMode::Command | AppMode::Filter => self.handle_search_mode(key),
+            AppMode::Processing | AppMode::ConfirmDelete | AppMode::ConfirmReplace => {
+                VimAction::None
+            }
         }
     }
 
     fn handle_normal_mode(&mut self, key: KeyEvent) -> VimAction {
+        if let Some(digit) = self.count_digit(&key) {
+            let count = self.pending_count.unwrap_or(0) * 10 + digit;
+            self.pending_count = Some(count.min(MAX_COUNT));
+            return VimAction::None;
+        }
+
+        let was_operator_pending = self.is_operator_pending();
+        let action = self.resolve_normal_key(key);
+        if !was_operator_pending && self.is_operator_pending() {
+            // Counts typed after the operator multiply this one
+            self.operator_count = self.pending_count.take();
+        } else if !self.is_sequence_pending() {
+            self.count =
+                self.operator_count.take().unwrap_or(1) * self.pending_count.take().unwrap_or(1);
+        }
+        action
+    }
+
+    fn resolve_normal_key(&mut self, key: KeyEvent) -> VimAction {
         // gc-pending: waiting for 'c' after 'g','c' to complete gcc
         if self.gc_pending {
             self.gc_pending = false;
@@ -194,6 +588,101 @@ impl VimMode {
             return VimAction::None;
         }
 
+        // Bracket motions: ]t / [t jump between open tasks, [T to the first one,
+        // ]b / [b between open notes, ]q / [q between locations, ]d / [d
+        // between daily notes and ]s / [s between misspelled words
+        if let Some(bracket) = self.bracket_pending.take() {
+            return match (bracket, key.code) {
+                (']', KeyCode::Char('t')) => VimAction::NextOpenTask,
+                (']', KeyCode::Char('b')) => VimAction::CycleBuffer { forward: true },
+                ('[', KeyCode::Char('b')) => VimAction::CycleBuffer { forward: false },
+                (']', KeyCode::Char('q')) => VimAction::CycleLocation { forward: true },
+                ('[', KeyCode::Char('q')) => VimAction::CycleLocation { forward: false },
+                (']', KeyCode::Char('d')) => VimAction::CycleDailyNote { forward: true },
+                ('[', KeyCode::Char('d')) => VimAction::CycleDailyNote { forward: false },
+                (']', KeyCode::Char('s')) => VimAction::CycleSpellError { forward: true },
+                ('[', KeyCode::Char('s')) => VimAction::CycleSpellError { forward: false },
+                ('[', KeyCode::Char('t')) => VimAction::PrevOpenTask,
+                ('[', KeyCode::Char('T')) => VimAction::FirstOpenTask,
+                _ => VimAction::None,
+            };
+        }
+
+        // r{char}; r<Enter> splits the line
+        if self.replace_pending {
+            self.replace_pending = false;
+            return match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    VimAction::ReplaceChar(c)
+                }
+                KeyCode::Enter => VimAction::ReplaceChar('\n'),
+                _ => VimAction::None,
+            };
+        }
+
+        // Ctrl-W window commands; the second key may be typed with Ctrl too
+        if self.window_pending {
+            self.window_pending = false;
+            return match key.code {
+                KeyCode::Char('s' | 'S') => VimAction::SplitPane(SplitDir::Horizontal),
+                KeyCode::Char('v') => VimAction::SplitPane(SplitDir::Vertical),
+                KeyCode::Char('w') => VimAction::CyclePane { forward: true },
+                KeyCode::Char('W') => VimAction::CyclePane { forward: false },
+                KeyCode::Char('h') | KeyCode::Left => VimAction::FocusPane(PaneDir::Left),
+                KeyCode::Char('j') | KeyCode::Down => VimAction::FocusPane(PaneDir::Down),
+                KeyCode::Char('k') | KeyCode::Up => VimAction::FocusPane(PaneDir::Up),
+                KeyCode::Char('l') | KeyCode::Right => VimAction::FocusPane(PaneDir::Right),
+                KeyCode::Char('c' | 'q') => VimAction::ClosePane,
+                KeyCode::Char('o') => VimAction::OnlyPane,
+                KeyCode::Char('+') => VimAction::ResizePane {
+                    dir: SplitDir::Horizontal,
+                    grow: true,
+                },
+                KeyCode::Char('-') => VimAction::ResizePane {
+                    dir: SplitDir::Horizontal,
+                    grow: false,
+                },
+                KeyCode::Char('>') => VimAction::ResizePane {
+                    dir: SplitDir::Vertical,
+                    grow: true,
+                },
+                KeyCode::Char('<') => VimAction::ResizePane {
+                    dir: SplitDir::Vertical,
+                    grow: false,
+                },
+                KeyCode::Char('=') => VimAction::EqualizePanes,
+                _ => VimAction::None,
+            };
+        }
+
+        // zz / zt / zb scroll the cursor row to the center, top or bottom;
+        // za / zR / zM toggle, open and close folds; z= suggests spellings
+        if self.scroll_pending {
+            self.scroll_pending = false;
+            return match key.code {
+                KeyCode::Char('z') => VimAction::AlignView(ViewAlign::Center),
+                KeyCode::Char('t') => VimAction::AlignView(ViewAlign::Top),
+                KeyCode::Char('b') => VimAction::AlignView(ViewAlign::Bottom),
+                KeyCode::Char('a') => VimAction::ToggleFold,
+                KeyCode::Char('R') => VimAction::OpenAllFolds,
+                KeyCode::Char('M') => VimAction::CloseAllFolds,
+                KeyCode::Char('=') => VimAction::SpellSuggest,
+                _ => VimAction::None,
+            };
+        }
+
+        // Marks: m{a-z} sets one, '{a-z} and `{a-z} jump to it
+        if let Some(prefix) = self.mark_pending.take() {
+            return match key.code {
+                KeyCode::Char(mark @ 'a'..='z') if prefix == 'm' => VimAction::SetMark(mark),
+                KeyCode::Char(mark @ 'a'..='z') => VimAction::JumpToMark {
+                    mark,
+                    exact: prefix == '`',
+                },
+                _ => VimAction::None,
+            };
+        }
+
         // g-pending: 'g' was pressed, waiting for second key
         if self.normal_g_pending {
             self.normal_g_pending = false;
@@ -205,98 +694,47 @@ impl VimMode {
                     self.gc_pending = true;
                     return VimAction::None;
                 }
+                if c == 'f' {
+                    return VimAction::FollowLink;
+                }
             }
             return VimAction::None;
         }
 
-        match &self.leader_state {
-            LeaderState::AwaitingSecond(first) => {
-                let first = *first;
-                self.leader_state = LeaderState::Inactive;
-                return match (first, key.code) {
-                    ('n', KeyCode::Char('n')) => VimAction::LeaderNew,
-                    ('m', KeyCode::Char('c')) => VimAction::InsertCheckbox,
-                    _ => VimAction::None,
-                };
-            }
-            LeaderState::AwaitingFirst => {
-                return match key.code {
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_process) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::LeaderProcess
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_list) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::LeaderList
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_quit) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::Quit
-                    }
-                    KeyCode::Char('d') => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleCheckbox
-                    }
-                    KeyCode::Char('h') => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleHints
-                    }
-                    // Formatting leader keys
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_bold) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleFormat(MarkdownFormat::Bold)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_italic) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleFormat(MarkdownFormat::Italic)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_strikethrough) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleFormat(MarkdownFormat::Strikethrough)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_code) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleFormat(MarkdownFormat::InlineCode)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_code_block) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::ToggleFormat(MarkdownFormat::CodeBlock)
-                    }
-                    KeyCode::Char('n') => {
-                        self.leader_state = LeaderState::AwaitingSecond('n');
-                        VimAction::None
-                    }
-                    KeyCode::Char('m') => {
-                        self.leader_state = LeaderState::AwaitingSecond('m');
-                        VimAction::None
-                    }
-                    _ => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::None
-                    }
-                };
-            }
-            LeaderState::Inactive => {}
+        if self.leader_state != LeaderState::Inactive {
+            return match self.advance_leader(&key) {
+                Some(command) => leader_action(command),
+                None => VimAction::None,
+            };
         }
 
-        // Operator-pending: resolve motion
+        // Operator-pending: resolve motion or text object
         if self.operator_state != OperatorPending::None {
             let op = self.operator_state;
+            let motion = match (self.text_object_pending.take(), key.code) {
+                (Some(around), KeyCode::Char(c)) => {
+                    TextObject::from_key(c, around).map(Motion::TextObject)
+                }
+                (Some(_), _) => None,
+                (None, KeyCode::Char(c @ ('i' | 'a'))) => {
+                    self.text_object_pending = Some(c == 'a');
+                    return VimAction::None;
+                }
+                (None, _) => self.resolve_motion(key, op),
+            };
             self.operator_state = OperatorPending::None;
-            if let Some(motion) = self.resolve_motion(key) {
-                return match op {
-                    OperatorPending::Delete => VimAction::Delete(motion),
-                    OperatorPending::Yank => VimAction::Yank(motion),
-                    OperatorPending::None => unreachable!(),
-                };
-            }
-            return VimAction::None;
+            return match (op, motion) {
+                (OperatorPending::Delete, Some(motion)) => VimAction::Delete(motion),
+                (OperatorPending::Yank, Some(motion)) => VimAction::Yank(motion),
+                (OperatorPending::Change, Some(motion)) => VimAction::Change(motion),
+                _ => VimAction::None,
+            };
         }
 
         match key.code {
             // Leader key
             KeyCode::Char(' ') => {
-                self.leader_state = LeaderState::AwaitingFirst;
+                self.leader_state = LeaderState::Pending(String::new());
                 VimAction::LeaderKey
             }
 
@@ -308,6 +746,40 @@ impl VimMode {
             KeyCode::Home => VimAction::MoveLineStart,
             KeyCode::End => VimAction::MoveLineEnd,
 
+            // Jump list; terminals send Ctrl-I as Tab
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::JumpBack
+            }
+            KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::JumpForward
+            }
+            KeyCode::Tab => VimAction::JumpForward,
+
+            KeyCode::Char('}') => VimAction::MoveParagraphForward,
+            KeyCode::Char('{') => VimAction::MoveParagraphBackward,
+            KeyCode::Char(')') => VimAction::MoveSentenceForward,
+            KeyCode::Char('(') => VimAction::MoveSentenceBackward,
+            KeyCode::Enter => VimAction::FollowLink,
+
+            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::IncrementNumber
+            }
+            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::DecrementNumber
+            }
+
+            // Scrolling, ahead of the configurable keys they share letters with
+            KeyCode::Char(c @ ('d' | 'u')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::ScrollHalfPage { down: c == 'd' }
+            }
+            KeyCode::Char(c @ ('f' | 'b')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::ScrollPage { down: c == 'f' }
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.window_pending = true;
+                VimAction::None
+            }
+
             // Navigation - configurable keys
             KeyCode::Char(c) if self.key_matches(c, &self.keys.move_left) => VimAction::MoveLeft,
             KeyCode::Char(c) if self.key_matches(c, &self.keys.move_right) => VimAction::MoveRight,
@@ -366,6 +838,13 @@ impl VimMode {
                 self.operator_state = OperatorPending::Yank;
                 VimAction::None
             }
+            KeyCode::Char(c)
+                if self.key_matches(c, &self.keys.change)
+                    && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.operator_state = OperatorPending::Change;
+                VimAction::None
+            }
             KeyCode::Char(c) if self.key_matches(c, &self.keys.paste_after) => {
                 VimAction::PasteAfter
             }
@@ -386,7 +865,9 @@ impl VimMode {
                 VimAction::EnterVisualLine
             }
             KeyCode::Char(c) if self.key_matches(c, &self.keys.search) => VimAction::Search,
-            KeyCode::Char('f') => VimAction::Search, // Alternative search key
+            KeyCode::Char('f') => VimAction::Filter,
+            KeyCode::Char(':') => VimAction::EnterCommandMode,
+            KeyCode::Char('.') => VimAction::RepeatLastChange,
             KeyCode::Char(c) if self.key_matches(c, &self.keys.search_next) => {
                 VimAction::SearchNext
             }
@@ -418,6 +899,24 @@ impl VimMode {
             KeyCode::Char('>') => VimAction::Indent,
             KeyCode::Char('<') => VimAction::Dedent,
 
+            KeyCode::Char(c @ (']' | '[')) => {
+                self.bracket_pending = Some(c);
+                VimAction::None
+            }
+            KeyCode::Char(c @ ('m' | '\'' | '`')) => {
+                self.mark_pending = Some(c);
+                VimAction::None
+            }
+            KeyCode::Char('r') => {
+                self.replace_pending = true;
+                VimAction::None
+            }
+            KeyCode::Char('R') => VimAction::EnterReplaceMode,
+            KeyCode::Char('z') => {
+                self.scroll_pending = true;
+                VimAction::None
+            }
+
             _ => VimAction::None,
         }
     }
@@ -451,6 +950,25 @@ impl VimMode {
         }
     }
 
+    fn handle_replace_mode(&mut self, key: KeyEvent) -> VimAction {
+        match key.code {
+            KeyCode::Esc => VimAction::ExitToNormal,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::ExitToNormal
+            }
+            KeyCode::Backspace => VimAction::Backspace,
+            KeyCode::Enter => VimAction::InsertNewline,
+            KeyCode::Left => VimAction::MoveLeft,
+            KeyCode::Right => VimAction::MoveRight,
+            KeyCode::Up => VimAction::MoveUp,
+            KeyCode::Down => VimAction::MoveDown,
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                VimAction::InsertChar(c)
+            }
+            _ => VimAction::None,
+        }
+    }
+
     fn handle_visual_mode(
         &mut self,
         key: KeyEvent,
@@ -458,41 +976,12 @@ impl VimMode {
     ) -> VimAction {
         use crate::molecules::editor::VisualType;
 
-        // Leader key handling in Visual mode
-        match &self.leader_state {
-            LeaderState::AwaitingFirst => {
-                return match key.code {
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_bold) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::VisualToggleFormat(MarkdownFormat::Bold)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_italic) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::VisualToggleFormat(MarkdownFormat::Italic)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_strikethrough) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::VisualToggleFormat(MarkdownFormat::Strikethrough)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_code) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::VisualToggleFormat(MarkdownFormat::InlineCode)
-                    }
-                    KeyCode::Char(c) if self.key_matches(c, &self.keys.leader_code_block) => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::VisualToggleFormat(MarkdownFormat::CodeBlock)
-                    }
-                    _ => {
-                        self.leader_state = LeaderState::Inactive;
-                        VimAction::None
-                    }
-                };
-            }
-            LeaderState::AwaitingSecond(_) => {
-                self.leader_state = LeaderState::Inactive;
-                return VimAction::None;
-            }
-            LeaderState::Inactive => {}
+        // Leader key handling in Visual mode: only formatting applies
+        if self.leader_state != LeaderState::Inactive {
+            return match self.advance_leader(&key).map(leader_action) {
+                Some(VimAction::ToggleFormat(format)) => VimAction::VisualToggleFormat(format),
+                _ => VimAction::None,
+            };
         }
 
         // Handle g-pending state for gc (comment) and gg (file start)
@@ -554,10 +1043,11 @@ impl VimMode {
 
         match key.code {
             KeyCode::Esc => VimAction::ExitToNormal,
+            KeyCode::Char(':') => VimAction::EnterCommandMode,
 
             // Leader key (Space)
             KeyCode::Char(' ') => {
-                self.leader_state = LeaderState::AwaitingFirst;
+                self.leader_state = LeaderState::Pending(String::new());
                 VimAction::LeaderKey
             }
 
@@ -584,6 +1074,10 @@ impl VimMode {
                 VimAction::None
             }
             KeyCode::Char(c) if self.key_matches(c, &self.keys.file_end) => VimAction::MoveFileEnd,
+            KeyCode::Char('}') => VimAction::MoveParagraphForward,
+            KeyCode::Char('{') => VimAction::MoveParagraphBackward,
+            KeyCode::Char(')') => VimAction::MoveSentenceForward,
+            KeyCode::Char('(') => VimAction::MoveSentenceBackward,
             KeyCode::Char(c) if self.key_matches(c, &self.keys.delete_line) => {
                 VimAction::VisualDelete
             }
@@ -595,18 +1089,38 @@ impl VimMode {
     }
 
     fn handle_search_mode(&mut self, key: KeyEvent) -> VimAction {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return match key.code {
+                KeyCode::Char('u') => VimAction::PromptDeleteToStart,
+                KeyCode::Char('w') => VimAction::PromptDeleteWord,
+                _ => VimAction::None,
+            };
+        }
         match key.code {
             KeyCode::Esc | KeyCode::Enter => VimAction::ExitToNormal,
             KeyCode::Backspace => VimAction::Backspace,
+            KeyCode::Delete => VimAction::DeleteChar,
+            KeyCode::Left => VimAction::MoveLeft,
+            KeyCode::Right => VimAction::MoveRight,
+            KeyCode::Home => VimAction::MoveLineStart,
+            KeyCode::End => VimAction::MoveLineEnd,
+            KeyCode::Up => VimAction::MoveUp,
+            KeyCode::Down => VimAction::MoveDown,
             KeyCode::Char(c) => VimAction::InsertChar(c),
             _ => VimAction::None,
         }
     }
 
-    fn resolve_motion(&self, key: KeyEvent) -> Option<Motion> {
+    fn resolve_motion(&self, key: KeyEvent, op: OperatorPending) -> Option<Motion> {
+        // Doubling the operator key (dd, yy, cc) acts on whole lines
+        let line_key = match op {
+            OperatorPending::Delete => &self.keys.delete_line,
+            OperatorPending::Yank => &self.keys.yank,
+            OperatorPending::Change => &self.keys.change,
+            OperatorPending::None => return None,
+        };
         match key.code {
-            KeyCode::Char(c) if self.key_matches(c, &self.keys.delete_line) => Some(Motion::Line),
-            KeyCode::Char(c) if self.key_matches(c, &self.keys.yank) => Some(Motion::Line),
+            KeyCode::Char(c) if self.key_matches(c, line_key) => Some(Motion::Line),
             KeyCode::Char(c) if self.key_matches(c, &self.keys.word_forward) => {
                 Some(Motion::WordForward)
             }
@@ -621,6 +1135,10 @@ impl VimMode {
             KeyCode::Char(c) if self.key_matches(c, &self.keys.file_start) => {
                 Some(Motion::FileStart)
             }
+            KeyCode::Char('}') => Some(Motion::ParagraphForward),
+            KeyCode::Char('{') => Some(Motion::ParagraphBackward),
+            KeyCode::Char(')') => Some(Motion::SentenceForward),
+            KeyCode::Char('(') => Some(Motion::SentenceBackward),
             _ => None,
         }
     }
@@ -670,6 +1188,69 @@ mod tests {
         assert!(!vim.is_leader_pending());
     }
 
+    #[test]
+    fn test_leader_cheat_sheet() {
+        let mut vim = VimMode::new();
+        vim.handle_key(
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            AppMode::Normal,
+        );
+        let action = vim.handle_key(
+            KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE),
+            AppMode::Normal,
+        );
+        assert_eq!(action, VimAction::LeaderCheatSheet);
+        assert!(!vim.is_leader_pending());
+    }
+
+    #[test]
+    fn test_leader_stats() {
+        let mut vim = VimMode::new();
+        vim.handle_key(
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            AppMode::Normal,
+        );
+        let action = vim.handle_key(
+            KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE),
+            AppMode::Normal,
+        );
+        assert_eq!(action, VimAction::LeaderStats);
+        assert!(!action.is_mutating());
+    }
+
+    #[test]
+    fn test_buffer_keys() {
+        let mut vim = VimMode::new();
+        assert_eq!(
+            type_normal(&mut vim, "]b"),
+            VimAction::CycleBuffer { forward: true }
+        );
+        assert_eq!(
+            type_normal(&mut vim, "2[b"),
+            VimAction::CycleBuffer { forward: false }
+        );
+        assert_eq!(vim.count(), 2);
+        assert_eq!(
+            type_normal(&mut vim, "3]q"),
+            VimAction::CycleLocation { forward: true }
+        );
+        assert_eq!(
+            type_normal(&mut vim, "[q"),
+            VimAction::CycleLocation { forward: false }
+        );
+        assert_eq!(type_normal(&mut vim, " B"), VimAction::LeaderBuffers);
+        assert!(!vim.is_leader_pending());
+        assert_eq!(type_normal(&mut vim, " r"), VimAction::LeaderRename);
+        assert!(VimAction::LeaderRename.is_mutating());
+        assert_eq!(type_normal(&mut vim, " T"), VimAction::LeaderSyncTitle);
+        assert_eq!(type_normal(&mut vim, " g"), VimAction::LeaderGitSync);
+        assert_eq!(type_normal(&mut vim, " D"), VimAction::LeaderToday);
+        assert_eq!(
+            type_normal(&mut vim, "2[d"),
+            VimAction::CycleDailyNote { forward: false }
+        );
+    }
+
     #[test]
     fn test_leader_multi_char_cancel() {
         let mut vim = VimMode::new();
@@ -767,6 +1348,40 @@ mod tests {
         assert!(!vim.is_leader_pending());
     }
 
+    #[test]
+    fn test_configured_leader_sequences() {
+        let keys = KeyboardConfig {
+            leader: [
+                ("ot", LeaderCommand::Today),
+                ("ob", LeaderCommand::Bold),
+                ("?", LeaderCommand::None),
+            ]
+            .into_iter()
+            .map(|(k, c)| (k.to_string(), c))
+            .collect(),
+            ..Default::default()
+        };
+        let mut vim = VimMode::with_config(keys);
+
+        assert_eq!(type_normal(&mut vim, " o"), VimAction::None);
+        assert_eq!(vim.leader_prefix(), Some("o"));
+        assert_eq!(type_normal(&mut vim, "t"), VimAction::LeaderToday);
+        assert_eq!(vim.leader_prefix(), None);
+        assert_eq!(type_normal(&mut vim, " ?"), VimAction::None);
+        assert!(!vim.is_leader_pending());
+        assert_eq!(type_normal(&mut vim, " nn"), VimAction::LeaderNew);
+
+        let visual = AppMode::Visual(crate::molecules::editor::VisualType::Character);
+        for c in [' ', 'o'] {
+            vim.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), visual);
+        }
+        let bold = vim.handle_key(
+            KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE),
+            visual,
+        );
+        assert_eq!(bold, VimAction::VisualToggleFormat(MarkdownFormat::Bold));
+    }
+
     #[test]
     fn test_leader_new_note() {
         let mut vim = VimMode::new();
@@ -796,6 +1411,51 @@ mod tests {
         assert!(!vim.is_leader_pending());
     }
 
+    #[test]
+    fn test_open_task_motions() {
+        let mut vim = VimMode::new();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(vim.handle_key(key(']'), AppMode::Normal), VimAction::None);
+        assert_eq!(
+            vim.handle_key(key('t'), AppMode::Normal),
+            VimAction::NextOpenTask
+        );
+        vim.handle_key(key('['), AppMode::Normal);
+        assert_eq!(
+            vim.handle_key(key('t'), AppMode::Normal),
+            VimAction::PrevOpenTask
+        );
+        vim.handle_key(key('['), AppMode::Normal);
+        assert_eq!(
+            vim.handle_key(key('T'), AppMode::Normal),
+            VimAction::FirstOpenTask
+        );
+        vim.handle_key(key('['), AppMode::Normal);
+        assert_eq!(
+            vim.handle_key(key('s'), AppMode::Normal),
+            VimAction::CycleSpellError { forward: false }
+        );
+        // Unknown target cancels the bracket and the next key acts normally
+        vim.handle_key(key(']'), AppMode::Normal);
+        assert_eq!(vim.handle_key(key('x'), AppMode::Normal), VimAction::None);
+        assert_eq!(
+            vim.handle_key(key('x'), AppMode::Normal),
+            VimAction::DeleteChar
+        );
+    }
+
+    #[test]
+    fn test_is_mutating() {
+        assert!(VimAction::DeleteLine.is_mutating());
+        assert!(VimAction::Delete(Motion::WordForward).is_mutating());
+        assert!(VimAction::InsertMode.is_mutating());
+        assert!(VimAction::LeaderProcess.is_mutating());
+        assert!(!VimAction::MoveDown.is_mutating());
+        assert!(!VimAction::Yank(Motion::Line).is_mutating());
+        assert!(!VimAction::Search.is_mutating());
+        assert!(!VimAction::Quit.is_mutating());
+    }
+
     #[test]
     fn test_insert_mode() {
         let mut vim = VimMode::new();
@@ -873,6 +1533,299 @@ mod tests {
         assert!(!vim.is_operator_pending());
     }
 
+    fn type_normal(vim: &mut VimMode, keys: &str) -> VimAction {
+        keys.chars()
+            .map(|c| {
+                vim.handle_key(
+                    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+                    AppMode::Normal,
+                )
+            })
+            .last()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "5"), VimAction::None);
+        assert_eq!(type_normal(&mut vim, "j"), VimAction::MoveDown);
+        assert_eq!(vim.count(), 5);
+
+        assert_eq!(type_normal(&mut vim, "10x"), VimAction::DeleteChar);
+        assert_eq!(vim.count(), 10);
+
+        assert_eq!(type_normal(&mut vim, "k"), VimAction::MoveUp);
+        assert_eq!(vim.count(), 1);
+    }
+
+    #[test]
+    fn test_count_with_operator() {
+        let mut vim = VimMode::new();
+        assert_eq!(
+            type_normal(&mut vim, "3dd"),
+            VimAction::Delete(Motion::Line)
+        );
+        assert_eq!(vim.count(), 3);
+
+        assert_eq!(
+            type_normal(&mut vim, "2d3w"),
+            VimAction::Delete(Motion::WordForward)
+        );
+        assert_eq!(vim.count(), 6);
+    }
+
+    #[test]
+    fn test_text_objects_and_change() {
+        let mut vim = VimMode::new();
+        assert_eq!(
+            type_normal(&mut vim, "diw"),
+            VimAction::Delete(Motion::TextObject(TextObject::Word { around: false }))
+        );
+        assert_eq!(
+            type_normal(&mut vim, "ci\""),
+            VimAction::Change(Motion::TextObject(TextObject::Pair {
+                open: '"',
+                close: '"',
+                around: false,
+            }))
+        );
+        assert_eq!(
+            type_normal(&mut vim, "ya("),
+            VimAction::Yank(Motion::TextObject(TextObject::Pair {
+                open: '(',
+                close: ')',
+                around: true,
+            }))
+        );
+        assert_eq!(type_normal(&mut vim, "cc"), VimAction::Change(Motion::Line));
+        assert_eq!(type_normal(&mut vim, "dy"), VimAction::None);
+        assert!(!vim.is_operator_pending());
+    }
+
+    #[test]
+    fn test_dot_repeat_and_pending_command() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "3"), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert_eq!(type_normal(&mut vim, "."), VimAction::RepeatLastChange);
+        assert_eq!(vim.count(), 3);
+        assert!(!vim.is_command_pending());
+
+        assert_eq!(type_normal(&mut vim, "d"), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert!(type_normal(&mut vim, "iw").is_repeatable());
+        assert!(!VimAction::MoveDown.is_repeatable());
+    }
+
+    #[test]
+    fn test_colon_enters_command_line() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, ":"), VimAction::EnterCommandMode);
+        let action = vim.handle_key(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE),
+            AppMode::Command,
+        );
+        assert_eq!(action, VimAction::InsertChar('c'));
+    }
+
+    #[test]
+    fn test_marks_and_jump_list_keys() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "m"), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert_eq!(type_normal(&mut vim, "a"), VimAction::SetMark('a'));
+        assert_eq!(
+            type_normal(&mut vim, "'b"),
+            VimAction::JumpToMark {
+                mark: 'b',
+                exact: false
+            }
+        );
+        assert_eq!(
+            type_normal(&mut vim, "`b"),
+            VimAction::JumpToMark {
+                mark: 'b',
+                exact: true
+            }
+        );
+        assert_eq!(type_normal(&mut vim, "m1"), VimAction::None);
+        assert_eq!(type_normal(&mut vim, "j"), VimAction::MoveDown);
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(
+            vim.handle_key(ctrl('o'), AppMode::Normal),
+            VimAction::JumpBack
+        );
+        assert_eq!(
+            vim.handle_key(ctrl('i'), AppMode::Normal),
+            VimAction::JumpForward
+        );
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(vim.handle_key(tab, AppMode::Normal), VimAction::JumpForward);
+    }
+
+    #[test]
+    fn test_paragraph_and_sentence_keys() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "}"), VimAction::MoveParagraphForward);
+        assert_eq!(type_normal(&mut vim, "("), VimAction::MoveSentenceBackward);
+        assert_eq!(
+            type_normal(&mut vim, "d}"),
+            VimAction::Delete(Motion::ParagraphForward)
+        );
+        assert_eq!(
+            type_normal(&mut vim, "c)"),
+            VimAction::Change(Motion::SentenceForward)
+        );
+        assert_eq!(
+            type_normal(&mut vim, "y{"),
+            VimAction::Yank(Motion::ParagraphBackward)
+        );
+    }
+
+    #[test]
+    fn test_increment_keys() {
+        let mut vim = VimMode::new();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(
+            vim.handle_key(ctrl('a'), AppMode::Normal),
+            VimAction::IncrementNumber
+        );
+        assert_eq!(type_normal(&mut vim, "5"), VimAction::None);
+        assert_eq!(
+            vim.handle_key(ctrl('x'), AppMode::Normal),
+            VimAction::DecrementNumber
+        );
+        assert_eq!(vim.count(), 5);
+        assert!(VimAction::DecrementNumber.is_repeatable());
+    }
+
+    #[test]
+    fn test_scroll_keys() {
+        let mut vim = VimMode::new();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(
+            vim.handle_key(ctrl('d'), AppMode::Normal),
+            VimAction::ScrollHalfPage { down: true }
+        );
+        assert_eq!(type_normal(&mut vim, "3"), VimAction::None);
+        assert_eq!(
+            vim.handle_key(ctrl('u'), AppMode::Normal),
+            VimAction::ScrollHalfPage { down: false }
+        );
+        assert_eq!(vim.count(), 3);
+        assert_eq!(
+            vim.handle_key(ctrl('b'), AppMode::Normal),
+            VimAction::ScrollPage { down: false }
+        );
+
+        assert_eq!(type_normal(&mut vim, "z"), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert_eq!(
+            type_normal(&mut vim, "z"),
+            VimAction::AlignView(ViewAlign::Center)
+        );
+        assert_eq!(
+            type_normal(&mut vim, "zb"),
+            VimAction::AlignView(ViewAlign::Bottom)
+        );
+        assert_eq!(type_normal(&mut vim, "za"), VimAction::ToggleFold);
+        assert_eq!(type_normal(&mut vim, "zR"), VimAction::OpenAllFolds);
+        assert_eq!(type_normal(&mut vim, "zM"), VimAction::CloseAllFolds);
+        assert_eq!(type_normal(&mut vim, "z="), VimAction::SpellSuggest);
+        assert_eq!(type_normal(&mut vim, "zx"), VimAction::None);
+        assert_eq!(type_normal(&mut vim, "j"), VimAction::MoveDown);
+        assert_eq!(type_normal(&mut vim, "gf"), VimAction::FollowLink);
+    }
+
+    #[test]
+    fn test_window_keys() {
+        let mut vim = VimMode::new();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(vim.handle_key(ctrl('w'), AppMode::Normal), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert_eq!(
+            type_normal(&mut vim, "v"),
+            VimAction::SplitPane(SplitDir::Vertical)
+        );
+        vim.handle_key(ctrl('w'), AppMode::Normal);
+        assert_eq!(
+            vim.handle_key(ctrl('w'), AppMode::Normal),
+            VimAction::CyclePane { forward: true }
+        );
+        vim.handle_key(ctrl('w'), AppMode::Normal);
+        assert_eq!(
+            type_normal(&mut vim, "l"),
+            VimAction::FocusPane(PaneDir::Right)
+        );
+        vim.handle_key(ctrl('w'), AppMode::Normal);
+        assert_eq!(type_normal(&mut vim, "c"), VimAction::ClosePane);
+        assert_eq!(type_normal(&mut vim, "w"), VimAction::MoveWordForward);
+    }
+
+    #[test]
+    fn test_replace_keys() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "r"), VimAction::None);
+        assert!(vim.is_command_pending());
+        assert_eq!(type_normal(&mut vim, "x"), VimAction::ReplaceChar('x'));
+        assert_eq!(type_normal(&mut vim, "3r5"), VimAction::ReplaceChar('5'));
+        assert_eq!(vim.count(), 3);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(type_normal(&mut vim, "r"), VimAction::None);
+        assert_eq!(
+            vim.handle_key(enter, AppMode::Normal),
+            VimAction::ReplaceChar('\n')
+        );
+        assert_eq!(type_normal(&mut vim, "R"), VimAction::EnterReplaceMode);
+        assert!(VimAction::ReplaceChar('x').is_repeatable());
+
+        let key = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(
+            vim.handle_key(key, AppMode::Replace),
+            VimAction::InsertChar('j')
+        );
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(
+            vim.handle_key(esc, AppMode::Replace),
+            VimAction::ExitToNormal
+        );
+    }
+
+    #[test]
+    fn test_search_prompt_editing_keys() {
+        let mut vim = VimMode::new();
+        let mut search =
+            |code, modifiers| vim.handle_key(KeyEvent::new(code, modifiers), AppMode::Search);
+        assert_eq!(
+            search(KeyCode::Left, KeyModifiers::NONE),
+            VimAction::MoveLeft
+        );
+        assert_eq!(search(KeyCode::Up, KeyModifiers::NONE), VimAction::MoveUp);
+        assert_eq!(
+            search(KeyCode::Char('u'), KeyModifiers::CONTROL),
+            VimAction::PromptDeleteToStart
+        );
+        assert_eq!(
+            search(KeyCode::Char('w'), KeyModifiers::CONTROL),
+            VimAction::PromptDeleteWord
+        );
+        assert_eq!(
+            search(KeyCode::Char('w'), KeyModifiers::NONE),
+            VimAction::InsertChar('w')
+        );
+        assert_eq!(type_normal(&mut vim, "/"), VimAction::Search);
+        assert_eq!(type_normal(&mut vim, "f"), VimAction::Filter);
+    }
+
+    #[test]
+    fn test_zero_is_line_start_without_count() {
+        let mut vim = VimMode::new();
+        assert_eq!(type_normal(&mut vim, "0"), VimAction::MoveLineStart);
+        assert_eq!(vim.count(), 1);
+    }
+
     #[test]
     fn test_visual_d() {
         let mut vim = VimMode::new();
@@ -79,6 +79,31 @@ impl VisualMode {
         }
     }
 
+    /// Size of the selection as text, e.g. `3 lines` or `2x4 block`.
+    pub fn summary(&self, buffer: &TextBuffer, cursor: (usize, usize)) -> String {
+        let plural =
+            |n: usize, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+        match self.render_data(buffer, cursor) {
+            RenderSelection::CharacterRange { .. } => {
+                let text = self.yank_selection(buffer, cursor);
+                plural(text.graphemes(true).count(), "char")
+            }
+            RenderSelection::LineRange { start_row, end_row } => {
+                plural(end_row - start_row + 1, "line")
+            }
+            RenderSelection::BlockRegion {
+                top_row,
+                bottom_row,
+                left_col,
+                right_col,
+            } => format!(
+                "{}x{} block",
+                bottom_row - top_row + 1,
+                right_col - left_col + 1
+            ),
+        }
+    }
+
     pub fn delete_selection(&self, buffer: &mut TextBuffer, cursor: (usize, usize)) -> String {
         match self.visual_type {
             VisualType::Character => {
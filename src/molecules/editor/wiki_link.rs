@@ -0,0 +1,86 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A `[[Note Title]]` link on a line. `[[Title|label]]` and
+/// `[[Title#Heading]]` link to `Title`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub target: String,
+    /// Grapheme columns of the opening `[[` and just past the closing `]]`.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl WikiLink {
+    pub fn contains(&self, col: usize) -> bool {
+        (self.start..self.end).contains(&col)
+    }
+}
+
+/// Links on `line`, left to right. Empty targets are skipped.
+pub fn wiki_links(line: &str) -> Vec<WikiLink> {
+    let graphemes: Vec<&str> = line.graphemes(true).collect();
+    let mut links = Vec::new();
+    let mut col = 0;
+    while col + 1 < graphemes.len() {
+        if graphemes[col] != "[" || graphemes[col + 1] != "[" {
+            col += 1;
+            continue;
+        }
+        let inner = col + 2;
+        let close = (inner..graphemes.len().saturating_sub(1))
+            .find(|&i| graphemes[i] == "]" && graphemes[i + 1] == "]");
+        let Some(close) = close else {
+            break;
+        };
+        let text = graphemes[inner..close].concat();
+        let target = text.split(['|', '#']).next().unwrap_or("").trim();
+        if !target.is_empty() && !text.contains('[') {
+            links.push(WikiLink {
+                target: target.to_string(),
+                start: col,
+                end: close + 2,
+            });
+            col = close + 2;
+        } else {
+            col += 1;
+        }
+    }
+    links
+}
+
+/// The link under grapheme column `col` of `line`.
+pub fn link_at(line: &str, col: usize) -> Option<WikiLink> {
+    wiki_links(line).into_iter().find(|link| link.contains(col))
+}
+
+/// Targets of every link in `content`, in order, repeats included.
+pub fn link_targets(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .flat_map(wiki_links)
+        .map(|link| link.target)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let links = wiki_links("See [[Café Notes]] and [[Plan|the plan]], [[]] or [[x");
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, ["Café Notes", "Plan"]);
+        assert_eq!((links[0].start, links[0].end), (4, 18));
+        assert_eq!(link_targets("[[A#Intro]]\n[[ B ]]"), ["A", "B"]);
+    }
+
+    #[test]
+    fn test_link_under_cursor() {
+        let line = "go [[Home]] now";
+        assert_eq!(link_at(line, 3).unwrap().target, "Home");
+        assert_eq!(link_at(line, 10).unwrap().target, "Home");
+        assert!(link_at(line, 11).is_none());
+        assert!(link_at(line, 2).is_none());
+    }
+}
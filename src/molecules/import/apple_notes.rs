@@ -0,0 +1,129 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+
+use super::html::{decode_entities, html_title, html_to_markdown};
+use super::imported::{ImportedNote, extract_hashtags, file_stem_title, push_unique_tags};
+
+static NOTE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<note>(.*?)</note>").unwrap());
+
+static TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<title>(.*?)</title>").unwrap());
+
+static CONTENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<content>\s*(?:<!\[CDATA\[(.*?)\]\]>|(.*?))\s*</content>").unwrap()
+});
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<tag>(.*?)</tag>").unwrap());
+
+static CREATED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<created>\s*(\d{8}T\d{6}Z)\s*</created>").unwrap());
+
+fn parse_enex_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Parse every `<note>` in an ENEX export. Explicit `<tag>` elements are
+/// kept, along with any inline `#tags` in the note text.
+pub fn parse_enex(xml: &str) -> Vec<ImportedNote> {
+    NOTE_RE
+        .captures_iter(xml)
+        .map(|note| {
+            let note = &note[1];
+            let title = TITLE_RE
+                .captures(note)
+                .map(|c| decode_entities(c[1].trim()))
+                .unwrap_or_default();
+            let content = CONTENT_RE
+                .captures(note)
+                .and_then(|c| {
+                    c.get(1)
+                        .map(|m| m.as_str().to_string())
+                        .or_else(|| c.get(2).map(|m| decode_entities(m.as_str())))
+                })
+                .unwrap_or_default();
+            let body = html_to_markdown(&content);
+
+            let mut tags: Vec<String> = TAG_RE
+                .captures_iter(note)
+                .map(|c| decode_entities(c[1].trim()))
+                .collect();
+            push_unique_tags(&mut tags, extract_hashtags(&body));
+
+            ImportedNote {
+                title: if title.is_empty() {
+                    "Untitled".to_string()
+                } else {
+                    title
+                },
+                body,
+                tags,
+                created_at: CREATED_RE
+                    .captures(note)
+                    .and_then(|c| parse_enex_date(&c[1])),
+            }
+        })
+        .collect()
+}
+
+/// Convert a single Apple Notes HTML export. The title comes from `<title>`,
+/// falling back to the file name.
+pub fn parse_apple_notes_html(path: &Path, html: &str) -> ImportedNote {
+    let body = html_to_markdown(html);
+    ImportedNote {
+        title: html_title(html).unwrap_or_else(|| file_stem_title(path)),
+        tags: extract_hashtags(&body),
+        body,
+        created_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_enex() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export4.dtd">
+<en-export>
+  <note>
+    <title>Trip &amp; Packing</title>
+    <content><![CDATA[<?xml version="1.0"?><en-note><div>Passport</div><div>Charger #travel</div></en-note>]]></content>
+    <created>20240301T093000Z</created>
+    <tag>Personal</tag>
+    <tag>travel</tag>
+  </note>
+  <note>
+    <title></title>
+    <content><![CDATA[<en-note><div>second</div></en-note>]]></content>
+  </note>
+</en-export>"#;
+        let notes = parse_enex(xml);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].title, "Trip & Packing");
+        assert_eq!(notes[0].body, "Passport\nCharger #travel");
+        assert_eq!(notes[0].tags, vec!["Personal", "travel"]);
+        assert_eq!(
+            notes[0].created_at,
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap())
+        );
+        assert_eq!(notes[1].title, "Untitled");
+        assert!(notes[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_apple_notes_html_title_fallback() {
+        let note = parse_apple_notes_html(
+            Path::new("export/Shopping List.html"),
+            "<div>eggs #home</div>",
+        );
+        assert_eq!(note.title, "Shopping List");
+        assert_eq!(note.tags, vec!["home"]);
+    }
+}
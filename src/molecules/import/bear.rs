@@ -0,0 +1,61 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use super::imported::{ImportedNote, extract_hashtags, file_stem_title};
+
+static CREATION_DATE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""creationDate"\s*:\s*"([^"]+)""#).unwrap());
+
+/// Convert a Bear note. Bear keeps tags inline, so they stay in the text and
+/// are also collected into frontmatter. `info_json` is the TextBundle
+/// `info.json`, used for the original creation date when present.
+pub fn parse_bear_note(path: &Path, text: &str, info_json: Option<&str>) -> ImportedNote {
+    let body = text.trim().to_string();
+    let title = body
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l.trim_start_matches('#').trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| file_stem_title(path));
+
+    let created_at = info_json
+        .and_then(|info| CREATION_DATE_RE.captures(info))
+        .and_then(|c| DateTime::parse_from_rfc3339(&c[1]).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    ImportedNote {
+        title,
+        tags: extract_hashtags(&body),
+        body,
+        created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_bear_note() {
+        let info = r#"{"net.shinyfrog.bear": {"creationDate": "2023-05-02T08:00:00Z"}}"#;
+        let note = parse_bear_note(
+            Path::new("Meeting.textbundle"),
+            "# Standup\n#work/daily #team notes#\n- ship it\n",
+            Some(info),
+        );
+        assert_eq!(note.title, "Standup");
+        assert_eq!(note.tags, vec!["team notes", "work/daily"]);
+        assert_eq!(
+            note.created_at,
+            Some(Utc.with_ymd_and_hms(2023, 5, 2, 8, 0, 0).unwrap())
+        );
+        assert!(
+            note.to_markdown()
+                .ends_with("# Standup\n#work/daily #team notes#\n- ship it\n")
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::imported::{ImportFormat, import_document};
+use crate::atoms::storage::{FileNaming, read_import_sources, save_draft};
+use crate::types::Note;
+
+/// What `import_into_drafts` did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub notes: usize,
+    pub files: usize,
+    /// Files read that held no note.
+    pub empty_files: usize,
+}
+
+/// Convert every file under `path` that `--import` understands and save the
+/// notes as drafts in `data_dir`.
+pub fn import_into_drafts(
+    data_dir: &Path,
+    path: &Path,
+    naming: FileNaming,
+) -> Result<ImportSummary> {
+    let accept = |p: &Path| ImportFormat::from_path(p).is_some();
+    let sources = read_import_sources(path, &accept)?;
+    let mut summary = ImportSummary {
+        files: sources.len(),
+        ..Default::default()
+    };
+    for source in &sources {
+        let docs = import_document(&source.path, &source.text, source.info_json.as_deref());
+        if docs.is_empty() {
+            summary.empty_files += 1;
+        }
+        for doc in docs {
+            let content = doc.to_markdown();
+            let note = Note::new(
+                uuid::Uuid::new_v4().to_string(),
+                Note::extract_title(&content),
+                content,
+            );
+            save_draft(data_dir, &note, naming)?;
+            summary.notes += 1;
+        }
+    }
+    Ok(summary)
+}
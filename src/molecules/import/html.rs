@@ -0,0 +1,307 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// An HTML tag: closing slash, name, attributes, self-closing slash.
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(/?)([a-zA-Z][a-zA-Z0-9-]*)([^>]*?)(/?)>|<!--.*?-->|<![^>]*>|<\?[^>]*\?>")
+        .unwrap()
+});
+
+static ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([a-zA-Z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+static ENTITY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+
+static BLANK_LINES_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\n{3,}").unwrap());
+
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    ATTR_RE
+        .captures_iter(attrs)
+        .find(|c| c[1].eq_ignore_ascii_case(name))
+        .map(|c| {
+            let value = c.get(2).or_else(|| c.get(3)).map_or("", |m| m.as_str());
+            decode_entities(value)
+        })
+}
+
+/// Decode the named entities notes apps emit plus numeric references.
+pub fn decode_entities(text: &str) -> String {
+    ENTITY_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => None,
+                }
+            };
+            decoded.map_or_else(|| caps[0].to_string(), |c| c.to_string())
+        })
+        .into_owned()
+}
+
+struct ListLevel {
+    ordered: bool,
+    checklist: bool,
+    count: usize,
+}
+
+struct Converter {
+    out: String,
+    lists: Vec<ListLevel>,
+    links: Vec<Option<String>>,
+    skip_depth: usize,
+    pre_depth: usize,
+}
+
+impl Converter {
+    fn ensure_newline(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn ensure_blank_line(&mut self) {
+        self.ensure_newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, raw: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        let text = decode_entities(raw);
+        if self.pre_depth > 0 {
+            self.out.push_str(&text);
+            return;
+        }
+        let mut collapsed = String::with_capacity(text.len());
+        for c in text.chars() {
+            if !c.is_whitespace() {
+                collapsed.push(c);
+                continue;
+            }
+            let previous = collapsed.chars().last().or(self.out.chars().last());
+            if previous.is_some_and(|p| p != ' ' && p != '\n') {
+                collapsed.push(' ');
+            }
+        }
+        self.out.push_str(&collapsed);
+    }
+
+    fn open(&mut self, name: &str, attrs: &str) {
+        match name {
+            "head" | "style" | "script" | "title" => self.skip_depth += 1,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.ensure_blank_line();
+                let level = name[1..].parse().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "p" | "blockquote" => self.ensure_blank_line(),
+            "div" => self.ensure_newline(),
+            "pre" => {
+                self.ensure_blank_line();
+                self.out.push_str("```\n");
+                self.pre_depth += 1;
+            }
+            "br" => self.out.push('\n'),
+            "hr" => {
+                self.ensure_blank_line();
+                self.out.push_str("---\n\n");
+            }
+            "b" | "strong" => self.out.push_str("**"),
+            "i" | "em" => self.out.push('*'),
+            "s" | "strike" | "del" => self.out.push_str("~~"),
+            "code" | "tt" if self.pre_depth == 0 => self.out.push('`'),
+            "ul" | "ol" => {
+                self.ensure_newline();
+                let class = attr(attrs, "class").unwrap_or_default();
+                self.lists.push(ListLevel {
+                    ordered: name == "ol",
+                    checklist: class.split_whitespace().any(|c| c == "checklist"),
+                    count: 0,
+                });
+            }
+            "li" => {
+                self.ensure_newline();
+                let depth = self.lists.len().saturating_sub(1);
+                self.out.push_str(&"  ".repeat(depth));
+                let class = attr(attrs, "class").unwrap_or_default();
+                let checked = class.split_whitespace().any(|c| c == "checked");
+                match self.lists.last_mut() {
+                    Some(level) if level.checklist => {
+                        self.out.push_str(if checked { "- [x] " } else { "- [ ] " })
+                    }
+                    Some(level) if level.ordered => {
+                        level.count += 1;
+                        self.out.push_str(&format!("{}. ", level.count));
+                    }
+                    _ => self.out.push_str("- "),
+                }
+            }
+            "en-todo" => {
+                let checked = attr(attrs, "checked").is_some_and(|v| v == "true");
+                self.out.push_str(if checked { "- [x] " } else { "- [ ] " });
+            }
+            "a" => {
+                let href = attr(attrs, "href").filter(|h| !h.is_empty());
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.links.push(href);
+            }
+            "img" => {
+                if let Some(src) = attr(attrs, "src") {
+                    let alt = attr(attrs, "alt").unwrap_or_default();
+                    self.out.push_str(&format!("![{}]({})", alt, src));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "head" | "style" | "script" | "title" => {
+                self.skip_depth = self.skip_depth.saturating_sub(1)
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "blockquote" => {
+                self.ensure_blank_line()
+            }
+            "div" => self.ensure_newline(),
+            "pre" => {
+                self.ensure_newline();
+                self.out.push_str("```\n\n");
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+            }
+            "b" | "strong" => self.out.push_str("**"),
+            "i" | "em" => self.out.push('*'),
+            "s" | "strike" | "del" => self.out.push_str("~~"),
+            "code" | "tt" if self.pre_depth == 0 => self.out.push('`'),
+            "ul" | "ol" => {
+                self.lists.pop();
+                self.ensure_newline();
+            }
+            "a" => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.out.push_str(&format!("]({})", href));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Convert the HTML produced by notes apps (Apple Notes, ENEX note content)
+/// to markdown. Handles headings, paragraphs, emphasis, links, images,
+/// nested lists and checklists; unknown tags are dropped, keeping their text.
+pub fn html_to_markdown(html: &str) -> String {
+    let mut conv = Converter {
+        out: String::new(),
+        lists: Vec::new(),
+        links: Vec::new(),
+        skip_depth: 0,
+        pre_depth: 0,
+    };
+
+    let mut last = 0;
+    for caps in TAG_RE.captures_iter(html) {
+        let whole = caps.get(0).unwrap();
+        conv.push_text(&html[last..whole.start()]);
+        last = whole.end();
+
+        let Some(name) = caps.get(2) else {
+            continue;
+        };
+        let name = name.as_str().to_lowercase();
+        if &caps[1] == "/" {
+            conv.close(&name);
+        } else {
+            conv.open(&name, &caps[3]);
+            if &caps[4] == "/" && name != "br" && name != "img" && name != "en-todo" {
+                conv.close(&name);
+            }
+        }
+    }
+    conv.push_text(&html[last..]);
+
+    let trimmed: Vec<&str> = conv.out.lines().map(|l| l.trim_end()).collect();
+    BLANK_LINES_RE
+        .replace_all(&trimmed.join("\n"), "\n\n")
+        .trim()
+        .to_string()
+}
+
+/// Text content of the first `<title>` element, if any.
+pub fn html_title(html: &str) -> Option<String> {
+    static TITLE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+    TITLE_RE
+        .captures(html)
+        .map(|c| decode_entities(c[1].trim()))
+        .filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apple_notes_divs_and_formatting() {
+        let html = "<html><head><title>Groceries</title></head><body>\
+                    <div><h1>Groceries</h1></div>\
+                    <div>Buy <b>milk</b> &amp; <i>eggs</i></div>\
+                    <div><br></div>\
+                    <div>See <a href=\"https://example.com\">list</a></div>\
+                    </body></html>";
+        assert_eq!(
+            html_to_markdown(html),
+            "# Groceries\n\nBuy **milk** & *eggs*\n\nSee [list](https://example.com)"
+        );
+        assert_eq!(html_title(html).as_deref(), Some("Groceries"));
+    }
+
+    #[test]
+    fn test_lists_and_checklists() {
+        let html = "<ul><li>one<ul><li>nested</li></ul></li><li>two</li></ul>\
+                    <ol><li>first</li><li>second</li></ol>\
+                    <ul class=\"checklist\"><li class=\"checked\">done</li><li class=\"unchecked\">todo</li></ul>";
+        assert_eq!(
+            html_to_markdown(html),
+            "- one\n  - nested\n- two\n1. first\n2. second\n- [x] done\n- [ ] todo"
+        );
+    }
+
+    #[test]
+    fn test_enex_todo_and_entities() {
+        let html = "<en-note><div><en-todo checked=\"true\"/>Call &#8220;Bob&#8221;</div>\
+                    <div><en-todo checked=\"false\"/>Pay&nbsp;rent</div></en-note>";
+        assert_eq!(
+            html_to_markdown(html),
+            "- [x] Call \u{201c}Bob\u{201d}\n- [ ] Pay rent"
+        );
+    }
+
+    #[test]
+    fn test_pre_keeps_whitespace() {
+        let html = "<p>Code:</p><pre>fn main() {\n    x &lt; y\n}</pre>";
+        assert_eq!(
+            html_to_markdown(html),
+            "Code:\n\n```\nfn main() {\n    x < y\n}\n```"
+        );
+    }
+}
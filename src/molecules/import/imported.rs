@@ -0,0 +1,213 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use super::apple_notes::{parse_apple_notes_html, parse_enex};
+use super::bear::parse_bear_note;
+use super::markdown::parse_markdown_note;
+use crate::types::{Frontmatter, insert_frontmatter_field, set_frontmatter_field};
+
+/// Bear-style multi-word tag: `#two words#`.
+static MULTI_WORD_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(])#([^\s#][^#\n]*?[^\s#])#(?:[\s).,;:!?]|$)").unwrap());
+
+/// Single-word tag, optionally nested: `#work` or `#work/meetings`.
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(])#([\p{L}\p{N}_][\p{L}\p{N}_/-]*)").unwrap());
+
+/// A note converted from another app, ready to be saved as a draft.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedNote {
+    pub title: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl ImportedNote {
+    /// Render as a kenotex draft. Tags and the original creation date go into
+    /// frontmatter, the body's own if it has one; a `# title` heading is added
+    /// unless the body already starts with one or has a `title` field.
+    pub fn to_markdown(&self) -> String {
+        let body = self.body.trim();
+        let fm = Frontmatter::parse(body).unwrap_or_default();
+        let (header, text) = body.split_at(fm.body_offset);
+        let text = text.trim_start();
+
+        let mut out = header.to_string();
+        if !text.starts_with('#') && !self.title.is_empty() && fm.get("title").is_none() {
+            out.push_str(&format!("# {}\n\n", self.title));
+        }
+        out.push_str(text);
+        out.push('\n');
+
+        if let Some(created) = self.created_at.filter(|_| fm.get("created").is_none()) {
+            let created = created.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            out = insert_frontmatter_field(&out, "created", &created);
+        }
+        if !self.tags.is_empty() {
+            let mut tags = fm.list("tags");
+            push_unique_tags(&mut tags, self.tags.iter().cloned());
+            out = set_frontmatter_field(&out, "tags", &tags.join(", "));
+        }
+        out
+    }
+}
+
+/// Export formats `--import` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Evernote-style `.enex` XML, as written by Apple Notes exporters.
+    Enex,
+    /// One HTML file per note (Apple Notes export).
+    AppleNotesHtml,
+    /// A `.textbundle` from Bear, on its own or in a backup.
+    BearMarkdown,
+    /// A plain markdown or text file.
+    Markdown,
+}
+
+impl ImportFormat {
+    /// Pick a format from a file name; `None` for files to skip.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "enex" => Some(ImportFormat::Enex),
+            "html" | "htm" => Some(ImportFormat::AppleNotesHtml),
+            "textbundle" => Some(ImportFormat::BearMarkdown),
+            "md" | "markdown" | "txt" => Some(ImportFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Convert one export file to notes. An ENEX file can hold many notes; the
+/// other formats hold one each. Unsupported files yield nothing.
+pub fn import_document(path: &Path, text: &str, info_json: Option<&str>) -> Vec<ImportedNote> {
+    match ImportFormat::from_path(path) {
+        Some(ImportFormat::Enex) => parse_enex(text),
+        Some(ImportFormat::AppleNotesHtml) => vec![parse_apple_notes_html(path, text)],
+        Some(ImportFormat::BearMarkdown) => vec![parse_bear_note(path, text, info_json)],
+        Some(ImportFormat::Markdown) => vec![parse_markdown_note(path, text)],
+        None => Vec::new(),
+    }
+}
+
+pub fn file_stem_title(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
+
+/// Merge tags case-insensitively, keeping the first spelling seen.
+pub fn push_unique_tags(tags: &mut Vec<String>, new: impl IntoIterator<Item = String>) {
+    for tag in new {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            tags.push(tag);
+        }
+    }
+}
+
+/// Inline `#tags` in note text, as used by Bear and Apple Notes. Headings,
+/// URL fragments, bare numbers (`#1`) and fenced code are ignored.
+pub fn extract_hashtags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let multi: Vec<String> = MULTI_WORD_TAG_RE
+            .captures_iter(line)
+            .map(|c| c[1].to_string())
+            .collect();
+        let rest = MULTI_WORD_TAG_RE.replace_all(line, " ");
+        let single = TAG_RE
+            .captures_iter(&rest)
+            .map(|c| c[1].trim_end_matches('/').to_string())
+            .filter(|t| !t.chars().all(|c| c.is_ascii_digit()))
+            .collect::<Vec<_>>();
+
+        push_unique_tags(&mut tags, multi);
+        push_unique_tags(&mut tags, single);
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_to_markdown_with_tags() {
+        let note = ImportedNote {
+            title: "Groceries".to_string(),
+            body: "milk\neggs".to_string(),
+            tags: vec!["home".to_string(), "errands".to_string()],
+            created_at: Some(Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap()),
+        };
+        assert_eq!(
+            note.to_markdown(),
+            "---\ntags: home, errands\ncreated: 2024-03-01T09:30:00Z\n---\n# Groceries\n\nmilk\neggs\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_keeps_existing_heading() {
+        let note = ImportedNote {
+            title: "Plan".to_string(),
+            body: "# Plan\nsteps".to_string(),
+            tags: Vec::new(),
+            created_at: None,
+        };
+        assert_eq!(note.to_markdown(), "# Plan\nsteps\n");
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ImportFormat::from_path(Path::new("My Notes.enex")),
+            Some(ImportFormat::Enex)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("a/Note.HTML")),
+            Some(ImportFormat::AppleNotesHtml)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("x.textbundle")),
+            Some(ImportFormat::BearMarkdown)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("vault/Plan.md")),
+            Some(ImportFormat::Markdown)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("photo.png")), None);
+    }
+
+    #[test]
+    fn test_extract_hashtags() {
+        let text = "# Heading\nMeet #work/meetings and #team sync# today (#ideas)\n\
+                    Issue #42, see http://x.y/#anchor\n```\n#not-a-tag\n```\n#Work/meetings";
+        assert_eq!(
+            extract_hashtags(text),
+            vec!["team sync", "work/meetings", "ideas"]
+        );
+    }
+
+    #[test]
+    fn test_push_unique_tags() {
+        let mut tags = vec!["Work".to_string()];
+        push_unique_tags(&mut tags, ["work".to_string(), " ideas ".to_string()]);
+        assert_eq!(tags, vec!["Work", "ideas"]);
+    }
+}
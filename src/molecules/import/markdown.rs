@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use super::imported::{ImportedNote, extract_hashtags, file_stem_title};
+use crate::types::{Frontmatter, strip_frontmatter};
+
+/// Convert a plain markdown file: a Bear markdown export, an Obsidian vault,
+/// any folder of notes. Frontmatter is kept, and inline tags are collected
+/// into it as for Bear. The title is the `title` field, else the heading on
+/// the first line, else the file name.
+pub fn parse_markdown_note(path: &Path, text: &str) -> ImportedNote {
+    let body = text.trim().to_string();
+    let text = strip_frontmatter(&body);
+    let heading = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .filter(|l| l.starts_with('#'))
+        .map(|l| l.trim_start_matches('#').trim().to_string());
+    let title = Frontmatter::parse(&body)
+        .and_then(|fm| fm.get("title").map(str::to_string))
+        .or(heading)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| file_stem_title(path));
+
+    ImportedNote {
+        title,
+        tags: extract_hashtags(text),
+        body,
+        created_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_titles_from_headings_and_file_names() {
+        let note = parse_markdown_note(Path::new("vault/Trip.md"), "# Lisbon\nBook #travel\n");
+        assert_eq!(note.title, "Lisbon");
+        assert_eq!(
+            note.to_markdown(),
+            "---\ntags: travel\n---\n# Lisbon\nBook #travel\n"
+        );
+
+        let note = parse_markdown_note(Path::new("vault/Trip ideas.md"), "Some text\n# Later");
+        assert_eq!(note.title, "Trip ideas");
+        assert_eq!(note.to_markdown(), "# Trip ideas\n\nSome text\n# Later\n");
+
+        // Frontmatter stays, with a heading added below it and tags merged in
+        let text = "---\naliases: [Q3]\ntags: [work]\n---\nPlan for #team\n";
+        let note = parse_markdown_note(Path::new("plan.md"), text);
+        assert_eq!(note.title, "plan");
+        assert_eq!(
+            note.to_markdown(),
+            "---\naliases: [Q3]\ntags: work, team\n---\n# plan\n\nPlan for #team\n"
+        );
+        let note = parse_markdown_note(Path::new("x.md"), "---\ntitle: Roadmap\n---\nbody");
+        assert_eq!(note.title, "Roadmap");
+        assert_eq!(note.to_markdown(), "---\ntitle: Roadmap\n---\nbody\n");
+    }
+}
@@ -0,0 +1,13 @@
+mod apple_notes;
+mod bear;
+mod drafts;
+mod html;
+mod imported;
+mod markdown;
+
+pub use apple_notes::{parse_apple_notes_html, parse_enex};
+pub use bear::parse_bear_note;
+pub use drafts::{ImportSummary, import_into_drafts};
+pub use html::html_to_markdown;
+pub use imported::{ImportFormat, ImportedNote, extract_hashtags, import_document};
+pub use markdown::parse_markdown_note;
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use super::search_index::note_hash;
+use crate::molecules::editor::link_targets;
+use crate::types::Note;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LinkingNote {
+    /// Hash of the note the targets were read from.
+    hash: u32,
+    /// Lowercased `[[link]]` targets.
+    targets: Vec<String>,
+}
+
+/// Which notes link to which names, so the backlinks of a note are found
+/// without reading every note again.
+#[derive(Debug, Clone, Default)]
+pub struct Backlinks {
+    notes: HashMap<String, LinkingNote>,
+}
+
+impl Backlinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the links of `note` if it is new or its text changed. Returns
+    /// whether it was.
+    pub fn update(&mut self, note: &Note) -> bool {
+        let hash = note_hash(note);
+        if self
+            .notes
+            .get(&note.id)
+            .is_some_and(|known| known.hash == hash)
+        {
+            return false;
+        }
+        let mut targets: Vec<String> = link_targets(&note.content)
+            .iter()
+            .map(|t| t.to_lowercase())
+            .collect();
+        targets.sort();
+        targets.dedup();
+        self.notes
+            .insert(note.id.clone(), LinkingNote { hash, targets });
+        true
+    }
+
+    /// Update each of `notes`. Returns how many changed.
+    pub fn sync<'a>(&mut self, notes: impl IntoIterator<Item = &'a Note>) -> usize {
+        notes.into_iter().filter(|note| self.update(note)).count()
+    }
+
+    /// Forget a deleted note.
+    pub fn remove(&mut self, id: &str) {
+        self.notes.remove(id);
+    }
+
+    /// Forget every note for which `keep` is false.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.notes.retain(|id, _| keep(id));
+    }
+
+    /// Ids of the other notes linking to `note` by its title or an alias,
+    /// sorted.
+    pub fn linking_to(&self, note: &Note) -> Vec<&str> {
+        let mut names = note.aliases();
+        names.push(note.title.clone());
+        let names: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+        let mut ids: Vec<&str> = self
+            .notes
+            .iter()
+            .filter(|(id, linking)| {
+                **id != note.id && linking.targets.iter().any(|t| names.contains(t))
+            })
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// The note a `[[target]]` link opens: the one titled `target`, else the
+/// one with `target` as an alias, ignoring case.
+pub fn resolve_link<'a>(target: &str, notes: &[&'a Note]) -> Option<&'a Note> {
+    let target = target.to_lowercase();
+    notes
+        .iter()
+        .find(|n| n.title.to_lowercase() == target)
+        .or_else(|| notes.iter().find(|n| n.has_alias(&target)))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, content: &str) -> Note {
+        Note::new(
+            id.to_string(),
+            Note::extract_title(content),
+            content.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_linking_notes() {
+        let home = note("home", "---\naliases: [Start]\n---\n# Home");
+        let a = note("a", "# A\nsee [[home]]");
+        let b = note("b", "# B\n[[Start|begin]] and [[A]]");
+        let mut backlinks = Backlinks::new();
+        assert_eq!(backlinks.sync([&home, &a, &b]), 3);
+        assert_eq!(backlinks.sync([&home, &a, &b]), 0);
+        assert_eq!(backlinks.linking_to(&home), ["a", "b"]);
+        assert_eq!(backlinks.linking_to(&a), ["b"]);
+
+        let b = note("b", "# B\nno links");
+        assert!(backlinks.update(&b));
+        assert_eq!(backlinks.linking_to(&home), ["a"]);
+        backlinks.remove("a");
+        assert!(backlinks.linking_to(&home).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_link() {
+        let home = note("home", "---\naliases: [Start]\n---\n# Home");
+        let start = note("start", "# Start here");
+        let notes = [&home, &start];
+        assert_eq!(resolve_link("HOME", &notes).unwrap().id, "home");
+        assert_eq!(resolve_link("start", &notes).unwrap().id, "home");
+        assert_eq!(resolve_link("Start here", &notes).unwrap().id, "start");
+        assert!(resolve_link("Elsewhere", &notes).is_none());
+    }
+}
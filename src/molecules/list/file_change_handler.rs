@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::Path;
 use std::time::{Duration, Instant};
 
 use crate::atoms::storage::file_watcher::FileEvent;
+use crate::atoms::storage::{note_content_for_path, note_id_for_path};
+use crate::types::Note;
 
 const SUPPRESSION_WINDOW_MS: u64 = 2000;
 
@@ -16,6 +19,15 @@ pub enum FileChangeAction {
 
 pub struct FileChangeTracker {
     save_timestamps: HashMap<String, Instant>,
+    /// Checksum of each note's content as last loaded or saved, so rewrites
+    /// with identical content (sync clients, touch) are not treated as edits.
+    checksums: HashMap<String, u64>,
+}
+
+fn checksum(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
 }
 
 impl Default for FileChangeTracker {
@@ -28,6 +40,7 @@ impl FileChangeTracker {
     pub fn new() -> Self {
         Self {
             save_timestamps: HashMap::new(),
+            checksums: HashMap::new(),
         }
     }
 
@@ -36,6 +49,29 @@ impl FileChangeTracker {
             .insert(note_id.to_string(), Instant::now());
     }
 
+    /// Stop suppressing events for `note_id`: its next change is known not
+    /// to be our own write (a sync pulled it).
+    pub fn forget_save(&mut self, note_id: &str) {
+        self.save_timestamps.remove(note_id);
+    }
+
+    /// Remember `content` as the on-disk state of `note_id`.
+    pub fn record_content(&mut self, note_id: &str, content: &str) {
+        self.checksums
+            .insert(note_id.to_string(), checksum(content));
+    }
+
+    /// Remember the content of every note in `notes`, e.g. after loading lists.
+    pub fn record_notes<'a>(&mut self, notes: impl IntoIterator<Item = &'a Note>) {
+        for note in notes {
+            self.record_content(&note.id, &note.content);
+        }
+    }
+
+    fn is_unchanged(&self, note_id: &str, content: &str) -> bool {
+        self.checksums.get(note_id) == Some(&checksum(content))
+    }
+
     fn should_suppress(&self, note_id: &str) -> bool {
         if let Some(timestamp) = self.save_timestamps.get(note_id) {
             timestamp.elapsed() < Duration::from_millis(SUPPRESSION_WINDOW_MS)
@@ -50,8 +86,10 @@ impl FileChangeTracker {
     }
 }
 
+/// The note id for a changed file. Title-named files carry it in their
+/// frontmatter; removed files fall back to the file stem.
 fn extract_note_id(path: &Path) -> Option<String> {
-    path.file_stem().map(|s| s.to_string_lossy().to_string())
+    note_id_for_path(path)
 }
 
 pub fn classify_event(
@@ -66,6 +104,10 @@ pub fn classify_event(
                     return FileChangeAction::Suppressed;
                 }
                 if known_ids.contains(&id) {
+                    // Metadata-only change or identical rewrite
+                    if note_content_for_path(path).is_some_and(|c| tracker.is_unchanged(&id, &c)) {
+                        return FileChangeAction::Suppressed;
+                    }
                     FileChangeAction::ReloadNote {
                         id,
                         is_archived: *is_archived,
@@ -93,6 +135,7 @@ pub fn classify_event(
                 FileChangeAction::Suppressed
             }
         }
+        FileEvent::Error(_) => FileChangeAction::Suppressed,
     }
 }
 
@@ -127,6 +170,30 @@ mod tests {
         assert!(matches!(action, FileChangeAction::ReloadNote { .. }));
     }
 
+    #[test]
+    fn test_suppress_identical_content() {
+        let dir = std::env::temp_dir().join(format!("kenotex-change-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("note-123.md");
+        std::fs::write(&path, "# Same\n").unwrap();
+        let known = ["note-123".to_string()];
+
+        let mut tracker = FileChangeTracker::new();
+        let event = FileEvent::Modified(path.clone(), false);
+        let action = classify_event(&event, &tracker, &known);
+        assert!(matches!(action, FileChangeAction::ReloadNote { .. }));
+
+        tracker.record_content("note-123", "# Same\n");
+        let action = classify_event(&event, &tracker, &known);
+        assert!(matches!(action, FileChangeAction::Suppressed));
+
+        std::fs::write(&path, "# Edited\n").unwrap();
+        let action = classify_event(&event, &tracker, &known);
+        assert!(matches!(action, FileChangeAction::ReloadNote { .. }));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_new_note_detection() {
         let tracker = FileChangeTracker::new();
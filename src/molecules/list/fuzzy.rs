@@ -0,0 +1,125 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::types::as_tag;
+
+/// A query typed to narrow down a list of notes. Accents never matter;
+/// case only does when the query has an uppercase letter (smart case).
+/// A query that is one `#tag` asks for the notes with that tag instead,
+/// and a `key:value` one also for the notes with that frontmatter field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyQuery {
+    folded: String,
+    case_sensitive: bool,
+    tag: Option<String>,
+    field: Option<(String, String)>,
+}
+
+impl FuzzyQuery {
+    pub fn new(query: &str) -> Self {
+        let case_sensitive = query.chars().any(char::is_uppercase);
+        Self {
+            folded: fold(query, case_sensitive),
+            case_sensitive,
+            tag: as_tag(query.trim()).map(str::to_string),
+            field: as_field(query.trim()),
+        }
+    }
+
+    /// The key and value of a `key:value` query.
+    pub fn field(&self) -> Option<(&str, &str)> {
+        self.field
+            .as_ref()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// The tag of a `#tag` query.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folded.is_empty()
+    }
+
+    /// Whether `text` contains the query as one run.
+    pub fn is_substring_of(&self, text: &str) -> bool {
+        fold(text, self.case_sensitive).contains(&self.folded)
+    }
+
+    /// Whether `text` starts with the query.
+    pub fn is_prefix_of(&self, text: &str) -> bool {
+        fold(text, self.case_sensitive).starts_with(&self.folded)
+    }
+
+    /// Whether the characters of the query, spaces aside, appear in `text`
+    /// in order: `mtg notes` is in `Meeting Notes 2024`.
+    pub fn is_subsequence_of(&self, text: &str) -> bool {
+        let text = fold(text, self.case_sensitive);
+        let mut text = text.chars();
+        self.folded
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|wanted| text.any(|c| c == wanted))
+    }
+}
+
+/// `status:draft`: a key of letters, digits, `_` or `-`, then a value not
+/// starting with a space.
+fn as_field(text: &str) -> Option<(String, String)> {
+    let (key, value) = text.split_once(':')?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    let is_value = value.chars().next().is_some_and(|c| !c.is_whitespace());
+    (is_key && is_value).then(|| (key.to_string(), value.to_string()))
+}
+
+/// `text` without accents (NFD minus combining marks) and, unless
+/// `keep_case`, lowercased.
+pub fn fold(text: &str, keep_case: bool) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfd().filter(|&c| !is_combining_mark(c)) {
+        if keep_case {
+            folded.push(c);
+        } else {
+            folded.extend(c.to_lowercase());
+        }
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smart_case_and_accents() {
+        let query = FuzzyQuery::new("cafe");
+        assert!(query.is_substring_of("Le Café"));
+        assert!(FuzzyQuery::new("CAFÉ").is_substring_of("LE CAFE"));
+        // An uppercase letter makes case matter
+        assert!(!FuzzyQuery::new("Cafe").is_substring_of("le café"));
+        assert!(FuzzyQuery::new("Cafe").is_substring_of("Le Café"));
+        assert!(FuzzyQuery::new("").is_empty());
+        assert!(FuzzyQuery::new("cafe").is_prefix_of("Café noir"));
+        assert!(!FuzzyQuery::new("noir").is_prefix_of("Café noir"));
+        assert_eq!(
+            FuzzyQuery::new("status:in review").field(),
+            Some(("status", "in review"))
+        );
+        assert!(FuzzyQuery::new("todo: milk").field().is_none());
+        assert!(FuzzyQuery::new("a b:c").field().is_none());
+    }
+
+    #[test]
+    fn test_subsequence() {
+        let query = FuzzyQuery::new("mtg notes");
+        assert!(query.is_subsequence_of("Meeting Notes 2024"));
+        assert!(!query.is_substring_of("Meeting Notes 2024"));
+        assert!(!query.is_subsequence_of("Notes on meetings"));
+        assert!(FuzzyQuery::new("rsm").is_subsequence_of("Résumé"));
+        assert!(!FuzzyQuery::new("MN").is_subsequence_of("meeting notes"));
+    }
+}
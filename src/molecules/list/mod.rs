@@ -1,7 +1,19 @@
-mod archive_list;
-mod draft_list;
+mod backlinks;
 pub mod file_change_handler;
+mod fuzzy;
+mod note_list;
+mod note_search;
+mod recent_removal;
+mod review;
+mod search_index;
 
-pub use archive_list::ArchiveList;
-pub use draft_list::DraftList;
+pub use backlinks::{Backlinks, resolve_link};
 pub use file_change_handler::{FileChangeAction, FileChangeTracker, classify_event};
+pub use fuzzy::FuzzyQuery;
+pub use note_list::{ArchiveList, DraftList, NoteList, SortOrder};
+pub use note_search::{
+    alias_conflict, find_note_match, name_matches, note_match_index, note_matches, tag_counts,
+};
+pub use recent_removal::{RecentRemoval, RemovalKind};
+pub use review::{ReviewAction, ReviewSession};
+pub use search_index::SearchIndex;
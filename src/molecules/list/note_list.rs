@@ -0,0 +1,502 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use super::fuzzy::FuzzyQuery;
+use super::note_search::{field_matches, name_matches};
+use crate::types::Note;
+
+/// Drafts and archives share one list model.
+pub type DraftList = NoteList;
+pub type ArchiveList = NoteList;
+
+/// Order of a note list, cycled with `s`. Pinned notes come first in each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Most recently edited first.
+    #[default]
+    Updated,
+    /// Most recently created first.
+    Created,
+    /// Alphabetical by title, ignoring case.
+    Title,
+    /// Largest first.
+    Size,
+}
+
+impl SortOrder {
+    /// Parse a `list_sort` config value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "updated" | "modified" => Some(SortOrder::Updated),
+            "created" => Some(SortOrder::Created),
+            "title" => Some(SortOrder::Title),
+            "size" => Some(SortOrder::Size),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::Updated => SortOrder::Created,
+            SortOrder::Created => SortOrder::Title,
+            SortOrder::Title => SortOrder::Size,
+            SortOrder::Size => SortOrder::Updated,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOrder::Updated => "updated",
+            SortOrder::Created => "created",
+            SortOrder::Title => "title",
+            SortOrder::Size => "size",
+        }
+    }
+
+    fn sort(self, notes: &mut [Note]) {
+        match self {
+            SortOrder::Updated => notes.sort_by_key(|n| Reverse(n.updated_at)),
+            SortOrder::Created => notes.sort_by_key(|n| Reverse(n.created_at)),
+            SortOrder::Title => notes.sort_by_key(|n| n.title.to_lowercase()),
+            SortOrder::Size => notes.sort_by_key(|n| Reverse(n.content.len())),
+        }
+        pinned_first(notes);
+    }
+}
+
+/// Move pinned notes to the top, keeping the order within both groups.
+fn pinned_first(notes: &mut [Note]) {
+    notes.sort_by_cached_key(|n| !n.is_pinned());
+}
+
+/// A filterable, sortable list of notes with a cursor and multi-select
+/// marks (`Note::selected`).
+#[derive(Debug, Clone, Default)]
+pub struct NoteList {
+    notes: Vec<Note>,
+    selected_index: usize,
+    search_query: String,
+    /// Ids the search index allows for `search_query`; `None` checks every
+    /// note.
+    candidates: Option<HashSet<String>>,
+    filtered_indices: Vec<usize>,
+    sort_order: SortOrder,
+}
+
+impl NoteList {
+    pub fn new(notes: Vec<Note>) -> Self {
+        let mut list = Self {
+            notes,
+            ..Self::default()
+        };
+        list.sort_order.sort(&mut list.notes);
+        list.update_filter();
+        list
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    pub fn filtered_notes(&self) -> Vec<&Note> {
+        self.filtered_indices
+            .iter()
+            .filter_map(|&idx| self.notes.get(idx))
+            .collect()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_note(&self) -> Option<&Note> {
+        self.filtered_indices
+            .get(self.selected_index)
+            .and_then(|&idx| self.notes.get(idx))
+    }
+
+    pub fn selected_note_mut(&mut self) -> Option<&mut Note> {
+        let idx = self.filtered_indices.get(self.selected_index).copied()?;
+        self.notes.get_mut(idx)
+    }
+
+    /// Select the visible note at `index`, clamped to the list.
+    pub fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.filtered_indices.len().saturating_sub(1));
+    }
+
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+
+    pub fn set_search_query(&mut self, query: String) {
+        self.set_search_query_in(query, None);
+    }
+
+    /// Filter by `query`, checking only the notes in `candidates` when
+    /// given (see `SearchIndex::candidates`).
+    pub fn set_search_query_in(&mut self, query: String, candidates: Option<HashSet<String>>) {
+        self.search_query = query;
+        self.candidates = candidates;
+        self.update_filter();
+    }
+
+    pub fn add_search_char(&mut self, c: char) {
+        self.search_query.push(c);
+        self.candidates = None;
+        self.update_filter();
+    }
+
+    pub fn remove_search_char(&mut self) {
+        self.search_query.pop();
+        self.candidates = None;
+        self.update_filter();
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.candidates = None;
+        self.update_filter();
+    }
+
+    fn update_filter(&mut self) {
+        if self.search_query.is_empty() {
+            self.filtered_indices = (0..self.notes.len()).collect();
+        } else {
+            // Titles and aliases are matched fuzzily, which the index can't
+            // narrow down, so only the content check is limited to candidates
+            let query = FuzzyQuery::new(&self.search_query);
+            self.filtered_indices = self
+                .notes
+                .iter()
+                .enumerate()
+                .filter(|(_, note)| {
+                    if let Some(tag) = query.tag() {
+                        return note.has_tag(tag);
+                    }
+                    field_matches(note, &query)
+                        || name_matches(note, &query)
+                        || (self
+                            .candidates
+                            .as_ref()
+                            .is_none_or(|ids| ids.contains(&note.id))
+                            && query.is_substring_of(&note.content))
+                })
+                .map(|(idx, _)| idx)
+                .collect();
+        }
+
+        if self.selected_index >= self.filtered_indices.len() {
+            self.selected_index = self.filtered_indices.len().saturating_sub(1);
+        }
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+
+    /// Switch to the next sort order, keeping the cursor on the same note.
+    pub fn cycle_sort(&mut self) -> SortOrder {
+        self.set_sort_order(self.sort_order.next());
+        self.sort_order
+    }
+
+    /// Sort by `order`, keeping the cursor on the same note.
+    pub fn set_sort_order(&mut self, order: SortOrder) {
+        let current = self.selected_note().map(|n| n.id.clone());
+        self.sort_order = order;
+        self.sort_order.sort(&mut self.notes);
+        self.update_filter();
+        if let Some(id) = current {
+            self.select_id(&id);
+        }
+    }
+
+    /// Put the cursor on the note with `id` if it is visible.
+    pub fn select_id(&mut self, id: &str) {
+        if let Some(pos) = self
+            .filtered_indices
+            .iter()
+            .position(|&idx| self.notes[idx].id == id)
+        {
+            self.selected_index = pos;
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index < self.filtered_indices.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+
+    /// Add a note and put the cursor on it.
+    pub fn add_note(&mut self, note: Note) {
+        let id = note.id.clone();
+        self.notes.insert(0, note);
+        if self.sort_order == SortOrder::Updated {
+            pinned_first(&mut self.notes);
+        } else {
+            self.sort_order.sort(&mut self.notes);
+        }
+        self.update_filter();
+        self.selected_index = 0;
+        self.select_id(&id);
+    }
+
+    pub fn remove_selected(&mut self) -> Option<Note> {
+        if let Some(&real_idx) = self.filtered_indices.get(self.selected_index) {
+            let note = self.notes.remove(real_idx);
+            self.update_filter();
+            if self.selected_index >= self.filtered_indices.len() && self.selected_index > 0 {
+                self.selected_index -= 1;
+            }
+            Some(note)
+        } else {
+            None
+        }
+    }
+
+    /// Remove the marked notes, or the note under the cursor when none are
+    /// marked.
+    pub fn remove_marked_or_selected(&mut self) -> Vec<Note> {
+        if self.marked_count() == 0 {
+            return self.remove_selected().into_iter().collect();
+        }
+        let (marked, kept) = std::mem::take(&mut self.notes)
+            .into_iter()
+            .partition(|n| n.selected);
+        self.notes = kept;
+        self.update_filter();
+        marked
+    }
+
+    /// Replace the notes, keeping the sort order and any marks.
+    pub fn update_notes(&mut self, mut notes: Vec<Note>) {
+        let marked: HashSet<&str> = self
+            .notes
+            .iter()
+            .filter(|n| n.selected)
+            .map(|n| n.id.as_str())
+            .collect();
+        for note in &mut notes {
+            note.selected |= marked.contains(note.id.as_str());
+        }
+        self.sort_order.sort(&mut notes);
+        self.notes = notes;
+        self.update_filter();
+    }
+
+    pub fn update_note(&mut self, updated: &Note) {
+        if let Some(note) = self.notes.iter_mut().find(|n| n.id == updated.id) {
+            note.title = updated.title.clone();
+            note.content = updated.content.clone();
+            note.tags = updated.tags.clone();
+            note.updated_at = updated.updated_at;
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(&real_idx) = self.filtered_indices.get(self.selected_index)
+            && let Some(note) = self.notes.get_mut(real_idx)
+        {
+            note.selected = !note.selected;
+        }
+    }
+
+    pub fn get_selected_notes(&self) -> Vec<&Note> {
+        self.notes.iter().filter(|n| n.selected).collect()
+    }
+
+    pub fn clear_marks(&mut self) {
+        for note in &mut self.notes {
+            note.selected = false;
+        }
+    }
+
+    /// Number of notes marked with Space.
+    pub fn marked_count(&self) -> usize {
+        self.notes.iter().filter(|n| n.selected).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filtered_indices.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.filtered_indices.len()
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// The page of `page_size` visible notes holding the cursor: its range
+    /// of visible indices, its number and the page count, both from 1. A
+    /// `page_size` of 0 puts everything on one page.
+    pub fn page(&self, page_size: usize) -> (Range<usize>, usize, usize) {
+        let len = self.filtered_indices.len();
+        if page_size == 0 || len <= page_size {
+            return (0..len, 1, 1);
+        }
+        let page = self.selected_index / page_size;
+        let start = page * page_size;
+        (
+            start..(start + page_size).min(len),
+            page + 1,
+            len.div_ceil(page_size),
+        )
+    }
+
+    pub fn all_note_ids(&self) -> Vec<String> {
+        self.notes.iter().map(|n| n.id.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn make_note(id: &str, title: &str, content: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_archived: false,
+            selected: false,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_search_filter() {
+        let notes = vec![
+            make_note("1", "Hello World", "content"),
+            make_note("2", "Goodbye", "world content"),
+            make_note("3", "Test", "nothing"),
+        ];
+
+        let mut list = DraftList::new(notes);
+        assert_eq!(list.len(), 3);
+
+        list.set_search_query("world".to_string());
+        assert_eq!(list.len(), 2);
+
+        list.clear_search();
+        assert_eq!(list.len(), 3);
+
+        // Only candidates' contents are checked; titles always are
+        let candidates = HashSet::from(["3".to_string()]);
+        list.set_search_query_in("world".to_string(), Some(candidates));
+        assert_eq!(list.len(), 1);
+        list.set_search_query("world".to_string());
+        assert_eq!(list.len(), 2);
+
+        // Titles match fuzzily, with smart case
+        list.set_search_query("hw".to_string());
+        assert_eq!(list.len(), 1);
+        list.set_search_query("World".to_string());
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_cycle_sort_keeps_cursor() {
+        let now = Utc::now();
+        let mut a = make_note("a", "beta", "a longer body");
+        a.created_at = now - Duration::days(2);
+        a.updated_at = now;
+        let mut b = make_note("b", "Alpha", "");
+        b.created_at = now - Duration::days(1);
+        b.updated_at = now - Duration::days(3);
+
+        let mut list = ArchiveList::new(vec![b, a]);
+        let ids = |list: &NoteList| -> Vec<String> {
+            list.filtered_notes().iter().map(|n| n.id.clone()).collect()
+        };
+        assert_eq!(ids(&list), ["a", "b"]);
+
+        list.move_down();
+        assert_eq!(list.cycle_sort(), SortOrder::Created);
+        assert_eq!(ids(&list), ["b", "a"]);
+        assert_eq!(list.selected_note().unwrap().id, "b");
+
+        assert_eq!(list.cycle_sort(), SortOrder::Title);
+        assert_eq!(ids(&list), ["b", "a"]);
+        assert_eq!(list.cycle_sort(), SortOrder::Size);
+        assert_eq!(ids(&list), ["a", "b"]);
+        assert_eq!(list.cycle_sort(), SortOrder::Updated);
+        assert_eq!(SortOrder::from_name(" Modified"), Some(SortOrder::Updated));
+        assert_eq!(SortOrder::from_name("size"), Some(SortOrder::Size));
+        assert_eq!(SortOrder::from_name("random"), None);
+    }
+
+    #[test]
+    fn test_pinned_notes_first() {
+        let pinned = make_note("p", "Zed", "---\npinned: true\n---\n# Zed");
+        let notes = vec![
+            make_note("a", "Alpha", ""),
+            pinned,
+            make_note("b", "Beta", ""),
+        ];
+        let mut list = DraftList::new(notes);
+        list.set_sort_order(SortOrder::Title);
+        let ids: Vec<&str> = list.notes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["p", "a", "b"]);
+
+        list.set_sort_order(SortOrder::Updated);
+        list.add_note(make_note("c", "Gamma", ""));
+        assert_eq!(list.notes()[0].id, "p");
+        assert_eq!(list.selected_note().unwrap().id, "c");
+    }
+
+    #[test]
+    fn test_page_follows_cursor() {
+        let notes = (0..5)
+            .map(|i| make_note(&i.to_string(), "Note", ""))
+            .collect();
+        let mut list = DraftList::new(notes);
+        assert_eq!(list.page(0), (0..5, 1, 1));
+        assert_eq!(list.page(5), (0..5, 1, 1));
+        assert_eq!(list.page(2), (0..2, 1, 3));
+
+        list.select(4);
+        assert_eq!(list.page(2), (4..5, 3, 3));
+        list.move_up();
+        assert_eq!(list.page(2), (2..4, 2, 3));
+    }
+
+    #[test]
+    fn test_marks_survive_reload_and_remove_together() {
+        let notes = vec![
+            make_note("1", "One", ""),
+            make_note("2", "Two", ""),
+            make_note("3", "Three", ""),
+        ];
+        let mut list = ArchiveList::new(notes.clone());
+        list.toggle_selected();
+        list.move_down();
+        list.move_down();
+        list.toggle_selected();
+        assert_eq!(list.marked_count(), 2);
+
+        list.update_notes(notes);
+        assert_eq!(list.marked_count(), 2);
+
+        let removed = list.remove_marked_or_selected();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(list.total_count(), 1);
+        assert_eq!(list.selected_note().unwrap().id, "2");
+
+        assert_eq!(list.remove_marked_or_selected().len(), 1);
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use super::fuzzy::FuzzyQuery;
+use crate::types::Note;
+
+/// Whether `query` is a fuzzy match for the title of `note` or one of its
+/// aliases, or is found in its content. Accents are ignored, and case too
+/// unless the query has an uppercase letter. A `#tag` query matches the
+/// notes with the tag, and a `key:value` one also those with the field.
+pub fn note_matches(note: &Note, query: &FuzzyQuery) -> bool {
+    match query.tag() {
+        Some(tag) => note.has_tag(tag),
+        None => {
+            field_matches(note, query)
+                || name_matches(note, query)
+                || query.is_substring_of(&note.content)
+        }
+    }
+}
+
+/// Whether a `key:value` query names a frontmatter field of `note`, key
+/// case aside, whose value contains the query's.
+pub fn field_matches(note: &Note, query: &FuzzyQuery) -> bool {
+    let Some((key, value)) = query.field() else {
+        return false;
+    };
+    let value = FuzzyQuery::new(value);
+    note.frontmatter().is_some_and(|fm| {
+        fm.fields
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(key) && value.is_substring_of(v))
+    })
+}
+
+/// Whether `query` is a fuzzy match for the title or an alias of `note`.
+pub fn name_matches(note: &Note, query: &FuzzyQuery) -> bool {
+    query.is_subsequence_of(&note.title)
+        || note.aliases().iter().any(|a| query.is_subsequence_of(a))
+}
+
+/// Each tag of `notes` with the number of notes that have it, most used
+/// first, then by name.
+pub fn tag_counts<'a>(notes: impl IntoIterator<Item = &'a Note>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for tag in notes.into_iter().flat_map(|note| &note.tags) {
+        match counts.iter_mut().find(|(t, _)| t == tag) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((tag.clone(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// An alias of `note` that another of `notes` also claims, with that note.
+pub fn alias_conflict<'a>(
+    note: &Note,
+    notes: impl IntoIterator<Item = &'a Note>,
+) -> Option<(String, &'a Note)> {
+    let aliases = note.aliases();
+    if aliases.is_empty() {
+        return None;
+    }
+    notes
+        .into_iter()
+        .filter(|other| other.id != note.id)
+        .find_map(|other| {
+            let alias = aliases.iter().find(|a| other.has_alias(a))?;
+            Some((alias.clone(), other))
+        })
+}
+
+/// Index of the next (or previous) note matching `query`, starting at `from`
+/// (or just past it with `skip_from`) and wrapping around the list. The flag
+/// tells whether the search wrapped.
+pub fn find_note_match(
+    notes: &[&Note],
+    from: usize,
+    query: &str,
+    forward: bool,
+    skip_from: bool,
+) -> Option<(usize, bool)> {
+    let len = notes.len();
+    if len == 0 || query.is_empty() {
+        return None;
+    }
+    let query = FuzzyQuery::new(query);
+    let from = from.min(len - 1);
+    let first = usize::from(skip_from);
+    (first..first + len).find_map(|offset| {
+        let index = if forward {
+            (from + offset) % len
+        } else {
+            (from + len - offset % len) % len
+        };
+        let wrapped = offset > 0
+            && if forward {
+                index <= from
+            } else {
+                index >= from
+            };
+        note_matches(notes[index], &query).then_some((index, wrapped))
+    })
+}
+
+/// 1-based position of the note at `selected` among the notes matching
+/// `query` (`None` if it does not match), and the number of matches.
+pub fn note_match_index(notes: &[&Note], selected: usize, query: &str) -> (Option<usize>, usize) {
+    let query = FuzzyQuery::new(query);
+    let mut position = None;
+    let mut count = 0;
+    for (index, note) in notes.iter().enumerate() {
+        if note_matches(note, &query) {
+            count += 1;
+            if index == selected {
+                position = Some(count);
+            }
+        }
+    }
+    (position, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes() -> Vec<Note> {
+        ["Groceries", "Meeting notes", "Ideas", "Team meeting"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| Note::new(i.to_string(), title.to_string(), title.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_note_match_wraps() {
+        let notes = notes();
+        let refs: Vec<&Note> = notes.iter().collect();
+        assert_eq!(
+            find_note_match(&refs, 0, "meeting", true, false),
+            Some((1, false))
+        );
+        assert_eq!(
+            find_note_match(&refs, 1, "meeting", true, false),
+            Some((1, false))
+        );
+        assert_eq!(
+            find_note_match(&refs, 1, "meeting", true, true),
+            Some((3, false))
+        );
+        assert_eq!(
+            find_note_match(&refs, 3, "meeting", true, true),
+            Some((1, true))
+        );
+        // Smart case: an uppercase letter makes case matter
+        assert_eq!(
+            find_note_match(&refs, 1, "Meeting", true, true),
+            Some((1, true))
+        );
+        assert_eq!(
+            find_note_match(&refs, 1, "meeting", false, true),
+            Some((3, true))
+        );
+        assert_eq!(
+            find_note_match(&refs, 3, "ideas", true, true),
+            Some((2, true))
+        );
+        assert_eq!(
+            find_note_match(&refs, 2, "ideas", true, true),
+            Some((2, true))
+        );
+        assert_eq!(find_note_match(&refs, 0, "nothing", true, false), None);
+        assert_eq!(find_note_match(&refs, 0, "", true, false), None);
+    }
+
+    #[test]
+    fn test_aliases() {
+        let plan = Note::new(
+            "p".to_string(),
+            "Plan".to_string(),
+            "---\naliases: [Roadmap]\n---\n# Plan".to_string(),
+        );
+        let other = Note::new(
+            "o".to_string(),
+            "Other".to_string(),
+            "---\naliases: roadmap, x\n---\n# Other".to_string(),
+        );
+        assert!(name_matches(&plan, &FuzzyQuery::new("rdmp")));
+        assert!(!name_matches(&other, &FuzzyQuery::new("plan")));
+
+        let notes = notes();
+        assert!(alias_conflict(&plan, &notes).is_none());
+        let all = [plan.clone(), other];
+        let (alias, by) = alias_conflict(&plan, &all).unwrap();
+        assert_eq!((alias.as_str(), by.id.as_str()), ("Roadmap", "o"));
+    }
+
+    #[test]
+    fn test_tag_queries() {
+        let note = Note::new(
+            "t".to_string(),
+            "Standup".to_string(),
+            "# Standup\n#work/meetings, see #Home".to_string(),
+        );
+        assert_eq!(note.tags, ["home", "work/meetings"]);
+        assert!(note_matches(&note, &FuzzyQuery::new("#work")));
+        assert!(note_matches(&note, &FuzzyQuery::new("#HOME")));
+        assert!(!note_matches(&note, &FuzzyQuery::new("#wor")));
+        // A tag query ignores the text: `#standup` is not a tag here
+        assert!(!note_matches(&note, &FuzzyQuery::new("#standup")));
+        assert!(note_matches(&note, &FuzzyQuery::new("standup")));
+
+        let draft = Note::new(
+            "d".to_string(),
+            "Pitch".to_string(),
+            "+++\nStatus = \"In Review\"\n+++\n# Pitch".to_string(),
+        );
+        assert!(note_matches(&draft, &FuzzyQuery::new("status:review")));
+        assert!(!note_matches(&draft, &FuzzyQuery::new("status:done")));
+        assert!(!note_matches(&note, &FuzzyQuery::new("status:review")));
+
+        let other = Note::new("o".to_string(), "O".to_string(), "#home #zen".to_string());
+        assert_eq!(
+            tag_counts([&note, &other]),
+            [
+                ("home".to_string(), 2),
+                ("work/meetings".to_string(), 1),
+                ("zen".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_note_match_index() {
+        let notes = notes();
+        let refs: Vec<&Note> = notes.iter().collect();
+        assert_eq!(note_match_index(&refs, 3, "meeting"), (Some(2), 2));
+        assert_eq!(note_match_index(&refs, 0, "meeting"), (None, 2));
+        assert_eq!(note_match_index(&refs, 0, "nothing"), (None, 0));
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use crate::types::Note;
+
+/// How long `u` in a list view can take back a delete or archive.
+pub const UNDO_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalKind {
+    Deleted,
+    Archived,
+}
+
+/// Notes taken out of a list by the last delete or archive, kept as they
+/// were so they can be written back.
+#[derive(Debug, Clone)]
+pub struct RecentRemoval {
+    pub kind: RemovalKind,
+    pub notes: Vec<Note>,
+    removed_at: Instant,
+}
+
+impl RecentRemoval {
+    pub fn new(kind: RemovalKind, notes: Vec<Note>) -> Self {
+        Self {
+            kind,
+            notes,
+            removed_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.removed_at) >= UNDO_WINDOW
+    }
+
+    /// The status message offering the undo.
+    pub fn message(&self) -> String {
+        let verb = match self.kind {
+            RemovalKind::Deleted => "deleted",
+            RemovalKind::Archived => "archived",
+        };
+        match self.notes.len() {
+            1 => format!("Note {} (u to undo)", verb),
+            n => format!("{} notes {} (u to undo)", n, verb),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires_after_window() {
+        let note = Note::new("1".to_string(), "One".to_string(), "# One".to_string());
+        let removal = RecentRemoval::new(RemovalKind::Deleted, vec![note.clone()]);
+        assert!(!removal.is_expired(Instant::now()));
+        assert!(removal.is_expired(Instant::now() + UNDO_WINDOW));
+        assert_eq!(removal.message(), "Note deleted (u to undo)");
+
+        let removal = RecentRemoval::new(RemovalKind::Archived, vec![note.clone(), note]);
+        assert_eq!(removal.message(), "2 notes archived (u to undo)");
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::types::Note;
+
+/// What happened to a note during a review, besides being kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewAction {
+    Archive,
+    Process,
+    Tag(String),
+}
+
+/// A heading of the summary and which notes go under it.
+type SummarySection = (&'static str, fn(&ReviewItem) -> bool);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ReviewItem {
+    id: String,
+    title: String,
+    archived: bool,
+    processed: bool,
+    tags: Vec<String>,
+    reviewed: bool,
+}
+
+/// `:review`: the drafts modified since a date, gone through one at a time,
+/// and what was done with each for the summary note.
+#[derive(Debug, Clone)]
+pub struct ReviewSession {
+    since: DateTime<Utc>,
+    items: Vec<ReviewItem>,
+    index: usize,
+}
+
+impl ReviewSession {
+    /// A review of the notes modified after `since`, oldest first, or
+    /// `None` if there are none.
+    pub fn new(notes: &[Note], since: DateTime<Utc>) -> Option<Self> {
+        let mut recent: Vec<&Note> = notes.iter().filter(|n| n.updated_at > since).collect();
+        if recent.is_empty() {
+            return None;
+        }
+        recent.sort_by_key(|n| n.updated_at);
+        let items = recent
+            .iter()
+            .map(|n| ReviewItem {
+                id: n.id.clone(),
+                title: n.title.clone(),
+                ..ReviewItem::default()
+            })
+            .collect();
+        Some(Self {
+            since,
+            items,
+            index: 0,
+        })
+    }
+
+    /// Id of the note being reviewed, `None` once past the last one.
+    pub fn current(&self) -> Option<&str> {
+        self.items.get(self.index).map(|item| item.id.as_str())
+    }
+
+    /// 1-based number of the current note and the number of notes.
+    pub fn position(&self) -> (usize, usize) {
+        (self.index + 1, self.items.len())
+    }
+
+    pub fn record(&mut self, action: ReviewAction) {
+        let Some(item) = self.items.get_mut(self.index) else {
+            return;
+        };
+        match action {
+            ReviewAction::Archive => item.archived = true,
+            ReviewAction::Process => item.processed = true,
+            ReviewAction::Tag(tag) => {
+                if !item.tags.contains(&tag) {
+                    item.tags.push(tag);
+                }
+            }
+        }
+    }
+
+    /// Done with the current note. Returns whether another one follows.
+    pub fn advance(&mut self) -> bool {
+        if let Some(item) = self.items.get_mut(self.index) {
+            item.reviewed = true;
+            self.index += 1;
+        }
+        self.index < self.items.len()
+    }
+
+    /// The summary note: the notes archived, processed, tagged, kept as
+    /// they were and, if the review was stopped early, not reviewed, as
+    /// `[[links]]`.
+    pub fn summary(&self, today: NaiveDate) -> String {
+        let mut out = format!(
+            "# Weekly review {}\n\n{} notes modified since {}.\n",
+            today.format("%Y-%m-%d"),
+            self.items.len(),
+            self.since.date_naive().format("%Y-%m-%d")
+        );
+        let sections: [SummarySection; 5] = [
+            ("Archived", |i| i.archived),
+            ("Processed", |i| i.processed),
+            ("Tagged", |i| !i.tags.is_empty()),
+            ("Kept", |i| {
+                i.reviewed && !i.archived && !i.processed && i.tags.is_empty()
+            }),
+            ("Not reviewed", |i| !i.reviewed),
+        ];
+        for (heading, wanted) in sections {
+            let items: Vec<&ReviewItem> = self.items.iter().filter(|i| wanted(i)).collect();
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n## {}\n\n", heading));
+            for item in items {
+                out.push_str(&format!("- [[{}]]", item.title));
+                if heading == "Tagged" {
+                    for tag in &item.tags {
+                        out.push_str(&format!(" #{}", tag));
+                    }
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn note(id: &str, days_ago: i64) -> Note {
+        let mut note = Note::new(id.to_string(), id.to_uppercase(), format!("# {}", id));
+        note.updated_at = Utc::now() - Duration::days(days_ago);
+        note
+    }
+
+    #[test]
+    fn test_review_walks_recent_notes() {
+        let since = Utc::now() - Duration::days(7);
+        let notes = [note("a", 1), note("old", 30), note("b", 3), note("c", 2)];
+        let mut review = ReviewSession::new(&notes, since).unwrap();
+        assert_eq!(review.position(), (1, 3));
+        assert_eq!(review.current(), Some("b"));
+
+        review.record(ReviewAction::Archive);
+        assert!(review.advance());
+        review.record(ReviewAction::Tag("work".to_string()));
+        review.record(ReviewAction::Process);
+        assert!(review.advance());
+        assert_eq!(review.current(), Some("a"));
+
+        let summary = review.summary(NaiveDate::from_ymd_opt(2024, 5, 8).unwrap());
+        assert!(summary.starts_with("# Weekly review 2024-05-08\n\n3 notes modified since "));
+        assert!(summary.contains("## Archived\n\n- [[B]]\n"));
+        assert!(summary.contains("## Processed\n\n- [[C]]\n"));
+        assert!(summary.contains("## Tagged\n\n- [[C]] #work\n"));
+        assert!(summary.contains("## Not reviewed\n\n- [[A]]\n"));
+        assert!(!summary.contains("## Kept"));
+
+        assert!(!review.advance());
+        assert_eq!(review.current(), None);
+        assert!(ReviewSession::new(&notes[1..2], since).is_none());
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{HashMap, HashSet};
+
+use super::fuzzy::fold;
+use crate::types::{IndexSnapshot, IndexedNote, Note};
+
+/// Bumped when the indexed form of the text changes, so older saved
+/// indexes are rebuilt instead of trusted.
+const INDEX_VERSION: u32 = 1;
+
+/// Trigram index over note titles and contents, so the list filter only
+/// checks the notes containing every trigram of the query instead of all
+/// of them.
+///
+/// Trigrams of the lowercased text without accents are packed into a `u64` (21 bits per
+/// character) and map to the sorted slots of the notes containing them. A
+/// hash of each note's text tells whether it has to be indexed again.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    notes: Vec<IndexedNote>,
+    slots: HashMap<String, u32>,
+    trigrams: HashMap<u64, Vec<u32>>,
+    changed: bool,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index `notes` from scratch.
+    pub fn build<'a>(notes: impl IntoIterator<Item = &'a Note>) -> Self {
+        let mut index = Self::new();
+        index.sync(notes);
+        index.changed = true;
+        index
+    }
+
+    pub fn from_snapshot(snapshot: IndexSnapshot) -> Self {
+        if snapshot.version != INDEX_VERSION {
+            let mut index = Self::new();
+            index.changed = true;
+            return index;
+        }
+        let len = snapshot.notes.len();
+        let slots = snapshot
+            .notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| !note.id.is_empty())
+            .map(|(slot, note)| (note.id.clone(), slot as u32))
+            .collect();
+        let trigrams = snapshot
+            .trigrams
+            .into_iter()
+            .filter_map(|(key, mut slots)| {
+                slots.retain(|&slot| (slot as usize) < len);
+                Some((pack_str(&key)?, slots))
+            })
+            .collect();
+        Self {
+            notes: snapshot.notes,
+            slots,
+            trigrams,
+            changed: false,
+        }
+    }
+
+    pub fn snapshot(&self) -> IndexSnapshot {
+        IndexSnapshot {
+            version: INDEX_VERSION,
+            notes: self.notes.clone(),
+            trigrams: self
+                .trigrams
+                .iter()
+                .map(|(&trigram, slots)| (unpack(trigram), slots.clone()))
+                .collect(),
+        }
+    }
+
+    /// Number of indexed notes.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.slots.contains_key(id)
+    }
+
+    /// Whether the index changed since it was loaded or last saved.
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.changed = false;
+    }
+
+    /// Index `note` if it is new or its text changed. Returns whether it was.
+    pub fn update(&mut self, note: &Note) -> bool {
+        let hash = note_hash(note);
+        if let Some(&slot) = self.slots.get(&note.id) {
+            if self.notes[slot as usize].hash == hash {
+                return false;
+            }
+            self.clear_slot(slot);
+        }
+        let slot = match self.notes.iter().position(|n| n.id.is_empty()) {
+            Some(free) => free,
+            None => {
+                self.notes.push(IndexedNote {
+                    id: String::new(),
+                    hash: 0,
+                });
+                self.notes.len() - 1
+            }
+        };
+        self.notes[slot] = IndexedNote {
+            id: note.id.clone(),
+            hash,
+        };
+        let slot = slot as u32;
+        self.slots.insert(note.id.clone(), slot);
+
+        let mut trigrams = trigrams_of(&fold(&note.title, false));
+        trigrams.extend(trigrams_of(&fold(&note.content, false)));
+        for trigram in trigrams {
+            let slots = self.trigrams.entry(trigram).or_default();
+            if let Err(at) = slots.binary_search(&slot) {
+                slots.insert(at, slot);
+            }
+        }
+        self.changed = true;
+        true
+    }
+
+    /// Forget a deleted note.
+    pub fn remove(&mut self, id: &str) {
+        if let Some(&slot) = self.slots.get(id) {
+            self.clear_slot(slot);
+            self.changed = true;
+        }
+    }
+
+    /// Forget every note for which `keep` is false. Returns how many were.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) -> usize {
+        let gone: Vec<u32> = self
+            .slots
+            .iter()
+            .filter(|(id, _)| !keep(id))
+            .map(|(_, &slot)| slot)
+            .collect();
+        for &slot in &gone {
+            self.clear_slot(slot);
+        }
+        if !gone.is_empty() {
+            self.changed = true;
+        }
+        gone.len()
+    }
+
+    /// Index each of `notes` that is new or changed. Returns how many were.
+    pub fn sync<'a>(&mut self, notes: impl IntoIterator<Item = &'a Note>) -> usize {
+        let mut indexed = 0;
+        for note in notes {
+            if self.update(note) {
+                indexed += 1;
+            }
+        }
+        indexed
+    }
+
+    /// Ids of the notes that may contain `query`, ignoring case and
+    /// accents; no other indexed note does. `None` for queries under three characters, which
+    /// the index cannot narrow down.
+    pub fn candidates(&self, query: &str) -> Option<HashSet<String>> {
+        let trigrams = trigrams_of(&fold(query, false));
+        if trigrams.is_empty() {
+            return None;
+        }
+        let mut lists: Vec<&[u32]> = trigrams
+            .iter()
+            .map(|trigram| self.trigrams.get(trigram).map_or(&[][..], Vec::as_slice))
+            .collect();
+        lists.sort_by_key(|slots| slots.len());
+        let mut slots = lists[0].to_vec();
+        for list in &lists[1..] {
+            slots.retain(|slot| list.binary_search(slot).is_ok());
+        }
+        Some(
+            slots
+                .into_iter()
+                .filter_map(|slot| self.notes.get(slot as usize))
+                .filter(|note| !note.id.is_empty())
+                .map(|note| note.id.clone())
+                .collect(),
+        )
+    }
+
+    fn clear_slot(&mut self, slot: u32) {
+        self.trigrams.retain(|_, slots| {
+            if let Ok(at) = slots.binary_search(&slot) {
+                slots.remove(at);
+            }
+            !slots.is_empty()
+        });
+        let note = &mut self.notes[slot as usize];
+        self.slots.remove(&note.id);
+        note.id.clear();
+    }
+}
+
+fn trigrams_of(text: &str) -> HashSet<u64> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(3).map(|w| pack(w[0], w[1], w[2])).collect()
+}
+
+fn pack(a: char, b: char, c: char) -> u64 {
+    ((a as u64) << 42) | ((b as u64) << 21) | c as u64
+}
+
+fn pack_str(key: &str) -> Option<u64> {
+    let mut chars = key.chars();
+    let packed = pack(chars.next()?, chars.next()?, chars.next()?);
+    chars.next().is_none().then_some(packed)
+}
+
+fn unpack(trigram: u64) -> String {
+    [
+        trigram >> 42,
+        (trigram >> 21) & 0x1f_ffff,
+        trigram & 0x1f_ffff,
+    ]
+    .into_iter()
+    .filter_map(|c| char::from_u32(c as u32))
+    .collect()
+}
+
+/// FNV-1a over the title and content, stable across runs.
+pub(super) fn note_hash(note: &Note) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    let bytes = note.title.bytes().chain([0xff]).chain(note.content.bytes());
+    for byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, title: &str, content: &str) -> Note {
+        Note::new(id.to_string(), title.to_string(), content.to_string())
+    }
+
+    fn sorted(ids: Option<HashSet<String>>) -> Option<Vec<String>> {
+        ids.map(|ids| {
+            let mut ids: Vec<String> = ids.into_iter().collect();
+            ids.sort();
+            ids
+        })
+    }
+
+    #[test]
+    fn test_candidates_follow_updates() {
+        let notes = [
+            note("a", "Groceries", "milk and eggs"),
+            note("b", "Meeting", "Agenda: budget"),
+            note("c", "会议记录", "预算讨论"),
+        ];
+        let mut index = SearchIndex::build(&notes);
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            sorted(index.candidates("EGGS")),
+            Some(vec!["a".to_string()])
+        );
+        assert_eq!(
+            sorted(index.candidates("预算讨")),
+            Some(vec!["c".to_string()])
+        );
+        assert_eq!(sorted(index.candidates("zebra")), Some(vec![]));
+        // Accents are dropped on both sides
+        let accented = SearchIndex::build(&[note("e", "Café", "crème brûlée")]);
+        assert_eq!(
+            sorted(accented.candidates("CREME BRULEE")),
+            Some(vec!["e".to_string()])
+        );
+        // Too short to narrow down
+        assert_eq!(index.candidates("mi"), None);
+
+        // Unchanged notes are skipped
+        assert_eq!(index.sync(&notes), 0);
+        assert!(index.update(&note("a", "Groceries", "budget coffee")));
+        assert_eq!(
+            sorted(index.candidates("budget")),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(sorted(index.candidates("eggs")), Some(vec![]));
+
+        index.remove("b");
+        assert!(!index.contains("b"));
+        assert_eq!(index.retain(|id| id != "c"), 1);
+        assert_eq!(index.len(), 1);
+        assert_eq!(
+            sorted(index.candidates("budget")),
+            Some(vec!["a".to_string()])
+        );
+        // Freed slots are reused
+        index.update(&note("d", "Budget", ""));
+        assert_eq!(index.snapshot().notes.len(), 3);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let notes = [
+            note("a", "Plan", "line one\nline two"),
+            note("b", "x", "\"q.\""),
+        ];
+        let index = SearchIndex::build(&notes);
+        assert!(index.is_changed());
+
+        // Indexes saved in an older format are rebuilt
+        let mut old = index.snapshot();
+        old.version = 0;
+        let rebuilt = SearchIndex::from_snapshot(old);
+        assert!(rebuilt.is_empty() && rebuilt.is_changed());
+
+        let loaded = SearchIndex::from_snapshot(index.snapshot());
+        assert!(!loaded.is_changed());
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(
+            sorted(loaded.candidates("e\nl")),
+            Some(vec!["a".to_string()])
+        );
+        assert_eq!(
+            sorted(loaded.candidates("\"q.")),
+            Some(vec!["b".to_string()])
+        );
+        assert_eq!(
+            sorted(loaded.candidates("one")),
+            Some(vec!["a".to_string()])
+        );
+    }
+}
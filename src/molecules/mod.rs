@@ -1,4 +1,8 @@
+pub mod capture;
 pub mod config;
 pub mod distribution;
 pub mod editor;
+pub mod import;
 pub mod list;
+pub mod share;
+pub mod stats;
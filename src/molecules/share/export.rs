@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use regex::Regex;
+
+use crate::atoms::storage::{expand_tilde, title_slug};
+use crate::types::strip_frontmatter;
+
+/// Markdown link or image: `[text](target)` / `![alt](target)`.
+static LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+
+static COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->\n?").unwrap());
+
+fn is_remote(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:") || target.starts_with('#')
+}
+
+fn resolve_target(target: &str, note_dir: &Path) -> PathBuf {
+    let path = expand_tilde(target);
+    if path.is_absolute() {
+        path
+    } else {
+        note_dir.join(path)
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") => "audio/mp4",
+        Some("wav") => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Local files referenced by links or images in `content` that exist on disk.
+/// Relative targets are resolved against `note_dir`.
+pub fn find_local_assets(content: &str, note_dir: &Path) -> Vec<PathBuf> {
+    let mut assets: Vec<PathBuf> = Vec::new();
+    for caps in LINK_RE.captures_iter(content) {
+        let target = &caps[3];
+        if is_remote(target) {
+            continue;
+        }
+        let path = resolve_target(target, note_dir);
+        if path.is_file() && !assets.contains(&path) {
+            assets.push(path);
+        }
+    }
+    assets
+}
+
+/// Prepare a note for sending to someone else: frontmatter and HTML comments
+/// are removed, local images are inlined as base64 data URIs, and other local
+/// links are reduced to their text since the recipient cannot open them.
+pub fn sanitize_for_sharing(content: &str, note_dir: &Path) -> String {
+    let body = COMMENT_RE.replace_all(strip_frontmatter(content), "");
+
+    LINK_RE
+        .replace_all(&body, |caps: &regex::Captures| {
+            let (bang, text, target) = (&caps[1], &caps[2], &caps[3]);
+            if is_remote(target) {
+                return caps[0].to_string();
+            }
+            let path = resolve_target(target, note_dir);
+            match std::fs::read(&path) {
+                Ok(bytes) if bang == "!" => format!(
+                    "![{}](data:{};base64,{})",
+                    text,
+                    mime_type(&path),
+                    STANDARD.encode(bytes)
+                ),
+                _ => text.to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Point local asset links at `assets/<file name>` for a bundled copy.
+pub fn rewrite_asset_links(content: &str, note_dir: &Path) -> String {
+    LINK_RE
+        .replace_all(content, |caps: &regex::Captures| {
+            let target = &caps[3];
+            let path = resolve_target(target, note_dir);
+            match path.file_name() {
+                Some(name) if !is_remote(target) && path.is_file() => format!(
+                    "{}[{}](assets/{})",
+                    &caps[1],
+                    &caps[2],
+                    name.to_string_lossy()
+                ),
+                _ => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// What `:export` writes: the shareable markdown, a standalone HTML page, or
+/// that page turned into a PDF by `general.pdf_command`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+        ExportFormat::Pdf,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "html" | "htm" => Some(ExportFormat::Html),
+            "pdf" => Some(ExportFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Pdf => "PDF",
+        }
+    }
+}
+
+/// File-name friendly version of a note title.
+pub fn share_file_stem(title: &str) -> String {
+    title_slug(title, "note")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kenotex-share-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sanitize_inlines_images_and_strips_private_parts() {
+        let dir = temp_dir();
+        fs::write(dir.join("dot.png"), [1u8, 2, 3]).unwrap();
+        let content = "---\nword_goal: 10\n---\n# Trip\n<!-- sent to Reminders -->\n\
+                       ![map](dot.png) [plan](plan.pdf) [site](https://example.com)";
+
+        let shared = sanitize_for_sharing(content, &dir);
+        assert_eq!(
+            shared,
+            "# Trip\n![map](data:image/png;base64,AQID) plan [site](https://example.com)"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_share_file_stem() {
+        assert_eq!(share_file_stem("Trip: Kyoto / Osaka!"), "trip-kyoto-osaka");
+        assert_eq!(share_file_stem("???"), "note");
+        assert_eq!(share_file_stem("会议 记录"), "会议-记录");
+    }
+
+    #[test]
+    fn test_find_local_assets() {
+        let dir = temp_dir();
+        fs::write(dir.join("a.txt"), "x").unwrap();
+        let content = "[a](a.txt) [again](a.txt) [gone](missing.txt) [web](http://x.y/a.txt)";
+
+        assert_eq!(find_local_assets(content, &dir), vec![dir.join("a.txt")]);
+        assert_eq!(
+            rewrite_asset_links(content, &dir),
+            "[a](assets/a.txt) [again](assets/a.txt) [gone](missing.txt) [web](http://x.y/a.txt)"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
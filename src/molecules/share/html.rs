@@ -0,0 +1,259 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::types::Theme;
+
+static IMAGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+static LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap());
+static BOLD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*\*(.+?)\*\*").unwrap());
+static ITALIC_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*([^*\s][^*]*)\*|\b_([^_\s][^_]*)_\b").unwrap());
+static STRIKE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"~~(.+?)~~").unwrap());
+static ORDERED_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d+[.)]\s+").unwrap());
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One line of markdown text as HTML: code spans, images, links, bold,
+/// italic and strikethrough; everything else escaped.
+fn inline(text: &str) -> String {
+    let mut html = String::new();
+    for (i, part) in text.split('`').enumerate() {
+        // Odd parts sit between backticks; an unpaired one stays literal
+        if i % 2 == 1 && text.matches('`').count() > i {
+            html.push_str(&format!("<code>{}</code>", escape(part)));
+            continue;
+        }
+        if i > 0 && i % 2 == 1 {
+            html.push('`');
+        }
+        let part = escape(part);
+        let part = IMAGE_RE.replace_all(&part, r#"<img src="$2" alt="$1">"#);
+        let part = LINK_RE.replace_all(&part, r#"<a href="$2">$1</a>"#);
+        let part = BOLD_RE.replace_all(&part, "<strong>$1</strong>");
+        let part = ITALIC_RE.replace_all(&part, "<em>$1$2</em>");
+        let part = STRIKE_RE.replace_all(&part, "<del>$1</del>");
+        html.push_str(&part);
+    }
+    html
+}
+
+/// Text of a list item and whether the list is ordered.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+    {
+        return Some((false, rest));
+    }
+    ORDERED_RE
+        .find(trimmed)
+        .map(|m| (true, &trimmed[m.end()..]))
+}
+
+fn list_item_html(text: &str) -> String {
+    let checkbox = |checked: bool, rest: &str| {
+        let checked = if checked { " checked" } else { "" };
+        format!(
+            "<input type=\"checkbox\" disabled{}> {}",
+            checked,
+            inline(rest)
+        )
+    };
+    if let Some(rest) = text.strip_prefix("[ ] ") {
+        checkbox(false, rest)
+    } else if let Some(rest) = text
+        .strip_prefix("[x] ")
+        .or_else(|| text.strip_prefix("[X] "))
+    {
+        checkbox(true, rest)
+    } else {
+        inline(text)
+    }
+}
+
+/// The body of an HTML page for `markdown`: headings, paragraphs, lists
+/// and task lists, quotes, fenced code and rules.
+fn body(markdown: &str) -> String {
+    let mut html = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut quote: Vec<String> = Vec::new();
+    // Open list: ordered, items
+    let mut list: Option<(bool, Vec<String>)> = None;
+    let mut code: Option<(String, Vec<String>)> = None;
+
+    let flush = |html: &mut Vec<String>,
+                 paragraph: &mut Vec<String>,
+                 quote: &mut Vec<String>,
+                 list: &mut Option<(bool, Vec<String>)>| {
+        if !paragraph.is_empty() {
+            html.push(format!("<p>{}</p>", paragraph.join("\n")));
+            paragraph.clear();
+        }
+        if !quote.is_empty() {
+            html.push(format!(
+                "<blockquote><p>{}</p></blockquote>",
+                quote.join("<br>\n")
+            ));
+            quote.clear();
+        }
+        if let Some((ordered, items)) = list.take() {
+            let tag = if ordered { "ol" } else { "ul" };
+            let items: String = items.iter().map(|i| format!("<li>{}</li>", i)).collect();
+            html.push(format!("<{}>{}</{}>", tag, items, tag));
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some((lang, lines)) = &mut code {
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                let class = if lang.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"language-{}\"", escape(lang))
+                };
+                html.push(format!(
+                    "<pre><code{}>{}</code></pre>",
+                    class,
+                    escape(&lines.join("\n"))
+                ));
+                code = None;
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush(&mut html, &mut paragraph, &mut quote, &mut list);
+            code = Some((trimmed[3..].trim().to_string(), Vec::new()));
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut html, &mut paragraph, &mut quote, &mut list);
+            continue;
+        }
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            flush(&mut html, &mut paragraph, &mut quote, &mut list);
+            let text = trimmed[hashes..].trim().trim_end_matches('#').trim_end();
+            html.push(format!("<h{}>{}</h{}>", hashes, inline(text), hashes));
+            continue;
+        }
+        if matches!(trimmed.trim_end(), "---" | "***" | "___") {
+            flush(&mut html, &mut paragraph, &mut quote, &mut list);
+            html.push("<hr>".to_string());
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix('>') {
+            if quote.is_empty() {
+                flush(&mut html, &mut paragraph, &mut quote, &mut list);
+            }
+            quote.push(inline(rest.trim_start()));
+            continue;
+        }
+        if let Some((ordered, text)) = list_item(line) {
+            if list.as_ref().is_none_or(|(o, _)| *o != ordered) {
+                flush(&mut html, &mut paragraph, &mut quote, &mut list);
+                list = Some((ordered, Vec::new()));
+            }
+            if let Some((_, items)) = &mut list {
+                items.push(list_item_html(text));
+            }
+            continue;
+        }
+        if list.is_some() || !quote.is_empty() {
+            flush(&mut html, &mut paragraph, &mut quote, &mut list);
+        }
+        paragraph.push(inline(trimmed));
+    }
+    if let Some((_, lines)) = code {
+        html.push(format!(
+            "<pre><code>{}</code></pre>",
+            escape(&lines.join("\n"))
+        ));
+    }
+    flush(&mut html, &mut paragraph, &mut quote, &mut list);
+    html.join("\n")
+}
+
+/// `markdown` as a standalone HTML page titled `title`, styled with the
+/// colors of `theme`.
+pub fn render_html(markdown: &str, title: &str, theme: &Theme) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ background: {bg}; color: {fg}; font-family: -apple-system, system-ui, sans-serif; line-height: 1.6; max-width: 46em; margin: 2em auto; padding: 0 1em; }}
+h1, h2, h3, h4, h5, h6 {{ color: {accent}; }}
+a {{ color: {accent}; }}
+code, pre {{ background: {panel}; font-family: ui-monospace, monospace; }}
+code {{ padding: 0.1em 0.3em; border-radius: 3px; }}
+pre {{ padding: 0.8em; border-radius: 4px; overflow-x: auto; }}
+pre code {{ padding: 0; }}
+blockquote {{ border-left: 3px solid {border}; margin-left: 0; padding-left: 1em; }}
+hr {{ border: none; border-top: 1px solid {border}; }}
+del {{ color: {border}; }}
+img {{ max-width: 100%; }}
+</style>
+</head>
+<body>
+{body}
+</body>
+</html>
+",
+        title = escape(title),
+        bg = theme.bg,
+        fg = theme.fg,
+        accent = theme.accent,
+        panel = theme.panel,
+        border = theme.border,
+        body = body(markdown),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let markdown = "# Plan <v2>\nSome **bold**, *it* and `a<b`\nsame paragraph\n\n\
+                        - [x] done\n- [ ] [link](https://x.y?a=1&b=2)\n1. one\n\n\
+                        > quoted\n```rust\nlet x = 1 < 2;\n```\n---";
+        assert_eq!(
+            body(markdown),
+            "<h1>Plan &lt;v2&gt;</h1>\n\
+             <p>Some <strong>bold</strong>, <em>it</em> and <code>a&lt;b</code>\nsame paragraph</p>\n\
+             <ul><li><input type=\"checkbox\" disabled checked> done</li>\
+             <li><input type=\"checkbox\" disabled> <a href=\"https://x.y?a=1&amp;b=2\">link</a></li></ul>\n\
+             <ol><li>one</li></ol>\n\
+             <blockquote><p>quoted</p></blockquote>\n\
+             <pre><code class=\"language-rust\">let x = 1 &lt; 2;</code></pre>\n\
+             <hr>"
+        );
+        assert_eq!(inline("snake_case_name and 1`2"), "snake_case_name and 1`2");
+    }
+
+    #[test]
+    fn test_page_uses_theme_colors() {
+        let theme = crate::molecules::config::ThemeManager::new()
+            .current()
+            .clone();
+        let page = render_html("hi", "A & B", &theme);
+        assert!(page.contains("<title>A &amp; B</title>"));
+        assert!(page.contains(&format!("background: {};", theme.bg)));
+        assert!(page.contains("<p>hi</p>"));
+    }
+}
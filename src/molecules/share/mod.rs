@@ -0,0 +1,9 @@
+mod export;
+mod html;
+mod transclude;
+
+pub use export::{
+    ExportFormat, find_local_assets, rewrite_asset_links, sanitize_for_sharing, share_file_stem,
+};
+pub use html::render_html;
+pub use transclude::expand_transclusions;
@@ -0,0 +1,133 @@
+use crate::molecules::editor::outline;
+use crate::types::strip_frontmatter;
+
+/// Longest chain of notes embedding one another that is expanded.
+const MAX_DEPTH: usize = 8;
+
+/// `![[Title]]` or `![[Title#Heading]]` (optionally `|label`) alone on a
+/// line: the title and heading.
+fn embed(line: &str) -> Option<(&str, Option<&str>)> {
+    let inner = line.trim().strip_prefix("![[")?.strip_suffix("]]")?;
+    if inner.contains(['[', ']']) {
+        return None;
+    }
+    let inner = inner.split('|').next().unwrap_or(inner);
+    let (title, heading) = match inner.split_once('#') {
+        Some((title, heading)) => (title.trim(), Some(heading.trim())),
+        None => (inner.trim(), None),
+    };
+    (!title.is_empty()).then_some((title, heading.filter(|h| !h.is_empty())))
+}
+
+/// `content` with every line that is only `![[Title]]` replaced by the body
+/// of that note, and `![[Title#Heading]]` by that section, heading line
+/// included. `lookup` gives the content of the note a title or alias names.
+/// Embeds in the embedded text are expanded too, except ones leading back
+/// to a note already being expanded (starting with `title`); embeds that
+/// resolve to nothing and those in fenced code stay as written.
+/// Only export and `--share` call this; the editor shows embeds as written.
+pub fn expand_transclusions(
+    content: &str,
+    title: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    expand(content, lookup, &mut vec![title.to_lowercase()])
+}
+
+fn expand(
+    content: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    open: &mut Vec<String>,
+) -> String {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in content.split('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            lines.push(line.to_string());
+            continue;
+        }
+        let expanded = embed(line).and_then(|(target, heading)| {
+            let key = target.to_lowercase();
+            if open.len() >= MAX_DEPTH || open.contains(&key) {
+                return None;
+            }
+            let text = excerpt(&lookup(target)?, heading)?;
+            open.push(key);
+            let text = expand(&text, lookup, open);
+            open.pop();
+            Some(text)
+        });
+        lines.push(expanded.unwrap_or_else(|| line.to_string()));
+    }
+    lines.join("\n")
+}
+
+/// The body of a note, or its section under `heading` (any case) down to the
+/// next heading of the same or a higher level.
+fn excerpt(content: &str, heading: Option<&str>) -> Option<String> {
+    let body = strip_frontmatter(content);
+    let Some(heading) = heading else {
+        return Some(body.trim_end().to_string());
+    };
+    let lines: Vec<&str> = body.lines().collect();
+    let entries = outline(lines.iter().copied());
+    let heading = heading.to_lowercase();
+    let start = entries
+        .iter()
+        .position(|e| e.title.to_lowercase() == heading)?;
+    let end = entries[start + 1..]
+        .iter()
+        .find(|e| e.level <= entries[start].level)
+        .map_or(lines.len(), |e| e.row);
+    Some(
+        lines[entries[start].row..end]
+            .join("\n")
+            .trim_end()
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(title: &str) -> Option<String> {
+        match title.to_lowercase().as_str() {
+            "intro" => Some("---\ntags: [a]\n---\n# Intro\nHello.\n".to_string()),
+            "plan" => {
+                Some("# Plan\n## Goals\nShip it\n### Detail\nsoon\n## Risks\nnone".to_string())
+            }
+            "loop" => Some("# Loop\n![[Book]]".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_notes_and_sections() {
+        let book = "# Book\n![[Intro]]\n\n![[plan#goals|the goals]]\n![[Missing]]";
+        assert_eq!(
+            expand_transclusions(book, "Book", &lookup),
+            "# Book\n# Intro\nHello.\n\n## Goals\nShip it\n### Detail\nsoon\n![[Missing]]"
+        );
+        // Inline embeds, code and unknown headings are left alone
+        let text = "see ![[Intro]]\n```\n![[Intro]]\n```\n![[Plan#Budget]]";
+        assert_eq!(expand_transclusions(text, "Book", &lookup), text);
+    }
+
+    #[test]
+    fn test_cycles_stop() {
+        assert_eq!(
+            expand_transclusions("# Book\n![[Loop]]", "Book", &lookup),
+            "# Book\n# Loop\n![[Book]]"
+        );
+    }
+}
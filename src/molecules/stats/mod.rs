@@ -0,0 +1,5 @@
+mod session;
+mod word_count;
+
+pub use session::{WritingSession, day_key, summarize, writing_streak};
+pub use word_count::count_words;
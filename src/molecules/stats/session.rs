@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+
+use crate::types::{StatsSummary, WritingHistory};
+
+/// Words written during one run of the app, tracked per note.
+#[derive(Debug, Clone)]
+pub struct WritingSession {
+    started_at: DateTime<Local>,
+    /// Word count of each note before its first save this session.
+    baselines: HashMap<String, usize>,
+    current: HashMap<String, usize>,
+    /// Words already recorded for each day before this session touched it.
+    day_bases: HashMap<String, usize>,
+    /// Session words credited to each day so far.
+    credited: HashMap<String, usize>,
+}
+
+impl WritingSession {
+    pub fn new(started_at: DateTime<Local>, history: &WritingHistory) -> Self {
+        let start_day = day_key(started_at.date_naive());
+        let day_base = history.days.get(&start_day).copied().unwrap_or(0);
+        Self {
+            started_at,
+            baselines: HashMap::new(),
+            current: HashMap::new(),
+            day_bases: HashMap::from([(start_day, day_base)]),
+            credited: HashMap::new(),
+        }
+    }
+
+    pub fn started_at(&self) -> DateTime<Local> {
+        self.started_at
+    }
+
+    /// Record a save of `note_id` whose body went from `before` to `after` words.
+    pub fn record(&mut self, note_id: &str, before: usize, after: usize) {
+        self.baselines.entry(note_id.to_string()).or_insert(before);
+        self.current.insert(note_id.to_string(), after);
+    }
+
+    /// Net words added this session; notes that shrank count as zero.
+    pub fn words_written(&self) -> usize {
+        self.current
+            .iter()
+            .map(|(id, &now)| now.saturating_sub(self.baselines[id]))
+            .sum()
+    }
+
+    pub fn notes_touched(&self) -> usize {
+        self.current
+            .iter()
+            .filter(|(id, now)| self.baselines[*id] != **now)
+            .count()
+    }
+
+    /// Write this session's total into `history`: the words not yet credited
+    /// to an earlier day go to the day of `now`, so a session running past
+    /// midnight splits across both days.
+    pub fn apply_to(&mut self, history: &mut WritingHistory, now: DateTime<Local>) {
+        let day = day_key(now.date_naive());
+        let earlier: usize = self
+            .credited
+            .iter()
+            .filter(|(d, _)| **d != day)
+            .map(|(_, words)| words)
+            .sum();
+        let words = self.words_written().saturating_sub(earlier);
+        let base = *self
+            .day_bases
+            .entry(day.clone())
+            .or_insert_with(|| history.days.get(&day).copied().unwrap_or(0));
+        self.credited.insert(day.clone(), words);
+        history.days.insert(day, base + words);
+    }
+
+    /// One-line summary suitable for appending to a writing log.
+    pub fn log_line(&self, ended_at: DateTime<Local>) -> String {
+        format!(
+            "{} {}-{}  +{} words across {} notes",
+            self.started_at.format("%Y-%m-%d"),
+            self.started_at.format("%H:%M"),
+            ended_at.format("%H:%M"),
+            self.words_written(),
+            self.notes_touched()
+        )
+    }
+}
+
+pub fn day_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Consecutive days with writing, ending today (or yesterday if nothing has
+/// been written yet today).
+pub fn writing_streak(history: &WritingHistory, today: NaiveDate) -> usize {
+    let written = |date: NaiveDate| history.days.get(&day_key(date)).is_some_and(|&w| w > 0);
+    let mut day = if written(today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while written(day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+pub fn summarize(
+    session: &WritingSession,
+    history: &WritingHistory,
+    now: DateTime<Local>,
+) -> StatsSummary {
+    let today = now.date_naive();
+    let words_on = |date: NaiveDate| history.days.get(&day_key(date)).copied().unwrap_or(0);
+    let last_week = (0..7)
+        .rev()
+        .map(|offset| {
+            let date = today - Duration::days(offset);
+            (day_key(date), words_on(date))
+        })
+        .collect();
+    let best_day = history
+        .days
+        .iter()
+        .filter(|(_, w)| **w > 0)
+        .max_by_key(|(_, w)| **w)
+        .map(|(d, w)| (d.clone(), *w));
+
+    StatsSummary {
+        session_words: session.words_written(),
+        session_notes: session.notes_touched(),
+        session_minutes: (now - session.started_at()).num_minutes(),
+        today: words_on(today),
+        streak: writing_streak(history, today),
+        best_day,
+        last_week,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_session_counts_net_growth_per_note() {
+        let mut session = WritingSession::new(at(15, 9), &WritingHistory::default());
+        session.record("a", 100, 150);
+        session.record("a", 150, 180);
+        session.record("b", 40, 10);
+        assert_eq!(session.words_written(), 80);
+        assert_eq!(session.notes_touched(), 2);
+        assert_eq!(
+            session.log_line(at(15, 10)),
+            "2026-10-15 09:00-10:00  +80 words across 2 notes"
+        );
+    }
+
+    #[test]
+    fn test_apply_adds_to_existing_day() {
+        let mut history = WritingHistory::default();
+        history.days.insert("2026-10-15".to_string(), 200);
+        let mut session = WritingSession::new(at(15, 9), &history);
+        session.record("a", 0, 50);
+        session.apply_to(&mut history, at(15, 9));
+        session.record("a", 50, 70);
+        session.apply_to(&mut history, at(15, 10));
+        assert_eq!(history.days["2026-10-15"], 270);
+    }
+
+    #[test]
+    fn test_apply_credits_words_to_the_day_they_were_written() {
+        let mut history = WritingHistory::default();
+        history.days.insert("2026-10-16".to_string(), 10);
+        let mut session = WritingSession::new(at(15, 23), &history);
+        session.record("a", 0, 50);
+        session.apply_to(&mut history, at(15, 23));
+        session.record("a", 50, 80);
+        session.apply_to(&mut history, at(16, 1));
+        session.record("a", 80, 90);
+        session.apply_to(&mut history, at(16, 2));
+        assert_eq!(history.days["2026-10-15"], 50);
+        assert_eq!(history.days["2026-10-16"], 50);
+    }
+
+    #[test]
+    fn test_streak_and_summary() {
+        let mut history = WritingHistory::default();
+        for (day, words) in [("2026-10-12", 10), ("2026-10-13", 300), ("2026-10-14", 20)] {
+            history.days.insert(day.to_string(), words);
+        }
+        let today = at(15, 12).date_naive();
+        assert_eq!(writing_streak(&history, today), 3);
+        history.days.insert("2026-10-15".to_string(), 5);
+        assert_eq!(writing_streak(&history, today), 4);
+
+        let session = WritingSession::new(at(15, 11), &history);
+        let summary = summarize(&session, &history, at(15, 12));
+        assert_eq!(summary.today, 5);
+        assert_eq!(summary.session_minutes, 60);
+        assert_eq!(summary.best_day, Some(("2026-10-13".to_string(), 300)));
+        assert_eq!(summary.last_week.len(), 7);
+        assert_eq!(summary.last_week[6], ("2026-10-15".to_string(), 5));
+    }
+}
@@ -0,0 +1,60 @@
+/// Returns true for scripts written without spaces between words, where each
+/// character is counted as one word (CJK ideographs, kana, hangul).
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{20000}'..='\u{2fa1f}')
+}
+
+/// Count words in Markdown text.
+///
+/// Whitespace-separated runs containing at least one alphanumeric character
+/// count once; every CJK character counts on its own. Pure markup such as
+/// `#`, `-` or `- [ ]` contributes nothing.
+pub fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    for token in text.split_whitespace() {
+        let mut run_has_alnum = false;
+        for c in token.chars() {
+            if is_cjk(c) {
+                if run_has_alnum {
+                    count += 1;
+                    run_has_alnum = false;
+                }
+                count += 1;
+            } else if c.is_alphanumeric() {
+                run_has_alnum = true;
+            }
+        }
+        if run_has_alnum {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_latin_words() {
+        assert_eq!(count_words("Hello world, don't stop"), 4);
+        assert_eq!(count_words(""), 0);
+    }
+
+    #[test]
+    fn test_markup_is_not_counted() {
+        assert_eq!(count_words("# Title\n- [ ] buy milk\n---"), 3);
+    }
+
+    #[test]
+    fn test_count_cjk() {
+        assert_eq!(count_words("你好世界"), 4);
+        assert_eq!(count_words("用Rust写"), 3);
+    }
+}
@@ -0,0 +1,340 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use kenotex::atoms::applescript::active_osascript_count;
+use kenotex::atoms::storage::{
+    FileNaming, IntegrityReport, find_note, load_all_drafts, load_config, resolve_data_dir,
+    save_draft, scan_and_repair,
+};
+use kenotex::molecules::distribution::{
+    AppleScriptDispatcher, DispatchResult, Dispatcher, RecordingDispatcher, parse_smart_blocks,
+};
+use kenotex::molecules::list::{FuzzyQuery, note_matches};
+use kenotex::types::{Note, ProcessedStyle, SmartBlock};
+
+use crate::cli::{ScriptArgs, ScriptCommand, ScriptFormat};
+
+/// A JSON value, written compactly. Object keys keep their order, so the
+/// output of each subcommand has a fixed layout.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<&str> for Json {
+    fn from(text: &str) -> Self {
+        Json::String(text.to_string())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", Json::from(*key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// `{id, title, archived, pinned, tags, created, updated}`; times in RFC 3339.
+fn note_json(note: &Note) -> Json {
+    Json::Object(vec![
+        ("id", note.id.as_str().into()),
+        ("title", note.title.as_str().into()),
+        ("archived", Json::Bool(note.is_archived)),
+        ("pinned", Json::Bool(note.is_pinned())),
+        (
+            "tags",
+            Json::Array(note.tags.iter().map(|t| t.as_str().into()).collect()),
+        ),
+        ("created", note.created_at.to_rfc3339().as_str().into()),
+        ("updated", note.updated_at.to_rfc3339().as_str().into()),
+    ])
+}
+
+/// `{clean, recovery_dir, quarantined: [{original, moved_to, reason}],
+/// osascript_children, duplicates: [{id, paths}]}`.
+fn doctor_json(report: &IntegrityReport, osascript_children: usize) -> Json {
+    let path = |p: &std::path::Path| Json::String(p.display().to_string());
+    let quarantined = report
+        .quarantined
+        .iter()
+        .map(|q| {
+            Json::Object(vec![
+                ("original", path(&q.original)),
+                ("moved_to", path(&q.moved_to)),
+                ("reason", q.reason.describe().as_str().into()),
+            ])
+        })
+        .collect();
+    let duplicates = report
+        .duplicates
+        .iter()
+        .map(|d| {
+            Json::Object(vec![
+                ("id", d.id.as_str().into()),
+                (
+                    "paths",
+                    Json::Array(d.paths.iter().map(|p| path(p)).collect()),
+                ),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("clean", Json::Bool(report.is_clean())),
+        (
+            "recovery_dir",
+            report.recovery_dir.as_deref().map_or(Json::Null, path),
+        ),
+        ("quarantined", Json::Array(quarantined)),
+        ("osascript_children", Json::Number(osascript_children)),
+        ("duplicates", Json::Array(duplicates)),
+    ])
+}
+
+/// `{note, dry_run, sent, skipped, failed, blocks: [{type, status, error}]}`.
+fn dispatch_json(note: &Note, dry_run: bool, results: &[(SmartBlock, DispatchResult)]) -> Json {
+    let count =
+        |f: fn(&DispatchResult) -> bool| Json::Number(results.iter().filter(|(_, r)| f(r)).count());
+    let blocks = results
+        .iter()
+        .map(|(block, result)| {
+            let (status, error) = match result {
+                DispatchResult::Sent => ("sent", Json::Null),
+                DispatchResult::Skipped => ("skipped", Json::Null),
+                DispatchResult::Failed(e) => ("failed", e.as_str().into()),
+            };
+            Json::Object(vec![
+                (
+                    "type",
+                    block.block_type.as_str().to_lowercase().as_str().into(),
+                ),
+                ("status", status.into()),
+                ("error", error),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("note", note.id.as_str().into()),
+        ("dry_run", Json::Bool(dry_run)),
+        ("sent", count(|r| *r == DispatchResult::Sent)),
+        ("skipped", count(|r| *r == DispatchResult::Skipped)),
+        ("failed", count(|r| matches!(r, DispatchResult::Failed(_)))),
+        ("blocks", Json::Array(blocks)),
+    ])
+}
+
+/// An Alfred script filter item that passes the note id on, for a workflow
+/// running `kenotex --open {query}`.
+fn alfred_item(note: &Note) -> Json {
+    let mut subtitle = note.updated_at.format("%Y-%m-%d").to_string();
+    if note.is_archived {
+        subtitle.push_str(" · archived");
+    }
+    for tag in &note.tags {
+        subtitle.push_str(&format!(" #{}", tag));
+    }
+    let mut words = vec![note.title.clone()];
+    words.extend(note.aliases());
+    words.extend(note.tags.iter().map(|t| format!("#{}", t)));
+    Json::Object(vec![
+        ("uid", note.id.as_str().into()),
+        ("title", note.title.as_str().into()),
+        ("subtitle", subtitle.as_str().into()),
+        ("arg", note.id.as_str().into()),
+        ("match", words.join(" ").as_str().into()),
+        ("autocomplete", note.title.as_str().into()),
+    ])
+}
+
+fn print_notes(notes: &[Note], format: ScriptFormat) {
+    match format {
+        ScriptFormat::Text => {
+            for note in notes {
+                println!("{}\t{}", note.id, note.title);
+            }
+        }
+        ScriptFormat::Json => println!("{}", Json::Array(notes.iter().map(note_json).collect())),
+        ScriptFormat::Alfred => {
+            let items = Json::Array(notes.iter().map(alfred_item).collect());
+            println!("{}", Json::Object(vec![("items", items)]));
+        }
+    }
+}
+
+/// Run `kenotex list|search|doctor|dispatch`.
+pub fn run_script(args: &ScriptArgs) -> Result<()> {
+    let json = args.format == ScriptFormat::Json;
+    let config = load_config()?;
+    let data_dir = resolve_data_dir(config.general.data_dir.as_deref());
+    match &args.command {
+        ScriptCommand::List { archived } => {
+            print_notes(&load_all_drafts(&data_dir, *archived)?, args.format);
+        }
+        ScriptCommand::Search { query } => {
+            let query = FuzzyQuery::new(query);
+            let mut notes = load_all_drafts(&data_dir, false)?;
+            notes.extend(load_all_drafts(&data_dir, true)?);
+            notes.retain(|n| note_matches(n, &query));
+            print_notes(&notes, args.format);
+        }
+        ScriptCommand::Doctor => {
+            let report = scan_and_repair(&data_dir)?;
+            let children = active_osascript_count();
+            if json {
+                println!("{}", doctor_json(&report, children));
+            } else {
+                println!(
+                    "{}",
+                    report
+                        .summary()
+                        .unwrap_or_else(|| "No damaged or duplicate note files".to_string())
+                );
+                println!("Running osascript processes: {}", children);
+            }
+        }
+        ScriptCommand::Dispatch { note, dry_run } => {
+            let Some(mut note) = find_note(&data_dir, note)? else {
+                anyhow::bail!("No note matches '{}'", note);
+            };
+            let mut dispatcher: Box<dyn Dispatcher> = if *dry_run {
+                Box::new(RecordingDispatcher::new())
+            } else {
+                Box::new(AppleScriptDispatcher)
+            };
+            let results: Vec<(SmartBlock, DispatchResult)> = parse_smart_blocks(&note.content)
+                .into_iter()
+                .map(|block| {
+                    let block = block.with_note(&note.id);
+                    let result = dispatcher.dispatch(&block, &config.destinations);
+                    (block, result)
+                })
+                .collect();
+
+            // Mark what was sent, from the end so earlier offsets hold
+            let mut sent: Vec<(usize, usize)> = results
+                .iter()
+                .filter(|(_, r)| *r == DispatchResult::Sent)
+                .filter_map(|(b, _)| b.original_range)
+                .collect();
+            sent.sort_by_key(|r| std::cmp::Reverse(r.0));
+            if !sent.is_empty() && !dry_run {
+                let style =
+                    ProcessedStyle::from_name(&config.general.processed_style).unwrap_or_default();
+                let mut content = note.content.clone();
+                for (start, end) in sent {
+                    let marked = style.mark(&content[start..end]);
+                    content.replace_range(start..end, &marked);
+                }
+                note.update_content(content);
+                let naming = FileNaming::from_name(&config.general.file_naming).unwrap_or_default();
+                save_draft(&data_dir, &note, naming)?;
+            }
+
+            if json {
+                println!("{}", dispatch_json(&note, *dry_run, &results));
+            } else {
+                for (block, result) in &results {
+                    match result {
+                        DispatchResult::Sent => println!("{}\tsent", block.block_type.as_str()),
+                        DispatchResult::Skipped => {
+                            println!("{}\tskipped", block.block_type.as_str())
+                        }
+                        DispatchResult::Failed(e) => {
+                            println!("{}\tfailed: {}", block.block_type.as_str(), e)
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kenotex::types::BlockType;
+
+    #[test]
+    fn test_json_escapes_and_orders_fields() {
+        let value = Json::Object(vec![
+            ("b", "say \"hi\"\n\\ \u{1}".into()),
+            ("a", Json::Array(vec![Json::Null, Json::Number(3)])),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            r#"{"b":"say \"hi\"\n\\ \u0001","a":[null,3]}"#
+        );
+    }
+
+    #[test]
+    fn test_note_and_dispatch_schemas() {
+        let mut note = Note::new("n1".into(), "Plan".into(), "# Plan #work".into());
+        note.tags = vec!["work".into()];
+        let json = note_json(&note).to_string();
+        assert!(json.starts_with(
+            r#"{"id":"n1","title":"Plan","archived":false,"pinned":false,"tags":["work"],"created":""#
+        ));
+
+        assert_eq!(
+            alfred_item(&note).to_string(),
+            format!(
+                r#"{{"uid":"n1","title":"Plan","subtitle":"{} #work","arg":"n1","match":"Plan #work","autocomplete":"Plan"}}"#,
+                note.updated_at.format("%Y-%m-%d")
+            )
+        );
+
+        let block = SmartBlock::new("b".into(), ":::td x".into(), BlockType::Reminder);
+        let results = [
+            (block.clone(), DispatchResult::Sent),
+            (block, DispatchResult::Failed("no list".into())),
+        ];
+        assert_eq!(
+            dispatch_json(&note, true, &results).to_string(),
+            r#"{"note":"n1","dry_run":true,"sent":1,"skipped":0,"failed":1,"blocks":[{"type":"reminder","status":"sent","error":null},{"type":"reminder","status":"failed","error":"no list"}]}"#
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use chrono::NaiveTime;
+
+/// An event of today's calendar, as listed in an agenda.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgendaEvent {
+    pub title: String,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub all_day: bool,
+}
@@ -5,6 +5,8 @@ pub enum BlockType {
     Reminder,
     Calendar,
     Note,
+    Audio,
+    Mail,
 }
 
 impl BlockType {
@@ -13,6 +15,8 @@ impl BlockType {
             BlockType::Reminder => "REMINDER",
             BlockType::Calendar => "CALENDAR",
             BlockType::Note => "NOTE",
+            BlockType::Audio => "AUDIO",
+            BlockType::Mail => "MAIL",
         }
     }
 
@@ -21,6 +25,8 @@ impl BlockType {
             BlockType::Reminder => "Apple Reminders",
             BlockType::Calendar => "Apple Calendar",
             BlockType::Note => "Apple Notes",
+            BlockType::Audio => "Notes attachment",
+            BlockType::Mail => "Apple Mail",
         }
     }
 }
@@ -33,6 +39,113 @@ pub enum ProcessingStatus {
     Skipped,
 }
 
+/// How `finish_processing` marks a sent block so later runs skip it. Blocks
+/// marked in any style count as sent, whichever one is configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessedStyle {
+    /// `<!-- block -->`
+    #[default]
+    Html,
+    /// `%% block %%`, an Obsidian comment.
+    Obsidian,
+    /// `> ✅ sent: block`, every later line quoted too.
+    Quote,
+}
+
+const QUOTE_MARK: &str = "> ✅ sent:";
+
+impl ProcessedStyle {
+    const ALL: [ProcessedStyle; 3] = [
+        ProcessedStyle::Html,
+        ProcessedStyle::Obsidian,
+        ProcessedStyle::Quote,
+    ];
+
+    /// Parse a `processed_style` config value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "html" => Some(ProcessedStyle::Html),
+            "obsidian" => Some(ProcessedStyle::Obsidian),
+            "quote" => Some(ProcessedStyle::Quote),
+            _ => None,
+        }
+    }
+
+    /// The style `text` is marked in, if it was sent.
+    pub fn of(text: &str) -> Option<Self> {
+        let text = text.trim();
+        Self::ALL.into_iter().find(|style| {
+            text.starts_with(style.opening())
+                && text.len() >= style.opening().len() + style.closing().len()
+                && text.ends_with(style.closing())
+        })
+    }
+
+    /// Whether `line` could be the first of a marked block.
+    pub fn opens(line: &str) -> bool {
+        let line = line.trim_start();
+        Self::ALL
+            .iter()
+            .any(|style| line.starts_with(style.opening()))
+    }
+
+    /// What a marked block starts with.
+    pub fn opening(self) -> &'static str {
+        match self {
+            ProcessedStyle::Html => "<!--",
+            ProcessedStyle::Obsidian => "%%",
+            ProcessedStyle::Quote => QUOTE_MARK,
+        }
+    }
+
+    fn closing(self) -> &'static str {
+        match self {
+            ProcessedStyle::Html => "-->",
+            ProcessedStyle::Obsidian => "%%",
+            ProcessedStyle::Quote => "",
+        }
+    }
+
+    /// `text` marked as sent.
+    pub fn mark(self, text: &str) -> String {
+        match self {
+            ProcessedStyle::Html | ProcessedStyle::Obsidian => {
+                format!("{} {} {}", self.opening(), text, self.closing())
+            }
+            ProcessedStyle::Quote => {
+                let body = text.trim_end();
+                let quoted: Vec<String> = body
+                    .lines()
+                    .enumerate()
+                    .map(|(i, line)| match (i, line.is_empty()) {
+                        (0, _) => format!("{} {}", QUOTE_MARK, line),
+                        (_, true) => ">".to_string(),
+                        _ => format!("> {}", line),
+                    })
+                    .collect();
+                format!("{}{}", quoted.join("\n"), &text[body.len()..])
+            }
+        }
+    }
+
+    /// A line of a block marked in this style without its marks.
+    pub fn line_body(self, line: &str) -> &str {
+        let line = line.trim();
+        let line = line.strip_prefix(self.opening()).unwrap_or(line);
+        let stripped = match self {
+            ProcessedStyle::Quote => line.strip_prefix('>'),
+            _ => line.strip_suffix(self.closing()),
+        };
+        stripped.unwrap_or(line).trim()
+    }
+
+    /// The text of a block marked in this style, without its marks.
+    pub fn unmark(self, text: &str) -> String {
+        let lines: Vec<&str> = text.trim().lines().map(|l| self.line_body(l)).collect();
+        lines.join("\n").trim().to_string()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartBlock {
     pub id: String,
@@ -40,6 +153,9 @@ pub struct SmartBlock {
     pub block_type: BlockType,
     pub status: ProcessingStatus,
     pub original_range: Option<(usize, usize)>,
+    /// Id of the note the block was parsed from.
+    #[serde(default)]
+    pub note_id: Option<String>,
 }
 
 impl SmartBlock {
@@ -50,6 +166,7 @@ impl SmartBlock {
             block_type,
             status: ProcessingStatus::Pending,
             original_range: None,
+            note_id: None,
         }
     }
 
@@ -58,6 +175,11 @@ impl SmartBlock {
         self
     }
 
+    pub fn with_note(mut self, note_id: &str) -> Self {
+        self.note_id = Some(note_id.to_string());
+        self
+    }
+
     pub fn preview(&self, max_len: usize) -> String {
         let preview = self
             .content
@@ -67,6 +189,8 @@ impl SmartBlock {
             .trim_start_matches(":::td")
             .trim_start_matches(":::cal")
             .trim_start_matches(":::note")
+            .trim_start_matches(":::audio")
+            .trim_start_matches(":::mail")
             .trim();
 
         if preview.len() > max_len {
@@ -76,3 +200,34 @@ impl SmartBlock {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_processed_styles() {
+        let block = ":::td\n- [ ] Milk\n\n- [ ] Eggs\n";
+        for style in ProcessedStyle::ALL {
+            let marked = style.mark(block);
+            assert_eq!(ProcessedStyle::of(&marked), Some(style));
+            assert!(ProcessedStyle::opens(marked.lines().next().unwrap()));
+            assert_eq!(style.unmark(&marked), ":::td\n- [ ] Milk\n\n- [ ] Eggs");
+        }
+        assert_eq!(
+            ProcessedStyle::Quote.mark(":::td Milk\nlater\n"),
+            "> ✅ sent: :::td Milk\n> later\n"
+        );
+        assert_eq!(ProcessedStyle::Obsidian.mark("x"), "%% x %%");
+        assert_eq!(
+            ProcessedStyle::Html.line_body("- [ ] Bread -->"),
+            "- [ ] Bread"
+        );
+        assert_eq!(ProcessedStyle::of("%%"), None);
+        assert_eq!(ProcessedStyle::of("> quoted"), None);
+        assert_eq!(
+            ProcessedStyle::from_name("Obsidian"),
+            Some(ProcessedStyle::Obsidian)
+        );
+    }
+}
@@ -1,14 +1,187 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use super::LeaderCommand;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(Default)]
 pub struct Config {
     #[serde(default)]
     pub general: GeneralConfig,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_keyboard")]
     pub keyboard: KeyboardConfig,
     #[serde(default)]
     pub destinations: Destinations,
+    #[serde(default)]
+    pub colors: ColorOverrides,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub daily: DailyConfig,
+    #[serde(default)]
+    pub auto_links: AutoLinkConfig,
+    #[serde(default)]
+    pub appearance: AppearanceConfig,
+    /// `[snippets]`: trigger → body, expanded by Tab in Insert mode.
+    #[serde(default)]
+    pub snippets: BTreeMap<String, String>,
+}
+
+/// `[sync]`: keep the data directory in a git repository and exchange it
+/// with a remote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Off unless set; `Space g` then reports how to turn it on.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name of the git remote to pull from and push to; without one the
+    /// changes are only committed.
+    #[serde(default = "default_sync_remote")]
+    pub remote: String,
+    /// Minutes between automatic syncs; 0 syncs only on demand.
+    #[serde(default)]
+    pub interval_minutes: u64,
+}
+
+fn default_sync_remote() -> String {
+    "origin".to_string()
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remote: default_sync_remote(),
+            interval_minutes: 0,
+        }
+    }
+}
+
+/// `[daily]`: the notes `Space D` / `:today` opens, one per day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyConfig {
+    /// chrono format of the title that makes a note a day's note.
+    #[serde(default = "default_daily_title_format")]
+    pub title_format: String,
+    /// Content of a new daily note; `{{date}}` becomes its title.
+    #[serde(default = "default_daily_template")]
+    pub template: String,
+}
+
+fn default_daily_title_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_daily_template() -> String {
+    "# {{date}}\n\n".to_string()
+}
+
+impl Default for DailyConfig {
+    fn default() -> Self {
+        Self {
+            title_format: default_daily_title_format(),
+            template: default_daily_template(),
+        }
+    }
+}
+
+/// What `[appearance]` follows to pick between its light and dark theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppearanceMode {
+    /// Keep `general.theme`.
+    #[default]
+    Off,
+    /// The system's dark mode setting (macOS, or GNOME's color scheme).
+    System,
+    /// The time of day: light from `light_at`, dark from `dark_at`.
+    Schedule,
+}
+
+/// `[appearance]`: switch between a light and a dark theme by itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppearanceConfig {
+    #[serde(default)]
+    pub auto: AppearanceMode,
+    /// Theme name used while light.
+    #[serde(default = "default_light_theme")]
+    pub light: String,
+    /// Theme name used while dark.
+    #[serde(default = "default_dark_theme")]
+    pub dark: String,
+    /// `HH:MM` the schedule turns light.
+    #[serde(default = "default_light_at")]
+    pub light_at: String,
+    /// `HH:MM` the schedule turns dark.
+    #[serde(default = "default_dark_at")]
+    pub dark_at: String,
+}
+
+fn default_light_theme() -> String {
+    "catppuccin_latte".to_string()
+}
+
+fn default_dark_theme() -> String {
+    "tokyo_night".to_string()
+}
+
+fn default_light_at() -> String {
+    "07:00".to_string()
+}
+
+fn default_dark_at() -> String {
+    "19:00".to_string()
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            auto: AppearanceMode::default(),
+            light: default_light_theme(),
+            dark: default_dark_theme(),
+            light_at: default_light_at(),
+            dark_at: default_dark_at(),
+        }
+    }
+}
+
+/// `[auto_links]`: bare text in notes that `gf` / Enter follows like a
+/// `[[link]]`, one switch per pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoLinkConfig {
+    /// Ids of other notes (UUIDs) open that note.
+    #[serde(default = "default_auto_link")]
+    pub note_ids: bool,
+    /// ISO dates (`2024-03-01`) open that day's daily note.
+    #[serde(default = "default_auto_link")]
+    pub dates: bool,
+}
+
+fn default_auto_link() -> bool {
+    true
+}
+
+impl Default for AutoLinkConfig {
+    fn default() -> Self {
+        Self {
+            note_ids: true,
+            dates: true,
+        }
+    }
+}
+
+/// Optional hex overrides for editor highlight colors, applied on top of every theme.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColorOverrides {
+    #[serde(default)]
+    pub visual_char: Option<String>,
+    #[serde(default)]
+    pub visual_line: Option<String>,
+    #[serde(default)]
+    pub visual_block: Option<String>,
+    #[serde(default)]
+    pub search_match: Option<String>,
 }
 
 
@@ -22,14 +195,83 @@ pub struct GeneralConfig {
     pub auto_save_interval_ms: u64,
     #[serde(default = "default_show_hints")]
     pub show_hints: bool,
+    /// UI language: `en` (default) or `zh`.
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Screen reader friendly UI: text instead of color-only cues, no hint
+    /// bar, and the terminal cursor always where the focus is.
+    #[serde(default)]
+    pub accessibility: bool,
     #[serde(default)]
     pub data_dir: Option<String>,
     #[serde(default = "default_file_watch")]
     pub file_watch: bool,
     #[serde(default = "default_file_watch_debounce_ms")]
     pub file_watch_debounce_ms: u64,
+    /// Reloading a changed file into a dirty buffer merges the two instead
+    /// of discarding local edits.
+    #[serde(default = "default_reload_merge")]
+    pub reload_merge: bool,
     #[serde(default = "default_tab_width")]
     pub tab_width: u8,
+    /// Wrap long lines in the editor; when off, the view scrolls sideways.
+    #[serde(default = "default_soft_wrap")]
+    pub soft_wrap: bool,
+    /// `/` searches in the editor ignore accents: `cafe` finds `café`.
+    #[serde(default)]
+    pub search_ignore_diacritics: bool,
+    /// Notes open with their frontmatter folded away.
+    #[serde(default)]
+    pub hide_frontmatter: bool,
+    /// Underline misspelled words in the editor; `:set spell` /
+    /// `:set nospell` toggles it.
+    #[serde(default)]
+    pub spell: bool,
+    /// Hunspell dictionary checked against, e.g. `en_US`; a note's
+    /// `spelllang` frontmatter field overrides it.
+    #[serde(default = "default_spell_language")]
+    pub spell_language: String,
+    /// Set the terminal title to the open note, with `[+]` while it has
+    /// unsaved changes.
+    #[serde(default)]
+    pub terminal_title: bool,
+    /// Inside tmux, set the pane title the same way.
+    #[serde(default)]
+    pub tmux_title: bool,
+    #[serde(default = "default_view")]
+    pub default_view: String,
+    /// Note lists show this many notes per page; 0 shows them all.
+    #[serde(default = "default_list_page_size")]
+    pub list_page_size: usize,
+    /// Order of the note lists: `updated`, `created`, `title` or `size`.
+    /// `s` in a list switches it and writes it back here.
+    #[serde(default = "default_list_sort")]
+    pub list_sort: String,
+    #[serde(default)]
+    pub writing_log: Option<String>,
+    /// Notes at least this large (KiB) auto-save less often, off the UI thread.
+    #[serde(default = "default_large_note_threshold_kb")]
+    pub large_note_threshold_kb: u64,
+    #[serde(default = "default_large_note_auto_save_interval")]
+    pub large_note_auto_save_interval_ms: u64,
+    /// How sent blocks are marked: `html` (`<!-- -->`), `obsidian`
+    /// (`%% %%`) or `quote` (`> ✅ sent:`).
+    #[serde(default = "default_processed_style")]
+    pub processed_style: String,
+    /// Deleted notes stay in the trash this many days; 0 keeps them until
+    /// purged by hand.
+    #[serde(default = "default_trash_days")]
+    pub trash_days: u64,
+    /// `id` names note files `<uuid>.md`; `title` uses the slugified title.
+    #[serde(default = "default_file_naming")]
+    pub file_naming: String,
+    /// How long the UI waits for input once idle; 0 keeps the normal tick.
+    #[serde(default = "default_idle_poll_ms")]
+    pub idle_poll_ms: u64,
+    /// Turns an exported HTML page into a PDF, e.g.
+    /// `wkhtmltopdf {input} {output}`; empty leaves PDF out of `:export`.
+    #[serde(default)]
+    pub pdf_command: String,
 }
 
 fn default_theme() -> String {
@@ -44,6 +286,10 @@ fn default_auto_save_interval() -> u64 {
     5000
 }
 
+fn default_language() -> String {
+    "en".to_string()
+}
+
 fn default_show_hints() -> bool {
     true
 }
@@ -56,10 +302,58 @@ fn default_file_watch_debounce_ms() -> u64 {
     300
 }
 
+fn default_reload_merge() -> bool {
+    true
+}
+
 fn default_tab_width() -> u8 {
     4
 }
 
+fn default_soft_wrap() -> bool {
+    true
+}
+
+fn default_spell_language() -> String {
+    "en_US".to_string()
+}
+
+fn default_view() -> String {
+    "editor".to_string()
+}
+
+fn default_list_page_size() -> usize {
+    200
+}
+
+fn default_list_sort() -> String {
+    "updated".to_string()
+}
+
+fn default_large_note_threshold_kb() -> u64 {
+    256
+}
+
+fn default_large_note_auto_save_interval() -> u64 {
+    30000
+}
+
+fn default_processed_style() -> String {
+    "html".to_string()
+}
+
+fn default_trash_days() -> u64 {
+    30
+}
+
+fn default_file_naming() -> String {
+    "id".to_string()
+}
+
+fn default_idle_poll_ms() -> u64 {
+    2000
+}
+
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
@@ -67,10 +361,31 @@ impl Default for GeneralConfig {
             leader_key: default_leader_key(),
             auto_save_interval_ms: default_auto_save_interval(),
             show_hints: default_show_hints(),
+            language: default_language(),
+            accessibility: false,
             data_dir: None,
             file_watch: default_file_watch(),
             file_watch_debounce_ms: default_file_watch_debounce_ms(),
+            reload_merge: default_reload_merge(),
             tab_width: default_tab_width(),
+            soft_wrap: default_soft_wrap(),
+            search_ignore_diacritics: false,
+            hide_frontmatter: false,
+            spell: false,
+            spell_language: default_spell_language(),
+            terminal_title: false,
+            tmux_title: false,
+            default_view: default_view(),
+            list_page_size: default_list_page_size(),
+            list_sort: default_list_sort(),
+            writing_log: None,
+            large_note_threshold_kb: default_large_note_threshold_kb(),
+            large_note_auto_save_interval_ms: default_large_note_auto_save_interval(),
+            processed_style: default_processed_style(),
+            trash_days: default_trash_days(),
+            file_naming: default_file_naming(),
+            idle_poll_ms: default_idle_poll_ms(),
+            pdf_command: String::new(),
         }
     }
 }
@@ -121,6 +436,8 @@ pub struct KeyboardConfig {
     pub delete_char: String,
     #[serde(default = "default_delete_line")]
     pub delete_line: String,
+    #[serde(default = "default_change")]
+    pub change: String,
     #[serde(default = "default_undo")]
     pub undo: String,
     #[serde(default = "default_redo")]
@@ -175,6 +492,15 @@ pub struct KeyboardConfig {
     pub leader_code: String,
     #[serde(default = "default_leader_code_block")]
     pub leader_code_block: String,
+
+    /// `[keyboard.leader]`: key sequence after Space → command, on top of
+    /// the built-in leader tree (see `leader_bindings`).
+    #[serde(
+        default,
+        deserialize_with = "super::leader::deserialize_leader",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub leader: BTreeMap<String, LeaderCommand>,
 }
 
 fn default_layout() -> String {
@@ -240,6 +566,9 @@ fn default_delete_char() -> String {
 fn default_delete_line() -> String {
     "d".to_string()
 }
+fn default_change() -> String {
+    "c".to_string()
+}
 fn default_undo() -> String {
     "u".to_string()
 }
@@ -340,6 +669,7 @@ impl Default for KeyboardConfig {
             insert_line_above: default_insert_line_above(),
             delete_char: default_delete_char(),
             delete_line: default_delete_line(),
+            change: default_change(),
             undo: default_undo(),
             redo: default_redo(),
             yank: default_yank(),
@@ -363,6 +693,7 @@ impl Default for KeyboardConfig {
             leader_strikethrough: default_leader_strikethrough(),
             leader_code: default_leader_code(),
             leader_code_block: default_leader_code_block(),
+            leader: BTreeMap::new(),
         }
     }
 }
@@ -377,6 +708,77 @@ impl KeyboardConfig {
             ..Default::default()
         }
     }
+
+    /// Movement on the keys where QWERTY has `hjkl`.
+    pub fn dvorak() -> Self {
+        Self {
+            layout: "dvorak".to_string(),
+            move_left: "d".to_string(),
+            move_down: "h".to_string(),
+            move_up: "t".to_string(),
+            move_right: "n".to_string(),
+            delete_line: "j".to_string(), // 'd' is used for move_left
+            search_next: "l".to_string(), // 'n' is used for move_right
+            search_prev: "L".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Movement on the keys where QWERTY has `hjkl`.
+    pub fn workman() -> Self {
+        Self {
+            layout: "workman".to_string(),
+            move_left: "y".to_string(),
+            move_down: "n".to_string(),
+            move_up: "e".to_string(),
+            move_right: "o".to_string(),
+            yank: "j".to_string(),        // 'y' is used for move_left
+            search_next: "k".to_string(), // 'n' is used for move_down
+            search_prev: "K".to_string(),
+            insert_line_below: "l".to_string(), // 'o' is used for move_right
+            insert_line_above: "L".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The keymap `layout` starts from: `qwerty` (the defaults),
+    /// `colemak`, `dvorak` or `workman`.
+    pub fn preset(layout: &str) -> Option<Self> {
+        match layout.to_lowercase().as_str() {
+            "qwerty" => Some(Self::default()),
+            "colemak" => Some(Self::colemak()),
+            "dvorak" => Some(Self::dvorak()),
+            "workman" => Some(Self::workman()),
+            _ => None,
+        }
+    }
+}
+
+/// Read `[keyboard]` on top of the preset its `layout` names, so keys set
+/// in the file still win over the preset's.
+fn deserialize_keyboard<'de, D>(deserializer: D) -> Result<KeyboardConfig, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let table = toml::Table::deserialize(deserializer)?;
+    let layout = match table.get("layout") {
+        Some(toml::Value::String(layout)) => layout.as_str(),
+        Some(_) => return Err(D::Error::custom("keyboard.layout must be a string")),
+        None => "qwerty",
+    };
+    let preset = KeyboardConfig::preset(layout).ok_or_else(|| {
+        D::Error::custom(format!(
+            "unknown keyboard.layout \"{}\" (qwerty, colemak, dvorak or workman)",
+            layout
+        ))
+    })?;
+    let mut keys = toml::Table::try_from(preset).map_err(D::Error::custom)?;
+    keys.extend(table);
+    toml::Value::Table(keys)
+        .try_into()
+        .map_err(D::Error::custom)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -388,6 +790,12 @@ pub struct Destinations {
     pub calendar: DestinationApp,
     #[serde(default)]
     pub notes: NotesDestination,
+    #[serde(default)]
+    pub mail: MailDestination,
+    #[serde(default)]
+    pub todoist: TodoistDestination,
+    #[serde(default)]
+    pub notion: NotionDestination,
 }
 
 
@@ -413,6 +821,72 @@ impl Default for DestinationApp {
     }
 }
 
+/// `[destinations.mail]`: where `:::mail` blocks become drafts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailDestination {
+    /// `"apple"` (Apple Mail); empty skips `:::mail` blocks.
+    #[serde(default = "default_app")]
+    pub app: String,
+    /// Address of the account to send from; Mail's default when unset.
+    pub sender: Option<String>,
+}
+
+impl Default for MailDestination {
+    fn default() -> Self {
+        Self {
+            app: default_app(),
+            sender: None,
+        }
+    }
+}
+
+/// `[destinations.todoist]`: the account `reminders.app = "todoist"`
+/// sends tasks to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TodoistDestination {
+    /// API token; read from the keychain when unset.
+    pub token: Option<String>,
+    /// Project to add tasks to; the Inbox when unset.
+    pub project_id: Option<String>,
+}
+
+/// `[destinations.notion]`: the database `notes.app = "notion"` adds
+/// pages to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotionDestination {
+    /// Integration token; read from the keychain when unset.
+    pub token: Option<String>,
+    pub database_id: Option<String>,
+    #[serde(default)]
+    pub properties: NotionProperties,
+}
+
+/// `[destinations.notion.properties]`: the database columns a page fills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotionProperties {
+    /// The title column, for the block's first line.
+    #[serde(default = "default_notion_title")]
+    pub title: String,
+    /// A multi-select column for the block's `#tags`.
+    pub tags: Option<String>,
+    /// A date column for the time in the block, or the day it was sent.
+    pub date: Option<String>,
+}
+
+fn default_notion_title() -> String {
+    "Name".to_string()
+}
+
+impl Default for NotionProperties {
+    fn default() -> Self {
+        Self {
+            title: default_notion_title(),
+            tags: None,
+            date: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotesDestination {
     #[serde(
@@ -469,12 +943,46 @@ impl Default for NotesDestination {
     }
 }
 
+/// Where `:::td` blocks go, named by `reminders.app`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoApp {
+    /// Apple Reminders (`"apple"`).
+    Reminders,
+    Things,
+    OmniFocus,
+    /// Todoist, over its REST API.
+    Todoist,
+}
+
+impl TodoApp {
+    /// Parse a `reminders.app` value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "apple" => Some(TodoApp::Reminders),
+            "things" => Some(TodoApp::Things),
+            "omnifocus" => Some(TodoApp::OmniFocus),
+            "todoist" => Some(TodoApp::Todoist),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TodoApp::Reminders => "Reminders",
+            TodoApp::Things => "Things",
+            TodoApp::OmniFocus => "OmniFocus",
+            TodoApp::Todoist => "Todoist",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum NotesApp {
     AppleNotes,
     Bear,
     Obsidian,
+    Notion,
 }
 
 impl NotesApp {
@@ -483,6 +991,7 @@ impl NotesApp {
             NotesApp::AppleNotes => "Apple Notes",
             NotesApp::Bear => "Bear",
             NotesApp::Obsidian => "Obsidian",
+            NotesApp::Notion => "Notion",
         }
     }
 }
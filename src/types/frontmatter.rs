@@ -0,0 +1,252 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Metadata from a block at the top of a note: YAML style `key: value`
+/// lines between `---` fences, or TOML style `key = value` between `+++`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub fields: Vec<(String, String)>,
+    /// Byte offset where the note body starts (just past the closing fence).
+    pub body_offset: usize,
+    /// Whether the block is TOML (`+++`) rather than YAML (`---`).
+    pub toml: bool,
+}
+
+impl Frontmatter {
+    /// Parse a leading frontmatter block. Returns `None` if the note does not
+    /// start with `---` or `+++` or the block is never closed.
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.split_inclusive('\n');
+        let toml = match lines.next()?.trim_end() {
+            "---" => false,
+            "+++" => true,
+            _ => return None,
+        };
+
+        let mut offset = content.find('\n')? + 1;
+        let mut fields = Vec::new();
+        for line in lines {
+            offset += line.len();
+            let trimmed = line.trim();
+            let closes = if toml {
+                trimmed == "+++"
+            } else {
+                trimmed == "---" || trimmed == "..."
+            };
+            if closes {
+                return Some(Self {
+                    fields,
+                    body_offset: offset,
+                    toml,
+                });
+            }
+            if let Some((key, value)) = split_field(trimmed, toml) {
+                let value = value.trim_matches(|c| c == '"' || c == '\'');
+                fields.push((key.to_string(), value.to_string()));
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Items of a list field, written inline as `[a, "b c"]` or `a, b c`.
+    pub fn list(&self, key: &str) -> Vec<String> {
+        let Some(value) = self.get(key) else {
+            return Vec::new();
+        };
+        value
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\''))
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// A date field: RFC 3339 (`2024-03-01T09:30:00Z`), `2024-03-01 09:30`
+    /// or `2024-03-01`, the last two in local time.
+    pub fn date(&self, key: &str) -> Option<DateTime<Utc>> {
+        let value = self.get(key)?;
+        if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+            return Some(date.with_timezone(&Utc));
+        }
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+            })?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
+/// Key and value of a frontmatter line, both trimmed.
+fn split_field(line: &str, toml: bool) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(if toml { '=' } else { ':' })?;
+    Some((key.trim(), value.trim()))
+}
+
+/// A frontmatter line for `key` and `value` in the block's style.
+fn field_line(key: &str, value: &str, toml: bool) -> String {
+    if toml {
+        format!("{} = \"{}\"\n", key, value)
+    } else {
+        format!("{}: {}\n", key, value)
+    }
+}
+
+/// Return the note body with any leading frontmatter removed.
+pub fn strip_frontmatter(content: &str) -> &str {
+    match Frontmatter::parse(content) {
+        Some(fm) => &content[fm.body_offset..],
+        None => content,
+    }
+}
+
+/// Remove `key` from the frontmatter, dropping the block if nothing else is
+/// left in it. Returns the new content and the removed value.
+pub fn take_frontmatter_field(content: &str, key: &str) -> Option<(String, String)> {
+    let fm = Frontmatter::parse(content)?;
+    let value = fm.get(key)?.to_string();
+
+    let (header, body) = content.split_at(fm.body_offset);
+    let lines: Vec<&str> = header
+        .split_inclusive('\n')
+        .filter(|line| split_field(line, fm.toml).is_none_or(|(k, _)| k != key))
+        .collect();
+    let rest = if lines.len() <= 2 {
+        body.to_string()
+    } else {
+        format!("{}{}", lines.concat(), body)
+    };
+    Some((rest, value))
+}
+
+/// Add `key: value` as the first frontmatter field, creating the block if
+/// the note has none.
+pub fn insert_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    match (content.find('\n'), Frontmatter::parse(content)) {
+        (Some(end), Some(fm)) => {
+            let field = field_line(key, value, fm.toml);
+            format!("{}{}{}", &content[..=end], field, &content[end + 1..])
+        }
+        _ => format!("---\n{}---\n{}", field_line(key, value, false), content),
+    }
+}
+
+/// Set `key` to `value`: its line is rewritten where it is, or the field is
+/// inserted first when the note doesn't have it.
+pub fn set_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    let Some(fm) = Frontmatter::parse(content).filter(|fm| fm.get(key).is_some()) else {
+        return insert_frontmatter_field(content, key, value);
+    };
+    let (header, body) = content.split_at(fm.body_offset);
+    let header: String = header
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, line)| match split_field(line, fm.toml) {
+            Some((k, _)) if i > 0 && k == key => field_line(key, value, fm.toml),
+            _ => line.to_string(),
+        })
+        .collect();
+    format!("{}{}", header, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frontmatter() {
+        let content = "---\nword_goal: 500\ntitle: \"Draft\"\n---\n# Heading\nBody";
+        let fm = Frontmatter::parse(content).unwrap();
+        assert_eq!(fm.get("word_goal"), Some("500"));
+        assert_eq!(fm.get("title"), Some("Draft"));
+        assert_eq!(fm.get("missing"), None);
+        assert_eq!(&content[fm.body_offset..], "# Heading\nBody");
+
+        let fm =
+            Frontmatter::parse("---\naliases: [Mtg, \"Weekly sync\"]\ntags: a, b\n---\n").unwrap();
+        assert_eq!(fm.list("aliases"), ["Mtg", "Weekly sync"]);
+        assert_eq!(fm.list("tags"), ["a", "b"]);
+        assert!(fm.list("missing").is_empty());
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        assert!(Frontmatter::parse("# Title\n---\n").is_none());
+        assert!(Frontmatter::parse("---\nunclosed: true\n").is_none());
+        assert_eq!(strip_frontmatter("plain"), "plain");
+    }
+
+    #[test]
+    fn test_take_and_insert_field() {
+        let content = "---\nid: abc\nword_goal: 5\n---\n# Title";
+        let (rest, id) = take_frontmatter_field(content, "id").unwrap();
+        assert_eq!(id, "abc");
+        assert_eq!(rest, "---\nword_goal: 5\n---\n# Title");
+        assert_eq!(insert_frontmatter_field(&rest, "id", "abc"), content);
+
+        let (rest, _) = take_frontmatter_field("---\nid: abc\n---\n# Title", "id").unwrap();
+        assert_eq!(rest, "# Title");
+        assert_eq!(
+            insert_frontmatter_field(&rest, "id", "abc"),
+            "---\nid: abc\n---\n# Title"
+        );
+        assert!(take_frontmatter_field("# Title", "id").is_none());
+    }
+
+    #[test]
+    fn test_toml_frontmatter() {
+        let content = "+++\ntitle = \"Plan\"\ntags = [\"a\", \"b\"]\n+++\n# Heading";
+        let fm = Frontmatter::parse(content).unwrap();
+        assert!(fm.toml);
+        assert_eq!(fm.get("title"), Some("Plan"));
+        assert_eq!(fm.list("tags"), ["a", "b"]);
+        assert_eq!(strip_frontmatter(content), "# Heading");
+
+        let with_id = insert_frontmatter_field(content, "id", "abc");
+        assert!(with_id.starts_with("+++\nid = \"abc\"\ntitle"));
+        assert_eq!(take_frontmatter_field(&with_id, "id").unwrap().0, content);
+    }
+
+    #[test]
+    fn test_set_field_and_dates() {
+        let content = "---\ntitle: Old\ncreated: 2024-03-01\n---\nbody";
+        assert_eq!(
+            set_frontmatter_field(content, "title", "New"),
+            "---\ntitle: New\ncreated: 2024-03-01\n---\nbody"
+        );
+        assert_eq!(
+            set_frontmatter_field("body", "status", "done"),
+            "---\nstatus: done\n---\nbody"
+        );
+
+        let fm = Frontmatter::parse(
+            "---\ncreated: 2024-03-01T09:30:00Z\nupdated: 2024-03-02 10:00\nbad: soon\n---\n",
+        )
+        .unwrap();
+        assert_eq!(
+            fm.date("created").unwrap().to_rfc3339(),
+            "2024-03-01T09:30:00+00:00"
+        );
+        assert!(fm.date("updated").is_some());
+        assert!(fm.date("bad").is_none());
+    }
+
+    #[test]
+    fn test_strip_frontmatter_at_end() {
+        assert_eq!(strip_frontmatter("---\na: 1\n---"), "");
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Share of a split given to its first (top or left) pane, in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitRatios {
+    #[serde(default = "default_ratio")]
+    pub horizontal: u16,
+    #[serde(default = "default_ratio")]
+    pub vertical: u16,
+}
+
+fn default_ratio() -> u16 {
+    50
+}
+
+impl Default for SplitRatios {
+    fn default() -> Self {
+        Self {
+            horizontal: default_ratio(),
+            vertical: default_ratio(),
+        }
+    }
+}
+
+/// Pane proportions last set with `Ctrl-W +/-/</>/=`, keyed by view name.
+/// New splits in a view start from them. Persisted as `layout.toml` in the
+/// data directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutState {
+    #[serde(default)]
+    pub views: BTreeMap<String, SplitRatios>,
+}
+
+impl LayoutState {
+    pub fn ratios(&self, view: &str) -> SplitRatios {
+        self.views.get(view).copied().unwrap_or_default()
+    }
+
+    pub fn ratios_mut(&mut self, view: &str) -> &mut SplitRatios {
+        self.views.entry(view.to_string()).or_default()
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::KeyboardConfig;
+
+/// What a leader sequence (`Space` and then its keys) runs. Named in
+/// snake_case in `[keyboard.leader]`, e.g. `"nn" = "new_note"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderCommand {
+    Process,
+    List,
+    NewNote,
+    Quit,
+    ToggleHints,
+    ToggleCheckbox,
+    InsertCheckbox,
+    Bold,
+    Italic,
+    Strikethrough,
+    InlineCode,
+    CodeBlock,
+    WritingStats,
+    Export,
+    Today,
+    RestartWatcher,
+    Buffers,
+    Outline,
+    Backlinks,
+    Tags,
+    Rename,
+    SyncTitle,
+    GitSync,
+    CheatSheet,
+    /// Takes a default sequence away without binding it to anything.
+    None,
+}
+
+impl LeaderCommand {
+    /// Shown next to its sequence in the leader popup.
+    pub fn label(self) -> &'static str {
+        match self {
+            LeaderCommand::Process => "Process blocks",
+            LeaderCommand::List => "Draft list",
+            LeaderCommand::NewNote => "New note",
+            LeaderCommand::Quit => "Quit",
+            LeaderCommand::ToggleHints => "Toggle hints",
+            LeaderCommand::ToggleCheckbox => "Toggle checkbox",
+            LeaderCommand::InsertCheckbox => "Insert checkbox",
+            LeaderCommand::Bold => "Bold",
+            LeaderCommand::Italic => "Italic",
+            LeaderCommand::Strikethrough => "Strikethrough",
+            LeaderCommand::InlineCode => "Inline code",
+            LeaderCommand::CodeBlock => "Code block",
+            LeaderCommand::WritingStats => "Writing stats",
+            LeaderCommand::Export => "Export",
+            LeaderCommand::Today => "Today's note",
+            LeaderCommand::RestartWatcher => "Restart watcher",
+            LeaderCommand::Buffers => "Open buffers",
+            LeaderCommand::Outline => "Outline",
+            LeaderCommand::Backlinks => "Backlinks",
+            LeaderCommand::Tags => "Tags",
+            LeaderCommand::Rename => "Rename",
+            LeaderCommand::SyncTitle => "Sync title",
+            LeaderCommand::GitSync => "Git sync",
+            LeaderCommand::CheatSheet => "Keymap sheet",
+            LeaderCommand::None => "",
+        }
+    }
+}
+
+/// What a key does partway through a leader sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderStep {
+    /// Completes a sequence and runs its command.
+    Run(LeaderCommand),
+    /// Continues longer sequences: `count` commands, the first `first`.
+    Group { first: LeaderCommand, count: usize },
+}
+
+/// The keys that may follow `prefix` (the keys typed after Space so far)
+/// in `bindings`, in the order of their first binding, each with what it
+/// does next.
+pub fn leader_steps(bindings: &[(String, LeaderCommand)], prefix: &str) -> Vec<(char, LeaderStep)> {
+    let mut steps: Vec<(char, LeaderStep)> = Vec::new();
+    for (keys, command) in bindings {
+        let Some(rest) = keys.strip_prefix(prefix) else {
+            continue;
+        };
+        let mut chars = rest.chars();
+        let Some(key) = chars.next() else {
+            continue;
+        };
+        let step = if chars.next().is_none() {
+            LeaderStep::Run(*command)
+        } else {
+            LeaderStep::Group {
+                first: *command,
+                count: 1,
+            }
+        };
+        match steps.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, LeaderStep::Group { count, .. })) => *count += 1,
+            Some(_) => {}
+            None => steps.push((key, step)),
+        }
+    }
+    steps
+}
+
+/// Whether one sequence is the start of the other, so the longer one
+/// could never be typed.
+fn overlaps(a: &str, b: &str) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+impl KeyboardConfig {
+    /// The leader tree in effect: the built-in sequences (those with a
+    /// `leader_*` field take it), then `[keyboard.leader]` on top. A
+    /// custom sequence replaces the default it equals or overlaps.
+    pub fn leader_bindings(&self) -> Vec<(String, LeaderCommand)> {
+        let mut bindings: Vec<(String, LeaderCommand)> = [
+            (self.leader_process.as_str(), LeaderCommand::Process),
+            (&self.leader_list, LeaderCommand::List),
+            (&self.leader_new, LeaderCommand::NewNote),
+            (&self.leader_quit, LeaderCommand::Quit),
+            ("h", LeaderCommand::ToggleHints),
+            ("d", LeaderCommand::ToggleCheckbox),
+            ("mc", LeaderCommand::InsertCheckbox),
+            (&self.leader_bold, LeaderCommand::Bold),
+            (&self.leader_italic, LeaderCommand::Italic),
+            (&self.leader_strikethrough, LeaderCommand::Strikethrough),
+            (&self.leader_code, LeaderCommand::InlineCode),
+            (&self.leader_code_block, LeaderCommand::CodeBlock),
+            ("w", LeaderCommand::WritingStats),
+            ("e", LeaderCommand::Export),
+            ("D", LeaderCommand::Today),
+            ("R", LeaderCommand::RestartWatcher),
+            ("B", LeaderCommand::Buffers),
+            ("o", LeaderCommand::Outline),
+            ("L", LeaderCommand::Backlinks),
+            ("t", LeaderCommand::Tags),
+            ("r", LeaderCommand::Rename),
+            ("T", LeaderCommand::SyncTitle),
+            ("g", LeaderCommand::GitSync),
+            ("?", LeaderCommand::CheatSheet),
+        ]
+        .into_iter()
+        .map(|(keys, command)| (keys.to_string(), command))
+        .collect();
+        for (keys, &command) in &self.leader {
+            match bindings.iter().position(|(k, _)| k == keys) {
+                Some(i) => bindings[i].1 = command,
+                None => {
+                    bindings.retain(|(k, _)| !overlaps(k, keys));
+                    bindings.push((keys.clone(), command));
+                }
+            }
+        }
+        bindings.retain(|(_, command)| *command != LeaderCommand::None);
+        bindings
+    }
+}
+
+/// Check `[keyboard.leader]`: no empty sequence, and none that starts
+/// another.
+fn validate_leader(leader: &BTreeMap<String, LeaderCommand>) -> Result<(), String> {
+    if leader.contains_key("") {
+        return Err("[keyboard.leader]: empty key sequence".to_string());
+    }
+    for (a, b) in leader.keys().zip(leader.keys().skip(1)) {
+        if b.starts_with(a.as_str()) {
+            return Err(format!(
+                "[keyboard.leader]: \"{}\" starts \"{}\", so \"{}\" can never be typed",
+                a, b, b
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn deserialize_leader<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, LeaderCommand>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let leader = BTreeMap::<String, LeaderCommand>::deserialize(deserializer)?;
+    validate_leader(&leader).map_err(serde::de::Error::custom)?;
+    Ok(leader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(keys: &KeyboardConfig, sequence: &str) -> Option<LeaderCommand> {
+        keys.leader_bindings()
+            .into_iter()
+            .find(|(k, _)| k == sequence)
+            .map(|(_, command)| command)
+    }
+
+    #[test]
+    fn test_custom_bindings_replace_defaults() {
+        let keys = KeyboardConfig {
+            leader_process: "S".to_string(),
+            leader: BTreeMap::from([
+                ("n".to_string(), LeaderCommand::Today),
+                ("D".to_string(), LeaderCommand::None),
+                ("gs".to_string(), LeaderCommand::GitSync),
+                ("?".to_string(), LeaderCommand::Tags),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(bound(&keys, "S"), Some(LeaderCommand::Process));
+        assert_eq!(bound(&keys, "n"), Some(LeaderCommand::Today));
+        assert_eq!(bound(&keys, "nn"), None);
+        assert_eq!(bound(&keys, "D"), None);
+        assert_eq!(bound(&keys, "g"), None);
+        assert_eq!(bound(&keys, "gs"), Some(LeaderCommand::GitSync));
+        assert_eq!(bound(&keys, "?"), Some(LeaderCommand::Tags));
+    }
+
+    #[test]
+    fn test_steps_at_each_stage() {
+        let keys = KeyboardConfig {
+            leader: BTreeMap::from([
+                ("gs".to_string(), LeaderCommand::GitSync),
+                ("gt".to_string(), LeaderCommand::Tags),
+            ]),
+            ..Default::default()
+        };
+        let bindings = keys.leader_bindings();
+        let root = leader_steps(&bindings, "");
+        assert_eq!(root[0], ('s', LeaderStep::Run(LeaderCommand::Process)));
+        let group = |key| root.iter().find(|(k, _)| *k == key).unwrap().1;
+        assert_eq!(
+            group('n'),
+            LeaderStep::Group {
+                first: LeaderCommand::NewNote,
+                count: 1
+            }
+        );
+        assert_eq!(
+            group('g'),
+            LeaderStep::Group {
+                first: LeaderCommand::GitSync,
+                count: 2
+            }
+        );
+        assert_eq!(
+            leader_steps(&bindings, "g"),
+            [
+                ('s', LeaderStep::Run(LeaderCommand::GitSync)),
+                ('t', LeaderStep::Run(LeaderCommand::Tags)),
+            ]
+        );
+        assert!(leader_steps(&bindings, "gs").is_empty());
+    }
+
+    #[test]
+    fn test_validation_at_load() {
+        let parse = |toml: &str| toml::from_str::<KeyboardConfig>(toml);
+        let keys = parse("[leader]\nnd = \"today\"\nx = \"none\"").unwrap();
+        assert_eq!(keys.leader["nd"], LeaderCommand::Today);
+        assert!(parse("[leader]\nn = \"tomorrow\"").is_err());
+        let err = parse("[leader]\nn = \"today\"\nnd = \"tags\"").unwrap_err();
+        assert!(err.to_string().contains("\"n\" starts \"nd\""));
+        assert!(parse("[leader]\n\"\" = \"tags\"").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+use std::borrow::Cow;
+
+/// Language of the UI chrome (`general.language`). Note content is never
+/// translated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Locale {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('_', "-").as_str() {
+            "en" | "english" => Some(Locale::English),
+            "zh" | "zh-cn" | "zh-hans" | "chinese" => Some(Locale::Chinese),
+            _ => None,
+        }
+    }
+
+    /// Translate an English UI string. Catalog entries may contain `{}`,
+    /// which matches any text and carries it over, so formatted messages
+    /// such as `Theme: Nord` translate too. Anything not in the catalog is
+    /// returned unchanged.
+    pub fn tr<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let catalog = match self {
+            Locale::English => return Cow::Borrowed(text),
+            Locale::Chinese => ZH,
+        };
+        if let Some((_, translated)) = catalog.iter().find(|(en, _)| *en == text) {
+            return Cow::Borrowed(translated);
+        }
+        catalog
+            .iter()
+            .filter(|(en, _)| en.contains("{}"))
+            .find_map(|(en, translated)| {
+                let captures = captures(en, text)?;
+                Some(Cow::Owned(fill(translated, &captures)))
+            })
+            .unwrap_or(Cow::Borrowed(text))
+    }
+}
+
+/// The text each `{}` of `template` matched in `text`.
+fn captures<'a>(template: &str, text: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = text.strip_prefix(parts.next()?)?;
+    let parts: Vec<&str> = parts.collect();
+    let mut found = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i + 1 == parts.len() {
+            found.push(rest.strip_suffix(part)?);
+        } else {
+            let at = rest.find(part)?;
+            found.push(&rest[..at]);
+            rest = &rest[at + part.len()..];
+        }
+    }
+    Some(found)
+}
+
+fn fill(template: &str, values: &[&str]) -> String {
+    let mut out = String::new();
+    for (i, part) in template.split("{}").enumerate() {
+        if i > 0 {
+            out.push_str(values.get(i - 1).copied().unwrap_or_default());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+const ZH: &[(&str, &str)] = &[
+    // Status messages
+    ("-- INSERT --", "-- 插入 --"),
+    ("-- (block) INSERT --", "-- （块）插入 --"),
+    ("-- REPLACE --", "-- 替换 --"),
+    ("-- VISUAL --", "-- 可视 --"),
+    ("-- VISUAL LINE --", "-- 可视行 --"),
+    ("-- VISUAL BLOCK --", "-- 可视块 --"),
+    ("Already at newest change", "已是最新的修改"),
+    ("Already at oldest change", "已是最早的修改"),
+    (
+        "Already at newest position in jump list",
+        "已在跳转列表的最新位置",
+    ),
+    (
+        "Already at oldest position in jump list",
+        "已在跳转列表的最早位置",
+    ),
+    (
+        "Buffer updated from external editor",
+        "已从外部编辑器更新缓冲区",
+    ),
+    ("Cannot close last pane", "无法关闭最后一个窗格"),
+    ("Current note deleted externally", "当前笔记已被外部删除"),
+    ("External editor exited with error", "外部编辑器异常退出"),
+    ("Failed to reload note from disk", "从磁盘重新加载笔记失败"),
+    ("No changes from external editor", "外部编辑器未做任何更改"),
+    ("File reloaded", "文件已重新加载"),
+    ("File watcher restarted", "文件监视已重启"),
+    (
+        "File watching is disabled (file_watch = false)",
+        "文件监视已禁用（file_watch = false）",
+    ),
+    ("Filter cleared", "已清除筛选"),
+    ("Hints shown", "已显示提示"),
+    ("Hints hidden", "已隐藏提示"),
+    ("Keymap cheat sheet created", "已生成快捷键速查表"),
+    ("Merged external changes", "已合并外部修改"),
+    ("New note created", "已新建笔记"),
+    ("No Visual selection", "没有可视选区"),
+    ("No link under cursor", "光标处没有链接"),
+    ("No completions", "没有可补全的内容"),
+    ("No snapshots of this note", "此笔记没有快照"),
+    ("Snapshot failed: {}", "快照失败：{}"),
+    ("History failed: {}", "读取历史失败：{}"),
+    ("Restore failed: {}", "恢复失败：{}"),
+    ("Restored the snapshot from {}", "已恢复 {} 的快照"),
+    ("History", "历史"),
+    ("Before :replace-all", ":replace-all 之前"),
+    ("Before external editor", "外部编辑器之前"),
+    ("Before :agenda", ":agenda 之前"),
+    ("Before processing", "处理之前"),
+    ("Before restore", "恢复之前"),
+    ("Before :split-headings", ":split-headings 之前"),
+    ("No headings to split at", "没有可拆分的标题"),
+    ("Split into {} note(s)", "已拆分为 {} 篇笔记"),
+    ("No notes link here", "没有笔记链接到此处"),
+    ("No tags", "没有标签"),
+    ("No note open", "没有打开的笔记"),
+    (
+        "No notes modified in the past week",
+        "过去一周没有修改过的笔记",
+    ),
+    ("Review summary created", "已生成回顾总结"),
+    ("Review finished", "回顾已结束"),
+    ("No completed reminders", "没有已完成的提醒事项"),
+    ("No blocks to process", "没有可处理的块"),
+    ("No other buffers", "没有其他缓冲区"),
+    ("No more open tasks", "没有更多未完成任务"),
+    ("No number under cursor", "光标处没有数字"),
+    ("No open tasks", "没有未完成任务"),
+    ("No previous change to repeat", "没有可重复的修改"),
+    ("No previous search pattern", "没有上一次的搜索内容"),
+    ("Note archived (u to undo)", "笔记已归档（按 u 撤销）"),
+    ("Note deleted (u to undo)", "笔记已删除（按 u 撤销）"),
+    ("Nothing to undo", "没有可撤销的操作"),
+    ("Note restored", "笔记已恢复"),
+    ("Read-only mode", "只读模式"),
+    ("Read-only mode: changes are disabled", "只读模式：禁止修改"),
+    ("Redo", "重做"),
+    ("Undo", "撤销"),
+    (
+        "Replace this match? (y/n/a/q/l)",
+        "替换此处匹配？(y/n/a/q/l)",
+    ),
+    ("Replace this line? (y/n/a/q/l)", "替换此行？(y/n/a/q/l)"),
+    ("Replace preview", "替换预览"),
+    (
+        "{} line(s) changed in {} note(s)",
+        "已修改 {} 行，涉及 {} 篇笔记",
+    ),
+    ("Saved", "已保存"),
+    ("Soft-wrap on", "自动换行已开启"),
+    ("Soft-wrap off", "自动换行已关闭"),
+    ("Search ignores diacritics", "搜索忽略重音符号"),
+    ("Search matches diacritics", "搜索区分重音符号"),
+    ("Spell checking on", "拼写检查已开启"),
+    ("Spell checking off", "拼写检查已关闭"),
+    ("Spell checking is off", "拼写检查未开启"),
+    ("No dictionary for {}", "没有 {} 的词典"),
+    ("Spell language: {}", "拼写检查语言：{}"),
+    ("No misspelled words", "没有拼写错误"),
+    ("No word under the cursor", "光标处没有单词"),
+    ("No suggestions for {}", "没有 {} 的拼写建议"),
+    ("Spelling", "拼写建议"),
+    ("Saving...", "正在保存..."),
+    (
+        "Substitute only works in the editor",
+        "替换只能在编辑器中使用",
+    ),
+    ("Yanked", "已复制"),
+    (
+        "search hit BOTTOM, continuing at TOP",
+        "已搜索到底部，从顶部继续",
+    ),
+    (
+        "search hit TOP, continuing at BOTTOM",
+        "已搜索到顶部，从底部继续",
+    ),
+    ("Block failed: {}", "块处理失败：{}"),
+    ("Created '{}'", "已新建“{}”"),
+    ("Invalid tag: {}", "无效的标签：{}"),
+    ("Tagged #{}", "已添加标签 #{}"),
+    ("Tagged {} note(s) #{}", "已为 {} 篇笔记添加标签 #{}"),
+    ("Renamed to '{}'", "已重命名为“{}”"),
+    ("Title set to '{}'", "标题已设为“{}”"),
+    ("Title matches the first heading", "标题与首个标题行一致"),
+    (
+        "Git sync is off (set enabled = true under [sync])",
+        "Git 同步未开启（在 [sync] 下设置 enabled = true）",
+    ),
+    ("Sync already running", "同步正在进行"),
+    ("Syncing...", "正在同步..."),
+    ("Sync failed: {}", "同步失败：{}"),
+    ("Writing stats not saved: {}", "写作统计未保存：{}"),
+    (
+        "Synced with conflicts in {} file(s)",
+        "已同步，{} 个文件有冲突",
+    ),
+    ("Synced", "已同步"),
+    (
+        "Changes committed (no remote to push to)",
+        "已提交更改（没有可推送的远程仓库）",
+    ),
+    ("Nothing to sync", "没有需要同步的内容"),
+    ("Contacts unavailable: {}", "无法读取通讯录：{}"),
+    ("Reminder sync failed: {}", "同步提醒事项失败：{}"),
+    ("Agenda failed: {}", "获取日程失败：{}"),
+    ("{} event(s) today", "今天有 {} 个日程"),
+    ("{} reminder(s) checked off", "已勾选 {} 个提醒事项"),
+    (
+        "Review {}/{}: a archive, n keep, p process, t tag, q stop",
+        "回顾 {}/{}：a 归档，n 保留，p 处理，t 标签，q 停止",
+    ),
+    ("Data directory: {}", "数据目录：{}"),
+    ("Failed to launch editor: {}", "启动编辑器失败：{}"),
+    ("File event error: {}", "文件事件错误：{}"),
+    (
+        "File watcher error: {} (Space+R to restart)",
+        "文件监视错误：{}（Space+R 重启）",
+    ),
+    ("File watcher failed: {}", "文件监视失败：{}"),
+    ("Config watcher failed: {}", "配置监视失败：{}"),
+    ("Config watcher error: {}", "配置监视错误：{}"),
+    ("Config reloaded", "已重新加载配置"),
+    ("Config not reloaded: {}", "未重新加载配置：{}"),
+    ("Integrity scan failed: {}", "完整性检查失败：{}"),
+    ("Loading archives failed: {}", "加载归档失败：{}"),
+    ("Loading trash failed: {}", "加载回收站失败：{}"),
+    (
+        "Moved 1 damaged note file to {} ({})",
+        "已将 1 个损坏的笔记文件移到 {}（{}）",
+    ),
+    (
+        "Moved {} damaged note files to {} ({})",
+        "已将 {} 个损坏的笔记文件移到 {}（{}）",
+    ),
+    ("Mark not set: {}", "标记未设置：{}"),
+    ("Saving layout failed: {}", "保存布局失败：{}"),
+    (
+        "Notes sharing an id, left as they are: {}",
+        "以下笔记 id 相同，已保留原样：{}",
+    ),
+    ("No note named '{}'", "没有名为“{}”的笔记"),
+    ("No note with id {}", "没有 id 为 {} 的笔记"),
+    ("No template named '{}'", "没有名为“{}”的模板"),
+    ("New note from '{}'", "已用模板“{}”新建笔记"),
+    ("Template failed: {}", "模板读取失败：{}"),
+    ("Pattern not found: {}", "未找到：{}"),
+    ("Reindex failed: {}", "重建索引失败：{}"),
+    ("Save failed: {}", "保存失败：{}"),
+    (
+        "Saved; alias '{}' is also claimed by '{}'",
+        "已保存；别名“{}”也被“{}”使用",
+    ),
+    (
+        "Search index rebuilt: {} notes",
+        "搜索索引已重建：{} 篇笔记",
+    ),
+    ("Saving marks failed: {}", "保存标记失败：{}"),
+    ("Saving sort order failed: {}", "保存排序方式失败：{}"),
+    ("Export failed: {}", "导出失败：{}"),
+    ("Import failed: {}", "导入失败：{}"),
+    ("No later daily note", "没有更晚的日记"),
+    ("No earlier daily note", "没有更早的日记"),
+    ("Import path required", "需要指定导入路径"),
+    (
+        "Imported {} note(s) from {} file(s)",
+        "已导入 {} 篇笔记（来自 {} 个文件）",
+    ),
+    ("Exported to {}", "已导出到 {}"),
+    (
+        "Set general.pdf_command to export PDF",
+        "请设置 general.pdf_command 以导出 PDF",
+    ),
+    ("Unknown export format: {}", "未知的导出格式：{}"),
+    ("Theme: {}", "主题：{}"),
+    ("Themes failed: {}", "读取主题失败：{}"),
+    ("Theme skipped: {}", "已跳过主题：{}"),
+    ("Unknown theme: {}", "未知主题：{}"),
+    ("Sort: {}", "排序：{}"),
+    ("No block problems", "智能块没有问题"),
+    ("No locations", "没有位置列表"),
+    ("No more locations", "没有更多位置"),
+    (
+        "Restore the note with r to open it",
+        "请先按 r 恢复笔记再打开",
+    ),
+    ("Note deleted permanently", "笔记已永久删除"),
+    ("{} notes deleted permanently", "已永久删除 {} 篇笔记"),
+    ("Trash is empty", "回收站是空的"),
+    ("Pinned '{}'", "已置顶“{}”"),
+    ("Unpinned '{}'", "已取消置顶“{}”"),
+    (
+        "{} notes archived (u to undo)",
+        "已归档 {} 篇笔记（按 u 撤销）",
+    ),
+    ("{} notes restored", "已恢复 {} 篇笔记"),
+    (
+        "{} notes deleted (u to undo)",
+        "已删除 {} 篇笔记（按 u 撤销）",
+    ),
+    ("cd failed: {}", "cd 失败：{}"),
+    (
+        "{} substitution(s) on {} line(s)",
+        "替换了 {} 处，涉及 {} 行",
+    ),
+    (
+        "Merged with {} conflict(s): resolve the <<<<<<< markers",
+        "合并时有 {} 处冲突：请处理 <<<<<<< 标记",
+    ),
+    (
+        "Processing complete: {} sent, {} skipped, {} failed",
+        "处理完成：发送 {}，跳过 {}，失败 {}",
+    ),
+    (
+        "Dry run: {} would be sent, {} skipped, {} failed",
+        "试运行：将发送 {}，跳过 {}，失败 {}",
+    ),
+    ("Dry run: blocks will not be sent", "试运行：不会实际发送块"),
+    (
+        "Simulated dispatch: blocks will not be sent",
+        "模拟发送：不会实际发送块",
+    ),
+    ("match {} of {}", "第 {} 个匹配，共 {} 个"),
+    ("1 match", "1 个匹配"),
+    ("{} matches", "{} 个匹配"),
+    // Hint bar
+    ("All", "全部"),
+    ("Apply", "应用"),
+    ("Archive", "归档"),
+    ("Archives", "归档列表"),
+    ("Back", "返回"),
+    ("Cancel", "取消"),
+    ("Clear", "清除"),
+    ("Comment", "注释"),
+    ("Confirm", "确认"),
+    ("DelLine", "删行"),
+    ("Delete", "删除"),
+    ("ExtEdit", "外部编辑"),
+    ("Filter", "筛选"),
+    ("Format", "格式"),
+    ("Insert", "插入"),
+    ("Last", "最后"),
+    ("Leader", "Leader"),
+    ("Move", "移动"),
+    ("Nav", "导航"),
+    ("New", "新建"),
+    ("Normal", "普通"),
+    ("Open", "打开"),
+    ("Purge", "彻底删除"),
+    ("Paste", "粘贴"),
+    ("Quit", "退出"),
+    ("Replace", "替换"),
+    ("Restore", "恢复"),
+    ("Run", "执行"),
+    ("Search", "搜索"),
+    ("Select", "选择"),
+    ("Skip", "跳过"),
+    ("Sort", "排序"),
+    ("Stop", "停止"),
+    ("Switch", "切换"),
+    ("Trash", "回收站"),
+    ("View", "查看"),
+    ("Visual", "可视"),
+    ("Yank", "复制"),
+    // Leader popup
+    ("Process blocks", "处理块"),
+    ("Draft list", "草稿列表"),
+    ("New note", "新建笔记"),
+    ("Toggle hints", "切换提示"),
+    ("Toggle checkbox", "切换复选框"),
+    ("Insert checkbox", "插入复选框"),
+    ("Bold", "粗体"),
+    ("Italic", "斜体"),
+    ("Strikethrough", "删除线"),
+    ("Inline code", "行内代码"),
+    ("Code block", "代码块"),
+    ("Writing stats", "写作统计"),
+    ("Restart watcher", "重启监视"),
+    ("Open buffers", "已打开的缓冲区"),
+    ("Outline", "大纲"),
+    ("Backlinks", "反向链接"),
+    ("Tags", "标签"),
+    ("Export", "导出"),
+    ("Blank note", "空白笔记"),
+    ("Block problems", "智能块问题"),
+    ("Rename", "重命名"),
+    ("Sync title", "同步标题"),
+    ("Git sync", "Git 同步"),
+    ("Today's note", "今日笔记"),
+    ("Keymap sheet", "快捷键表"),
+    ("+{} commands", "+{} 个命令"),
+    // Overlays
+    ("Buffers", "缓冲区"),
+    ("No headings", "没有标题"),
+    ("Confirm Delete", "确认删除"),
+    ("Delete '{}'?", "删除“{}”？"),
+    ("Yes", "是"),
+    ("No", "否"),
+    ("Processing Blocks", "处理块"),
+    ("Writing Stats", "写作统计"),
+    ("Session", "本次"),
+    ("Today", "今天"),
+    ("Streak", "连续"),
+    ("Best day", "最佳"),
+    (
+        "{} words in {} notes ({} min)",
+        "{} 字，{} 篇笔记（{} 分钟）",
+    ),
+    ("{} words", "{} 字"),
+    ("{} day", "{} 天"),
+    ("{} days", "{} 天"),
+    ("{} ({} words)", "{}（{} 字）"),
+    ("Press any key to close", "按任意键关闭"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Locale::from_name("zh_CN"), Some(Locale::Chinese));
+        assert_eq!(Locale::from_name("en"), Some(Locale::English));
+        assert_eq!(Locale::from_name("fr"), None);
+    }
+
+    #[test]
+    fn test_translate() {
+        let zh = Locale::Chinese;
+        assert_eq!(zh.tr("Saved"), "已保存");
+        assert_eq!(zh.tr("Theme: Nord"), "主题：Nord");
+        assert_eq!(zh.tr("match 2 of 5"), "第 2 个匹配，共 5 个");
+        assert_eq!(zh.tr("My note title"), "My note title");
+        assert_eq!(Locale::English.tr("Saved"), "Saved");
+    }
+
+    #[test]
+    fn test_catalog_placeholders_match() {
+        for (en, zh) in ZH {
+            assert_eq!(en.matches("{}").count(), zh.matches("{}").count(), "{}", en);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A cursor position saved with `m{a-z}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mark {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Marks of every note, keyed by note id and then mark letter. Persisted as
+/// `marks.toml` in the data directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkStore {
+    #[serde(default)]
+    pub notes: BTreeMap<String, BTreeMap<String, Mark>>,
+}
+
+impl MarkStore {
+    pub fn get(&self, note_id: &str, mark: char) -> Option<Mark> {
+        self.notes.get(note_id)?.get(&mark.to_string()).copied()
+    }
+
+    pub fn set(&mut self, note_id: &str, mark: char, position: Mark) {
+        self.notes
+            .entry(note_id.to_string())
+            .or_default()
+            .insert(mark.to_string(), position);
+    }
+}
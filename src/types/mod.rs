@@ -1,13 +1,38 @@
+mod agenda;
 mod block;
 mod config;
+mod frontmatter;
+mod layout;
+mod leader;
+mod locale;
+mod marks;
 mod mode;
 mod note;
+mod search_index;
+mod snapshot;
+mod stats;
+mod tags;
 mod theme;
 
-pub use block::{BlockType, ProcessingStatus, SmartBlock};
+pub use agenda::AgendaEvent;
+pub use block::{BlockType, ProcessedStyle, ProcessingStatus, SmartBlock};
 pub use config::{
-    Config, DestinationApp, Destinations, KeyboardConfig, NotesApp, NotesDestination,
+    AppearanceConfig, AppearanceMode, AutoLinkConfig, ColorOverrides, Config, DestinationApp,
+    Destinations, KeyboardConfig, MailDestination, NotesApp, NotesDestination, NotionDestination,
+    NotionProperties, TodoApp, TodoistDestination,
 };
+pub use frontmatter::{
+    Frontmatter, insert_frontmatter_field, set_frontmatter_field, strip_frontmatter,
+    take_frontmatter_field,
+};
+pub use layout::{LayoutState, SplitRatios};
+pub use leader::{LeaderCommand, LeaderStep, leader_steps};
+pub use locale::Locale;
+pub use marks::{Mark, MarkStore};
 pub use mode::{AppMode, View};
 pub use note::Note;
+pub use search_index::{IndexSnapshot, IndexedNote};
+pub use snapshot::{NoteSnapshot, SnapshotReason};
+pub use stats::{StatsSummary, WritingHistory};
+pub use tags::{add_tag, as_tag, parse_tags, tag_spans};
 pub use theme::Theme;
@@ -8,10 +8,18 @@ pub enum AppMode {
     #[default]
     Normal,
     Insert,
+    /// Overtyping after `R`.
+    Replace,
     Visual(VisualType),
     Processing,
     Search,
+    /// Typing an ex command after `:`.
+    Command,
+    /// Typing a list view filter after `f`.
+    Filter,
     ConfirmDelete,
+    /// Answering y/n/a/q/l for each match of `:s///c`.
+    ConfirmReplace,
 }
 
 
@@ -20,12 +28,16 @@ impl AppMode {
         match self {
             AppMode::Normal => "NORMAL",
             AppMode::Insert => "INSERT",
+            AppMode::Replace => "REPLACE",
             AppMode::Visual(VisualType::Character) => "VISUAL",
             AppMode::Visual(VisualType::Line) => "VISUAL LINE",
             AppMode::Visual(VisualType::Block) => "VISUAL BLOCK",
             AppMode::Processing => "PROCESSING",
             AppMode::Search => "SEARCH",
+            AppMode::Command => "COMMAND",
+            AppMode::Filter => "FILTER",
             AppMode::ConfirmDelete => "CONFIRM",
+            AppMode::ConfirmReplace => "SUBSTITUTE",
         }
     }
 }
@@ -36,6 +48,8 @@ pub enum View {
     Editor,
     DraftList,
     ArchiveList,
+    /// Deleted notes, restorable until purged.
+    Trash,
 }
 
 impl View {
@@ -44,6 +58,32 @@ impl View {
             View::Editor => "Editor",
             View::DraftList => "Drafts",
             View::ArchiveList => "Archive",
+            View::Trash => "Trash",
         }
     }
+
+    /// Parse a view name as used by `--view` and `default_view`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "editor" => Some(View::Editor),
+            "drafts" | "draft_list" | "list" => Some(View::DraftList),
+            "archive" | "archives" | "archive_list" => Some(View::ArchiveList),
+            "trash" => Some(View::Trash),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_from_name() {
+        assert_eq!(View::from_name("editor"), Some(View::Editor));
+        assert_eq!(View::from_name("drafts"), Some(View::DraftList));
+        assert_eq!(View::from_name("Archive"), Some(View::ArchiveList));
+        assert_eq!(View::from_name("trash"), Some(View::Trash));
+        assert_eq!(View::from_name("bogus"), None);
+    }
 }
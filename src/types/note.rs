@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::{Frontmatter, parse_tags, set_frontmatter_field, strip_frontmatter};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub id: String,
@@ -11,6 +13,9 @@ pub struct Note {
     pub is_archived: bool,
     #[serde(default)]
     pub selected: bool,
+    /// `#tags` of the content (see `parse_tags`), kept in step with it.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Note {
@@ -19,6 +24,7 @@ impl Note {
         Self {
             id,
             title,
+            tags: parse_tags(&content),
             content,
             created_at: now,
             updated_at: now,
@@ -27,8 +33,20 @@ impl Note {
         }
     }
 
+    /// The `title` frontmatter field, else the first line of the body
+    /// without its heading marks.
     pub fn extract_title(content: &str) -> String {
-        let first_line = content.lines().next().unwrap_or("Untitled");
+        if let Some(title) = Frontmatter::parse(content)
+            .as_ref()
+            .and_then(|fm| fm.get("title"))
+            .filter(|title| !title.is_empty())
+        {
+            return title.chars().take(50).collect();
+        }
+        let first_line = strip_frontmatter(content)
+            .lines()
+            .next()
+            .unwrap_or("Untitled");
         let title = first_line
             .trim_start_matches('#')
             .trim_start_matches(' ')
@@ -40,15 +58,97 @@ impl Note {
         }
     }
 
+    /// `content` with the title `title`: a `title` frontmatter field or the
+    /// heading the title comes from is rewritten, or a `# title` line goes
+    /// above the body when its first line is not a heading.
+    pub fn retitle(content: &str, title: &str) -> String {
+        let fm = Frontmatter::parse(content);
+        if fm.as_ref().is_some_and(|fm| fm.get("title").is_some()) {
+            return set_frontmatter_field(content, "title", title);
+        }
+        let offset = fm.map_or(0, |fm| fm.body_offset);
+        let (frontmatter, body) = content.split_at(offset);
+        let first = body.lines().next().unwrap_or("");
+        let level = first.len() - first.trim_start_matches('#').len();
+        if level == 0 {
+            let body = if body.is_empty() {
+                String::new()
+            } else {
+                format!("\n{}", body)
+            };
+            return format!("{}# {}{}", frontmatter, title, body);
+        }
+        let heading = format!("{} {}", &first[..level], title);
+        match body.split_once('\n') {
+            Some((_, rest)) => format!("{}{}\n{}", frontmatter, heading, rest),
+            None => format!("{}{}", frontmatter, heading),
+        }
+    }
+
+    /// The heading opening the body when the `title` frontmatter field,
+    /// which names the note, says something else: the two drifted apart.
+    pub fn title_drift(content: &str) -> Option<String> {
+        let fm = Frontmatter::parse(content)?;
+        let title = fm.get("title").map(str::trim).filter(|t| !t.is_empty())?;
+        let first = content[fm.body_offset..]
+            .lines()
+            .find(|line| !line.trim().is_empty())?;
+        let rest = first.trim_start_matches('#');
+        let level = first.len() - rest.len();
+        if !(1..=6).contains(&level) || !rest.starts_with([' ', '\t']) {
+            return None;
+        }
+        let heading = rest.trim();
+        (!heading.is_empty() && heading != title).then(|| heading.to_string())
+    }
+
+    pub fn frontmatter(&self) -> Option<Frontmatter> {
+        Frontmatter::parse(&self.content)
+    }
+
+    /// Value of frontmatter field `key`.
+    pub fn field(&self, key: &str) -> Option<String> {
+        self.frontmatter()?.get(key).map(str::to_string)
+    }
+
+    /// Whether the `pinned` frontmatter field is `true`: the note stays at
+    /// the top of its list.
+    pub fn is_pinned(&self) -> bool {
+        self.field("pinned")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+    }
+
+    /// Other names of the note, from the `aliases` frontmatter field. Lookups
+    /// by title also accept them.
+    pub fn aliases(&self) -> Vec<String> {
+        Frontmatter::parse(&self.content).map_or_else(Vec::new, |fm| fm.list("aliases"))
+    }
+
+    /// Whether `name` is one of the aliases, ignoring case.
+    pub fn has_alias(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.aliases().iter().any(|a| a.to_lowercase() == name)
+    }
+
+    /// Whether the note has `tag` or a tag nested under it (`work/meetings`
+    /// for `work`), ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_lowercase();
+        self.tags.iter().any(|t| {
+            t.strip_prefix(tag.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+
     pub fn update_content(&mut self, content: String) {
         self.content = content;
         self.title = Self::extract_title(&self.content);
+        self.tags = parse_tags(&self.content);
         self.updated_at = Utc::now();
     }
 
     pub fn preview(&self, max_len: usize) -> String {
-        let preview_content = self
-            .content
+        let preview_content = strip_frontmatter(&self.content)
             .lines()
             .find(|line| !line.trim().starts_with('#') && !line.trim().is_empty())
             .unwrap_or("");
@@ -60,3 +160,43 @@ impl Note {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_drift() {
+        let content = "---\ntitle: Q3 plan\n---\n\n# Q4 plan\nbody";
+        assert_eq!(Note::title_drift(content).as_deref(), Some("Q4 plan"));
+        assert_eq!(
+            Note::title_drift("---\ntitle: Q3 plan\n---\n# Q3 plan"),
+            None
+        );
+        // Without the field, or a heading first, the title cannot drift
+        assert_eq!(Note::title_drift("# Q4 plan\nbody"), None);
+        assert_eq!(Note::title_drift("---\ntitle: A\n---\n#tag\n# B"), None);
+    }
+
+    #[test]
+    fn test_retitle() {
+        assert_eq!(Note::retitle("## Old\nbody", "New"), "## New\nbody");
+        assert_eq!(
+            Note::retitle("plain first line", "New"),
+            "# New\nplain first line"
+        );
+        assert_eq!(Note::retitle("", "New"), "# New");
+        assert_eq!(
+            Note::retitle("---\ntags: [a]\n---\n# Old", "New"),
+            "---\ntags: [a]\n---\n# New"
+        );
+        assert_eq!(Note::extract_title(&Note::retitle("# Old\n", "New")), "New");
+
+        let content = "+++\ntitle = \"Old\"\n+++\n# Heading";
+        assert_eq!(Note::extract_title(content), "Old");
+        assert_eq!(
+            Note::retitle(content, "New"),
+            "+++\ntitle = \"New\"\n+++\n# Heading"
+        );
+    }
+}
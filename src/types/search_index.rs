@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A note as recorded in the search index: its id and a hash of the text
+/// that was indexed, to tell whether it changed since.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedNote {
+    pub id: String,
+    pub hash: u32,
+}
+
+/// Saved form of the search index. `notes` is indexed by slot (an empty id
+/// marks a free slot) and each trigram lists the slots containing it.
+/// Persisted as `search_index.toml` in the data directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    /// Format of the indexed text; see `SearchIndex`. Missing in indexes
+    /// saved before it was added.
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub notes: Vec<IndexedNote>,
+    #[serde(default)]
+    pub trigrams: BTreeMap<String, Vec<u32>>,
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+/// The operation a note snapshot was taken before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotReason {
+    Replace,
+    ExternalEditor,
+    Agenda,
+    Processing,
+    Restore,
+    Split,
+}
+
+impl SnapshotReason {
+    const ALL: [SnapshotReason; 6] = [
+        SnapshotReason::Replace,
+        SnapshotReason::ExternalEditor,
+        SnapshotReason::Agenda,
+        SnapshotReason::Processing,
+        SnapshotReason::Restore,
+        SnapshotReason::Split,
+    ];
+
+    /// The name used in the snapshot's file name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SnapshotReason::Replace => "replace",
+            SnapshotReason::ExternalEditor => "external-editor",
+            SnapshotReason::Agenda => "agenda",
+            SnapshotReason::Processing => "processing",
+            SnapshotReason::Restore => "restore",
+            SnapshotReason::Split => "split",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.as_str() == name)
+    }
+
+    /// Shown in the history picker.
+    pub fn label(self) -> &'static str {
+        match self {
+            SnapshotReason::Replace => "Before :replace-all",
+            SnapshotReason::ExternalEditor => "Before external editor",
+            SnapshotReason::Agenda => "Before :agenda",
+            SnapshotReason::Processing => "Before processing",
+            SnapshotReason::Restore => "Before restore",
+            SnapshotReason::Split => "Before :split-headings",
+        }
+    }
+}
+
+/// A copy of a note as it was before a risky operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSnapshot {
+    pub path: PathBuf,
+    pub taken: NaiveDateTime,
+    pub reason: SnapshotReason,
+}
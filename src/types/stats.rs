@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Words written per day, keyed by `YYYY-MM-DD`. Persisted as `stats.toml`
+/// in the data directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WritingHistory {
+    #[serde(default)]
+    pub days: BTreeMap<String, usize>,
+}
+
+/// Data shown in the stats dashboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsSummary {
+    pub session_words: usize,
+    pub session_notes: usize,
+    pub session_minutes: i64,
+    pub today: usize,
+    pub streak: usize,
+    pub best_day: Option<(String, usize)>,
+    /// Oldest first, ending today.
+    pub last_week: Vec<(String, usize)>,
+}
@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use super::Frontmatter;
+
+/// Character ranges of the `#tags` on `line`, `#` included. A tag starts
+/// after whitespace, `(` or at the line start with a letter, digit or `_`,
+/// goes on over those and `-` and `/` (`#work/meetings`), and has at least
+/// one non-digit, so `# Heading`, `#1` and `page#anchor` are not tags. Tags
+/// in inline code are skipped.
+pub fn tag_spans(line: &str) -> Vec<Range<usize>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut in_code = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '`' {
+            in_code = !in_code;
+        }
+        let starts_tag = c == '#'
+            && !in_code
+            && (i == 0 || chars[i - 1].is_whitespace() || chars[i - 1] == '(')
+            && chars
+                .get(i + 1)
+                .is_some_and(|&next| next.is_alphanumeric() || next == '_');
+        if !starts_tag {
+            i += 1;
+            continue;
+        }
+        let mut end = (i + 1..chars.len())
+            .find(|&j| !is_tag_char(chars[j]))
+            .unwrap_or(chars.len());
+        while chars[end - 1] == '/' {
+            end -= 1;
+        }
+        if chars[i + 1..end].iter().any(|c| !c.is_ascii_digit()) {
+            spans.push(i..end);
+        }
+        i = end;
+    }
+    spans
+}
+
+/// Tags of a note, lowercased, sorted and without `#`: those written in
+/// the body (outside fenced code) and those of the `tags` frontmatter field.
+pub fn parse_tags(content: &str) -> Vec<String> {
+    let frontmatter = Frontmatter::parse(content);
+    let body = &content[frontmatter.as_ref().map_or(0, |fm| fm.body_offset)..];
+    let mut tags: Vec<String> = frontmatter
+        .map(|fm| fm.list("tags"))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|tag| as_tag(&format!("#{}", tag.trim_start_matches('#'))).map(str::to_string))
+        .collect();
+
+    let mut fence: Option<&str> = None;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        let chars: Vec<char> = line.chars().collect();
+        for span in tag_spans(line) {
+            tags.push(chars[span.start + 1..span.end].iter().collect());
+        }
+    }
+
+    let mut tags: Vec<String> = tags.iter().map(|tag| tag.to_lowercase()).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// `text` without its `#` if the whole of it is one tag, e.g. a list search
+/// for `#work`.
+pub fn as_tag(text: &str) -> Option<&str> {
+    match tag_spans(text).as_slice() {
+        [span] if span.start == 0 && span.end == text.chars().count() => Some(&text[1..]),
+        _ => None,
+    }
+}
+
+/// `content` with `#tag` added: to its last line if that holds only tags,
+/// else on a line of its own after a blank one.
+pub fn add_tag(content: &str, tag: &str) -> String {
+    let body = content.trim_end();
+    let last = body.lines().last().unwrap_or("");
+    let chars = last.chars().count();
+    let tag_chars: usize = tag_spans(last).iter().map(|span| span.len()).sum();
+    let only_tags = tag_chars > 0 && chars - tag_chars == last.matches(' ').count();
+    if body.is_empty() {
+        format!("#{}", tag)
+    } else if only_tags {
+        format!("{} #{}", body, tag)
+    } else {
+        format!("{}\n\n#{}", body, tag)
+    }
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_spans() {
+        assert_eq!(tag_spans("#work and #Home/garden."), [0..5, 10..22]);
+        assert!(tag_spans("# Heading").is_empty());
+        assert!(tag_spans("## Sub #1 page#anchor `#code`").is_empty());
+        assert_eq!(tag_spans("- [ ] call #mom (#urgent/)"), [11..15, 17..24]);
+        assert_eq!(as_tag("#work/q3"), Some("work/q3"));
+        assert_eq!(as_tag("#work notes"), None);
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        let content =
+            "---\ntags: [Work, \"#idea\"]\n---\n# Plan #work\n```\n#not-a-tag\n```\n#café";
+        assert_eq!(parse_tags(content), ["café", "idea", "work"]);
+        assert!(parse_tags("# Title\nplain text").is_empty());
+    }
+
+    #[test]
+    fn test_add_tag() {
+        assert_eq!(add_tag("# Plan\nsteps\n", "work"), "# Plan\nsteps\n\n#work");
+        assert_eq!(
+            add_tag("# Plan\n\n#work #idea", "q3"),
+            "# Plan\n\n#work #idea #q3"
+        );
+        assert_eq!(add_tag("", "work"), "#work");
+    }
+}
@@ -1,6 +1,8 @@
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
+use super::ColorOverrides;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theme {
     pub name: String,
@@ -14,6 +16,10 @@ pub struct Theme {
     pub warning: String,
     pub error: String,
     pub panel: String,
+    pub visual_char: String,
+    pub visual_line: String,
+    pub visual_block: String,
+    pub search_match: String,
 }
 
 impl Theme {
@@ -57,6 +63,71 @@ impl Theme {
         Self::parse_hex(&self.panel)
     }
 
+    pub fn visual_char_color(&self) -> Color {
+        Self::parse_hex(&self.visual_char)
+    }
+
+    pub fn visual_line_color(&self) -> Color {
+        Self::parse_hex(&self.visual_line)
+    }
+
+    pub fn visual_block_color(&self) -> Color {
+        Self::parse_hex(&self.visual_block)
+    }
+
+    pub fn search_match_color(&self) -> Color {
+        Self::parse_hex(&self.search_match)
+    }
+
+    /// Replace editor highlight colors with any values set in `[colors]`.
+    pub fn apply_overrides(&mut self, overrides: &ColorOverrides) {
+        let pairs = [
+            (&overrides.visual_char, &mut self.visual_char),
+            (&overrides.visual_line, &mut self.visual_line),
+            (&overrides.visual_block, &mut self.visual_block),
+            (&overrides.search_match, &mut self.search_match),
+        ];
+        for (value, field) in pairs {
+            if let Some(hex) = value {
+                *field = hex.clone();
+            }
+        }
+    }
+
+    /// Check that every color role is a `#rrggbb` value; the error names
+    /// the first that is not.
+    pub fn validate(&self) -> Result<(), String> {
+        let roles = [
+            ("bg", &self.bg),
+            ("fg", &self.fg),
+            ("cursor", &self.cursor),
+            ("selection", &self.selection),
+            ("border", &self.border),
+            ("accent", &self.accent),
+            ("success", &self.success),
+            ("warning", &self.warning),
+            ("error", &self.error),
+            ("panel", &self.panel),
+            ("visual_char", &self.visual_char),
+            ("visual_line", &self.visual_line),
+            ("visual_block", &self.visual_block),
+            ("search_match", &self.search_match),
+        ];
+        for (role, value) in roles {
+            let hex = value.strip_prefix('#').unwrap_or_default();
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("{} = \"{}\" is not a #rrggbb color", role, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// The name `general.theme` selects this theme by: lowercase, with
+    /// spaces as underscores (`Tokyo Night` → `tokyo_night`).
+    pub fn key(&self) -> String {
+        self.name.to_lowercase().replace(' ', "_")
+    }
+
     fn parse_hex(hex: &str) -> Color {
         let hex = hex.trim_start_matches('#');
         if hex.len() == 6 {
@@ -82,6 +153,10 @@ impl Theme {
             warning: "#e0af68".to_string(),
             error: "#f7768e".to_string(),
             panel: "#16161e".to_string(),
+            visual_char: "#3d59a1".to_string(),
+            visual_line: "#2e3c64".to_string(),
+            visual_block: "#394b70".to_string(),
+            search_match: "#e0af68".to_string(),
         }
     }
 
@@ -98,6 +173,10 @@ impl Theme {
             warning: "#d65d0e".to_string(),
             error: "#cc241d".to_string(),
             panel: "#1d2021".to_string(),
+            visual_char: "#076678".to_string(),
+            visual_line: "#504945".to_string(),
+            visual_block: "#8f3f71".to_string(),
+            search_match: "#d79921".to_string(),
         }
     }
 
@@ -114,6 +193,10 @@ impl Theme {
             warning: "#ebcb8b".to_string(),
             error: "#bf616a".to_string(),
             panel: "#242933".to_string(),
+            visual_char: "#5e81ac".to_string(),
+            visual_line: "#434c5e".to_string(),
+            visual_block: "#4c566a".to_string(),
+            search_match: "#ebcb8b".to_string(),
         }
     }
 
@@ -130,6 +213,10 @@ impl Theme {
             warning: "#f9e2af".to_string(),
             error: "#f38ba8".to_string(),
             panel: "#181825".to_string(),
+            visual_char: "#45475a".to_string(),
+            visual_line: "#313244".to_string(),
+            visual_block: "#585b70".to_string(),
+            search_match: "#f9e2af".to_string(),
         }
     }
 
@@ -146,6 +233,10 @@ impl Theme {
             warning: "#eed49f".to_string(),
             error: "#ed8796".to_string(),
             panel: "#1e2030".to_string(),
+            visual_char: "#494d64".to_string(),
+            visual_line: "#363a4f".to_string(),
+            visual_block: "#5b6078".to_string(),
+            search_match: "#eed49f".to_string(),
         }
     }
 
@@ -162,6 +253,10 @@ impl Theme {
             warning: "#e5c890".to_string(),
             error: "#e78284".to_string(),
             panel: "#292c3c".to_string(),
+            visual_char: "#51576d".to_string(),
+            visual_line: "#414559".to_string(),
+            visual_block: "#626880".to_string(),
+            search_match: "#e5c890".to_string(),
         }
     }
 
@@ -178,6 +273,74 @@ impl Theme {
             warning: "#df8e1d".to_string(),
             error: "#d20f39".to_string(),
             panel: "#e6e9ef".to_string(),
+            visual_char: "#acb0be".to_string(),
+            visual_line: "#ccd0da".to_string(),
+            visual_block: "#bcc0cc".to_string(),
+            search_match: "#df8e1d".to_string(),
+        }
+    }
+
+    /// Pure black and white with saturated accents for low vision.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "High Contrast".to_string(),
+            bg: "#000000".to_string(),
+            fg: "#ffffff".to_string(),
+            cursor: "#ffff00".to_string(),
+            selection: "#0000c0".to_string(),
+            border: "#ffffff".to_string(),
+            accent: "#00ffff".to_string(),
+            success: "#00ff00".to_string(),
+            warning: "#ffff00".to_string(),
+            error: "#ff4040".to_string(),
+            panel: "#000000".to_string(),
+            visual_char: "#005f87".to_string(),
+            visual_line: "#303030".to_string(),
+            visual_block: "#5f005f".to_string(),
+            search_match: "#ff00ff".to_string(),
+        }
+    }
+
+    /// Okabe-Ito colors: success is blue and error is vermillion, so status
+    /// never hinges on telling red from green.
+    pub fn deuteranopia() -> Self {
+        Self {
+            name: "Deuteranopia".to_string(),
+            bg: "#1c1c1c".to_string(),
+            fg: "#e4e4e4".to_string(),
+            cursor: "#f0e442".to_string(),
+            selection: "#2f3f5f".to_string(),
+            border: "#5a5a5a".to_string(),
+            accent: "#56b4e9".to_string(),
+            success: "#0093e0".to_string(),
+            warning: "#f0e442".to_string(),
+            error: "#d55e00".to_string(),
+            panel: "#141414".to_string(),
+            visual_char: "#004f7a".to_string(),
+            visual_line: "#333a48".to_string(),
+            visual_block: "#5a3a50".to_string(),
+            search_match: "#cc79a7".to_string(),
+        }
+    }
+
+    /// Like Deuteranopia, but error is orange: red looks dark to protans.
+    pub fn protanopia() -> Self {
+        Self {
+            name: "Protanopia".to_string(),
+            bg: "#1c1c1c".to_string(),
+            fg: "#e4e4e4".to_string(),
+            cursor: "#f0e442".to_string(),
+            selection: "#2f3f5f".to_string(),
+            border: "#5a5a5a".to_string(),
+            accent: "#56b4e9".to_string(),
+            success: "#0093e0".to_string(),
+            warning: "#f0e442".to_string(),
+            error: "#e69f00".to_string(),
+            panel: "#141414".to_string(),
+            visual_char: "#004f7a".to_string(),
+            visual_line: "#333a48".to_string(),
+            visual_block: "#5a3a50".to_string(),
+            search_match: "#cc79a7".to_string(),
         }
     }
 
@@ -190,6 +353,9 @@ impl Theme {
             Self::catppuccin_macchiato(),
             Self::catppuccin_frappe(),
             Self::catppuccin_latte(),
+            Self::high_contrast(),
+            Self::deuteranopia(),
+            Self::protanopia(),
         ]
     }
 }
@@ -0,0 +1,1468 @@
+//! End-to-end tests: an `App` on a temporary data directory, driven by key
+//! presses through `EventDispatcher` the way the main loop routes them, with
+//! assertions on the files left on disk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use kenotex::atoms::storage::load_all_drafts;
+use kenotex::coordinator::{App, EventDispatcher};
+use kenotex::molecules::distribution::RecordingDispatcher;
+use kenotex::types::{AppMode, AppearanceMode, BlockType, Config, Note, SnapshotReason, View};
+
+struct TestApp {
+    app: App,
+    dir: PathBuf,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    fn with_config(config: Config) -> Self {
+        let dir = std::env::temp_dir().join(format!("kenotex-e2e-{}", uuid::Uuid::new_v4()));
+        let mut app = App::with_config(config, dir.clone()).unwrap();
+        app.set_view(View::DraftList);
+        Self { app, dir }
+    }
+
+    /// Route a key like the main loop: list keys first, then the dispatcher.
+    fn press(&mut self, code: KeyCode) {
+        self.press_with(code, KeyModifiers::NONE);
+    }
+
+    fn ctrl(&mut self, c: char) {
+        self.press_with(KeyCode::Char(c), KeyModifiers::CONTROL);
+    }
+
+    fn press_with(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let app = &mut self.app;
+        let key = KeyEvent::new(code, modifiers);
+        if matches!(app.view, View::DraftList | View::ArchiveList | View::Trash)
+            && app.mode == AppMode::Normal
+            && !app.vim_mode.is_leader_pending()
+            && EventDispatcher::handle_list_key(app, key).unwrap()
+        {
+            return;
+        }
+        EventDispatcher::handle_key(app, key).unwrap();
+    }
+
+    fn keys(&mut self, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\x1b' => KeyCode::Esc,
+                c => KeyCode::Char(c),
+            };
+            self.press(code);
+        }
+    }
+
+    /// `n` in the draft list, type `text`, then `Space l` to save and return.
+    fn write_note(&mut self, text: &str) {
+        self.keys("n");
+        self.keys(text);
+        self.keys("\x1b l");
+        self.app.flush_saves().unwrap();
+    }
+
+    fn on_disk(&self, archived: bool) -> Vec<Note> {
+        load_all_drafts(&self.dir, archived).unwrap()
+    }
+
+    fn file_count(&self, folder: &str) -> usize {
+        md_files(&self.dir.join(folder)).len()
+    }
+
+    /// Open the selected note, run `Space s` and drive processing to the
+    /// end like the main loop.
+    fn process_selected(&mut self) {
+        self.keys("\n s");
+        assert_eq!(self.app.mode, AppMode::Processing);
+        while self.app.process_next_block() {}
+        self.app.finish_processing();
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn md_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_new_note_is_written_to_drafts() {
+    let mut t = TestApp::new();
+    t.write_note("# Groceries\nmilk\neggs");
+
+    assert_eq!(t.app.view, View::DraftList);
+    assert!(!t.app.dirty);
+    let drafts = t.on_disk(false);
+    assert_eq!(drafts.len(), 1);
+    assert_eq!(drafts[0].title, "Groceries");
+    assert_eq!(drafts[0].content, "# Groceries\nmilk\neggs");
+    assert_eq!(t.file_count("drafts"), 1);
+}
+
+#[test]
+fn test_edit_existing_note_rewrites_file() {
+    let mut t = TestApp::new();
+    t.write_note("# Plan\nfirst");
+
+    // Open it, append a line, save
+    t.keys("\nGosecond\x1b l");
+    t.app.flush_saves().unwrap();
+
+    let drafts = t.on_disk(false);
+    assert_eq!(drafts.len(), 1);
+    assert_eq!(drafts[0].content, "# Plan\nfirst\nsecond");
+    assert_eq!(t.file_count("drafts"), 1);
+}
+
+#[test]
+fn test_archive_restore_and_undo_move_files() {
+    let mut t = TestApp::new();
+    t.write_note("# Keep");
+    t.write_note("# Old");
+
+    // The newest note is first; archive it
+    t.keys("a");
+    assert_eq!(t.app.command_message, "Note archived (u to undo)");
+    assert_eq!(t.file_count("drafts"), 1);
+    assert_eq!(t.on_disk(true)[0].title, "Old");
+
+    t.keys("u");
+    assert_eq!(t.file_count("archives"), 0);
+    assert_eq!(t.file_count("drafts"), 2);
+
+    // Archive again, then restore from the archive list
+    t.keys("aAr");
+    assert_eq!(t.app.view, View::ArchiveList);
+    assert_eq!(t.file_count("archives"), 0);
+    let mut titles: Vec<String> = t.on_disk(false).into_iter().map(|n| n.title).collect();
+    titles.sort();
+    assert_eq!(titles, ["Keep", "Old"]);
+}
+
+#[test]
+fn test_archive_is_read_when_first_opened() {
+    let mut t = TestApp::new();
+    t.write_note("# Old");
+    t.keys("a");
+    assert!(!t.app.archives_loaded());
+    assert!(t.app.archive_list.is_empty());
+
+    t.keys("A");
+    assert!(t.app.archives_loaded());
+    assert_eq!(t.app.archive_list.selected_note().unwrap().title, "Old");
+}
+
+#[test]
+fn test_delete_needs_confirmation() {
+    let mut t = TestApp::new();
+    t.write_note("# Scratch");
+
+    t.keys("dn");
+    assert_eq!(t.file_count("drafts"), 1);
+
+    t.keys("dy");
+    assert_eq!(t.app.mode, AppMode::Normal);
+    assert_eq!(t.file_count("drafts"), 0);
+    assert!(t.app.draft_list.is_empty());
+}
+
+#[test]
+fn test_deleted_notes_go_to_trash() {
+    let mut t = TestApp::new();
+    t.write_note("# Scratch");
+    t.write_note("# Keep");
+
+    t.keys("ggdy");
+    assert_eq!(t.file_count("drafts"), 1);
+    assert_eq!(t.file_count("trash"), 1);
+    // Undo takes it back out of the trash
+    t.keys("u");
+    assert_eq!(t.file_count("drafts"), 2);
+    assert_eq!(t.file_count("trash"), 0);
+
+    t.keys("ggdyT");
+    assert_eq!(t.app.view, View::Trash);
+    assert_eq!(t.app.trash_list.len(), 1);
+    t.keys("\n");
+    assert_eq!(t.app.view, View::Trash);
+    t.keys("r");
+    assert_eq!(t.app.command_message, "Note restored");
+    assert_eq!(t.file_count("drafts"), 2);
+    assert!(t.app.trash_list.is_empty());
+
+    t.keys("\x1bggdyTdy");
+    assert_eq!(t.app.command_message, "Note deleted permanently");
+    assert_eq!(t.file_count("drafts"), 1);
+    assert_eq!(t.file_count("trash"), 0);
+    t.keys("\x1b");
+    assert_eq!(t.app.view, View::DraftList);
+}
+
+#[test]
+fn test_export_expands_transclusions() {
+    let mut t = TestApp::new();
+    t.write_note("# Intro\nHello");
+    t.write_note("# Plan\n## Goals\nShip\n## Risks\nnone");
+    t.write_note("# Book\n![[Intro]]\n![[Plan#Goals]]");
+
+    t.keys("gg\n e\n");
+    let exports = md_files(&t.dir.join("exports"));
+    assert_eq!(exports.len(), 1);
+    assert_eq!(
+        fs::read_to_string(&exports[0]).unwrap(),
+        "# Book\n# Intro\nHello\n## Goals\nShip"
+    );
+}
+
+#[test]
+fn test_export_html_and_pdf_to_a_path() {
+    let mut config = Config::default();
+    config.general.pdf_command = "cp {input} {output}".to_string();
+    let mut t = TestApp::with_config(config);
+    t.write_note("# Plan\n- [x] **done**");
+    let out = t.dir.join("out");
+
+    t.keys(&format!(
+        "gg\n:export html {}\n",
+        out.join("plan.html").display()
+    ));
+    let html = fs::read_to_string(out.join("plan.html")).unwrap();
+    assert!(html.contains("<title>Plan</title>"));
+    assert!(html.contains("<input type=\"checkbox\" disabled checked> <strong>done</strong>"));
+
+    // The picker offers PDF once a tool is set; a directory gets the note's name
+    t.keys(&format!(":export pdf {}\n", out.display()));
+    assert!(
+        fs::read_to_string(out.join("plan.pdf"))
+            .unwrap()
+            .starts_with("<!DOCTYPE html>")
+    );
+    t.keys(" e");
+    assert_eq!(t.app.export_picker.as_ref().unwrap().0.len(), 3);
+}
+
+#[test]
+fn test_open_note_by_id_or_alias() {
+    let mut t = TestApp::new();
+    t.write_note("---\naliases: [Q3]\n---\n# Roadmap");
+    t.write_note("# Other");
+    let notes = t.on_disk(false);
+    let id = notes
+        .iter()
+        .find(|n| n.title == "Roadmap")
+        .unwrap()
+        .id
+        .clone();
+
+    t.app.open_note(&id);
+    assert_eq!(t.app.view, View::Editor);
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Roadmap");
+    t.app.open_note("other");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Other");
+    t.app.open_note("q3");
+    assert_eq!(t.app.current_note.as_ref().unwrap().id, id);
+    t.app.open_note("missing");
+    assert_eq!(t.app.command_message, "No note named 'missing'");
+}
+
+#[test]
+fn test_daily_notes() {
+    let mut config = Config::default();
+    config.daily.template = "# {{date}}\n\n## Tasks\n".to_string();
+    let mut t = TestApp::with_config(config);
+    t.write_note("2024-06-01\nold");
+    t.write_note("2024-06-03\nnewer");
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let title = |t: &TestApp| t.app.current_note.as_ref().unwrap().title.clone();
+
+    t.keys(":today\n");
+    assert_eq!(t.app.view, View::Editor);
+    assert_eq!(title(&t), today);
+    assert_eq!(t.app.buffer.to_string(), format!("# {}\n\n## Tasks", today));
+
+    t.keys("[d");
+    assert_eq!(title(&t), "2024-06-03");
+    t.keys("[d[d");
+    assert_eq!(title(&t), "2024-06-01");
+    assert_eq!(t.app.command_message, "No earlier daily note");
+    t.keys("2]d");
+    assert_eq!(title(&t), today);
+    t.keys("[d D");
+    assert_eq!(title(&t), today);
+    t.app.flush_saves().unwrap();
+    assert_eq!(t.file_count("drafts"), 3);
+}
+
+#[test]
+fn test_new_note_from_template() {
+    let mut t = TestApp::new();
+    let templates = t.dir.join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("meeting.md"), "# {{title}}\n{{date}}\n").unwrap();
+    t.app.templates_dir = Some(templates);
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    // Blank note first, then the templates
+    t.keys("n");
+    assert_eq!(t.app.template_picker.as_ref().unwrap().0.len(), 2);
+    t.keys("j\n");
+    assert_eq!(t.app.view, View::Editor);
+    assert_eq!(t.app.buffer.to_string(), format!("# Untitled\n{}", today));
+
+    t.keys(":new MEETING Weekly sync\n");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Weekly sync");
+    t.keys(":new standup\n");
+    assert_eq!(t.app.command_message, "No template named 'standup'");
+
+    t.keys(" nnq");
+    assert!(t.app.template_picker.is_none());
+    t.keys(" nn\n");
+    assert_eq!(t.app.mode, AppMode::Insert);
+    assert!(t.app.buffer.to_string().is_empty());
+}
+
+#[test]
+fn test_replace_all_across_notes() {
+    let mut t = TestApp::new();
+    t.write_note("Pets\ncat one\ncat two");
+    t.write_note("Farm\nbarn");
+    let content = |t: &TestApp, title: &str| {
+        t.on_disk(false)
+            .into_iter()
+            .find(|n| n.title == title)
+            .unwrap()
+            .content
+    };
+
+    t.keys(":replace-all /cat/dog/\n");
+    assert_eq!(t.app.mode, AppMode::ConfirmReplace);
+    assert_eq!(t.app.quickfix.as_ref().unwrap().locations().len(), 2);
+    assert_eq!(t.app.buffer.cursor_position(), (1, 0));
+    t.keys("yn");
+    assert_eq!(t.app.mode, AppMode::Normal);
+    assert_eq!(t.app.command_message, "1 line(s) changed in 1 note(s)");
+    assert!(t.app.quickfix.is_none());
+    t.app.flush_saves().unwrap();
+    assert_eq!(content(&t, "Pets"), "Pets\ndog one\ncat two");
+
+    // The whole note comes back with one undo
+    t.keys("u");
+    assert_eq!(t.app.buffer.to_string(), "Pets\ncat one\ncat two");
+
+    t.keys(":replace-all /barn/shed/\nq");
+    assert_eq!(t.app.command_message, "0 line(s) changed in 0 note(s)");
+    t.keys(":replace-all /llama/x/\n");
+    assert_eq!(t.app.command_message, "Pattern not found: llama");
+    t.app.flush_saves().unwrap();
+    assert_eq!(content(&t, "Farm"), "Farm\nbarn");
+}
+
+#[test]
+fn test_external_editor_result_keeps_cursor_and_undoes_at_once() {
+    let mut t = TestApp::new();
+    t.write_note("Plan\none\ntwo\nthree");
+    t.keys("\nggjj0ll");
+    t.app
+        .apply_external_editor_result("Plan\none\ntwo\nthree".to_string());
+    assert_eq!(t.app.command_message, "No changes from external editor");
+
+    t.app
+        .apply_external_editor_result("Plan\nadded\nalso added\none\nTWO\nthree".to_string());
+    assert_eq!(t.app.buffer.cursor_position(), (4, 2));
+    assert_eq!(t.app.command_message, "Buffer updated from external editor");
+    t.keys("u");
+    assert_eq!(t.app.buffer.to_string(), "Plan\none\ntwo\nthree");
+}
+
+#[test]
+fn test_spell_errors_navigation_and_suggestions() {
+    let mut config = Config::default();
+    config.general.spell = true;
+    let mut t = TestApp::with_config(config);
+    let dicts = t.dir.join("dicts");
+    fs::create_dir_all(&dicts).unwrap();
+    fs::write(dicts.join("en_US.dic"), "5\nplan\nthe\ncity\nis\nbig\n").unwrap();
+    t.app.dictionary_dirs = vec![dicts];
+    t.write_note("Plan\nthe citty is bigg");
+    t.keys("\ngg");
+    assert_eq!(&t.app.spell_errors()[..], [(1, 4, 5), (1, 13, 4)]);
+
+    t.keys("]s");
+    assert_eq!(t.app.buffer.cursor_position(), (1, 4));
+    t.keys("]s]s");
+    assert_eq!(t.app.buffer.cursor_position(), (1, 4));
+    t.keys("[s");
+    assert_eq!(t.app.buffer.cursor_position(), (1, 13));
+
+    t.keys("0w");
+    t.keys("z=");
+    let (suggestions, _) = t.app.spell_picker.clone().unwrap();
+    assert_eq!(suggestions[0], "city");
+    t.keys("\n");
+    assert_eq!(t.app.buffer.content()[1], "the city is bigg");
+    assert_eq!(&t.app.spell_errors()[..], [(1, 12, 4)]);
+    t.keys("u");
+    assert_eq!(t.app.buffer.content()[1], "the citty is bigg");
+
+    t.keys(":set spelllang=fr_FR\n");
+    assert_eq!(t.app.command_message, "No dictionary for fr_FR");
+    assert!(t.app.buffer.to_string().contains("spelllang: fr_FR"));
+    assert!(t.app.spell_errors().is_empty());
+    t.keys(":set nospell\n");
+    t.keys("]s");
+    assert_eq!(t.app.command_message, "Spell checking is off");
+}
+
+#[test]
+fn test_snapshots_before_rewrites_and_restore() {
+    let mut t = TestApp::new();
+    t.write_note("Pets\ncat");
+    t.keys(":replace-all /cat/dog/\na");
+    t.app.apply_external_editor_result("Pets\nbird".to_string());
+    let reasons = |t: &TestApp| -> Vec<SnapshotReason> {
+        let (snapshots, _) = t.app.snapshot_picker.as_ref().unwrap();
+        snapshots.iter().map(|s| s.reason).collect()
+    };
+
+    t.keys(":history\n");
+    assert_eq!(
+        reasons(&t),
+        [SnapshotReason::ExternalEditor, SnapshotReason::Replace]
+    );
+    t.keys("j\n");
+    assert!(t.app.snapshot_picker.is_none());
+    assert_eq!(t.app.buffer.to_string(), "Pets\ncat");
+    t.keys("u");
+    assert_eq!(t.app.buffer.to_string(), "Pets\nbird");
+
+    t.keys(":history\n");
+    assert_eq!(reasons(&t)[0], SnapshotReason::Restore);
+    t.keys("q");
+    assert!(t.app.snapshot_picker.is_none());
+}
+
+#[test]
+fn test_snippet_expands_and_jumps_tabstops() {
+    let mut config = Config::default();
+    config.snippets.insert(
+        ";mt".to_string(),
+        "## Meeting: $1\nWith: $2\n$0".to_string(),
+    );
+    let mut t = TestApp::with_config(config);
+
+    t.keys("n- ;mt");
+    t.press(KeyCode::Tab);
+    assert_eq!(t.app.buffer.to_string(), "- ## Meeting: \nWith: \n");
+    assert_eq!(t.app.buffer.cursor_position(), (0, 14));
+    t.keys("Plan");
+    t.press(KeyCode::Tab);
+    assert_eq!(t.app.buffer.cursor_position(), (1, 6));
+    t.keys("Ann");
+    t.press(KeyCode::Tab);
+    assert!(t.app.snippet.is_none());
+    t.keys("x");
+    assert_eq!(t.app.buffer.to_string(), "- ## Meeting: Plan\nWith: Ann\nx");
+
+    // Without a trigger or tabstops left, Tab indents
+    t.press(KeyCode::Tab);
+    assert!(t.app.buffer.to_string().ends_with("x    "));
+}
+
+#[test]
+fn test_import_markdown_folder() {
+    let mut t = TestApp::new();
+    let vault = t.dir.join("vault");
+    fs::create_dir_all(vault.join("sub")).unwrap();
+    fs::write(vault.join("Trip.md"), "# Lisbon\nflights").unwrap();
+    fs::write(vault.join("sub/groceries.md"), "- eggs").unwrap();
+    fs::write(vault.join("photo.png"), "").unwrap();
+
+    t.keys(&format!(":import {}\n", vault.display()));
+    assert_eq!(t.app.command_message, "Imported 2 note(s) from 2 file(s)");
+    let mut titles: Vec<&str> = t
+        .app
+        .draft_list
+        .notes()
+        .iter()
+        .map(|n| n.title.as_str())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Lisbon", "groceries"]);
+    assert_eq!(t.file_count("drafts"), 2);
+}
+
+#[test]
+fn test_process_blocks_with_recording_dispatcher() {
+    let mut t = TestApp::new();
+    let recorder = RecordingDispatcher::new();
+    t.app.dispatcher = Box::new(recorder.clone());
+    t.write_note(":::td Buy milk\n\n:::note Random thought");
+
+    t.process_selected();
+    assert_eq!(
+        t.app.command_message,
+        "Processing complete: 2 sent, 0 skipped, 0 failed"
+    );
+    let kinds: Vec<BlockType> = recorder.recorded().iter().map(|b| b.block_type).collect();
+    assert_eq!(kinds, [BlockType::Reminder, BlockType::Note]);
+
+    t.keys(" l");
+    t.app.flush_saves().unwrap();
+    assert_eq!(
+        t.on_disk(false)[0].content,
+        "<!-- :::td Buy milk -->\n\n<!-- :::note Random thought -->"
+    );
+
+    // Sent blocks are commented out, so a second run sends nothing
+    t.process_selected();
+    assert_eq!(
+        t.app.command_message,
+        "Processing complete: 0 sent, 2 skipped, 0 failed"
+    );
+    assert_eq!(recorder.recorded().len(), 2);
+}
+
+#[test]
+fn test_processed_style_from_config() {
+    let mut config = Config::default();
+    config.general.processed_style = "quote".to_string();
+    let mut t = TestApp::with_config(config);
+    let recorder = RecordingDispatcher::new();
+    t.app.dispatcher = Box::new(recorder.clone());
+    t.write_note(":::td Buy milk\n\n%% :::note Sent before %%");
+
+    t.process_selected();
+    assert_eq!(
+        t.app.command_message,
+        "Processing complete: 1 sent, 1 skipped, 0 failed"
+    );
+    assert_eq!(
+        t.app.buffer.to_string(),
+        "> ✅ sent: :::td Buy milk\n\n%% :::note Sent before %%"
+    );
+    t.keys(" l");
+    t.process_selected();
+    assert_eq!(recorder.recorded().len(), 1);
+}
+
+#[test]
+fn test_lint_blocks_jumps_to_problem() {
+    let mut t = TestApp::new();
+    t.write_note("# Plan\n\n:::td Buy milk\n\n:::cal Lunch with Ann\n\n:::remind me");
+    t.keys("\n:lint-blocks\n");
+    let list = t.app.quickfix.as_ref().unwrap();
+    let rows: Vec<usize> = list.locations().iter().map(|l| l.row).collect();
+    assert_eq!(rows, [4, 6]);
+    assert!(t.app.show_quickfix && t.app.quickfix_focused);
+    t.keys("j\n");
+    assert!(t.app.show_quickfix && !t.app.quickfix_focused);
+    assert_eq!(t.app.buffer.cursor_position(), (6, 0));
+
+    // ]q / [q walk the list from the editor, with the panel hidden too
+    t.keys(":cclose\n[q");
+    assert!(!t.app.show_quickfix);
+    assert_eq!(t.app.buffer.cursor_position(), (4, 0));
+    t.keys("[q");
+    assert_eq!(t.app.command_message, "No more locations");
+    t.keys(":copen\nq");
+    assert!(!t.app.show_quickfix && !t.app.quickfix_focused);
+
+    t.keys("jjdd:lint-blocks\n\x1b");
+    assert_eq!(t.app.quickfix.as_ref().unwrap().locations().len(), 1);
+    t.keys("ggjjjjdd:lint-blocks\n");
+    assert_eq!(t.app.command_message, "No block problems");
+}
+
+#[test]
+fn test_sync_reminders_checks_off_completed() {
+    let mut t = TestApp::new();
+    let recorder = RecordingDispatcher::new().completing(&["Milk", "Call Bob"]);
+    t.app.dispatcher = Box::new(recorder);
+    // Enter continues the checklist, and again on an empty item ends it
+    t.write_note(":::td\n- [ ] Milk\nEggs\n\n:::td Call Bob");
+
+    t.process_selected();
+    t.keys(":syncreminders\n");
+    assert_eq!(t.app.command_message, "2 reminder(s) checked off");
+    t.keys(" l");
+    t.app.flush_saves().unwrap();
+    assert_eq!(
+        t.on_disk(false)[0].content,
+        "<!-- :::td\n- [x] Milk\n- [ ] Eggs -->\n\n<!-- [x] :::td Call Bob -->"
+    );
+
+    t.keys("\n:syncreminders\n");
+    assert_eq!(t.app.command_message, "No completed reminders");
+}
+
+#[test]
+fn test_agenda_fills_placeholder() {
+    use chrono::NaiveTime;
+    use kenotex::types::AgendaEvent;
+
+    let mut t = TestApp::new();
+    let standup = AgendaEvent {
+        title: "Standup".to_string(),
+        start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+        all_day: false,
+    };
+    t.app.dispatcher = Box::new(RecordingDispatcher::new().scheduling(vec![standup]));
+    t.write_note("# Today\n{{agenda}}\n## Log");
+
+    t.keys("\n:agenda\n");
+    assert_eq!(t.app.command_message, "1 event(s) today");
+    let content = t.app.buffer.to_string();
+    assert!(content.starts_with("# Today\n## Agenda "));
+    assert!(content.ends_with("\n\n- 09:00–09:15 Standup\n## Log"));
+}
+
+#[test]
+fn test_dry_run_leaves_note_alone() {
+    let mut t = TestApp::new();
+    t.app.enable_dry_run();
+    t.write_note(":::td Buy milk");
+
+    t.process_selected();
+    assert_eq!(
+        t.app.command_message,
+        "Dry run: 1 would be sent, 0 skipped, 0 failed"
+    );
+    assert!(!t.app.dirty);
+    assert_eq!(t.app.buffer.to_string(), ":::td Buy milk");
+}
+
+#[test]
+fn test_nowrap_scrolls_sideways_to_cursor() {
+    let mut t = TestApp::new();
+    t.write_note(&format!("# Wide\n{}", "x".repeat(100)));
+    t.keys("\n:set nowrap\n");
+    assert_eq!(t.app.command_message, "Soft-wrap off");
+    assert!(!t.app.soft_wrap);
+
+    // A 42-column editor has 40 columns inside its border; `$` puts the
+    // cursor after the last character
+    t.keys("j$");
+    assert_eq!(t.app.horizontal_scroll(42), 61);
+    assert_eq!(t.app.scroll_offset(42, 10), 0);
+    t.keys("10h");
+    assert_eq!(t.app.horizontal_scroll(42), 61);
+    t.keys("0");
+    assert_eq!(t.app.horizontal_scroll(42), 0);
+
+    t.keys("$:set wrap\n");
+    assert!(t.app.soft_wrap);
+    assert_eq!(t.app.horizontal_scroll(42), 0);
+}
+
+#[test]
+fn test_scroll_commands_move_view_and_cursor() {
+    let mut t = TestApp::new();
+    let lines: Vec<String> = (1..=50).map(|n| format!("line {}", n)).collect();
+    t.write_note(&lines.join("\n"));
+    t.keys("\n");
+    let row = |t: &TestApp| t.app.buffer.cursor_position().0;
+
+    // A 12-row editor shows 10 rows inside its border
+    assert_eq!(t.app.scroll_offset(42, 12), 0);
+    t.ctrl('d');
+    assert_eq!(row(&t), 7);
+    assert_eq!(t.app.scroll_offset(42, 12), 5);
+    t.ctrl('u');
+    assert_eq!(t.app.scroll_offset(42, 12), 0);
+    assert_eq!(row(&t), 2);
+
+    t.ctrl('f');
+    assert_eq!(t.app.scroll_offset(42, 12), 8);
+    assert_eq!(row(&t), 10);
+    t.keys("2");
+    t.ctrl('f');
+    assert_eq!(t.app.scroll_offset(42, 12), 24);
+    t.ctrl('b');
+    assert_eq!(t.app.scroll_offset(42, 12), 16);
+    assert_eq!(row(&t), 23);
+
+    t.keys("zt");
+    assert_eq!(t.app.scroll_offset(42, 12), 21);
+    t.keys("zb");
+    assert_eq!(t.app.scroll_offset(42, 12), 16);
+    t.keys("zz");
+    assert_eq!(t.app.scroll_offset(42, 12), 18);
+
+    // Paging past the end lands on the last line
+    t.keys("9");
+    t.ctrl('f');
+    t.ctrl('f');
+    assert_eq!(row(&t), 49);
+}
+
+#[test]
+fn test_switching_buffers_keeps_cursor_and_undo() {
+    let mut t = TestApp::new();
+    t.write_note("# One\na\nb");
+    t.write_note("# Two\nx");
+
+    // Both new notes are open; reopen "One" and change it
+    t.keys("j\n");
+    assert_eq!(t.app.buffers.len(), 2);
+    t.keys("jAz\x1b");
+    assert_eq!(t.app.buffer.to_string(), "# One\naz\nb");
+    let cursor = t.app.buffer.cursor_position();
+
+    // Switching away saves the change
+    t.keys(":bn\n");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Two");
+    assert!(!t.app.dirty);
+    t.app.flush_saves().unwrap();
+    assert!(t.on_disk(false).iter().any(|n| n.content == "# One\naz\nb"));
+
+    t.keys("]b");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "One");
+    assert_eq!(t.app.buffer.cursor_position(), cursor);
+    t.keys("u");
+    assert_eq!(t.app.buffer.to_string(), "# One\na\nb");
+
+    // The picker starts on the current note
+    t.keys(" B");
+    assert_eq!(t.app.buffer_picker, Some(0));
+    t.keys("j\n");
+    assert_eq!(t.app.buffer_picker, None);
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Two");
+}
+
+#[test]
+fn test_split_panes_keep_their_own_note_and_cursor() {
+    let mut t = TestApp::new();
+    t.write_note("# One\na\nb");
+    t.write_note("# Two\nx");
+    let title = |t: &TestApp| t.app.current_note.as_ref().unwrap().title.clone();
+
+    t.keys("j\njj");
+    t.keys(":vsplit\n");
+    assert_eq!(t.app.panes.len(), 2);
+    assert_eq!(t.app.panes.focus(), 1);
+    // The new pane shows Two; the other keeps One and its cursor
+    t.keys("]b");
+    assert_eq!(title(&t), "Two");
+    let other = t.app.panes.next(true, 1);
+    let view = t.app.pane_view(other);
+    assert_eq!(view.title, "One");
+    assert_eq!(view.lines, ["# One", "a", "b"]);
+    assert_eq!(view.cursor.0, 2);
+
+    t.ctrl('w');
+    t.keys("w");
+    assert_eq!(t.app.panes.focus(), other);
+    assert_eq!(title(&t), "One");
+    assert_eq!(t.app.buffer.cursor_position().0, 2);
+    // The new pane went to the left
+    t.ctrl('w');
+    t.keys("h");
+    assert_eq!(title(&t), "Two");
+
+    t.ctrl('w');
+    t.keys("o");
+    assert_eq!(t.app.panes.len(), 1);
+    assert_eq!(title(&t), "Two");
+    t.keys(":close\n");
+    assert_eq!(t.app.command_message, "Cannot close last pane");
+    t.ctrl('w');
+    t.keys("s:close\n");
+    assert!(!t.app.panes.is_split());
+}
+
+#[test]
+fn test_vsview_opens_another_note_read_only() {
+    let mut t = TestApp::new();
+    t.write_note("# Draft\nwriting");
+    t.write_note("# Reference\nfacts");
+    let title = |t: &TestApp| t.app.current_note.as_ref().unwrap().title.clone();
+
+    t.keys("j\n");
+    assert_eq!(title(&t), "Draft");
+    t.keys(":vsview reference\n");
+    assert_eq!(t.app.panes.len(), 2);
+    assert_eq!(title(&t), "Reference");
+    assert!(t.app.is_read_only());
+    t.keys("dd");
+    assert_eq!(t.app.buffer.to_string(), "# Reference\nfacts");
+    assert_eq!(
+        t.app.command_message,
+        "Read-only mode: changes are disabled"
+    );
+
+    // The other pane still edits its note
+    t.ctrl('w');
+    t.keys("w");
+    assert_eq!(title(&t), "Draft");
+    assert!(!t.app.is_read_only());
+    t.keys("jdd");
+    assert_eq!(t.app.buffer.to_string(), "# Draft");
+
+    t.keys(":vsplit Elsewhere\n");
+    assert_eq!(t.app.command_message, "No note named 'Elsewhere'");
+    assert_eq!(t.app.panes.len(), 2);
+}
+
+#[test]
+fn test_filter_uses_search_index_kept_across_runs() {
+    let mut t = TestApp::new();
+    t.write_note("# Groceries\nmilk and eggs");
+    t.write_note("# Budget\nQ3 numbers");
+    assert_eq!(t.app.search_index().len(), 2);
+
+    // Accents are ignored; an uppercase letter makes case matter
+    t.keys("fÈGGS\n");
+    assert_eq!(t.app.draft_list.len(), 0);
+    t.keys("\x1bfèggs\n");
+    assert_eq!(t.app.draft_list.len(), 1);
+    // Saving a note re-indexes it and the filter follows
+    t.keys("\x1b\x1b\nGoeggs too\x1b l");
+    t.app.flush_saves().unwrap();
+    t.keys("feggs\n");
+    assert_eq!(t.app.draft_list.len(), 2);
+
+    t.app.save_search_index().unwrap();
+    assert!(t.dir.join("search_index.toml").exists());
+    let reopened = App::with_config(Config::default(), t.dir.clone()).unwrap();
+    assert_eq!(reopened.search_index().len(), 2);
+    assert!(!reopened.search_index().is_changed());
+
+    t.keys(":reindex\n");
+    assert_eq!(t.app.command_message, "Search index rebuilt: 2 notes");
+}
+
+#[test]
+fn test_tags_filter_drafts_and_browse() {
+    let mut t = TestApp::new();
+    t.write_note("# Standup\nnotes #work/meetings");
+    t.write_note("# Plan\n#work and #home");
+    t.write_note("# Recipe\n`#work` is code #1");
+    assert_eq!(t.app.draft_list.notes()[0].tags, Vec::<String>::new());
+
+    t.keys("f#work\n");
+    assert_eq!(t.app.draft_list.len(), 2);
+    t.keys("\x1b");
+    assert_eq!(t.app.draft_list.len(), 3);
+
+    t.keys("\x1b:tags\n");
+    let (tags, _) = t.app.tag_picker.as_ref().unwrap();
+    let names: Vec<&str> = tags.iter().map(|(tag, _)| tag.as_str()).collect();
+    assert_eq!(names, ["home", "work", "work/meetings"]);
+    // Enter shows the drafts with the selected tag
+    t.keys("j\n");
+    assert!(t.app.tag_picker.is_none());
+    assert_eq!(t.app.view, View::DraftList);
+    assert_eq!(t.app.list_filter(), "#work");
+    assert_eq!(t.app.draft_list.len(), 2);
+}
+
+#[test]
+fn test_weekly_review_writes_summary() {
+    let mut t = TestApp::new();
+    t.write_note("# Alpha\ndone");
+    t.write_note("# Beta\nidea");
+    t.write_note("# Gamma\nlater");
+
+    t.keys(":review\n");
+    assert_eq!(t.app.view, View::Editor);
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Alpha");
+    assert!(t.app.command_message.starts_with("Review 1/3"));
+
+    // a archives and moves on; t asks for a tag; other keys still move
+    t.keys("a");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Beta");
+    t.keys("tspark\nj");
+    assert!(t.app.buffer.to_string().ends_with("idea\n\n#spark"));
+    t.keys("n");
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Gamma");
+    t.keys("q");
+    assert!(t.app.review.is_none());
+
+    let summary = t.app.buffer.to_string();
+    assert!(summary.starts_with("# Weekly review "));
+    assert!(summary.contains("## Archived\n\n- [[Alpha]]\n"));
+    assert!(summary.contains("## Tagged\n\n- [[Beta]] #spark\n"));
+    assert!(summary.ends_with("## Not reviewed\n\n- [[Gamma]]"));
+    t.app.flush_saves().unwrap();
+    assert_eq!(t.on_disk(true)[0].title, "Alpha");
+    assert!(t.on_disk(false).iter().all(|n| n.title != "Alpha"));
+}
+
+#[test]
+fn test_poll_slows_down_only_when_idle() {
+    use std::time::Duration;
+
+    let mut t = TestApp::new();
+    let tick = Duration::from_millis(100);
+    let idle = Duration::from_secs(10);
+    assert_eq!(t.app.poll_timeout(Duration::ZERO), tick);
+    assert_eq!(t.app.poll_timeout(idle), Duration::from_secs(2));
+
+    // Unsaved edits wait for auto-save, an archive for its undo window
+    t.keys("nabc");
+    assert_eq!(t.app.poll_timeout(idle), tick);
+    t.keys("\x1b l");
+    t.app.flush_saves().unwrap();
+    assert_eq!(t.app.poll_timeout(idle), Duration::from_secs(2));
+    t.keys("a");
+    assert_eq!(t.app.poll_timeout(idle), tick);
+
+    let mut config = Config::default();
+    config.general.idle_poll_ms = 0;
+    let app = App::with_config(config, t.dir.clone()).unwrap();
+    assert_eq!(app.poll_timeout(idle), tick);
+}
+
+#[test]
+fn test_folds_hide_sections_per_note() {
+    let mut t = TestApp::new();
+    t.write_note("# Plan\nintro\n## Steps\none\n```\ncode\n```\n# Done\nend");
+    t.write_note("# Two\nx");
+    let shown = |t: &TestApp| t.app.folded_lines().as_strs().join("|");
+
+    t.keys("j\nzM");
+    assert_eq!(shown(&t), "# Plan ··· 6 lines|# Done ··· 1 line");
+    // j and k step over a closed fold
+    t.keys("j");
+    assert_eq!(t.app.buffer.cursor_position().0, 7);
+    t.keys("k");
+    assert_eq!(t.app.buffer.cursor_position().0, 0);
+
+    // za opens the outer fold; the inner ones stay closed
+    t.keys("za");
+    assert_eq!(
+        shown(&t),
+        "# Plan|intro|## Steps ··· 4 lines|# Done ··· 1 line"
+    );
+    // A search landing in a fold opens it
+    t.keys("/code\n");
+    assert_eq!(t.app.buffer.cursor_position().0, 5);
+    assert_eq!(t.app.closed_folds().len(), 1);
+
+    // Folds are kept per note
+    t.keys("]b");
+    assert!(t.app.closed_folds().is_empty());
+    t.keys("]b");
+    assert_eq!(shown(&t).matches("···").count(), 1);
+    t.keys("zR");
+    assert!(t.app.closed_folds().is_empty());
+}
+
+#[test]
+fn test_wiki_links_follow_and_backlinks() {
+    let mut t = TestApp::new();
+    t.write_note("# Home\nsee [[ideas|my ideas]]");
+    let title = |t: &TestApp| t.app.current_note.as_ref().unwrap().title.clone();
+
+    t.keys("\ngf");
+    assert_eq!(t.app.command_message, "No link under cursor");
+    // Enter on a link to a missing note creates it
+    t.keys("j$\n");
+    assert_eq!(title(&t), "ideas");
+    assert_eq!(t.app.draft_list.notes().len(), 2);
+
+    t.keys(" L");
+    let (linking, _) = t.app.backlink_picker.as_ref().unwrap();
+    assert_eq!(linking.len(), 1);
+    assert_eq!(linking[0].title, "Home");
+    t.keys("\n");
+    assert!(t.app.backlink_picker.is_none());
+    assert_eq!(title(&t), "Home");
+
+    // gf opens the existing note; nothing links to it but Home
+    t.keys("j$gf");
+    assert_eq!(title(&t), "ideas");
+    assert_eq!(t.app.draft_list.notes().len(), 2);
+    t.keys("]b");
+    t.keys(":backlinks\n");
+    assert!(t.app.backlink_picker.is_none());
+    assert_eq!(t.app.command_message, "No notes link here");
+}
+
+#[test]
+fn test_note_ids_and_dates_follow_as_links() {
+    let mut t = TestApp::new();
+    t.write_note("# Target\nbody");
+    let id = t.app.draft_list.notes()[0].id.clone();
+    t.write_note(&format!("# Hub\nsee {}\non 2024-03-01, not 2024-02-30", id));
+    let title = |t: &TestApp| t.app.current_note.as_ref().unwrap().title.clone();
+
+    t.app.open_note("Hub");
+    assert_eq!(t.app.auto_link_matches(), [(1, 4, 36), (2, 3, 10)]);
+    t.keys("ggj$gf");
+    assert_eq!(title(&t), "Target");
+
+    t.app.open_note("Hub");
+    t.keys("G$gf");
+    assert_eq!(t.app.command_message, "No link under cursor");
+    t.keys("0w\n");
+    assert_eq!(title(&t), "2024-03-01");
+
+    let mut config = Config::default();
+    config.auto_links.dates = false;
+    let mut t = TestApp::with_config(config);
+    t.write_note("# Hub\n2024-03-01");
+    t.keys("\njgf");
+    assert_eq!(t.app.command_message, "No link under cursor");
+}
+
+#[test]
+fn test_split_headings_moves_sections_into_drafts() {
+    let mut t = TestApp::new();
+    t.write_note("# Dump\n## Groceries\nmilk\n\n## Ideas\nfly\n```\n## code\n```");
+    t.keys("\n:split-headings\n");
+    assert_eq!(t.app.command_message, "Split into 2 note(s)");
+    assert_eq!(
+        t.app.buffer.to_string(),
+        "# Dump\n\n- [[Groceries]]\n- [[Ideas]]"
+    );
+    t.app.flush_saves().unwrap();
+    let content = |t: &TestApp, title: &str| {
+        let note = t.app.draft_list.notes().iter().find(|n| n.title == title);
+        note.unwrap().content.clone()
+    };
+    assert_eq!(content(&t, "Groceries"), "## Groceries\nmilk\n");
+    assert_eq!(content(&t, "Ideas"), "## Ideas\nfly\n```\n## code\n```\n");
+
+    t.keys("u");
+    assert!(t.app.buffer.to_string().starts_with("# Dump\n## Groceries"));
+
+    t.app.open_note("Ideas");
+    t.keys(":split-headings!\n");
+    assert_eq!(t.app.command_message, "Split into 1 note(s)");
+    assert_eq!(t.app.buffer.to_string(), "");
+    t.keys(":split-headings\n");
+    assert_eq!(t.app.command_message, "No headings to split at");
+}
+
+#[test]
+fn test_outline_sidebar_jumps_to_heading() {
+    let mut t = TestApp::new();
+    t.keys("n");
+    t.keys("# Plan\nintro\n## Steps\none\n```\n# not a heading\n```\n## Done\x1b");
+    let titles: Vec<String> = t.app.outline().iter().map(|e| e.title.clone()).collect();
+    assert_eq!(titles, ["Plan", "Steps", "Done"]);
+
+    // Opens on the cursor's section and keeps the keys to itself
+    t.keys("gg4j o");
+    assert!(t.app.show_outline);
+    assert_eq!(t.app.outline_selection, Some(1));
+    t.keys("jj\n");
+    assert_eq!(t.app.outline_selection, None);
+    assert_eq!(t.app.buffer.cursor_position(), (7, 0));
+    assert_eq!(t.app.mode, AppMode::Normal);
+
+    t.ctrl('o');
+    assert_eq!(t.app.buffer.cursor_position().0, 4);
+    // Shown without focus, Space o hides it; q hides it from inside
+    t.keys(" o");
+    assert!(!t.app.show_outline);
+    t.keys(" o\x1b");
+    assert!(t.app.show_outline);
+    assert_eq!(t.app.outline_selection, None);
+    t.keys(" oq");
+    assert!(!t.app.show_outline);
+}
+
+#[test]
+fn test_rename_rewrites_title_and_file() {
+    let mut config = Config::default();
+    config.general.file_naming = "title".to_string();
+    let mut t = TestApp::with_config(config);
+    t.write_note("# Old\nbody");
+    assert!(t.dir.join("drafts/old.md").exists());
+
+    t.keys("\n r");
+    assert_eq!(t.app.rename_title.as_deref(), Some("Old"));
+    t.ctrl('u');
+    t.keys("New\n");
+    assert!(t.app.rename_title.is_none());
+    assert_eq!(t.app.command_message, "Renamed to 'New'");
+    assert_eq!(t.app.buffer.to_string(), "# New\nbody");
+    assert!(t.dir.join("drafts/new.md").exists());
+    assert!(!t.dir.join("drafts/old.md").exists());
+
+    t.keys(" l:rename Plan B\n");
+    assert_eq!(t.app.draft_list.notes()[0].title, "Plan B");
+    assert_eq!(t.on_disk(false)[0].content, "# Plan B\nbody");
+    assert_eq!(t.file_count("drafts"), 1);
+}
+
+#[test]
+fn test_sync_title_from_first_heading() {
+    let mut t = TestApp::new();
+    t.write_note("---\ntitle: Q3 plan\n---\n# Q3 plan\nbody");
+    t.keys("\n");
+    assert!(t.app.title_drift().is_none());
+
+    t.keys("jjjcc# Q4 plan\x1b");
+    assert_eq!(t.app.title_drift().as_deref(), Some("Q4 plan"));
+    t.keys(" T");
+    assert_eq!(t.app.command_message, "Title set to 'Q4 plan'");
+    assert!(t.app.title_drift().is_none());
+    assert_eq!(t.app.current_note.as_ref().unwrap().title, "Q4 plan");
+    assert_eq!(
+        t.on_disk(false)[0].content,
+        "---\ntitle: Q4 plan\n---\n# Q4 plan\nbody"
+    );
+}
+
+#[test]
+fn test_appearance_switches_between_light_and_dark_themes() {
+    let mut config = Config::default();
+    config.appearance.auto = AppearanceMode::Schedule;
+    config.appearance.light = "nord".to_string();
+    config.appearance.dark = "gruvbox".to_string();
+    let mut t = TestApp::with_config(config);
+    assert_eq!(t.app.theme().name, "Tokyo Night");
+
+    t.app.set_appearance(true);
+    assert_eq!(t.app.theme().name, "Gruvbox");
+    // A theme picked by hand stays until the appearance changes again
+    t.app.cycle_theme();
+    t.app.set_appearance(true);
+    assert_eq!(t.app.theme().name, "Nord");
+    t.app.set_appearance(false);
+    t.app.cycle_theme();
+    t.app.set_appearance(false);
+    assert_eq!(t.app.theme().name, "Catppuccin Mocha");
+}
+
+#[test]
+fn test_config_reload_applies_keys_and_theme() {
+    let mut t = TestApp::new();
+    let config_file = t.dir.join("config.toml");
+    fs::write(&config_file, "").unwrap();
+    t.app.config_file = Some(config_file.clone());
+    t.write_note("one\ntwo\nthree");
+    t.keys("\n");
+
+    fs::write(
+        &config_file,
+        "[general]\ntheme = \"nord\"\nshow_hints = false\n[keyboard]\nlayout = \"colemak\"\n",
+    )
+    .unwrap();
+    t.app.reload_config();
+    assert_eq!(t.app.command_message, "Config reloaded");
+    assert_eq!(t.app.theme().name, "Nord");
+    assert!(!t.app.show_hints);
+    t.keys("e");
+    assert_eq!(t.app.buffer.cursor_position(), (1, 0));
+
+    // A broken file keeps the settings in effect
+    fs::write(&config_file, "[general\n").unwrap();
+    t.app.reload_config();
+    assert!(t.app.command_message.starts_with("Config not reloaded"));
+    assert_eq!(t.app.theme().name, "Nord");
+}
+
+#[test]
+fn test_window_title_follows_note_and_dirty_state() {
+    let mut t = TestApp::new();
+    t.write_note("# Q3 plan\nbody");
+    assert_eq!(t.app.window_title(), "Drafts - kenotex");
+    t.keys("\n");
+    assert_eq!(t.app.window_title(), "Q3 plan - kenotex");
+    t.keys("Gox\x1b");
+    assert_eq!(t.app.window_title(), "Q3 plan [+] - kenotex");
+    t.app.save_current_note().unwrap();
+    assert_eq!(t.app.window_title(), "Q3 plan - kenotex");
+}
+
+#[test]
+fn test_git_sync_commits_pushes_and_reloads_pulled_changes() {
+    let git = |dir: &Path, args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    };
+    let remote = std::env::temp_dir().join(format!("kenotex-remote-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&remote).unwrap();
+    git(&remote, &["init", "-q", "--bare"]);
+
+    let mut t = TestApp::new();
+    t.keys(":sync\n");
+    assert_eq!(
+        t.app.command_message,
+        "Git sync is off (set enabled = true under [sync])"
+    );
+    let mut config = Config::default();
+    config.sync.enabled = true;
+    t.app = App::with_config(config, t.dir.clone()).unwrap();
+    t.app.set_view(View::DraftList);
+    t.write_note("# Plan\nfirst");
+
+    t.keys(":sync\n");
+    t.app.wait_for_sync().unwrap();
+    assert_eq!(
+        t.app.command_message,
+        "Changes committed (no remote to push to)"
+    );
+    git(
+        &t.dir,
+        &["remote", "add", "origin", remote.to_str().unwrap()],
+    );
+    t.keys("\n g");
+    t.app.wait_for_sync().unwrap();
+    assert_eq!(t.app.command_message, "Synced");
+
+    // Another machine edits the note while it is open here
+    let other = remote.with_extension("clone");
+    git(&remote, &["clone", "-q", ".", other.to_str().unwrap()]);
+    let file = md_files(&other.join("drafts")).remove(0);
+    fs::write(&file, "# Plan\nfirst\nfrom desktop").unwrap();
+    git(&other, &["commit", "-qam", "edit"]);
+    git(&other, &["push", "-q"]);
+
+    t.keys(" g");
+    t.app.wait_for_sync().unwrap();
+    assert_eq!(t.app.buffer.to_string(), "# Plan\nfirst\nfrom desktop");
+    assert_eq!(t.app.command_message, "Synced");
+    for dir in [remote, other] {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+#[test]
+fn test_list_sort_from_config_and_cycled() {
+    let mut t = TestApp::new();
+    t.write_note("# Zeta\nshort");
+    t.write_note("# Alpha\na much longer body");
+    let mut config = Config::default();
+    config.general.list_sort = "title".to_string();
+    t.app = App::with_config(config, t.dir.clone()).unwrap();
+    t.app.set_view(View::DraftList);
+    let titles = |t: &TestApp| -> Vec<String> {
+        let notes = t.app.draft_list.filtered_notes();
+        notes.iter().map(|n| n.title.clone()).collect()
+    };
+    assert_eq!(titles(&t), ["Alpha", "Zeta"]);
+
+    t.keys("s");
+    assert_eq!(t.app.command_message, "Sort: size");
+    assert_eq!(t.app.config.general.list_sort, "size");
+    assert_eq!(titles(&t), ["Alpha", "Zeta"]);
+    t.keys("s");
+    assert_eq!(titles(&t), ["Alpha", "Zeta"]);
+    assert_eq!(t.app.config.general.list_sort, "updated");
+}
+
+#[test]
+fn test_tag_marked_notes_from_list() {
+    let mut t = TestApp::new();
+    t.write_note("# One");
+    t.write_note("# Two");
+    t.write_note("# Three");
+    t.keys(" j :tag Work\n");
+    assert_eq!(t.app.command_message, "Tagged 2 note(s) #work");
+    assert_eq!(t.app.draft_list.marked_count(), 0);
+    let mut tagged: Vec<String> = t
+        .on_disk(false)
+        .into_iter()
+        .filter(|n| n.has_tag("work"))
+        .map(|n| n.content)
+        .collect();
+    tagged.sort();
+    assert_eq!(tagged, ["# Three\n\n#work", "# Two\n\n#work"]);
+
+    // Without marks the note under the cursor is tagged
+    t.keys("G:tag idea\n");
+    assert_eq!(t.app.command_message, "Tagged 1 note(s) #idea");
+    assert_eq!(t.app.draft_list.selected_note().unwrap().tags, ["idea"]);
+}
+
+#[test]
+fn test_pin_keeps_note_on_top() {
+    let mut t = TestApp::new();
+    t.write_note("# Old");
+    t.write_note("# New");
+    t.keys("jp");
+    assert_eq!(t.app.command_message, "Pinned 'Old'");
+    assert_eq!(t.app.draft_list.notes()[0].title, "Old");
+    assert!(t.app.draft_list.notes()[0].is_pinned());
+    assert_eq!(t.app.draft_list.selected_note().unwrap().title, "Old");
+
+    t.write_note("# Newest");
+    assert_eq!(t.app.draft_list.notes()[0].title, "Old");
+    let on_disk = t.on_disk(false);
+    let old = on_disk.iter().find(|n| n.title == "Old").unwrap();
+    assert_eq!(old.content, "---\npinned: true\n---\n# Old");
+
+    t.keys("ggp");
+    assert_eq!(t.app.command_message, "Unpinned 'Old'");
+    let on_disk = t.on_disk(false);
+    let old = on_disk.iter().find(|n| n.title == "Old").unwrap();
+    assert_eq!(old.content, "# Old");
+}
+
+fn completions(t: &TestApp) -> Vec<String> {
+    t.app
+        .completions()
+        .map(|(_, _, items)| items)
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_mention_completes_contact() {
+    let mut t = TestApp::new();
+    t.app.dispatcher = Box::new(RecordingDispatcher::new().knowing(&["Anna Smith", "Jo Ann"]));
+    t.keys("nask me@x or @an");
+    assert_eq!(completions(&t), ["Anna Smith", "Jo Ann"]);
+    t.ctrl('n');
+    t.press(KeyCode::Tab);
+    t.keys(" today");
+    assert!(completions(&t).is_empty());
+    assert_eq!(t.app.buffer.to_string(), "ask me@x or @Jo Ann today");
+
+    // Without a popup Tab indents as usual
+    t.keys("\x1b0i");
+    t.press(KeyCode::Tab);
+    assert!(t.app.buffer.to_string().starts_with("    ask"));
+}
+
+#[test]
+fn test_completion_popup_for_tags_links_and_words() {
+    let mut t = TestApp::new();
+    t.write_note("Weekly plan\n#work stuff");
+    t.write_note("Groceries\n#home");
+
+    t.keys("nsee #wo");
+    assert_eq!(completions(&t), ["work"]);
+    t.press(KeyCode::Tab);
+    t.keys(" [[gro");
+    assert_eq!(completions(&t), ["Groceries"]);
+    t.press(KeyCode::Tab);
+    assert_eq!(t.app.buffer.to_string(), "see #work [[Groceries]]");
+
+    // Ctrl-N asks for the words of the note; while shown it moves
+    t.keys("\nMeeting about meet me");
+    assert!(completions(&t).is_empty());
+    t.ctrl('n');
+    assert_eq!(completions(&t), ["Meeting", "meet"]);
+    t.ctrl('n');
+    t.press(KeyCode::Tab);
+    assert!(t.app.buffer.to_string().ends_with("about meet meet"));
+    t.keys(" zz");
+    t.ctrl('n');
+    assert_eq!(t.app.command_message, "No completions");
+}
+
+#[test]
+fn test_frontmatter_fields_title_filter_and_fold() {
+    let mut config = Config::default();
+    config.general.hide_frontmatter = true;
+    let mut t = TestApp::with_config(config);
+    t.write_note("+++\ntitle = \"Pitch deck\"\nstatus = \"draft\"\n+++\nslides");
+    t.write_note("# Other\nno fields");
+
+    let titles: Vec<String> = t.on_disk(false).iter().map(|n| n.title.clone()).collect();
+    assert!(titles.contains(&"Pitch deck".to_string()));
+
+    t.keys("fstatus:draft\n");
+    assert_eq!(t.app.draft_list.len(), 1);
+    t.keys("\n");
+    assert_eq!(t.app.folded_lines().as_strs()[0], "+++ ··· 3 lines");
+    t.keys("za");
+    assert_eq!(t.app.folded_lines().as_strs()[0], "+++");
+}
+
+#[test]
+fn test_stats_write_failure_does_not_block_note_save() {
+    let mut t = TestApp::new();
+    fs::create_dir_all(t.dir.join("stats.toml")).unwrap();
+    t.write_note("# Kept\nwords that count");
+
+    assert_eq!(t.on_disk(false)[0].title, "Kept");
+    assert!(t.app.flush_writing_stats().is_err());
+}
+
+#[test]
+fn test_config_reload_applies_file_naming_to_saves() {
+    let mut t = TestApp::new();
+    let config_file = t.dir.join("config.toml");
+    fs::write(&config_file, "").unwrap();
+    t.app.config_file = Some(config_file.clone());
+
+    fs::write(&config_file, "[general]\nfile_naming = \"title\"\n").unwrap();
+    t.app.reload_config();
+    t.write_note("# Renamed Plan\nbody");
+    assert!(t.dir.join("drafts/renamed-plan.md").exists());
+}
+
+#[test]
+fn test_pane_resize_keys_and_persisted_proportions() {
+    let mut t = TestApp::new();
+    t.write_note("# One\na");
+    t.keys("\n:vsplit\n");
+    let area = ratatui::layout::Rect::new(0, 0, 100, 20);
+    let width = |t: &TestApp| t.app.panes.rects(area)[0].1.width;
+    assert_eq!(width(&t), 50);
+
+    t.keys("2");
+    t.ctrl('w');
+    t.keys(">");
+    assert_eq!(width(&t), 60);
+    t.ctrl('w');
+    t.keys("<");
+    assert_eq!(width(&t), 55);
+    t.ctrl('w');
+    t.keys("+");
+    assert_eq!(t.app.panes.rects(area)[0].1.height, 20);
+
+    // A new session splits with the saved proportion; Ctrl-W = resets it
+    let mut t = TestApp {
+        app: App::with_config(Config::default(), t.dir.clone()).unwrap(),
+        dir: t.dir.clone(),
+    };
+    t.app.set_view(View::DraftList);
+    t.keys("\n:vsplit\n");
+    assert_eq!(width(&t), 55);
+    t.ctrl('w');
+    t.keys("=");
+    assert_eq!(width(&t), 50);
+    assert!(
+        fs::read_to_string(t.dir.join("layout.toml"))
+            .unwrap()
+            .contains("vertical = 50")
+    );
+}
+
+#[test]
+fn test_ctrl_a_after_dollar_counts_the_number_before_the_cursor() {
+    let mut t = TestApp::new();
+    t.write_note("# Reading\npage 41");
+    t.keys("\nj$");
+    t.ctrl('a');
+    assert_eq!(t.app.buffer.to_string(), "# Reading\npage 42");
+    t.keys("$");
+    t.ctrl('x');
+    t.ctrl('x');
+    assert_eq!(t.app.buffer.to_string(), "# Reading\npage 40");
+}